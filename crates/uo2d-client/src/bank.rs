@@ -0,0 +1,162 @@
+use uo2d_proto::components::{Vec2, Vec3};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::{Locale, Text};
+use uo2d_proto::packet::payloads::{BankPayload, BankRequest};
+use uo2d_proto::packet::{Action, Payload};
+
+use super::input::within;
+use super::packet_processor::{Context, Handlers};
+use super::renderer::Renderer;
+
+/// Window listing the stacks kept at the bank, along the left of the screen.
+/// Clicking a stack withdraws it into the inventory.
+#[derive(Default)]
+pub struct BankWindow {
+    bank: Option<BankPayload>,
+}
+
+impl BankWindow {
+    const WIDTH: f64 = 260.;
+    const ROW_HEIGHT: f64 = 20.;
+    const PADDING: f64 = 10.;
+    /// Distance kept from the edges of the screen.
+    const MARGIN: f64 = 10.;
+    const BUTTON_WIDTH: f64 = 110.;
+    /// Rows of stacks shown, those beyond are shown once others are withdrawn.
+    const ROWS: usize = 16;
+    const BACKGROUND: [u8; 3] = [32, 32, 32];
+    const BUTTON_COLOR: [u8; 3] = [64, 64, 64];
+
+    /// Checks if the bank is being shown.
+    pub fn is_open(&self) -> bool {
+        self.bank.is_some()
+    }
+
+    /// Hides the window, the server is told separately.
+    pub fn close(&mut self) {
+        self.bank = None;
+    }
+
+    /// Top-left corner and size of the window, centered vertically on the left.
+    fn frame(screen: Vec2) -> (Vec2, Vec2) {
+        // Header, the stacks, then the hint.
+        let rows = Self::ROWS as f64 + 2.;
+        let size = Vec2::new(
+            Self::WIDTH,
+            (rows + 1.) * Self::ROW_HEIGHT + Self::PADDING * 3.,
+        );
+        let top_left = Vec2::new(Self::MARGIN, (screen.y() - size.y()) / 2.);
+        (top_left, size)
+    }
+
+    /// Top-left corner of a row of text.
+    fn row(top_left: Vec2, row: usize) -> Vec2 {
+        Vec2::new(
+            top_left.x() + Self::PADDING,
+            top_left.y() + Self::PADDING + row as f64 * Self::ROW_HEIGHT,
+        )
+    }
+
+    /// Top-left corner of the close button.
+    fn button(top_left: Vec2) -> Vec2 {
+        Vec2::new(
+            top_left.x() + Self::PADDING,
+            top_left.y() + Self::PADDING * 2. + (Self::ROWS as f64 + 2.) * Self::ROW_HEIGHT,
+        )
+    }
+
+    /// Checks if a point on the screen is within the window.
+    pub fn contains(&self, screen: Vec2, point: Vec2) -> bool {
+        self.is_open() && {
+            let (top_left, size) = Self::frame(screen);
+            within(point, top_left, size)
+        }
+    }
+
+    /// Request made by clicking a point on the screen, closing the bank or withdrawing a stack.
+    pub fn click(&self, screen: Vec2, point: Vec2) -> Option<BankRequest> {
+        let bank = self.bank.as_ref()?;
+        let (top_left, _) = Self::frame(screen);
+
+        let size = Vec2::new(Self::BUTTON_WIDTH, Self::ROW_HEIGHT);
+        if within(point, Self::button(top_left), size) {
+            return Some(BankRequest::Close);
+        }
+
+        let size = Vec2::new(Self::WIDTH - Self::PADDING * 2., Self::ROW_HEIGHT);
+        (0..bank.stacks.len().min(Self::ROWS))
+            .find(|i| within(point, Self::row(top_left, i + 1), size))
+            .map(|i| BankRequest::Withdraw {
+                slot: i as u16,
+                count: 0,
+            })
+    }
+
+    /// Draws the window along the left of the screen.
+    pub fn draw(&self, renderer: &mut dyn Renderer, locale: &Locale, items: &ItemManager) {
+        let bank = match &self.bank {
+            Some(bank) => bank,
+            None => return,
+        };
+
+        let (top_left, size) = Self::frame(renderer.screen_size());
+        renderer.draw_rect(top_left, size, color(Self::BACKGROUND));
+
+        let white = Vec3::new(255., 255., 255.);
+        let weight: u32 = bank
+            .stacks
+            .iter()
+            .filter_map(|stack| Some(items.get(&stack.item)?.weight * stack.count as u32))
+            .sum();
+        let header = locale.text(
+            &Text::new("bank.header")
+                .with("weight", weight)
+                .with("capacity", bank.capacity),
+        );
+        renderer.draw_text(&header, Self::row(top_left, 0), white, 255);
+
+        let lines = bank.stacks.iter().map(|stack| {
+            let name = items.get(&stack.item).map_or_else(
+                || format!("#{}", stack.item),
+                |item| locale.text(&item.text()),
+            );
+            format!("{} x {}", stack.count, name)
+        });
+        let empty = bank
+            .stacks
+            .is_empty()
+            .then(|| locale.get("bank.empty").to_string());
+        for (i, line) in lines.chain(empty).take(Self::ROWS).enumerate() {
+            renderer.draw_text(&line, Self::row(top_left, i + 1), white, 255);
+        }
+
+        let hint = locale.get("bank.hint");
+        let position = Self::row(top_left, Self::ROWS + 1);
+        renderer.draw_text(hint, position, Vec3::new(160., 160., 160.), 255);
+
+        let size = Vec2::new(Self::BUTTON_WIDTH, Self::ROW_HEIGHT);
+        let position = Self::button(top_left);
+        renderer.draw_rect(position, size, color(Self::BUTTON_COLOR));
+        let label = locale.get("bank.close");
+        let width = renderer.text_size(label).map_or(0., |size| size.x());
+        let text = Vec2::new(position.x() + (size.x() - width) / 2., position.y());
+        renderer.draw_text(label, text, white, 255);
+    }
+}
+
+/// Color to draw with from its red, green, and blue.
+fn color([r, g, b]: [u8; 3]) -> Vec3 {
+    Vec3::new(r as f64, g as f64, b as f64)
+}
+
+/// Registers the handler showing the bank the server sends.
+pub(crate) fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Bank, show);
+}
+
+fn show(ctx: &mut Context, payload: Payload) -> Option<(Action, Payload)> {
+    if let Payload::Bank(data) = payload {
+        ctx.gamestate.bank.bank = Some(data);
+    }
+    None
+}
@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex as SyncMutex};
+
+use uo2d_proto::cprintln;
+use uo2d_proto::packet::{Action, Packet};
+
+/// Packets waiting to be processed and how many were dropped since the last flush.
+struct Queue {
+    packets: VecDeque<Packet>,
+    dropped: usize,
+}
+
+/// Holds packets and allows for access between threads.
+/// Once full, the oldest movement packets are dropped to make room, everything else is kept.
+#[derive(Clone)]
+pub struct PacketCacheSync {
+    queue: Arc<SyncMutex<Queue>>,
+    capacity: usize,
+}
+
+impl PacketCacheSync {
+    /// Creates a new cache for packets, holding up to `capacity` before dropping any.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: Arc::new(SyncMutex::new(Queue {
+                packets: VecDeque::new(),
+                dropped: 0,
+            })),
+            capacity,
+        }
+    }
+
+    /// Checks if a packet is superseded by later ones and can be lost safely.
+    fn is_droppable(packet: &Packet) -> bool {
+        matches!(
+            packet.action(),
+            Some(Action::Movement | Action::MovementDelta)
+        )
+    }
+
+    /// Retrieve received packets from the cache. This clears the packet list.
+    pub fn get_all(&self) -> Vec<Packet> {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.dropped > 0 {
+            cprintln!(
+                "WARNING: dropped {} movement packets, processing is falling behind.",
+                queue.dropped
+            );
+            queue.dropped = 0;
+        }
+
+        std::mem::take(&mut queue.packets).into()
+    }
+
+    /// Add a new packet to the cache, dropping the oldest movement packet if full.
+    pub fn add(&self, packet: Packet) {
+        let mut queue = self.queue.lock().unwrap();
+
+        if queue.packets.len() >= self.capacity {
+            let oldest = queue.packets.iter().position(Self::is_droppable);
+            match oldest {
+                Some(position) => {
+                    queue.packets.remove(position);
+                    queue.dropped += 1;
+                }
+                None if Self::is_droppable(&packet) => {
+                    queue.dropped += 1;
+                    return;
+                }
+                // Critical packets are never dropped, even past the capacity.
+                None => (),
+            }
+        }
+
+        queue.packets.push_back(packet);
+    }
+}
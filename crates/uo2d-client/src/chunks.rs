@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use uo2d_proto::chunk::{chunk_distance, tile_bounds, ChunkCoord, EVICT_DISTANCE, VOID, WATER};
+use uo2d_proto::components::{Transform, Vec3};
+
+use super::entities::Camera;
+use super::renderer::Renderer;
+
+/// Map chunks streamed from the server, limited to a memory budget.
+pub struct ChunkCache {
+    chunks: HashMap<ChunkCoord, Vec<u8>>,
+    budget: usize,
+    size: usize,
+}
+
+impl ChunkCache {
+    /// Color drawn over tiles that are outside of every region.
+    const VOID_COLOR: Vec3 = Vec3::ORIGIN;
+    /// Sprite drawn over those tiles instead, if it was loaded.
+    const VOID_SPRITE: &'static str = "void";
+    const WATER_COLOR: [u8; 3] = [40, 90, 200];
+    const WATER_ALPHA: u8 = 160;
+    const WATER_SPRITE: &'static str = "water";
+
+    /// Creates a new cache that holds up to `budget` bytes of tiles.
+    pub fn new(budget: usize) -> Self {
+        Self {
+            chunks: HashMap::new(),
+            budget,
+            size: 0,
+        }
+    }
+
+    /// Stores a chunk received from the server.
+    pub fn insert(&mut self, coord: ChunkCoord, tiles: Vec<u8>) {
+        self.size += tiles.len();
+        if let Some(old) = self.chunks.insert(coord, tiles) {
+            self.size -= old.len();
+        }
+    }
+
+    /// Removes chunks that are too far away, then the farthest while over budget.
+    pub fn evict(&mut self, position: &Vec3) {
+        let mut removed = 0;
+        self.chunks.retain(|coord, tiles| {
+            let keep = chunk_distance(coord, position) <= EVICT_DISTANCE;
+            if !keep {
+                removed += tiles.len();
+            }
+            keep
+        });
+        self.size -= removed;
+
+        while self.size > self.budget {
+            let farthest = match self
+                .chunks
+                .keys()
+                .max_by_key(|coord| chunk_distance(coord, position))
+            {
+                Some(coord) => *coord,
+                None => break,
+            };
+
+            if let Some(tiles) = self.chunks.remove(&farthest) {
+                self.size -= tiles.len();
+            }
+        }
+    }
+
+    /// Draws over the tiles that cannot be walked on, and those covered in water.
+    pub fn draw(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        for (coord, tiles) in self.chunks.iter() {
+            for (index, tile) in tiles.iter().enumerate() {
+                let transform = Transform::from_bounds(tile_bounds(coord, index));
+                if *tile == VOID {
+                    if !camera.draw_sprite(renderer, Self::VOID_SPRITE, &transform) {
+                        camera.draw(renderer, &transform, 0, Self::VOID_COLOR);
+                    }
+                } else if *tile & WATER != 0
+                    && !camera.draw_sprite(renderer, Self::WATER_SPRITE, &transform)
+                {
+                    let [r, g, b] = Self::WATER_COLOR;
+                    let color = Vec3::new(r as f64, g as f64, b as f64);
+                    camera.draw_translucent(renderer, &transform, color, Self::WATER_ALPHA);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use uo2d_proto::components::Vec3;
+use uo2d_proto::ecs::Entity;
+
+/// Type of health change being displayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombatTextKind {
+    Damage,
+    Heal,
+}
+
+/// Floating text that rises above an entity and fades out.
+#[derive(Debug, Clone)]
+pub struct CombatText {
+    pub entity: Entity,
+    pub kind: CombatTextKind,
+    pub amount: u32,
+    pub position: Vec3,
+    age: u32,
+}
+
+impl CombatText {
+    /// Length of time in client ticks the text is displayed for.
+    const LIFESPAN: u32 = 30;
+    /// Distance the text rises every tick.
+    const RISE_PER_TICK: f64 = 1.0;
+    /// Ticks a text can still absorb new amounts for the same entity.
+    const MERGE_WINDOW: u32 = 5;
+
+    fn new(entity: Entity, kind: CombatTextKind, amount: u32, position: Vec3) -> Self {
+        Self {
+            entity,
+            kind,
+            amount,
+            position,
+            age: 0,
+        }
+    }
+
+    /// Reuses an existing text for a new value.
+    fn reset(&mut self, entity: Entity, kind: CombatTextKind, amount: u32, position: Vec3) {
+        self.entity = entity;
+        self.kind = kind;
+        self.amount = amount;
+        self.position = position;
+        self.age = 0;
+    }
+
+    /// Text to be rendered.
+    pub fn text(&self) -> String {
+        match self.kind {
+            CombatTextKind::Damage => format!("-{}", self.amount),
+            CombatTextKind::Heal => format!("+{}", self.amount),
+        }
+    }
+
+    /// Color of the text.
+    pub fn color(&self) -> Vec3 {
+        match self.kind {
+            CombatTextKind::Damage => Vec3::new(255., 64., 64.),
+            CombatTextKind::Heal => Vec3::new(64., 255., 64.),
+        }
+    }
+
+    /// Transparency of the text, fading out as it ages.
+    pub fn alpha(&self) -> u8 {
+        let remaining = Self::LIFESPAN.saturating_sub(self.age) as f64 / Self::LIFESPAN as f64;
+        (remaining * 255.).round() as u8
+    }
+
+    /// Checks if the text has finished displaying.
+    fn is_expired(&self) -> bool {
+        self.age >= Self::LIFESPAN
+    }
+}
+
+/// Pool of floating combat texts, capped to prevent flooding the screen.
+pub struct CombatTextPool {
+    active: Vec<CombatText>,
+    free: Vec<CombatText>,
+    capacity: usize,
+}
+
+impl CombatTextPool {
+    /// Creates a new pool that displays at most `capacity` texts at once.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            active: Vec::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Displays a new amount above an entity.
+    pub fn spawn(&mut self, entity: Entity, kind: CombatTextKind, amount: u32, position: Vec3) {
+        // Merge into a recent text for the same entity rather than stacking them.
+        if let Some(text) = self
+            .active
+            .iter_mut()
+            .find(|t| t.entity == entity && t.kind == kind && t.age <= CombatText::MERGE_WINDOW)
+        {
+            text.amount += amount;
+            return;
+        }
+
+        // Recycle the oldest text if at capacity.
+        if self.active.len() >= self.capacity {
+            let oldest = self.active.remove(0);
+            self.free.push(oldest);
+        }
+
+        let text = match self.free.pop() {
+            Some(mut text) => {
+                text.reset(entity, kind, amount, position);
+                text
+            }
+            None => CombatText::new(entity, kind, amount, position),
+        };
+
+        self.active.push(text);
+    }
+
+    /// Ages and moves all texts, returning expired ones to the pool.
+    pub fn update(&mut self) {
+        for text in self.active.iter_mut() {
+            text.age += 1;
+            text.position
+                .set_y(text.position.y() - CombatText::RISE_PER_TICK);
+        }
+
+        let (expired, active): (Vec<CombatText>, Vec<CombatText>) =
+            self.active.drain(..).partition(|t| t.is_expired());
+        self.active = active;
+        self.free.extend(expired);
+    }
+
+    /// All texts currently being displayed.
+    pub fn iter(&self) -> impl Iterator<Item = &CombatText> {
+        self.active.iter()
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::VecDeque;
+
+use uo2d_proto::components::{Vec2, Vec3};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::OverlayKind;
+
+use super::renderer::Renderer;
+
+/// Commands typed into the developer console.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Leaves the server, joining the one at the address instead.
+    Connect(String),
+    /// Shows where the player is standing.
+    Position,
+    /// Moves the player, only allowed for gamemasters.
+    Teleport(f64, f64),
+    /// Shows or hides the network statistics.
+    NetGraph,
+    /// Shows or hides a debug overlay, every one of them if none is named.
+    ToggleOverlay(Option<OverlayKind>),
+    Help,
+    Clear,
+}
+
+impl Command {
+    /// Parses a line typed into the console, explaining how it is used if it is not understood.
+    pub fn parse(line: &str) -> Result<Self, Text> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default().to_lowercase();
+        let arguments: Vec<&str> = words.collect();
+
+        match (command.as_str(), arguments.as_slice()) {
+            ("connect", [address]) => Ok(Command::Connect(address.to_string())),
+            ("connect", _) => Err("console.usage_connect".into()),
+            ("pos", []) => Ok(Command::Position),
+            ("tp", [x, y]) => match (x.parse::<f64>(), y.parse::<f64>()) {
+                (Ok(x), Ok(y)) if x.is_finite() && y.is_finite() => Ok(Command::Teleport(x, y)),
+                _ => Err("console.usage_tp".into()),
+            },
+            ("tp", _) => Err("console.usage_tp".into()),
+            ("netgraph", []) => Ok(Command::NetGraph),
+            ("toggle", ["overlay"]) => Ok(Command::ToggleOverlay(None)),
+            ("toggle", ["overlay", name]) => match OverlayKind::from_name(name) {
+                Some(kind) => Ok(Command::ToggleOverlay(Some(kind))),
+                None => Err("console.usage_toggle".into()),
+            },
+            ("toggle", _) => Err("console.usage_toggle".into()),
+            ("help", _) => Ok(Command::Help),
+            ("clear", _) => Ok(Command::Clear),
+            _ => Err(Text::new("console.unknown").with("command", command)),
+        }
+    }
+}
+
+/// Output of the developer console and the state it controls.
+pub struct Console {
+    lines: VecDeque<String>,
+    /// Whether the network statistics are drawn.
+    pub netgraph: bool,
+}
+
+impl Console {
+    /// Lines of output kept, the oldest are forgotten.
+    const MAX_LINES: usize = 12;
+    const LINE_HEIGHT: f64 = 20.;
+
+    pub fn new() -> Self {
+        Self {
+            lines: VecDeque::new(),
+            netgraph: false,
+        }
+    }
+
+    /// Adds a line of output.
+    pub fn print(&mut self, line: impl ToString) {
+        if self.lines.len() == Self::MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_string());
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Draws the output across the top of the screen along with the line being typed.
+    pub fn draw(&self, renderer: &mut dyn Renderer, typing: &str) {
+        let rows = Self::MAX_LINES as f64 + 1.;
+        let size = Vec2::new(renderer.screen_size().x(), rows * Self::LINE_HEIGHT + 10.);
+        renderer.draw_rect_alpha(Vec2::ORIGIN, size, Vec3::new(16., 16., 16.), 200);
+
+        let color = Vec3::new(200., 200., 200.);
+        let offset = (Self::MAX_LINES - self.lines.len()) as f64;
+        for (i, line) in self.lines.iter().enumerate() {
+            let top_left = Vec2::new(10., 5. + (offset + i as f64) * Self::LINE_HEIGHT);
+            renderer.draw_text(line, top_left, color, 255);
+        }
+
+        let prompt = Vec2::new(10., 5. + Self::MAX_LINES as f64 * Self::LINE_HEIGHT);
+        renderer.draw_text(
+            &format!("~ {}", typing),
+            prompt,
+            Vec3::new(255., 255., 255.),
+            255,
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use uo2d_proto::ecs::Entity;
+use uo2d_proto::packet::payloads::EmoteKind;
+use uo2d_proto::packet::{Action, Payload};
+
+use super::packet_processor::{Context, Handlers};
+
+/// An emote being played above an entity.
+#[derive(Debug, Clone)]
+pub struct Emote {
+    pub kind: EmoteKind,
+    age: u32,
+}
+
+impl Emote {
+    /// Length of time in client ticks the emote is displayed for.
+    const LIFESPAN: u32 = 60;
+    /// Client ticks each frame of the animation is shown for.
+    const FRAME_TICKS: u32 = 8;
+
+    /// Current frame of the animation.
+    pub fn text(&self) -> &'static str {
+        let frames: &[&'static str] = match self.kind {
+            EmoteKind::Wave => &["o/", "o|"],
+            EmoteKind::Dance => &["\\o/", "|o|", "/o\\"],
+            EmoteKind::Point => &["o->", "o-->"],
+        };
+        frames[(self.age / Self::FRAME_TICKS) as usize % frames.len()]
+    }
+
+    /// Checks if the emote has finished playing.
+    fn is_expired(&self) -> bool {
+        self.age >= Self::LIFESPAN
+    }
+}
+
+/// Emotes currently being played, at most one per entity.
+#[derive(Default)]
+pub struct EmoteTracker {
+    active: HashMap<Entity, Emote>,
+}
+
+impl EmoteTracker {
+    /// Plays an emote above an entity, replacing any it was already playing.
+    pub fn play(&mut self, entity: Entity, kind: EmoteKind) {
+        self.active.insert(entity, Emote { kind, age: 0 });
+    }
+
+    /// Advances the animations, removing the ones that have finished.
+    pub fn update(&mut self) {
+        for emote in self.active.values_mut() {
+            emote.age += 1;
+        }
+        self.active.retain(|_, emote| !emote.is_expired());
+    }
+
+    /// All emotes currently being played.
+    pub fn iter(&self) -> impl Iterator<Item = (&Entity, &Emote)> {
+        self.active.iter()
+    }
+}
+
+/// Registers the handler playing the emotes the server sends.
+pub(crate) fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Emote, play);
+}
+
+fn play(ctx: &mut Context, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Emote(data) => data,
+        _ => return None,
+    };
+
+    ctx.gamestate.emotes.play(payload.entity, payload.kind);
+    None
+}
@@ -0,0 +1,236 @@
+use uo2d_proto::components::{Bounds, Transform, Vec2, Vec3};
+
+use crate::Renderer;
+
+/// How the camera follows its target.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraSettings {
+    /// Portion of the screen, from 0 to 1, the target moves within before the camera follows.
+    pub deadzone: f64,
+    /// Portion of the remaining distance covered each frame, 1 follows rigidly.
+    pub smoothing: f64,
+    /// Scale the world is drawn at, above 1 shows less of it.
+    pub zoom: f64,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.2,
+            smoothing: 0.15,
+            zoom: 1.,
+        }
+    }
+}
+
+/// View of the world, its transform covers the area of the world that is visible.
+pub struct Camera {
+    transform: Transform,
+    settings: CameraSettings,
+    /// Ignores the smoothing, following the target exactly.
+    rigid: bool,
+}
+
+impl Camera {
+    /// Creates a new camera instance, `size` being the size of the screen.
+    pub fn new(position: Vec3, size: Vec2, settings: CameraSettings) -> Self {
+        let settings = CameraSettings {
+            deadzone: settings.deadzone.clamp(0., 1.),
+            smoothing: settings.smoothing.clamp(0.01, 1.),
+            zoom: settings.zoom.clamp(0.25, 4.),
+        };
+
+        let bounds = Bounds::from_vec(position, size.apply_scalar(1. / settings.zoom));
+        Self {
+            transform: Transform::from_bounds(bounds),
+            settings,
+            rigid: false,
+        }
+    }
+
+    /// Current position of the camera.
+    pub fn position(&self) -> Vec3 {
+        self.transform.position()
+    }
+
+    /// Scale the world is drawn at.
+    pub fn zoom(&self) -> f64 {
+        self.settings.zoom
+    }
+
+    /// Converts a coordinate in the world to where it is drawn on the screen.
+    /// Every conversion goes through this and `screen_to_world`, keeping them the inverse of each other.
+    pub fn world_to_screen(&self, coord: &Vec3) -> Vec2 {
+        coord
+            .offset_from_2d(&self.position())
+            .as_vec2()
+            .apply_scalar(self.zoom())
+    }
+
+    /// Converts a point on the screen, such as the mouse, to the coordinate in the world under it.
+    pub fn screen_to_world(&self, point: &Vec2) -> Vec2 {
+        let position = self.position();
+        let point = point.apply_scalar(1. / self.zoom());
+        Vec2::new(position.x() + point.x(), position.y() + point.y())
+    }
+
+    /// Centers the camera on a coordinate.
+    pub fn center_on(&mut self, coord: Vec3) {
+        let offset = Vec3::from_vec2(
+            self.bounding_box().dimensions().apply_scalar(0.5),
+            coord.z(),
+        );
+
+        self.transform.set_position(&coord.offset_from_2d(&offset));
+    }
+
+    /// Eases the camera towards a coordinate once it leaves the deadzone in the center.
+    /// Snaps to it instead if it is too far away to see, such as after respawning.
+    pub fn follow(&mut self, coord: Vec3) {
+        let size = self.bounding_box().dimensions();
+        let (dx, dy) = coord.as_vec2().offset_from(&self.center()).as_tuple();
+        if dx.abs() > size.x() || dy.abs() > size.y() {
+            self.center_on(coord);
+            return;
+        }
+
+        // Distance outside of the deadzone along an axis.
+        let half = size.apply_scalar(self.settings.deadzone / 2.);
+        let outside = |delta: f64, limit: f64| delta.signum() * (delta.abs() - limit).max(0.);
+
+        let position = self.position();
+        let step = if self.rigid {
+            1.
+        } else {
+            self.settings.smoothing
+        };
+        self.transform.set_position(&Vec3::new(
+            position.x() + outside(dx, half.x()) * step,
+            position.y() + outside(dy, half.y()) * step,
+            coord.z(),
+        ));
+    }
+
+    /// Follows the target exactly instead of easing towards it.
+    pub fn set_rigid(&mut self, rigid: bool) {
+        self.rigid = rigid;
+    }
+
+    /// Checks if a transform is in current view.
+    pub fn in_view(&self, other: &Transform) -> bool {
+        self.transform
+            .bounding_box()
+            .intersects_2d(&other.bounding_box())
+    }
+
+    /// Bounding box for the camera.
+    pub fn bounding_box(&self) -> Bounds {
+        self.transform.bounding_box()
+    }
+
+    /// Center based on current coordinate.
+    pub fn center(&self) -> Vec2 {
+        self.bounding_box().center_2d()
+    }
+
+    /// Draws a transform to the screen.
+    pub fn draw(&self, renderer: &mut dyn Renderer, object: &Transform, border: u32, color: Vec3) {
+        // Prevent drawing items not inview.
+        if !self.in_view(object) {
+            return;
+        }
+
+        let pos = self.world_to_screen(&object.position());
+        let size = object.bounding_box().dimensions().apply_scalar(self.zoom());
+
+        if border != 0 {
+            // Draw the border
+            renderer.draw_rect(pos, size, Vec3::ORIGIN);
+        }
+
+        // Draw the base square on top of the border
+        let border = border as f64;
+        renderer.draw_rect(
+            Vec2::new(pos.x() + border, pos.y() + border),
+            Vec2::new(size.x() - border * 2., size.y() - border * 2.),
+            color,
+        );
+    }
+
+    /// Draws a sprite stretched over a transform, returns false if the sprite is not loaded.
+    pub fn draw_sprite(
+        &self,
+        renderer: &mut dyn Renderer,
+        sprite: &str,
+        object: &Transform,
+    ) -> bool {
+        if renderer.sprite_size(sprite).is_none() {
+            return false;
+        } else if !self.in_view(object) {
+            return true;
+        }
+
+        let pos = self.world_to_screen(&object.position());
+        let size = object.bounding_box().dimensions().apply_scalar(self.zoom());
+        renderer.draw_sprite(sprite, pos, size);
+        true
+    }
+
+    /// Draws a transform to the screen, blended over what is beneath it.
+    pub fn draw_translucent(
+        &self,
+        renderer: &mut dyn Renderer,
+        object: &Transform,
+        color: Vec3,
+        alpha: u8,
+    ) {
+        if !self.in_view(object) {
+            return;
+        }
+
+        let pos = self.world_to_screen(&object.position());
+        let size = object.bounding_box().dimensions().apply_scalar(self.zoom());
+        renderer.draw_rect_alpha(pos, size, color, alpha);
+    }
+
+    /// Draws a line between two coordinates to the screen.
+    pub fn draw_line(&self, renderer: &mut dyn Renderer, from: Vec2, to: Vec2, color: Vec3) {
+        let view = self.bounding_box();
+        let (left, right) = (from.x().min(to.x()), from.x().max(to.x()));
+        let (top, bottom) = (from.y().min(to.y()), from.y().max(to.y()));
+        // Prevent drawing lines not inview.
+        let outside_x = right < view.x() || left > view.x() + view.width();
+        let outside_y = bottom < view.y() || top > view.y() + view.height();
+        if outside_x || outside_y {
+            return;
+        }
+
+        let from = self.world_to_screen(&Vec3::new(from.x(), from.y(), 0.));
+        let to = self.world_to_screen(&Vec3::new(to.x(), to.y(), 0.));
+        renderer.draw_line(from, to, color);
+    }
+
+    /// Draws text centered above a coordinate to the screen.
+    pub fn draw_text(
+        &self,
+        renderer: &mut dyn Renderer,
+        text: &str,
+        coord: Vec3,
+        color: Vec3,
+        alpha: u8,
+    ) {
+        // Prevent drawing text not inview.
+        if !self.bounding_box().coord_within_2d(&coord) {
+            return;
+        }
+
+        let size = match renderer.text_size(text) {
+            Some(size) => size,
+            None => return,
+        };
+
+        let pos = self.world_to_screen(&coord);
+        let top_left = Vec2::new(pos.x() - (size.x() / 2.).floor(), pos.y() - size.y());
+        renderer.draw_text(text, top_left, color, alpha);
+    }
+}
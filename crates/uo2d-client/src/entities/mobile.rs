@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use uo2d_proto::components::{
+    Bounds, Equipment, MovementMode, StatusEffect, Team, Transform, Vec2, Vec3,
+};
+use uo2d_proto::ecs::Entity;
+use uo2d_proto::packet::payloads::FallPayload;
+use uo2d_proto::time::Instant;
+
+/// Server side representation of an entity to check movement.
+#[derive(Clone)]
+pub struct Mobile {
+    pub entity: Entity,
+    pub transform: Transform,
+    pub faced_left: bool,
+    pub appearance: Equipment,
+    pub team: Option<Team>,
+    pub effects: HashSet<StatusEffect>,
+    /// Entity it is attacking, only known for NPCs.
+    pub target: Option<Entity>,
+    pub mounted: bool,
+    pub mode: MovementMode,
+    /// Drop from a ledge being animated, and when it began.
+    pub falling: Option<(FallPayload, Instant)>,
+}
+
+impl Mobile {
+    /// Creates a new entity including the rectangle for collision checkings.
+    pub fn new(entity: Entity, coord: Vec3, size: Vec2) -> Self {
+        Self {
+            entity,
+            transform: Transform::from_vecs(coord, size),
+            faced_left: false,
+            appearance: Equipment::default(),
+            team: None,
+            effects: HashSet::new(),
+            target: None,
+            mounted: false,
+            mode: MovementMode::Walking,
+            falling: None,
+        }
+    }
+
+    /// Obtains the bounding box of the entity.
+    pub fn bounding_box(&self) -> Bounds {
+        self.transform.bounding_box()
+    }
+
+    /// Current position of the entity.
+    pub fn position(&self) -> Vec3 {
+        self.bounding_box().top_left_3d()
+    }
+
+    /// Layer the entity is drawn at, eased between the layers while it falls.
+    pub fn layer(&self) -> f64 {
+        match &self.falling {
+            Some((fall, at)) if at.elapsed().as_secs_f32() < fall.duration => {
+                let progress = (at.elapsed().as_secs_f32() / fall.duration) as f64;
+                // Falling speeds up as it goes.
+                fall.from - (fall.from - fall.to) * progress * progress
+            }
+            _ => self.position().z(),
+        }
+    }
+
+    /// Maximum size of the collision box for the entity.
+    pub fn size(&self) -> Vec2 {
+        self.transform.bounding_box().dimensions()
+    }
+}
+
+impl PartialEq for Mobile {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity
+    }
+}
+
+impl Eq for Mobile {}
+
+impl Hash for Mobile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.entity.hash(state);
+    }
+}
@@ -1,7 +1,5 @@
 mod camera;
 mod mobile;
-mod region;
 
 pub use camera::*;
 pub use mobile::*;
-pub use region::*;
@@ -0,0 +1,180 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+#[cfg(not(target_arch = "wasm32"))]
+use sdl2::EventPump;
+use uo2d_proto::components::{Vec2, Vec3};
+use uo2d_proto::locale::Locale;
+#[cfg(not(target_arch = "wasm32"))]
+use uo2d_proto::time::Duration;
+
+use crate::input::{InputEvent, Key, MouseButton};
+use crate::renderer::Renderer;
+
+/// What the player chose to do after the game stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorChoice {
+    Retry,
+    Quit,
+}
+
+/// Explains why the game stopped, letting the player retry or quit.
+pub struct ErrorScreen {
+    reason: String,
+    title: String,
+    hint: String,
+    retry: String,
+    quit: String,
+    /// Shown beneath the choices, such as a newer release being available.
+    notice: Option<String>,
+}
+
+impl ErrorScreen {
+    const BUTTON_WIDTH: f64 = 120.;
+    const BUTTON_HEIGHT: f64 = 32.;
+    const BUTTON_GAP: f64 = 20.;
+    const LINE_SPACING: f64 = 28.;
+    /// Time between redraws while waiting on the player.
+    #[cfg(not(target_arch = "wasm32"))]
+    const FRAME: Duration = Duration::from_millis(33);
+
+    pub fn new(reason: impl ToString, locale: &Locale) -> Self {
+        Self {
+            reason: reason.to_string(),
+            title: locale.get("error.title").to_string(),
+            hint: locale.get("error.hint").to_string(),
+            retry: locale.get("error.retry").to_string(),
+            quit: locale.get("error.quit").to_string(),
+            notice: None,
+        }
+    }
+
+    /// Shows a line beneath the choices.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn notice(mut self, notice: Option<String>) -> Self {
+        self.notice = notice;
+        self
+    }
+
+    /// Buttons centered below the reason, with what choosing them does.
+    fn buttons(&self, screen: Vec2) -> [(ErrorChoice, &str, Vec2); 2] {
+        let width = Self::BUTTON_WIDTH * 2. + Self::BUTTON_GAP;
+        let left = (screen.x() - width) / 2.;
+        let top = screen.y() / 2. + Self::LINE_SPACING * 2.;
+        [
+            (ErrorChoice::Retry, &self.retry, Vec2::new(left, top)),
+            (
+                ErrorChoice::Quit,
+                &self.quit,
+                Vec2::new(left + Self::BUTTON_WIDTH + Self::BUTTON_GAP, top),
+            ),
+        ]
+    }
+
+    /// Button beneath a point on the screen, if any.
+    fn button_at(&self, screen: Vec2, point: Vec2) -> Option<ErrorChoice> {
+        self.buttons(screen)
+            .into_iter()
+            .find(|(_, _, top_left)| {
+                point.x() >= top_left.x()
+                    && point.x() <= top_left.x() + Self::BUTTON_WIDTH
+                    && point.y() >= top_left.y()
+                    && point.y() <= top_left.y() + Self::BUTTON_HEIGHT
+            })
+            .map(|(choice, _, _)| choice)
+    }
+
+    /// Draws text centered horizontally.
+    fn draw_centered(renderer: &mut dyn Renderer, text: &str, y: f64, color: Vec3) {
+        let width = renderer.text_size(text).map_or(0., |size| size.x());
+        let top_left = Vec2::new((renderer.screen_size().x() - width) / 2., y);
+        renderer.draw_text(text, top_left, color, 255);
+    }
+
+    /// Draws the reason and the choices over a blank screen.
+    pub fn draw(&self, renderer: &mut dyn Renderer) {
+        let screen = renderer.screen_size();
+        let middle = screen.y() / 2.;
+
+        renderer.clear();
+        Self::draw_centered(
+            renderer,
+            &self.title,
+            middle - Self::LINE_SPACING * 2.,
+            Vec3::new(255., 64., 64.),
+        );
+        Self::draw_centered(
+            renderer,
+            &self.reason,
+            middle - Self::LINE_SPACING,
+            Vec3::new(255., 255., 255.),
+        );
+        Self::draw_centered(renderer, &self.hint, middle, Vec3::new(160., 160., 160.));
+
+        for (_, label, top_left) in self.buttons(screen) {
+            let size = Vec2::new(Self::BUTTON_WIDTH, Self::BUTTON_HEIGHT);
+            renderer.draw_rect(top_left, size, Vec3::new(64., 64., 64.));
+            let width = renderer.text_size(label).map_or(0., |size| size.x());
+            let text = Vec2::new(
+                top_left.x() + (Self::BUTTON_WIDTH - width) / 2.,
+                top_left.y() + 6.,
+            );
+            renderer.draw_text(label, text, Vec3::new(255., 255., 255.), 255);
+        }
+
+        if let Some(notice) = &self.notice {
+            let y = middle + Self::LINE_SPACING * 4.;
+            Self::draw_centered(renderer, notice, y, Vec3::new(255., 215., 0.));
+        }
+        renderer.present();
+    }
+
+    /// Choice made by an event, if any.
+    pub fn choose(&self, screen: Vec2, event: &InputEvent) -> Option<ErrorChoice> {
+        match event {
+            #[cfg(not(target_arch = "wasm32"))]
+            InputEvent::Quit => Some(ErrorChoice::Quit),
+            InputEvent::KeyDown {
+                key: Some(key),
+                repeat: false,
+            } => match key {
+                Key::R | Key::Return => Some(ErrorChoice::Retry),
+                Key::Escape | Key::Q => Some(ErrorChoice::Quit),
+                _ => None,
+            },
+            InputEvent::MouseUp {
+                button: MouseButton::Left,
+                position,
+            } => self.button_at(screen, *position),
+            _ => None,
+        }
+    }
+
+    /// Shows the screen until the player chooses, quitting if the process is asked to stop.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run(
+        &self,
+        renderer: &mut dyn Renderer,
+        pump: &mut EventPump,
+        interrupted: &AtomicBool,
+    ) -> ErrorChoice {
+        loop {
+            if interrupted.load(Ordering::Relaxed) {
+                return ErrorChoice::Quit;
+            }
+
+            let screen = renderer.screen_size();
+            let (_, events) = crate::input::events(pump);
+            for event in &events {
+                if let Some(choice) = self.choose(screen, event) {
+                    return choice;
+                }
+            }
+
+            self.draw(renderer);
+            thread::sleep(Self::FRAME);
+        }
+    }
+}
@@ -0,0 +1,977 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::{
+    Ability, AreaShape, Bounds, EquipSlot, Equipment, ItemStack, MovementMode, StatusEffect, Team,
+    Transform, Vec2, Vec3,
+};
+use uo2d_proto::ecs::Entity;
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::{Locale, Text};
+use uo2d_proto::packet::payloads::{
+    AreaWarningPayload, CastPayload, CastStage, DialoguePayload, FallPayload, FlagStatus,
+    HudPayload, LeaderboardEntry, Letter, MatchPayload, MatchPhase, NotificationKind, OverlayKind,
+    OverlayPayload, OverlayShape, RedirectPayload,
+};
+use uo2d_proto::time::Instant;
+use uo2d_proto::timer::TimerManager;
+
+use super::bank::BankWindow;
+use super::chunks::ChunkCache;
+use super::combat_text::CombatTextPool;
+use super::emotes::EmoteTracker;
+use super::quality::Quality;
+use super::renderer::Renderer;
+use super::toast::ToastQueue;
+use super::trade::TradeWindow;
+use super::transport::ConnectionState;
+use crate::entities::{Camera, Mobile};
+
+/// Current tracked state of the game.
+pub struct Gamestate {
+    pub timers: TimerManager,
+    locations: HashMap<Entity, i8>,
+    pub entities: HashMap<i8, HashMap<Entity, Mobile>>,
+    pub kill: bool,
+    pub error: Option<String>,
+    pub combat_text: CombatTextPool,
+    pub toasts: ToastQueue,
+    pub emotes: EmoteTracker,
+    pub leaderboard: Vec<LeaderboardEntry>,
+    /// Letters in the mailbox when it was last opened.
+    pub mailbox: Vec<Letter>,
+    pub unread_mail: u32,
+    /// Server the client has been asked to move to.
+    pub redirect: Option<RedirectPayload>,
+    /// Page of the conversation with an NPC currently being shown.
+    pub dialogue: Option<DialoguePayload>,
+    /// Invitation or trade with another player currently being shown.
+    pub trade: TradeWindow,
+    /// Stacks kept at the bank while it is open.
+    pub bank: BankWindow,
+    pub hud: Option<HudPayload>,
+    /// Phase of the match and when it was received.
+    pub matches: Option<(MatchPayload, Instant)>,
+    pub items: ItemManager,
+    /// Translations for text shown to the player.
+    pub locale: Locale,
+    /// Level of detail to draw at, lowered when frames take too long.
+    pub quality: Quality,
+    pub inventory: Vec<ItemStack>,
+    pub capacity: u32,
+    pub equipment: Equipment,
+    pub ground: HashMap<Entity, (ItemStack, Bounds)>,
+    /// Areas about to be struck or still active, and when they were warned about.
+    warnings: HashMap<Entity, (AreaWarningPayload, Instant)>,
+    pub chunks: ChunkCache,
+    keyframes: HashMap<Entity, (u8, Vec3, Vec2)>,
+    /// Most recent shapes of each debug overlay being viewed.
+    pub overlays: HashMap<OverlayKind, Vec<OverlayShape>>,
+    /// Abilities being cast, how long they take, and when they began.
+    casts: HashMap<Entity, (Ability, f32, Instant)>,
+    player: Entity,
+}
+
+impl Gamestate {
+    const MAX_COMBAT_TEXT: usize = 32;
+    const MAX_TOASTS: usize = 5;
+    const TOAST_SPACING: f64 = 20.;
+    const EMOTE_HEIGHT: f64 = 20.;
+    const SCOREBOARD_WIDTH: f64 = 500.;
+    const DIALOGUE_OFFSET: f64 = 48.;
+    /// Characters per line of dialogue when the text cannot be measured.
+    const WRAP_LENGTH: usize = 60;
+    const CHUNK_BUDGET: usize = 64 * 1024;
+    const BLINK_TICKS: u64 = 3;
+    const ELEVATION: f64 = 4.;
+    const SHADOW_COLOR: [u8; 3] = [16, 16, 16];
+    const WARNING_COLOR: [u8; 3] = [255, 200, 64];
+    const ACTIVE_COLOR: [u8; 3] = [220, 40, 40];
+    /// Segments the outline of a circular overlay is drawn with.
+    const OVERLAY_SEGMENTS: usize = 32;
+    const CAST_HEIGHT: f64 = 6.;
+    /// Width of a mount relative to its rider.
+    const MOUNT_SCALE: f64 = 1.6;
+    const MOUNT_SPRITE: &str = "mount";
+    const MOUNT_COLOR: [u8; 3] = [120, 80, 40];
+    /// Portion of a swimmer that stays above the water.
+    const SWIM_DEPTH: f64 = 0.5;
+    const CAST_COLOR: [u8; 3] = [64, 160, 255];
+
+    /// Initializes the gamestate.
+    pub fn new(locale: Locale) -> Self {
+        Self {
+            timers: TimerManager::new(),
+            locations: HashMap::new(),
+            entities: HashMap::new(),
+            kill: false,
+            error: None,
+            combat_text: CombatTextPool::new(Self::MAX_COMBAT_TEXT),
+            toasts: ToastQueue::new(Self::MAX_TOASTS),
+            emotes: EmoteTracker::default(),
+            leaderboard: Vec::new(),
+            mailbox: Vec::new(),
+            unread_mail: 0,
+            redirect: None,
+            dialogue: None,
+            trade: TradeWindow::default(),
+            bank: BankWindow::default(),
+            hud: None,
+            matches: None,
+            items: ItemManager::new(),
+            locale,
+            quality: Quality::High,
+            inventory: Vec::new(),
+            capacity: 0,
+            equipment: Equipment::default(),
+            ground: HashMap::new(),
+            warnings: HashMap::new(),
+            chunks: ChunkCache::new(Self::CHUNK_BUDGET),
+            keyframes: HashMap::new(),
+            overlays: HashMap::new(),
+            casts: HashMap::new(),
+            player: Entity::INVALID,
+        }
+    }
+
+    /// Forgets every entity and item on the ground, used when entering a different world.
+    pub fn clear_world(&mut self) {
+        self.locations.clear();
+        self.entities.clear();
+        self.keyframes.clear();
+        self.ground.clear();
+        self.warnings.clear();
+        self.overlays.clear();
+        self.casts.clear();
+        self.dialogue = None;
+    }
+
+    /// Switches the language text is shown in, keeping the current one if it is not translated.
+    pub fn set_language(&mut self, language: &str) {
+        let languages = Locale::languages();
+        if !languages.iter().any(|known| known == language) {
+            let text = Text::new("language.unknown").with("languages", languages.join(", "));
+            let text = self.locale.text(&text);
+            self.toasts.push(NotificationKind::Warning, text);
+            return;
+        }
+
+        self.locale = Locale::new(language);
+        let text = self.locale.get("language.changed").to_string();
+        self.toasts.push(NotificationKind::Announcement, text);
+    }
+
+    /// Changes the level of detail drawn at, letting the player know why things disappeared.
+    pub fn set_quality(&mut self, quality: Quality) {
+        let level = self
+            .locale
+            .get(&format!("quality.{}", quality.name()))
+            .to_string();
+        let text = self
+            .locale
+            .text(&Text::new("quality.changed").with("level", level));
+        self.toasts.push(NotificationKind::Announcement, text);
+        self.quality = quality;
+    }
+
+    /// Sets the player / entity belonging to the client.
+    pub fn set_player(&mut self, entity: Entity) {
+        self.player = entity;
+    }
+
+    /// Obtains the entity representing the client.
+    pub fn get_player(&self) -> Entity {
+        self.player
+    }
+
+    // Get a mobile by its entity.
+    pub fn get_mobile(&self, entity: &Entity) -> Option<&Mobile> {
+        if let Some(layer) = self.locations.get(entity) {
+            if let Some(entities) = self.entities.get(layer) {
+                return entities.get(entity);
+            }
+        }
+        None
+    }
+
+    /// Obtains the mobile other than the player drawn beneath a point, the highest if several are.
+    pub fn mobile_at(&self, point: Vec2) -> Option<Entity> {
+        self.entities
+            .values()
+            .flat_map(|entities| entities.values())
+            .filter(|mobile| mobile.entity != self.player)
+            .filter(|mobile| {
+                let bounds = Self::raised(mobile).bounding_box();
+                bounds.coord_within_2d(&Vec3::new(point.x(), point.y(), bounds.z()))
+            })
+            .max_by(|a, b| a.layer().total_cmp(&b.layer()))
+            .map(|mobile| mobile.entity)
+    }
+
+    /// Updates an entity's position and size, if it exists, or inserts a new entity.
+    pub fn upsert_entity(&mut self, entity: Entity, position: Vec3, size: Vec2) {
+        // Create or update the entity, keeping its existing appearance.
+        let mut mobile = Mobile::new(entity, position, size);
+        if let Some(existing) = self.get_mobile(&entity) {
+            mobile.appearance = existing.appearance;
+        }
+
+        // Assign entity to the new layer and update locations mapping.
+        self.locations.insert(entity, position.z() as i8);
+        self.entities
+            .entry(position.z() as i8)
+            .or_default()
+            .insert(entity, mobile);
+    }
+
+    /// Stores the position later movement deltas for an entity are relative to, ignoring a
+    /// keyframe resent after a newer one arrived.
+    pub fn set_keyframe(&mut self, entity: Entity, keyframe: u8, position: Vec3, size: Vec2) {
+        if let Some((current, ..)) = self.keyframes.get(&entity) {
+            if (keyframe.wrapping_sub(*current) as i8) < 0 {
+                return;
+            }
+        }
+
+        self.keyframes.insert(entity, (keyframe, position, size));
+        self.upsert_entity(entity, position, size);
+    }
+
+    /// Moves an entity by an offset from its keyframe, ignored if that keyframe was missed.
+    pub fn apply_delta(&mut self, entity: Entity, keyframe: u8, offset: Vec2) {
+        let (position, size) = match self.keyframes.get(&entity) {
+            Some((id, position, size)) if *id == keyframe => (*position, *size),
+            _ => return,
+        };
+
+        let position = Vec3::new(
+            position.x() + offset.x(),
+            position.y() + offset.y(),
+            position.z(),
+        );
+        self.upsert_entity(entity, position, size);
+    }
+
+    /// Sets the visible equipment and team for an entity.
+    pub fn set_appearance(&mut self, entity: Entity, appearance: Equipment, team: Option<Team>) {
+        if let Some(layer) = self.locations.get(&entity) {
+            if let Some(mobile) = self
+                .entities
+                .get_mut(layer)
+                .and_then(|entities| entities.get_mut(&entity))
+            {
+                mobile.appearance = appearance;
+                mobile.team = team;
+            }
+        }
+    }
+
+    /// Shows or hides the mount an entity is riding.
+    pub fn set_mounted(&mut self, entity: Entity, mounted: bool) {
+        if let Some(layer) = self.locations.get(&entity) {
+            if let Some(mobile) = self
+                .entities
+                .get_mut(layer)
+                .and_then(|entities| entities.get_mut(&entity))
+            {
+                mobile.mounted = mounted;
+            }
+        }
+    }
+
+    /// Animates an entity dropping from a ledge.
+    pub fn set_falling(&mut self, fall: FallPayload) {
+        if let Some(layer) = self.locations.get(&fall.entity) {
+            if let Some(mobile) = self
+                .entities
+                .get_mut(layer)
+                .and_then(|entities| entities.get_mut(&fall.entity))
+            {
+                mobile.falling = Some((fall, Instant::now()));
+            }
+        }
+    }
+
+    /// Changes how an entity is drawn moving, such as swimming.
+    pub fn set_mode(&mut self, entity: Entity, mode: MovementMode) {
+        if let Some(layer) = self.locations.get(&entity) {
+            if let Some(mobile) = self
+                .entities
+                .get_mut(layer)
+                .and_then(|entities| entities.get_mut(&entity))
+            {
+                mobile.mode = mode;
+            }
+        }
+    }
+
+    /// Starts or ends a status effect on an entity.
+    pub fn set_effect(&mut self, entity: Entity, effect: StatusEffect, active: bool) {
+        if let Some(layer) = self.locations.get(&entity) {
+            if let Some(mobile) = self
+                .entities
+                .get_mut(layer)
+                .and_then(|entities| entities.get_mut(&entity))
+            {
+                if active {
+                    mobile.effects.insert(effect);
+                } else {
+                    mobile.effects.remove(&effect);
+                }
+            }
+        }
+    }
+
+    /// Sets the entity an NPC is attacking.
+    pub fn set_target(&mut self, entity: Entity, target: Option<Entity>) {
+        if let Some(layer) = self.locations.get(&entity) {
+            if let Some(mobile) = self
+                .entities
+                .get_mut(layer)
+                .and_then(|entities| entities.get_mut(&entity))
+            {
+                mobile.target = target;
+            }
+        }
+    }
+
+    /// Shows where an area is about to be struck, forgetting those that have faded.
+    pub fn warn(&mut self, warning: AreaWarningPayload) {
+        self.warnings.retain(|_, (warning, at)| {
+            at.elapsed().as_secs_f32() < warning.delay + warning.duration
+        });
+        self.warnings
+            .insert(warning.entity, (warning, Instant::now()));
+    }
+
+    /// Tracks the progress of a cast, forgetting it once it finishes or is interrupted.
+    pub fn set_cast(&mut self, cast: CastPayload) {
+        match cast.stage {
+            CastStage::Started(duration) => {
+                self.casts
+                    .insert(cast.entity, (cast.ability, duration, Instant::now()));
+            }
+            CastStage::Finished | CastStage::Interrupted => {
+                self.casts.remove(&cast.entity);
+            }
+        }
+    }
+
+    /// Removes an entity from being tracked.
+    pub fn remove_entity(&mut self, entity: &Entity) {
+        self.keyframes.remove(entity);
+        self.warnings.remove(entity);
+        self.casts.remove(entity);
+
+        // First, find the layer the entity is in using the locations map and remove the entry.
+        if let Some(layer) = self.locations.remove(entity) {
+            // Then, access the sub-map for the layer and attempt to remove the entity by its UUID.
+            if let Some(entities) = self.entities.get_mut(&layer) {
+                entities.remove(entity);
+
+                // Remove the layer if it is empty.
+                if entities.is_empty() {
+                    self.entities.remove(&layer);
+                }
+            }
+        }
+    }
+
+    /// Draws all currently stored entities.
+    pub fn draw(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        let mut layers: Vec<&i8> = self.entities.keys().collect();
+        layers.sort();
+
+        // The map is beneath everything else, followed by items on the ground.
+        self.chunks.draw(renderer, camera);
+
+        for (stack, bounds) in self.ground.values() {
+            if let Some(item) = self.items.get(&stack.item) {
+                let transform = Transform::from_bounds(*bounds);
+                if camera.draw_sprite(renderer, item.sprite(), &transform) {
+                    continue;
+                }
+
+                let [r, g, b] = item.color;
+                let color = Vec3::new(r as f64, g as f64, b as f64);
+                camera.draw(renderer, &transform, 1, color);
+            }
+        }
+
+        // Flags from the game mode lie beneath the players carrying them.
+        if let Some(HudPayload::Ctf { flags, .. }) = &self.hud {
+            for flag in flags {
+                let [r, g, b] = flag.team.color();
+                let color = Vec3::new(r as f64, g as f64, b as f64);
+                camera.draw(renderer, &Transform::from_bounds(flag.bounds), 1, color);
+            }
+        }
+
+        // Areas are painted on the ground, growing brighter until they strike.
+        for (warning, at) in self.warnings.values() {
+            let elapsed = at.elapsed().as_secs_f32();
+            let (color, alpha) = if elapsed < warning.delay {
+                let progress = (elapsed / warning.delay) as f64;
+                let alpha = 64. + 96. * progress;
+                (Self::WARNING_COLOR, alpha as u8)
+            } else if elapsed < warning.delay + warning.duration {
+                (Self::ACTIVE_COLOR, 176)
+            } else {
+                continue;
+            };
+            let [r, g, b] = color;
+            let color = Vec3::new(r as f64, g as f64, b as f64);
+            let strips = self.quality.area_strips();
+            Self::draw_area(renderer, camera, warning, strips, color, alpha);
+        }
+
+        // Shadows stay on the ground beneath every mobile, regardless of their layer.
+        let mobiles = || {
+            layers
+                .iter()
+                .filter_map(|layer| self.entities.get(layer))
+                .flat_map(|entities| entities.values())
+        };
+        if self.quality.shadows() {
+            for entity in mobiles().filter(|mobile| mobile.mode != MovementMode::Swimming) {
+                Self::draw_shadow(renderer, camera, entity);
+            }
+        }
+
+        // Invulnerable mobiles blink in and out of view.
+        let blink = (self.timers.tick() / Self::BLINK_TICKS).is_multiple_of(2);
+
+        // Higher layers are drawn last and raised above their shadow.
+        for entity in mobiles() {
+            if blink && entity.effects.contains(&StatusEffect::Invulnerable) {
+                continue;
+            }
+
+            let raised = Self::raised(entity);
+            if entity.mode == MovementMode::Swimming {
+                Self::draw_swimming(renderer, camera, &raised);
+                continue;
+            }
+
+            if entity.mounted {
+                Self::draw_mount(renderer, camera, &raised);
+            }
+
+            // Teams are drawn in their color, everything else is red.
+            let [r, g, b] = entity.team.map_or([255, 0, 0], |team| team.color());
+            let color = Vec3::new(r as f64, g as f64, b as f64);
+            camera.draw(renderer, &raised.transform, 2, color);
+            self.draw_equipment(renderer, camera, &raised);
+        }
+    }
+
+    /// Draws an area translucent over the ground, circles being built from horizontal strips.
+    fn draw_area(
+        renderer: &mut dyn Renderer,
+        camera: &Camera,
+        warning: &AreaWarningPayload,
+        strips: usize,
+        color: Vec3,
+        alpha: u8,
+    ) {
+        let bounds = warning.shape.bounds(warning.center);
+        match warning.shape {
+            AreaShape::Rect { .. } => {
+                camera.draw_translucent(renderer, &Transform::from_bounds(bounds), color, alpha);
+            }
+            AreaShape::Circle { radius } => {
+                let height = bounds.height() / strips as f64;
+                for strip in 0..strips {
+                    let offset = (strip as f64 + 0.5) * height - radius;
+                    let width = 2. * (radius * radius - offset * offset).max(0.).sqrt();
+                    let position = Vec3::new(
+                        warning.center.x() - width / 2.,
+                        bounds.y() + strip as f64 * height,
+                        bounds.z(),
+                    );
+                    let transform = Transform::from_vecs(position, Vec2::new(width, height));
+                    camera.draw_translucent(renderer, &transform, color, alpha);
+                }
+            }
+        }
+    }
+
+    /// Layers a mobile is above the ground.
+    fn height(mobile: &Mobile) -> f64 {
+        mobile.layer().max(0.)
+    }
+
+    /// Copy of a mobile moved to where it is drawn, higher layers being raised up the screen.
+    fn raised(mobile: &Mobile) -> Mobile {
+        let position = mobile.position();
+        let lift = Self::height(mobile) * Self::ELEVATION;
+
+        let mut raised = mobile.clone();
+        raised
+            .transform
+            .set_position(&Vec3::new(position.x(), position.y() - lift, position.z()));
+        raised
+    }
+
+    /// Draws the shadow at the feet of a mobile, growing the higher it is.
+    fn draw_shadow(renderer: &mut dyn Renderer, camera: &Camera, mobile: &Mobile) {
+        let bounds = mobile.bounding_box();
+        let scale = 0.8 + Self::height(mobile) * 0.1;
+        let size = Vec2::new(bounds.width() * scale, bounds.height() * 0.25);
+        let position = Vec3::new(
+            bounds.center_2d().x() - size.x() / 2.,
+            bounds.y() + bounds.height() - size.y() / 2.,
+            bounds.z(),
+        );
+
+        let [r, g, b] = Self::SHADOW_COLOR;
+        let color = Vec3::new(r as f64, g as f64, b as f64);
+        camera.draw(renderer, &Transform::from_vecs(position, size), 0, color);
+    }
+
+    /// Draws the part of a swimmer above the water, their equipment hidden beneath it.
+    fn draw_swimming(renderer: &mut dyn Renderer, camera: &Camera, mobile: &Mobile) {
+        let bounds = mobile.bounding_box();
+        let size = Vec2::new(bounds.width(), bounds.height() * Self::SWIM_DEPTH);
+        let [r, g, b] = mobile.team.map_or([255, 0, 0], |team| team.color());
+        let color = Vec3::new(r as f64, g as f64, b as f64);
+        camera.draw(
+            renderer,
+            &Transform::from_vecs(bounds.top_left_3d(), size),
+            2,
+            color,
+        );
+    }
+
+    /// Draws the mount beneath a rider, wider than they are and covering their lower half.
+    fn draw_mount(renderer: &mut dyn Renderer, camera: &Camera, mobile: &Mobile) {
+        let bounds = mobile.bounding_box();
+        let size = Vec2::new(
+            bounds.width() * Self::MOUNT_SCALE,
+            bounds.height() * Self::MOUNT_SCALE / 2.,
+        );
+        let position = Vec3::new(
+            bounds.center_2d().x() - size.x() / 2.,
+            bounds.y() + bounds.height() - size.y() * 0.75,
+            bounds.z(),
+        );
+
+        let transform = Transform::from_vecs(position, size);
+        if !camera.draw_sprite(renderer, Self::MOUNT_SPRITE, &transform) {
+            let [r, g, b] = Self::MOUNT_COLOR;
+            camera.draw(
+                renderer,
+                &transform,
+                1,
+                Vec3::new(r as f64, g as f64, b as f64),
+            );
+        }
+    }
+
+    /// Draws the equipped items layered over a mobile.
+    fn draw_equipment(&self, renderer: &mut dyn Renderer, camera: &Camera, mobile: &Mobile) {
+        let bounds = mobile.bounding_box();
+        let (x, y, z) = bounds.top_left_3d().as_tuple();
+        let (width, height) = (bounds.width(), bounds.height());
+
+        for slot in EquipSlot::ALL {
+            let item = match mobile
+                .appearance
+                .get(slot)
+                .and_then(|id| self.items.get(&id))
+            {
+                Some(item) => item,
+                None => continue,
+            };
+
+            // Each slot covers a portion of the mobile, weapons are held to the side.
+            let (position, size) = match slot {
+                EquipSlot::Head => (Vec3::new(x, y, z), Vec2::new(width, height * 0.3)),
+                EquipSlot::Body => (
+                    Vec3::new(x, y + height * 0.3, z),
+                    Vec2::new(width, height * 0.5),
+                ),
+                EquipSlot::Weapon => {
+                    let offset = if mobile.faced_left {
+                        -width * 0.3
+                    } else {
+                        width
+                    };
+                    (
+                        Vec3::new(x + offset, y + height * 0.4, z),
+                        Vec2::new(width * 0.3, height * 0.4),
+                    )
+                }
+            };
+
+            let [r, g, b] = item.color;
+            let color = Vec3::new(r as f64, g as f64, b as f64);
+            camera.draw(renderer, &Transform::from_vecs(position, size), 1, color);
+        }
+    }
+
+    /// Draws the floating combat text above entities.
+    pub fn draw_combat_text(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        if !self.quality.combat_text() {
+            return;
+        }
+
+        for text in self.combat_text.iter() {
+            camera.draw_text(
+                renderer,
+                &text.text(),
+                text.position,
+                text.color(),
+                text.alpha(),
+            );
+        }
+    }
+
+    /// Draws the emotes being played above entities.
+    pub fn draw_emotes(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        for (entity, emote) in self.emotes.iter() {
+            if let Some(mobile) = self.get_mobile(entity) {
+                let bounds = mobile.bounding_box();
+                let position = Vec3::new(bounds.x(), bounds.y() - Self::EMOTE_HEIGHT, bounds.z());
+                camera.draw_text(
+                    renderer,
+                    emote.text(),
+                    position,
+                    Vec3::new(255., 255., 255.),
+                    255,
+                );
+            }
+        }
+    }
+
+    /// Marks the mobiles attacking the player above their heads.
+    pub fn draw_threats(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        let mobiles = self
+            .entities
+            .values()
+            .flat_map(|entities| entities.values());
+        for mobile in mobiles.filter(|mobile| mobile.target == Some(self.player)) {
+            let bounds = Self::raised(mobile).bounding_box();
+            let position = Vec3::new(
+                bounds.center_2d().x(),
+                bounds.y() - Self::EMOTE_HEIGHT,
+                bounds.z(),
+            );
+            camera.draw_text(renderer, "!", position, Vec3::new(255., 64., 64.), 255);
+        }
+    }
+
+    /// Draws a bar beneath the entities casting, filling as the cast completes.
+    pub fn draw_casts(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        for (entity, (_, duration, started)) in self.casts.iter() {
+            let mobile = match self.get_mobile(entity) {
+                Some(mobile) => mobile,
+                None => continue,
+            };
+
+            let bounds = mobile.bounding_box();
+            let progress = if *duration > 0. {
+                (started.elapsed().as_secs_f32() / duration).min(1.) as f64
+            } else {
+                1.
+            };
+            let position = Vec3::new(bounds.x(), bounds.y() + bounds.height() + 2., bounds.z());
+            let size = Vec2::new(bounds.width(), Self::CAST_HEIGHT);
+            camera.draw(
+                renderer,
+                &Transform::from_vecs(position, size),
+                1,
+                Vec3::new(48., 48., 48.),
+            );
+
+            let [r, g, b] = Self::CAST_COLOR;
+            let filled = Vec2::new(bounds.width() * progress, Self::CAST_HEIGHT);
+            camera.draw(
+                renderer,
+                &Transform::from_vecs(position, filled),
+                1,
+                Vec3::new(r as f64, g as f64, b as f64),
+            );
+        }
+    }
+
+    /// Replaces the shapes of a debug overlay.
+    pub fn set_overlay(&mut self, overlay: OverlayPayload) {
+        self.overlays.insert(overlay.kind, overlay.shapes);
+    }
+
+    /// Draws the outlines of the debug overlays being viewed.
+    pub fn draw_overlays(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        for (kind, shapes) in self.overlays.iter() {
+            let color = match kind {
+                OverlayKind::Bounds => Vec3::new(0., 255., 0.),
+                OverlayKind::Cells => Vec3::new(96., 96., 255.),
+                OverlayKind::Regions => Vec3::new(255., 255., 0.),
+                OverlayKind::Interest => Vec3::new(0., 255., 255.),
+                OverlayKind::Paths => Vec3::new(255., 0., 255.),
+            };
+            for shape in shapes.iter() {
+                Self::draw_outline(renderer, camera, shape, color);
+            }
+        }
+    }
+
+    /// Draws the outline of an overlay shape as lines.
+    fn draw_outline(
+        renderer: &mut dyn Renderer,
+        camera: &Camera,
+        shape: &OverlayShape,
+        color: Vec3,
+    ) {
+        let point = |x: i32, y: i32| Vec2::new(x as f64, y as f64);
+        let points: Vec<Vec2> = match shape {
+            OverlayShape::Line([x1, y1, x2, y2]) => {
+                camera.draw_line(renderer, point(*x1, *y1), point(*x2, *y2), color);
+                return;
+            }
+            OverlayShape::Rect([x, y, w, h]) => vec![
+                point(*x, *y),
+                point(x + w, *y),
+                point(x + w, y + h),
+                point(*x, y + h),
+            ],
+            OverlayShape::Circle([x, y, radius]) => (0..Self::OVERLAY_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f64 / Self::OVERLAY_SEGMENTS as f64 * std::f64::consts::TAU;
+                    let radius = *radius as f64;
+                    Vec2::new(
+                        *x as f64 + angle.cos() * radius,
+                        *y as f64 + angle.sin() * radius,
+                    )
+                })
+                .collect(),
+            OverlayShape::Polygon(vertices) => {
+                vertices.iter().map(|[x, y]| point(*x, *y)).collect()
+            }
+        };
+
+        // Closes the outline by joining the last point back to the first.
+        for (i, from) in points.iter().enumerate() {
+            let to = points[(i + 1) % points.len()];
+            camera.draw_line(renderer, *from, to, color);
+        }
+    }
+
+    /// Draws the notification area in the top-left of the screen.
+    pub fn draw_toasts(&self, renderer: &mut dyn Renderer) {
+        for (i, toast) in self.toasts.iter().enumerate() {
+            let top_left = Vec2::new(10., 10. + i as f64 * Self::TOAST_SPACING);
+            renderer.draw_text(&toast.message, top_left, toast.color(), toast.alpha());
+        }
+    }
+
+    /// Draws a warning at the top of the screen while the connection is unhealthy.
+    pub fn draw_connection(&self, renderer: &mut dyn Renderer, state: ConnectionState) {
+        let text = self.locale.get(match state {
+            ConnectionState::Connected => return,
+            ConnectionState::Connecting => "connection.connecting",
+            ConnectionState::Lost => "connection.lost",
+            ConnectionState::Closed => "connection.closed",
+        });
+
+        let width = renderer.text_size(text).map_or(0., |size| size.x());
+        let top_left = Vec2::new((renderer.screen_size().x() - width) / 2., 10.);
+        renderer.draw_text(text, top_left, Vec3::new(255., 200., 0.), 255);
+    }
+
+    /// Draws the scoreboard in the middle of the screen.
+    pub fn draw_leaderboard(&self, renderer: &mut dyn Renderer) {
+        let rows = self.leaderboard.len() as f64 + 1.;
+        let size = Vec2::new(Self::SCOREBOARD_WIDTH, rows * Self::TOAST_SPACING + 20.);
+        let screen = renderer.screen_size();
+        let top_left = Vec2::new((screen.x() - size.x()) / 2., (screen.y() - size.y()) / 2.);
+        renderer.draw_rect(top_left, size, Vec3::new(32., 32., 32.));
+
+        let header = self.locale.get("leaderboard.header").to_string();
+        let lines = self.leaderboard.iter().enumerate().map(|(i, entry)| {
+            format!(
+                "{}. {} / {} / {} / {:.0} / {}m",
+                i + 1,
+                entry.name,
+                entry.stats.kills,
+                entry.stats.deaths,
+                entry.stats.distance,
+                (entry.stats.playtime / 60.) as u64
+            )
+        });
+
+        for (i, line) in std::iter::once(header).chain(lines).enumerate() {
+            let position = Vec2::new(
+                top_left.x() + 10.,
+                top_left.y() + 10. + i as f64 * Self::TOAST_SPACING,
+            );
+            renderer.draw_text(&line, position, Vec3::new(255., 255., 255.), 255);
+        }
+    }
+
+    /// Draws the letters in the mailbox in the middle of the screen.
+    pub fn draw_mailbox(&self, renderer: &mut dyn Renderer) {
+        let rows = self.mailbox.len().max(1) as f64 + 1.;
+        let size = Vec2::new(Self::SCOREBOARD_WIDTH, rows * Self::TOAST_SPACING + 20.);
+        let screen = renderer.screen_size();
+        let top_left = Vec2::new((screen.x() - size.x()) / 2., (screen.y() - size.y()) / 2.);
+        renderer.draw_rect(top_left, size, Vec3::new(32., 32., 32.));
+
+        let header = self
+            .locale
+            .text(&Text::new("mailbox.header").with("unread", self.unread_mail));
+        let lines = self.mailbox.iter().map(|letter| {
+            let marker = if letter.read { " " } else { "*" };
+            format!("{} {}: {}", marker, letter.sender, letter.message)
+        });
+        let empty = self
+            .mailbox
+            .is_empty()
+            .then(|| self.locale.get("mailbox.empty").to_string());
+
+        for (i, line) in std::iter::once(header)
+            .chain(lines)
+            .chain(empty)
+            .enumerate()
+        {
+            let position = Vec2::new(
+                top_left.x() + 10.,
+                top_left.y() + 10. + i as f64 * Self::TOAST_SPACING,
+            );
+            renderer.draw_text(&line, position, Vec3::new(255., 255., 255.), 255);
+        }
+    }
+
+    /// Draws the conversation with an NPC along the bottom of the screen.
+    pub fn draw_dialogue(&self, renderer: &mut dyn Renderer) {
+        let dialogue = match &self.dialogue {
+            Some(dialogue) => dialogue,
+            None => return,
+        };
+
+        let mut lines = vec![format!("{}:", self.locale.text(&dialogue.speaker))];
+        lines.extend(Self::wrap(
+            renderer,
+            &dialogue.text,
+            Self::SCOREBOARD_WIDTH - 20.,
+        ));
+        if !dialogue.is_last() {
+            let more = Text::new("dialogue.more")
+                .with("page", dialogue.page + 1)
+                .with("pages", dialogue.pages);
+            lines.push(self.locale.text(&more));
+        } else if dialogue.choices.is_empty() {
+            lines.push(self.locale.get("dialogue.goodbye").to_string());
+        } else {
+            lines.extend(
+                dialogue
+                    .choices
+                    .iter()
+                    .enumerate()
+                    .map(|(i, choice)| format!("[{}] {}", i + 1, choice)),
+            );
+        }
+
+        let screen = renderer.screen_size();
+        let size = Vec2::new(
+            Self::SCOREBOARD_WIDTH,
+            lines.len() as f64 * Self::TOAST_SPACING + 20.,
+        );
+        let top_left = Vec2::new(
+            (screen.x() - size.x()) / 2.,
+            screen.y() - size.y() - Self::DIALOGUE_OFFSET,
+        );
+        renderer.draw_rect(top_left, size, Vec3::new(32., 32., 32.));
+
+        for (i, line) in lines.iter().enumerate() {
+            let position = Vec2::new(
+                top_left.x() + 10.,
+                top_left.y() + 10. + i as f64 * Self::TOAST_SPACING,
+            );
+            renderer.draw_text(line, position, Vec3::new(255., 255., 255.), 255);
+        }
+    }
+
+    /// Breaks text into lines that fit within a width, between words.
+    fn wrap(renderer: &dyn Renderer, text: &str, width: f64) -> Vec<String> {
+        let mut lines = vec![];
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+
+            let fits = renderer
+                .text_size(&candidate)
+                .map_or(candidate.len() <= Self::WRAP_LENGTH, |size| {
+                    size.x() <= width
+                });
+            if fits || line.is_empty() {
+                line = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            }
+        }
+
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Draws the state of the match and game mode in the top-right of the screen.
+    pub fn draw_hud(&self, renderer: &mut dyn Renderer) {
+        let mut lines = vec![];
+        if let Some((payload, received)) = &self.matches {
+            let phase = self.locale.get(match payload.phase {
+                MatchPhase::Lobby => "hud.lobby",
+                MatchPhase::Countdown => "hud.countdown",
+                MatchPhase::Active => "hud.active",
+                MatchPhase::Ended => "hud.ended",
+            });
+            let text = if payload.phase == MatchPhase::Lobby {
+                phase.to_string()
+            } else {
+                let remaining = payload
+                    .remaining
+                    .saturating_sub(received.elapsed().as_secs());
+                format!("{} {}:{:02}", phase, remaining / 60, remaining % 60)
+            };
+            lines.push((text, [255, 255, 255]));
+        }
+
+        if let Some(HudPayload::Ctf { scores, flags }) = &self.hud {
+            for (team, score) in scores {
+                let status = flags.iter().find(|flag| flag.team == *team).map_or(
+                    "hud.flag_missing",
+                    |flag| match flag.status {
+                        FlagStatus::Home => "hud.flag_home",
+                        FlagStatus::Carried => "hud.flag_taken",
+                        FlagStatus::Dropped => "hud.flag_dropped",
+                    },
+                );
+                let text = Text::new("hud.flag")
+                    .with("team", team.name())
+                    .with("score", score)
+                    .with("status", self.locale.get(status));
+                let text = self.locale.text(&text);
+                lines.push((text, team.color()));
+            }
+        }
+
+        if self.unread_mail > 0 {
+            let text = Text::new("hud.unread_mail").with("unread", self.unread_mail);
+            let text = self.locale.text(&text);
+            lines.push((text, [255, 200, 0]));
+        }
+
+        let right = renderer.screen_size().x() - 10.;
+        for (i, (text, [r, g, b])) in lines.into_iter().enumerate() {
+            let width = renderer.text_size(&text).map_or(0., |size| size.x());
+            let top_left = Vec2::new(right - width, 10. + i as f64 * Self::TOAST_SPACING);
+            let color = Vec3::new(r as f64, g as f64, b as f64);
+            renderer.draw_text(&text, top_left, color, 255);
+        }
+    }
+}
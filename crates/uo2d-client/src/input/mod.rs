@@ -0,0 +1,696 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use uo2d_proto::components::Vec2;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod sdl;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use sdl::events;
+#[cfg(target_arch = "wasm32")]
+pub use web::WebInput;
+
+/// Keys the client responds to, numbered by their USB HID usage as SDL scancodes are.
+/// Recordings store the numbers, whichever library the keys were read with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    A = 4,
+    B = 5,
+    C = 6,
+    D = 7,
+    E = 8,
+    F = 9,
+    G = 10,
+    I = 12,
+    M = 16,
+    Q = 20,
+    R = 21,
+    S = 22,
+    T = 23,
+    W = 26,
+    X = 27,
+    Y = 28,
+    Z = 29,
+    Num1 = 30,
+    Num2 = 31,
+    Num3 = 32,
+    Num4 = 33,
+    Num5 = 34,
+    Num6 = 35,
+    Num7 = 36,
+    Num8 = 37,
+    Num9 = 38,
+    Return = 40,
+    Escape = 41,
+    Backspace = 42,
+    Tab = 43,
+    /// The tilde key, left of 1.
+    Grave = 53,
+    F1 = 58,
+    F2 = 59,
+    F3 = 60,
+    F4 = 61,
+    F5 = 62,
+    F6 = 63,
+    F7 = 64,
+    F8 = 65,
+    F9 = 66,
+    LCtrl = 224,
+    LShift = 225,
+    RCtrl = 228,
+    RShift = 229,
+}
+
+impl Key {
+    const ALL: [Key; 44] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::I,
+        Key::M,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+        Key::Return,
+        Key::Escape,
+        Key::Backspace,
+        Key::Tab,
+        Key::Grave,
+        Key::F1,
+        Key::F2,
+        Key::F3,
+        Key::F4,
+        Key::F5,
+        Key::F6,
+        Key::F7,
+        Key::F8,
+        Key::F9,
+        Key::LCtrl,
+        Key::LShift,
+        Key::RCtrl,
+        Key::RShift,
+    ];
+
+    /// USB HID usage of the key.
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Key with the USB HID usage, if the client responds to it.
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.code() == code)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+}
+
+/// Input from the keyboard and mouse, read from whichever library the client runs with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// Keys the client does not respond to are still pressed, such as while typing.
+    KeyDown {
+        key: Option<Key>,
+        repeat: bool,
+    },
+    KeyUp {
+        key: Option<Key>,
+    },
+    /// Text typed, in the layout of the keyboard.
+    Text(String),
+    MouseDown {
+        button: MouseButton,
+        position: Vec2,
+    },
+    MouseUp {
+        button: MouseButton,
+        position: Vec2,
+    },
+    MouseMotion(Vec2),
+    /// The window was asked to close, browsers close the page instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    Quit,
+}
+
+#[derive(Default)]
+pub struct MouseState {
+    pub position: Vec2,
+    pub last_target: Option<Vec2>,
+    left_clicked: bool,
+    right_clicked: bool,
+    /// Left button went down or up during the tick, however long it was held.
+    left_pressed: bool,
+    left_released: bool,
+    tick_delay: u32,
+    left_held_ticks: u32,
+    right_held_ticks: u32,
+}
+
+impl MouseState {
+    fn reset(&mut self) {
+        self.left_clicked = false;
+        self.right_clicked = false;
+        self.left_pressed = false;
+        self.left_released = false;
+    }
+
+    pub fn set_delay(&mut self, delay_ticks: u32) {
+        self.tick_delay = delay_ticks;
+    }
+
+    pub fn clicked(&self) -> bool {
+        self.left_clicked || self.right_clicked
+    }
+
+    pub fn left_clicked(&self) -> bool {
+        self.left_clicked && self.left_held_ticks <= self.tick_delay
+    }
+
+    pub fn right_clicked(&self) -> bool {
+        self.right_clicked && self.right_held_ticks <= self.tick_delay
+    }
+
+    /// Checks if the left button went down this tick, such as to start dragging.
+    pub fn left_pressed(&self) -> bool {
+        self.left_pressed
+    }
+
+    /// Checks if the left button was let go this tick, such as to drop what was dragged.
+    pub fn left_released(&self) -> bool {
+        self.left_released
+    }
+
+    /// Checks if the mouse pointer is within an area of the screen.
+    pub fn is_over(&self, top_left: Vec2, size: Vec2) -> bool {
+        within(self.position, top_left, size)
+    }
+
+    pub fn held(&self) -> bool {
+        self.left_held() || self.right_held()
+    }
+
+    pub fn left_held(&self) -> bool {
+        self.left_held_ticks > self.tick_delay
+    }
+
+    pub fn right_held(&self) -> bool {
+        self.right_held_ticks > self.tick_delay
+    }
+
+    pub fn update(&mut self, event: &InputEvent) {
+        match event {
+            InputEvent::MouseDown { button, .. } => match button {
+                MouseButton::Left => {
+                    if self.left_held_ticks == 0 {
+                        self.left_held_ticks = 1;
+                        self.left_pressed = true;
+                    }
+                }
+                MouseButton::Right => {
+                    if self.right_held_ticks == 0 {
+                        self.right_held_ticks = 1;
+                    }
+                }
+            },
+            InputEvent::MouseUp { button, .. } => match button {
+                MouseButton::Left => {
+                    self.left_clicked =
+                        self.left_held_ticks > 0 && self.left_held_ticks <= self.tick_delay;
+                    self.left_released = self.left_held_ticks > 0;
+                    self.left_held_ticks = 0;
+                }
+                MouseButton::Right => {
+                    self.right_clicked =
+                        self.right_held_ticks > 0 && self.right_held_ticks <= self.tick_delay;
+                    self.right_held_ticks = 0;
+                }
+            },
+            InputEvent::MouseMotion(position) => {
+                self.position = *position;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn post_update(&mut self) {
+        // Increment ticks for held buttons
+        if self.left_held_ticks > 0 {
+            self.left_held_ticks += 1;
+        }
+
+        if self.right_held_ticks > 0 {
+            self.right_held_ticks += 1;
+        }
+
+        // Handle target update logic
+        if self.clicked() || self.held() {
+            self.last_target = Some(self.position);
+        }
+
+        // Reset click states at the end of the update cycle
+        // self.reset();
+    }
+}
+
+/// Checks if a point on the screen lies within an area of it.
+pub fn within(point: Vec2, top_left: Vec2, size: Vec2) -> bool {
+    point.x() >= top_left.x()
+        && point.x() <= top_left.x() + size.x()
+        && point.y() >= top_left.y()
+        && point.y() <= top_left.y() + size.y()
+}
+
+#[derive(Default)]
+pub struct KeyboardState {
+    movement_pressed: bool,
+    pub w_pressed: bool,
+    pub a_pressed: bool,
+    pub s_pressed: bool,
+    pub d_pressed: bool,
+    pub esc_pressed: bool,
+    pub shift_pressed: bool,
+    pub ctrl_pressed: bool,
+    pub tab_pressed: bool,
+    just_pressed: HashSet<Key>,
+}
+
+impl KeyboardState {
+    fn reset(&mut self) {
+        self.just_pressed.clear();
+        self.movement_pressed = false;
+        self.w_pressed = false;
+        self.a_pressed = false;
+        self.s_pressed = false;
+        self.d_pressed = false;
+        self.esc_pressed = false;
+        self.shift_pressed = false;
+        self.ctrl_pressed = false;
+        self.tab_pressed = false;
+    }
+
+    pub fn movement_pressed(&self) -> bool {
+        self.w_pressed || self.a_pressed || self.s_pressed || self.d_pressed
+    }
+
+    /// Checks if a key was pressed down this tick, ignoring held repeats.
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.just_pressed.contains(&key)
+    }
+
+    /// Tracks keys that have been pressed down this tick.
+    pub fn event(&mut self, event: &InputEvent) {
+        if let InputEvent::KeyDown {
+            key: Some(key),
+            repeat: false,
+        } = event
+        {
+            self.just_pressed.insert(*key);
+            if *key == Key::Escape {
+                self.esc_pressed = true;
+            }
+        }
+    }
+
+    /// Tracks the keys being held down.
+    pub fn update(&mut self, held: &HashSet<Key>) {
+        if held.contains(&Key::LShift) || held.contains(&Key::RShift) {
+            self.shift_pressed = true;
+        }
+        if held.contains(&Key::LCtrl) || held.contains(&Key::RCtrl) {
+            self.ctrl_pressed = true;
+        }
+        if held.contains(&Key::Tab) {
+            self.tab_pressed = true;
+        }
+        if held.contains(&Key::W) {
+            self.w_pressed = true;
+        }
+        if held.contains(&Key::A) {
+            self.a_pressed = true;
+        }
+        if held.contains(&Key::S) {
+            self.s_pressed = true;
+        }
+        if held.contains(&Key::D) {
+            self.d_pressed = true;
+        }
+    }
+}
+
+/// Line of text being typed, opened with a key and sent with Enter.
+pub struct TextInput {
+    /// Key that opens the line, also closing it unless it is Enter.
+    opener: Key,
+    text: Option<String>,
+    submitted: Option<String>,
+    /// Set while the text typed by the opening key has yet to arrive, it is not part of the line.
+    opening: bool,
+}
+
+impl TextInput {
+    /// Longest message that can be typed, in characters.
+    const MAX_LENGTH: usize = 200;
+
+    pub fn new(opener: Key) -> Self {
+        Self {
+            opener,
+            text: None,
+            submitted: None,
+            opening: false,
+        }
+    }
+
+    /// Text currently being typed, if the chat is open.
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Checks if the line is open and capturing the keyboard.
+    pub fn is_open(&self) -> bool {
+        self.text.is_some()
+    }
+
+    /// Takes the message sent this tick, if there was one.
+    pub fn take(&mut self) -> Option<String> {
+        self.submitted.take()
+    }
+
+    /// Updates the text being typed, returns true if the event was consumed by the line.
+    pub fn event(&mut self, event: &InputEvent) -> bool {
+        let text = match self.text.as_mut() {
+            Some(text) => text,
+            None => {
+                if let InputEvent::KeyDown {
+                    key: Some(key),
+                    repeat: false,
+                } = event
+                {
+                    if *key == self.opener {
+                        self.text = Some(String::new());
+                        self.opening = true;
+                        return true;
+                    }
+                }
+                return false;
+            }
+        };
+
+        match event {
+            InputEvent::Text(_) if self.opening => self.opening = false,
+            InputEvent::Text(typed) => {
+                let room = Self::MAX_LENGTH.saturating_sub(text.chars().count());
+                text.extend(typed.chars().take(room));
+            }
+            InputEvent::KeyDown {
+                key: Some(key),
+                repeat: false,
+            } if *key == self.opener && *key != Key::Return => self.text = None,
+            InputEvent::KeyDown {
+                key: Some(Key::Return),
+                repeat: false,
+            } => {
+                let message = self.text.take().unwrap_or_default();
+                if !message.trim().is_empty() {
+                    self.submitted = Some(message.trim().to_string());
+                }
+            }
+            InputEvent::KeyDown {
+                key: Some(Key::Escape),
+                ..
+            } => self.text = None,
+            InputEvent::KeyDown {
+                key: Some(Key::Backspace),
+                ..
+            } => {
+                text.pop();
+            }
+            InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. } => (),
+            _ => return false,
+        }
+        if matches!(event, InputEvent::KeyDown { .. }) {
+            self.opening = false;
+        }
+        true
+    }
+}
+
+pub struct Input {
+    pub mouse: MouseState,
+    pub keyboard: KeyboardState,
+    /// Line of chat, opened with Enter.
+    pub chat: TextInput,
+    /// Developer console, opened with the tilde key.
+    pub console: TextInput,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self {
+            mouse: MouseState::default(),
+            keyboard: KeyboardState::default(),
+            chat: TextInput::new(Key::Return),
+            console: TextInput::new(Key::Grave),
+        }
+    }
+}
+
+/// Source of the players input, allowing the client to run without a window.
+pub trait InputSource {
+    /// Updates the input for the current tick.
+    fn poll(&mut self, input: &mut Input);
+}
+
+/// Input that never has anything pressed, used when running without a window.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct HeadlessInput;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl InputSource for HeadlessInput {
+    fn poll(&mut self, input: &mut Input) {
+        input.reset();
+        input.mouse.post_update();
+    }
+}
+
+/// Wanders around by holding a random direction for a while, used to load test servers.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct BotInput {
+    seed: u64,
+    ticks: u32,
+    direction: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BotInput {
+    /// Client ticks a direction is held for.
+    const HOLD: u32 = 30;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed: seed | 1,
+            ticks: 0,
+            direction: 0,
+        }
+    }
+
+    /// Next pseudo-random number, xorshift is plenty for picking directions.
+    fn next(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl InputSource for BotInput {
+    fn poll(&mut self, input: &mut Input) {
+        input.reset();
+        input.mouse.post_update();
+
+        if self.ticks == 0 {
+            self.direction = self.next() % 5;
+            self.ticks = Self::HOLD;
+        }
+        self.ticks -= 1;
+
+        // The last direction stands still.
+        match self.direction {
+            0 => input.keyboard.w_pressed = true,
+            1 => input.keyboard.a_pressed = true,
+            2 => input.keyboard.s_pressed = true,
+            3 => input.keyboard.d_pressed = true,
+            _ => (),
+        }
+    }
+}
+
+impl Input {
+    /// Checks if the chat or console is capturing the keyboard.
+    pub fn is_typing(&self) -> bool {
+        self.chat.is_open() || self.console.is_open()
+    }
+
+    fn reset(&mut self) {
+        self.mouse.reset();
+        self.keyboard.reset();
+    }
+
+    /// Updates the input with the keys held down and the events since the last tick.
+    pub fn update(&mut self, held: &HashSet<Key>, events: &[InputEvent]) {
+        self.reset();
+
+        let typing = self.is_typing();
+        self.keyboard.update(held);
+        for event in events {
+            self.mouse.update(event);
+            // While chatting the tilde is part of the message instead of opening the console.
+            let consumed = if self.chat.is_open() {
+                self.chat.event(event)
+            } else {
+                self.console.event(event) || self.chat.event(event)
+            };
+            if !consumed {
+                self.keyboard.event(event);
+            }
+        }
+
+        // Keys pressed while typing are part of the message, not actions.
+        if typing || self.is_typing() {
+            self.keyboard.reset();
+        }
+        self.mouse.post_update();
+    }
+}
+
+/// Line of text as it was during a tick.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct TextFrame {
+    text: Option<String>,
+    submitted: Option<String>,
+}
+
+impl TextFrame {
+    fn capture(line: &TextInput) -> Self {
+        Self {
+            text: line.text.clone(),
+            submitted: line.submitted.clone(),
+        }
+    }
+
+    fn apply(&self, line: &mut TextInput) {
+        line.text = self.text.clone();
+        line.submitted = self.submitted.clone();
+        line.opening = false;
+    }
+}
+
+/// Input as it was once a tick was polled, recorded to play the session back.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputFrame {
+    mouse: Vec2,
+    last_target: Option<Vec2>,
+    left_clicked: bool,
+    right_clicked: bool,
+    left_pressed: bool,
+    left_released: bool,
+    tick_delay: u32,
+    left_held_ticks: u32,
+    right_held_ticks: u32,
+    w: bool,
+    a: bool,
+    s: bool,
+    d: bool,
+    esc: bool,
+    shift: bool,
+    ctrl: bool,
+    tab: bool,
+    /// Keys pressed down during the tick, by their USB HID usage.
+    just_pressed: Vec<i32>,
+    chat: TextFrame,
+    console: TextFrame,
+}
+
+impl InputFrame {
+    pub fn capture(input: &Input) -> Self {
+        let (mouse, keyboard) = (&input.mouse, &input.keyboard);
+        Self {
+            mouse: mouse.position,
+            last_target: mouse.last_target,
+            left_clicked: mouse.left_clicked,
+            right_clicked: mouse.right_clicked,
+            left_pressed: mouse.left_pressed,
+            left_released: mouse.left_released,
+            tick_delay: mouse.tick_delay,
+            left_held_ticks: mouse.left_held_ticks,
+            right_held_ticks: mouse.right_held_ticks,
+            w: keyboard.w_pressed,
+            a: keyboard.a_pressed,
+            s: keyboard.s_pressed,
+            d: keyboard.d_pressed,
+            esc: keyboard.esc_pressed,
+            shift: keyboard.shift_pressed,
+            ctrl: keyboard.ctrl_pressed,
+            tab: keyboard.tab_pressed,
+            just_pressed: keyboard.just_pressed.iter().map(|key| key.code()).collect(),
+            chat: TextFrame::capture(&input.chat),
+            console: TextFrame::capture(&input.console),
+        }
+    }
+
+    /// Replaces the input with the one recorded.
+    pub fn apply(&self, input: &mut Input) {
+        let mouse = &mut input.mouse;
+        mouse.position = self.mouse;
+        mouse.last_target = self.last_target;
+        mouse.left_clicked = self.left_clicked;
+        mouse.right_clicked = self.right_clicked;
+        mouse.left_pressed = self.left_pressed;
+        mouse.left_released = self.left_released;
+        mouse.tick_delay = self.tick_delay;
+        mouse.left_held_ticks = self.left_held_ticks;
+        mouse.right_held_ticks = self.right_held_ticks;
+
+        let keyboard = &mut input.keyboard;
+        keyboard.w_pressed = self.w;
+        keyboard.a_pressed = self.a;
+        keyboard.s_pressed = self.s;
+        keyboard.d_pressed = self.d;
+        keyboard.esc_pressed = self.esc;
+        keyboard.shift_pressed = self.shift;
+        keyboard.ctrl_pressed = self.ctrl;
+        keyboard.tab_pressed = self.tab;
+        keyboard.just_pressed = self
+            .just_pressed
+            .iter()
+            .filter_map(|code| Key::from_code(*code))
+            .collect();
+
+        self.chat.apply(&mut input.chat);
+        self.console.apply(&mut input.console);
+    }
+}
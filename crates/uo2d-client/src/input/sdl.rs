@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+use sdl2::mouse::MouseButton as SdlButton;
+use sdl2::EventPump;
+use uo2d_proto::components::Vec2;
+
+use super::{Input, InputEvent, InputSource, Key, MouseButton};
+
+impl InputSource for EventPump {
+    fn poll(&mut self, input: &mut Input) {
+        let (held, events) = events(self);
+        input.update(&held, &events);
+    }
+}
+
+/// Keys held down and the events waiting in the window.
+pub fn events(pump: &mut EventPump) -> (HashSet<Key>, Vec<InputEvent>) {
+    let held = pump
+        .keyboard_state()
+        .pressed_scancodes()
+        .filter_map(key)
+        .collect();
+    let events = pump
+        .poll_iter()
+        .filter_map(|event| convert(&event))
+        .collect();
+    (held, events)
+}
+
+fn key(scancode: Scancode) -> Option<Key> {
+    Key::from_code(scancode as i32)
+}
+
+fn button(button: SdlButton) -> Option<MouseButton> {
+    match button {
+        SdlButton::Left => Some(MouseButton::Left),
+        SdlButton::Right => Some(MouseButton::Right),
+        _ => None,
+    }
+}
+
+fn convert(event: &Event) -> Option<InputEvent> {
+    Some(match event {
+        Event::Quit { .. } => InputEvent::Quit,
+        Event::KeyDown {
+            scancode, repeat, ..
+        } => InputEvent::KeyDown {
+            key: scancode.and_then(key),
+            repeat: *repeat,
+        },
+        Event::KeyUp { scancode, .. } => InputEvent::KeyUp {
+            key: scancode.and_then(key),
+        },
+        Event::TextInput { text, .. } => InputEvent::Text(text.clone()),
+        Event::MouseButtonDown {
+            mouse_btn, x, y, ..
+        } => InputEvent::MouseDown {
+            button: button(*mouse_btn)?,
+            position: Vec2::new(*x as f64, *y as f64),
+        },
+        Event::MouseButtonUp {
+            mouse_btn, x, y, ..
+        } => InputEvent::MouseUp {
+            button: button(*mouse_btn)?,
+            position: Vec2::new(*x as f64, *y as f64),
+        },
+        Event::MouseMotion { x, y, .. } => InputEvent::MouseMotion(Vec2::new(*x as f64, *y as f64)),
+        _ => return None,
+    })
+}
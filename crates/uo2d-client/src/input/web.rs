@@ -0,0 +1,202 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use uo2d_proto::components::Vec2;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Event, HtmlCanvasElement, KeyboardEvent, MouseEvent};
+
+use super::{Input, InputEvent, InputSource, Key, MouseButton};
+
+type Listener = Closure<dyn FnMut(Event)>;
+
+/// Keys held down and the events since they were last taken.
+#[derive(Default)]
+struct Pending {
+    held: HashSet<Key>,
+    events: Vec<InputEvent>,
+}
+
+/// Reads the keyboard and mouse from the events of a canvas on the page.
+pub struct WebInput {
+    canvas: HtmlCanvasElement,
+    pending: Rc<RefCell<Pending>>,
+    /// Removed from the canvas once the input is dropped.
+    listeners: Vec<(&'static str, Listener)>,
+}
+
+impl WebInput {
+    /// Starts listening to the canvas, focusing it to receive the keyboard.
+    pub fn new(canvas: &HtmlCanvasElement) -> Result<Self, JsValue> {
+        let mut input = Self {
+            canvas: canvas.clone(),
+            pending: Rc::new(RefCell::new(Pending::default())),
+            listeners: Vec::new(),
+        };
+
+        input.listen("keydown", |pending, event| {
+            let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                return;
+            };
+            // The browser would otherwise act on keys such as tab and backspace.
+            event.prevent_default();
+
+            let key = key(&event.code());
+            pending.held.extend(key);
+            pending.events.push(InputEvent::KeyDown {
+                key,
+                repeat: event.repeat(),
+            });
+
+            // Printable keys carry the character typed, named keys such as "Enter" do not.
+            let typed = event.key();
+            if typed.chars().count() == 1 && !event.ctrl_key() && !event.meta_key() {
+                pending.events.push(InputEvent::Text(typed));
+            }
+        })?;
+        input.listen("keyup", |pending, event| {
+            let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                return;
+            };
+            let key = key(&event.code());
+            if let Some(key) = key {
+                pending.held.remove(&key);
+            }
+            pending.events.push(InputEvent::KeyUp { key });
+        })?;
+        // Keys released elsewhere are never heard of.
+        input.listen("blur", |pending, _| pending.held.clear())?;
+        input.listen("mousedown", |pending, event| {
+            if let Some((button, position)) = click(event) {
+                pending
+                    .events
+                    .push(InputEvent::MouseDown { button, position });
+            }
+        })?;
+        input.listen("mouseup", |pending, event| {
+            if let Some((button, position)) = click(event) {
+                pending
+                    .events
+                    .push(InputEvent::MouseUp { button, position });
+            }
+        })?;
+        input.listen("mousemove", |pending, event| {
+            if let Some(event) = event.dyn_ref::<MouseEvent>() {
+                pending
+                    .events
+                    .push(InputEvent::MouseMotion(position(event)));
+            }
+        })?;
+        // Right clicks fire projectiles instead of opening the menu.
+        input.listen("contextmenu", |_, event| event.prevent_default())?;
+
+        canvas.set_tab_index(0);
+        canvas.focus()?;
+        Ok(input)
+    }
+
+    fn listen(
+        &mut self,
+        name: &'static str,
+        mut handler: impl FnMut(&mut Pending, &Event) + 'static,
+    ) -> Result<(), JsValue> {
+        let pending = Rc::clone(&self.pending);
+        let listener = Listener::new(move |event: Event| {
+            handler(&mut pending.borrow_mut(), &event);
+        });
+        self.canvas
+            .add_event_listener_with_callback(name, listener.as_ref().unchecked_ref())?;
+        self.listeners.push((name, listener));
+        Ok(())
+    }
+
+    /// Keys held down and the events since the last call.
+    pub fn take(&self) -> (HashSet<Key>, Vec<InputEvent>) {
+        let mut pending = self.pending.borrow_mut();
+        (pending.held.clone(), std::mem::take(&mut pending.events))
+    }
+}
+
+impl Drop for WebInput {
+    fn drop(&mut self) {
+        for (name, listener) in &self.listeners {
+            let _ = self
+                .canvas
+                .remove_event_listener_with_callback(name, listener.as_ref().unchecked_ref());
+        }
+    }
+}
+
+impl InputSource for WebInput {
+    fn poll(&mut self, input: &mut Input) {
+        let (held, events) = self.take();
+        input.update(&held, &events);
+    }
+}
+
+/// Position of the mouse within the canvas.
+fn position(event: &MouseEvent) -> Vec2 {
+    Vec2::new(event.offset_x() as f64, event.offset_y() as f64)
+}
+
+fn click(event: &Event) -> Option<(MouseButton, Vec2)> {
+    let event = event.dyn_ref::<MouseEvent>()?;
+    let button = match event.button() {
+        0 => MouseButton::Left,
+        2 => MouseButton::Right,
+        _ => return None,
+    };
+    Some((button, position(event)))
+}
+
+/// Key by its physical position, as named by the browser.
+fn key(code: &str) -> Option<Key> {
+    Some(match code {
+        "KeyA" => Key::A,
+        "KeyB" => Key::B,
+        "KeyC" => Key::C,
+        "KeyD" => Key::D,
+        "KeyE" => Key::E,
+        "KeyF" => Key::F,
+        "KeyG" => Key::G,
+        "KeyI" => Key::I,
+        "KeyM" => Key::M,
+        "KeyQ" => Key::Q,
+        "KeyR" => Key::R,
+        "KeyS" => Key::S,
+        "KeyT" => Key::T,
+        "KeyW" => Key::W,
+        "KeyX" => Key::X,
+        "KeyY" => Key::Y,
+        "KeyZ" => Key::Z,
+        "Digit1" => Key::Num1,
+        "Digit2" => Key::Num2,
+        "Digit3" => Key::Num3,
+        "Digit4" => Key::Num4,
+        "Digit5" => Key::Num5,
+        "Digit6" => Key::Num6,
+        "Digit7" => Key::Num7,
+        "Digit8" => Key::Num8,
+        "Digit9" => Key::Num9,
+        "Enter" | "NumpadEnter" => Key::Return,
+        "Escape" => Key::Escape,
+        "Backspace" => Key::Backspace,
+        "Tab" => Key::Tab,
+        "Backquote" => Key::Grave,
+        "F1" => Key::F1,
+        "F2" => Key::F2,
+        "F3" => Key::F3,
+        "F4" => Key::F4,
+        "F5" => Key::F5,
+        "F6" => Key::F6,
+        "F7" => Key::F7,
+        "F8" => Key::F8,
+        "F9" => Key::F9,
+        "ControlLeft" => Key::LCtrl,
+        "ShiftLeft" => Key::LShift,
+        "ControlRight" => Key::RCtrl,
+        "ShiftRight" => Key::RShift,
+        _ => return None,
+    })
+}
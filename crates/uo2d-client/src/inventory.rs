@@ -0,0 +1,246 @@
+use uo2d_proto::components::{ItemStack, Vec2, Vec3};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::{Locale, Text};
+
+use super::input::{within, MouseState};
+use super::renderer::Renderer;
+
+/// Something done to a stack from the inventory window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackAction {
+    /// Moves a stack to another slot, dragged there with the mouse.
+    Move {
+        from: usize,
+        to: usize,
+    },
+    /// Equips or uses the item in the slot.
+    Use(usize),
+    Split(usize),
+    Drop(usize),
+    /// Moves the whole stack into whatever is open, such as a trade.
+    QuickMove(usize),
+}
+
+/// Actions listed when right-clicking a stack.
+#[derive(Debug, Clone, Copy)]
+struct ContextMenu {
+    slot: usize,
+    top_left: Vec2,
+}
+
+impl ContextMenu {
+    const ENTRIES: [&'static str; 3] = ["inventory.use", "inventory.split", "inventory.drop"];
+    const WIDTH: f64 = 100.;
+
+    fn size() -> Vec2 {
+        Vec2::new(
+            Self::WIDTH,
+            Self::ENTRIES.len() as f64 * InventoryWindow::ROW_HEIGHT,
+        )
+    }
+
+    /// Action of the entry beneath a point on the screen.
+    fn action_at(&self, point: Vec2) -> Option<StackAction> {
+        let size = Vec2::new(Self::WIDTH, InventoryWindow::ROW_HEIGHT);
+        let index = (0..Self::ENTRIES.len()).find(|i| within(point, self.entry(*i), size))?;
+        Some(match index {
+            0 => StackAction::Use(self.slot),
+            1 => StackAction::Split(self.slot),
+            _ => StackAction::Drop(self.slot),
+        })
+    }
+
+    /// Top-left corner of an entry.
+    fn entry(&self, index: usize) -> Vec2 {
+        Vec2::new(
+            self.top_left.x(),
+            self.top_left.y() + index as f64 * InventoryWindow::ROW_HEIGHT,
+        )
+    }
+}
+
+/// Stacks being carried, listed in the bottom-right of the screen.
+/// Stacks are dragged between slots, right-clicked for more actions, or shift-clicked to move them.
+#[derive(Default)]
+pub struct InventoryWindow {
+    pub open: bool,
+    /// Slot of the stack being dragged.
+    dragging: Option<usize>,
+    menu: Option<ContextMenu>,
+}
+
+impl InventoryWindow {
+    const WIDTH: f64 = 220.;
+    const ROW_HEIGHT: f64 = 20.;
+    const PADDING: f64 = 10.;
+    /// Distance kept from the edges of the screen.
+    const MARGIN: f64 = 10.;
+
+    /// Shows or hides the window, letting go of anything being dragged.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.dragging = None;
+        self.menu = None;
+    }
+
+    /// Top-left corner and size of the window, sized to the stacks carried.
+    fn frame(screen: Vec2, stacks: usize) -> (Vec2, Vec2) {
+        let rows = stacks.max(1) as f64 + 1.;
+        let size = Vec2::new(Self::WIDTH, rows * Self::ROW_HEIGHT + Self::PADDING * 2.);
+        let top_left = Vec2::new(
+            screen.x() - size.x() - Self::MARGIN,
+            screen.y() - size.y() - Self::MARGIN,
+        );
+        (top_left, size)
+    }
+
+    /// Top-left corner of the row a slot is drawn on, beneath the header.
+    fn row(top_left: Vec2, slot: usize) -> Vec2 {
+        Vec2::new(
+            top_left.x(),
+            top_left.y() + Self::PADDING + (slot + 1) as f64 * Self::ROW_HEIGHT,
+        )
+    }
+
+    /// Slot beneath a point on the screen.
+    fn slot_at(screen: Vec2, point: Vec2, stacks: usize) -> Option<usize> {
+        let (top_left, _) = Self::frame(screen, stacks);
+        let size = Vec2::new(Self::WIDTH, Self::ROW_HEIGHT);
+        (0..stacks).find(|slot| within(point, Self::row(top_left, *slot), size))
+    }
+
+    /// Checks if the mouse is being used by the window, rather than to move or fire.
+    pub fn captures(&self, screen: Vec2, mouse: &MouseState, stacks: usize) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let (top_left, size) = Self::frame(screen, stacks);
+        self.dragging.is_some()
+            || mouse.is_over(top_left, size)
+            || self
+                .menu
+                .is_some_and(|menu| mouse.is_over(menu.top_left, ContextMenu::size()))
+    }
+
+    /// Follows the mouse, returning what was done once a stack is dropped, chosen, or clicked.
+    pub fn update(
+        &mut self,
+        mouse: &MouseState,
+        shift: bool,
+        screen: Vec2,
+        stacks: usize,
+    ) -> Option<StackAction> {
+        if !self.open {
+            return None;
+        }
+
+        // Any click closes the menu, choosing the entry beneath it if there is one.
+        let point = mouse.position;
+        if mouse.left_released() || mouse.right_clicked() {
+            if let Some(menu) = self.menu.take() {
+                self.dragging = None;
+                return menu.action_at(point).filter(|_| mouse.left_released());
+            }
+        }
+
+        if mouse.left_pressed() {
+            self.dragging = Self::slot_at(screen, point, stacks);
+        }
+
+        if mouse.left_released() {
+            let from = self.dragging.take()?;
+            return match Self::slot_at(screen, point, stacks) {
+                Some(to) if to != from => Some(StackAction::Move { from, to }),
+                Some(_) if shift => Some(StackAction::QuickMove(from)),
+                _ => None,
+            };
+        }
+
+        if mouse.right_clicked() {
+            let slot = Self::slot_at(screen, point, stacks)?;
+            // Kept on the screen when opened near its edges.
+            let size = ContextMenu::size();
+            let top_left = Vec2::new(
+                point.x().min(screen.x() - size.x()),
+                point.y().min(screen.y() - size.y()),
+            );
+            self.menu = Some(ContextMenu { slot, top_left });
+        }
+        None
+    }
+
+    /// Draws the stacks carried, the stack being dragged beneath the pointer, and the menu.
+    pub fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        locale: &Locale,
+        items: &ItemManager,
+        inventory: (&[ItemStack], u32),
+        pointer: Vec2,
+    ) {
+        if !self.open {
+            return;
+        }
+
+        let (stacks, capacity) = inventory;
+        let screen = renderer.screen_size();
+        let (top_left, size) = Self::frame(screen, stacks.len());
+        renderer.draw_rect(top_left, size, Vec3::new(32., 32., 32.));
+
+        let white = Vec3::new(255., 255., 255.);
+        let weight: u32 = stacks
+            .iter()
+            .filter_map(|stack| Some(items.get(&stack.item)?.weight * stack.count as u32))
+            .sum();
+        let header = Text::new("inventory.header")
+            .with("weight", weight)
+            .with("capacity", capacity);
+        let position = Vec2::new(top_left.x() + Self::PADDING, top_left.y() + Self::PADDING);
+        renderer.draw_text(&locale.text(&header), position, white, 255);
+
+        if stacks.is_empty() {
+            let position = Self::row(top_left, 0);
+            let position = Vec2::new(position.x() + Self::PADDING, position.y());
+            renderer.draw_text(locale.get("inventory.empty"), position, white, 255);
+        }
+
+        // The stack being dragged and the slot it would be dropped on stand out.
+        let target = self
+            .dragging
+            .and_then(|_| Self::slot_at(screen, pointer, stacks.len()));
+        let row_size = Vec2::new(Self::WIDTH, Self::ROW_HEIGHT);
+        for (slot, stack) in stacks.iter().enumerate() {
+            let position = Self::row(top_left, slot);
+            if self.dragging == Some(slot) || target == Some(slot) {
+                renderer.draw_rect(position, row_size, Vec3::new(64., 64., 64.));
+            }
+
+            let line = format!("[{}] {}", slot + 1, Self::label(locale, items, stack));
+            let position = Vec2::new(position.x() + Self::PADDING, position.y());
+            renderer.draw_text(&line, position, white, 255);
+        }
+
+        if let Some(stack) = self.dragging.and_then(|slot| stacks.get(slot)) {
+            let position = Vec2::new(pointer.x() + Self::PADDING, pointer.y());
+            renderer.draw_text(&Self::label(locale, items, stack), position, white, 200);
+        }
+
+        if let Some(menu) = &self.menu {
+            renderer.draw_rect(menu.top_left, ContextMenu::size(), Vec3::new(48., 48., 48.));
+            for (i, entry) in ContextMenu::ENTRIES.iter().enumerate() {
+                let position = menu.entry(i);
+                let position = Vec2::new(position.x() + Self::PADDING, position.y());
+                renderer.draw_text(locale.get(entry), position, white, 255);
+            }
+        }
+    }
+
+    /// Name of the item in a stack and how many there are.
+    fn label(locale: &Locale, items: &ItemManager, stack: &ItemStack) -> String {
+        match items.get(&stack.item) {
+            Some(item) => format!("{} x{}", locale.text(&item.text()), stack.count),
+            None => format!("#{} x{}", stack.item, stack.count),
+        }
+    }
+}
@@ -0,0 +1,1221 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{error::Error, thread};
+
+#[cfg(not(target_arch = "wasm32"))]
+use sdl2::image::{self, InitFlag};
+use uo2d_proto::components::{Bounds, EquipSlot, MovementMode, Vec2, Vec3};
+use uo2d_proto::cprintln;
+use uo2d_proto::locale::{Locale, Text};
+use uo2d_proto::packet::payloads::{
+    BankRequest, CredentialsPayload, DialogueReply, EmoteKind, EmotePayload, EquipPayload,
+    JoinPayload, MailPayload, MessagePayload, MoveStackPayload, MovementPayload, NotificationKind,
+    OverlayKind, OverlayRequest, StackPayload, TargetPayload, TradeRequest, UuidPayload,
+};
+use uo2d_proto::packet::{Action, Packet, Payload};
+#[cfg(not(target_arch = "wasm32"))]
+use uo2d_proto::shutdown;
+use uo2d_proto::time::{Duration, Instant};
+use uuid::Uuid;
+
+pub use crate::entities::CameraSettings;
+use crate::entities::{Camera, Mobile};
+pub use crate::quality::{Quality, QualitySettings};
+
+mod bank;
+mod cache;
+mod chunks;
+mod combat_text;
+mod console;
+mod emotes;
+mod entities;
+mod error_screen;
+mod gamestate;
+mod input;
+mod inventory;
+mod netgraph;
+mod packet_processor;
+mod quality;
+mod recording;
+mod renderer;
+#[cfg(not(target_arch = "wasm32"))]
+mod socket_client;
+mod toast;
+mod trade;
+mod transport;
+mod update;
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+mod websocket_client;
+
+use self::console::{Command, Console};
+#[cfg(not(target_arch = "wasm32"))]
+use self::error_screen::{ErrorChoice, ErrorScreen};
+use self::gamestate::Gamestate;
+#[cfg(not(target_arch = "wasm32"))]
+use self::input::{BotInput, HeadlessInput};
+use self::input::{Input, InputSource, Key};
+use self::inventory::{InventoryWindow, StackAction};
+use self::netgraph::NetGraph;
+use self::packet_processor::{processor, Handlers};
+use self::quality::QualityTuner;
+use self::recording::{Recorder, Replay};
+pub use self::renderer::Renderer;
+#[cfg(not(target_arch = "wasm32"))]
+use self::renderer::{HeadlessRenderer, SdlRenderer};
+#[cfg(not(target_arch = "wasm32"))]
+use self::socket_client::SocketClient;
+use self::transport::{ConnectionState, SendStatus, Transport};
+use self::update::UpdateCheck;
+#[cfg(target_arch = "wasm32")]
+pub use self::web::start;
+#[cfg(target_arch = "wasm32")]
+use self::websocket_client::WebSocketClient;
+
+#[cfg(not(target_arch = "wasm32"))]
+const WINDOW_DIMENSIONS: (u32, u32) = (800, 800);
+#[cfg(not(target_arch = "wasm32"))]
+const FONT_PATH: &str = "assets/font.ttf";
+const FONT_SIZE: u16 = 16;
+const BACKGROUND: &str = "background";
+/// Sprites for entities and tiles, packed together into an atlas.
+#[cfg(not(target_arch = "wasm32"))]
+const SPRITES_PATH: &str = "assets/sprites";
+const CHAT_OFFSET: f64 = 32.0;
+/// Time the server has to accept the join before giving up.
+const JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+const INVENTORY_KEYS: [Key; 9] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+];
+const UNEQUIP_KEYS: [(Key, EquipSlot); 3] = [
+    (Key::Z, EquipSlot::Head),
+    (Key::X, EquipSlot::Body),
+    (Key::C, EquipSlot::Weapon),
+];
+const EMOTE_KEYS: [(Key, EmoteKind); 3] = [
+    (Key::F1, EmoteKind::Wave),
+    (Key::F2, EmoteKind::Dance),
+    (Key::F3, EmoteKind::Point),
+];
+/// Toggle the debug overlays, only drawn for gamemasters.
+const OVERLAY_KEYS: [(Key, OverlayKind); 5] = [
+    (Key::F5, OverlayKind::Bounds),
+    (Key::F6, OverlayKind::Cells),
+    (Key::F7, OverlayKind::Regions),
+    (Key::F8, OverlayKind::Interest),
+    (Key::F9, OverlayKind::Paths),
+];
+
+/// How the client is presented and controlled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frontend {
+    /// SDL2 window controlled by the keyboard and mouse.
+    Window,
+    /// No window and no input, only stays connected.
+    Headless,
+    /// No window, wanders around on its own.
+    Bot,
+}
+
+/// Account to authenticate with instead of joining as a guest.
+#[derive(Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+    pub register: bool,
+}
+
+pub struct Client {
+    socket: Box<dyn Transport>,
+    gamestate: Gamestate,
+    camera: CameraSettings,
+    quality: QualitySettings,
+    interrupted: Arc<AtomicBool>,
+    /// Handlers for the packets the server sends.
+    handlers: Handlers,
+    console: Console,
+    netgraph: NetGraph,
+    /// Server the player asked to connect to instead, from the console.
+    reconnect: Option<String>,
+    /// Records the session to be played back later.
+    recorder: Option<Recorder>,
+    /// Input of the recording being played back, instead of the player's.
+    replay: Option<Replay>,
+    /// Looks for a newer release, until one is announced.
+    update: Option<UpdateCheck>,
+}
+
+impl Client {
+    /// Creates a new client, holding the connection to the server.
+    fn new(
+        socket: Box<dyn Transport>,
+        locale: Locale,
+        camera: CameraSettings,
+        quality: QualitySettings,
+        interrupted: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            socket,
+            gamestate: Gamestate::new(locale),
+            camera,
+            quality,
+            interrupted,
+            handlers: Handlers::new(),
+            console: Console::new(),
+            netgraph: NetGraph::new(),
+            reconnect: None,
+            recorder: None,
+            replay: None,
+            update: None,
+        }
+    }
+
+    /// Watches for the process being asked to stop, letting the client leave cleanly.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn watch_shutdown() -> Arc<AtomicBool> {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let flag = interrupted.clone();
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create a runtime");
+            rt.block_on(shutdown::signal());
+            flag.store(true, Ordering::Relaxed);
+        });
+        interrupted
+    }
+
+    /// Wraps sending packets, the connection state shows when they cannot be delivered.
+    fn send(&self, action: Action, payload: Payload) -> SendStatus {
+        self.socket.send(action, payload)
+    }
+
+    fn uuid(&self) -> Uuid {
+        self.socket.uuid()
+    }
+
+    /// Takes the packets received this tick, recording them if the session is being recorded.
+    fn receive(&mut self) -> Vec<Packet> {
+        let packets = self.socket.get_packets();
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(why) = recorder.packets(&packets) {
+                cprintln!("Stopped recording {}: {}", recorder.path().display(), why);
+                self.recorder = None;
+            }
+        }
+        packets
+    }
+
+    fn player(&self) -> &Mobile {
+        self.gamestate
+            .get_mobile(&self.gamestate.get_player())
+            .unwrap()
+    }
+
+    /// Starts the client, this begins the remote listerning and graphics.
+    /// Each session is written to the recordings directory if `record` is set.
+    /// Newer releases are looked for at the manifest address if one is given.
+    #[allow(clippy::too_many_arguments)]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start(
+        address: &str,
+        credentials: Option<Credentials>,
+        server_password: Option<String>,
+        language: &str,
+        frontend: Frontend,
+        camera: CameraSettings,
+        quality: QualitySettings,
+        record: bool,
+        update_manifest: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let join = Join {
+            address: address.to_string(),
+            credentials,
+            server_password,
+            language: language.to_string(),
+            record,
+            playback: None,
+            update: update_manifest.map(UpdateCheck::start),
+        };
+        Self::run(join, frontend, camera, quality)
+    }
+
+    /// Plays back a recorded session, drawing it as it was played without connecting to a server.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn playback(
+        path: &Path,
+        language: &str,
+        frontend: Frontend,
+        camera: CameraSettings,
+        quality: QualitySettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let join = Join {
+            address: path.display().to_string(),
+            credentials: None,
+            server_password: None,
+            language: language.to_string(),
+            record: false,
+            playback: Some(path.to_path_buf()),
+            update: None,
+        };
+        Self::run(join, frontend, camera, quality)
+    }
+
+    /// Joins and plays within the frontend until the player quits.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run(
+        join: Join,
+        frontend: Frontend,
+        camera: CameraSettings,
+        quality: QualitySettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let interrupted = Self::watch_shutdown();
+
+        // Windows explain what went wrong, the others have nobody to show it to.
+        if frontend == Frontend::Window {
+            return Ok(Self::sdl_start(join, camera, quality, interrupted)?);
+        }
+
+        let mut client = Self::connect(&join, camera, quality, interrupted)?;
+        let mut renderer = HeadlessRenderer::new(Vec2::new(
+            WINDOW_DIMENSIONS.0 as f64,
+            WINDOW_DIMENSIONS.1 as f64,
+        ));
+        let result = match frontend {
+            Frontend::Bot => {
+                let seed = client.uuid().as_u64_pair().0;
+                client.gameloop(&mut renderer, &mut BotInput::new(seed))
+            }
+            _ => client.gameloop(&mut renderer, &mut HeadlessInput),
+        };
+        client.leave();
+
+        Ok(result?)
+    }
+
+    /// Joins the server, waiting until it has accepted or refused the player.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn connect(
+        join: &Join,
+        camera: CameraSettings,
+        quality: QualitySettings,
+        interrupted: Arc<AtomicBool>,
+    ) -> Result<Self, String> {
+        let mut client = Self::open(join, camera, quality, interrupted)?;
+        let started = Instant::now();
+        while !client.joined(join, started)? {
+            thread::sleep(client.gamestate.timers.client_tick_time());
+        }
+        Ok(client)
+    }
+
+    /// Creates the client and asks to join the server, recordings play back what it said instead.
+    fn open(
+        join: &Join,
+        camera: CameraSettings,
+        quality: QualitySettings,
+        interrupted: Arc<AtomicBool>,
+    ) -> Result<Self, String> {
+        let locale = Locale::new(&join.language);
+        let (socket, replay): (Box<dyn Transport>, _) = match &join.playback {
+            Some(path) => {
+                let (playback, replay) = recording::open(path).map_err(|why| {
+                    let text = Text::new("recording.unreadable")
+                        .with("path", path.display())
+                        .with("reason", why);
+                    locale.text(&text)
+                })?;
+                (Box::new(playback), Some(replay))
+            }
+            None => (transport(&join.address), None),
+        };
+
+        let mut client = Self::new(socket, locale, camera, quality, interrupted);
+        client.replay = replay;
+        client.update = join.update.clone();
+        if join.record {
+            match Recorder::create(Path::new(Recorder::DIRECTORY)) {
+                Ok(recorder) => {
+                    cprintln!("Recording the session to {}.", recorder.path().display());
+                    client.recorder = Some(recorder);
+                }
+                Err(why) => cprintln!("Unable to record the session: {}", why),
+            }
+        }
+        let status = match join.credentials.clone() {
+            Some(credentials) => {
+                let action = if credentials.register {
+                    Action::Register
+                } else {
+                    Action::Login
+                };
+                let payload = CredentialsPayload::new(
+                    credentials.username,
+                    credentials.password,
+                    join.server_password.clone(),
+                );
+                client.send(action, Payload::Credentials(payload))
+            }
+            None => client.send(
+                Action::ClientJoin,
+                Payload::Join(JoinPayload::new(join.server_password.clone())),
+            ),
+        };
+        if status == SendStatus::Disconnected {
+            return Err(client.refused("join.closed".into()));
+        }
+        Ok(client)
+    }
+
+    /// Processes what the server sent since it was asked to join, checking if it accepted the player.
+    /// Fails once the server refused, the connection closed, or the server took too long.
+    fn joined(&mut self, join: &Join, started: Instant) -> Result<bool, String> {
+        if let Some(why) = self.gamestate.error.take() {
+            return Err(self.refused(Text::literal(why)));
+        }
+        if self.socket.state() == ConnectionState::Closed {
+            return Err(self.refused("join.closed".into()));
+        }
+        if started.elapsed() > JOIN_TIMEOUT {
+            let why = Text::new("join.timeout").with("address", &join.address);
+            return Err(self.refused(why));
+        }
+        if self.interrupted.load(Ordering::Relaxed) {
+            return Err(self.refused("join.interrupted".into()));
+        }
+
+        self.socket.flush();
+
+        let packets = self.receive();
+        for packet in packets.into_iter() {
+            processor(
+                &self.handlers,
+                self.socket.as_mut(),
+                &mut self.gamestate,
+                packet,
+            );
+        }
+        if self.uuid() == Uuid::nil() {
+            return Ok(false);
+        }
+
+        // Add the client as a player.
+        cprintln!(
+            "Player [{}] UUID: {}",
+            self.gamestate.get_player(),
+            self.uuid()
+        );
+        Ok(true)
+    }
+
+    /// Explains why the player could not join.
+    fn refused(&self, why: Text) -> String {
+        let why = Text::new("join.failed").with("reason", self.gamestate.locale.text(&why));
+        self.gamestate.locale.text(&why)
+    }
+
+    /// Informs the server we are quitting.
+    fn leave(&self) {
+        self.send(Action::ClientLeave, Payload::Empty);
+        // Gives the packet time to be sent before the connection is dropped.
+        #[cfg(not(target_arch = "wasm32"))]
+        thread::sleep(Duration::from_millis(250));
+    }
+
+    /// Creates the SDL2 window and plays within it, showing why the game stopped until the player quits.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sdl_start(
+        mut join: Join,
+        camera: CameraSettings,
+        quality: QualitySettings,
+        interrupted: Arc<AtomicBool>,
+    ) -> Result<(), String> {
+        let sdl_context = sdl2::init().map_err(|e| e.to_string())?;
+        let video_subsystem = sdl_context.video().map_err(|e| e.to_string())?;
+
+        let _image_context = image::init(InitFlag::PNG).map_err(|e| e.to_string())?;
+        let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string())?;
+
+        // The font ships with the assets, without it the menus and chat cannot be drawn.
+        let font = ttf_context
+            .load_font(Path::new(FONT_PATH), FONT_SIZE)
+            .map_err(|why| format!("Unable to load the font {}: {}", FONT_PATH, why))?;
+
+        let window = video_subsystem
+            .window("uo2d", WINDOW_DIMENSIONS.0, WINDOW_DIMENSIONS.1)
+            .position_centered()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let canvas = window
+            .into_canvas()
+            .present_vsync()
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let texture_creator = canvas.texture_creator();
+        let mut renderer = SdlRenderer::new(canvas, &texture_creator, Some(font));
+        let mut event_pump = sdl_context.event_pump().map_err(|e| e.to_string())?;
+
+        // Anything without a sprite is drawn as a colored rectangle instead.
+        match renderer.load_atlas(Path::new(SPRITES_PATH)) {
+            Ok(0) => (),
+            Ok(count) => cprintln!("Packed {} sprites into the atlas.", count),
+            Err(why) => cprintln!("Unable to load the sprites in {}: {}", SPRITES_PATH, why),
+        }
+
+        loop {
+            let result = renderer
+                .load_sprite(BACKGROUND, Path::new("assets/background.png"))
+                .map_err(|why| format!("Unable to load assets: {}", why))
+                .and_then(|_| Self::connect(&join, camera, quality, interrupted.clone()))
+                .and_then(|mut client| {
+                    renderer.set_title(&format!("uo2d - {}", client.uuid()));
+                    let result = client.gameloop(&mut renderer, &mut event_pump);
+                    client.leave();
+                    // Retries keep the language chosen while playing.
+                    join.language = client.gamestate.locale.language().to_string();
+                    result.map(|()| client.reconnect.take())
+                });
+
+            let reason = match result {
+                Ok(None) => return Ok(()),
+                Ok(Some(address)) => {
+                    cprintln!("Connecting to {}.", address);
+                    join.address = address;
+                    continue;
+                }
+                Err(_) if interrupted.load(Ordering::Relaxed) => return Ok(()),
+                Err(reason) => reason,
+            };
+
+            cprintln!("{}", reason);
+            renderer.set_title("uo2d");
+            let locale = Locale::new(&join.language);
+            let update = join.update.as_ref().and_then(UpdateCheck::available);
+            let screen = ErrorScreen::new(reason, &locale)
+                .notice(update.map(|update| locale.text(&update.text())));
+            if screen.run(&mut renderer, &mut event_pump, &interrupted) == ErrorChoice::Quit {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Makes a request of the bank, hiding its window as soon as it is closed.
+    fn bank(&mut self, request: BankRequest) {
+        if request == BankRequest::Close {
+            self.gamestate.bank.close();
+        }
+        self.send(Action::Bank, Payload::BankRequest(request));
+    }
+
+    /// Acts on a stack in the inventory, chosen from its window or with the number keys.
+    fn stack_action(&mut self, action: StackAction) {
+        let slot = match action {
+            StackAction::Move { from, to } => {
+                let payload = MoveStackPayload::new(from as u16, to as u16);
+                self.send(Action::MoveStack, Payload::MoveStack(payload));
+                return;
+            }
+            StackAction::Use(slot)
+            | StackAction::Split(slot)
+            | StackAction::Drop(slot)
+            | StackAction::QuickMove(slot) => slot,
+        };
+        let stack = match self.gamestate.inventory.get(slot) {
+            Some(stack) => *stack,
+            None => return,
+        };
+
+        let index = slot as u16;
+        match action {
+            StackAction::Drop(_) => {
+                self.send(
+                    Action::Drop,
+                    Payload::Stack(StackPayload::new(index, stack.count)),
+                );
+            }
+            StackAction::Split(_) => {
+                self.send(
+                    Action::SplitStack,
+                    Payload::Stack(StackPayload::new(index, stack.count / 2)),
+                );
+            }
+            // Stacks are moved into an open trade, or deposited into an open bank.
+            StackAction::QuickMove(_) if self.gamestate.trade.is_open() => {
+                let request = TradeRequest::Offer {
+                    slot: index,
+                    count: stack.count,
+                };
+                self.send(Action::Trade, Payload::TradeRequest(request));
+            }
+            StackAction::QuickMove(_) if self.gamestate.bank.is_open() => {
+                let request = BankRequest::Deposit {
+                    slot: index,
+                    count: stack.count,
+                };
+                self.send(Action::Bank, Payload::BankRequest(request));
+            }
+            StackAction::QuickMove(_) => {
+                let text = self.gamestate.locale.get("inventory.nowhere").to_string();
+                self.gamestate.toasts.push(NotificationKind::Warning, text);
+            }
+            _ => {
+                let item = match self.gamestate.items.get(&stack.item) {
+                    Some(item) => item,
+                    None => return,
+                };
+                if let Some(equip) = item.slot {
+                    self.send(
+                        Action::Equip,
+                        Payload::Equip(EquipPayload::new(equip, Some(stack.item))),
+                    );
+                } else if item.effect.is_some() {
+                    self.send(Action::UseItem, Payload::Stack(StackPayload::new(index, 1)));
+                }
+            }
+        }
+    }
+
+    /// This is responsible for processing the graphics and responses from the remote server.
+    /// Stops with the reason once the connection is lost, the player was kicked, or the server shut down.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn gameloop(
+        &mut self,
+        renderer: &mut dyn Renderer,
+        input_source: &mut dyn InputSource,
+    ) -> Result<(), String> {
+        let mut play = self.play(renderer);
+        while self.frame(&mut play, renderer, input_source)? {
+            thread::sleep(
+                self.gamestate
+                    .timers
+                    .client_tick_time()
+                    .saturating_sub(self.gamestate.timers.tick_time()),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Starts playing once the player has joined.
+    fn play(&mut self, renderer: &dyn Renderer) -> Play {
+        // Create the camera.
+        let mut camera = Camera::new(Vec3::ORIGIN, renderer.screen_size(), self.camera);
+
+        // Position the camera where the player is centered.
+        camera.center_on(self.player().position());
+
+        // Starts at the best quality unless one was chosen, lowering it if frames take too long.
+        let tuner = QualityTuner::new(self.quality);
+        self.gamestate.quality = tuner.level();
+        camera.set_rigid(!tuner.level().smoothing());
+
+        let mut input = Input::default();
+        input.mouse.set_delay(10);
+        Play {
+            camera,
+            tuner,
+            input,
+            held_move: false,
+            mailbox_open: false,
+            inventory: InventoryWindow::default(),
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Processes the packets and input of a single tick and draws it.
+    /// Returns false once the player has stopped playing.
+    fn frame(
+        &mut self,
+        play: &mut Play,
+        renderer: &mut dyn Renderer,
+        input_source: &mut dyn InputSource,
+    ) -> Result<bool, String> {
+        let Play {
+            camera,
+            tuner,
+            input,
+            held_move,
+            mailbox_open,
+            inventory,
+            overlays,
+        } = play;
+        // Measured each frame, the sprite may still be loading when play starts.
+        let bg_size = renderer.sprite_size(BACKGROUND).unwrap_or(Vec2::ORIGIN);
+        let move_speed = 32.0;
+
+        // The recording has been played back in full.
+        if self.replay.as_ref().is_some_and(Replay::is_finished) {
+            return Ok(false);
+        }
+
+        for timer in self.gamestate.timers.update() {
+            cprintln!("Expired: {:?}", timer);
+        }
+        self.gamestate.combat_text.update();
+        self.gamestate.toasts.update();
+        self.gamestate.emotes.update();
+
+        // Announced once the check finds a newer release, without holding up the game.
+        if let Some(update) = self.update.as_ref().and_then(UpdateCheck::available) {
+            let text = self.gamestate.locale.text(&update.text());
+            self.gamestate
+                .toasts
+                .push(NotificationKind::Announcement, text);
+            self.update = None;
+        }
+
+        // Process the data from the server if there is any.
+        let packets = self.receive();
+        self.netgraph.update(self.socket.stats(), packets.len());
+        for packet in packets.into_iter() {
+            if let Some((action, payload)) = processor(
+                &self.handlers,
+                self.socket.as_mut(),
+                &mut self.gamestate,
+                packet,
+            ) {
+                self.send(action, payload);
+            }
+        }
+        self.socket.flush();
+
+        // Another server has taken the player, continue with it instead.
+        // Recordings already hold what the new server sent.
+        let redirect = self.gamestate.redirect.take();
+        if let Some(redirect) = redirect.filter(|_| self.replay.is_none()) {
+            cprintln!("Redirected to {}.", redirect.address);
+            self.send(Action::ClientLeave, Payload::Empty);
+            self.socket = transport(&redirect.address);
+            self.send(
+                Action::Handoff,
+                Payload::Uuid(UuidPayload::new(redirect.token)),
+            );
+        }
+
+        // Errors from the server during play are kicks, stop and explain.
+        if let Some(why) = self.gamestate.error.take() {
+            return Err(why);
+        } else if self.gamestate.kill {
+            return Err(self.gamestate.locale.get("server.shutdown").to_string());
+        } else if self.socket.state() == ConnectionState::Closed {
+            return Err(self.gamestate.locale.get("connection.closed").to_string());
+        }
+
+        // Drop the map chunks that are too far away.
+        let position = self.player().position();
+        self.gamestate.chunks.evict(&position);
+
+        // Most recent version of player, update camera.
+        let player = self.player().clone();
+        camera.follow(player.position());
+
+        renderer.clear();
+
+        // Renders the background and gamestate entities.
+        // Move the background / map.
+        let offset = camera.world_to_screen(&Vec3::ORIGIN);
+        renderer.draw_sprite(BACKGROUND, offset, bg_size.apply_scalar(camera.zoom()));
+
+        self.gamestate.draw(renderer, camera);
+        self.gamestate.draw_overlays(renderer, camera);
+        self.gamestate.draw_emotes(renderer, camera);
+        self.gamestate.draw_threats(renderer, camera);
+        self.gamestate.draw_casts(renderer, camera);
+        self.gamestate.draw_combat_text(renderer, camera);
+        self.gamestate.draw_toasts(renderer);
+        self.gamestate.draw_hud(renderer);
+        self.gamestate
+            .draw_connection(renderer, self.socket.state());
+        if input.keyboard.tab_pressed {
+            self.gamestate.draw_leaderboard(renderer);
+        } else if *mailbox_open {
+            self.gamestate.draw_mailbox(renderer);
+        }
+        self.gamestate.draw_dialogue(renderer);
+        self.gamestate
+            .trade
+            .draw(renderer, &self.gamestate.locale, &self.gamestate.items);
+        self.gamestate
+            .bank
+            .draw(renderer, &self.gamestate.locale, &self.gamestate.items);
+        inventory.draw(
+            renderer,
+            &self.gamestate.locale,
+            &self.gamestate.items,
+            (&self.gamestate.inventory, self.gamestate.capacity),
+            input.mouse.position,
+        );
+        if let Some(text) = input.chat.text() {
+            let top_left = Vec2::new(10., renderer.screen_size().y() - CHAT_OFFSET);
+            let color = Vec3::new(255., 255., 255.);
+            renderer.draw_text(&format!("> {}", text), top_left, color, 255);
+        }
+        if self.console.netgraph {
+            self.netgraph.draw(renderer, &self.gamestate.locale);
+        }
+        if let Some(text) = input.console.text() {
+            self.console.draw(renderer, text);
+        }
+        renderer.present();
+
+        // Update the input tracker.
+        let mut velocity: Vec2 = Vec2::ORIGIN;
+        input_source.poll(input);
+        if let Some(replay) = self.replay.as_mut() {
+            replay.apply(input);
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.input(input);
+        }
+        let interrupted = self.interrupted.load(Ordering::Relaxed);
+        if input.keyboard.esc_pressed || interrupted {
+            return Ok(false);
+        } else if input.mouse.left_held() {
+            *held_move = true;
+        }
+
+        // Clicks within the trade, bank, and inventory windows act on them instead of moving or firing.
+        let screen = renderer.screen_size();
+        let stacks = self.gamestate.inventory.len();
+        let captured = inventory.captures(screen, &input.mouse, stacks)
+            || self.gamestate.trade.contains(screen, input.mouse.position)
+            || self.gamestate.bank.contains(screen, input.mouse.position);
+        if input.mouse.left_clicked() {
+            if let Some(request) = self.gamestate.trade.click(screen, input.mouse.position) {
+                self.send(Action::Trade, Payload::TradeRequest(request));
+            }
+            if let Some(request) = self.gamestate.bank.click(screen, input.mouse.position) {
+                self.bank(request);
+            }
+        }
+        let shift = input.keyboard.shift_pressed;
+        if let Some(action) = inventory.update(&input.mouse, shift, screen, stacks) {
+            self.stack_action(action);
+        }
+
+        // Update the movement towards the mouse pointer.
+        let mut move_to: Option<Vec2> = None;
+        let mut stopped: bool = false;
+        if !captured && (input.mouse.left_clicked() || input.mouse.left_held()) {
+            if let Some(target) = input.mouse.last_target {
+                move_to = Some(camera.screen_to_world(&target));
+            }
+        } else if !input.mouse.left_held() && *held_move {
+            // Let go and stop movement.
+            *held_move = false;
+            move_to = None;
+            stopped = true; // Used to send no velocity to server.
+        }
+
+        // Update the projectile towards the mouse pointer.
+        let mut projectile: Vec2 = Vec2::ORIGIN;
+        if !captured && (input.mouse.right_clicked() || input.mouse.right_held()) {
+            if let Some(target) = input.mouse.last_target {
+                let bb = player.bounding_box();
+                let (x, y) = camera.screen_to_world(&target).as_tuple();
+                let mut focus = Some(Vec2::new(x - bb.width() / 2., y - bb.height() / 2.));
+
+                projectile = get_velocity(player.position(), &mut focus);
+            }
+        }
+
+        // Calculate movement based on keyboard actions.
+        if input.keyboard.movement_pressed() {
+            if input.keyboard.w_pressed {
+                velocity.set_y(-move_speed); // Move up
+            }
+            if input.keyboard.a_pressed {
+                velocity.set_x(-move_speed); // Move left
+            }
+            if input.keyboard.s_pressed {
+                velocity.set_y(move_speed); // Move down
+            }
+            if input.keyboard.d_pressed {
+                velocity.set_x(move_speed); // Move right
+            }
+
+            move_to = None; // Override the mouse clicking.
+        } else if move_to.is_some() {
+            velocity = get_velocity(player.position(), &mut move_to);
+        }
+
+        // Produces a packet that we have moved to send to server or that we wish to stop movement.
+        if velocity != Vec2::ORIGIN && (move_to.is_some() || input.keyboard.movement_pressed())
+            || stopped
+        {
+            self.send(
+                Action::Movement,
+                Payload::Movement(MovementPayload::new(
+                    player.entity,
+                    player.size(),
+                    player.position(),
+                    velocity,
+                )),
+            );
+        }
+
+        // Projectiles cannot be fired while swimming.
+        if projectile != Vec2::ORIGIN && player.mode != MovementMode::Swimming {
+            let area = Bounds::from_vec(player.position(), player.size());
+            let size = Vec2::new(16., 16.);
+            let loc = place_outside(&area, projectile, size);
+
+            self.send(
+                Action::Projectile,
+                Payload::Movement(MovementPayload::new(player.entity, size, loc, projectile)),
+            );
+        }
+
+        // Equip or use items from the inventory, split the stack in half while holding shift,
+        // or drop the stack while holding control. Answers the NPC instead while choosing,
+        // and offers the stack, or half of it while holding shift, while trading.
+        let choosing = self
+            .gamestate
+            .dialogue
+            .as_ref()
+            .is_some_and(|dialogue| dialogue.is_last() && !dialogue.choices.is_empty());
+        let trading = self.gamestate.trade.is_open();
+        for (i, key) in INVENTORY_KEYS.iter().enumerate() {
+            if !input.keyboard.just_pressed(*key) {
+                continue;
+            }
+
+            if choosing {
+                self.send(
+                    Action::Dialogue,
+                    Payload::DialogueReply(DialogueReply::Choice(i as u8)),
+                );
+                continue;
+            }
+
+            let stack = match self.gamestate.inventory.get(i) {
+                Some(stack) => *stack,
+                None => continue,
+            };
+
+            if trading {
+                let count = match input.keyboard.shift_pressed {
+                    true => (stack.count / 2).max(1),
+                    false => stack.count,
+                };
+                self.send(
+                    Action::Trade,
+                    Payload::TradeRequest(TradeRequest::Offer {
+                        slot: i as u16,
+                        count,
+                    }),
+                );
+            } else if input.keyboard.ctrl_pressed {
+                self.stack_action(StackAction::Drop(i));
+            } else if input.keyboard.shift_pressed {
+                self.stack_action(StackAction::Split(i));
+            } else {
+                self.stack_action(StackAction::Use(i));
+            }
+        }
+
+        // Chat commands for emotes, mail, and the language are not sent as messages.
+        if let Some(message) = input.chat.take() {
+            let entity = self.gamestate.get_player();
+            let language = message.strip_prefix("/language ").map(str::trim);
+            let mail = MailPayload::from_command(&message);
+            match (language, EmoteKind::from_command(&message), mail) {
+                (Some(language), _, _) => self.gamestate.set_language(language),
+                (None, Some(kind), _) => {
+                    self.send(
+                        Action::Emote,
+                        Payload::Emote(EmotePayload::new(entity, kind)),
+                    );
+                }
+                (None, None, Some(mail)) => {
+                    self.send(Action::Mail, Payload::Mail(mail));
+                }
+                (None, None, None) => {
+                    self.send(
+                        Action::Message,
+                        Payload::Message(MessagePayload::new(message)),
+                    );
+                }
+            }
+        }
+
+        for (key, kind) in EMOTE_KEYS {
+            if input.keyboard.just_pressed(key) {
+                let entity = self.gamestate.get_player();
+                self.send(
+                    Action::Emote,
+                    Payload::Emote(EmotePayload::new(entity, kind)),
+                );
+            }
+        }
+
+        let mut toggles: Vec<OverlayKind> = OVERLAY_KEYS
+            .into_iter()
+            .filter(|(key, _)| input.keyboard.just_pressed(*key))
+            .map(|(_, kind)| kind)
+            .collect();
+
+        if let Some(line) = input.console.take() {
+            self.console.print(format!("~ {}", line));
+            match Command::parse(&line) {
+                // Recordings are played back without connecting anywhere.
+                Ok(Command::Connect(_)) if self.replay.is_some() => (),
+                Ok(Command::Connect(address)) => {
+                    self.reconnect = Some(address);
+                    return Ok(false);
+                }
+                Ok(Command::Position) => {
+                    let (x, y, z) = self.player().position().as_tuple();
+                    let text = Text::new("console.position")
+                        .with("x", x.round())
+                        .with("y", y.round())
+                        .with("z", z);
+                    self.console.print(self.gamestate.locale.text(&text));
+                }
+                // The server decides if the player is allowed to.
+                Ok(Command::Teleport(x, y)) => {
+                    self.send(
+                        Action::Message,
+                        Payload::Message(MessagePayload::new(format!("/tp {} {}", x, y))),
+                    );
+                }
+                Ok(Command::NetGraph) => self.console.netgraph = !self.console.netgraph,
+                Ok(Command::ToggleOverlay(Some(kind))) => toggles.push(kind),
+                // Shows them all unless every one is already shown.
+                Ok(Command::ToggleOverlay(None)) => {
+                    let all = OverlayKind::ALL.iter().all(|kind| overlays.contains(kind));
+                    toggles.extend(
+                        OverlayKind::ALL
+                            .into_iter()
+                            .filter(|kind| all || !overlays.contains(kind)),
+                    );
+                }
+                Ok(Command::Help) => {
+                    let help = self.gamestate.locale.get("console.help").to_string();
+                    self.console.print(help);
+                }
+                Ok(Command::Clear) => self.console.clear(),
+                Err(usage) => self.console.print(self.gamestate.locale.text(&usage)),
+            }
+        }
+
+        // The server refuses those that are not gamemasters, leaving nothing to draw.
+        let toggled = !toggles.is_empty();
+        for kind in toggles {
+            if let Some(i) = overlays.iter().position(|viewing| *viewing == kind) {
+                overlays.remove(i);
+                self.gamestate.overlays.remove(&kind);
+            } else {
+                overlays.push(kind);
+            }
+        }
+        if toggled {
+            self.send(
+                Action::Overlay,
+                Payload::OverlayRequest(OverlayRequest::new(overlays.clone())),
+            );
+        }
+
+        // The scoreboard is refreshed each time it is opened.
+        if input.keyboard.just_pressed(Key::Tab) {
+            self.send(Action::Leaderboard, Payload::Empty);
+        }
+
+        if input.keyboard.just_pressed(Key::I) {
+            inventory.toggle();
+        }
+
+        // Open the bank while near a banker or within a bank, or close it.
+        if input.keyboard.just_pressed(Key::B) {
+            let request = match self.gamestate.bank.is_open() {
+                true => BankRequest::Close,
+                false => BankRequest::Open,
+            };
+            self.bank(request);
+        }
+
+        // The mailbox is refreshed each time it is opened, marking the letters as read.
+        if input.keyboard.just_pressed(Key::M) {
+            *mailbox_open = !*mailbox_open;
+            if *mailbox_open {
+                self.send(Action::Mailbox, Payload::Empty);
+            }
+        }
+
+        // Talk to the closest NPC, or continue the conversation with them.
+        if input.keyboard.just_pressed(Key::E) {
+            let reply = match &self.gamestate.dialogue {
+                None => Some(DialogueReply::Talk),
+                Some(dialogue) if !dialogue.is_last() => {
+                    Some(DialogueReply::Page(dialogue.page + 1))
+                }
+                Some(dialogue) if dialogue.choices.is_empty() => Some(DialogueReply::Close),
+                Some(_) => None,
+            };
+
+            if let Some(reply) = reply {
+                if reply == DialogueReply::Close {
+                    self.gamestate.dialogue = None;
+                }
+                self.send(Action::Dialogue, Payload::DialogueReply(reply));
+            }
+        }
+
+        if input.keyboard.just_pressed(Key::Q) && self.gamestate.dialogue.take().is_some() {
+            self.send(
+                Action::Dialogue,
+                Payload::DialogueReply(DialogueReply::Close),
+            );
+        }
+
+        if input.keyboard.just_pressed(Key::G) {
+            self.send(Action::Pickup, Payload::Empty);
+        }
+
+        // Lock on to the mobile beneath the mouse pointer, firing once it is in sight.
+        if input.keyboard.just_pressed(Key::T) {
+            let point = input
+                .mouse
+                .last_target
+                .map(|target| camera.screen_to_world(&target));
+            if let Some(target) = point.and_then(|point| self.gamestate.mobile_at(point)) {
+                self.send(
+                    Action::Attack,
+                    Payload::Target(TargetPayload::new(
+                        self.gamestate.get_player(),
+                        Some(target),
+                    )),
+                );
+            }
+        }
+
+        // Invite the player beneath the mouse pointer to trade.
+        if input.keyboard.just_pressed(Key::Y) {
+            let point = input
+                .mouse
+                .last_target
+                .map(|target| camera.screen_to_world(&target));
+            if let Some(target) = point.and_then(|point| self.gamestate.mobile_at(point)) {
+                self.send(
+                    Action::Trade,
+                    Payload::TradeRequest(TradeRequest::Invite(target)),
+                );
+            }
+        }
+
+        if input.keyboard.just_pressed(Key::R) {
+            self.send(Action::Mount, Payload::Empty);
+        }
+
+        // Gather from the closest tree, ore vein, or similar.
+        if input.keyboard.just_pressed(Key::F) {
+            self.send(Action::Gather, Payload::Empty);
+        }
+
+        for (key, slot) in UNEQUIP_KEYS {
+            if input.keyboard.just_pressed(key) {
+                self.send(
+                    Action::Unequip,
+                    Payload::Equip(EquipPayload::new(slot, None)),
+                );
+            }
+        }
+
+        if let Some(quality) = tuner.record(self.gamestate.timers.tick_time()) {
+            camera.set_rigid(!quality.smoothing());
+            self.gamestate.set_quality(quality);
+        }
+
+        Ok(true)
+    }
+}
+
+/// State kept between the frames while playing.
+struct Play {
+    camera: Camera,
+    tuner: QualityTuner,
+    input: Input,
+    held_move: bool,
+    mailbox_open: bool,
+    inventory: InventoryWindow,
+    overlays: Vec<OverlayKind>,
+}
+
+/// Where and how to join a server, kept to retry with.
+struct Join {
+    address: String,
+    credentials: Option<Credentials>,
+    server_password: Option<String>,
+    /// Language the player chose, kept when retrying.
+    language: String,
+    /// Records every session joined.
+    record: bool,
+    /// Recording played back instead of joining the server.
+    playback: Option<PathBuf>,
+    /// Looks for a newer release while the player joins and plays.
+    update: Option<UpdateCheck>,
+}
+
+/// Opens a connection to the server, over UDP where the target allows it.
+#[cfg(not(target_arch = "wasm32"))]
+fn transport(address: &str) -> Box<dyn Transport> {
+    Box::new(SocketClient::new(address))
+}
+
+/// Opens a connection to the server, browsers can only reach it through a WebSocket.
+#[cfg(target_arch = "wasm32")]
+fn transport(address: &str) -> Box<dyn Transport> {
+    Box::new(WebSocketClient::new(address))
+}
+
+/// Obtains the velocity required to move between start and target.
+fn get_velocity(start: Vec3, target: &mut Option<Vec2>) -> Vec2 {
+    if let Some(tar) = target {
+        let (px, py) = (start.x(), start.y());
+        let (tx, ty) = tar.as_tuple();
+
+        // Velocity required.
+        let vel = Vec2::new(tx - px, ty - py);
+        if vel.length() < 1.0 {
+            // Set velocity to 0 if we are already close by.
+            *target = None;
+            Vec2::new(0., 0.)
+        } else {
+            vel
+        }
+    } else {
+        // Set velocity to 0 if there is not target.
+        Vec2::new(0., 0.)
+    }
+}
+
+/// Gets the nearest coordinates that an object of `size` can exist in relation to the current object at the specified velocity.
+pub fn place_outside(mobile: &Bounds, velocity: Vec2, size: Vec2) -> Vec3 {
+    let center: Vec2 = mobile.center_2d(); // Center of hitbox coordinate.
+    let min_dist: f64 = center.distance(&mobile.top_left_2d()); // Center to top corner (furthest)
+    let (dx, dy) = size.apply_scalar(0.5).as_tuple();
+
+    // Get normalize the velocity.
+    let mut direction = velocity.normalize();
+
+    // Calculate the additional distance needed to place the object outside, considering its size.
+    let extra_dist = (size.x().max(size.y()) / 2.0) + min_dist;
+    direction = direction.scaled(extra_dist);
+
+    // Calculate the new position in the direction of the velocity.
+    let new_pos = Vec2::new(
+        center.x() + direction.x() - dx,
+        center.y() + direction.y() - dy,
+    );
+    Vec3::new(new_pos.x(), new_pos.y(), 1.)
+}
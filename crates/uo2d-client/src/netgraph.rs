@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use uo2d_proto::components::{Vec2, Vec3};
+use uo2d_proto::locale::{Locale, Text};
+use uo2d_proto::time::{Duration, Instant};
+
+use super::renderer::Renderer;
+use super::transport::NetStats;
+
+/// Recent samples of a statistic, drawn as a small line chart.
+struct Chart {
+    samples: VecDeque<f64>,
+    color: Vec3,
+}
+
+impl Chart {
+    /// Samples kept, the oldest scroll off the left of the chart.
+    const SAMPLES: usize = 60;
+    const WIDTH: f64 = 180.;
+    const HEIGHT: f64 = 36.;
+
+    fn new(color: Vec3) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(Self::SAMPLES),
+            color,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.samples.len() == Self::SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    /// Draws the samples scaled to the largest of them, along with the label above.
+    fn draw(&self, renderer: &mut dyn Renderer, top_left: Vec2, label: &str) {
+        renderer.draw_text(label, top_left, self.color, 255);
+
+        let (left, top) = (top_left.x(), top_left.y() + NetGraph::LABEL_HEIGHT);
+        let size = Vec2::new(Self::WIDTH, Self::HEIGHT);
+        renderer.draw_rect_alpha(Vec2::new(left, top), size, Vec3::new(16., 16., 16.), 160);
+
+        let peak = self.samples.iter().copied().fold(0., f64::max);
+        let scale = if peak > 0. { 1. / peak } else { 0. };
+        let step = Self::WIDTH / (Self::SAMPLES - 1) as f64;
+        let point = |i: usize, value: f64| {
+            Vec2::new(
+                left + i as f64 * step,
+                top + Self::HEIGHT * (1. - value * scale),
+            )
+        };
+
+        let points: Vec<Vec2> = self
+            .samples
+            .iter()
+            .enumerate()
+            .map(|(i, value)| point(i + Self::SAMPLES - self.samples.len(), *value))
+            .collect();
+        for line in points.windows(2) {
+            renderer.draw_line(line[0], line[1], self.color);
+        }
+    }
+}
+
+/// Charts of the connection's traffic, sampled from the transport statistics.
+pub struct NetGraph {
+    incoming: Chart,
+    outgoing: Chart,
+    loss: Chart,
+    rtt: Chart,
+    buffered: Chart,
+    /// Statistics when the last sample was taken.
+    last: NetStats,
+    since: Instant,
+    /// Most packets waiting to be processed in a single frame since the last sample.
+    depth: usize,
+}
+
+impl NetGraph {
+    /// Time between samples.
+    const INTERVAL: Duration = Duration::from_millis(250);
+    const LABEL_HEIGHT: f64 = 18.;
+    const MARGIN: f64 = 10.;
+
+    pub fn new() -> Self {
+        Self {
+            incoming: Chart::new(Vec3::new(128., 255., 128.)),
+            outgoing: Chart::new(Vec3::new(128., 192., 255.)),
+            loss: Chart::new(Vec3::new(255., 96., 96.)),
+            rtt: Chart::new(Vec3::new(255., 215., 0.)),
+            buffered: Chart::new(Vec3::new(200., 160., 255.)),
+            last: NetStats::default(),
+            since: Instant::now(),
+            depth: 0,
+        }
+    }
+
+    /// Called every frame with the packets about to be processed, sampling once the interval passes.
+    pub fn update(&mut self, stats: NetStats, buffered: usize) {
+        self.depth = self.depth.max(buffered);
+        let elapsed = self.since.elapsed();
+        if elapsed < Self::INTERVAL {
+            return;
+        }
+
+        // Counters start over with a new connection, such as after being redirected.
+        let seconds = elapsed.as_secs_f64();
+        let rate = |now: u64, last: u64| now.saturating_sub(last) as f64 / seconds;
+        self.incoming
+            .push(rate(stats.bytes_received, self.last.bytes_received));
+        self.outgoing
+            .push(rate(stats.bytes_sent, self.last.bytes_sent));
+        self.loss.push(stats.loss * 100.);
+        self.rtt
+            .push(stats.rtt.map_or(0., |rtt| rtt.as_secs_f64() * 1000.));
+        self.buffered.push(std::mem::take(&mut self.depth) as f64);
+
+        self.last = stats;
+        self.since = Instant::now();
+    }
+
+    /// Draws the charts stacked in the top right corner, labelled with their latest values.
+    pub fn draw(&self, renderer: &mut dyn Renderer, locale: &Locale) {
+        let latest = |chart: &Chart| chart.samples.back().copied().unwrap_or_default().round();
+        let rtt = match self.last.rtt {
+            Some(_) => Text::new("netgraph.rtt").with("ms", latest(&self.rtt)),
+            None => Text::new("netgraph.rtt_unknown"),
+        };
+        let charts = [
+            (
+                &self.incoming,
+                Text::new("netgraph.incoming").with("rate", latest(&self.incoming)),
+            ),
+            (
+                &self.outgoing,
+                Text::new("netgraph.outgoing").with("rate", latest(&self.outgoing)),
+            ),
+            (
+                &self.loss,
+                Text::new("netgraph.loss").with("percent", latest(&self.loss)),
+            ),
+            (&self.rtt, rtt),
+            (
+                &self.buffered,
+                Text::new("netgraph.buffered").with("packets", latest(&self.buffered)),
+            ),
+        ];
+
+        let left = renderer.screen_size().x() - Chart::WIDTH - Self::MARGIN;
+        let height = Self::LABEL_HEIGHT + Chart::HEIGHT + Self::MARGIN;
+        for (i, (chart, label)) in charts.iter().enumerate() {
+            let top_left = Vec2::new(left, Self::MARGIN + i as f64 * height);
+            chart.draw(renderer, top_left, &locale.text(label));
+        }
+    }
+}
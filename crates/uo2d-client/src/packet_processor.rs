@@ -0,0 +1,550 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::Vec3;
+use uo2d_proto::packet::payloads::NotificationKind;
+use uo2d_proto::packet::quantize::dequantize;
+use uo2d_proto::time::Instant;
+use uo2d_proto::{cprintln, packet::*};
+use uuid::Uuid;
+
+use super::combat_text::CombatTextKind;
+use super::gamestate::Gamestate;
+use super::transport::Transport;
+
+/// Packet being handled, along with what handlers need to apply it.
+pub(crate) struct Context<'a> {
+    pub transport: &'a mut dyn Transport,
+    pub gamestate: &'a mut Gamestate,
+    /// Sender of the packet.
+    pub uuid: Uuid,
+}
+
+/// Applies the payload of a packet, returning a response for the server if there is one.
+pub(crate) type Handler = fn(&mut Context, Payload) -> Option<(Action, Payload)>;
+
+/// Handlers for the actions the server sends, registered by the modules implementing them.
+#[derive(Default)]
+pub(crate) struct Handlers {
+    handlers: HashMap<Action, Handler>,
+}
+
+impl Handlers {
+    /// Handlers for every action the client understands.
+    pub fn new() -> Self {
+        let mut handlers = Self::default();
+        register(&mut handlers);
+        super::emotes::register(&mut handlers);
+        super::trade::register(&mut handlers);
+        super::bank::register(&mut handlers);
+        handlers
+    }
+
+    /// Handles the action with the handler, replacing any registered before it.
+    pub fn register_handler(&mut self, action: Action, handler: Handler) {
+        if self.handlers.insert(action, handler).is_some() {
+            cprintln!("Replaced the handler for {:?}.", action);
+        }
+    }
+}
+
+/// Registers the handlers for the core actions.
+fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Ping, |_, payload| ping(payload));
+    handlers.register_handler(Action::Success, |ctx, payload| {
+        success(ctx.transport, ctx.gamestate, ctx.uuid, payload)
+    });
+    handlers.register_handler(Action::Error, |ctx, payload| error(ctx.gamestate, payload));
+    handlers.register_handler(Action::VersionMismatch, |ctx, payload| {
+        version_mismatch(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Shutdown, |ctx, _| shutdown(ctx.gamestate));
+    handlers.register_handler(Action::Message, |ctx, payload| {
+        message(ctx.gamestate, ctx.uuid, payload)
+    });
+    handlers.register_handler(Action::ClientJoin, |ctx, payload| {
+        client_join(ctx.gamestate, ctx.uuid, payload)
+    });
+    handlers.register_handler(Action::ClientLeave, |ctx, payload| {
+        client_leave(ctx.gamestate, ctx.uuid, payload)
+    });
+    handlers.register_handler(Action::Movement, |ctx, payload| {
+        movement(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Keyframe, |ctx, payload| {
+        movement(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::MovementDelta, |ctx, payload| {
+        movement_delta(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::EntityDelete, |ctx, payload| {
+        entity_remove(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Damage, |ctx, payload| {
+        health(ctx.gamestate, payload, CombatTextKind::Damage)
+    });
+    handlers.register_handler(Action::Heal, |ctx, payload| {
+        health(ctx.gamestate, payload, CombatTextKind::Heal)
+    });
+    handlers.register_handler(Action::Notification, |ctx, payload| {
+        notification(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Inventory, |ctx, payload| {
+        inventory(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Appearance, |ctx, payload| {
+        appearance(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::GroundItem, |ctx, payload| {
+        ground_item(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Chunk, |ctx, payload| chunk(ctx.gamestate, payload));
+    handlers.register_handler(Action::Leaderboard, |ctx, payload| {
+        leaderboard(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Hud, |ctx, payload| hud(ctx.gamestate, payload));
+    handlers.register_handler(Action::Match, |ctx, payload| {
+        match_phase(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::StatusEffect, |ctx, payload| {
+        status_effect(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Mailbox, |ctx, payload| {
+        mailbox(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Redirect, |ctx, payload| {
+        redirect(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Dialogue, |ctx, payload| {
+        dialogue(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Target, |ctx, payload| {
+        target(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::AreaWarning, |ctx, payload| {
+        area_warning(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Overlay, |ctx, payload| {
+        overlay(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Cast, |ctx, payload| cast(ctx.gamestate, payload));
+    handlers.register_handler(Action::MovementMode, |ctx, payload| {
+        movement_mode(ctx.gamestate, payload)
+    });
+    handlers.register_handler(Action::Fall, |ctx, payload| fall(ctx.gamestate, payload));
+    handlers.register_handler(Action::Attack, |ctx, payload| {
+        attack_error(ctx.gamestate, payload)
+    });
+}
+
+/// Processes all packet types.
+pub(crate) fn processor(
+    handlers: &Handlers,
+    client: &mut dyn Transport,
+    gamestate: &mut Gamestate,
+    packet: Packet,
+) -> Option<(Action, Payload)> {
+    // Newer servers may send actions this client does not know about.
+    let Some(action) = packet.action() else {
+        cprintln!("Ignored a packet with an unknown action.");
+        return None;
+    };
+
+    let handler = handlers.handlers.get(&action)?;
+    let mut context = Context {
+        transport: client,
+        gamestate,
+        uuid: packet.uuid(),
+    };
+    handler(&mut context, packet.payload())
+}
+
+fn ping(payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Uuid(data) => data,
+        _ => return None,
+    };
+
+    Some((Action::Ping, Payload::Uuid(payload)))
+}
+
+fn success(
+    client: &mut dyn Transport,
+    gamestate: &mut Gamestate,
+    uuid: Uuid,
+    payload: Payload,
+) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Spawn(data) => data,
+        _ => return None,
+    };
+
+    let movement = payload.movement;
+    client.set_uuid(uuid);
+    gamestate.clear_world();
+    gamestate.set_player(movement.entity);
+    gamestate.upsert_entity(movement.entity, movement.position, movement.size);
+    gamestate.set_appearance(movement.entity, payload.appearance, payload.team);
+    None
+}
+
+fn error(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let message = match payload {
+        Payload::Text(text) => gamestate.locale.text(&text),
+        _ => return None,
+    };
+
+    cprintln!("Error from server: {}", message);
+    gamestate.error = Some(message);
+    None
+}
+
+fn version_mismatch(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Version(data) => data,
+        _ => return None,
+    };
+
+    let hint = gamestate
+        .locale
+        .text(&payload.upgrade_hint(Version::current()));
+    cprintln!("Refused by server: {}", hint);
+    gamestate.error = Some(hint);
+    None
+}
+
+fn shutdown(gamestate: &mut Gamestate) -> Option<(Action, Payload)> {
+    gamestate.kill = true;
+    cprintln!("{}", gamestate.locale.get("server.shutdown"));
+    None
+}
+
+fn message(gamestate: &Gamestate, uuid: Uuid, payload: Payload) -> Option<(Action, Payload)> {
+    // Players chat in their own words, the server sends text to be translated.
+    match payload {
+        Payload::Message(data) => cprintln!("{}: {}", uuid, data.message),
+        Payload::Text(text) => cprintln!("{}", gamestate.locale.text(&text)),
+        _ => (),
+    }
+    None
+}
+
+fn client_join(
+    gamestate: &mut Gamestate,
+    uuid: Uuid,
+    payload: Payload,
+) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Spawn(data) => data,
+        _ => return None,
+    };
+
+    let movement = payload.movement;
+    cprintln!("{} has joined.", uuid);
+    gamestate
+        .toasts
+        .push(NotificationKind::Join, format!("{} has joined.", uuid));
+    gamestate.upsert_entity(movement.entity, movement.position, movement.size);
+    gamestate.set_appearance(movement.entity, payload.appearance, payload.team);
+    None
+}
+
+fn client_leave(
+    gamestate: &mut Gamestate,
+    uuid: Uuid,
+    payload: Payload,
+) -> Option<(Action, Payload)> {
+    cprintln!("{} has left.", uuid);
+    gamestate
+        .toasts
+        .push(NotificationKind::Leave, format!("{} has left.", uuid));
+    entity_remove(gamestate, payload)
+}
+
+fn movement(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    match payload {
+        Payload::Movement(data) => gamestate.upsert_entity(data.entity, data.position, data.size),
+        Payload::Keyframe(data) => {
+            let movement = data.movement;
+            gamestate.set_keyframe(
+                movement.entity,
+                data.keyframe,
+                movement.position,
+                movement.size,
+            )
+        }
+        _ => (),
+    };
+
+    None
+}
+
+fn movement_delta(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::MovementDelta(data) => data,
+        _ => return None,
+    };
+
+    gamestate.apply_delta(payload.entity, payload.keyframe, dequantize(payload.offset));
+    None
+}
+
+fn entity_remove(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Entity(data) => data,
+        _ => return None,
+    };
+
+    gamestate.remove_entity(&payload.entity);
+    gamestate.ground.remove(&payload.entity);
+    None
+}
+
+fn health(
+    gamestate: &mut Gamestate,
+    payload: Payload,
+    kind: CombatTextKind,
+) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Health(data) => data,
+        _ => return None,
+    };
+
+    // Place the text centered above the entity.
+    let position = match gamestate.get_mobile(&payload.entity) {
+        Some(mobile) => {
+            let bounds = mobile.bounding_box();
+            Vec3::new(bounds.center_2d().x(), bounds.y(), bounds.z())
+        }
+        None => return None,
+    };
+
+    gamestate
+        .combat_text
+        .spawn(payload.entity, kind, payload.amount, position);
+    None
+}
+
+fn status_effect(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::StatusEffect(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_effect(payload.entity, payload.effect, payload.active);
+    None
+}
+
+fn leaderboard(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Leaderboard(data) => data,
+        _ => return None,
+    };
+
+    gamestate.leaderboard = payload.entries;
+    None
+}
+
+fn mailbox(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Mailbox(data) => data,
+        _ => return None,
+    };
+
+    match payload.letters {
+        Some(letters) => gamestate.mailbox = letters,
+        None if payload.unread > gamestate.unread_mail => {
+            let message = gamestate.locale.get("mailbox.new").to_string();
+            gamestate
+                .toasts
+                .push(NotificationKind::Announcement, message)
+        }
+        None => (),
+    }
+
+    gamestate.unread_mail = payload.unread;
+    None
+}
+
+fn redirect(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Redirect(data) => data,
+        _ => return None,
+    };
+
+    gamestate.redirect = Some(payload);
+    None
+}
+
+fn dialogue(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    gamestate.dialogue = match payload {
+        Payload::Dialogue(data) => Some(data),
+        _ => None,
+    };
+    None
+}
+
+fn target(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Target(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_target(payload.entity, payload.target);
+    None
+}
+
+fn area_warning(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::AreaWarning(data) => data,
+        _ => return None,
+    };
+
+    gamestate.warn(payload);
+    None
+}
+
+fn overlay(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Overlay(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_overlay(payload);
+    None
+}
+
+fn cast(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Cast(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_cast(payload);
+    None
+}
+
+fn movement_mode(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::MovementMode(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_mode(payload.entity, payload.mode);
+    None
+}
+
+fn fall(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Fall(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_falling(payload);
+    None
+}
+
+fn attack_error(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let error = match payload {
+        Payload::AttackError(data) => data,
+        _ => return None,
+    };
+
+    let message = gamestate.locale.text(&error.message());
+    cprintln!("{}", message);
+    gamestate.toasts.push(NotificationKind::Warning, message);
+    None
+}
+
+fn hud(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Hud(data) => data,
+        _ => return None,
+    };
+
+    gamestate.hud = Some(payload);
+    None
+}
+
+fn match_phase(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Match(data) => data,
+        _ => return None,
+    };
+
+    gamestate.matches = Some((payload, Instant::now()));
+    None
+}
+
+fn notification(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Notification(data) => data,
+        _ => return None,
+    };
+
+    let message = gamestate.locale.text(&payload.message);
+    cprintln!("{}", message);
+    gamestate.toasts.push(payload.kind, message);
+    None
+}
+
+fn inventory(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Inventory(data) => data,
+        _ => return None,
+    };
+
+    let weight: u32 = payload
+        .stacks
+        .iter()
+        .filter_map(|stack| {
+            let item = gamestate.items.get(&stack.item)?;
+            Some(item.weight * stack.count as u32)
+        })
+        .sum();
+
+    cprintln!("Inventory ({} / {} weight):", weight, payload.capacity);
+    for (i, stack) in payload.stacks.iter().enumerate() {
+        if let Some(item) = gamestate.items.get(&stack.item) {
+            let name = gamestate.locale.text(&item.text());
+            cprintln!("  [{}] {} x{}", i + 1, name, stack.count);
+        }
+    }
+
+    gamestate.inventory = payload.stacks;
+    gamestate.capacity = payload.capacity;
+    gamestate.equipment = payload.equipment;
+    None
+}
+
+fn appearance(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Appearance(data) => data,
+        _ => return None,
+    };
+
+    gamestate.set_appearance(payload.entity, payload.appearance, payload.team);
+    gamestate.set_mounted(payload.entity, payload.mounted);
+    None
+}
+
+fn ground_item(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::GroundItem(data) => data,
+        _ => return None,
+    };
+
+    gamestate
+        .ground
+        .insert(payload.entity, (payload.stack, payload.bounds));
+    None
+}
+
+fn chunk(gamestate: &mut Gamestate, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Chunk(data) => data,
+        _ => return None,
+    };
+
+    gamestate.chunks.insert(payload.coord, payload.tiles);
+    None
+}
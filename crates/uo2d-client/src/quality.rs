@@ -0,0 +1,155 @@
+use uo2d_proto::time::Duration;
+
+/// How much is drawn, lowered to keep the frame rate up on slower machines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quality {
+    Low,
+    Medium,
+    High,
+}
+
+impl Quality {
+    /// Name shown to the player.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quality::Low => "low",
+            Quality::Medium => "medium",
+            Quality::High => "high",
+        }
+    }
+
+    /// Parses the name of a level.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" => Some(Quality::Low),
+            "medium" => Some(Quality::Medium),
+            "high" => Some(Quality::High),
+            _ => None,
+        }
+    }
+
+    fn lower(&self) -> Option<Self> {
+        match self {
+            Quality::Low => None,
+            Quality::Medium => Some(Quality::Low),
+            Quality::High => Some(Quality::Medium),
+        }
+    }
+
+    fn higher(&self) -> Option<Self> {
+        match self {
+            Quality::Low => Some(Quality::Medium),
+            Quality::Medium => Some(Quality::High),
+            Quality::High => None,
+        }
+    }
+
+    /// Shadows beneath every mobile, the first to go.
+    pub fn shadows(&self) -> bool {
+        *self == Quality::High
+    }
+
+    /// Damage and healing numbers rising above mobiles.
+    pub fn combat_text(&self) -> bool {
+        *self >= Quality::Medium
+    }
+
+    /// Camera easing towards the player, following rigidly without it.
+    pub fn smoothing(&self) -> bool {
+        *self >= Quality::Medium
+    }
+
+    /// Strips that circular areas are built from, fewer looking blockier.
+    pub fn area_strips(&self) -> usize {
+        match self {
+            Quality::Low => 4,
+            Quality::Medium => 8,
+            Quality::High => 12,
+        }
+    }
+}
+
+/// Frame rate to keep and whether the level is chosen automatically.
+#[derive(Debug, Clone, Copy)]
+pub struct QualitySettings {
+    /// Frames per second that the level is lowered to keep up with.
+    pub target_fps: f64,
+    /// Level to always draw at, disabling the automatic tuning.
+    pub fixed: Option<Quality>,
+}
+
+impl Default for QualitySettings {
+    fn default() -> Self {
+        Self {
+            target_fps: 30.,
+            fixed: None,
+        }
+    }
+}
+
+/// Watches how long frames take, lowering the quality when they run over the target and
+/// raising it again once there is plenty of time to spare.
+pub struct QualityTuner {
+    settings: QualitySettings,
+    level: Quality,
+    /// Moving average of the seconds spent on each frame.
+    average: f64,
+    /// Frames since the level last changed.
+    frames: u32,
+}
+
+impl QualityTuner {
+    /// Weight of the newest frame within the average.
+    const SAMPLE_WEIGHT: f64 = 0.1;
+    /// Frames to wait after a change before judging the new level.
+    const SETTLE_FRAMES: u32 = 90;
+    /// Frames the spare time has to last before raising the level, longer to avoid flickering.
+    const RAISE_FRAMES: u32 = 300;
+    /// Portion of the frame budget that has to be left unused before raising the level.
+    const RAISE_BELOW: f64 = 0.6;
+
+    pub fn new(settings: QualitySettings) -> Self {
+        let settings = QualitySettings {
+            target_fps: settings.target_fps.max(1.),
+            ..settings
+        };
+
+        Self {
+            settings,
+            level: settings.fixed.unwrap_or(Quality::High),
+            average: 0.,
+            frames: 0,
+        }
+    }
+
+    /// Level to draw the current frame at.
+    pub fn level(&self) -> Quality {
+        self.level
+    }
+
+    /// Records the time spent on a frame, obtaining the new level if it changed.
+    pub fn record(&mut self, frame: Duration) -> Option<Quality> {
+        if self.settings.fixed.is_some() {
+            return None;
+        }
+
+        self.average += (frame.as_secs_f64() - self.average) * Self::SAMPLE_WEIGHT;
+        self.frames += 1;
+        if self.frames < Self::SETTLE_FRAMES {
+            return None;
+        }
+
+        let budget = 1. / self.settings.target_fps;
+        let level = if self.average > budget {
+            self.level.lower()
+        } else if self.average < budget * Self::RAISE_BELOW && self.frames >= Self::RAISE_FRAMES {
+            self.level.higher()
+        } else {
+            None
+        }?;
+
+        self.level = level;
+        self.frames = 0;
+        Some(level)
+    }
+}
@@ -0,0 +1,209 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uo2d_proto::packet::{Action, Packet, Payload, PACKET_VERSION};
+use uo2d_proto::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::input::{Input, InputFrame};
+use super::transport::{ConnectionState, NetStats, SendStatus, Transport};
+
+/// Opens every recording, followed by the protocol it was recorded with.
+const MAGIC: &[u8; 7] = b"UO2DREC";
+
+/// Everything the client took in during a single tick.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Tick {
+    /// Milliseconds since the recording started.
+    elapsed: u64,
+    packets: Vec<Vec<u8>>,
+    /// Only recorded once playing, not while joining.
+    input: Option<InputFrame>,
+}
+
+/// Writes the packets received and the input of each tick to a file.
+pub struct Recorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    started: Instant,
+    /// Tick being recorded, written once the next one begins.
+    tick: Option<Tick>,
+}
+
+impl Recorder {
+    pub const DIRECTORY: &'static str = "recordings";
+
+    /// Starts a new recording within the directory, named by when it started.
+    pub fn create(directory: &Path) -> io::Result<Self> {
+        fs::create_dir_all(directory)?;
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3f");
+        let path = directory.join(format!("session-{}.rec", stamp));
+
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[PACKET_VERSION])?;
+        Ok(Self {
+            path,
+            writer,
+            started: Instant::now(),
+            tick: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Starts the next tick with the packets received for it.
+    pub fn packets(&mut self, packets: &[Packet]) -> io::Result<()> {
+        self.write()?;
+        self.tick = Some(Tick {
+            elapsed: self.started.elapsed().as_millis() as u64,
+            packets: packets.iter().map(Packet::to_bytes).collect(),
+            input: None,
+        });
+        Ok(())
+    }
+
+    /// Records the input polled during the current tick.
+    pub fn input(&mut self, input: &Input) {
+        if let Some(tick) = self.tick.as_mut() {
+            tick.input = Some(InputFrame::capture(input));
+        }
+    }
+
+    /// Writes the finished tick, if there is one.
+    fn write(&mut self) -> io::Result<()> {
+        match self.tick.take() {
+            Some(tick) => {
+                bincode::serialize_into(&mut self.writer, &tick).map_err(io::Error::other)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Recorder {
+    /// Keeps the last tick, even if the client stopped because of an error.
+    fn drop(&mut self) {
+        let _ = self.write();
+        let _ = self.writer.flush();
+    }
+}
+
+/// Plays back the packets of a recording as if they came from the server, one tick at a time.
+/// Packets sent while playing back go nowhere.
+pub struct Playback {
+    uuid: Uuid,
+    /// Packets of each tick, along with when they were received.
+    ticks: RefCell<VecDeque<(Duration, Vec<Packet>)>>,
+    started: Instant,
+    /// Set once every tick has been played.
+    finished: Cell<bool>,
+}
+
+/// Replaces the input with the one recorded for each tick.
+pub struct Replay {
+    frames: VecDeque<InputFrame>,
+}
+
+/// Reads a recording, split into the packets and input played back.
+pub fn open(path: &Path) -> io::Result<(Playback, Replay)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    if &header[..7] != MAGIC {
+        return Err(io::Error::new(ErrorKind::InvalidData, "not a recording"));
+    } else if header[7] != PACKET_VERSION {
+        let why = format!("recorded with protocol {}", header[7]);
+        return Err(io::Error::new(ErrorKind::InvalidData, why));
+    }
+
+    let mut ticks = VecDeque::new();
+    let mut frames = VecDeque::new();
+    loop {
+        let tick: Tick = match bincode::deserialize_from(&mut reader) {
+            Ok(tick) => tick,
+            // A recording cut short still plays up until the tick that was cut.
+            Err(why) => match *why {
+                bincode::ErrorKind::Io(why) if why.kind() == ErrorKind::UnexpectedEof => break,
+                why => return Err(io::Error::new(ErrorKind::InvalidData, why)),
+            },
+        };
+
+        let packets = tick.packets.iter().map(|bytes| Packet::from_bytes(bytes));
+        ticks.push_back((Duration::from_millis(tick.elapsed), packets.collect()));
+        frames.extend(tick.input);
+    }
+
+    let playback = Playback {
+        uuid: Uuid::nil(),
+        ticks: RefCell::new(ticks),
+        started: Instant::now(),
+        finished: Cell::new(false),
+    };
+    Ok((playback, Replay { frames }))
+}
+
+impl Transport for Playback {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
+    fn send(&self, _action: Action, _payload: Payload) -> SendStatus {
+        SendStatus::Queued
+    }
+
+    fn flush(&self) {}
+
+    /// Closed once every tick has been played, such as a recording that never joined.
+    fn state(&self) -> ConnectionState {
+        if self.finished.get() {
+            ConnectionState::Closed
+        } else {
+            ConnectionState::Connected
+        }
+    }
+
+    /// Packets received during the next tick, never played ahead of when they were received.
+    fn get_packets(&self) -> Vec<Packet> {
+        let Some((elapsed, packets)) = self.ticks.borrow_mut().pop_front() else {
+            self.finished.set(true);
+            return Vec::new();
+        };
+
+        let due = self.started + elapsed;
+        thread::sleep(due.saturating_duration_since(Instant::now()));
+        packets
+    }
+
+    fn stats(&self) -> NetStats {
+        NetStats::default()
+    }
+}
+
+impl Replay {
+    /// Checks if the input of every tick has been played.
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Applies the input of the next tick, only the escape key is still taken from the player.
+    pub fn apply(&mut self, input: &mut Input) {
+        let quit = input.keyboard.esc_pressed;
+        if let Some(frame) = self.frames.pop_front() {
+            frame.apply(input);
+        }
+        input.keyboard.esc_pressed |= quit;
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+/// Where a sprite was placed within an atlas, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Placement of many sprites within a single texture, packed into rows.
+#[derive(Debug, Default)]
+pub struct AtlasLayout {
+    pub width: u32,
+    pub height: u32,
+    regions: HashMap<String, AtlasRegion>,
+}
+
+impl AtlasLayout {
+    /// Widest the atlas is allowed to grow, most graphics cards support at least this.
+    pub const MAX_WIDTH: u32 = 2048;
+    /// Space left between sprites, keeping scaled sprites from bleeding into their neighbours.
+    const PADDING: u32 = 1;
+
+    /// Packs the sprites, tallest first, into rows no wider than the maximum.
+    /// Fails if a sprite is wider than the atlas itself.
+    pub fn pack(sprites: &[(String, u32, u32)]) -> Result<Self, String> {
+        let mut order: Vec<&(String, u32, u32)> = sprites.iter().collect();
+        order.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+        let mut layout = Self::default();
+        let (mut x, mut y, mut row) = (0, 0, 0);
+        for (name, width, height) in order {
+            if *width > Self::MAX_WIDTH {
+                return Err(format!("sprite {} is wider than {}", name, Self::MAX_WIDTH));
+            }
+
+            // Starts a new row once the sprite no longer fits in the current one.
+            if x + width > Self::MAX_WIDTH {
+                x = 0;
+                y += row + Self::PADDING;
+                row = 0;
+            }
+
+            let region = AtlasRegion {
+                x,
+                y,
+                width: *width,
+                height: *height,
+            };
+            layout.regions.insert(name.clone(), region);
+            layout.width = layout.width.max(x + width);
+            layout.height = layout.height.max(y + height);
+
+            x += width + Self::PADDING;
+            row = row.max(*height);
+        }
+
+        Ok(layout)
+    }
+
+    /// Region the sprite was placed in.
+    pub fn get(&self, name: &str) -> Option<AtlasRegion> {
+        self.regions.get(name).copied()
+    }
+}
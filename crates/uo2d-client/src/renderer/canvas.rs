@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::{Vec2, Vec3};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+
+use super::{to_rgb, Renderer};
+
+/// Renderer that draws to a canvas within a web page.
+pub struct CanvasRenderer {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    /// Images load in the background, drawn once they have finished.
+    sprites: HashMap<String, HtmlImageElement>,
+    /// Height of a line of text, browsers only measure the width.
+    line_height: f64,
+}
+
+impl CanvasRenderer {
+    /// Creates a new renderer, text is drawn with the font family provided.
+    pub fn new(canvas: HtmlCanvasElement, font: &str, font_size: u16) -> Result<Self, JsValue> {
+        let context: CanvasRenderingContext2d = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("The canvas cannot be drawn in 2D"))?
+            .dyn_into()?;
+        context.set_font(&format!("{}px {}, sans-serif", font_size, font));
+        context.set_text_baseline("top");
+
+        Ok(Self {
+            canvas,
+            context,
+            sprites: HashMap::new(),
+            line_height: (font_size as f64 * 1.2).ceil(),
+        })
+    }
+
+    /// Starts loading an image to be drawn as a sprite by name.
+    pub fn load_sprite(&mut self, name: &str, url: &str) -> Result<(), JsValue> {
+        let image = HtmlImageElement::new()?;
+        image.set_src(url);
+        self.sprites.insert(name.to_string(), image);
+        Ok(())
+    }
+
+    /// Sprite that has finished loading.
+    fn sprite(&self, sprite: &str) -> Option<&HtmlImageElement> {
+        self.sprites
+            .get(sprite)
+            .filter(|image| image.complete() && image.natural_width() > 0)
+    }
+
+    /// Converts a color into a CSS color.
+    fn css(color: Vec3, alpha: u8) -> String {
+        let [r, g, b] = to_rgb(color);
+        format!("rgba({}, {}, {}, {})", r, g, b, alpha as f64 / 255.)
+    }
+}
+
+impl Renderer for CanvasRenderer {
+    fn clear(&mut self) {
+        let size = self.screen_size();
+        self.context.set_fill_style_str("black");
+        self.context.fill_rect(0., 0., size.x(), size.y());
+    }
+
+    fn draw_rect(&mut self, top_left: Vec2, size: Vec2, color: Vec3) {
+        self.draw_rect_alpha(top_left, size, color, 255);
+    }
+
+    fn draw_rect_alpha(&mut self, top_left: Vec2, size: Vec2, color: Vec3, alpha: u8) {
+        self.context.set_fill_style_str(&Self::css(color, alpha));
+        self.context.fill_rect(
+            top_left.x().round(),
+            top_left.y().round(),
+            size.x().round().max(0.),
+            size.y().round().max(0.),
+        );
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, color: Vec3) {
+        self.context.set_stroke_style_str(&Self::css(color, 255));
+        self.context.begin_path();
+        self.context.move_to(from.x(), from.y());
+        self.context.line_to(to.x(), to.y());
+        self.context.stroke();
+    }
+
+    fn draw_sprite(&mut self, sprite: &str, top_left: Vec2, size: Vec2) {
+        let image = match self.sprite(sprite) {
+            Some(image) => image,
+            None => return,
+        };
+
+        let (x, y) = (top_left.x().round(), top_left.y().round());
+        let (width, height) = (size.x().round(), size.y().round());
+        if let Err(why) = self
+            .context
+            .draw_image_with_html_image_element_and_dw_and_dh(image, x, y, width, height)
+        {
+            web_sys::console::error_1(&why);
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, top_left: Vec2, color: Vec3, alpha: u8) {
+        self.context.set_fill_style_str(&Self::css(color, alpha));
+        if let Err(why) = self
+            .context
+            .fill_text(text, top_left.x().round(), top_left.y().round())
+        {
+            web_sys::console::error_1(&why);
+        }
+    }
+
+    fn text_size(&self, text: &str) -> Option<Vec2> {
+        let metrics = self.context.measure_text(text).ok()?;
+        Some(Vec2::new(metrics.width().ceil(), self.line_height))
+    }
+
+    fn sprite_size(&self, sprite: &str) -> Option<Vec2> {
+        let image = self.sprite(sprite)?;
+        Some(Vec2::new(
+            image.natural_width() as f64,
+            image.natural_height() as f64,
+        ))
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        Vec2::new(self.canvas.width() as f64, self.canvas.height() as f64)
+    }
+
+    /// Browsers display the canvas once the frame has been drawn.
+    fn present(&mut self) {}
+}
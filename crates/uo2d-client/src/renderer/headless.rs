@@ -0,0 +1,43 @@
+use uo2d_proto::components::{Vec2, Vec3};
+
+use super::Renderer;
+
+/// Renderer that draws nothing, used when running without a window.
+pub struct HeadlessRenderer {
+    screen: Vec2,
+}
+
+impl HeadlessRenderer {
+    /// Creates a new renderer pretending to draw to a screen of the size provided.
+    pub fn new(screen: Vec2) -> Self {
+        Self { screen }
+    }
+}
+
+impl Renderer for HeadlessRenderer {
+    fn clear(&mut self) {}
+
+    fn draw_rect(&mut self, _top_left: Vec2, _size: Vec2, _color: Vec3) {}
+
+    fn draw_rect_alpha(&mut self, _top_left: Vec2, _size: Vec2, _color: Vec3, _alpha: u8) {}
+
+    fn draw_line(&mut self, _from: Vec2, _to: Vec2, _color: Vec3) {}
+
+    fn draw_sprite(&mut self, _sprite: &str, _top_left: Vec2, _size: Vec2) {}
+
+    fn draw_text(&mut self, _text: &str, _top_left: Vec2, _color: Vec3, _alpha: u8) {}
+
+    fn text_size(&self, _text: &str) -> Option<Vec2> {
+        None
+    }
+
+    fn sprite_size(&self, _sprite: &str) -> Option<Vec2> {
+        None
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        self.screen
+    }
+
+    fn present(&mut self) {}
+}
@@ -0,0 +1,56 @@
+use uo2d_proto::components::{Vec2, Vec3};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod atlas;
+#[cfg(target_arch = "wasm32")]
+mod canvas;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
+#[cfg(not(target_arch = "wasm32"))]
+mod sdl;
+
+#[cfg(target_arch = "wasm32")]
+pub use canvas::CanvasRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use headless::HeadlessRenderer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use sdl::SdlRenderer;
+
+/// Draws the game to a screen, allowing the client to use different graphics backends.
+/// All positions are in screen coordinates and colors are RGB.
+pub trait Renderer {
+    /// Clears the screen to black.
+    fn clear(&mut self);
+
+    /// Draws a filled rectangle.
+    fn draw_rect(&mut self, top_left: Vec2, size: Vec2, color: Vec3);
+
+    /// Draws a filled rectangle blended over what is beneath it.
+    fn draw_rect_alpha(&mut self, top_left: Vec2, size: Vec2, color: Vec3, alpha: u8);
+
+    /// Draws a line between two points.
+    fn draw_line(&mut self, from: Vec2, to: Vec2, color: Vec3);
+
+    /// Draws a previously loaded sprite, stretched to the size provided.
+    fn draw_sprite(&mut self, sprite: &str, top_left: Vec2, size: Vec2);
+
+    /// Draws text, does nothing if text is not supported.
+    fn draw_text(&mut self, text: &str, top_left: Vec2, color: Vec3, alpha: u8);
+
+    /// Size the text would occupy on the screen, if text is supported.
+    fn text_size(&self, text: &str) -> Option<Vec2>;
+
+    /// Size of a previously loaded sprite.
+    fn sprite_size(&self, sprite: &str) -> Option<Vec2>;
+
+    /// Size of the screen being drawn to.
+    fn screen_size(&self) -> Vec2;
+
+    /// Displays everything drawn since the last clear.
+    fn present(&mut self);
+}
+
+/// Converts a color into its RGB components.
+fn to_rgb(color: Vec3) -> [u8; 3] {
+    color.as_vec().map(|c| c.round().clamp(0., 255.) as u8)
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use sdl2::image::{LoadSurface, LoadTexture};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, Texture, TextureCreator, TextureQuery, WindowCanvas};
+use sdl2::surface::Surface;
+use sdl2::ttf::Font;
+use sdl2::video::WindowContext;
+use uo2d_proto::components::{Vec2, Vec3};
+
+use super::atlas::{AtlasLayout, AtlasRegion};
+use super::{to_rgb, Renderer};
+
+/// Renderer that draws to an SDL2 window.
+pub struct SdlRenderer<'a> {
+    canvas: WindowCanvas,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    font: Option<Font<'a, 'static>>,
+    sprites: HashMap<String, Texture<'a>>,
+    /// Sprites packed into one texture, drawn without switching textures.
+    atlas: Option<(Texture<'a>, AtlasLayout)>,
+}
+
+impl<'a> SdlRenderer<'a> {
+    /// Creates a new renderer, text is only drawn if a font is provided.
+    pub fn new(
+        canvas: WindowCanvas,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        font: Option<Font<'a, 'static>>,
+    ) -> Self {
+        Self {
+            canvas,
+            texture_creator,
+            font,
+            sprites: HashMap::new(),
+            atlas: None,
+        }
+    }
+
+    /// Loads an image to be drawn as a sprite by name.
+    pub fn load_sprite(&mut self, name: &str, path: &Path) -> Result<(), String> {
+        let texture = self.texture_creator.load_texture(path)?;
+        self.sprites.insert(name.to_string(), texture);
+        Ok(())
+    }
+
+    /// Packs every PNG within a directory into a single texture, each drawn as a sprite by its file name.
+    /// Returns the amount of sprites packed, none if the directory does not exist.
+    pub fn load_atlas(&mut self, directory: &Path) -> Result<usize, String> {
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(0),
+        };
+
+        let mut surfaces = Vec::new();
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.extension().is_none_or(|ext| ext != "png") {
+                continue;
+            }
+
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let mut surface = Surface::from_file(&path)?;
+            surface.set_blend_mode(BlendMode::None)?;
+            surfaces.push((name, surface));
+        }
+
+        if surfaces.is_empty() {
+            return Ok(0);
+        }
+
+        let sizes: Vec<(String, u32, u32)> = surfaces
+            .iter()
+            .map(|(name, surface)| (name.clone(), surface.width(), surface.height()))
+            .collect();
+        let layout = AtlasLayout::pack(&sizes)?;
+
+        // Copy every sprite into its place, then upload them together.
+        let mut sheet = Surface::new(layout.width, layout.height, PixelFormatEnum::RGBA32)?;
+        for (name, surface) in surfaces.iter() {
+            if let Some(region) = layout.get(name) {
+                surface.blit(None, &mut sheet, Self::region_rect(region))?;
+            }
+        }
+
+        let mut texture = self
+            .texture_creator
+            .create_texture_from_surface(&sheet)
+            .map_err(|why| why.to_string())?;
+        texture.set_blend_mode(BlendMode::Blend);
+
+        self.atlas = Some((texture, layout));
+        Ok(surfaces.len())
+    }
+
+    /// Renames the window.
+    pub fn set_title(&mut self, title: &str) {
+        if let Err(why) = self.canvas.window_mut().set_title(title) {
+            eprintln!("Unable to set the window title: {}", why);
+        }
+    }
+
+    /// Converts a region of the atlas into a rectangle.
+    fn region_rect(region: AtlasRegion) -> Rect {
+        Rect::new(
+            region.x as i32,
+            region.y as i32,
+            region.width,
+            region.height,
+        )
+    }
+
+    /// Converts a position and size into a rectangle.
+    fn rect(top_left: Vec2, size: Vec2) -> Rect {
+        Rect::new(
+            top_left.x().round() as i32,
+            top_left.y().round() as i32,
+            size.x().round().max(0.) as u32,
+            size.y().round().max(0.) as u32,
+        )
+    }
+}
+
+impl Renderer for SdlRenderer<'_> {
+    fn clear(&mut self) {
+        self.canvas.set_draw_color(Color::BLACK);
+        self.canvas.clear();
+    }
+
+    fn draw_rect(&mut self, top_left: Vec2, size: Vec2, color: Vec3) {
+        let [r, g, b] = to_rgb(color);
+        self.canvas.set_draw_color(Color::RGB(r, g, b));
+        if let Err(why) = self.canvas.fill_rect(Self::rect(top_left, size)) {
+            eprintln!("Unable to render rect: {}", why);
+        }
+    }
+
+    fn draw_rect_alpha(&mut self, top_left: Vec2, size: Vec2, color: Vec3, alpha: u8) {
+        let [r, g, b] = to_rgb(color);
+        self.canvas.set_blend_mode(BlendMode::Blend);
+        self.canvas.set_draw_color(Color::RGBA(r, g, b, alpha));
+        if let Err(why) = self.canvas.fill_rect(Self::rect(top_left, size)) {
+            eprintln!("Unable to render rect: {}", why);
+        }
+        self.canvas.set_blend_mode(BlendMode::None);
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, color: Vec3) {
+        let [r, g, b] = to_rgb(color);
+        self.canvas.set_draw_color(Color::RGB(r, g, b));
+        let from = Point::new(from.x() as i32, from.y() as i32);
+        let to = Point::new(to.x() as i32, to.y() as i32);
+        if let Err(why) = self.canvas.draw_line(from, to) {
+            eprintln!("Unable to render line: {}", why);
+        }
+    }
+
+    fn draw_sprite(&mut self, sprite: &str, top_left: Vec2, size: Vec2) {
+        // Sprites within the atlas are copied from their region of it.
+        let packed = self.atlas.as_ref().and_then(|(texture, layout)| {
+            let region = layout.get(sprite)?;
+            Some((texture, Some(Self::region_rect(region))))
+        });
+
+        let (texture, src) = match packed.or_else(|| Some((self.sprites.get(sprite)?, None))) {
+            Some(found) => found,
+            None => return,
+        };
+
+        if let Err(why) = self
+            .canvas
+            .copy(texture, src, Some(Self::rect(top_left, size)))
+        {
+            eprintln!("Unable to render sprite {}: {}", sprite, why);
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, top_left: Vec2, color: Vec3, alpha: u8) {
+        let font = match &self.font {
+            Some(font) => font,
+            None => return,
+        };
+
+        // Render the text into a texture.
+        let [r, g, b] = to_rgb(color);
+        let surface = match font.render(text).blended(Color::RGB(r, g, b)) {
+            Ok(surface) => surface,
+            Err(why) => {
+                eprintln!("Unable to render text: {}", why);
+                return;
+            }
+        };
+
+        let mut texture = match self.texture_creator.create_texture_from_surface(&surface) {
+            Ok(texture) => texture,
+            Err(why) => {
+                eprintln!("Unable to create text texture: {}", why);
+                return;
+            }
+        };
+        texture.set_alpha_mod(alpha);
+
+        let size = Vec2::new(surface.width() as f64, surface.height() as f64);
+        if let Err(why) = self
+            .canvas
+            .copy(&texture, None, Some(Self::rect(top_left, size)))
+        {
+            eprintln!("Unable to render text: {}", why);
+        }
+    }
+
+    fn text_size(&self, text: &str) -> Option<Vec2> {
+        match self.font.as_ref()?.size_of(text) {
+            Ok((width, height)) => Some(Vec2::new(width as f64, height as f64)),
+            Err(why) => {
+                eprintln!("Unable to size text: {}", why);
+                None
+            }
+        }
+    }
+
+    fn sprite_size(&self, sprite: &str) -> Option<Vec2> {
+        if let Some(region) = self
+            .atlas
+            .as_ref()
+            .and_then(|(_, layout)| layout.get(sprite))
+        {
+            return Some(Vec2::new(region.width as f64, region.height as f64));
+        }
+
+        let TextureQuery { width, height, .. } = self.sprites.get(sprite)?.query();
+        Some(Vec2::new(width as f64, height as f64))
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        let (width, height) = self.canvas.window().size();
+        Vec2::new(width as f64, height as f64)
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+    }
+}
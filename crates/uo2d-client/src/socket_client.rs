@@ -0,0 +1,291 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as SyncMutex};
+use std::thread;
+
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Mutex};
+use uo2d_proto::cprintln;
+use uo2d_proto::packet::payloads::UuidPayload;
+use uo2d_proto::packet::{with_handshake, Action, Packet, Payload, Reliability, MAX_DATAGRAM};
+use uo2d_proto::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::transport::{is_critical, ConnectionState, NetStats, SendStatus, Statistics, Transport};
+use crate::cache::PacketCacheSync;
+
+/// Used to communicate to the remove server over UDP.
+pub struct SocketClient {
+    uuid: Uuid,
+    sender: mpsc::Sender<Packet>,
+    packet_cache: PacketCacheSync,
+    /// Critical packets waiting for room in the outbound queue.
+    retry: SyncMutex<VecDeque<Packet>>,
+    /// When a packet was last received from the server.
+    received: Arc<SyncMutex<Option<Instant>>>,
+    statistics: Arc<SyncMutex<Statistics>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl SocketClient {
+    /// Most packets held before movement updates start being dropped.
+    const MAX_QUEUED: usize = 1024;
+    /// Packets waiting to be sent before new ones are refused.
+    const MAX_OUTBOUND: usize = 32;
+    /// Time without hearing from the server before the connection is considered lost.
+    const SILENCE_LIMIT: Duration = Duration::from_secs(15);
+
+    /// Create a new client instance.
+    pub fn new(address: &str) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<Packet>(Self::MAX_OUTBOUND);
+        let packet_cache = PacketCacheSync::new(Self::MAX_QUEUED);
+
+        let cache_clone = packet_cache.clone();
+        let addr_clone = address.to_string();
+
+        // Set once the server replies, until then every packet carries the handshake.
+        let received = Arc::new(SyncMutex::new(None));
+        let send_received = Arc::clone(&received);
+        let recv_received = Arc::clone(&received);
+        let closed = Arc::new(AtomicBool::new(false));
+        let thread_closed = Arc::clone(&closed);
+        let statistics = Arc::new(SyncMutex::new(Statistics::default()));
+        let send_statistics = Arc::clone(&statistics);
+        let recv_statistics = Arc::clone(&statistics);
+        let probe_statistics = Arc::clone(&statistics);
+        let probe_sender = sender.clone();
+        let ack_sender = sender.clone();
+        let resend_sender = sender.clone();
+        // Numbers the critical packets until the server acknowledges them.
+        let reliability = Arc::new(SyncMutex::new(Reliability::new()));
+        let send_reliability = Arc::clone(&reliability);
+        let recv_reliability = Arc::clone(&reliability);
+        let resend_reliability = Arc::clone(&reliability);
+
+        // Launch the asynchronous task.
+        thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let local_addr = "0.0.0.0:0";
+                let socket = match UdpSocket::bind(local_addr).await {
+                    Ok(socket) => socket,
+                    Err(why) => {
+                        cprintln!("ERROR BINDING: {}", why);
+                        thread_closed.store(true, Ordering::Relaxed);
+                        return;
+                    }
+                };
+                if let Err(why) = socket.connect(addr_clone).await {
+                    cprintln!("ERROR CONNECTING: {}", why);
+                    thread_closed.store(true, Ordering::Relaxed);
+                    return;
+                }
+                let socket = Arc::new(Mutex::new(socket));
+
+                // Handle sending packets to the server.
+                let send_socket = Arc::clone(&socket);
+
+                let send_task = tokio::spawn(async move {
+                    while let Some(packet) = receiver.recv().await {
+                        // Convert Packet to bytes and send.
+                        let packet = send_reliability
+                            .lock()
+                            .unwrap()
+                            .prepare(packet, Instant::now());
+                        let mut packet_bytes = packet.to_bytes();
+                        if send_received.lock().unwrap().is_none() {
+                            packet_bytes = with_handshake(&packet_bytes);
+                        }
+                        match send_socket.lock().await.send(&packet_bytes).await {
+                            Ok(n) => send_statistics.lock().unwrap().stats.bytes_sent += n as u64,
+                            Err(why) => cprintln!("ERROR SENDING: {}", why),
+                        }
+                    }
+                });
+
+                // Handle receiving packets from the server.
+                let recv_socket = Arc::clone(&socket);
+                let recv_closed = Arc::clone(&thread_closed);
+                let recv_task = tokio::spawn(async move {
+                    let mut buf = [0u8; MAX_DATAGRAM];
+                    loop {
+                        // The client was dropped, such as after being redirected.
+                        if recv_closed.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        // Temporarily store the result of trying to receive data
+                        let recv_result = {
+                            let socket = recv_socket.lock().await; // Lock is acquired and immediately dropped after the block
+                            socket.try_recv(&mut buf)
+                        };
+
+                        if let Ok(n) = recv_result {
+                            if n == 0 {
+                                break;
+                            }
+
+                            *recv_received.lock().unwrap() = Some(Instant::now());
+                            let mut statistics = recv_statistics.lock().unwrap();
+                            statistics.stats.bytes_received += n as u64;
+
+                            // Packets written within the same tick arrive bundled together.
+                            for packet in Packet::from_bytes(&buf[..n]).unbundle() {
+                                let received = recv_reliability.lock().unwrap().receive(packet);
+                                if let Some(ack) = received.ack {
+                                    let _ = ack_sender.try_send(ack);
+                                }
+                                let Some(packet) = received.packet else {
+                                    continue;
+                                };
+
+                                // Probes are answered here, never reaching the gamestate.
+                                if packet.action() == Some(Action::Echo) {
+                                    if let Payload::Uuid(probe) = packet.payload() {
+                                        statistics.answer(&probe.uuid);
+                                    }
+                                    continue;
+                                }
+                                cache_clone.add(packet);
+                            }
+                        }
+                    }
+                });
+
+                // Probe the server every so often, measuring the round trip and loss.
+                let probe_closed = Arc::clone(&thread_closed);
+                let probe_task = tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(Statistics::PROBE_INTERVAL);
+                    loop {
+                        interval.tick().await;
+                        if probe_closed.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        let id = probe_statistics.lock().unwrap().probe();
+                        let probe = Packet::new(
+                            Action::Echo,
+                            Uuid::nil(),
+                            Payload::Uuid(UuidPayload::new(id)),
+                        );
+                        if let Err(TrySendError::Closed(_)) = probe_sender.try_send(probe) {
+                            break;
+                        }
+                    }
+                });
+
+                // Send the critical packets again until the server acknowledges them.
+                let resend_closed = Arc::clone(&thread_closed);
+                let resend_task = tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(Reliability::RESEND_AFTER);
+                    loop {
+                        interval.tick().await;
+                        if resend_closed.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        let resend = resend_reliability.lock().unwrap().resend(Instant::now());
+                        for packet in resend {
+                            if let Err(TrySendError::Closed(_)) = resend_sender.try_send(packet) {
+                                return;
+                            }
+                        }
+                    }
+                });
+
+                // Wait for all tasks to complete
+                let _ = tokio::try_join!(send_task, recv_task, probe_task, resend_task);
+                thread_closed.store(true, Ordering::Relaxed);
+            });
+        });
+
+        Self {
+            uuid: Uuid::nil(),
+            sender,
+            packet_cache,
+            retry: SyncMutex::new(VecDeque::new()),
+            received,
+            statistics,
+            closed,
+        }
+    }
+}
+
+impl Drop for SocketClient {
+    /// Stops receiving once the client is no longer used.
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Transport for SocketClient {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
+    /// Send a packet to the server asynchronously.
+    fn send(&self, action: Action, payload: Payload) -> SendStatus {
+        let packet = Packet::new(action, self.uuid, payload);
+        let critical = is_critical(&packet);
+
+        // Critical packets wait behind the ones already held back to keep their order.
+        let mut retry = self.retry.lock().unwrap();
+        if critical && !retry.is_empty() {
+            retry.push_back(packet);
+            return SendStatus::Queued;
+        }
+
+        match self.sender.try_send(packet) {
+            Ok(()) => SendStatus::Queued,
+            Err(TrySendError::Full(packet)) if critical => {
+                retry.push_back(packet);
+                SendStatus::Queued
+            }
+            Err(TrySendError::Full(_)) => SendStatus::Dropped,
+            Err(TrySendError::Closed(_)) => SendStatus::Disconnected,
+        }
+    }
+
+    fn flush(&self) {
+        let mut retry = self.retry.lock().unwrap();
+        while let Some(packet) = retry.pop_front() {
+            match self.sender.try_send(packet) {
+                Ok(()) => continue,
+                Err(TrySendError::Full(packet)) => {
+                    retry.push_front(packet);
+                    break;
+                }
+                Err(TrySendError::Closed(_)) => {
+                    retry.clear();
+                    break;
+                }
+            }
+        }
+    }
+
+    fn state(&self) -> ConnectionState {
+        if self.closed.load(Ordering::Relaxed) || self.sender.is_closed() {
+            return ConnectionState::Closed;
+        }
+
+        match *self.received.lock().unwrap() {
+            None => ConnectionState::Connecting,
+            Some(time) if time.elapsed() > Self::SILENCE_LIMIT => ConnectionState::Lost,
+            Some(_) => ConnectionState::Connected,
+        }
+    }
+
+    /// Retrieve received packets from the cache.
+    fn get_packets(&self) -> Vec<Packet> {
+        self.packet_cache.get_all()
+    }
+
+    fn stats(&self) -> NetStats {
+        self.statistics.lock().unwrap().stats
+    }
+}
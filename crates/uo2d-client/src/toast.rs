@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use uo2d_proto::components::Vec3;
+use uo2d_proto::packet::payloads::NotificationKind;
+
+/// A transient message shown in the notification area.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub kind: NotificationKind,
+    pub message: String,
+    age: u32,
+}
+
+impl Toast {
+    /// Length of time in client ticks the toast is displayed for.
+    const LIFESPAN: u32 = 150;
+    /// Final ticks of the lifespan spent fading out.
+    const FADE: u32 = 30;
+
+    fn new(kind: NotificationKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            age: 0,
+        }
+    }
+
+    /// Color of the toast.
+    pub fn color(&self) -> Vec3 {
+        match self.kind {
+            NotificationKind::Announcement => Vec3::new(255., 215., 0.),
+            NotificationKind::Join => Vec3::new(128., 255., 128.),
+            NotificationKind::Leave => Vec3::new(192., 192., 192.),
+            NotificationKind::Death => Vec3::new(255., 64., 64.),
+            NotificationKind::Warning => Vec3::new(255., 160., 64.),
+        }
+    }
+
+    /// Transparency of the toast, fading out at the end of its life.
+    pub fn alpha(&self) -> u8 {
+        let remaining = Self::LIFESPAN.saturating_sub(self.age).min(Self::FADE);
+        ((remaining as f64 / Self::FADE as f64) * 255.).round() as u8
+    }
+
+    /// Checks if the toast has finished displaying.
+    fn is_expired(&self) -> bool {
+        self.age >= Self::LIFESPAN
+    }
+}
+
+/// Queues toasts, only showing a limited amount at once.
+pub struct ToastQueue {
+    visible: VecDeque<Toast>,
+    pending: VecDeque<Toast>,
+    max_visible: usize,
+}
+
+impl ToastQueue {
+    /// Creates a new queue that displays at most `max_visible` toasts at once.
+    pub fn new(max_visible: usize) -> Self {
+        Self {
+            visible: VecDeque::new(),
+            pending: VecDeque::new(),
+            max_visible,
+        }
+    }
+
+    /// Queues a new toast to be displayed.
+    pub fn push(&mut self, kind: NotificationKind, message: impl ToString) {
+        self.pending
+            .push_back(Toast::new(kind, message.to_string()));
+    }
+
+    /// Ages the visible toasts, replacing expired ones with pending toasts.
+    pub fn update(&mut self) {
+        for toast in self.visible.iter_mut() {
+            toast.age += 1;
+        }
+        self.visible.retain(|toast| !toast.is_expired());
+
+        while self.visible.len() < self.max_visible {
+            match self.pending.pop_front() {
+                Some(toast) => self.visible.push_back(toast),
+                None => break,
+            }
+        }
+    }
+
+    /// All toasts currently being displayed, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.visible.iter()
+    }
+}
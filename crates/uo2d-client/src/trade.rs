@@ -0,0 +1,244 @@
+use uo2d_proto::components::{Vec2, Vec3};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::{Locale, Text};
+use uo2d_proto::packet::payloads::{
+    NotificationKind, TradeOffer, TradePayload, TradeRequest, TradeState,
+};
+use uo2d_proto::packet::{Action, Payload};
+
+use super::input::within;
+use super::packet_processor::{Context, Handlers};
+use super::renderer::Renderer;
+
+/// Window showing an invitation to trade, or both offers side by side once trading.
+#[derive(Default)]
+pub struct TradeWindow {
+    trade: Option<TradePayload>,
+}
+
+impl TradeWindow {
+    const WIDTH: f64 = 500.;
+    const ROW_HEIGHT: f64 = 20.;
+    const PADDING: f64 = 10.;
+    const BUTTON_WIDTH: f64 = 110.;
+    /// Rows of items shown for each offer, the most the server accepts.
+    const OFFER_ROWS: usize = 8;
+    const BACKGROUND: [u8; 3] = [32, 32, 32];
+    const BUTTON_COLOR: [u8; 3] = [64, 64, 64];
+    const LOCKED_COLOR: [u8; 3] = [255, 200, 64];
+    const CONFIRMED_COLOR: [u8; 3] = [64, 220, 64];
+
+    /// Checks if offers are being made, rather than waiting on an invitation.
+    pub fn is_open(&self) -> bool {
+        self.trade
+            .as_ref()
+            .is_some_and(|trade| trade.state == TradeState::Open)
+    }
+
+    /// Shows the trade, closing the window once the trade has ended.
+    /// Returns the notice to show the player if it ended.
+    fn update(&mut self, trade: TradePayload) -> Option<(NotificationKind, Text)> {
+        if !trade.is_closed() {
+            self.trade = Some(trade);
+            return None;
+        }
+
+        self.trade = None;
+        Some(match trade.state {
+            TradeState::Completed => (
+                NotificationKind::Announcement,
+                Text::new("trade.completed").with("name", trade.partner),
+            ),
+            _ => (
+                NotificationKind::Warning,
+                Text::new("trade.cancelled").with("name", trade.partner),
+            ),
+        })
+    }
+
+    /// Rows of text above the buttons.
+    fn rows(trade: &TradePayload) -> usize {
+        match trade.state {
+            // Title, headers, the offers, then the hint.
+            TradeState::Open => Self::OFFER_ROWS + 3,
+            _ => 1,
+        }
+    }
+
+    /// Buttons along the bottom of the window, and the request each makes.
+    fn buttons(trade: &TradePayload) -> Vec<(&'static str, TradeRequest)> {
+        match trade.state {
+            TradeState::Invited => vec![
+                ("trade.accept", TradeRequest::Accept),
+                ("trade.decline", TradeRequest::Cancel),
+            ],
+            TradeState::Open if trade.mine.locked => vec![
+                ("trade.unlock", TradeRequest::Lock(false)),
+                ("trade.confirm", TradeRequest::Confirm),
+                ("trade.cancel", TradeRequest::Cancel),
+            ],
+            TradeState::Open => vec![
+                ("trade.lock", TradeRequest::Lock(true)),
+                ("trade.cancel", TradeRequest::Cancel),
+            ],
+            _ => vec![("trade.cancel", TradeRequest::Cancel)],
+        }
+    }
+
+    /// Top-left corner and size of the window, in the middle of the screen.
+    fn frame(trade: &TradePayload, screen: Vec2) -> (Vec2, Vec2) {
+        let rows = Self::rows(trade) as f64 + 1.;
+        let size = Vec2::new(Self::WIDTH, rows * Self::ROW_HEIGHT + Self::PADDING * 3.);
+        let top_left = Vec2::new((screen.x() - size.x()) / 2., (screen.y() - size.y()) / 2.);
+        (top_left, size)
+    }
+
+    /// Top-left corner of a row of text.
+    fn row(top_left: Vec2, column: usize, row: usize) -> Vec2 {
+        Vec2::new(
+            top_left.x() + Self::PADDING + column as f64 * Self::WIDTH / 2.,
+            top_left.y() + Self::PADDING + row as f64 * Self::ROW_HEIGHT,
+        )
+    }
+
+    /// Top-left corner of a button.
+    fn button(trade: &TradePayload, top_left: Vec2, index: usize) -> Vec2 {
+        Vec2::new(
+            top_left.x() + Self::PADDING + index as f64 * (Self::BUTTON_WIDTH + Self::PADDING),
+            top_left.y() + Self::PADDING * 2. + Self::rows(trade) as f64 * Self::ROW_HEIGHT,
+        )
+    }
+
+    /// Checks if a point on the screen is within the window.
+    pub fn contains(&self, screen: Vec2, point: Vec2) -> bool {
+        self.trade.as_ref().is_some_and(|trade| {
+            let (top_left, size) = Self::frame(trade, screen);
+            within(point, top_left, size)
+        })
+    }
+
+    /// Request made by clicking a point on the screen, pressing a button or withdrawing an item.
+    pub fn click(&self, screen: Vec2, point: Vec2) -> Option<TradeRequest> {
+        let trade = self.trade.as_ref()?;
+        let (top_left, _) = Self::frame(trade, screen);
+
+        let size = Vec2::new(Self::BUTTON_WIDTH, Self::ROW_HEIGHT);
+        for (i, (_, request)) in Self::buttons(trade).into_iter().enumerate() {
+            if within(point, Self::button(trade, top_left, i), size) {
+                return Some(request);
+            }
+        }
+
+        // Items in their own offer are taken back out when clicked.
+        let size = Vec2::new(Self::WIDTH / 2. - Self::PADDING, Self::ROW_HEIGHT);
+        (0..trade.mine.items.len().min(Self::OFFER_ROWS))
+            .filter(|_| trade.state == TradeState::Open && !trade.mine.locked)
+            .find(|i| within(point, Self::row(top_left, 0, i + 2), size))
+            .map(|i| TradeRequest::Withdraw(i as u16))
+    }
+
+    /// Draws the window in the middle of the screen.
+    pub fn draw(&self, renderer: &mut dyn Renderer, locale: &Locale, items: &ItemManager) {
+        let trade = match &self.trade {
+            Some(trade) => trade,
+            None => return,
+        };
+
+        let (top_left, size) = Self::frame(trade, renderer.screen_size());
+        renderer.draw_rect(top_left, size, color(Self::BACKGROUND));
+
+        let white = Vec3::new(255., 255., 255.);
+        let title = match trade.state {
+            TradeState::Waiting => "trade.waiting",
+            TradeState::Invited => "trade.invited",
+            _ => "trade.title",
+        };
+        let title = locale.text(&Text::new(title).with("name", &trade.partner));
+        renderer.draw_text(&title, Self::row(top_left, 0, 0), white, 255);
+
+        if trade.state == TradeState::Open {
+            let offers = [("trade.mine", &trade.mine), ("trade.theirs", &trade.theirs)];
+            for (column, (header, offer)) in offers.into_iter().enumerate() {
+                Self::draw_offer(renderer, locale, items, top_left, column, header, offer);
+            }
+
+            let hint = locale.get("trade.hint");
+            let position = Self::row(top_left, 0, Self::OFFER_ROWS + 2);
+            renderer.draw_text(hint, position, Vec3::new(160., 160., 160.), 255);
+        }
+
+        let size = Vec2::new(Self::BUTTON_WIDTH, Self::ROW_HEIGHT);
+        for (i, (label, _)) in Self::buttons(trade).into_iter().enumerate() {
+            let position = Self::button(trade, top_left, i);
+            renderer.draw_rect(position, size, color(Self::BUTTON_COLOR));
+            let label = locale.get(label);
+            let width = renderer.text_size(label).map_or(0., |size| size.x());
+            let text = Vec2::new(position.x() + (size.x() - width) / 2., position.y());
+            renderer.draw_text(label, text, white, 255);
+        }
+    }
+
+    /// Draws the items offered by one side, with whether they have locked or confirmed.
+    fn draw_offer(
+        renderer: &mut dyn Renderer,
+        locale: &Locale,
+        items: &ItemManager,
+        top_left: Vec2,
+        column: usize,
+        header: &str,
+        offer: &TradeOffer,
+    ) {
+        let (status, rgb) = if offer.confirmed {
+            (Some("trade.confirmed"), Self::CONFIRMED_COLOR)
+        } else if offer.locked {
+            (Some("trade.locked_label"), Self::LOCKED_COLOR)
+        } else {
+            (None, [255, 255, 255])
+        };
+        let header = match status {
+            Some(status) => format!("{} - {}", locale.get(header), locale.get(status)),
+            None => locale.get(header).to_string(),
+        };
+        renderer.draw_text(&header, Self::row(top_left, column, 1), color(rgb), 255);
+
+        let lines = offer.items.iter().map(|stack| {
+            let name = items.get(&stack.item).map_or_else(
+                || format!("#{}", stack.item),
+                |item| locale.text(&item.text()),
+            );
+            format!("{} x {}", stack.count, name)
+        });
+        let empty = offer
+            .items
+            .is_empty()
+            .then(|| locale.get("trade.empty").to_string());
+
+        let white = Vec3::new(255., 255., 255.);
+        for (i, line) in lines.chain(empty).take(Self::OFFER_ROWS).enumerate() {
+            renderer.draw_text(&line, Self::row(top_left, column, i + 2), white, 255);
+        }
+    }
+}
+
+/// Color to draw with from its red, green, and blue.
+fn color([r, g, b]: [u8; 3]) -> Vec3 {
+    Vec3::new(r as f64, g as f64, b as f64)
+}
+
+/// Registers the handler showing the trades the server sends.
+pub(crate) fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Trade, show);
+}
+
+fn show(ctx: &mut Context, payload: Payload) -> Option<(Action, Payload)> {
+    let payload = match payload {
+        Payload::Trade(data) => data,
+        _ => return None,
+    };
+
+    if let Some((kind, text)) = ctx.gamestate.trade.update(payload) {
+        let message = ctx.gamestate.locale.text(&text);
+        ctx.gamestate.toasts.push(kind, message);
+    }
+    None
+}
@@ -0,0 +1,127 @@
+use std::collections::{HashMap, VecDeque};
+
+use uo2d_proto::packet::{Action, Packet, Payload};
+use uo2d_proto::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Outcome of handing a packet to the transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendStatus {
+    /// Queued to be sent, critical packets may be waiting for room to retry.
+    Queued,
+    /// Discarded because the outbound queue was full.
+    Dropped,
+    /// Discarded because the connection has closed.
+    Disconnected,
+}
+
+/// State of the connection to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Waiting for the first reply from the server.
+    Connecting,
+    Connected,
+    /// Nothing has been heard from the server in a while, recovers once it replies.
+    Lost,
+    /// The connection has closed and can no longer be used.
+    Closed,
+}
+
+/// Traffic over the connection since it was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetStats {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Share of the recent probes that were never answered, from 0 to 1.
+    pub loss: f64,
+    /// Round trip of the latest probe to be answered.
+    pub rtt: Option<Duration>,
+}
+
+/// Traffic counted by a transport, along with the probes measuring the round trip.
+#[derive(Default)]
+pub(crate) struct Statistics {
+    pub stats: NetStats,
+    /// Probes waiting to be answered, by when they were sent.
+    probes: HashMap<Uuid, Instant>,
+    /// Whether each of the recent probes was answered, oldest first.
+    answered: VecDeque<bool>,
+}
+
+impl Statistics {
+    /// Probes the loss is estimated from.
+    const RECENT_PROBES: usize = 20;
+    /// Time between probes sent to measure the round trip.
+    pub const PROBE_INTERVAL: Duration = Duration::from_secs(1);
+    /// Time a probe is waited on before it is counted as lost.
+    const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn record(&mut self, answered: bool) {
+        if self.answered.len() == Self::RECENT_PROBES {
+            self.answered.pop_front();
+        }
+        self.answered.push_back(answered);
+
+        let lost = self.answered.iter().filter(|answered| !**answered).count();
+        self.stats.loss = lost as f64 / self.answered.len() as f64;
+    }
+
+    /// Starts a new probe, giving up on those left unanswered for too long.
+    pub fn probe(&mut self) -> Uuid {
+        let expired: Vec<Uuid> = self
+            .probes
+            .iter()
+            .filter(|(_, sent)| sent.elapsed() > Self::PROBE_TIMEOUT)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in expired {
+            self.probes.remove(&id);
+            self.record(false);
+        }
+
+        let id = Uuid::new_v4();
+        self.probes.insert(id, Instant::now());
+        id
+    }
+
+    /// Measures the round trip of a probe the server sent back, late ones are ignored.
+    pub fn answer(&mut self, id: &Uuid) {
+        if let Some(sent) = self.probes.remove(id) {
+            self.stats.rtt = Some(sent.elapsed());
+            self.record(true);
+        }
+    }
+}
+
+/// Checks if a packet must reach the server, instead of being superseded by a later one.
+pub(crate) fn is_critical(packet: &Packet) -> bool {
+    !matches!(
+        packet.action(),
+        Some(Action::Ping | Action::Movement | Action::Projectile)
+    )
+}
+
+/// Connection to the remote server, allowing the client to run over different networks.
+/// Native builds use UDP, other targets provide their own implementation.
+pub trait Transport {
+    /// UUID assigned by the server, nil until the client has joined.
+    fn uuid(&self) -> Uuid;
+
+    /// Sets the UUID assigned by the server.
+    fn set_uuid(&mut self, uuid: Uuid);
+
+    /// Sends a packet to the server without blocking.
+    fn send(&self, action: Action, payload: Payload) -> SendStatus;
+
+    /// Retries the critical packets that were held back, called once per frame.
+    fn flush(&self);
+
+    /// Current state of the connection.
+    fn state(&self) -> ConnectionState;
+
+    /// Retrieves all packets received since the last call.
+    fn get_packets(&self) -> Vec<Packet>;
+
+    /// Traffic over the connection, sampled for the netgraph.
+    fn stats(&self) -> NetStats;
+}
@@ -0,0 +1,100 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+#[cfg(not(target_arch = "wasm32"))]
+use serde::Deserialize;
+#[cfg(not(target_arch = "wasm32"))]
+use uo2d_proto::cprintln;
+#[cfg(not(target_arch = "wasm32"))]
+use uo2d_proto::http;
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::Version;
+#[cfg(not(target_arch = "wasm32"))]
+use uo2d_proto::time::Duration;
+
+/// Latest release of the client, published at the manifest address.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateManifest {
+    pub version: String,
+    /// Where the release can be downloaded from.
+    pub download: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl UpdateManifest {
+    /// The release it describes, if it is newer than the one given.
+    pub fn newer_than(&self, current: Version) -> Result<Option<Update>, String> {
+        let version = Version::parse(&self.version)
+            .ok_or_else(|| format!("invalid version '{}'", self.version))?;
+        Ok((version > current).then(|| Update {
+            version,
+            download: self.download.clone(),
+        }))
+    }
+}
+
+/// Release newer than the one running.
+#[derive(Debug, Clone)]
+pub struct Update {
+    pub version: Version,
+    pub download: String,
+}
+
+impl Update {
+    /// Tells the player where to download it from.
+    pub fn text(&self) -> Text {
+        Text::new("update.available")
+            .with("version", self.version)
+            .with("download", &self.download)
+    }
+}
+
+/// Checks the manifest in the background, the client starts without waiting on it.
+#[derive(Clone, Default)]
+pub struct UpdateCheck {
+    found: Arc<Mutex<Option<Update>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl UpdateCheck {
+    /// Time allowed to fetch the manifest before giving up.
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    const MAX_MANIFEST_SIZE: u64 = 16 * 1024;
+
+    /// Starts fetching the manifest, failures are only printed.
+    pub fn start(url: &str) -> Self {
+        let check = Self::default();
+        let found = Arc::clone(&check.found);
+        let url = url.to_string();
+        thread::spawn(move || match Self::fetch(&url) {
+            Ok(Some(update)) => {
+                cprintln!(
+                    "Version {} is available from {}.",
+                    update.version,
+                    update.download
+                );
+                *found.lock().unwrap() = Some(update);
+            }
+            Ok(None) => (),
+            Err(why) => cprintln!("Unable to check {} for updates: {}", url, why),
+        });
+        check
+    }
+
+    fn fetch(url: &str) -> Result<Option<Update>, Box<dyn Error>> {
+        let body = http::get(url, Self::TIMEOUT, Self::MAX_MANIFEST_SIZE)?;
+        let manifest: UpdateManifest = serde_yaml::from_str(&body)?;
+        Ok(manifest.newer_than(Version::current())?)
+    }
+}
+
+impl UpdateCheck {
+    /// Newer release, once the check has found one.
+    pub fn available(&self) -> Option<Update> {
+        self.found.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,178 @@
+//! Runs the client within a web page, drawing to a canvas and connecting over a WebSocket.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use uo2d_proto::cprintln;
+use uo2d_proto::locale::Locale;
+use uo2d_proto::time::Instant;
+use uo2d_proto::timer::TimerManager;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::HtmlCanvasElement;
+
+use super::error_screen::{ErrorChoice, ErrorScreen};
+use super::input::WebInput;
+use super::renderer::{CanvasRenderer, Renderer};
+use super::{CameraSettings, Client, Join, Play, QualitySettings, BACKGROUND, FONT_SIZE};
+
+/// Font family the page loads the font of the assets as.
+const FONT_FAMILY: &str = "uo2d";
+
+/// Where the client is between ticks, browsers cannot wait within one.
+enum Stage {
+    /// Waiting for the server to accept the player.
+    Joining {
+        client: Box<Client>,
+        started: Instant,
+    },
+    Playing {
+        client: Box<Client>,
+        play: Box<Play>,
+    },
+    /// Explaining why the game stopped until the player retries or quits.
+    Stopped(ErrorScreen),
+}
+
+/// Client within the page, ticked by the browser.
+struct WebClient {
+    join: Join,
+    renderer: CanvasRenderer,
+    input: WebInput,
+    /// Browsers never ask the client to stop, the page is simply closed.
+    interrupted: Arc<AtomicBool>,
+}
+
+impl WebClient {
+    /// Starts joining the server.
+    fn open(&self) -> Stage {
+        let camera = CameraSettings::default();
+        let quality = QualitySettings::default();
+        match Client::open(&self.join, camera, quality, self.interrupted.clone()) {
+            Ok(client) => Stage::Joining {
+                client: Box::new(client),
+                started: Instant::now(),
+            },
+            Err(reason) => self.stopped(reason),
+        }
+    }
+
+    fn stopped(&self, reason: String) -> Stage {
+        cprintln!("{}", reason);
+        let locale = Locale::new(&self.join.language);
+        Stage::Stopped(ErrorScreen::new(reason, &locale))
+    }
+
+    /// Advances the stage by a tick, None once the player has quit.
+    fn tick(&mut self, stage: Stage) -> Option<Stage> {
+        Some(match stage {
+            Stage::Joining {
+                mut client,
+                started,
+            } => {
+                // Nothing is done with the input until the player has joined.
+                self.input.take();
+                match client.joined(&self.join, started) {
+                    Ok(false) => Stage::Joining { client, started },
+                    Ok(true) => {
+                        let play = Box::new(client.play(&self.renderer));
+                        Stage::Playing { client, play }
+                    }
+                    Err(reason) => self.stopped(reason),
+                }
+            }
+            Stage::Playing {
+                mut client,
+                mut play,
+            } => {
+                let result = client.frame(&mut play, &mut self.renderer, &mut self.input);
+                if let Ok(true) = result {
+                    return Some(Stage::Playing { client, play });
+                }
+
+                client.leave();
+                // Retries keep the language chosen while playing.
+                self.join.language = client.gamestate.locale.language().to_string();
+                match result.map(|_| client.reconnect.take()) {
+                    Ok(None) => return None,
+                    Ok(Some(address)) => {
+                        cprintln!("Connecting to {}.", address);
+                        self.join.address = address;
+                        self.open()
+                    }
+                    Err(reason) => self.stopped(reason),
+                }
+            }
+            Stage::Stopped(screen) => {
+                let size = self.renderer.screen_size();
+                let (_, events) = self.input.take();
+                match events.iter().find_map(|event| screen.choose(size, event)) {
+                    Some(ErrorChoice::Quit) => return None,
+                    Some(ErrorChoice::Retry) => self.open(),
+                    None => {
+                        screen.draw(&mut self.renderer);
+                        Stage::Stopped(screen)
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Starts the client within the canvas of the page, joining the server at the address as a guest.
+/// The address is a WebSocket URL, or the host and port of an unencrypted one.
+/// Assets such as the background are loaded from the URL provided.
+#[wasm_bindgen]
+pub fn start(canvas: &str, address: &str, language: &str, assets: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("There is no window"))?;
+    let canvas: HtmlCanvasElement = window
+        .document()
+        .and_then(|document| document.get_element_by_id(canvas))
+        .ok_or_else(|| JsValue::from_str(&format!("There is no canvas '{}'", canvas)))?
+        .dyn_into()?;
+
+    let mut renderer = CanvasRenderer::new(canvas.clone(), FONT_FAMILY, FONT_SIZE)?;
+    renderer.load_sprite(BACKGROUND, &format!("{}/background.png", assets))?;
+    let mut client = WebClient {
+        join: Join {
+            address: address.to_string(),
+            credentials: None,
+            server_password: None,
+            language: language.to_string(),
+            record: false,
+            playback: None,
+            update: None,
+        },
+        renderer,
+        input: WebInput::new(&canvas)?,
+        interrupted: Arc::new(AtomicBool::new(false)),
+    };
+    let mut stage = Some(client.open());
+
+    // Ticks until the player quits, then stops the interval and lets go of the client.
+    let interval = Rc::new(Cell::new(None));
+    let tick_interval = Rc::clone(&interval);
+    let tick_window = window.clone();
+    let tick = Closure::<dyn FnMut()>::new(move || {
+        stage = stage.take().and_then(|stage| client.tick(stage));
+        if stage.is_none() {
+            if let Some(id) = tick_interval.take() {
+                tick_window.clear_interval_with_handle(id);
+            }
+            client.renderer.clear();
+        }
+    });
+
+    let tick_time = TimerManager::new().client_tick_time().as_millis() as i32;
+    let id = window.set_interval_with_callback_and_timeout_and_arguments_0(
+        tick.as_ref().unchecked_ref(),
+        tick_time,
+    )?;
+    interval.set(Some(id));
+    // Lives for as long as the page, it is only called while the interval runs.
+    tick.forget();
+    Ok(())
+}
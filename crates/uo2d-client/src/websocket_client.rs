@@ -0,0 +1,263 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use js_sys::{ArrayBuffer, Uint8Array};
+use uo2d_proto::cprintln;
+use uo2d_proto::packet::payloads::UuidPayload;
+use uo2d_proto::packet::{with_handshake, Action, Packet, Payload, Reliability};
+use uo2d_proto::time::{Duration, Instant};
+use uuid::Uuid;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{BinaryType, Event, MessageEvent, WebSocket};
+
+use super::transport::{is_critical, ConnectionState, NetStats, SendStatus, Statistics, Transport};
+use crate::cache::PacketCacheSync;
+
+/// Connection shared with the callbacks of the socket.
+struct Shared {
+    socket: WebSocket,
+    /// When a message was last received from the server.
+    received: Option<Instant>,
+    /// Sent before the socket opened, in order.
+    pending: Vec<Vec<u8>>,
+    statistics: Statistics,
+    /// Acknowledges the critical packets the server sends.
+    reliability: Reliability,
+}
+
+impl Shared {
+    /// Sends a packet, carrying the handshake until the server replies.
+    /// Returns false once the socket has closed.
+    fn transmit(&mut self, packet: &Packet) -> bool {
+        let mut bytes = packet.to_bytes();
+        if self.received.is_none() {
+            bytes = with_handshake(&bytes);
+        }
+
+        match self.socket.ready_state() {
+            WebSocket::CONNECTING => {
+                self.pending.push(bytes);
+                true
+            }
+            WebSocket::OPEN => self.send(&bytes),
+            _ => false,
+        }
+    }
+
+    fn send(&mut self, bytes: &[u8]) -> bool {
+        match self.socket.send_with_u8_array(bytes) {
+            Ok(()) => {
+                self.statistics.stats.bytes_sent += bytes.len() as u64;
+                true
+            }
+            Err(why) => {
+                cprintln!("ERROR SENDING: {:?}", why);
+                false
+            }
+        }
+    }
+
+    /// Sends what was held back while the socket was opening.
+    fn opened(&mut self) {
+        for bytes in std::mem::take(&mut self.pending) {
+            self.send(&bytes);
+        }
+    }
+
+    /// Unbundles a message from the server, keeping the packets meant for the gamestate.
+    fn message(&mut self, bytes: &[u8], cache: &PacketCacheSync) {
+        self.received = Some(Instant::now());
+        self.statistics.stats.bytes_received += bytes.len() as u64;
+
+        for packet in Packet::from_bytes(bytes).unbundle() {
+            let received = self.reliability.receive(packet);
+            if let Some(ack) = received.ack {
+                self.transmit(&ack);
+            }
+            let Some(packet) = received.packet else {
+                continue;
+            };
+
+            // Probes are answered here, never reaching the gamestate.
+            if packet.action() == Some(Action::Echo) {
+                if let Payload::Uuid(probe) = packet.payload() {
+                    self.statistics.answer(&probe.uuid);
+                }
+                continue;
+            }
+            cache.add(packet);
+        }
+    }
+}
+
+/// Used to communicate to the remote server over a WebSocket, the only network browsers allow.
+/// The server relays each socket to the game as if it were a UDP client.
+/// Nothing is numbered for the server to acknowledge, the socket already delivers in order.
+pub struct WebSocketClient {
+    uuid: Uuid,
+    /// None if the address could not be connected to.
+    shared: Option<Rc<RefCell<Shared>>>,
+    packet_cache: PacketCacheSync,
+    /// When the last probe was sent.
+    probed: Cell<Option<Instant>>,
+    /// Kept alive for as long as the socket may call them.
+    _onopen: Option<Closure<dyn FnMut(Event)>>,
+    _onmessage: Option<Closure<dyn FnMut(MessageEvent)>>,
+}
+
+impl WebSocketClient {
+    /// Most packets held before movement updates start being dropped.
+    const MAX_QUEUED: usize = 1024;
+    /// Time without hearing from the server before the connection is considered lost.
+    const SILENCE_LIMIT: Duration = Duration::from_secs(15);
+    /// Bytes waiting to be sent before packets that are superseded by later ones are dropped.
+    const MAX_BUFFERED: u32 = 64 * 1024;
+
+    /// Create a new client instance, addresses without a scheme are connected to unencrypted.
+    pub fn new(address: &str) -> Self {
+        let mut client = Self {
+            uuid: Uuid::nil(),
+            shared: None,
+            packet_cache: PacketCacheSync::new(Self::MAX_QUEUED),
+            probed: Cell::new(None),
+            _onopen: None,
+            _onmessage: None,
+        };
+
+        let url = match address.contains("://") {
+            true => address.to_string(),
+            false => format!("ws://{}", address),
+        };
+        let socket = match WebSocket::new(&url) {
+            Ok(socket) => socket,
+            Err(why) => {
+                cprintln!("ERROR CONNECTING: {:?}", why);
+                return client;
+            }
+        };
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let shared = Rc::new(RefCell::new(Shared {
+            socket: socket.clone(),
+            received: None,
+            pending: Vec::new(),
+            statistics: Statistics::default(),
+            reliability: Reliability::new(),
+        }));
+
+        let open_shared = Rc::clone(&shared);
+        let onopen = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            open_shared.borrow_mut().opened();
+        });
+        socket.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+
+        let message_shared = Rc::clone(&shared);
+        let cache = client.packet_cache.clone();
+        let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Ok(buffer) = event.data().dyn_into::<ArrayBuffer>() {
+                let bytes = Uint8Array::new(&buffer).to_vec();
+                message_shared.borrow_mut().message(&bytes, &cache);
+            }
+        });
+        socket.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+        client.shared = Some(shared);
+        client._onopen = Some(onopen);
+        client._onmessage = Some(onmessage);
+        client
+    }
+}
+
+impl Drop for WebSocketClient {
+    /// Closes the socket, its callbacks are dropped along with the client.
+    fn drop(&mut self) {
+        if let Some(shared) = &self.shared {
+            let shared = shared.borrow();
+            shared.socket.set_onopen(None);
+            shared.socket.set_onmessage(None);
+            let _ = shared.socket.close();
+        }
+    }
+}
+
+impl Transport for WebSocketClient {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
+    /// Sends a packet to the server, held back until the socket opens.
+    fn send(&self, action: Action, payload: Payload) -> SendStatus {
+        let packet = Packet::new(action, self.uuid, payload);
+        let Some(shared) = self.shared.as_ref() else {
+            return SendStatus::Disconnected;
+        };
+
+        let mut shared = shared.borrow_mut();
+        if !is_critical(&packet) && shared.socket.buffered_amount() > Self::MAX_BUFFERED {
+            SendStatus::Dropped
+        } else if shared.transmit(&packet) {
+            SendStatus::Queued
+        } else {
+            SendStatus::Disconnected
+        }
+    }
+
+    /// Probes the server every so often, measuring the round trip and loss.
+    fn flush(&self) {
+        let Some(shared) = self.shared.as_ref() else {
+            return;
+        };
+        let mut shared = shared.borrow_mut();
+        let due = self
+            .probed
+            .get()
+            .is_none_or(|sent| sent.elapsed() >= Statistics::PROBE_INTERVAL);
+        if shared.socket.ready_state() != WebSocket::OPEN || !due {
+            return;
+        }
+
+        self.probed.set(Some(Instant::now()));
+        let id = shared.statistics.probe();
+        let probe = Packet::new(
+            Action::Echo,
+            Uuid::nil(),
+            Payload::Uuid(UuidPayload::new(id)),
+        );
+        shared.transmit(&probe);
+    }
+
+    fn state(&self) -> ConnectionState {
+        let Some(shared) = self.shared.as_ref() else {
+            return ConnectionState::Closed;
+        };
+        let shared = shared.borrow();
+        if matches!(
+            shared.socket.ready_state(),
+            WebSocket::CLOSING | WebSocket::CLOSED
+        ) {
+            return ConnectionState::Closed;
+        }
+
+        match shared.received {
+            None => ConnectionState::Connecting,
+            Some(time) if time.elapsed() > Self::SILENCE_LIMIT => ConnectionState::Lost,
+            Some(_) => ConnectionState::Connected,
+        }
+    }
+
+    /// Retrieve received packets from the cache.
+    fn get_packets(&self) -> Vec<Packet> {
+        self.packet_cache.get_all()
+    }
+
+    fn stats(&self) -> NetStats {
+        self.shared.as_ref().map_or(NetStats::default(), |shared| {
+            shared.borrow().statistics.stats
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use uo2d_proto::components::{Health, Position, Vec2, Vec3, Velocity};
+use uo2d_proto::ecs::World;
+
+/// Entity counts the worlds are built with.
+const SIZES: [usize; 2] = [1_000, 10_000];
+
+/// World where every entity has a position, half move, and a quarter have health.
+fn world(count: usize, grouped: bool) -> World {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    world.register_component::<Health>();
+    if grouped {
+        world.group::<Position, Velocity>();
+    }
+
+    for i in 0..count {
+        let position = Position::new(Vec3::new(i as f64, i as f64, 1.), Vec2::new(16., 16.));
+        let mut builder = world.spawn().with(position);
+        if i % 2 == 0 {
+            builder = builder.with(Velocity(Vec2::new(1., 0.)));
+        }
+        if i % 4 == 0 {
+            builder = builder.with(Health::new(100));
+        }
+        builder.build();
+    }
+
+    world
+}
+
+fn queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world");
+    group.sample_size(10);
+
+    for count in SIZES {
+        let plain = world(count, false);
+        group.bench_with_input(BenchmarkId::new("query2", count), &plain, |b, world| {
+            b.iter(|| black_box(world.query2::<Position, Velocity>().len()))
+        });
+        group.bench_with_input(BenchmarkId::new("query3", count), &plain, |b, world| {
+            b.iter(|| black_box(world.query3::<Position, Velocity, Health>().len()))
+        });
+
+        let grouped = world(count, true);
+        group.bench_with_input(
+            BenchmarkId::new("query2_grouped", count),
+            &grouped,
+            |b, world| b.iter(|| black_box(world.query2::<Position, Velocity>().len())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("query3_grouped", count),
+            &grouped,
+            |b, world| b.iter(|| black_box(world.query3::<Position, Velocity, Health>().len())),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, queries);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use uo2d_proto::components::{Vec2, Vec3};
+use uo2d_proto::ecs::Entity;
+use uo2d_proto::packet::payloads::{MessagePayload, MovementPayload};
+use uo2d_proto::packet::{Action, Packet, Payload};
+use uuid::Uuid;
+
+/// Movement is the most frequently sent packet, messages carry the largest payloads.
+fn packets() -> [(&'static str, Packet); 2] {
+    let movement = MovementPayload::new(
+        Entity::new(42),
+        Vec2::new(32., 32.),
+        Vec3::new(512., 512., 1.),
+        Vec2::new(4., -4.),
+    );
+    [
+        (
+            "movement",
+            Packet::new(
+                Action::Movement,
+                Uuid::new_v4(),
+                Payload::Movement(movement),
+            ),
+        ),
+        (
+            "message",
+            Packet::new(
+                Action::Message,
+                Uuid::new_v4(),
+                Payload::Message(MessagePayload::new("a".repeat(200))),
+            ),
+        ),
+    ]
+}
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("packet");
+
+    for (name, packet) in packets() {
+        let bytes = packet.to_bytes();
+        group.bench_function(format!("to_bytes/{}", name), |b| {
+            b.iter(|| black_box(packet.to_bytes()))
+        });
+        group.bench_function(format!("from_bytes/{}", name), |b| {
+            b.iter(|| black_box(Packet::from_bytes(black_box(&bytes)).payload()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, serialization);
+criterion_main!(benches);
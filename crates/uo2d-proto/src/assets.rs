@@ -0,0 +1,77 @@
+//! Reads the assets shared by the server and client, such as the items and translations.
+//! Browsers have no filesystem, so builds for them carry the few the client reads within them.
+
+use std::io;
+#[cfg(target_arch = "wasm32")]
+use std::path::Path;
+
+/// Directory the assets are read from unless another root is given.
+pub const ROOT: &str = "assets";
+
+/// Assets read by the client, by their path.
+#[cfg(target_arch = "wasm32")]
+const EMBEDDED: [(&str, &str); 5] = [
+    (
+        "assets/items.yaml",
+        include_str!("../../../assets/items.yaml"),
+    ),
+    (
+        "assets/locale/en.toml",
+        include_str!("../../../assets/locale/en.toml"),
+    ),
+    (
+        "assets/locale/fr.toml",
+        include_str!("../../../assets/locale/fr.toml"),
+    ),
+    (
+        "assets/locale/world/en.toml",
+        include_str!("../../../assets/locale/world/en.toml"),
+    ),
+    (
+        "assets/locale/world/fr.toml",
+        include_str!("../../../assets/locale/world/fr.toml"),
+    ),
+];
+
+/// Reads an asset, such as `assets/items.yaml`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_to_string(path: &str) -> io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+/// Reads an asset, such as `assets/items.yaml`, from those embedded.
+#[cfg(target_arch = "wasm32")]
+pub fn read_to_string(path: &str) -> io::Result<String> {
+    EMBEDDED
+        .iter()
+        .find(|(embedded, _)| *embedded == path)
+        .map(|(_, content)| content.to_string())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} is not embedded", path)))
+}
+
+/// Names of the files within a directory with the extension, without it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn file_stems(directory: &str, extension: &str) -> Vec<String> {
+    std::fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+                .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Names of the embedded files within a directory with the extension, without it.
+#[cfg(target_arch = "wasm32")]
+pub fn file_stems(directory: &str, extension: &str) -> Vec<String> {
+    EMBEDDED
+        .iter()
+        .map(|(path, _)| Path::new(path))
+        .filter(|path| path.parent() == Some(Path::new(directory)))
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect()
+}
@@ -0,0 +1,57 @@
+use crate::components::{Bounds, Vec2, Vec3};
+
+/// Number of tiles along each side of a chunk.
+pub const CHUNK_TILES: usize = 16;
+/// Size of a single tile in world units.
+pub const TILE_SIZE: f64 = 32.0;
+/// Size of a chunk in world units.
+pub const CHUNK_SIZE: f64 = CHUNK_TILES as f64 * TILE_SIZE;
+/// Distance in chunks past which a chunk is no longer kept by a client.
+pub const EVICT_DISTANCE: u16 = 2;
+/// Tile value for a location outside of every region.
+pub const VOID: u8 = 0;
+/// Bit set on the tiles covered in water, alongside the region they are within.
+pub const WATER: u8 = 0x80;
+
+/// Location of a chunk, in chunks from the origin.
+pub type ChunkCoord = (u16, u16);
+
+/// Obtains the chunk containing a position.
+pub fn chunk_of(position: &Vec3) -> ChunkCoord {
+    let coord = |value: f64| (value.max(0.) / CHUNK_SIZE) as u16;
+    (coord(position.x()), coord(position.y()))
+}
+
+/// Obtains all chunks overlapping the area within a distance of a position.
+pub fn chunks_within(position: &Vec3, distance: f64) -> Vec<ChunkCoord> {
+    let corner = |offset: f64| {
+        chunk_of(&Vec3::new(
+            position.x() + offset,
+            position.y() + offset,
+            position.z(),
+        ))
+    };
+    let (min_x, min_y) = corner(-distance);
+    let (max_x, max_y) = corner(distance);
+
+    let mut chunks = Vec::new();
+    for x in min_x..=max_x {
+        for y in min_y..=max_y {
+            chunks.push((x, y));
+        }
+    }
+    chunks
+}
+
+/// Distance in chunks between a chunk and the one containing a position.
+pub fn chunk_distance(coord: &ChunkCoord, position: &Vec3) -> u16 {
+    let (x, y) = chunk_of(position);
+    coord.0.abs_diff(x).max(coord.1.abs_diff(y))
+}
+
+/// Bounds of a tile within a chunk, tiles are stored row by row.
+pub fn tile_bounds(coord: &ChunkCoord, index: usize) -> Bounds {
+    let x = coord.0 as f64 * CHUNK_SIZE + (index % CHUNK_TILES) as f64 * TILE_SIZE;
+    let y = coord.1 as f64 * CHUNK_SIZE + (index / CHUNK_TILES) as f64 * TILE_SIZE;
+    Bounds::from_vec(Vec3::new(x, y, 0.), Vec2::new(TILE_SIZE, TILE_SIZE))
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Bounds, Vec2, Vec3};
+use crate::ecs::Entity;
+use crate::impl_component;
+
+/// Shape of an area affected by an ability, centered on its location.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AreaShape {
+    Circle { radius: f64 },
+    Rect { width: f64, height: f64 },
+}
+
+impl AreaShape {
+    /// Box surrounding the shape when centered on a location.
+    pub fn bounds(&self, center: Vec3) -> Bounds {
+        let size = match self {
+            AreaShape::Circle { radius } => Vec2::new(radius * 2., radius * 2.),
+            AreaShape::Rect { width, height } => Vec2::new(*width, *height),
+        };
+        Bounds::from_vec(
+            Vec3::new(
+                center.x() - size.x() / 2.,
+                center.y() - size.y() / 2.,
+                center.z(),
+            ),
+            size,
+        )
+    }
+
+    /// Checks if the shape centered on a location touches the bounds.
+    pub fn touches(&self, center: Vec3, bounds: &Bounds) -> bool {
+        match self {
+            AreaShape::Circle { radius } => {
+                // Closest point of the bounds to the center.
+                let x = center.x().clamp(bounds.x(), bounds.x() + bounds.width());
+                let y = center.y().clamp(bounds.y(), bounds.y() + bounds.height());
+                Vec2::new(x, y).distance(&Vec2::new(center.x(), center.y())) <= *radius
+            }
+            AreaShape::Rect { .. } => self.bounds(center).intersects_2d(bounds),
+        }
+    }
+}
+
+/// Area warned about ahead of time, damaging those within once it strikes and while it lingers.
+#[derive(Debug, Clone, Copy)]
+pub struct Area {
+    pub shape: AreaShape,
+    pub center: Vec3,
+    /// Damage dealt as it strikes.
+    pub damage: u32,
+    /// Damage dealt on every tick it is active.
+    pub per_tick: u32,
+    /// Entity that created it, credited with the damage.
+    pub source: Entity,
+    /// Tick it strikes on, becoming active.
+    pub strikes: u64,
+    /// Tick it fades on.
+    pub expires: u64,
+    pub struck: bool,
+}
+
+impl Area {
+    /// Checks if it has struck and is damaging those within.
+    pub fn is_active(&self, tick: u64) -> bool {
+        tick >= self.strikes
+    }
+}
+
+impl_component!(Area);
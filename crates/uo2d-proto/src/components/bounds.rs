@@ -226,7 +226,7 @@ impl Bounds {
 }
 
 /// Sorts coordinates in clockwise order around their centroid.
-pub(crate) fn sort_coordinates_clockwise(coordinates: &[Vec3]) -> Vec<Vec3> {
+pub fn sort_coordinates_clockwise(coordinates: &[Vec3]) -> Vec<Vec3> {
     // Clone the input vector to not modify the original
     let mut sorted_coordinates = coordinates.to_vec();
 
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{ItemId, Vec3};
+use crate::impl_component;
+
+/// Action that takes time to cast or has to wait before being used again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ability {
+    /// Using an item from the inventory.
+    Item(ItemId),
+    Gather,
+    Emote,
+}
+
+/// Abilities waiting to be used again, with the tick each one is ready on.
+#[derive(Debug, Clone, Default)]
+pub struct Cooldowns(HashMap<Ability, u64>);
+
+impl Cooldowns {
+    /// Prevents an ability from being used until the tick provided, extending it if already waiting.
+    pub fn start(&mut self, ability: Ability, ready: u64) {
+        let current = self.0.entry(ability).or_insert(ready);
+        *current = (*current).max(ready);
+    }
+
+    /// Checks if an ability can be used on the tick.
+    pub fn is_ready(&self, ability: Ability, tick: u64) -> bool {
+        self.remaining(ability, tick) == 0
+    }
+
+    /// Ticks left until an ability can be used.
+    pub fn remaining(&self, ability: Ability, tick: u64) -> u64 {
+        self.0
+            .get(&ability)
+            .map_or(0, |ready| ready.saturating_sub(tick))
+    }
+
+    /// Forgets the abilities that are ready again.
+    pub fn expire(&mut self, tick: u64) {
+        self.0.retain(|_, ready| *ready > tick);
+    }
+}
+
+impl_component!(Cooldowns);
+
+/// Ability being cast, used once it finishes unless the caster moves or is hurt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Casting {
+    pub ability: Ability,
+    /// Inventory slot the ability uses, if any.
+    pub slot: Option<usize>,
+    /// Where the caster stood when the cast began.
+    pub origin: Vec3,
+    pub finishes: u64,
+}
+
+impl_component!(Casting);
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::impl_component;
+
+/// Temporary condition changing how an entity is treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusEffect {
+    /// Takes no damage, given briefly after spawning.
+    Invulnerable,
+    /// Moves faster than the terrain normally allows.
+    Haste,
+}
+
+/// Status effects on an entity, with the tick each one expires on.
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects(HashMap<StatusEffect, u64>);
+
+impl StatusEffects {
+    /// Applies an effect until the tick provided, extending it if already active.
+    pub fn apply(&mut self, effect: StatusEffect, expires: u64) {
+        let current = self.0.entry(effect).or_insert(expires);
+        *current = (*current).max(expires);
+    }
+
+    /// Checks if an effect is active.
+    pub fn has(&self, effect: StatusEffect) -> bool {
+        self.0.contains_key(&effect)
+    }
+
+    /// Removes the effects that have run out, returning them.
+    pub fn expire(&mut self, tick: u64) -> Vec<StatusEffect> {
+        let expired: Vec<StatusEffect> = self
+            .0
+            .iter()
+            .filter(|(_, expires)| **expires <= tick)
+            .map(|(effect, _)| *effect)
+            .collect();
+        for effect in expired.iter() {
+            self.0.remove(effect);
+        }
+        expired
+    }
+}
+
+impl_component!(StatusEffects);
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+use crate::impl_component;
+
+use super::Bounds;
+
+/// Identifier for an item definition.
+pub type ItemId = u16;
+
+/// Locations items can be equipped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EquipSlot {
+    Head,
+    Body,
+    Weapon,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Head, EquipSlot::Body, EquipSlot::Weapon];
+}
+
+/// Items currently equipped, also used as the appearance of an entity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Equipment {
+    pub head: Option<ItemId>,
+    pub body: Option<ItemId>,
+    pub weapon: Option<ItemId>,
+}
+
+impl Equipment {
+    /// Obtains the item in a slot.
+    pub fn get(&self, slot: EquipSlot) -> Option<ItemId> {
+        match slot {
+            EquipSlot::Head => self.head,
+            EquipSlot::Body => self.body,
+            EquipSlot::Weapon => self.weapon,
+        }
+    }
+
+    /// Places an item into a slot, returning the item previously there.
+    pub fn set(&mut self, slot: EquipSlot, item: Option<ItemId>) -> Option<ItemId> {
+        let current = match slot {
+            EquipSlot::Head => &mut self.head,
+            EquipSlot::Body => &mut self.body,
+            EquipSlot::Weapon => &mut self.weapon,
+        };
+
+        std::mem::replace(current, item)
+    }
+
+    /// All items that are equipped.
+    pub fn items(&self) -> impl Iterator<Item = ItemId> + '_ {
+        EquipSlot::ALL.iter().filter_map(|slot| self.get(*slot))
+    }
+}
+
+/// Quantity of a single item occupying one inventory slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u16,
+}
+
+impl ItemStack {
+    /// Creates a new stack of items.
+    pub fn new(item: ItemId, count: u16) -> Self {
+        Self { item, count }
+    }
+}
+
+/// Items being carried by an entity, limited by the weight it can hold.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    pub stacks: Vec<ItemStack>,
+    pub capacity: u32,
+}
+
+impl Inventory {
+    /// Creates an empty inventory that can hold up to `capacity` weight.
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            stacks: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Total amount of an item being carried.
+    pub fn count(&self, item: ItemId) -> u32 {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count as u32)
+            .sum()
+    }
+
+    /// Removes an amount of an item, returning if enough was present.
+    pub fn remove(&mut self, item: ItemId, count: u16) -> bool {
+        if self.count(item) < count as u32 {
+            return false;
+        }
+
+        // Take from the smallest stacks first to keep the larger ones intact.
+        let mut remaining = count;
+        let mut order: Vec<usize> = (0..self.stacks.len())
+            .filter(|i| self.stacks[*i].item == item)
+            .collect();
+        order.sort_by_key(|i| self.stacks[*i].count);
+        for i in order {
+            let taken = remaining.min(self.stacks[i].count);
+            self.stacks[i].count -= taken;
+            remaining -= taken;
+            if remaining == 0 {
+                break;
+            }
+        }
+
+        self.stacks.retain(|stack| stack.count > 0);
+        true
+    }
+
+    /// Removes an amount from a specific stack, returning if enough was present.
+    pub fn remove_at(&mut self, index: usize, count: u16) -> bool {
+        match self.stacks.get_mut(index) {
+            Some(stack) if stack.count >= count => stack.count -= count,
+            _ => return false,
+        }
+
+        self.stacks.retain(|stack| stack.count > 0);
+        true
+    }
+
+    /// Splits `count` items from a stack into a new stack, returning if successful.
+    pub fn split(&mut self, index: usize, count: u16) -> bool {
+        let stack = match self.stacks.get_mut(index) {
+            Some(stack) if count > 0 && count < stack.count => stack,
+            _ => return false,
+        };
+
+        stack.count -= count;
+        let item = stack.item;
+        self.stacks.insert(index + 1, ItemStack::new(item, count));
+        true
+    }
+
+    /// Moves a stack to another slot, shifting the stacks between them, returning if successful.
+    pub fn move_stack(&mut self, from: usize, to: usize) -> bool {
+        if from == to || from >= self.stacks.len() || to >= self.stacks.len() {
+            return false;
+        }
+
+        let stack = self.stacks.remove(from);
+        self.stacks.insert(to, stack);
+        true
+    }
+}
+
+/// Items a character keeps at the bank, holding far more than they can carry.
+#[derive(Debug, Clone, Default)]
+pub struct Bank(pub Inventory);
+
+/// Stack of items lying on the ground, waiting to be picked up or to decay.
+#[derive(Debug, Clone, Copy)]
+pub struct GroundItem {
+    pub stack: ItemStack,
+    pub bounds: Bounds,
+    /// Time in seconds since the epoch when the item decays.
+    pub expires: u64,
+}
+
+/// Loot table rolled for what an entity drops once it dies or is destroyed.
+#[derive(Debug, Clone)]
+pub struct Loot {
+    pub table: String,
+    /// Level the table is rolled at, deciding which of its entries can drop.
+    pub level: u32,
+}
+
+impl_component!(Equipment, Inventory, Bank, GroundItem, Loot);
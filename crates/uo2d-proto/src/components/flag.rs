@@ -0,0 +1,25 @@
+use crate::components::{Team, Vec3};
+use crate::ecs::Entity;
+use crate::impl_component;
+
+/// A team's flag, captured by carrying it to the opposing team's flag.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    pub team: Team,
+    /// Where the flag rests until it is taken.
+    pub home: Vec3,
+    /// Player currently carrying the flag.
+    pub carrier: Option<Entity>,
+}
+
+impl Flag {
+    pub fn new(team: Team, home: Vec3) -> Self {
+        Self {
+            team,
+            home,
+            carrier: None,
+        }
+    }
+}
+
+impl_component!(Flag);
@@ -0,0 +1,34 @@
+use crate::impl_component;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Checks if the health has been depleted.
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+
+    /// Removes health, returning the amount actually removed.
+    pub fn damage(&mut self, amount: u32) -> u32 {
+        let removed = amount.min(self.current);
+        self.current -= removed;
+        removed
+    }
+
+    /// Restores health up to the maximum, returning the amount actually restored.
+    pub fn heal(&mut self, amount: u32) -> u32 {
+        let restored = amount.min(self.max - self.current);
+        self.current += restored;
+        restored
+    }
+}
+
+impl_component!(Health);
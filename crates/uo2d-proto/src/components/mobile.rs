@@ -1,5 +1,6 @@
 use uuid::Uuid;
 
+use crate::ecs::Entity;
 use crate::impl_component;
 
 #[derive(Debug, Clone, Copy)]
@@ -16,6 +17,13 @@ impl Player {
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct Projectile;
+pub struct Projectile {
+    /// Entity that fired the projectile.
+    pub owner: Entity,
+}
+
+/// Riding a mount, moving faster and appearing larger.
+#[derive(Debug, Clone, Copy)]
+pub struct Mounted;
 
-impl_component!(Player, Projectile);
+impl_component!(Player, Projectile, Mounted);
@@ -0,0 +1,41 @@
+mod area;
+mod bounds;
+mod casting;
+mod effects;
+mod equipment;
+mod flag;
+mod health;
+mod mobile;
+mod npc;
+mod obstacle;
+mod plot;
+mod position;
+mod resource;
+mod skills;
+mod stats;
+mod team;
+mod terrain;
+mod transform;
+mod vec;
+mod velocity;
+
+pub use area::*;
+pub use bounds::*;
+pub use casting::*;
+pub use effects::*;
+pub use equipment::*;
+pub use flag::*;
+pub use health::*;
+pub use mobile::*;
+pub use npc::*;
+pub use obstacle::*;
+pub use plot::*;
+pub use position::*;
+pub use resource::*;
+pub use skills::*;
+pub use stats::*;
+pub use team::*;
+pub use terrain::*;
+pub use transform::*;
+pub use vec::*;
+pub use velocity::*;
@@ -0,0 +1,168 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Vec2, Vec3};
+use crate::ecs::Entity;
+use crate::impl_component;
+use crate::locale::Text;
+
+/// Character placed within a region by a spawner.
+#[derive(Debug, Clone)]
+pub struct Npc {
+    /// Type of NPC, naming it within the string tables.
+    pub kind: String,
+    /// Dialogue tree the character speaks with, if they can be talked to.
+    pub dialogue: Option<String>,
+    /// Speed it wanders around at, 0 stands still.
+    pub wander: f64,
+    /// Direction it is currently wandering in.
+    pub heading: Vec2,
+    /// Speed it chases its target at.
+    pub chase: f64,
+}
+
+impl Npc {
+    /// Name of the NPC, translated by each client.
+    pub fn text(&self) -> Text {
+        Text::new(format!("npcs.{}", self.kind))
+    }
+}
+
+/// Keeps a number of NPCs alive around its home, replacing them after a delay.
+#[derive(Debug, Clone)]
+pub struct Spawner {
+    /// Type of NPC spawned.
+    pub npc: String,
+    pub home: Vec3,
+    pub max_alive: u32,
+    /// Seconds before a replacement is spawned.
+    pub respawn: f32,
+    /// Distance NPCs can stray from home before they are despawned.
+    pub leash: f64,
+    pub alive: u32,
+}
+
+/// NPC kept alive by the spawner.
+#[derive(Debug, Clone, Copy)]
+pub struct Spawned(pub Entity);
+
+/// Threat an NPC holds towards others, the most threatening being its target.
+#[derive(Debug, Clone, Default)]
+pub struct Threat {
+    pub table: HashMap<Entity, f64>,
+    pub target: Option<Entity>,
+    /// Distance players draw threat from by being nearby, 0 only reacts to damage.
+    pub aggro: f64,
+    /// Heading back to its spawner after being pulled too far, ignoring all threat.
+    pub returning: bool,
+}
+
+impl Threat {
+    /// Creates an empty threat table.
+    pub fn new(aggro: f64) -> Self {
+        Self {
+            aggro,
+            ..Default::default()
+        }
+    }
+
+    /// Adds threat towards an entity, ignored while returning home.
+    pub fn add(&mut self, entity: Entity, amount: f64) {
+        if !self.returning {
+            *self.table.entry(entity).or_default() += amount;
+        }
+    }
+
+    /// Reduces all threat by a factor, forgetting those falling below the minimum.
+    pub fn decay(&mut self, factor: f64, minimum: f64) {
+        self.table.retain(|_, threat| {
+            *threat *= factor;
+            *threat >= minimum
+        });
+    }
+
+    /// Entity with the most threat.
+    pub fn highest(&self) -> Option<Entity> {
+        self.table
+            .iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| *entity)
+    }
+
+    /// Forgets all threat and the target, sending the NPC home.
+    pub fn reset(&mut self) {
+        self.table.clear();
+        self.target = None;
+        self.returning = true;
+    }
+
+    /// Checks if the NPC is chasing a target or returning home.
+    pub fn is_engaged(&self) -> bool {
+        self.target.is_some() || self.returning
+    }
+}
+
+/// NPC fought through phases, each unlocking abilities as its health drops.
+#[derive(Debug, Clone, Default)]
+pub struct Boss {
+    /// Type of NPC, holding the definitions of its phases.
+    pub kind: String,
+    /// Index of the phase it is in.
+    pub phase: usize,
+    /// Tick each ability of the phase is ready on.
+    pub ready: Vec<u64>,
+    /// Fighting players, reset once they are all gone.
+    pub engaged: bool,
+}
+
+impl Boss {
+    /// Creates a boss waiting to be engaged.
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// NPC simulated cheaply while no player is near, jumping around its home instead of moving.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dormant;
+
+/// NPC players reach their bank through while standing near it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Banker;
+
+/// Flags a player has earned through conversations, including the quests they started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub flags: BTreeSet<String>,
+}
+
+impl Progress {
+    /// Prefix of the flags marking a quest as started.
+    const QUEST: &'static str = "quest:";
+
+    /// Checks if a flag has been set.
+    pub fn has(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    /// Sets a flag, returning false if it was already set.
+    pub fn set(&mut self, flag: &str) -> bool {
+        self.flags.insert(flag.to_string())
+    }
+
+    /// Checks if a quest has been started.
+    pub fn has_quest(&self, quest: &str) -> bool {
+        self.has(&format!("{}{}", Self::QUEST, quest))
+    }
+
+    /// Starts a quest, returning false if it was already started.
+    pub fn start_quest(&mut self, quest: &str) -> bool {
+        self.set(&format!("{}{}", Self::QUEST, quest))
+    }
+}
+
+impl_component!(Npc, Spawner, Spawned, Threat, Boss, Dormant, Banker, Progress);
@@ -0,0 +1,58 @@
+use serde::Deserialize;
+
+use crate::impl_component;
+
+/// Blocks the movement of other entities that run into it.
+#[derive(Debug, Clone, Copy)]
+pub struct Collidable;
+
+/// Moved along by entities that walk into it.
+#[derive(Debug, Clone, Copy)]
+pub struct Pushable;
+
+/// Kinds of obstacles that can be placed within a region.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ObstacleKind {
+    /// Pushed around by players.
+    Block,
+    /// Destroyed once its health is depleted.
+    Barrel,
+    /// Furnishing placed by a player within their plot.
+    Decoration,
+    /// Furnishing placed by a player within their plot, holding items.
+    Container,
+}
+
+impl ObstacleKind {
+    /// Kinds players can place within their plots.
+    pub const PLACEABLE: [ObstacleKind; 2] = [ObstacleKind::Decoration, ObstacleKind::Container];
+
+    /// Obtains a kind by its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            ObstacleKind::Block,
+            ObstacleKind::Barrel,
+            ObstacleKind::Decoration,
+            ObstacleKind::Container,
+        ]
+        .into_iter()
+        .find(|kind| kind.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Name of the kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObstacleKind::Block => "block",
+            ObstacleKind::Barrel => "barrel",
+            ObstacleKind::Decoration => "decoration",
+            ObstacleKind::Container => "container",
+        }
+    }
+}
+
+/// A world object that is not controlled by a player.
+#[derive(Debug, Clone, Copy)]
+pub struct Obstacle(pub ObstacleKind);
+
+impl_component!(Collidable, Pushable, Obstacle);
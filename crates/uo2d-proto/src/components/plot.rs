@@ -0,0 +1,10 @@
+use crate::impl_component;
+
+/// Object a player placed within a housing plot.
+#[derive(Debug, Clone)]
+pub struct Furnishing {
+    /// Name of the plot it was placed within.
+    pub plot: String,
+}
+
+impl_component!(Furnishing);
@@ -0,0 +1,19 @@
+use crate::impl_component;
+
+/// Tree, ore vein, or similar that can be gathered from until depleted.
+#[derive(Debug, Clone)]
+pub struct ResourceNode {
+    /// Type of resource, holding what it yields.
+    pub kind: String,
+    /// Gathers left before it is depleted.
+    pub charges: u32,
+}
+
+impl ResourceNode {
+    /// Checks if nothing is left to gather.
+    pub fn is_depleted(&self) -> bool {
+        self.charges == 0
+    }
+}
+
+impl_component!(ResourceNode);
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::impl_component;
+
+/// Experience a character has earned in each of their skills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Skills {
+    pub gathering: u32,
+}
+
+impl Skills {
+    /// Highest level a skill can reach.
+    pub const MAX_LEVEL: u32 = 100;
+    /// Experience needed for the first level, each level after costs more.
+    const EXPERIENCE_SCALE: f64 = 10.;
+
+    /// Level reached with an amount of experience.
+    pub fn level(experience: u32) -> u32 {
+        let level = (experience as f64 / Self::EXPERIENCE_SCALE).sqrt().floor() as u32;
+        level.min(Self::MAX_LEVEL)
+    }
+
+    /// Level of the gathering skill.
+    pub fn gathering_level(&self) -> u32 {
+        Self::level(self.gathering)
+    }
+}
+
+impl_component!(Skills);
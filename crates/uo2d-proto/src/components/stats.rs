@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::impl_component;
+
+/// Statistics tracked for a player, ranked on the leaderboard.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub kills: u32,
+    pub deaths: u32,
+    /// Distance traveled in world units.
+    pub distance: f64,
+    /// Time spent in the world in seconds.
+    pub playtime: f64,
+}
+
+impl Stats {
+    /// Orders players by most kills, then fewest deaths.
+    pub fn rank(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .kills
+            .cmp(&self.kills)
+            .then(self.deaths.cmp(&other.deaths))
+    }
+}
+
+impl_component!(Stats);
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::impl_component;
+
+/// Side a player fights for, teammates are shielded from each other's attacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Team {
+    Red,
+    Blue,
+}
+
+impl Team {
+    /// Every team players can be assigned to.
+    pub const ALL: [Team; 2] = [Team::Red, Team::Blue];
+
+    /// Obtains a team by its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|team| team.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Name of the team.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Team::Red => "red",
+            Team::Blue => "blue",
+        }
+    }
+
+    /// Color members of the team are drawn with.
+    pub fn color(&self) -> [u8; 3] {
+        match self {
+            Team::Red => [220, 40, 40],
+            Team::Blue => [40, 90, 220],
+        }
+    }
+}
+
+impl_component!(Team);
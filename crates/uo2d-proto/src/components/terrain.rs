@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::impl_component;
+
+/// Ground covering part of a region, changing how entities move across it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Terrain {
+    #[default]
+    Ground,
+    Water,
+}
+
+/// How an entity is moving across the terrain beneath it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MovementMode {
+    #[default]
+    Walking,
+    /// Slowed and unable to fire projectiles.
+    Swimming,
+}
+
+impl MovementMode {
+    /// Mode an entity moves in while on the terrain.
+    pub fn from_terrain(terrain: Terrain) -> Self {
+        match terrain {
+            Terrain::Ground => MovementMode::Walking,
+            Terrain::Water => MovementMode::Swimming,
+        }
+    }
+}
+
+/// Dropping from a ledge, hurt on landing by how far it fell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Falling {
+    /// Layer the fall began from.
+    pub from: f64,
+}
+
+impl_component!(MovementMode, Falling);
@@ -105,6 +105,11 @@ impl Transform {
         self.bounding_box
     }
 
+    /// Corners of the polygon, in clockwise order.
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
     /// Gets the top-left bounding box position that represents this transform.
     pub fn position(&self) -> Vec3 {
         self.bounding_box().top_left_3d()
@@ -0,0 +1,14 @@
+use crate::impl_component;
+
+use super::Vec2;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Velocity(pub Vec2);
+
+impl_component!(Velocity);
+
+/// Movement input applied to the velocity on the next physics step, then consumed.
+#[derive(Clone, Copy, Debug)]
+pub struct Acceleration(pub Vec2);
+
+impl_component!(Acceleration);
@@ -0,0 +1,139 @@
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::http;
+
+/// Log lines kept to be included in a crash report.
+const RECENT_CAPACITY: usize = 200;
+/// Time allowed to deliver a report before giving up.
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most recent log lines, printed or not.
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Remembers a log line for crash reports, dropping the oldest once full.
+pub fn record(line: &str) {
+    let Ok(mut recent) = RECENT.lock() else {
+        return;
+    };
+
+    if recent.len() >= RECENT_CAPACITY {
+        recent.pop_front();
+    }
+    recent.push_back(line.to_string());
+}
+
+/// Writes a report whenever a thread or task panics.
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    /// Part of the process that is running, such as `server` or `client`.
+    pub name: String,
+    pub version: String,
+    /// Directory the reports are written to.
+    pub directory: PathBuf,
+    /// Plain HTTP address the reports are posted to, if set.
+    pub endpoint: Option<String>,
+}
+
+impl CrashReporter {
+    pub const DIRECTORY: &'static str = "crashes";
+
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            directory: PathBuf::from(Self::DIRECTORY),
+            endpoint: None,
+        }
+    }
+
+    /// Posts the reports to an address as well.
+    pub fn endpoint(mut self, endpoint: Option<String>) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Replaces the panic hook, still printing the panic as before once the report is written.
+    pub fn install(self) {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let report = self.report(info);
+            match self.write(&report) {
+                Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                Err(why) => eprintln!("Unable to write crash report: {}", why),
+            }
+
+            if let Some(endpoint) = &self.endpoint {
+                if let Err(why) = http::post(endpoint, "text/plain", &report, SEND_TIMEOUT) {
+                    eprintln!("Unable to send crash report to {}: {}", endpoint, why);
+                }
+            }
+
+            previous(info);
+        }));
+    }
+
+    /// Describes the panic, the build, and what was logged leading up to it.
+    fn report(&self, info: &PanicHookInfo) -> String {
+        let thread = std::thread::current();
+        let message = match info.payload().downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match info.payload().downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "unknown".to_string(),
+            },
+        };
+
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "{} {} crashed at {}",
+            self.name,
+            self.version,
+            Utc::now()
+        );
+        let _ = writeln!(
+            report,
+            "Build: {} {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+        );
+        let _ = writeln!(report, "Thread: {}", thread.name().unwrap_or("unnamed"));
+        let _ = writeln!(report, "Panic: {}", message);
+        if let Some(location) = info.location() {
+            let _ = writeln!(report, "Location: {}", location);
+        }
+
+        let _ = writeln!(report, "\nBacktrace:\n{}", Backtrace::force_capture());
+
+        let _ = writeln!(report, "Recent log:");
+        if let Ok(recent) = RECENT.lock() {
+            for line in recent.iter() {
+                let _ = writeln!(report, "{}", line);
+            }
+        }
+
+        report
+    }
+
+    /// Saves the report, named by the part that crashed and when.
+    fn write(&self, report: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.directory)?;
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3f");
+        let path = self.directory.join(format!("{}-{}.txt", self.name, stamp));
+        std::fs::write(&path, report)?;
+        Ok(path)
+    }
+}
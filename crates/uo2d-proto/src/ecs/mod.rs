@@ -1,10 +1,12 @@
 mod component;
 mod entity;
+mod pool;
 mod query;
 mod sparse_set;
 mod world;
 
 pub use component::Component;
 pub use entity::Entity;
+pub use pool::PoolStats;
 pub use query::ComponentChange;
 pub use world::World;
@@ -0,0 +1,73 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::entity::Entity;
+
+/// Allocation counts for a pool of short-lived entities.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Entities spawned with a brand new id.
+    pub allocated: u64,
+    /// Entities spawned with the id of a released one.
+    pub reused: u64,
+    pub released: u64,
+    /// Released entities waiting to be reused.
+    pub free: usize,
+}
+
+/// Recycles the ids of a kind of short-lived entity, such as projectiles.
+pub(crate) struct EntityPool {
+    /// Ticks a released entity waits before reuse, outliving anything still referring to it.
+    cooldown: u64,
+    /// Released entities in the order they were released, along with the tick they were.
+    free: VecDeque<(Entity, u64)>,
+    /// Released entities that have not been reused, preventing double releases.
+    released: HashSet<Entity>,
+    stats: PoolStats,
+}
+
+impl EntityPool {
+    pub(crate) fn new(cooldown: u64) -> Self {
+        Self {
+            cooldown,
+            free: VecDeque::new(),
+            released: HashSet::new(),
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// Takes the oldest released entity whose cooldown has passed.
+    pub(crate) fn acquire(&mut self, tick: u64) -> Option<Entity> {
+        let (entity, released) = *self.free.front()?;
+        if tick < released + self.cooldown {
+            return None;
+        }
+
+        self.free.pop_front();
+        self.released.remove(&entity);
+        self.stats.reused += 1;
+        Some(entity)
+    }
+
+    /// Records a brand new entity being spawned for the pool.
+    pub(crate) fn allocated(&mut self) {
+        self.stats.allocated += 1;
+    }
+
+    /// Holds onto an entity for reuse, returning false if it was already released.
+    pub(crate) fn release(&mut self, entity: Entity, tick: u64) -> bool {
+        if !self.released.insert(entity) {
+            return false;
+        }
+
+        self.free.push_back((entity, tick));
+        self.stats.released += 1;
+        true
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        PoolStats {
+            free: self.free.len(),
+            ..self.stats
+        }
+    }
+}
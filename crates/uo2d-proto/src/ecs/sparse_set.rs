@@ -9,6 +9,10 @@ pub(crate) struct SparseSet {
     dense: Vec<Box<dyn Component>>,
     // Mapping from dense index back to entity.
     entities: Vec<Entity>,
+    // Leading dense entries owned by a group, kept in the same order as the other set of the group.
+    grouped: usize,
+    // Components of released entities, overwritten in place instead of allocating new ones.
+    spare: Vec<Box<dyn Component>>,
 }
 
 impl SparseSet {
@@ -16,6 +20,58 @@ impl SparseSet {
         &self.entities
     }
 
+    // Number of leading entries packed into a group.
+    pub(crate) fn grouped(&self) -> usize {
+        self.grouped
+    }
+
+    // Dense index of the entity's component, if it has one.
+    pub(crate) fn index(&self, entity: &Entity) -> Option<usize> {
+        self.sparse.get(entity.id() as usize).copied().flatten()
+    }
+
+    // Checks if the entity's component is packed into the group.
+    pub(crate) fn is_packed(&self, entity: &Entity) -> bool {
+        self.index(entity).is_some_and(|index| index < self.grouped)
+    }
+
+    // Retrieve a component by its dense index.
+    pub(crate) fn get_at(&self, index: usize) -> Option<&dyn Component> {
+        self.dense.get(index).map(|component| component.as_ref())
+    }
+
+    // Swaps two dense entries, pointing the sparse indices at their new places.
+    fn swap(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+
+        self.dense.swap(a, b);
+        self.entities.swap(a, b);
+        self.sparse[self.entities[a].id() as usize] = Some(a);
+        self.sparse[self.entities[b].id() as usize] = Some(b);
+    }
+
+    // Moves the entity's component to the end of the grouped entries.
+    pub(crate) fn pack(&mut self, entity: &Entity) {
+        if let Some(index) = self.index(entity) {
+            if index >= self.grouped {
+                self.swap(index, self.grouped);
+                self.grouped += 1;
+            }
+        }
+    }
+
+    // Moves the entity's component out of the grouped entries.
+    pub(crate) fn unpack(&mut self, entity: &Entity) {
+        if let Some(index) = self.index(entity) {
+            if index < self.grouped {
+                self.grouped -= 1;
+                self.swap(index, self.grouped);
+            }
+        }
+    }
+
     // Add a component to an entity.
     pub(crate) fn insert(&mut self, entity: Entity, component: Box<dyn Component>) {
         let entity_id = entity.id() as usize;
@@ -35,6 +91,23 @@ impl SparseSet {
         }
     }
 
+    // Most spare components kept around for reuse.
+    const SPARE_CAPACITY: usize = 256;
+
+    // Removes a component, keeping it to be reused by a later insert.
+    pub(crate) fn recycle(&mut self, entity: &Entity) {
+        if let Some(component) = self.take(entity) {
+            if self.spare.len() < Self::SPARE_CAPACITY {
+                self.spare.push(component);
+            }
+        }
+    }
+
+    // Takes a component left behind by a released entity.
+    pub(crate) fn spare(&mut self) -> Option<Box<dyn Component>> {
+        self.spare.pop()
+    }
+
     // Retrieve a component by entity.
     pub(crate) fn get(&self, entity: &Entity) -> Option<&dyn Component> {
         let entity_id = entity.id() as usize;
@@ -63,12 +136,17 @@ impl SparseSet {
 
     // Removes a component associated with an entity.
     pub(crate) fn remove(&mut self, entity: &Entity) {
+        self.take(entity);
+    }
+
+    // Removes a component associated with an entity, returning it.
+    pub(crate) fn take(&mut self, entity: &Entity) -> Option<Box<dyn Component>> {
         let entity_id = entity.id() as usize;
         if entity_id < self.sparse.len() {
             if let Some(dense_index) = self.sparse[entity_id] {
                 let last_index = self.dense.len() - 1;
                 self.dense.swap(dense_index, last_index);
-                self.dense.pop();
+                let component = self.dense.pop();
 
                 // Remove the entity from the `entities` array in a similar fashion.
                 self.entities.swap(dense_index, last_index);
@@ -83,12 +161,14 @@ impl SparseSet {
 
                 // Finally, mark the entity's component as removed in the `sparse` array.
                 self.sparse[entity_id] = None;
+                return component;
             }
         }
+
+        None
     }
 
     // Efficiently iterate over all components.
-    #[allow(dead_code)]
     pub(crate) fn iter(&self) -> impl Iterator<Item = &Box<dyn Component>> {
         self.dense.iter()
     }
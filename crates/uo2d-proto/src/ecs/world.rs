@@ -0,0 +1,457 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{HashMap, HashSet},
+};
+
+use super::pool::{EntityPool, PoolStats};
+use super::{component::Component, entity::Entity, sparse_set::SparseSet};
+
+/// Used to construct a new entity with components.
+pub struct EntityBuilder<'a> {
+    world: &'a mut World,
+    entity: Entity,
+}
+
+impl<'a> EntityBuilder<'a> {
+    // Constructor to create a new EntityBuilder.
+    pub fn new(world: &'a mut World, entity: Entity) -> Self {
+        Self { world, entity }
+    }
+
+    // Add a component to the entity.
+    pub fn with<T: Component + 'static>(self, component: T) -> Self {
+        self.world.add_component(self.entity, component);
+        self
+    }
+
+    // Finalize the entity.
+    pub fn build(self) -> Entity {
+        self.entity
+    }
+}
+
+/// Represents the world and all components that exist.
+pub struct World {
+    id: u64,
+    components: HashMap<TypeId, Box<dyn Any>>,
+    /// Pairs of components whose shared entities are packed in step at the front of both sets.
+    groups: Vec<(TypeId, TypeId)>,
+    /// Recycled ids of short-lived kinds of entities.
+    pools: HashMap<&'static str, EntityPool>,
+    /// Components that reused the allocation of a released entity's.
+    recycled: u64,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    /// Creates a new instance of the world.
+    pub fn new() -> Self {
+        Self {
+            id: 0,
+            components: HashMap::new(),
+            groups: Vec::new(),
+            pools: HashMap::new(),
+            recycled: 0,
+        }
+    }
+
+    /// Creates a new Id for an entity.
+    fn generate_id(&mut self) -> u64 {
+        self.id += 1;
+        self.id
+    }
+
+    /// Registers a new component that can be queried.
+    pub fn register_component<T: Component + 'static>(&mut self) {
+        self.components
+            .insert(TypeId::of::<T>(), Box::<SparseSet>::default());
+    }
+
+    /// Keeps the entities with both components packed together, letting `query2` over the pair iterate them directly.
+    /// Both must be registered, and a component can only belong to a single group.
+    pub fn group<T: Component + 'static, U: Component + 'static>(&mut self) {
+        let (t, u) = (TypeId::of::<T>(), TypeId::of::<U>());
+        let taken = self
+            .groups
+            .iter()
+            .any(|(a, b)| [a, b].iter().any(|id| **id == t || **id == u));
+        if t == u || taken || self.set::<T>().is_none() || self.set::<U>().is_none() {
+            return;
+        }
+
+        self.groups.push((t, u));
+        let entities = self
+            .set::<T>()
+            .map_or(vec![], |set| set.entities().to_vec());
+        for entity in entities.iter() {
+            self.pack(entity, Some(&t));
+        }
+    }
+
+    /// Checks if a pair of components is grouped, in either order.
+    fn is_grouped(&self, t: &TypeId, u: &TypeId) -> bool {
+        self.groups
+            .iter()
+            .any(|(a, b)| (a == t && b == u) || (a == u && b == t))
+    }
+
+    /// Obtains the storage for a component.
+    fn set<T: Component + 'static>(&self) -> Option<&SparseSet> {
+        self.components
+            .get(&TypeId::of::<T>())
+            .and_then(|set| set.downcast_ref::<SparseSet>())
+    }
+
+    /// Obtains the storage of both components in a group.
+    fn pair_mut<'a>(
+        components: &'a mut HashMap<TypeId, Box<dyn Any>>,
+        (a, b): &(TypeId, TypeId),
+    ) -> Option<(&'a mut SparseSet, &'a mut SparseSet)> {
+        let [a, b] = components.get_disjoint_mut([a, b]);
+        Some((
+            a?.downcast_mut::<SparseSet>()?,
+            b?.downcast_mut::<SparseSet>()?,
+        ))
+    }
+
+    /// Packs the entity into the groups it has both components of, only those of a type if given.
+    fn pack(&mut self, entity: &Entity, type_id: Option<&TypeId>) {
+        for group in self.groups.iter() {
+            if type_id.is_some_and(|id| *id != group.0 && *id != group.1) {
+                continue;
+            }
+
+            if let Some((a, b)) = Self::pair_mut(&mut self.components, group) {
+                let both = a.index(entity).is_some() && b.index(entity).is_some();
+                if both && !a.is_packed(entity) {
+                    a.pack(entity);
+                    b.pack(entity);
+                }
+            }
+        }
+    }
+
+    /// Removes the entity from the groups it is packed into, only those of a type if given.
+    fn unpack(&mut self, entity: &Entity, type_id: Option<&TypeId>) {
+        for group in self.groups.iter() {
+            if type_id.is_some_and(|id| *id != group.0 && *id != group.1) {
+                continue;
+            }
+
+            if let Some((a, b)) = Self::pair_mut(&mut self.components, group) {
+                if a.is_packed(entity) {
+                    a.unpack(entity);
+                    b.unpack(entity);
+                }
+            }
+        }
+    }
+
+    /// Spawns a new entity with a optional components.
+    pub fn spawn(&mut self) -> EntityBuilder<'_> {
+        let entity = Entity::new(self.generate_id());
+        EntityBuilder::new(self, entity)
+    }
+
+    /// Removes and entity and all associated components.
+    pub fn despawn(&mut self, entity: &Entity) {
+        self.unpack(entity, None);
+        for component in self.components.values_mut() {
+            if let Some(sparse_set) = component.downcast_mut::<SparseSet>() {
+                sparse_set.remove(entity);
+            }
+        }
+    }
+
+    /// Tracks a kind of short-lived entity whose ids are reused once released.
+    /// Released entities wait the cooldown in ticks first, anything still referring to them must be gone by then.
+    pub fn register_pool(&mut self, kind: &'static str, cooldown: u64) {
+        self.pools.insert(kind, EntityPool::new(cooldown));
+    }
+
+    /// Spawns an entity of a pooled kind, reusing a released one when it can.
+    pub fn spawn_pooled(&mut self, kind: &'static str, tick: u64) -> EntityBuilder<'_> {
+        let reused = self.pools.get_mut(kind).and_then(|pool| pool.acquire(tick));
+        let entity = match reused {
+            Some(entity) => entity,
+            None => {
+                if let Some(pool) = self.pools.get_mut(kind) {
+                    pool.allocated();
+                }
+                Entity::new(self.generate_id())
+            }
+        };
+
+        EntityBuilder::new(self, entity)
+    }
+
+    /// Despawns an entity of a pooled kind, keeping its id and components for reuse.
+    /// Releasing one that is already waiting to be reused does nothing.
+    pub fn release(&mut self, kind: &'static str, entity: &Entity, tick: u64) {
+        let released = self
+            .pools
+            .get_mut(kind)
+            .is_some_and(|pool| pool.release(*entity, tick));
+        if !released {
+            return;
+        }
+
+        self.unpack(entity, None);
+        for component in self.components.values_mut() {
+            if let Some(sparse_set) = component.downcast_mut::<SparseSet>() {
+                sparse_set.recycle(entity);
+            }
+        }
+    }
+
+    /// Allocation counts for each kind of pooled entity.
+    pub fn pool_stats(&self) -> Vec<(&'static str, PoolStats)> {
+        let mut stats: Vec<_> = self
+            .pools
+            .iter()
+            .map(|(kind, pool)| (*kind, pool.stats()))
+            .collect();
+        stats.sort_by_key(|(kind, _)| *kind);
+        stats
+    }
+
+    /// Number of components that reused the allocation of a released entity's.
+    pub fn recycled_components(&self) -> u64 {
+        self.recycled
+    }
+
+    /// Moves an entity and its components into another world, returning its new entity.
+    /// Components the other world has not registered are dropped.
+    pub fn transfer(&mut self, entity: &Entity, other: &mut World) -> Entity {
+        let moved = Entity::new(other.generate_id());
+        self.unpack(entity, None);
+        for (type_id, component) in self.components.iter_mut() {
+            let taken = component
+                .downcast_mut::<SparseSet>()
+                .and_then(|sparse_set| sparse_set.take(entity));
+            let target = other
+                .components
+                .get_mut(type_id)
+                .and_then(|target| target.downcast_mut::<SparseSet>());
+            if let (Some(component), Some(target)) = (taken, target) {
+                target.insert(moved, component);
+            }
+        }
+
+        other.pack(&moved, None);
+        moved
+    }
+
+    /// Obtains all entities.
+    pub fn get_entities<T: 'static>(&self) -> HashSet<Entity> {
+        let mut entities = HashSet::new();
+
+        if let Some(sparse_set_any) = self.components.get(&TypeId::of::<T>()) {
+            if let Some(sparse_set) = sparse_set_any.downcast_ref::<SparseSet>() {
+                for entity in sparse_set.entities() {
+                    entities.insert(*entity);
+                }
+            }
+        }
+
+        entities
+    }
+
+    /// Adds a new component to the entity.
+    pub fn add_component<T: Component + 'static>(&mut self, entity: Entity, component: T) {
+        if let Some(any_set) = self.components.get_mut(&TypeId::of::<T>()) {
+            if let Some(set) = any_set.downcast_mut::<SparseSet>() {
+                // Overwrite a released entity's component rather than allocating another.
+                let boxed = match set.spare() {
+                    Some(mut spare) => match spare.as_any_mut().downcast_mut::<T>() {
+                        Some(slot) => {
+                            *slot = component;
+                            self.recycled += 1;
+                            spare
+                        }
+                        None => Box::new(component),
+                    },
+                    None => Box::new(component),
+                };
+                set.insert(entity, boxed);
+            }
+        }
+        self.pack(&entity, Some(&TypeId::of::<T>()));
+    }
+
+    /// Obtains a specific component for an entity.
+    pub fn get_component<T: Component + 'static>(&self, entity: &Entity) -> Option<&T> {
+        self.components
+            .get(&TypeId::of::<T>())
+            .and_then(|any_sparse_set| {
+                if let Some(sparse_set) = any_sparse_set.downcast_ref::<SparseSet>() {
+                    sparse_set
+                        .get(entity)
+                        .and_then(|comp| comp.as_any().downcast_ref::<T>())
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Obtains a mutable component for an entity.
+    pub fn get_component_mut<T: Component + 'static>(&mut self, entity: &Entity) -> Option<&mut T> {
+        // Attempt to get a mutable reference to the SparseSet from the components HashMap.
+        self.components
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|any_sparse_set| {
+                // Downcast to SparseSet mutably.
+                if let Some(sparse_set) = any_sparse_set.downcast_mut::<SparseSet>() {
+                    // Attempt to get a mutable reference to the component.
+                    sparse_set
+                        .get_mut(entity)
+                        .and_then(|comp| comp.as_any_mut().downcast_mut::<T>())
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Removes a component attached to an entity.
+    pub fn remove_component<T: Component + 'static>(&mut self, entity: Entity) {
+        self.remove_component_by_type_id(entity, &TypeId::of::<T>());
+    }
+
+    /// Removes a component based on its type Id.
+    pub fn remove_component_by_type_id(&mut self, entity: Entity, type_id: &TypeId) {
+        self.unpack(&entity, Some(type_id));
+        if let Some(any_sparse_set) = self.components.get_mut(type_id) {
+            if let Some(sparse_set) = any_sparse_set.downcast_mut::<SparseSet>() {
+                sparse_set.remove(&entity);
+            }
+        }
+    }
+
+    // Generic method to replace or insert a component for a given entity.
+    pub fn upsert_component<T: Component + 'static>(&mut self, entity: Entity, new_component: T) {
+        // Get the TypeId for the component.
+        let type_id = TypeId::of::<T>();
+
+        // Check if the SparseSet for this component type exists; if not, create it.
+        let component_set = self
+            .components
+            .entry(type_id)
+            .or_insert_with(|| Box::<SparseSet>::default());
+
+        // Downcast the Box<dyn Any> to a mutable reference to SparseSet.
+        // Inserting replaces an existing component in place, keeping any group it is packed into.
+        if let Some(sparse_set) = component_set.downcast_mut::<SparseSet>() {
+            sparse_set.insert(entity, Box::new(new_component) as Box<dyn Component>);
+        } else {
+            // Handle the error case where downcast_mut fails (this should not happen in practice if your setup is correct)
+            eprintln!("Failed to downcast to SparseSet for component replacement.");
+        }
+        self.pack(&entity, Some(&type_id));
+    }
+
+    #[allow(dead_code)]
+    /// Updates several components.
+    pub fn update_components<T: Component + 'static>(&mut self, updates: Vec<(Entity, T)>) {
+        for (entity, new_comp) in updates {
+            // Update component.
+            if let Some(old_comp) = self.get_component_mut::<T>(&entity) {
+                *old_comp = new_comp;
+            } else {
+                // Create if component is missing.
+                self.upsert_component::<T>(entity, new_comp);
+            }
+        }
+    }
+
+    /// Queries all entities and components of a specified type.
+    pub fn query1<T: Component + 'static>(&self) -> Vec<(Entity, &T)> {
+        let set = match self.set::<T>() {
+            Some(set) => set,
+            None => return vec![],
+        };
+
+        set.entities()
+            .iter()
+            .zip(set.iter())
+            .filter_map(|(entity, component)| {
+                let component = component.as_any().downcast_ref::<T>()?;
+                Some((*entity, component))
+            })
+            .collect()
+    }
+
+    /// Entities a query has to check, the fewest of any set or grouped pair every match belongs to.
+    fn candidates<'a>(&self, types: &[TypeId], sets: &[&'a SparseSet]) -> &'a [Entity] {
+        let mut fewest = sets
+            .iter()
+            .map(|set| set.entities())
+            .min_by_key(|entities| entities.len())
+            .unwrap_or(&[]);
+
+        for (i, a) in types.iter().enumerate() {
+            for b in types.iter().skip(i + 1) {
+                let packed = &sets[i].entities()[..sets[i].grouped()];
+                if self.is_grouped(a, b) && packed.len() <= fewest.len() {
+                    fewest = packed;
+                }
+            }
+        }
+
+        fewest
+    }
+
+    // Method to query entities with multiple component types
+    pub fn query2<T: Component + 'static, U: Component + 'static>(&self) -> Vec<(Entity, &T, &U)> {
+        let (t_set, u_set) = match (self.set::<T>(), self.set::<U>()) {
+            (Some(t_set), Some(u_set)) => (t_set, u_set),
+            _ => return vec![],
+        };
+
+        // Grouped pairs share dense indices, no lookups are needed.
+        if self.is_grouped(&TypeId::of::<T>(), &TypeId::of::<U>()) {
+            return (0..t_set.grouped())
+                .filter_map(|index| {
+                    let t_component = t_set.get_at(index)?.as_any().downcast_ref::<T>()?;
+                    let u_component = u_set.get_at(index)?.as_any().downcast_ref::<U>()?;
+                    Some((t_set.entities()[index], t_component, u_component))
+                })
+                .collect();
+        }
+
+        let types = [TypeId::of::<T>(), TypeId::of::<U>()];
+        self.candidates(&types, &[t_set, u_set])
+            .iter()
+            .filter_map(|entity| {
+                let t_component = t_set.get(entity)?.as_any().downcast_ref::<T>()?;
+                let u_component = u_set.get(entity)?.as_any().downcast_ref::<U>()?;
+                Some((*entity, t_component, u_component))
+            })
+            .collect()
+    }
+
+    /// Queries based on three components obtaining all matching components and entities.
+    pub fn query3<T: Component + 'static, U: Component + 'static, V: Component + 'static>(
+        &self,
+    ) -> Vec<(Entity, &T, &U, &V)> {
+        let (t_set, u_set, v_set) = match (self.set::<T>(), self.set::<U>(), self.set::<V>()) {
+            (Some(t_set), Some(u_set), Some(v_set)) => (t_set, u_set, v_set),
+            _ => return vec![],
+        };
+
+        let types = [TypeId::of::<T>(), TypeId::of::<U>(), TypeId::of::<V>()];
+        self.candidates(&types, &[t_set, u_set, v_set])
+            .iter()
+            .filter_map(|entity| {
+                let t_component = t_set.get(entity)?.as_any().downcast_ref::<T>()?;
+                let u_component = u_set.get(entity)?.as_any().downcast_ref::<U>()?;
+                let v_component = v_set.get(entity)?.as_any().downcast_ref::<V>()?;
+                Some((*entity, t_component, u_component, v_component))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Plain HTTP address such as `http://host:port/path`, resolved to connect to.
+struct Endpoint {
+    host: String,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> Result<Self, Box<dyn Error>> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or("only http:// addresses are supported")?;
+        let (host, path) = match rest.split_once('/') {
+            Some((host, path)) => (host, format!("/{}", path)),
+            None => (rest, "/".to_string()),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            path,
+        })
+    }
+
+    /// Opens a connection, giving up on reads and writes after the timeout.
+    fn connect(&self, timeout: Duration) -> Result<TcpStream, Box<dyn Error>> {
+        let address = if self.host.contains(':') {
+            self.host.clone()
+        } else {
+            format!("{}:80", self.host)
+        };
+
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or("unable to resolve the address")?;
+        let stream = TcpStream::connect_timeout(&address, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(stream)
+    }
+}
+
+/// Posts text to a plain HTTP address, without waiting for the response.
+pub fn post(
+    url: &str,
+    content_type: &str,
+    body: &str,
+    timeout: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let endpoint = Endpoint::parse(url)?;
+    let mut stream = endpoint.connect(timeout)?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        endpoint.path,
+        endpoint.host,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Fetches the body of a plain HTTP address, refusing responses larger than the limit.
+/// Requests are made as HTTP/1.0 so that the body is never chunked.
+pub fn get(url: &str, timeout: Duration, limit: u64) -> Result<String, Box<dyn Error>> {
+    let endpoint = Endpoint::parse(url)?;
+    let mut stream = endpoint.connect(timeout)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        endpoint.path, endpoint.host
+    )?;
+    stream.flush()?;
+
+    let mut response = Vec::new();
+    stream.take(limit + 1).read_to_end(&mut response)?;
+    if response.len() as u64 > limit {
+        return Err(format!("the response is over the limit of {} bytes", limit).into());
+    }
+
+    let response = String::from_utf8(response)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("the response has no body")?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .ok_or("the response has no status")?;
+    if status != "200" {
+        return Err(format!("the server responded with {}", status).into());
+    }
+
+    Ok(body.to_string())
+}
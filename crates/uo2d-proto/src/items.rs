@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::assets;
+use crate::components::{EquipSlot, Equipment, Inventory, ItemId, ItemStack};
+use crate::locale::Text;
+
+/// What happens when an item is used, consuming one of it.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemEffect {
+    /// Restores an amount of health.
+    Heal(u32),
+    /// Returns the user to the spawn.
+    Recall,
+    /// Sends the user somewhere random within a distance.
+    Teleport(f64),
+    /// Hastens the user for a number of seconds.
+    Haste(f32),
+}
+
+/// Definition of an item loaded from the item data.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ItemDefinition {
+    pub id: ItemId,
+    /// Names the sprite drawn for the item and its name within the string tables.
+    pub key: String,
+    #[serde(default)]
+    pub slot: Option<EquipSlot>,
+    #[serde(default)]
+    pub armor: u32,
+    #[serde(default)]
+    pub damage: u32,
+    pub color: [u8; 3],
+    #[serde(default)]
+    pub starter: bool,
+    #[serde(default = "ItemDefinition::default_max_stack")]
+    pub max_stack: u16,
+    #[serde(default)]
+    pub weight: u32,
+    /// Items with an effect are consumed when used.
+    #[serde(default)]
+    pub effect: Option<ItemEffect>,
+    /// Seconds spent using the item before its effect applies.
+    #[serde(default)]
+    pub cast: f32,
+    /// Seconds before another of the item can be used.
+    #[serde(default)]
+    pub cooldown: f32,
+}
+
+impl ItemDefinition {
+    /// Items that are not stackable only hold one per stack.
+    fn default_max_stack() -> u16 {
+        1
+    }
+
+    /// Name of the sprite drawn for the item, such as `iron_sword`.
+    pub fn sprite(&self) -> &str {
+        &self.key
+    }
+
+    /// Name of the item, translated by each client.
+    pub fn text(&self) -> Text {
+        Text::new(format!("items.{}", self.key))
+    }
+}
+
+/// Manages the definitions for all items.
+pub struct ItemManager {
+    items: HashMap<ItemId, ItemDefinition>,
+}
+
+impl Default for ItemManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ItemManager {
+    /// File of the definitions, within the root of the assets.
+    const PATH: &'static str = "items.yaml";
+
+    /// Loads all item definitions at launch.
+    pub fn new() -> Self {
+        Self::from_root(assets::ROOT)
+    }
+
+    /// Loads all item definitions from the assets within a root.
+    pub fn from_root(root: &str) -> Self {
+        let path = format!("{}/{}", root, Self::PATH);
+        let items = match Self::load(&path) {
+            Ok(items) => items.into_iter().map(|item| (item.id, item)).collect(),
+            Err(why) => {
+                eprintln!("Error while loading {}: {}", path, why);
+                HashMap::new()
+            }
+        };
+
+        Self { items }
+    }
+
+    /// Reads the item definitions from a YAML file.
+    fn load(path: &str) -> Result<Vec<ItemDefinition>, Box<dyn std::error::Error>> {
+        let content = assets::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+
+    /// Obtains the definition for an item.
+    pub fn get(&self, id: &ItemId) -> Option<&ItemDefinition> {
+        self.items.get(id)
+    }
+
+    /// Items that every new character starts with, a full stack of each.
+    pub fn starter_items(&self) -> Vec<ItemStack> {
+        let mut items: Vec<ItemStack> = self
+            .items
+            .values()
+            .filter(|item| item.starter)
+            .map(|item| ItemStack::new(item.id, item.max_stack.max(1)))
+            .collect();
+        items.sort_by_key(|stack| stack.item);
+        items
+    }
+
+    /// Total weight of everything within the inventory.
+    pub fn weight(&self, inventory: &Inventory) -> u32 {
+        inventory
+            .stacks
+            .iter()
+            .filter_map(|stack| {
+                self.get(&stack.item)
+                    .map(|item| item.weight * stack.count as u32)
+            })
+            .sum()
+    }
+
+    /// Adds up to `count` of an item, filling existing stacks before creating new ones.
+    /// Returns the amount that fit within the capacity of the inventory.
+    pub fn add(&self, inventory: &mut Inventory, item: ItemId, count: u16) -> u16 {
+        let definition = match self.get(&item) {
+            Some(definition) => definition,
+            None => return 0,
+        };
+
+        // Limit the amount to the remaining capacity.
+        let free = inventory.capacity.saturating_sub(self.weight(inventory));
+        let mut remaining = match definition.weight {
+            0 => count,
+            weight => count.min((free / weight).min(u16::MAX as u32) as u16),
+        };
+        let accepted = remaining;
+        let max_stack = definition.max_stack.max(1);
+
+        for stack in inventory
+            .stacks
+            .iter_mut()
+            .filter(|stack| stack.item == item)
+        {
+            let added = remaining.min(max_stack.saturating_sub(stack.count));
+            stack.count += added;
+            remaining -= added;
+        }
+
+        while remaining > 0 {
+            let added = remaining.min(max_stack);
+            inventory.stacks.push(ItemStack::new(item, added));
+            remaining -= added;
+        }
+
+        accepted
+    }
+
+    /// Total armor provided by the equipment.
+    pub fn armor(&self, equipment: &Equipment) -> u32 {
+        equipment
+            .items()
+            .filter_map(|id| self.get(&id))
+            .map(|item| item.armor)
+            .sum()
+    }
+
+    /// Total damage bonus provided by the equipment.
+    pub fn damage(&self, equipment: &Equipment) -> u32 {
+        equipment
+            .items()
+            .filter_map(|id| self.get(&id))
+            .map(|item| item.damage)
+            .sum()
+    }
+}
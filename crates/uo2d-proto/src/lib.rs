@@ -0,0 +1,14 @@
+pub mod assets;
+pub mod chunk;
+pub mod components;
+pub mod crash;
+pub mod ecs;
+pub mod http;
+pub mod items;
+pub mod locale;
+pub mod packet;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod shutdown;
+pub mod time;
+pub mod timer;
+pub mod util;
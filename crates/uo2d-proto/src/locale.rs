@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets;
+
+/// Text shown to a player, sent as an id and parameters so that each client can translate it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Text {
+    /// Shown as it is, such as announcements written by an operator.
+    Literal(String),
+    /// Translated by id, replacing `{name}` with each of the parameters.
+    Localized {
+        id: String,
+        params: Vec<(String, Text)>,
+    },
+}
+
+impl Text {
+    /// Creates text to be translated, without any parameters.
+    pub fn new(id: impl ToString) -> Self {
+        Self::Localized {
+            id: id.to_string(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Creates text that is never translated.
+    pub fn literal(text: impl ToString) -> Self {
+        Self::Literal(text.to_string())
+    }
+
+    /// Adds a parameter to text being translated.
+    pub fn with(self, name: &str, value: impl ToString) -> Self {
+        self.with_text(name, Self::literal(value))
+    }
+
+    /// Adds a parameter that is translated itself, such as the name of an item.
+    pub fn with_text(mut self, name: &str, value: Text) -> Self {
+        if let Self::Localized { params, .. } = &mut self {
+            params.push((name.to_string(), value));
+        }
+        self
+    }
+}
+
+impl From<&str> for Text {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+/// Translations for a language, falling back to the default language for missing ids.
+pub struct Locale {
+    language: String,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT)
+    }
+}
+
+impl Locale {
+    /// Directory of the translations, within the root of the assets.
+    const PATH: &'static str = "locale";
+    /// Names of the items, NPCs, resources, and regions, kept apart from the data defining them.
+    const WORLD_PATH: &'static str = "locale/world";
+    /// Language used when another is missing a translation.
+    pub const DEFAULT: &'static str = "en";
+
+    /// Loads the translations for a language, falling back to the default if it cannot be read.
+    pub fn new(language: &str) -> Self {
+        Self::from_root(assets::ROOT, language)
+    }
+
+    /// Loads the translations for a language from the assets within a root.
+    pub fn from_root(root: &str, language: &str) -> Self {
+        let fallback = Self::read(root, Self::DEFAULT);
+        if language == Self::DEFAULT {
+            return Self::from_strings(Self::DEFAULT, fallback, HashMap::new());
+        }
+
+        match Self::load(root, language) {
+            Ok(strings) => Self::from_strings(language, strings, fallback),
+            Err(why) => {
+                eprintln!("Error while loading the '{}' locale: {}", language, why);
+                Self::from_strings(Self::DEFAULT, fallback, HashMap::new())
+            }
+        }
+    }
+
+    /// Creates a locale from already parsed translations.
+    pub fn from_strings(
+        language: &str,
+        strings: HashMap<String, String>,
+        fallback: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            language: language.to_string(),
+            strings,
+            fallback,
+        }
+    }
+
+    /// Reads a language, logging and ignoring any errors.
+    fn read(root: &str, language: &str) -> HashMap<String, String> {
+        Self::load(root, language).unwrap_or_else(|why| {
+            eprintln!("Error while loading the '{}' locale: {}", language, why);
+            HashMap::new()
+        })
+    }
+
+    /// Reads the translations for a language from its TOML files, the world strings are optional.
+    fn load(
+        root: &str,
+        language: &str,
+    ) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+        let path = format!("{}/{}/{}.toml", root, Self::PATH, language);
+        let mut strings = Self::parse(&assets::read_to_string(&path)?)?;
+
+        let path = format!("{}/{}/{}.toml", root, Self::WORLD_PATH, language);
+        if let Ok(content) = assets::read_to_string(&path) {
+            strings.extend(Self::parse(&content)?);
+        }
+        Ok(strings)
+    }
+
+    /// Parses translations, nested tables becoming dotted ids such as `mount.indoors`.
+    pub fn parse(content: &str) -> Result<HashMap<String, String>, toml::de::Error> {
+        let table: toml::Table = toml::from_str(content)?;
+        let mut strings = HashMap::new();
+        Self::flatten("", &table, &mut strings);
+        Ok(strings)
+    }
+
+    fn flatten(prefix: &str, table: &toml::Table, strings: &mut HashMap<String, String>) {
+        for (key, value) in table {
+            let id = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                toml::Value::Table(table) => Self::flatten(&id, table, strings),
+                toml::Value::String(text) => {
+                    strings.insert(id, text.clone());
+                }
+                other => {
+                    strings.insert(id, other.to_string());
+                }
+            }
+        }
+    }
+
+    /// Languages that have a translation file, sorted by their code.
+    pub fn languages() -> Vec<String> {
+        Self::languages_in(assets::ROOT)
+    }
+
+    /// Languages that have a translation file within the assets of a root.
+    pub fn languages_in(root: &str) -> Vec<String> {
+        let directory = format!("{}/{}", root, Self::PATH);
+        let mut languages = assets::file_stems(&directory, "toml");
+        languages.sort();
+        languages
+    }
+
+    /// Code of the language in use.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Translation for an id, the id itself if no language has it.
+    pub fn get<'a>(&'a self, id: &'a str) -> &'a str {
+        self.strings
+            .get(id)
+            .or_else(|| self.fallback.get(id))
+            .map(String::as_str)
+            .unwrap_or(id)
+    }
+
+    /// Translates text, substituting its parameters.
+    pub fn text(&self, text: &Text) -> String {
+        let (id, params) = match text {
+            Text::Literal(text) => return text.clone(),
+            Text::Localized { id, params } => (id, params),
+        };
+
+        let mut translated = self.get(id).to_string();
+        for (name, value) in params {
+            translated = translated.replace(&format!("{{{}}}", name), &self.text(value));
+        }
+        translated
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::PACKET_VERSION;
+
+/// Bytes opening the first packets from a client, identifying the protocol.
+pub const HANDSHAKE_MAGIC: [u8; 4] = *b"UO2D";
+
+/// Release of the crates a client or server was built from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl Version {
+    pub const ZERO: Self = Self::new(0, 0, 0);
+    const SIZE: usize = 6;
+
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Version these crates were built as.
+    pub fn current() -> Self {
+        Self::parse(env!("CARGO_PKG_VERSION")).unwrap_or(Self::ZERO)
+    }
+
+    /// Parses a version such as `1.2.3`, ignoring any pre-release or build suffix.
+    pub fn parse(text: &str) -> Option<Self> {
+        let core = text.split(['-', '+']).next()?;
+        let mut parts = core.trim().split('.').map(|part| part.parse::<u16>());
+        let version = Self::new(
+            parts.next()?.ok()?,
+            parts.next()?.ok()?,
+            parts.next()?.ok()?,
+        );
+        parts.next().is_none().then_some(version)
+    }
+
+    fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0..2].copy_from_slice(&self.major.to_be_bytes());
+        bytes[2..4].copy_from_slice(&self.minor.to_be_bytes());
+        bytes[4..6].copy_from_slice(&self.patch.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..Self::SIZE)?;
+        Some(Self::new(
+            u16::from_be_bytes([bytes[0], bytes[1]]),
+            u16::from_be_bytes([bytes[2], bytes[3]]),
+            u16::from_be_bytes([bytes[4], bytes[5]]),
+        ))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Opening of a packet from a client that has not been replied to yet.
+#[derive(Debug, Clone, Copy)]
+pub struct Handshake<'a> {
+    pub protocol: u8,
+    /// Release the client was built from, unknown for other protocols.
+    pub version: Option<Version>,
+    /// The packet following the handshake.
+    pub packet: &'a [u8],
+}
+
+/// Prefixes the bytes of a packet with the magic bytes, protocol version, and release.
+pub fn with_handshake(bytes: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HANDSHAKE_MAGIC.len() + 1 + Version::SIZE + bytes.len());
+    data.extend_from_slice(&HANDSHAKE_MAGIC);
+    data.push(PACKET_VERSION);
+    data.extend_from_slice(&Version::current().to_bytes());
+    data.extend_from_slice(bytes);
+    data
+}
+
+/// Checks if the bytes begin with a handshake, regardless of its version.
+pub fn has_handshake(bytes: &[u8]) -> bool {
+    bytes.starts_with(&HANDSHAKE_MAGIC)
+}
+
+/// Reads the handshake from the front of the bytes, if it is present.
+pub fn parse_handshake(bytes: &[u8]) -> Option<Handshake<'_>> {
+    let (protocol, rest) = bytes.strip_prefix(&HANDSHAKE_MAGIC)?.split_first()?;
+    if *protocol != PACKET_VERSION {
+        return Some(Handshake {
+            protocol: *protocol,
+            version: None,
+            packet: rest,
+        });
+    }
+
+    Some(Handshake {
+        protocol: *protocol,
+        version: Some(Version::from_bytes(rest)?),
+        packet: &rest[Version::SIZE..],
+    })
+}
+
+/// Removes the handshake from the front of the bytes, if it is present and for this version.
+pub fn strip_handshake(bytes: &[u8]) -> Option<&[u8]> {
+    parse_handshake(bytes)
+        .filter(|handshake| handshake.protocol == PACKET_VERSION)
+        .map(|handshake| handshake.packet)
+}
@@ -0,0 +1,178 @@
+mod handshake;
+mod packet_util;
+pub mod payloads;
+pub mod quantize;
+mod reliable;
+
+use std::collections::HashSet;
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use self::payloads::*;
+use crate::locale::Text;
+pub use handshake::*;
+pub use packet_util::*;
+pub use reliable::*;
+
+pub const PACKET_VERSION: u8 = 0x04;
+/// Largest datagram either side receives.
+pub const MAX_DATAGRAM: usize = 1024;
+
+#[derive(Clone)]
+pub enum BroadcastScope {
+    Local(HashSet<Uuid>),
+    Global,
+}
+
+#[allow(dead_code)]
+#[derive(Clone)]
+pub enum PacketConfiguration {
+    Empty,
+    Single(Packet),
+    Broadcast(Packet, BroadcastScope),
+    /// End of a tick, the packets held back for the clients are written.
+    Flush,
+}
+
+/// Action that represents the Packet.
+#[derive(Debug, FromPrimitive, ToPrimitive, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Ping = 0x1,
+    Success,
+    Error,
+    Shutdown,
+    ClientJoin,
+    ClientLeave,
+    Message,
+    Movement,
+    Projectile,
+    EntityDelete,
+    Damage,
+    Heal,
+    Notification,
+    Register,
+    Login,
+    Equip,
+    Unequip,
+    Inventory,
+    Appearance,
+    SplitStack,
+    Drop,
+    Pickup,
+    GroundItem,
+    MovementDelta,
+    Chunk,
+    Emote,
+    Leaderboard,
+    Hud,
+    Match,
+    StatusEffect,
+    Mail,
+    Mailbox,
+    Redirect,
+    Handoff,
+    Dialogue,
+    Target,
+    AreaWarning,
+    Gather,
+    /// The server refused the client for being too old or speaking another protocol.
+    VersionMismatch,
+    /// Several packets for a client written within a single datagram.
+    Bundle,
+    /// Debug overlays requested by a gamemaster, and the shapes drawn for them.
+    Overlay,
+    /// Uses the item within an inventory slot, consuming it.
+    UseItem,
+    /// Progress of an ability being cast.
+    Cast,
+    /// Mounts or dismounts the player, whichever they are not.
+    Mount,
+    /// An entity changing how it moves, such as swimming once in water.
+    MovementMode,
+    /// An entity stepping off a ledge, falling to the layer beneath.
+    Fall,
+    /// Fires at a target the player has locked on to, or why it could not be.
+    Attack,
+    /// Sent back to the client unchanged, measuring the round trip.
+    Echo,
+    /// Trading items with another player, the requests made and the state of the trade.
+    Trade,
+    /// Moves a stack to another slot of the inventory, such as when dragged there.
+    MoveStack,
+    /// Storage kept for each character, opened at bankers and banks.
+    Bank,
+    /// A packet that is sent again until the other side acknowledges it.
+    Reliable,
+    /// Acknowledges a reliable packet, stopping it from being sent again.
+    Ack,
+    /// Full position of an entity that the movement deltas which follow are offset from.
+    Keyframe,
+}
+
+impl Action {
+    /// Convert the action from bytes, if it is a known action.
+    pub fn try_from_bytes(bytes: &[u8; 2]) -> Option<Action> {
+        FromPrimitive::from_u16(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Convert to a numeric value.
+    pub fn to_u16(&self) -> u16 {
+        ToPrimitive::to_u16(self)
+            .unwrap_or_else(|| panic!("Unable to convert Packet Action {:?} to u16.", self))
+    }
+}
+
+/// Payloads that can be sent inside a packet.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Payload {
+    Empty,
+    Invalid,
+    Uuid(UuidPayload),
+    Entity(EntityPayload),
+    Message(MessagePayload),
+    Movement(MovementPayload),
+    Health(HealthPayload),
+    Notification(NotificationPayload),
+    Credentials(CredentialsPayload),
+    Join(JoinPayload),
+    Spawn(SpawnPayload),
+    Equip(EquipPayload),
+    Inventory(InventoryPayload),
+    Appearance(AppearancePayload),
+    Stack(StackPayload),
+    GroundItem(GroundItemPayload),
+    Keyframe(KeyframePayload),
+    MovementDelta(MovementDeltaPayload),
+    Chunk(ChunkPayload),
+    Emote(EmotePayload),
+    Leaderboard(LeaderboardPayload),
+    Hud(HudPayload),
+    Match(MatchPayload),
+    StatusEffect(StatusEffectPayload),
+    Mail(MailPayload),
+    Mailbox(MailboxPayload),
+    Redirect(RedirectPayload),
+    Dialogue(DialoguePayload),
+    DialogueReply(DialogueReply),
+    Target(TargetPayload),
+    AreaWarning(AreaWarningPayload),
+    Version(VersionPayload),
+    Bundle(BundlePayload),
+    Overlay(OverlayPayload),
+    OverlayRequest(OverlayRequest),
+    Cast(CastPayload),
+    MovementMode(MovementModePayload),
+    Fall(FallPayload),
+    AttackError(AttackError),
+    Text(Text),
+    Trade(TradePayload),
+    TradeRequest(TradeRequest),
+    MoveStack(MoveStackPayload),
+    Bank(BankPayload),
+    BankRequest(BankRequest),
+    Reliable(ReliablePayload),
+    Ack(AckPayload),
+}
@@ -33,15 +33,16 @@ impl Packet {
         self.data[0]
     }
 
-    /// Returns the packet action.
-    pub fn action(&self) -> Action {
-        let action_bytes = [self.data[1], self.data[2]];
-        Action::from_bytes(&action_bytes)
+    /// Returns the packet action, if it is a known action.
+    pub fn action(&self) -> Option<Action> {
+        Action::try_from_bytes(&[self.data[1], self.data[2]])
     }
 
     /// Returns the packet UUID.
     pub fn uuid(&self) -> Uuid {
-        Uuid::from_slice(&self.data[3..19]).unwrap()
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&self.data[3..19]);
+        Uuid::from_bytes(bytes)
     }
 
     /// Returns the packet payload, deserialized.
@@ -97,6 +98,27 @@ impl Packet {
         self.data.clone()
     }
 
+    /// Borrows the serialized packet, sending it to many clients without copies.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Splits a bundle into the packets it carries, any other packet is returned as is.
+    pub fn unbundle(self) -> Vec<Packet> {
+        if self.action() != Some(Action::Bundle) {
+            return vec![self];
+        }
+
+        match self.payload() {
+            Payload::Bundle(bundle) => bundle
+                .packets
+                .iter()
+                .map(|bytes| Packet::from_bytes(bytes))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Converts from a byte array to a Packet, resizing the byte array if it is not at least 20 bytes long.
     pub fn from_bytes(bytes: &[u8]) -> Packet {
         let mut data: Vec<u8> = vec![0; DATA_BASE_SIZE.max(bytes.len())];
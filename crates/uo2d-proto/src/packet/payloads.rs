@@ -0,0 +1,1019 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::chunk::ChunkCoord;
+use crate::components::{
+    Ability, AreaShape, Bounds, EquipSlot, Equipment, ItemId, ItemStack, MovementMode, Stats,
+    StatusEffect, Team, Vec2, Vec3,
+};
+use crate::ecs::Entity;
+use crate::locale::Text;
+
+use super::Version;
+
+/// Message payload, only contains text.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessagePayload {
+    pub message: String,
+}
+
+impl MessagePayload {
+    /// Create a new message payload.
+    pub fn new(message: impl ToString) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Ping payload, used to send current ping UUID.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UuidPayload {
+    pub uuid: Uuid,
+}
+
+impl UuidPayload {
+    /// Create a new ping payload.
+    pub fn new(uuid: Uuid) -> Self {
+        Self { uuid }
+    }
+}
+
+/// Entity payload, used to send an Entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntityPayload {
+    pub entity: Entity,
+}
+
+impl EntityPayload {
+    /// Create a new entity payload.
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+}
+
+/// Movement payload, used to send current position for an entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MovementPayload {
+    pub entity: Entity,
+    pub size: Vec2,
+    pub position: Vec3,
+    pub velocity: Vec2,
+}
+
+impl MovementPayload {
+    /// Create a new position payload.
+    pub fn new(entity: Entity, size: Vec2, position: Vec3, velocity: Vec2) -> Self {
+        Self {
+            entity,
+            size,
+            position,
+            velocity,
+        }
+    }
+}
+
+/// Health payload, used to send a change in health for an entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthPayload {
+    pub entity: Entity,
+    pub amount: u32,
+    pub current: u32,
+}
+
+impl HealthPayload {
+    /// Create a new health payload.
+    pub fn new(entity: Entity, amount: u32, current: u32) -> Self {
+        Self {
+            entity,
+            amount,
+            current,
+        }
+    }
+}
+
+/// Category of a notification, used by clients to style it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum NotificationKind {
+    Announcement,
+    Join,
+    Leave,
+    Death,
+    /// Something the player tried was refused.
+    Warning,
+}
+
+/// Notification payload, transient events shown to players.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationPayload {
+    pub kind: NotificationKind,
+    pub message: Text,
+}
+
+impl NotificationPayload {
+    /// Create a new notification payload.
+    pub fn new(kind: NotificationKind, message: Text) -> Self {
+        Self { kind, message }
+    }
+}
+
+/// Gesture performed by an entity.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum EmoteKind {
+    Wave,
+    Dance,
+    Point,
+}
+
+impl EmoteKind {
+    /// Obtains the emote for a chat command, such as `/wave`.
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command.trim().to_lowercase().as_str() {
+            "/wave" => Some(EmoteKind::Wave),
+            "/dance" => Some(EmoteKind::Dance),
+            "/point" => Some(EmoteKind::Point),
+            _ => None,
+        }
+    }
+}
+
+/// Emote payload, an entity performing a gesture.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmotePayload {
+    pub entity: Entity,
+    pub kind: EmoteKind,
+}
+
+impl EmotePayload {
+    /// Create a new emote payload.
+    pub fn new(entity: Entity, kind: EmoteKind) -> Self {
+        Self { entity, kind }
+    }
+}
+
+/// Status effect payload, an effect starting or ending on an entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatusEffectPayload {
+    pub entity: Entity,
+    pub effect: StatusEffect,
+    pub active: bool,
+}
+
+impl StatusEffectPayload {
+    /// Create a new status effect payload.
+    pub fn new(entity: Entity, effect: StatusEffect, active: bool) -> Self {
+        Self {
+            entity,
+            effect,
+            active,
+        }
+    }
+}
+
+/// Movement mode payload, the way an entity has started moving.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MovementModePayload {
+    pub entity: Entity,
+    pub mode: MovementMode,
+}
+
+impl MovementModePayload {
+    /// Create a new movement mode payload.
+    pub fn new(entity: Entity, mode: MovementMode) -> Self {
+        Self { entity, mode }
+    }
+}
+
+/// Fall payload, the layers an entity is dropping between and how long it takes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct FallPayload {
+    pub entity: Entity,
+    pub from: f64,
+    pub to: f64,
+    /// Seconds until the entity lands.
+    pub duration: f32,
+}
+
+impl FallPayload {
+    /// Create a new fall payload.
+    pub fn new(entity: Entity, from: f64, to: f64, duration: f32) -> Self {
+        Self {
+            entity,
+            from,
+            to,
+            duration,
+        }
+    }
+}
+
+/// Target payload, the entity an NPC has turned its attention to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TargetPayload {
+    pub entity: Entity,
+    /// None once it has lost interest.
+    pub target: Option<Entity>,
+}
+
+impl TargetPayload {
+    /// Create a new target payload.
+    pub fn new(entity: Entity, target: Option<Entity>) -> Self {
+        Self { entity, target }
+    }
+}
+
+/// Reasons an attack on a locked target was refused.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackError {
+    InvalidTarget,
+    OutOfRange,
+    NotInSight,
+}
+
+impl AttackError {
+    /// Explanation shown to the player.
+    pub fn message(&self) -> Text {
+        Text::new(match self {
+            AttackError::InvalidTarget => "attack.invalid_target",
+            AttackError::OutOfRange => "attack.out_of_range",
+            AttackError::NotInSight => "attack.not_in_sight",
+        })
+    }
+}
+
+/// Area warning payload, where an ability is about to strike and how long it stays active.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct AreaWarningPayload {
+    pub entity: Entity,
+    pub shape: AreaShape,
+    pub center: Vec3,
+    /// Seconds until it strikes.
+    pub delay: f32,
+    /// Seconds it stays active after striking.
+    pub duration: f32,
+}
+
+impl AreaWarningPayload {
+    /// Create a new area warning payload.
+    pub fn new(entity: Entity, shape: AreaShape, center: Vec3, delay: f32, duration: f32) -> Self {
+        Self {
+            entity,
+            shape,
+            center,
+            delay,
+            duration,
+        }
+    }
+}
+
+/// Stage a cast has reached.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum CastStage {
+    /// Began, finishing after the seconds provided.
+    Started(f32),
+    Finished,
+    /// Stopped early by the caster moving or being hurt.
+    Interrupted,
+}
+
+/// Cast payload, the progress of an ability an entity is casting.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct CastPayload {
+    pub entity: Entity,
+    pub ability: Ability,
+    pub stage: CastStage,
+}
+
+impl CastPayload {
+    /// Create a new cast payload.
+    pub fn new(entity: Entity, ability: Ability, stage: CastStage) -> Self {
+        Self {
+            entity,
+            ability,
+            stage,
+        }
+    }
+}
+
+/// Version payload, what the server runs and the oldest client it accepts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionPayload {
+    pub server: Version,
+    pub protocol: u8,
+    pub min_client: Version,
+}
+
+impl VersionPayload {
+    /// Create a new version payload.
+    pub fn new(server: Version, protocol: u8, min_client: Version) -> Self {
+        Self {
+            server,
+            protocol,
+            min_client,
+        }
+    }
+
+    /// Explains what the client has to update to.
+    pub fn upgrade_hint(&self, client: Version) -> Text {
+        Text::new("version.upgrade")
+            .with("required", self.min_client)
+            .with("client", client)
+            .with("server", self.server)
+            .with("protocol", self.protocol)
+    }
+}
+
+/// Bundle payload, the serialized packets in the order they were queued.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundlePayload {
+    pub packets: Vec<Vec<u8>>,
+}
+
+impl BundlePayload {
+    /// Bytes added to a packet by placing it in a bundle.
+    pub const ENTRY_OVERHEAD: usize = 8;
+    /// Bytes of a bundle before any packets, the header, payload variant, and length.
+    pub const BASE_SIZE: usize = 19 + 4 + 8;
+
+    /// Create a new bundle payload.
+    pub fn new(packets: Vec<Vec<u8>>) -> Self {
+        Self { packets }
+    }
+}
+
+/// Reliable payload, a serialized packet numbered so it can be acknowledged.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReliablePayload {
+    pub sequence: u32,
+    pub packet: Vec<u8>,
+}
+
+impl ReliablePayload {
+    /// Bytes added to a packet by wrapping it, the header, payload variant, sequence, and length.
+    pub const OVERHEAD: usize = 19 + 4 + 4 + 8;
+
+    /// Create a new reliable payload.
+    pub fn new(sequence: u32, packet: Vec<u8>) -> Self {
+        Self { sequence, packet }
+    }
+}
+
+/// Ack payload, the sequence of the reliable packet that arrived.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct AckPayload {
+    pub sequence: u32,
+}
+
+impl AckPayload {
+    /// Create a new ack payload.
+    pub fn new(sequence: u32) -> Self {
+        Self { sequence }
+    }
+}
+
+/// Debug information a gamemaster can have drawn over the world.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverlayKind {
+    /// Bounding boxes of the entities.
+    Bounds,
+    /// Occupied cells of the spatial hash.
+    Cells,
+    /// Outlines of the regions.
+    Regions,
+    /// Distances the entities are seen from.
+    Interest,
+    /// Where the NPCs are heading.
+    Paths,
+}
+
+impl OverlayKind {
+    pub const ALL: [OverlayKind; 5] = [
+        OverlayKind::Bounds,
+        OverlayKind::Cells,
+        OverlayKind::Regions,
+        OverlayKind::Interest,
+        OverlayKind::Paths,
+    ];
+
+    /// Obtains the overlay from its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Name of the overlay.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OverlayKind::Bounds => "bounds",
+            OverlayKind::Cells => "cells",
+            OverlayKind::Regions => "regions",
+            OverlayKind::Interest => "interest",
+            OverlayKind::Paths => "paths",
+        }
+    }
+}
+
+/// Shape drawn by an overlay, in world coordinates rounded to whole units.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum OverlayShape {
+    /// Outline of a rectangle, its left, top, width, and height.
+    Rect([i32; 4]),
+    /// Line between two points.
+    Line([i32; 4]),
+    /// Outline of a circle, its center and radius.
+    Circle([i32; 3]),
+    /// Closed outline through the points.
+    Polygon(Vec<[i32; 2]>),
+}
+
+impl OverlayShape {
+    /// Rectangle covering the bounds.
+    pub fn rect(bounds: &Bounds) -> Self {
+        Self::Rect([
+            bounds.x() as i32,
+            bounds.y() as i32,
+            bounds.width() as i32,
+            bounds.height() as i32,
+        ])
+    }
+
+    /// Line between two points.
+    pub fn line(from: Vec2, to: Vec2) -> Self {
+        Self::Line([
+            from.x() as i32,
+            from.y() as i32,
+            to.x() as i32,
+            to.y() as i32,
+        ])
+    }
+
+    /// Circle around a point.
+    pub fn circle(center: Vec2, radius: f64) -> Self {
+        Self::Circle([center.x() as i32, center.y() as i32, radius as i32])
+    }
+
+    /// Polygon through the points, in order.
+    pub fn polygon(points: &[Vec3]) -> Self {
+        Self::Polygon(
+            points
+                .iter()
+                .map(|p| [p.x() as i32, p.y() as i32])
+                .collect(),
+        )
+    }
+}
+
+/// Overlay payload, the shapes of one kind of overlay around the gamemaster.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OverlayPayload {
+    pub kind: OverlayKind,
+    pub shapes: Vec<OverlayShape>,
+    /// Bytes the shapes take once serialized.
+    #[serde(skip)]
+    size: usize,
+}
+
+impl OverlayPayload {
+    /// Most bytes of shapes carried, keeping the packet within a single datagram.
+    pub const MAX_SIZE: usize = super::MAX_DATAGRAM - 64;
+
+    /// Create a new overlay payload without any shapes.
+    pub fn new(kind: OverlayKind) -> Self {
+        Self {
+            kind,
+            shapes: Vec::new(),
+            size: 0,
+        }
+    }
+
+    /// Adds a shape, returning false once there is no room left for it.
+    pub fn push(&mut self, shape: OverlayShape) -> bool {
+        let size = bincode::serialized_size(&shape).unwrap_or(u64::MAX) as usize;
+        if self.size.saturating_add(size) > Self::MAX_SIZE {
+            return false;
+        }
+
+        self.size += size;
+        self.shapes.push(shape);
+        true
+    }
+}
+
+/// Overlay request, the kinds of overlay a gamemaster wants drawn. Empty turns them off.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OverlayRequest {
+    pub kinds: Vec<OverlayKind>,
+}
+
+impl OverlayRequest {
+    /// Create a new overlay request.
+    pub fn new(kinds: Vec<OverlayKind>) -> Self {
+        Self { kinds }
+    }
+}
+
+/// Mail payload, a message left for a player that is delivered even while they are offline.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MailPayload {
+    pub recipient: String,
+    pub message: String,
+}
+
+impl MailPayload {
+    /// Create a new mail payload.
+    pub fn new(recipient: impl ToString, message: impl ToString) -> Self {
+        Self {
+            recipient: recipient.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Obtains the mail from a chat command, such as `/mail name message`.
+    pub fn from_command(command: &str) -> Option<Self> {
+        let (name, rest) = command.trim().split_once(char::is_whitespace)?;
+        if !name.eq_ignore_ascii_case("/mail") {
+            return None;
+        }
+
+        let (recipient, message) = rest.trim().split_once(char::is_whitespace)?;
+        Some(Self::new(recipient, message.trim()))
+    }
+}
+
+/// A message waiting in a mailbox.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Letter {
+    pub sender: String,
+    pub message: String,
+    /// Time the letter was sent, in UTC.
+    pub sent: String,
+    pub read: bool,
+}
+
+/// Mailbox payload, how many letters are unread along with the most recent letters when requested.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MailboxPayload {
+    pub unread: u32,
+    pub letters: Option<Vec<Letter>>,
+}
+
+impl MailboxPayload {
+    /// Create a new mailbox payload.
+    pub fn new(unread: u32, letters: Option<Vec<Letter>>) -> Self {
+        Self { unread, letters }
+    }
+}
+
+/// Credentials payload, used to register or login to an account.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CredentialsPayload {
+    pub username: String,
+    pub password: String,
+    pub server_password: Option<String>,
+}
+
+impl CredentialsPayload {
+    /// Create a new credentials payload.
+    pub fn new(
+        username: impl ToString,
+        password: impl ToString,
+        server_password: Option<String>,
+    ) -> Self {
+        Self {
+            username: username.to_string(),
+            password: password.to_string(),
+            server_password,
+        }
+    }
+}
+
+/// Join payload, used to join as a guest.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JoinPayload {
+    pub server_password: Option<String>,
+}
+
+impl JoinPayload {
+    /// Create a new join payload.
+    pub fn new(server_password: Option<String>) -> Self {
+        Self { server_password }
+    }
+}
+
+/// Redirect payload, sends the client to the server that now owns their player.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedirectPayload {
+    pub address: String,
+    /// Presented to the new server to claim the player.
+    pub token: Uuid,
+}
+
+impl RedirectPayload {
+    /// Create a new redirect payload.
+    pub fn new(address: impl ToString, token: Uuid) -> Self {
+        Self {
+            address: address.to_string(),
+            token,
+        }
+    }
+}
+
+/// Dialogue payload, a page of what an NPC is saying. Choices are only sent with the last page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DialoguePayload {
+    pub entity: Entity,
+    /// Name of the NPC speaking.
+    pub speaker: Text,
+    pub text: String,
+    pub page: u8,
+    pub pages: u8,
+    pub choices: Vec<String>,
+}
+
+impl DialoguePayload {
+    /// Create a new dialogue payload.
+    pub fn new(
+        entity: Entity,
+        speaker: Text,
+        text: impl ToString,
+        page: u8,
+        pages: u8,
+        choices: Vec<String>,
+    ) -> Self {
+        Self {
+            entity,
+            speaker,
+            text: text.to_string(),
+            page,
+            pages,
+            choices,
+        }
+    }
+
+    /// Checks if this is the last page, where the choices are made.
+    pub fn is_last(&self) -> bool {
+        self.page + 1 >= self.pages
+    }
+}
+
+/// Response from a player to the NPC they are talking to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DialogueReply {
+    /// Starts talking to the closest NPC.
+    Talk,
+    /// Shows another page of the current text.
+    Page(u8),
+    /// Picks one of the choices offered on the last page.
+    Choice(u8),
+    Close,
+}
+
+/// Spawn payload, used when an entity enters the world with its appearance.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpawnPayload {
+    pub movement: MovementPayload,
+    pub appearance: Equipment,
+    pub team: Option<Team>,
+}
+
+impl SpawnPayload {
+    /// Create a new spawn payload.
+    pub fn new(movement: MovementPayload, appearance: Equipment, team: Option<Team>) -> Self {
+        Self {
+            movement,
+            appearance,
+            team,
+        }
+    }
+}
+
+/// Equip payload, used to equip an item or empty a slot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EquipPayload {
+    pub slot: EquipSlot,
+    pub item: Option<ItemId>,
+}
+
+impl EquipPayload {
+    /// Create a new equip payload.
+    pub fn new(slot: EquipSlot, item: Option<ItemId>) -> Self {
+        Self { slot, item }
+    }
+}
+
+/// Inventory payload, used to send the items being carried.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InventoryPayload {
+    pub stacks: Vec<ItemStack>,
+    pub capacity: u32,
+    pub equipment: Equipment,
+}
+
+impl InventoryPayload {
+    /// Create a new inventory payload.
+    pub fn new(stacks: Vec<ItemStack>, capacity: u32, equipment: Equipment) -> Self {
+        Self {
+            stacks,
+            capacity,
+            equipment,
+        }
+    }
+}
+
+/// Stack payload, used to split or drop part of an inventory stack.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StackPayload {
+    pub index: u16,
+    pub count: u16,
+}
+
+impl StackPayload {
+    /// Create a new stack payload.
+    pub fn new(index: u16, count: u16) -> Self {
+        Self { index, count }
+    }
+}
+
+/// Move stack payload, used to move a stack from one inventory slot to another.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MoveStackPayload {
+    pub from: u16,
+    pub to: u16,
+}
+
+impl MoveStackPayload {
+    /// Create a new move stack payload.
+    pub fn new(from: u16, to: u16) -> Self {
+        Self { from, to }
+    }
+}
+
+/// Appearance payload, used to send the visible equipment of an entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppearancePayload {
+    pub entity: Entity,
+    pub appearance: Equipment,
+    pub team: Option<Team>,
+    pub mounted: bool,
+}
+
+impl AppearancePayload {
+    /// Create a new appearance payload.
+    pub fn new(entity: Entity, appearance: Equipment, team: Option<Team>, mounted: bool) -> Self {
+        Self {
+            entity,
+            appearance,
+            team,
+            mounted,
+        }
+    }
+}
+
+/// Ground item payload, used to show a stack of items lying on the ground.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GroundItemPayload {
+    pub entity: Entity,
+    pub stack: ItemStack,
+    pub bounds: Bounds,
+}
+
+impl GroundItemPayload {
+    /// Create a new ground item payload.
+    pub fn new(entity: Entity, stack: ItemStack, bounds: Bounds) -> Self {
+        Self {
+            entity,
+            stack,
+            bounds,
+        }
+    }
+}
+
+/// Keyframe payload, a full movement update that later deltas are relative to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyframePayload {
+    pub keyframe: u8,
+    pub movement: MovementPayload,
+}
+
+impl KeyframePayload {
+    /// Create a new keyframe payload.
+    pub fn new(keyframe: u8, movement: MovementPayload) -> Self {
+        Self { keyframe, movement }
+    }
+}
+
+/// Movement delta payload, a quantized offset from the last keyframe of an entity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MovementDeltaPayload {
+    pub entity: Entity,
+    pub keyframe: u8,
+    pub offset: [i16; 2],
+    pub velocity: [i16; 2],
+}
+
+impl MovementDeltaPayload {
+    /// Create a new movement delta payload.
+    pub fn new(entity: Entity, keyframe: u8, offset: [i16; 2], velocity: [i16; 2]) -> Self {
+        Self {
+            entity,
+            keyframe,
+            offset,
+            velocity,
+        }
+    }
+}
+
+/// Chunk payload, the region of every tile within a chunk of the map.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChunkPayload {
+    pub coord: ChunkCoord,
+    pub tiles: Vec<u8>,
+}
+
+impl ChunkPayload {
+    /// Create a new chunk payload.
+    pub fn new(coord: ChunkCoord, tiles: Vec<u8>) -> Self {
+        Self { coord, tiles }
+    }
+}
+
+/// A player's placing on the leaderboard.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub stats: Stats,
+}
+
+/// Leaderboard payload, the top players ranked by their stats.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeaderboardPayload {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl LeaderboardPayload {
+    /// Create a new leaderboard payload.
+    pub fn new(entries: Vec<LeaderboardEntry>) -> Self {
+        Self { entries }
+    }
+}
+
+/// Where a flag currently is.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum FlagStatus {
+    Home,
+    Carried,
+    Dropped,
+}
+
+/// State of a single flag shown on the HUD and in the world.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FlagState {
+    pub team: Team,
+    pub status: FlagStatus,
+    pub bounds: Bounds,
+}
+
+/// HUD payload, the state of the active game mode.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum HudPayload {
+    /// Capture the flag, scores for each team and where their flags are.
+    Ctf {
+        scores: Vec<(Team, u32)>,
+        flags: Vec<FlagState>,
+    },
+}
+
+/// Stage of a match, played in order before returning to the lobby.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum MatchPhase {
+    /// Waiting for enough players to join.
+    Lobby,
+    /// Players are frozen in place until the round begins.
+    Countdown,
+    /// The round is being played.
+    Active,
+    /// Results are shown before the next round.
+    Ended,
+}
+
+/// Match payload, the current phase of the match and how long is left in it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MatchPayload {
+    pub phase: MatchPhase,
+    /// Seconds left in the phase, zero while it has no time limit.
+    pub remaining: u64,
+}
+
+impl MatchPayload {
+    /// Create a new match payload.
+    pub fn new(phase: MatchPhase, remaining: u64) -> Self {
+        Self { phase, remaining }
+    }
+}
+
+/// Requests a player makes while trading with another.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeRequest {
+    /// Invites a player to trade, or accepts their invitation.
+    Invite(Entity),
+    /// Accepts the invitation from whoever last invited the player.
+    Accept,
+    /// Adds items from an inventory slot to the offer.
+    Offer {
+        slot: u16,
+        count: u16,
+    },
+    /// Takes a stack back out of the offer.
+    Withdraw(u16),
+    /// Locks the offer so it can no longer change, or unlocks it again.
+    Lock(bool),
+    /// Agrees to the trade, only once both offers are locked.
+    Confirm,
+    Cancel,
+}
+
+/// Stage of a trade.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeState {
+    /// Waiting for the other player to accept the invitation.
+    Waiting,
+    /// Invited by the other player, waiting to be accepted.
+    Invited,
+    /// Both players are making their offers.
+    Open,
+    /// The offers were exchanged.
+    Completed,
+    Cancelled,
+}
+
+/// Items one side of a trade has offered.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TradeOffer {
+    pub items: Vec<ItemStack>,
+    pub locked: bool,
+    pub confirmed: bool,
+}
+
+/// Trade payload, the trade as seen by one of the players in it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TradePayload {
+    /// Name of the other player.
+    pub partner: String,
+    pub state: TradeState,
+    pub mine: TradeOffer,
+    pub theirs: TradeOffer,
+}
+
+impl TradePayload {
+    /// Create a new trade payload.
+    pub fn new(
+        partner: impl ToString,
+        state: TradeState,
+        mine: TradeOffer,
+        theirs: TradeOffer,
+    ) -> Self {
+        Self {
+            partner: partner.to_string(),
+            state,
+            mine,
+            theirs,
+        }
+    }
+
+    /// Checks if the trade is over, either exchanged or cancelled.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.state, TradeState::Completed | TradeState::Cancelled)
+    }
+}
+
+/// Requests a player makes of their bank.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankRequest {
+    /// Opens the bank, only while near a banker or within a bank.
+    Open,
+    Close,
+    /// Moves items from an inventory slot into the bank.
+    Deposit {
+        slot: u16,
+        count: u16,
+    },
+    /// Moves items from a bank slot into the inventory.
+    Withdraw {
+        slot: u16,
+        count: u16,
+    },
+}
+
+/// Bank payload, the contents of a player's bank while it is open.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BankPayload {
+    pub stacks: Vec<ItemStack>,
+    pub capacity: u32,
+}
+
+impl BankPayload {
+    /// Create a new bank payload.
+    pub fn new(stacks: Vec<ItemStack>, capacity: u32) -> Self {
+        Self { stacks, capacity }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::components::Vec2;
+
+/// Number of steps each world unit is divided into when quantized.
+pub const PRECISION: f64 = 16.0;
+
+/// Packs a vector into fixed precision, None if it does not fit within an i16.
+pub fn quantize(vec: &Vec2) -> Option<[i16; 2]> {
+    let pack = |value: f64| {
+        let value = (value * PRECISION).round();
+        if value < i16::MIN as f64 || value > i16::MAX as f64 {
+            None
+        } else {
+            Some(value as i16)
+        }
+    };
+
+    Some([pack(vec.x())?, pack(vec.y())?])
+}
+
+/// Unpacks a quantized vector back into world units.
+pub fn dequantize(value: [i16; 2]) -> Vec2 {
+    Vec2::new(value[0] as f64 / PRECISION, value[1] as f64 / PRECISION)
+}
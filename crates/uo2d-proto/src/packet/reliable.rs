@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::payloads::{AckPayload, ReliablePayload};
+use super::{Action, Packet, Payload, MAX_DATAGRAM};
+use crate::time::{Duration, Instant};
+
+/// Reliable packet waiting to be acknowledged.
+struct Unacked {
+    packet: Packet,
+    /// When it was last sent.
+    sent: Instant,
+    attempts: u32,
+}
+
+/// Packet received through the reliability layer.
+#[derive(Debug, Default)]
+pub struct Received {
+    /// Packet to handle, None for acknowledgements and duplicates.
+    pub packet: Option<Packet>,
+    /// Acknowledgement to send back, even for duplicates in case the first was lost.
+    pub ack: Option<Packet>,
+}
+
+/// Ensures the critical packets sent over a connection arrive, used by both of its ends.
+/// They are numbered and sent again until acknowledged, those arriving twice are dropped.
+/// Sequences wrap around, compared as serial numbers (RFC 1982).
+#[derive(Default)]
+pub struct Reliability {
+    /// Sequence of the next packet sent.
+    next: u32,
+    unacked: BTreeMap<u32, Unacked>,
+    /// Every sequence below it has been received.
+    contiguous: u32,
+    /// Sequences received past a gap, by their distance from it.
+    ahead: BTreeSet<u32>,
+    /// Packets given up on after being sent too many times.
+    lost: u64,
+}
+
+impl Reliability {
+    /// Time an acknowledgement is waited on before the packet is sent again.
+    pub const RESEND_AFTER: Duration = Duration::from_millis(250);
+    /// Times a packet is sent before it is given up on.
+    pub const MAX_ATTEMPTS: u32 = 20;
+    /// Sequences remembered past a gap, gaps left by packets the sender gave up on are skipped
+    /// once it is exceeded.
+    const WINDOW: usize = 1024;
+    /// Furthest past the gap a sequence is accepted, those beyond are refused rather than
+    /// jumped to.
+    const SPAN: u32 = 2 * Self::WINDOW as u32;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks if packets of the action must arrive, instead of being superseded by later ones.
+    pub fn requires(action: Option<Action>) -> bool {
+        matches!(
+            action,
+            Some(
+                Action::ClientJoin
+                    | Action::ClientLeave
+                    | Action::Success
+                    | Action::Register
+                    | Action::Login
+                    | Action::Message
+                    | Action::Notification
+                    | Action::Equip
+                    | Action::Unequip
+                    | Action::Inventory
+                    | Action::SplitStack
+                    | Action::Drop
+                    | Action::MoveStack
+                    | Action::UseItem
+                    | Action::Mail
+                    | Action::Handoff
+                    | Action::Dialogue
+                    | Action::Trade
+                    | Action::Bank
+                    | Action::Keyframe
+            )
+        )
+    }
+
+    /// Wraps the packet if its action must arrive, otherwise it is returned as is.
+    pub fn prepare(&mut self, packet: Packet, now: Instant) -> Packet {
+        match Self::requires(packet.action()) {
+            true => self.wrap(packet, now),
+            false => packet,
+        }
+    }
+
+    /// Numbers a packet, keeping it to be sent again until it is acknowledged.
+    /// Packets too large to be wrapped are returned as is, without the guarantee.
+    pub fn wrap(&mut self, packet: Packet, now: Instant) -> Packet {
+        if packet.as_bytes().len() + ReliablePayload::OVERHEAD > MAX_DATAGRAM {
+            return packet;
+        }
+
+        let sequence = self.next;
+        self.next = self.next.wrapping_add(1);
+        let wrapped = Packet::new(
+            Action::Reliable,
+            packet.uuid(),
+            Payload::Reliable(ReliablePayload::new(sequence, packet.to_bytes())),
+        );
+        self.unacked.insert(
+            sequence,
+            Unacked {
+                packet: wrapped.clone(),
+                sent: now,
+                attempts: 1,
+            },
+        );
+        wrapped
+    }
+
+    /// Unwraps a received packet, acknowledging it if it was reliable.
+    /// Acknowledgements stop the packets they are for from being sent again.
+    /// Reliable packets too far ahead of those received are dropped without an acknowledgement.
+    pub fn receive(&mut self, packet: Packet) -> Received {
+        match packet.action() {
+            Some(Action::Ack) => {
+                if let Payload::Ack(ack) = packet.payload() {
+                    self.unacked.remove(&ack.sequence);
+                }
+                Received::default()
+            }
+            Some(Action::Reliable) => {
+                let Payload::Reliable(reliable) = packet.payload() else {
+                    return Received::default();
+                };
+
+                let Some(first) = self.record(reliable.sequence) else {
+                    return Received::default();
+                };
+                let ack = Packet::new(
+                    Action::Ack,
+                    packet.uuid(),
+                    Payload::Ack(AckPayload::new(reliable.sequence)),
+                );
+                Received {
+                    packet: first.then(|| Packet::from_bytes(&reliable.packet)),
+                    ack: Some(ack),
+                }
+            }
+            _ => Received {
+                packet: Some(packet),
+                ack: None,
+            },
+        }
+    }
+
+    /// Remembers a sequence was received, returning if it is the first time.
+    /// None if it is too far ahead to be accepted.
+    fn record(&mut self, sequence: u32) -> Option<bool> {
+        let distance = sequence.wrapping_sub(self.contiguous);
+        if (distance as i32) < 0 {
+            // Behind the gap, already received.
+            return Some(false);
+        } else if distance >= Self::SPAN {
+            return None;
+        } else if !self.ahead.insert(distance) {
+            return Some(false);
+        }
+
+        while self.ahead.len() > Self::WINDOW {
+            if let Some(oldest) = self.ahead.first().copied() {
+                self.skip(oldest);
+            }
+        }
+        self.skip(0);
+        Some(true)
+    }
+
+    /// Moves the gap forward by the distance, then past the sequences received without a gap.
+    fn skip(&mut self, distance: u32) {
+        let mut moved = distance;
+        while self.ahead.contains(&moved) {
+            moved += 1;
+        }
+        if moved == 0 {
+            return;
+        }
+
+        self.contiguous = self.contiguous.wrapping_add(moved);
+        self.ahead = self
+            .ahead
+            .iter()
+            .filter_map(|ahead| ahead.checked_sub(moved))
+            .collect();
+    }
+
+    /// Packets left unacknowledged for too long, to be sent again.
+    /// Those already sent too many times are given up on.
+    pub fn resend(&mut self, now: Instant) -> Vec<Packet> {
+        let mut resend = vec![];
+        let mut lost = 0;
+        self.unacked.retain(|_, unacked| {
+            if now.duration_since(unacked.sent) < Self::RESEND_AFTER {
+                return true;
+            } else if unacked.attempts >= Self::MAX_ATTEMPTS {
+                lost += 1;
+                return false;
+            }
+
+            unacked.sent = now;
+            unacked.attempts += 1;
+            resend.push(unacked.packet.clone());
+            true
+        });
+
+        self.lost += lost;
+        resend
+    }
+
+    /// Packets sent that are still waiting to be acknowledged.
+    pub fn pending(&self) -> usize {
+        self.unacked.len()
+    }
+
+    /// Packets given up on after being sent too many times.
+    pub fn lost(&self) -> u64 {
+        self.lost
+    }
+}
@@ -0,0 +1,20 @@
+/// Completes once the process is asked to stop, SIGINT or SIGTERM on Unix and Ctrl-C elsewhere.
+#[cfg(unix)]
+pub async fn signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigint = signal(SignalKind::interrupt()).expect("Failed to bind SIGINT handler");
+    let mut sigterm = signal(SignalKind::terminate()).expect("Failed to bind SIGTERM handler");
+    tokio::select! {
+        _ = sigint.recv() => (),
+        _ = sigterm.recv() => (),
+    }
+}
+
+/// Completes once the process is asked to stop, SIGINT or SIGTERM on Unix and Ctrl-C elsewhere.
+#[cfg(not(unix))]
+pub async fn signal() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("Failed to bind Ctrl-C handler");
+}
@@ -0,0 +1,4 @@
+//! Clocks shared by every target, the standard ones are unavailable in browsers.
+//! Elsewhere these are the same types as in `std::time`.
+
+pub use web_time::{Duration, Instant, SystemTime, UNIX_EPOCH};
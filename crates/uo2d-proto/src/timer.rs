@@ -1,12 +1,24 @@
-use std::time::{Duration, Instant};
-
 use crate::ecs::Entity;
+use crate::time::{Duration, Instant};
 
 /// Data that is attached to the timer.
 #[derive(Debug)]
 pub enum TimerData {
     Empty,
     EntityDelete(Entity),
+    ItemDecay(Entity),
+    MatchPhase,
+    Announcement(u32),
+    /// A spawner replaces one of its NPCs.
+    Respawn(Entity),
+    /// A depleted resource node can be gathered from again.
+    Regrow(Entity),
+    /// The database is due to be backed up.
+    Backup,
+    /// Gameplay metrics are due to be reported.
+    Telemetry,
+    /// A timer registered by a plugin, by its id.
+    Plugin(usize),
 }
 
 /// Allows for tracking of various time sensitive events.
@@ -47,8 +59,14 @@ pub struct TimerManager {
     tick_start: Instant,
 }
 
+impl Default for TimerManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TimerManager {
-    const SERVER_TICKS_PER_SECOND: f32 = 10.0;
+    pub const SERVER_TICKS_PER_SECOND: f32 = 10.0;
     const SERVER_TICK_RATE_MICROSECOND: f32 = 1_000_000.0 / Self::SERVER_TICKS_PER_SECOND;
 
     const CLIENT_TICKS_PER_SECOND: f32 = Self::SERVER_TICKS_PER_SECOND * 3.0;
@@ -65,6 +83,14 @@ impl TimerManager {
         }
     }
 
+    /// Creates a new manager on the same tick as another, keeping the two in step.
+    pub fn synced(other: &TimerManager) -> Self {
+        Self {
+            tick: other.tick,
+            ..Self::new()
+        }
+    }
+
     /// Current tick the server is on.
     #[allow(dead_code)]
     pub fn tick(&self) -> u64 {
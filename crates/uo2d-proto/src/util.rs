@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use chrono::Utc;
+
+use crate::time::{SystemTime, UNIX_EPOCH};
+
+/// Highest level of output that is printed.
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+#[macro_export]
+macro_rules! sprintln {
+    ($($arg:tt)*) => {{
+        // Recorded even when not printed, for crash reports.
+        let line = format!("[{} SERVER] {}", $crate::util::get_utc(), format_args!($($arg)*));
+        $crate::crash::record(&line);
+        if $crate::util::log_enabled($crate::util::LogLevel::Info) {
+            println!("{}", line)
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! cprintln {
+    ($($arg:tt)*) => {{
+        // Recorded even when not printed, for crash reports.
+        let line = format!("[{} CLIENT] {}", $crate::util::get_utc(), format_args!($($arg)*));
+        $crate::crash::record(&line);
+        if $crate::util::log_enabled($crate::util::LogLevel::Info) {
+            println!("{}", line)
+        }
+    }};
+}
+
+/// Amount of output printed to the console.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum LogLevel {
+    Off,
+    Info,
+}
+
+/// Sets the highest level of output that is printed.
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Checks if output at a level is printed.
+pub fn log_enabled(level: LogLevel) -> bool {
+    level as u8 <= LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+/// UTC ISO 8601 formatted string.
+pub fn get_utc() -> String {
+    let now = Utc::now();
+    now.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// Current system time as u64.
+pub fn get_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Taste the rainbow.
+#[allow(dead_code)]
+pub fn exec_rainbow((r, g, b): (u8, u8, u8), step: u8) -> (u8, u8, u8) {
+    if r == 255 && g < 255 && b == 0 {
+        return (255, g.saturating_add(step), 0);
+    } else if g == 255 && r > 0 && b == 0 {
+        return (r.saturating_sub(step), 255, 0);
+    } else if g == 255 && b < 255 {
+        return (0, 255, b.saturating_add(step));
+    } else if b == 255 && g > 0 {
+        return (0, g.saturating_sub(step), 255);
+    } else if b == 255 && r < 255 {
+        return (r.saturating_add(step), 0, 255);
+    } else if r == 255 && b > 0 {
+        return (255, 0, b.saturating_sub(step));
+    } else if r == 255 && g == 0 && b == 0 {
+        return (255, step.min(255), 0);
+    }
+    (255, 0, 0)
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use uo2d_proto::components::{Health, Position, Vec2, Vec3, Velocity};
+use uo2d_proto::ecs::{Entity, World};
+
+fn world(grouped: bool) -> World {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    world.register_component::<Health>();
+    if grouped {
+        world.group::<Position, Velocity>();
+    }
+    world
+}
+
+fn position(i: usize) -> Position {
+    Position::new(Vec3::new(i as f64, 0., 1.), Vec2::new(8., 8.))
+}
+
+/// Entities matched by the pair query, in either order.
+fn matched(world: &World) -> (HashSet<Entity>, HashSet<Entity>) {
+    let forward = world
+        .query2::<Position, Velocity>()
+        .into_iter()
+        .map(|(entity, pos, vel)| {
+            let stored = world.get_component::<Position>(&entity).map(|pos| pos.loc);
+            assert_eq!(stored, Some(pos.loc));
+            let stored = world.get_component::<Velocity>(&entity).map(|vel| vel.0);
+            assert_eq!(stored, Some(vel.0));
+            entity
+        })
+        .collect();
+    let backward = world
+        .query2::<Velocity, Position>()
+        .into_iter()
+        .map(|(entity, _, _)| entity)
+        .collect();
+    (forward, backward)
+}
+
+#[test]
+fn grouped_queries_match_ungrouped() {
+    let mut worlds = [world(false), world(true)];
+    for world in worlds.iter_mut() {
+        let mut entities = vec![];
+        for i in 0..50 {
+            let mut builder = world.spawn().with(position(i));
+            if i % 2 == 0 {
+                builder = builder.with(Velocity(Vec2::new(1., 0.)));
+            }
+            if i % 3 == 0 {
+                builder = builder.with(Health::new(10));
+            }
+            entities.push(builder.build());
+        }
+
+        // Churn the group by adding, replacing, and removing members.
+        for (i, entity) in entities.iter().enumerate() {
+            match i % 5 {
+                0 => world.remove_component::<Velocity>(*entity),
+                1 => world.upsert_component(*entity, Velocity(Vec2::new(0., 1.))),
+                2 => world.upsert_component(*entity, position(i + 100)),
+                3 => world.despawn(entity),
+                _ => {}
+            }
+        }
+    }
+
+    let [plain, grouped] = &worlds;
+    let (forward, backward) = matched(grouped);
+    assert_eq!(forward, backward);
+    assert_eq!(forward, matched(plain).0);
+
+    let plain: HashSet<Entity> = plain
+        .query3::<Position, Velocity, Health>()
+        .into_iter()
+        .map(|(entity, _, _, _)| entity)
+        .collect();
+    let grouped: HashSet<Entity> = grouped
+        .query3::<Health, Velocity, Position>()
+        .into_iter()
+        .map(|(entity, _, _, _)| entity)
+        .collect();
+    assert_eq!(plain, grouped);
+}
+
+#[test]
+fn transferred_entities_join_the_group() {
+    let mut from = world(false);
+    let mut to = world(true);
+    let entity = from
+        .spawn()
+        .with(position(0))
+        .with(Velocity(Vec2::new(1., 1.)))
+        .build();
+
+    let moved = from.transfer(&entity, &mut to);
+    assert!(from.query2::<Position, Velocity>().is_empty());
+    assert_eq!(matched(&to).0, HashSet::from([moved]));
+}
+
+#[test]
+fn pooled_entities_are_reused_after_their_cooldown() {
+    let mut world = world(true);
+    world.register_pool("projectile", 10);
+
+    let first = world
+        .spawn_pooled("projectile", 0)
+        .with(position(0))
+        .with(Velocity(Vec2::new(1., 0.)))
+        .build();
+    world.release("projectile", &first, 5);
+    world.release("projectile", &first, 6);
+    assert!(world.get_component::<Position>(&first).is_none());
+
+    // Still cooling down, a new id is allocated.
+    let second = world
+        .spawn_pooled("projectile", 14)
+        .with(position(1))
+        .build();
+    assert_ne!(first, second);
+
+    let third = world
+        .spawn_pooled("projectile", 15)
+        .with(position(2))
+        .with(Velocity(Vec2::new(0., 1.)))
+        .build();
+    assert_eq!(first, third);
+    assert_eq!(world.recycled_components(), 2);
+    assert_eq!(matched(&world).0, HashSet::from([third]));
+
+    let stats = world.pool_stats()[0].1;
+    assert_eq!((stats.allocated, stats.reused, stats.released), (2, 1, 1));
+    assert_eq!(stats.free, 0);
+}
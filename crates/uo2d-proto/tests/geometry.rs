@@ -0,0 +1,100 @@
+use proptest::prelude::*;
+use uo2d_proto::components::{sort_coordinates_clockwise, Bounds, Transform, Vec3};
+
+/// Coordinates on a quarter grid, keeping the float math exact enough to compare.
+fn coord() -> impl Strategy<Value = f64> {
+    (-4000i32..4000).prop_map(|value| value as f64 / 4.)
+}
+
+fn length() -> impl Strategy<Value = f64> {
+    (1i32..2000).prop_map(|value| value as f64 / 4.)
+}
+
+fn bounds() -> impl Strategy<Value = Bounds> {
+    (coord(), coord(), length(), length())
+        .prop_map(|(x, y, width, height)| Bounds::new(x, y, 1., width, height))
+}
+
+fn vertex() -> impl Strategy<Value = Vec3> {
+    (coord(), coord()).prop_map(|(x, y)| Vec3::new(x, y, 0.))
+}
+
+proptest! {
+    #[test]
+    fn intersection_is_symmetric(a in bounds(), b in bounds()) {
+        prop_assert_eq!(a.intersects_2d(&b), b.intersects_2d(&a));
+        prop_assert_eq!(a.intersects_3d(&b), b.intersects_3d(&a));
+    }
+
+    #[test]
+    fn bounds_contain_and_intersect_themselves(a in bounds()) {
+        prop_assert!(a.contains_2d(&a));
+        prop_assert!(a.intersects_2d(&a));
+    }
+
+    #[test]
+    fn contained_bounds_intersect(a in bounds(), b in bounds()) {
+        if a.contains_2d(&b) {
+            prop_assert!(a.intersects_2d(&b));
+            prop_assert!(b.width() <= a.width() && b.height() <= a.height());
+        }
+    }
+
+    #[test]
+    fn clamping_stays_within_bounds(area in bounds(), other in bounds()) {
+        let clamped = area.clamp_within(&other);
+        prop_assert_eq!(clamped.dimensions(), other.dimensions());
+        if other.width() <= area.width() && other.height() <= area.height() {
+            prop_assert!(area.contains_2d(&clamped));
+        } else {
+            // Too large to fit, left where it was.
+            prop_assert_eq!(clamped.top_left_3d(), other.top_left_3d());
+        }
+    }
+
+    #[test]
+    fn clamped_coordinates_stay_within_bounds(area in bounds(), point in vertex()) {
+        let clamped = area.clamp_coord_within(point);
+        prop_assert!(area.coord_within_2d(&clamped));
+        if area.coord_within_2d(&point) {
+            prop_assert_eq!(clamped, point);
+        }
+    }
+
+    #[test]
+    fn scaling_keeps_the_center(a in bounds(), scalar in 1i32..16) {
+        let scaled = a.scaled_center(scalar as f64 / 4.);
+        prop_assert_eq!(scaled.center_2d(), a.center_2d());
+    }
+
+    #[test]
+    fn transform_intersection_matches_bounds(a in bounds(), b in bounds()) {
+        let (ta, tb) = (Transform::from_bounds(a), Transform::from_bounds(b));
+        prop_assert_eq!(ta.intersects(&tb), tb.intersects(&ta));
+        prop_assert_eq!(ta.intersects(&tb), a.intersects_3d(&b));
+    }
+
+    #[test]
+    fn transform_contains_its_center(a in bounds()) {
+        let center = a.center_2d();
+        let transform = Transform::from_bounds(a);
+        prop_assert!(transform.coord_within(&Vec3::new(center.x(), center.y(), a.z())));
+    }
+
+    #[test]
+    fn sorting_keeps_every_vertex(vertices in prop::collection::vec(vertex(), 1..12)) {
+        let sorted = sort_coordinates_clockwise(&vertices);
+        prop_assert_eq!(sorted.len(), vertices.len());
+        for vertex in &vertices {
+            let expected = vertices.iter().filter(|other| *other == vertex).count();
+            let found = sorted.iter().filter(|other| *other == vertex).count();
+            prop_assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn sorting_is_stable_once_sorted(vertices in prop::collection::vec(vertex(), 1..12)) {
+        let sorted = sort_coordinates_clockwise(&vertices);
+        prop_assert_eq!(sort_coordinates_clockwise(&sorted), sorted);
+    }
+}
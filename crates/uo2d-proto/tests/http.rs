@@ -0,0 +1,40 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+use uo2d_proto::http;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Answers a single request with the response, returning the address to request.
+fn serve(response: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 1024];
+        let _ = stream.read(&mut request);
+        let _ = stream.write_all(response.as_bytes());
+    });
+    format!("http://{}/manifest.yaml", address)
+}
+
+#[test]
+fn bodies_are_fetched_from_successful_responses() {
+    let url = serve("HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\nversion: \"1.2.3\"\n");
+    let body = http::get(&url, TIMEOUT, 1024).unwrap();
+    assert_eq!(body, "version: \"1.2.3\"\n");
+}
+
+#[test]
+fn failed_and_oversized_responses_are_refused() {
+    let url = serve("HTTP/1.0 404 Not Found\r\n\r\nmissing");
+    let why = http::get(&url, TIMEOUT, 1024).unwrap_err();
+    assert!(why.to_string().contains("404"), "{}", why);
+
+    let url = serve("HTTP/1.0 200 OK\r\n\r\nfar too long to fit");
+    assert!(http::get(&url, TIMEOUT, 16).is_err());
+
+    assert!(http::get("https://example.com/", TIMEOUT, 1024).is_err());
+}
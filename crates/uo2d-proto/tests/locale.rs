@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::{Locale, Text};
+
+/// Assets shipped with the game, found regardless of the working directory.
+const ASSETS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+
+#[test]
+fn text_is_translated_with_its_parameters() {
+    let english = Locale::parse("[plot]\nowned = \"You now own {plot}.\"\nnone = \"No plot.\"");
+    let french = Locale::parse("[plot]\nowned = \"Vous possédez {plot}.\"");
+    let locale = Locale::from_strings("fr", french.unwrap(), english.unwrap());
+
+    let owned = Text::new("plot.owned").with("plot", "Greenfield");
+    assert_eq!(locale.text(&owned), "Vous possédez Greenfield.");
+    // Missing translations fall back to English, then to the id itself.
+    assert_eq!(locale.text(&"plot.none".into()), "No plot.");
+    assert_eq!(locale.get("plot.unknown"), "plot.unknown");
+    // Literal text is shown as it is, parameters are never added to it.
+    let literal = Text::literal("{plot} stays").with("plot", "Greenfield");
+    assert_eq!(locale.text(&literal), "{plot} stays");
+}
+
+#[test]
+fn nested_text_is_translated_by_the_client() {
+    let english = Locale::parse("[gather]\nfound = \"Found {item}.\"\n[items]\nlog = \"Log\"");
+    let french = Locale::parse("[gather]\nfound = \"Trouvé {item}.\"\n[items]\nlog = \"Bûche\"");
+    let locale = Locale::from_strings("fr", french.unwrap(), english.unwrap());
+
+    let found = Text::new("gather.found").with_text("item", Text::new("items.log"));
+    assert_eq!(locale.text(&found), "Trouvé Bûche.");
+}
+
+#[test]
+fn every_language_translates_the_same_ids() {
+    let languages = Locale::languages_in(ASSETS);
+    assert!(languages.iter().any(|language| language == Locale::DEFAULT));
+    assert!(languages.len() > 1);
+
+    let ids = |path: String| -> HashSet<String> {
+        let content = std::fs::read_to_string(path).unwrap();
+        Locale::parse(&content).unwrap().into_keys().collect()
+    };
+    let english = ids(format!("{}/locale/{}.toml", ASSETS, Locale::DEFAULT));
+    let world = ids(format!("{}/locale/world/{}.toml", ASSETS, Locale::DEFAULT));
+    for language in languages {
+        let strings = ids(format!("{}/locale/{}.toml", ASSETS, language));
+        assert_eq!(strings, english, "{} is incomplete", language);
+        let strings = ids(format!("{}/locale/world/{}.toml", ASSETS, language));
+        assert_eq!(
+            strings, world,
+            "the world strings for {} are incomplete",
+            language
+        );
+    }
+}
+
+#[test]
+fn every_item_is_named() {
+    let locale = Locale::from_root(ASSETS, Locale::DEFAULT);
+    let items = ItemManager::from_root(ASSETS);
+
+    let definitions: Vec<_> = (0..1000).filter_map(|id| items.get(&id)).collect();
+    assert!(!definitions.is_empty());
+    for item in definitions {
+        let id = format!("items.{}", item.key);
+        assert_ne!(locale.get(&id), id, "{} has no name", item.key);
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::Instant;
+
+use uo2d_proto::packet::payloads::{MessagePayload, ReliablePayload};
+use uo2d_proto::packet::{Action, Packet, Payload, Reliability, MAX_DATAGRAM};
+use uuid::Uuid;
+
+fn message(text: &str) -> Packet {
+    Packet::new(
+        Action::Message,
+        Uuid::new_v4(),
+        Payload::Message(MessagePayload::new(text)),
+    )
+}
+
+#[test]
+fn critical_packets_arrive_once_and_are_acknowledged() {
+    let now = Instant::now();
+    let mut client = Reliability::new();
+    let mut server = Reliability::new();
+
+    let packet = message("hello");
+    let wrapped = client.prepare(packet.clone(), now);
+    assert_eq!(wrapped.action(), Some(Action::Reliable));
+    assert_eq!(client.pending(), 1);
+
+    let received = server.receive(wrapped.clone());
+    let unwrapped = received.packet.expect("The packet was not delivered");
+    assert_eq!(unwrapped.as_bytes(), packet.as_bytes());
+    let ack = received.ack.expect("The packet was not acknowledged");
+
+    // Retransmissions are acknowledged again, but never handled twice.
+    let duplicate = server.receive(wrapped);
+    assert!(duplicate.packet.is_none());
+    assert!(duplicate.ack.is_some());
+
+    let received = client.receive(ack);
+    assert!(received.packet.is_none() && received.ack.is_none());
+    assert_eq!(client.pending(), 0);
+}
+
+#[test]
+fn unacknowledged_packets_are_sent_again_until_given_up_on() {
+    let mut now = Instant::now();
+    let mut client = Reliability::new();
+    let wrapped = client.prepare(message("lost"), now);
+
+    assert!(client.resend(now).is_empty());
+    for _ in 1..Reliability::MAX_ATTEMPTS {
+        now += Reliability::RESEND_AFTER;
+        let resent = client.resend(now);
+        assert_eq!(resent.len(), 1);
+        assert_eq!(resent[0].as_bytes(), wrapped.as_bytes());
+    }
+
+    now += Reliability::RESEND_AFTER;
+    assert!(client.resend(now).is_empty());
+    assert_eq!((client.pending(), client.lost()), (0, 1));
+}
+
+#[test]
+fn only_critical_packets_fitting_a_datagram_are_wrapped() {
+    let now = Instant::now();
+    let mut reliability = Reliability::new();
+
+    let movement = Packet::new(Action::Movement, Uuid::nil(), Payload::Empty);
+    let prepared = reliability.prepare(movement.clone(), now);
+    assert_eq!(prepared.as_bytes(), movement.as_bytes());
+
+    let large = message(&"a".repeat(MAX_DATAGRAM));
+    let prepared = reliability.prepare(large.clone(), now);
+    assert_eq!(prepared.as_bytes(), large.as_bytes());
+    assert_eq!(reliability.pending(), 0);
+
+    // Unwrapped packets are passed through as they were received.
+    let received = reliability.receive(movement.clone());
+    assert!(received.ack.is_none());
+    assert_eq!(received.packet.unwrap().as_bytes(), movement.as_bytes());
+}
+
+#[test]
+fn keyframes_are_wrapped_unlike_the_deltas_offset_from_them() {
+    let now = Instant::now();
+    let mut reliability = Reliability::new();
+
+    let keyframe = Packet::new(Action::Keyframe, Uuid::nil(), Payload::Empty);
+    let prepared = reliability.prepare(keyframe, now);
+    assert_eq!(prepared.action(), Some(Action::Reliable));
+
+    let delta = Packet::new(Action::MovementDelta, Uuid::nil(), Payload::Empty);
+    let prepared = reliability.prepare(delta.clone(), now);
+    assert_eq!(prepared.as_bytes(), delta.as_bytes());
+    assert_eq!(reliability.pending(), 1);
+}
+
+#[test]
+fn packets_arriving_out_of_order_are_each_delivered() {
+    let now = Instant::now();
+    let mut client = Reliability::new();
+    let mut server = Reliability::new();
+
+    let wrapped: Vec<Packet> = (0..4)
+        .map(|index| client.prepare(message(&index.to_string()), now))
+        .collect();
+    for index in [2, 0, 3, 1] {
+        assert!(server.receive(wrapped[index].clone()).packet.is_some());
+    }
+    for packet in wrapped {
+        assert!(server.receive(packet).packet.is_none());
+    }
+}
+
+/// Wraps a message with the sequence given, as a peer could send it.
+fn numbered(sequence: u32) -> Packet {
+    Packet::new(
+        Action::Reliable,
+        Uuid::nil(),
+        Payload::Reliable(ReliablePayload::new(
+            sequence,
+            message("numbered").to_bytes(),
+        )),
+    )
+}
+
+#[test]
+fn sequences_near_the_wrap_are_never_jumped_to() {
+    let mut server = Reliability::new();
+
+    // Enough to overflow the window, all of them ending at the last sequence. Those just behind
+    // the wrap are taken as already received.
+    for sequence in u32::MAX - 1024..=u32::MAX {
+        let received = server.receive(numbered(sequence));
+        assert!(received.packet.is_none() && received.ack.is_some());
+    }
+
+    // Those far ahead are refused without being acknowledged.
+    for sequence in [1 << 16, 1 << 30, (1 << 31) - 1] {
+        let received = server.receive(numbered(sequence));
+        assert!(received.packet.is_none() && received.ack.is_none());
+    }
+
+    // The sender's own sequences still arrive.
+    for sequence in 0..4 {
+        assert!(server.receive(numbered(sequence)).packet.is_some());
+    }
+}
+
+#[test]
+fn gaps_given_up_on_are_skipped_once_the_window_fills() {
+    let mut server = Reliability::new();
+    for sequence in 1..=1025 {
+        assert!(server.receive(numbered(sequence)).packet.is_some());
+    }
+
+    // The first was skipped to make room, arriving late it is taken as already received.
+    assert!(server.receive(numbered(0)).packet.is_none());
+    assert!(server.receive(numbered(1026)).packet.is_some());
+    assert!(server.receive(numbered(1025)).packet.is_none());
+}
@@ -0,0 +1,127 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use uo2d_proto::components::{Bounds, Collidable, Position, Projectile, Pushable, Vec2, Vec3};
+use uo2d_proto::components::{Obstacle, Player, Velocity};
+use uo2d_proto::ecs::World;
+use uo2d_proto::items::ItemManager;
+use uo2d_server::config::ContentConfig;
+use uo2d_server::content::Content;
+use uo2d_server::delta::DeltaEncoder;
+use uo2d_server::net::Net;
+use uo2d_server::region::RegionManager;
+use uo2d_server::replication::Replicator;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::movement;
+
+/// Entity counts the worlds are built with.
+const SIZES: [usize; 2] = [1_000, 10_000];
+const ENTITY_SIZE: f64 = 4.;
+/// Furthest the entities are spread across the mainland.
+const AREA: f64 = 1000.;
+
+/// Content root shipped with the game, canonical as the content refuses parent directories.
+fn assets() -> String {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+    std::fs::canonicalize(path)
+        .expect("Unable to find the assets")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// World of colliding entities on a grid, every other one moving.
+fn world(count: usize) -> (World, SpatialHash) {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    world.register_component::<Collidable>();
+    world.register_component::<Projectile>();
+    world.register_component::<Pushable>();
+    world.register_component::<Obstacle>();
+    world.register_component::<Player>();
+
+    let mut spatial = SpatialHash::new(32);
+    let columns = (count as f64).sqrt().ceil() as usize;
+    let spacing = AREA / columns as f64;
+    let size = Vec2::new(ENTITY_SIZE, ENTITY_SIZE);
+    for i in 0..count {
+        let loc = Vec3::new(
+            (i % columns) as f64 * spacing + ENTITY_SIZE,
+            (i / columns) as f64 * spacing + ENTITY_SIZE,
+            1.,
+        );
+        let mut builder = world
+            .spawn()
+            .with(Position::new(loc, size))
+            .with(Collidable);
+        if i % 2 == 0 {
+            builder = builder.with(Velocity(Vec2::new(1., 1.)));
+        }
+        let entity = builder.build();
+        spatial.insert_object(&entity, &Bounds::from_vec(loc, size));
+    }
+
+    (world, spatial)
+}
+
+/// Prints the spatial hash queries made by a single tick, one per moving entity.
+fn report_queries(count: usize, regions: &RegionManager, items: &ItemManager) {
+    let (mut world, mut spatial) = world(count);
+    let mut deltas = DeltaEncoder::new();
+    movement::with_velocity(
+        &mut world,
+        &mut spatial,
+        regions,
+        items,
+        &mut deltas,
+        &mut Replicator::default(),
+        &mut Net::new(),
+        0.,
+    );
+    println!(
+        "movement/with_velocity/{}: {} spatial queries for {} moving entities",
+        count,
+        spatial.query_count(),
+        count.div_ceil(2)
+    );
+}
+
+fn with_velocity(c: &mut Criterion) {
+    // Entities are placed in the mainland shipped with the game.
+    let root = assets();
+    let content = Content::new(&ContentConfig {
+        root: root.clone(),
+        ..ContentConfig::default()
+    });
+    let regions = RegionManager::from_content(&content);
+    let items = ItemManager::from_root(&root);
+    let mut group = c.benchmark_group("movement");
+    group.sample_size(10);
+
+    for count in SIZES {
+        report_queries(count, &regions, &items);
+        group.bench_function(BenchmarkId::new("with_velocity", count), |b| {
+            b.iter_batched(
+                || (world(count), DeltaEncoder::new()),
+                |((mut world, mut spatial), mut deltas)| {
+                    let mut net = Net::new();
+                    movement::with_velocity(
+                        &mut world,
+                        &mut spatial,
+                        &regions,
+                        &items,
+                        &mut deltas,
+                        &mut Replicator::default(),
+                        &mut net,
+                        0.,
+                    );
+                    net
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, with_velocity);
+criterion_main!(benches);
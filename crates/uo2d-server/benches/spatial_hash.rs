@@ -0,0 +1,84 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use uo2d_proto::components::{Bounds, Vec2, Vec3};
+use uo2d_proto::ecs::Entity;
+use uo2d_server::spatial_hash::SpatialHash;
+
+/// Entity counts the hashes are filled with.
+const SIZES: [usize; 2] = [1_000, 10_000];
+const CELL_SIZE: usize = 32;
+const ENTITY_SIZE: f64 = 16.;
+/// Space between entities laid out on a grid.
+const SPACING: f64 = 24.;
+
+/// Bounds of each entity, spread out on a square grid.
+fn layout(count: usize) -> Vec<(Entity, Bounds)> {
+    let columns = (count as f64).sqrt().ceil() as usize;
+    (0..count)
+        .map(|i| {
+            let position = Vec3::new(
+                (i % columns) as f64 * SPACING,
+                (i / columns) as f64 * SPACING,
+                1.,
+            );
+            let bounds = Bounds::from_vec(position, Vec2::new(ENTITY_SIZE, ENTITY_SIZE));
+            (Entity::new(i as u64), bounds)
+        })
+        .collect()
+}
+
+/// Furthest corner of the layout, bounding the flat grid.
+fn extent(layout: &[(Entity, Bounds)]) -> Vec2 {
+    layout.iter().fold(Vec2::ORIGIN, |extent, (_, bounds)| {
+        let corner = bounds.bottom_right_2d();
+        Vec2::new(extent.x().max(corner.x()), extent.y().max(corner.y()))
+    })
+}
+
+/// Hash filled with the layout, its cells in a flat grid if bounded.
+fn filled(layout: &[(Entity, Bounds)], bounded: bool) -> SpatialHash {
+    let mut spatial = match bounded {
+        true => SpatialHash::bounded(CELL_SIZE, extent(layout)),
+        false => SpatialHash::new(CELL_SIZE),
+    };
+    for (entity, bounds) in layout {
+        spatial.insert_object(entity, bounds);
+    }
+    spatial
+}
+
+fn spatial_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spatial_hash");
+
+    for (store, bounded) in [("hashed", false), ("flat", true)] {
+        for count in SIZES {
+            let layout = layout(count);
+            let mut spatial = filled(&layout, bounded);
+            let id = |name: &str| BenchmarkId::new(format!("{}/{}", name, store), count);
+
+            group.bench_with_input(id("insert"), &layout, |b, layout| {
+                b.iter(|| black_box(filled(layout, bounded)))
+            });
+
+            // Removing and inserting again keeps the hash the same size between iterations.
+            group.bench_with_input(id("remove"), &layout, |b, layout| {
+                b.iter(|| {
+                    for (entity, bounds) in layout.iter().step_by(10) {
+                        spatial.remove_object(entity, bounds);
+                        spatial.insert_object(entity, bounds);
+                    }
+                })
+            });
+
+            // Roughly the area around a player that is checked each tick.
+            let area = Bounds::from_vec(Vec3::new(256., 256., 1.), Vec2::new(512., 512.));
+            group.bench_with_input(id("query"), &area, |b, area| {
+                b.iter(|| black_box(spatial.query(area, None).len()))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, spatial_hash);
+criterion_main!(benches);
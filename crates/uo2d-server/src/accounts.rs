@@ -0,0 +1,760 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uo2d_proto::components::{
+    Bounds, Equipment, GroundItem, ItemStack, ObstacleKind, Progress, Skills, Stats, Vec2, Vec3,
+};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::Letter;
+
+/// Unique identifier for an account.
+pub type AccountId = i64;
+
+/// Errors that occur while registering or authenticating.
+#[derive(Debug)]
+pub enum AccountError {
+    /// Username or password does not meet the requirements.
+    Invalid(String),
+    /// Username is already registered.
+    Taken,
+    /// Username or password is incorrect.
+    Credentials,
+    /// Accounts are not enabled on this server.
+    Unavailable,
+    /// The account is already logged in.
+    InUse,
+    /// The database could not be accessed.
+    Database(rusqlite::Error),
+    /// The password could not be hashed.
+    Hash(argon2::password_hash::Error),
+}
+
+impl fmt::Display for AccountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountError::Invalid(why) => write!(f, "{}", why),
+            AccountError::Taken => write!(f, "username is already taken"),
+            AccountError::Credentials => write!(f, "invalid username or password"),
+            AccountError::Unavailable => write!(f, "accounts are unavailable"),
+            AccountError::InUse => write!(f, "account is already logged in"),
+            AccountError::Database(why) => write!(f, "database error: {}", why),
+            AccountError::Hash(why) => write!(f, "hashing error: {}", why),
+        }
+    }
+}
+
+impl Error for AccountError {}
+
+impl AccountError {
+    /// Reason shown to the player, the details of internal errors are kept from them.
+    pub fn text(&self) -> Text {
+        match self {
+            AccountError::Invalid(why) => Text::literal(why),
+            AccountError::Taken => Text::new("account.taken"),
+            AccountError::Credentials => Text::new("account.credentials"),
+            AccountError::InUse => Text::new("account.in_use"),
+            AccountError::Unavailable | AccountError::Database(_) | AccountError::Hash(_) => {
+                Text::new("account.unavailable")
+            }
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AccountError {
+    fn from(why: rusqlite::Error) -> Self {
+        AccountError::Database(why)
+    }
+}
+
+impl From<argon2::password_hash::Error> for AccountError {
+    fn from(why: argon2::password_hash::Error) -> Self {
+        AccountError::Hash(why)
+    }
+}
+
+/// Persisted state of the character attached to an account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Character {
+    pub position: Vec3,
+    pub health: u32,
+    pub stats: Stats,
+    #[serde(default)]
+    pub skills: Skills,
+    /// Given the starter items when first created, never given them again.
+    #[serde(default)]
+    pub outfitted: bool,
+    /// Stacks carried, the bank is kept apart.
+    #[serde(default)]
+    pub inventory: Vec<ItemStack>,
+    #[serde(default)]
+    pub equipment: Equipment,
+}
+
+/// Object placed within a housing plot, along with anything stored inside of it.
+#[derive(Debug, Clone)]
+pub struct PlacedObject {
+    pub kind: ObstacleKind,
+    pub position: Vec3,
+    pub contents: Vec<ItemStack>,
+}
+
+/// Persisted ownership and furnishings of a claimed housing plot.
+#[derive(Debug, Clone)]
+pub struct PlotRecord {
+    pub name: String,
+    pub owner: AccountId,
+    /// Co-owners allowed to furnish the plot.
+    pub members: BTreeSet<AccountId>,
+    pub objects: Vec<PlacedObject>,
+}
+
+/// Stores accounts and their characters, along with the items left in the world.
+pub struct AccountDatabase {
+    conn: Connection,
+    path: PathBuf,
+}
+
+impl AccountDatabase {
+    const MIN_USERNAME: usize = 3;
+    const MAX_USERNAME: usize = 16;
+    const MIN_PASSWORD: usize = 8;
+    const MAX_MAILBOX: u32 = 50;
+
+    /// Opens the database, creating the tables if they do not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AccountError> {
+        let path = path.as_ref().to_path_buf();
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS accounts (
+                id INTEGER PRIMARY KEY,
+                username TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                password TEXT NOT NULL,
+                created TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS characters (
+                account_id INTEGER PRIMARY KEY REFERENCES accounts(id),
+                x REAL NOT NULL,
+                y REAL NOT NULL,
+                z REAL NOT NULL,
+                health INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS stats (
+                account_id INTEGER PRIMARY KEY REFERENCES accounts(id),
+                kills INTEGER NOT NULL,
+                deaths INTEGER NOT NULL,
+                distance REAL NOT NULL,
+                playtime REAL NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS ground_items (
+                item INTEGER NOT NULL,
+                count INTEGER NOT NULL,
+                x REAL NOT NULL,
+                y REAL NOT NULL,
+                z REAL NOT NULL,
+                width REAL NOT NULL,
+                height REAL NOT NULL,
+                expires INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS mail (
+                id INTEGER PRIMARY KEY,
+                recipient INTEGER NOT NULL REFERENCES accounts(id),
+                sender TEXT NOT NULL,
+                message TEXT NOT NULL,
+                sent TEXT NOT NULL,
+                read INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS skills (
+                account_id INTEGER PRIMARY KEY REFERENCES accounts(id),
+                gathering INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS outfits (
+                account_id INTEGER PRIMARY KEY REFERENCES accounts(id),
+                outfitted INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS belongings (
+                account_id INTEGER PRIMARY KEY REFERENCES accounts(id),
+                inventory TEXT NOT NULL,
+                equipment TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS plots (
+                name TEXT PRIMARY KEY,
+                owner INTEGER NOT NULL REFERENCES accounts(id)
+            );
+            CREATE TABLE IF NOT EXISTS plot_members (
+                plot TEXT NOT NULL REFERENCES plots(name),
+                account_id INTEGER NOT NULL REFERENCES accounts(id),
+                PRIMARY KEY (plot, account_id)
+            );
+            CREATE TABLE IF NOT EXISTS plot_objects (
+                plot TEXT NOT NULL REFERENCES plots(name),
+                kind TEXT NOT NULL,
+                x REAL NOT NULL,
+                y REAL NOT NULL,
+                z REAL NOT NULL,
+                contents TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS progress (
+                account_id INTEGER NOT NULL REFERENCES accounts(id),
+                flag TEXT NOT NULL,
+                PRIMARY KEY (account_id, flag)
+            );
+            CREATE TABLE IF NOT EXISTS banks (
+                account_id INTEGER PRIMARY KEY REFERENCES accounts(id),
+                contents TEXT NOT NULL
+            );",
+        )?;
+
+        Ok(Self { conn, path })
+    }
+
+    /// File the database is stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes a consistent copy of the database to a new file.
+    pub fn snapshot(&self, path: &Path) -> Result<(), AccountError> {
+        self.conn
+            .execute("VACUUM INTO ?1", params![path.to_string_lossy()])?;
+
+        Ok(())
+    }
+
+    /// Checks an account could be created, before its password is hashed.
+    pub fn available(&self, username: &str, password: &str) -> Result<(), AccountError> {
+        Self::validate(username, password)?;
+        self.unclaimed(username)
+    }
+
+    /// Fails if the username already belongs to an account.
+    fn unclaimed(&self, username: &str) -> Result<(), AccountError> {
+        let exists: Option<AccountId> = self
+            .conn
+            .query_row(
+                "SELECT id FROM accounts WHERE username = ?1",
+                params![username],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match exists {
+            Some(_) => Err(AccountError::Taken),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates a new account with an already hashed password, returning its id.
+    pub fn create(&self, username: &str, hash: &str) -> Result<AccountId, AccountError> {
+        // Another registration may have taken the username while the password was hashed.
+        self.unclaimed(username)?;
+
+        self.conn.execute(
+            "INSERT INTO accounts (username, password, created) VALUES (?1, ?2, ?3)",
+            params![username, hash, uo2d_proto::util::get_utc()],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Obtains the account and its password hash, to be verified.
+    pub fn password(&self, username: &str) -> Result<(AccountId, String), AccountError> {
+        let account: Option<(AccountId, String)> = self
+            .conn
+            .query_row(
+                "SELECT id, password FROM accounts WHERE username = ?1",
+                params![username],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        account.ok_or(AccountError::Credentials)
+    }
+
+    /// Hashes a password to be stored. Slow by design, so it is kept off of the tick.
+    pub fn hash(password: &str) -> Result<String, AccountError> {
+        let salt = SaltString::generate(&mut OsRng);
+        Ok(Argon2::default()
+            .hash_password(password.as_bytes(), &salt)?
+            .to_string())
+    }
+
+    /// Verifies a password against its stored hash. Slow by design like hashing.
+    pub fn verify(password: &str, hash: &str) -> Result<(), AccountError> {
+        let hash = PasswordHash::new(hash)?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .map_err(|_| AccountError::Credentials)
+    }
+
+    /// Loads the character for an account, if one has been saved.
+    pub fn load_character(&self, id: AccountId) -> Result<Option<Character>, AccountError> {
+        let character = self
+            .conn
+            .query_row(
+                "SELECT c.x, c.y, c.z, c.health, s.kills, s.deaths, s.distance, s.playtime,
+                    k.gathering, o.outfitted, b.inventory, b.equipment
+                FROM characters c
+                LEFT JOIN stats s ON s.account_id = c.account_id
+                LEFT JOIN skills k ON k.account_id = c.account_id
+                LEFT JOIN outfits o ON o.account_id = c.account_id
+                LEFT JOIN belongings b ON b.account_id = c.account_id
+                WHERE c.account_id = ?1",
+                params![id],
+                |row| {
+                    Ok(Character {
+                        position: Vec3::new(row.get(0)?, row.get(1)?, row.get(2)?),
+                        health: row.get(3)?,
+                        stats: Stats {
+                            kills: row.get::<_, Option<u32>>(4)?.unwrap_or_default(),
+                            deaths: row.get::<_, Option<u32>>(5)?.unwrap_or_default(),
+                            distance: row.get::<_, Option<f64>>(6)?.unwrap_or_default(),
+                            playtime: row.get::<_, Option<f64>>(7)?.unwrap_or_default(),
+                        },
+                        skills: Skills {
+                            gathering: row.get::<_, Option<u32>>(8)?.unwrap_or_default(),
+                        },
+                        // Characters saved before the flag existed were outfitted when created.
+                        outfitted: row.get::<_, Option<bool>>(9)?.unwrap_or(true),
+                        inventory: row
+                            .get::<_, Option<String>>(10)?
+                            .and_then(|inventory| serde_json::from_str(&inventory).ok())
+                            .unwrap_or_default(),
+                        equipment: row
+                            .get::<_, Option<String>>(11)?
+                            .and_then(|equipment| serde_json::from_str(&equipment).ok())
+                            .unwrap_or_default(),
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(character)
+    }
+
+    /// Saves the character for an account, along with everything they carry.
+    pub fn save_character(&self, id: AccountId, character: &Character) -> Result<(), AccountError> {
+        let (x, y, z) = character.position.as_tuple();
+        self.conn.execute(
+            "INSERT INTO characters (account_id, x, y, z, health) VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(account_id) DO UPDATE SET x = ?2, y = ?3, z = ?4, health = ?5",
+            params![id, x, y, z, character.health],
+        )?;
+
+        self.conn.execute(
+            "INSERT INTO outfits (account_id, outfitted) VALUES (?1, ?2)
+            ON CONFLICT(account_id) DO UPDATE SET outfitted = ?2",
+            params![id, character.outfitted],
+        )?;
+
+        let inventory = serde_json::to_string(&character.inventory).unwrap_or_default();
+        let equipment = serde_json::to_string(&character.equipment).unwrap_or_default();
+        self.conn.execute(
+            "INSERT INTO belongings (account_id, inventory, equipment) VALUES (?1, ?2, ?3)
+            ON CONFLICT(account_id) DO UPDATE SET inventory = ?2, equipment = ?3",
+            params![id, inventory, equipment],
+        )?;
+
+        self.save_stats(id, &character.stats)?;
+        self.save_skills(id, &character.skills)
+    }
+
+    /// Saves the skills for an account.
+    pub fn save_skills(&self, id: AccountId, skills: &Skills) -> Result<(), AccountError> {
+        self.conn.execute(
+            "INSERT INTO skills (account_id, gathering) VALUES (?1, ?2)
+            ON CONFLICT(account_id) DO UPDATE SET gathering = ?2",
+            params![id, skills.gathering],
+        )?;
+
+        Ok(())
+    }
+
+    /// Saves the statistics for an account.
+    pub fn save_stats(&self, id: AccountId, stats: &Stats) -> Result<(), AccountError> {
+        self.conn.execute(
+            "INSERT INTO stats (account_id, kills, deaths, distance, playtime)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(account_id) DO UPDATE SET
+                kills = ?2, deaths = ?3, distance = ?4, playtime = ?5",
+            params![
+                id,
+                stats.kills,
+                stats.deaths,
+                stats.distance,
+                stats.playtime
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Loads the flags an account has earned through conversations.
+    pub fn load_progress(&self, id: AccountId) -> Result<Progress, AccountError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT flag FROM progress WHERE account_id = ?1")?;
+        let flags = stmt
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<Result<BTreeSet<String>, rusqlite::Error>>()?;
+
+        Ok(Progress { flags })
+    }
+
+    /// Saves the flags an account has earned, flags are never cleared once set.
+    pub fn save_progress(&self, id: AccountId, progress: &Progress) -> Result<(), AccountError> {
+        let tx = self.conn.unchecked_transaction()?;
+        for flag in progress.flags.iter() {
+            tx.execute(
+                "INSERT OR IGNORE INTO progress (account_id, flag) VALUES (?1, ?2)",
+                params![id, flag],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Loads the stacks an account keeps in their bank.
+    pub fn load_bank(&self, id: AccountId) -> Result<Vec<ItemStack>, AccountError> {
+        let contents: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT contents FROM banks WHERE account_id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(contents
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default())
+    }
+
+    /// Replaces the stacks an account keeps in their bank.
+    pub fn save_bank(&self, id: AccountId, stacks: &[ItemStack]) -> Result<(), AccountError> {
+        let contents = serde_json::to_string(stacks).unwrap_or_default();
+        self.conn.execute(
+            "INSERT INTO banks (account_id, contents) VALUES (?1, ?2)
+            ON CONFLICT(account_id) DO UPDATE SET contents = ?2",
+            params![id, contents],
+        )?;
+
+        Ok(())
+    }
+
+    /// Obtains the usernames and statistics of the highest ranked accounts.
+    pub fn top_stats(&self, limit: usize) -> Result<Vec<(String, Stats)>, AccountError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.username, s.kills, s.deaths, s.distance, s.playtime
+            FROM stats s JOIN accounts a ON a.id = s.account_id
+            ORDER BY s.kills DESC, s.deaths ASC LIMIT ?1",
+        )?;
+        let top = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok((
+                    row.get(0)?,
+                    Stats {
+                        kills: row.get(1)?,
+                        deaths: row.get(2)?,
+                        distance: row.get(3)?,
+                        playtime: row.get(4)?,
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<(String, Stats)>, rusqlite::Error>>()?;
+
+        Ok(top)
+    }
+
+    /// Replaces the saved ground items with those currently in the world.
+    pub fn save_ground_items(&self, items: &[GroundItem]) -> Result<(), AccountError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM ground_items", [])?;
+        for ground in items {
+            let (x, y, z) = ground.bounds.top_left_3d().as_tuple();
+            let (width, height) = ground.bounds.dimensions().as_tuple();
+            tx.execute(
+                "INSERT INTO ground_items (item, count, x, y, z, width, height, expires)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    ground.stack.item,
+                    ground.stack.count,
+                    x,
+                    y,
+                    z,
+                    width,
+                    height,
+                    ground.expires as i64
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Loads the ground items that were saved.
+    pub fn load_ground_items(&self) -> Result<Vec<GroundItem>, AccountError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT item, count, x, y, z, width, height, expires FROM ground_items")?;
+        let items = stmt
+            .query_map([], |row| {
+                let expires: i64 = row.get(7)?;
+                Ok(GroundItem {
+                    stack: ItemStack::new(row.get(0)?, row.get(1)?),
+                    bounds: Bounds::from_vec(
+                        Vec3::new(row.get(2)?, row.get(3)?, row.get(4)?),
+                        Vec2::new(row.get(5)?, row.get(6)?),
+                    ),
+                    expires: expires.max(0) as u64,
+                })
+            })?
+            .collect::<Result<Vec<GroundItem>, rusqlite::Error>>()?;
+
+        Ok(items)
+    }
+
+    /// Obtains the id of an account by its username.
+    pub fn account_id(&self, username: &str) -> Result<Option<AccountId>, AccountError> {
+        let id = self
+            .conn
+            .query_row(
+                "SELECT id FROM accounts WHERE username = ?1",
+                params![username.trim()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(id)
+    }
+
+    /// Obtains the username of an account.
+    pub fn username(&self, id: AccountId) -> Result<Option<String>, AccountError> {
+        let username = self
+            .conn
+            .query_row(
+                "SELECT username FROM accounts WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(username)
+    }
+
+    /// Loads every claimed housing plot along with its co-owners and furnishings.
+    pub fn load_plots(&self) -> Result<Vec<PlotRecord>, AccountError> {
+        let mut plots = self
+            .conn
+            .prepare("SELECT name, owner FROM plots")?
+            .query_map([], |row| {
+                Ok(PlotRecord {
+                    name: row.get(0)?,
+                    owner: row.get(1)?,
+                    members: BTreeSet::new(),
+                    objects: vec![],
+                })
+            })?
+            .collect::<Result<Vec<PlotRecord>, rusqlite::Error>>()?;
+
+        for plot in plots.iter_mut() {
+            plot.members = self
+                .conn
+                .prepare("SELECT account_id FROM plot_members WHERE plot = ?1")?
+                .query_map(params![plot.name], |row| row.get(0))?
+                .collect::<Result<BTreeSet<AccountId>, rusqlite::Error>>()?;
+
+            let objects = self
+                .conn
+                .prepare("SELECT kind, x, y, z, contents FROM plot_objects WHERE plot = ?1")?
+                .query_map(params![plot.name], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        Vec3::new(row.get(1)?, row.get(2)?, row.get(3)?),
+                        row.get::<_, String>(4)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+            // Objects of kinds that no longer exist are dropped.
+            plot.objects = objects
+                .into_iter()
+                .filter_map(|(kind, position, contents)| {
+                    Some(PlacedObject {
+                        kind: ObstacleKind::from_name(&kind)?,
+                        position,
+                        contents: serde_json::from_str(&contents).unwrap_or_default(),
+                    })
+                })
+                .collect();
+        }
+
+        Ok(plots)
+    }
+
+    /// Replaces the saved ownership and furnishings of a plot.
+    pub fn save_plot(&self, plot: &PlotRecord) -> Result<(), AccountError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO plots (name, owner) VALUES (?1, ?2)
+            ON CONFLICT(name) DO UPDATE SET owner = ?2",
+            params![plot.name, plot.owner],
+        )?;
+        tx.execute(
+            "DELETE FROM plot_members WHERE plot = ?1",
+            params![plot.name],
+        )?;
+        for member in plot.members.iter() {
+            tx.execute(
+                "INSERT INTO plot_members (plot, account_id) VALUES (?1, ?2)",
+                params![plot.name, member],
+            )?;
+        }
+
+        tx.execute(
+            "DELETE FROM plot_objects WHERE plot = ?1",
+            params![plot.name],
+        )?;
+        for object in plot.objects.iter() {
+            let (x, y, z) = object.position.as_tuple();
+            let contents = serde_json::to_string(&object.contents).unwrap_or_default();
+            tx.execute(
+                "INSERT INTO plot_objects (plot, kind, x, y, z, contents)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![plot.name, object.kind.name(), x, y, z, contents],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Releases a plot, removing its co-owners and furnishings.
+    pub fn abandon_plot(&self, name: &str) -> Result<(), AccountError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM plot_objects WHERE plot = ?1", params![name])?;
+        tx.execute("DELETE FROM plot_members WHERE plot = ?1", params![name])?;
+        tx.execute("DELETE FROM plots WHERE name = ?1", params![name])?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Leaves a letter in the mailbox of another account, returning the recipient.
+    /// Refused once their mailbox is full.
+    pub fn send_mail(
+        &self,
+        sender: AccountId,
+        recipient: &str,
+        message: &str,
+    ) -> Result<AccountId, AccountError> {
+        let recipient: AccountId = self
+            .conn
+            .query_row(
+                "SELECT id FROM accounts WHERE username = ?1",
+                params![recipient],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| AccountError::Invalid(format!("no account named '{}'", recipient)))?;
+
+        let held: u32 = self.conn.query_row(
+            "SELECT COUNT(*) FROM mail WHERE recipient = ?1",
+            params![recipient],
+            |row| row.get(0),
+        )?;
+        if held >= Self::MAX_MAILBOX {
+            return Err(AccountError::Invalid("their mailbox is full".to_string()));
+        }
+
+        self.conn.execute(
+            "INSERT INTO mail (recipient, sender, message, sent)
+            SELECT ?1, username, ?3, ?4 FROM accounts WHERE id = ?2",
+            params![recipient, sender, message, uo2d_proto::util::get_utc()],
+        )?;
+
+        Ok(recipient)
+    }
+
+    /// Number of letters in the mailbox of an account that have not been read.
+    pub fn unread_mail(&self, id: AccountId) -> Result<u32, AccountError> {
+        let unread = self.conn.query_row(
+            "SELECT COUNT(*) FROM mail WHERE recipient = ?1 AND read = 0",
+            params![id],
+            |row| row.get(0),
+        )?;
+
+        Ok(unread)
+    }
+
+    /// Obtains the letters for an account, unread first, marking them as read.
+    /// Read letters that no longer fit within the limit are removed.
+    pub fn read_mail(&self, id: AccountId, limit: usize) -> Result<Vec<Letter>, AccountError> {
+        const SHOWN: &str = "SELECT id FROM mail WHERE recipient = ?1
+            ORDER BY read ASC, id DESC LIMIT ?2";
+
+        let tx = self.conn.unchecked_transaction()?;
+        let letters = tx
+            .prepare(
+                "SELECT sender, message, sent, read FROM mail WHERE recipient = ?1
+                ORDER BY read ASC, id DESC LIMIT ?2",
+            )?
+            .query_map(params![id, limit as i64], |row| {
+                Ok(Letter {
+                    sender: row.get(0)?,
+                    message: row.get(1)?,
+                    sent: row.get(2)?,
+                    read: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<Letter>, rusqlite::Error>>()?;
+
+        tx.execute(
+            &format!(
+                "DELETE FROM mail WHERE recipient = ?1 AND read = 1 AND id NOT IN ({})",
+                SHOWN
+            ),
+            params![id, limit as i64],
+        )?;
+        tx.execute(
+            &format!("UPDATE mail SET read = 1 WHERE id IN ({})", SHOWN),
+            params![id, limit as i64],
+        )?;
+        tx.commit()?;
+
+        Ok(letters)
+    }
+
+    /// Ensures the username and password meet the requirements.
+    fn validate(username: &str, password: &str) -> Result<(), AccountError> {
+        let length = username.chars().count();
+        if !(Self::MIN_USERNAME..=Self::MAX_USERNAME).contains(&length) {
+            return Err(AccountError::Invalid(format!(
+                "username must be {} to {} characters",
+                Self::MIN_USERNAME,
+                Self::MAX_USERNAME
+            )));
+        } else if !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(AccountError::Invalid(
+                "username may only contain letters, numbers, and underscores".to_string(),
+            ));
+        } else if password.chars().count() < Self::MIN_PASSWORD {
+            return Err(AccountError::Invalid(format!(
+                "password must be at least {} characters",
+                Self::MIN_PASSWORD
+            )));
+        }
+
+        Ok(())
+    }
+}
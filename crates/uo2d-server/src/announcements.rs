@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use uo2d_proto::timer::{TimerData, TimerManager};
+
+use crate::config::{AnnouncementConfig, ScheduledAnnouncement};
+
+/// Message of the day and the announcements repeated to every player.
+#[derive(Debug, Clone)]
+pub struct Announcements {
+    motd: Option<String>,
+    scheduled: BTreeMap<u32, ScheduledAnnouncement>,
+    next_id: u32,
+}
+
+impl Announcements {
+    /// Shortest time allowed between repeats, in seconds.
+    const MIN_INTERVAL: f32 = 10.;
+
+    /// Creates the announcements from the configuration, scheduling each of them.
+    pub fn new(config: AnnouncementConfig, timers: &mut TimerManager) -> Self {
+        let mut announcements = Self {
+            motd: None,
+            scheduled: BTreeMap::new(),
+            next_id: 0,
+        };
+
+        announcements.set_motd(config.motd);
+        for announcement in config.scheduled {
+            announcements.add(announcement, timers);
+        }
+        announcements
+    }
+
+    /// Message sent to players as they join.
+    pub fn motd(&self) -> Option<&str> {
+        self.motd.as_deref()
+    }
+
+    /// Replaces the message of the day, an empty message removes it.
+    pub fn set_motd(&mut self, motd: Option<String>) {
+        self.motd = motd.filter(|motd| !motd.trim().is_empty());
+    }
+
+    /// Scheduled announcements by their id.
+    pub fn scheduled(&self) -> impl Iterator<Item = (&u32, &ScheduledAnnouncement)> {
+        self.scheduled.iter()
+    }
+
+    /// Schedules an announcement, returning the id it can be removed by.
+    pub fn add(
+        &mut self,
+        mut announcement: ScheduledAnnouncement,
+        timers: &mut TimerManager,
+    ) -> u32 {
+        announcement.interval = announcement.interval.max(Self::MIN_INTERVAL);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        timers.add_timer_sec(announcement.interval, TimerData::Announcement(id), true);
+        self.scheduled.insert(id, announcement);
+        id
+    }
+
+    /// Stops repeating an announcement.
+    pub fn remove(&mut self, id: u32) -> Option<ScheduledAnnouncement> {
+        self.scheduled.remove(&id)
+    }
+
+    /// Message for an announcement that is due, scheduling it to repeat.
+    /// Removed announcements have nothing to send and are not rescheduled.
+    pub fn due(&self, id: u32, timers: &mut TimerManager) -> Option<String> {
+        let announcement = self.scheduled.get(&id)?;
+        timers.add_timer_sec(announcement.interval, TimerData::Announcement(id), true);
+        Some(announcement.message.clone())
+    }
+}
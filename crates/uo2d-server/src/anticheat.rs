@@ -0,0 +1,211 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use uo2d_proto::components::Vec3;
+use uuid::Uuid;
+
+use crate::config::AntiCheatConfig;
+use crate::event_log::{self, ServerEvent};
+
+/// Suspicious behavior detected from a client.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Violation {
+    /// Moving faster than the region allows.
+    Speed,
+    /// Reporting a position far from where the server has them.
+    Teleport,
+    /// Firing projectiles faster than a client can.
+    FireRate,
+    /// Sending packets that cannot be understood.
+    Malformed,
+    /// Sending packets faster than the rate limit.
+    Flood,
+}
+
+impl Violation {
+    /// How much a single occurrence counts towards being kicked.
+    fn weight(&self) -> f64 {
+        match self {
+            Violation::Speed => 1.,
+            Violation::Teleport => 3.,
+            Violation::FireRate => 1.,
+            Violation::Malformed => 2.,
+            Violation::Flood => 0.25,
+        }
+    }
+}
+
+/// Violations accumulated by a single client.
+struct Suspicion {
+    score: f64,
+    updated: Instant,
+    /// Times of the projectiles fired within the last second.
+    shots: VecDeque<Instant>,
+    /// Last position the client reported and when.
+    reported: Option<(Vec3, Instant)>,
+}
+
+impl Suspicion {
+    fn new() -> Self {
+        Self {
+            score: 0.,
+            updated: Instant::now(),
+            shots: VecDeque::new(),
+            reported: None,
+        }
+    }
+
+    /// Forgives violations as time passes without new ones.
+    fn decay(&mut self, rate: f64) {
+        let elapsed = self.updated.elapsed().as_secs_f64();
+        self.score = (self.score - rate * elapsed).max(0.);
+        self.updated = Instant::now();
+    }
+}
+
+/// Aggregates the violations of each client, shared between the socket server and gamestate.
+#[derive(Clone)]
+pub struct AntiCheat {
+    clients: Arc<Mutex<HashMap<Uuid, Suspicion>>>,
+    /// Shared so reloading the configuration reaches both servers.
+    config: Arc<RwLock<AntiCheatConfig>>,
+}
+
+impl AntiCheat {
+    /// Distance a reported position may be from the server's before it is a teleport.
+    const TELEPORT_DISTANCE: f64 = 256.;
+    /// Multiplier on the maximum speed allowed for latency between reports.
+    const SPEED_TOLERANCE: f64 = 2.;
+    /// Distance always allowed between reports, covering jitter.
+    const SPEED_SLACK: f64 = 64.;
+
+    pub fn new(config: AntiCheatConfig) -> Self {
+        Self {
+            clients: Arc::new(Mutex::new(HashMap::new())),
+            config: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// Replaces the thresholds, keeping the violations accumulated so far.
+    pub fn reconfigure(&self, config: AntiCheatConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Current thresholds.
+    fn config(&self) -> AntiCheatConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Records a violation and the evidence for it to the event log.
+    pub fn flag(&self, uuid: Uuid, violation: Violation, evidence: impl ToString) {
+        let config = self.config();
+        if !config.enabled {
+            return;
+        }
+
+        let score = {
+            let mut clients = self.clients.lock().unwrap();
+            let suspicion = clients.entry(uuid).or_insert_with(Suspicion::new);
+            suspicion.decay(config.decay);
+            suspicion.score += violation.weight();
+            suspicion.score
+        };
+
+        event_log::record(ServerEvent::Violation {
+            uuid,
+            violation,
+            score,
+            evidence: evidence.to_string(),
+        });
+    }
+
+    /// Counts a projectile fired, flagging clients that fire too quickly.
+    pub fn fire(&self, uuid: Uuid) {
+        let shots = {
+            let mut clients = self.clients.lock().unwrap();
+            let suspicion = clients.entry(uuid).or_insert_with(Suspicion::new);
+            let now = Instant::now();
+            suspicion.shots.push_back(now);
+            while suspicion
+                .shots
+                .front()
+                .is_some_and(|shot| now.duration_since(*shot) > Duration::from_secs(1))
+            {
+                suspicion.shots.pop_front();
+            }
+            suspicion.shots.len()
+        };
+
+        if shots as f64 > self.config().max_fire_rate {
+            self.flag(
+                uuid,
+                Violation::FireRate,
+                format!("{} projectiles within a second", shots),
+            );
+        }
+    }
+
+    /// Compares a position reported by a client to where the server has them.
+    /// `max_speed` is the furthest they can travel in a second.
+    pub fn moved(&self, uuid: Uuid, reported: Vec3, actual: Vec3, max_speed: f64) {
+        let previous = {
+            let mut clients = self.clients.lock().unwrap();
+            let suspicion = clients.entry(uuid).or_insert_with(Suspicion::new);
+            suspicion.reported.replace((reported, Instant::now()))
+        };
+
+        let offset = reported.distance_2d(&actual);
+        if offset > Self::TELEPORT_DISTANCE {
+            self.flag(
+                uuid,
+                Violation::Teleport,
+                format!("reported {:?}, server has {:?}", reported, actual),
+            );
+        }
+
+        if let Some((last, time)) = previous {
+            let distance = reported.distance_2d(&last);
+            let allowed = max_speed * time.elapsed().as_secs_f64() * Self::SPEED_TOLERANCE
+                + Self::SPEED_SLACK;
+            if distance > allowed {
+                self.flag(
+                    uuid,
+                    Violation::Speed,
+                    format!("moved {:.1} when {:.1} was allowed", distance, allowed),
+                );
+            }
+        }
+    }
+
+    /// Ignores the last reported position after the server moves a client.
+    pub fn relocated(&self, uuid: &Uuid) {
+        if let Some(suspicion) = self.clients.lock().unwrap().get_mut(uuid) {
+            suspicion.reported = None;
+        }
+    }
+
+    /// Checks if a client has accumulated enough violations to be kicked.
+    pub fn should_kick(&self, uuid: &Uuid) -> bool {
+        let config = self.config();
+        if !config.enabled {
+            return false;
+        }
+
+        let mut clients = self.clients.lock().unwrap();
+        match clients.get_mut(uuid) {
+            Some(suspicion) => {
+                suspicion.decay(config.decay);
+                suspicion.score >= config.threshold
+            }
+            None => false,
+        }
+    }
+
+    /// Stops tracking a client that has left.
+    pub fn forget(&self, uuid: &Uuid) {
+        self.clients.lock().unwrap().remove(uuid);
+    }
+}
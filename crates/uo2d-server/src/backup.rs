@@ -0,0 +1,170 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use uo2d_proto::sprintln;
+use uo2d_proto::timer::{TimerData, TimerManager};
+
+use crate::accounts::AccountDatabase;
+use crate::config::BackupConfig;
+
+/// Compressed snapshots of the database, taken on a schedule and pruned to the retention.
+pub struct Backups {
+    config: BackupConfig,
+    /// A backup is waiting on its timer.
+    scheduled: bool,
+    /// A backup is being taken.
+    running: Arc<AtomicBool>,
+}
+
+impl Backups {
+    /// Shortest time allowed between scheduled backups, in seconds.
+    const MIN_INTERVAL: f32 = 60.;
+    const PREFIX: &'static str = "uo2d-";
+    const EXTENSION: &'static str = ".db.gz";
+
+    /// Schedules the first backup if they are enabled.
+    pub fn new(config: BackupConfig, timers: &mut TimerManager) -> Self {
+        let mut backups = Self {
+            config,
+            scheduled: false,
+            running: Arc::new(AtomicBool::new(false)),
+        };
+        backups.schedule(timers);
+        backups
+    }
+
+    /// Schedules the next backup if they are enabled and one is not already waiting.
+    fn schedule(&mut self, timers: &mut TimerManager) {
+        if self.config.enabled && !self.scheduled {
+            let interval = self.config.interval.max(Self::MIN_INTERVAL);
+            timers.add_timer_sec(interval, TimerData::Backup, true);
+            self.scheduled = true;
+        }
+    }
+
+    /// Checks if a scheduled backup that came due should be taken, scheduling the next.
+    /// Backups disabled while one was waiting are skipped.
+    pub fn due(&mut self, timers: &mut TimerManager) -> bool {
+        self.scheduled = false;
+        self.schedule(timers);
+        self.config.enabled
+    }
+
+    /// Replaces the settings, scheduling a backup if they were just enabled.
+    /// A backup already waiting keeps its time, the new interval applies after it.
+    pub fn reconfigure(&mut self, config: BackupConfig, timers: &mut TimerManager) {
+        self.config = config;
+        self.schedule(timers);
+    }
+
+    /// Takes a backup of the database on a blocking thread, the tick carrying on meanwhile.
+    /// Skipped while the last is still being taken. The world should be saved beforehand so the
+    /// snapshot is current.
+    pub fn spawn(&self, database: &Path) {
+        if self.running.swap(true, Ordering::AcqRel) {
+            sprintln!("Skipped a backup, the last is still being taken.");
+            return;
+        }
+
+        let config = self.config.clone();
+        let database = database.to_path_buf();
+        let running = Arc::clone(&self.running);
+        tokio::task::spawn_blocking(move || {
+            match Self::create(&config, &database) {
+                Ok(path) => sprintln!("Backed up the database to {}.", path.display()),
+                Err(why) => sprintln!("Unable to back up the database: {}", why),
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Snapshots the database into the backup directory, removing the oldest beyond the retention.
+    fn create(config: &BackupConfig, database: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let directory = Path::new(&config.directory);
+        fs::create_dir_all(directory)?;
+
+        let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        let name = format!("{}{}", Self::PREFIX, stamp);
+        let snapshot = directory.join(format!("{}.db", name));
+        let path = directory.join(format!("{}{}", name, Self::EXTENSION));
+
+        // Copied through SQLite over a connection of its own, consistent while the server runs.
+        AccountDatabase::open(database)?.snapshot(&snapshot)?;
+        let compressed = compress(&snapshot, &path);
+        fs::remove_file(&snapshot)?;
+        compressed?;
+
+        Self::prune(config, directory)?;
+        Ok(path)
+    }
+
+    /// Removes the oldest backups until only the retained amount remain.
+    fn prune(config: &BackupConfig, directory: &Path) -> io::Result<()> {
+        let mut backups: Vec<PathBuf> = fs::read_dir(directory)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(Self::PREFIX) && name.ends_with(Self::EXTENSION)
+                    })
+            })
+            .collect();
+
+        // Timestamps in the names sort oldest first.
+        backups.sort();
+        let excess = backups.len().saturating_sub(config.retention.max(1));
+        for old in backups.into_iter().take(excess) {
+            fs::remove_file(&old)?;
+            sprintln!("Removed old backup {}.", old.display());
+        }
+
+        Ok(())
+    }
+}
+
+/// Compresses a file into another.
+fn compress(source: &Path, destination: &Path) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(source)?);
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(destination)?),
+        Compression::default(),
+    );
+    io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Replaces the database with a backup, keeping the replaced database beside it named by when
+/// it was replaced.
+/// Must be done before the server opens the database.
+pub fn restore(backup: &Path, database: &Path) -> Result<(), Box<dyn Error>> {
+    let restoring = database.with_extension("restoring");
+    let mut decoder = GzDecoder::new(BufReader::new(File::open(backup)?));
+    let mut writer = BufWriter::new(File::create(&restoring)?);
+    if let Err(why) = io::copy(&mut decoder, &mut writer) {
+        drop(writer);
+        let _ = fs::remove_file(&restoring);
+        return Err(format!("{} is not a valid backup: {}", backup.display(), why).into());
+    }
+    drop(writer);
+
+    if database.exists() {
+        let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        let replaced = database.with_extension(format!("before-restore-{}", stamp));
+        fs::rename(database, &replaced)?;
+        sprintln!("Previous database kept as {}.", replaced.display());
+    }
+    fs::rename(&restoring, database)?;
+
+    sprintln!("Restored {} from {}.", database.display(), backup.display());
+    Ok(())
+}
@@ -0,0 +1,292 @@
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc as std_mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::{sleep, timeout};
+use uo2d_proto::sprintln;
+use uuid::Uuid;
+
+use crate::config::{ShardConfig, ShardPeer};
+use crate::shards::Handoff;
+
+/// Message carried between the servers sharing the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BusMessage {
+    /// Opens a link, identifying the server sending on it.
+    Hello {
+        server: String,
+        secret: String,
+    },
+    Chat {
+        uuid: Uuid,
+        message: String,
+    },
+    /// Players online on the sending server.
+    Presence {
+        online: u32,
+    },
+    Handoff(Handoff),
+    /// A handoff with the token was received, the player's client can follow it.
+    Accepted(Uuid),
+}
+
+/// Outgoing link to another server.
+struct Link {
+    sender: UnboundedSender<BusMessage>,
+    connected: Arc<AtomicBool>,
+}
+
+/// Connects the servers sharing the world, one line of JSON per message.
+/// Messages for a server that cannot be reached are buffered until it reconnects.
+pub struct Bus {
+    links: HashMap<String, Link>,
+    incoming: std_mpsc::Receiver<(String, BusMessage)>,
+}
+
+impl Bus {
+    /// Most messages held for a server before the oldest are dropped.
+    const MAX_BUFFERED: usize = 256;
+    const MIN_RETRY: Duration = Duration::from_secs(1);
+    const MAX_RETRY: Duration = Duration::from_secs(30);
+    /// Longest message accepted, in bytes. Links sending longer ones are closed.
+    const MAX_LINE: u64 = 64 * 1024;
+    /// Time a server has to identify itself once connected.
+    const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Connects to every peer and accepts their connections on the configured address.
+    /// Nothing is linked without a secret. Must be called from within a runtime.
+    pub fn start(config: &ShardConfig) -> Self {
+        let (incoming_tx, incoming) = std_mpsc::channel();
+        let Some(secret) = config.secret.clone().filter(|secret| !secret.is_empty()) else {
+            if config.listen.is_some() || !config.peers.is_empty() {
+                sprintln!("Not linking to other servers, the shards are missing a secret.");
+            }
+            return Self {
+                links: HashMap::new(),
+                incoming,
+            };
+        };
+
+        if let Some(address) = config.listen.clone() {
+            let secret = secret.clone();
+            let peers = config.peers.iter().map(|peer| peer.name.clone()).collect();
+            tokio::spawn(async move {
+                if let Err(why) = Self::listen(&address, secret, peers, incoming_tx).await {
+                    sprintln!("Unable to accept servers on {}: {}", address, why);
+                }
+            });
+        }
+
+        let hello = BusMessage::Hello {
+            server: config.name.clone(),
+            secret,
+        };
+        let links = config
+            .peers
+            .iter()
+            .map(|peer| {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                let connected = Arc::new(AtomicBool::new(false));
+                tokio::spawn(Self::link(
+                    peer.clone(),
+                    hello.clone(),
+                    receiver,
+                    Arc::clone(&connected),
+                ));
+                (peer.name.clone(), Link { sender, connected })
+            })
+            .collect();
+
+        Self { links, incoming }
+    }
+
+    /// Sends a message to a single server, returning false if it is not a peer.
+    pub fn send(&self, server: &str, message: BusMessage) -> bool {
+        match self.links.get(server) {
+            Some(link) => link.sender.send(message).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Sends a message to every server.
+    pub fn publish(&self, message: BusMessage) {
+        for link in self.links.values() {
+            let _ = link.sender.send(message.clone());
+        }
+    }
+
+    /// Checks if the link to a server is currently open.
+    pub fn is_connected(&self, server: &str) -> bool {
+        self.links
+            .get(server)
+            .is_some_and(|link| link.connected.load(Ordering::Relaxed))
+    }
+
+    /// Messages received since the last call, along with the server that sent them.
+    pub fn receive(&self) -> Vec<(String, BusMessage)> {
+        self.incoming.try_iter().collect()
+    }
+
+    /// Keeps a link to a server open, reconnecting with a backoff whenever it is lost.
+    async fn link(
+        peer: ShardPeer,
+        hello: BusMessage,
+        mut messages: UnboundedReceiver<BusMessage>,
+        connected: Arc<AtomicBool>,
+    ) {
+        let mut buffer = VecDeque::new();
+        let mut retry = Self::MIN_RETRY;
+        loop {
+            if let Ok(mut stream) = TcpStream::connect(&peer.bus).await {
+                if Self::write(&mut stream, &hello).await.is_ok() {
+                    sprintln!("Connected to {} at {}.", peer.name, peer.bus);
+                    connected.store(true, Ordering::Relaxed);
+                    retry = Self::MIN_RETRY;
+
+                    let closed = Self::forward(&mut stream, &mut buffer, &mut messages).await;
+                    connected.store(false, Ordering::Relaxed);
+                    if closed {
+                        return;
+                    }
+                    sprintln!("Lost the connection to {}, reconnecting.", peer.name);
+                }
+            }
+
+            // Keep buffering while waiting to reconnect.
+            let wait = sleep(retry);
+            tokio::pin!(wait);
+            loop {
+                tokio::select! {
+                    _ = &mut wait => break,
+                    message = messages.recv() => match message {
+                        Some(message) => Self::buffer(&mut buffer, &message),
+                        None => return,
+                    },
+                }
+            }
+            retry = (retry * 2).min(Self::MAX_RETRY);
+        }
+    }
+
+    /// Writes the buffered and new messages until the connection fails.
+    /// Returns true once the bus has been dropped and nothing more will be sent.
+    async fn forward(
+        stream: &mut TcpStream,
+        buffer: &mut VecDeque<String>,
+        messages: &mut UnboundedReceiver<BusMessage>,
+    ) -> bool {
+        loop {
+            while let Some(line) = buffer.front() {
+                if stream.write_all(line.as_bytes()).await.is_err() {
+                    return false;
+                }
+                buffer.pop_front();
+            }
+
+            match messages.recv().await {
+                Some(message) => Self::buffer(buffer, &message),
+                None => return true,
+            }
+        }
+    }
+
+    /// Holds a message until it can be written, dropping the oldest once full.
+    fn buffer(buffer: &mut VecDeque<String>, message: &BusMessage) {
+        let line = match serde_json::to_string(message) {
+            Ok(line) => format!("{}\n", line),
+            Err(why) => {
+                sprintln!("Unable to encode a message for the bus: {}", why);
+                return;
+            }
+        };
+
+        if buffer.len() >= Self::MAX_BUFFERED {
+            buffer.pop_front();
+        }
+        buffer.push_back(line);
+    }
+
+    /// Writes a single message.
+    async fn write(stream: &mut TcpStream, message: &BusMessage) -> io::Result<()> {
+        let line = format!("{}\n", serde_json::to_string(message)?);
+        stream.write_all(line.as_bytes()).await
+    }
+
+    /// Accepts links from the peers.
+    async fn listen(
+        address: &str,
+        secret: String,
+        peers: Arc<[String]>,
+        incoming: std_mpsc::Sender<(String, BusMessage)>,
+    ) -> io::Result<()> {
+        let listener = TcpListener::bind(address).await?;
+        sprintln!("Accepting servers on {}", listener.local_addr()?);
+        loop {
+            let (stream, _addr) = listener.accept().await?;
+            tokio::spawn(Self::receive_link(
+                stream,
+                secret.clone(),
+                Arc::clone(&peers),
+                incoming.clone(),
+            ));
+        }
+    }
+
+    /// Reads a single message, None once the link closes or sends one that is too long.
+    async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Option<String> {
+        let mut line = String::new();
+        match reader.take(Self::MAX_LINE).read_line(&mut line).await {
+            Ok(_) if line.ends_with('\n') => Some(line),
+            _ => None,
+        }
+    }
+
+    /// Reads the messages from a linked server, once it has identified itself as a peer.
+    async fn receive_link(
+        stream: TcpStream,
+        secret: String,
+        peers: Arc<[String]>,
+        incoming: std_mpsc::Sender<(String, BusMessage)>,
+    ) {
+        let addr = stream.peer_addr().ok();
+        let mut reader = BufReader::new(stream);
+        let hello = match timeout(Self::HELLO_TIMEOUT, Self::read_line(&mut reader)).await {
+            Ok(Some(line)) => serde_json::from_str(&line).ok(),
+            _ => None,
+        };
+
+        let server = match hello {
+            Some(BusMessage::Hello {
+                server,
+                secret: given,
+            }) if given == secret && peers.contains(&server) => server,
+            _ => {
+                sprintln!(
+                    "Refused a server from {:?}, incorrect hello, secret, or peer.",
+                    addr
+                );
+                return;
+            }
+        };
+
+        sprintln!("{} linked from {:?}.", server, addr);
+        while let Some(line) = Self::read_line(&mut reader).await {
+            match serde_json::from_str(&line) {
+                Ok(message) => {
+                    if incoming.send((server.clone(), message)).is_err() {
+                        return;
+                    }
+                }
+                Err(why) => sprintln!("Invalid message from {}: {}", server, why),
+            }
+        }
+        sprintln!("{} unlinked.", server);
+    }
+}
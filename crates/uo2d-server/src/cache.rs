@@ -1,51 +1,30 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::net::SocketAddr;
-use std::sync::{Arc, Mutex as SyncMutex};
+use std::sync::Arc;
 
 use tokio::sync::{Mutex as AsyncMutex, MutexGuard};
+use uo2d_proto::packet::{Action, Packet};
 use uuid::Uuid;
 
-use crate::{packet::Packet, server::Client};
+use crate::Client;
 
-/// Holds packets and allows for access between threads.
-#[derive(Clone)]
-pub struct PacketCacheSync {
-    /// Counts of each packet signature
-    counts: Arc<SyncMutex<HashMap<Vec<u8>, usize>>>,
-    packets: Arc<SyncMutex<Vec<Packet>>>,
-    allowed_duplicates: usize,
+/// How packets of an action are combined while waiting for the gamestate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coalesce {
+    /// Every packet is kept in the order received.
+    KeepAll,
+    /// Only the most recent packet from each client is kept.
+    Latest,
 }
 
-impl PacketCacheSync {
-    /// Creates a new cache for packets.
-    pub fn new(allowed_duplicates: usize) -> Self {
-        Self {
-            counts: Arc::new(SyncMutex::new(HashMap::new())),
-            packets: Arc::new(SyncMutex::new(Vec::new())),
-            allowed_duplicates,
-        }
-    }
-
-    /// Retrieve received packets from the cache. This clears the packet list and their counts.
-    pub fn get_all(&self) -> Vec<Packet> {
-        let mut counts = self.counts.lock().unwrap(); // Lock counts first
-        let mut packets = self.packets.lock().unwrap(); // Then lock packets
-
-        counts.clear();
-        std::mem::take(&mut *packets)
-    }
-
-    /// Add a new packet to the cache if it doesn't exceed allowed duplicates.
-    pub fn add(&self, packet: Packet) {
-        let mut counts = self.counts.lock().unwrap(); // Lock counts first, consistent with get_all
-
-        let signature = packet.signature();
-        let count = counts.entry(signature.to_vec()).or_insert(0);
-
-        if *count < self.allowed_duplicates {
-            *count += 1;
-            let mut packets = self.packets.lock().unwrap(); // Then lock packets
-            packets.push(packet);
+impl Coalesce {
+    /// Rule applied to the packets of an action.
+    fn rule(action: Option<Action>) -> Self {
+        match action {
+            // Newer requests replace older ones within a tick. Anything else is an intent that
+            // is lost if replaced, such as a shot or picking up an item.
+            Some(Action::Movement | Action::Leaderboard) => Coalesce::Latest,
+            _ => Coalesce::KeepAll,
         }
     }
 }
@@ -53,50 +32,35 @@ impl PacketCacheSync {
 /// Holds packets and allows for access between threads.
 #[derive(Clone)]
 pub struct PacketCacheAsync {
-    /// Counts of each packet signature
-    counts: Arc<AsyncMutex<HashMap<Vec<u8>, usize>>>,
     packets: Arc<AsyncMutex<VecDeque<Packet>>>,
-    allowed_duplicates: usize,
 }
 
 impl PacketCacheAsync {
     /// Creates a new cache for packets.
-    pub fn new(allowed_duplicates: usize) -> Self {
+    pub fn new() -> Self {
         Self {
-            counts: Arc::new(AsyncMutex::new(HashMap::new())),
             packets: Arc::new(AsyncMutex::new(VecDeque::new())),
-            allowed_duplicates,
         }
     }
 
-    /// Retrieve received packets from the cache. This clears the packet list and their counts.
+    /// Retrieve received packets from the cache. This clears the packet list.
     pub async fn get_all(&self) -> Vec<Packet> {
-        let mut counts = self.counts.lock().await;
         let mut packets = self.packets.lock().await;
-
-        counts.clear();
         std::mem::take(&mut *packets).into()
     }
 
-    /// Add a new packet to the cache if it doesn't exceed allowed duplicates.
+    /// Add a new packet to the cache, coalescing it with earlier packets by its action.
     pub async fn add(&self, packet: Packet) {
-        let mut counts = self.counts.lock().await;
-
-        let signature = packet.signature();
-        let count = counts.entry(signature.to_vec()).or_insert(0);
+        let mut packets = self.packets.lock().await;
 
-        if *count < self.allowed_duplicates {
-            *count += 1;
-        } else {
-            // If we already have the allowed number of duplicates, remove the oldest packet with the same signature.
-            let mut packets = self.packets.lock().await;
+        // The signature is the action and client, replace the older packet from them.
+        if Coalesce::rule(packet.action()) == Coalesce::Latest {
+            let signature = packet.signature();
             if let Some(position) = packets.iter().position(|p| p.signature() == signature) {
                 packets.remove(position);
             }
         }
 
-        // Add the new packet.
-        let mut packets = self.packets.lock().await;
         packets.push_back(packet);
     }
 }
@@ -139,9 +103,16 @@ impl ClientCache {
         self.lock().await.insert(client.uuid, client);
     }
 
-    /// Retrieves a vector of clients from the cache.
-    pub async fn values(&self) -> Vec<Client> {
-        self.lock().await.values().cloned().collect()
+    /// Addresses of the clients, only those with the uuids if given.
+    pub async fn addrs(&self, filter: Option<&HashSet<Uuid>>) -> Vec<SocketAddr> {
+        let clients = self.lock().await;
+        match filter {
+            None => clients.values().map(|client| client.addr).collect(),
+            Some(uuids) => uuids
+                .iter()
+                .filter_map(|uuid| clients.get(uuid).map(|client| client.addr))
+                .collect(),
+        }
     }
 
     pub async fn keys(&self) -> HashSet<Uuid> {
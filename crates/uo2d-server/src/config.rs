@@ -0,0 +1,558 @@
+use std::error::Error;
+
+use serde::Deserialize;
+use uo2d_proto::components::{ItemStack, Vec3};
+use uo2d_proto::packet::Version;
+use uo2d_proto::sprintln;
+
+use crate::region::SpawnerSpawn;
+
+/// Restricts joining to a set of accounts.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Whitelist {
+    pub enabled: bool,
+    /// Account names that are allowed to join, guests are never allowed.
+    pub accounts: Vec<String>,
+}
+
+impl Whitelist {
+    /// Checks if the account is allowed to join.
+    pub fn allows(&self, username: Option<&str>) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        username.is_some_and(|username| {
+            self.accounts
+                .iter()
+                .any(|account| account.eq_ignore_ascii_case(username))
+        })
+    }
+}
+
+/// Lengths of the phases of a match, in seconds.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct MatchConfig {
+    /// Players required before the countdown begins.
+    pub min_players: usize,
+    /// Time players are held in place before a round.
+    pub countdown: f32,
+    pub round_duration: f32,
+    /// Time the results are shown before returning to the lobby.
+    pub intermission: f32,
+}
+
+impl Default for MatchConfig {
+    fn default() -> Self {
+        Self {
+            min_players: 2,
+            countdown: 10.,
+            round_duration: 300.,
+            intermission: 10.,
+        }
+    }
+}
+
+/// Detection of cheating clients, kicking those with too many violations.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AntiCheatConfig {
+    pub enabled: bool,
+    /// Violation score a client is kicked at.
+    pub threshold: f64,
+    /// Score forgiven every second.
+    pub decay: f64,
+    /// Most projectiles a client can fire within a second.
+    pub max_fire_rate: f64,
+}
+
+impl Default for AntiCheatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 10.,
+            decay: 0.5,
+            max_fire_rate: 40.,
+        }
+    }
+}
+
+/// Announcement repeated to every player.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ScheduledAnnouncement {
+    pub message: String,
+    /// Time between each repeat, in seconds.
+    pub interval: f32,
+}
+
+/// Messages shown to players, changeable at runtime from the console.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AnnouncementConfig {
+    /// Message of the day, sent to players as they join.
+    pub motd: Option<String>,
+    pub scheduled: Vec<ScheduledAnnouncement>,
+}
+
+/// When a world event starts, either every day at a time or every interval.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSchedule {
+    /// Time of day in UTC, written as `HH:MM`.
+    At(String),
+    /// Time between each start, in seconds.
+    Interval(f32),
+}
+
+/// Effect a world event has on the world while it runs.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventHook {
+    /// Multiplies the experience players earn.
+    Experience(f64),
+    /// Places a spawner, its NPCs leaving along with it once the event ends.
+    Spawner(SpawnerSpawn),
+    /// Sends waves of NPCs marching on a region.
+    Invasion(InvasionConfig),
+}
+
+/// Waves of NPCs spawning around the edge of a region and marching on a point within it.
+/// Players who fight them off are rewarded once the last wave is defeated.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct InvasionConfig {
+    /// Name of the region invaded.
+    pub region: String,
+    /// Point the invaders march on, the spawn of the region if unset.
+    #[serde(default)]
+    pub target: Option<Vec3>,
+    /// Types of NPC making up each wave, taking turns.
+    pub npcs: Vec<String>,
+    pub waves: u32,
+    /// NPCs in each wave, before adding those for the players online.
+    pub size: u32,
+    /// Extra NPCs in each wave for every player online.
+    #[serde(default)]
+    pub per_player: f32,
+    /// Seconds between a wave being defeated and the next arriving.
+    #[serde(default = "InvasionConfig::default_delay")]
+    pub delay: f32,
+    /// Items given to each player who took part.
+    #[serde(default)]
+    pub rewards: Vec<ItemStack>,
+}
+
+impl InvasionConfig {
+    fn default_delay() -> f32 {
+        10.
+    }
+
+    /// NPCs in a wave with a number of players online.
+    pub fn wave_size(&self, online: u32) -> u32 {
+        self.size + (self.per_player.max(0.) * online as f32).round() as u32
+    }
+}
+
+/// Event taking over the world for a while, such as double experience or an invasion.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WorldEventConfig {
+    /// Name players are told the event by.
+    pub name: String,
+    #[serde(flatten)]
+    pub schedule: EventSchedule,
+    /// Time the event lasts, in seconds.
+    pub duration: f32,
+    #[serde(default)]
+    pub hooks: Vec<EventHook>,
+}
+
+/// Another server that owns part of the world.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ShardPeer {
+    /// Name the server identifies itself with.
+    pub name: String,
+    /// Names of the regions simulated by the server.
+    pub regions: Vec<String>,
+    /// Address clients are redirected to.
+    pub address: String,
+    /// Address the server accepts other servers on.
+    pub bus: String,
+}
+
+impl ShardPeer {
+    /// Checks if the server owns the region.
+    pub fn owns(&self, region: &str) -> bool {
+        self.regions
+            .iter()
+            .any(|owned| owned.eq_ignore_ascii_case(region))
+    }
+}
+
+/// Splits the world between servers, handing players off as they cross between them.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ShardConfig {
+    /// Name this server identifies itself to the others with.
+    pub name: String,
+    /// Address to accept other servers on, none are accepted if unset.
+    pub listen: Option<String>,
+    /// Shared by the servers, links with a different secret are refused.
+    /// Nothing is linked without one.
+    pub secret: Option<String>,
+    pub peers: Vec<ShardPeer>,
+}
+
+impl Default for ShardConfig {
+    fn default() -> Self {
+        Self {
+            name: "uo2d".to_string(),
+            listen: None,
+            secret: None,
+            peers: Vec::new(),
+        }
+    }
+}
+
+/// Compressed snapshots of the database taken while the server runs.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    /// Directory the backups are written to.
+    pub directory: String,
+    /// Time between each backup, in seconds.
+    pub interval: f32,
+    /// Backups kept, the oldest are removed beyond this.
+    pub retention: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "backups".to_string(),
+            interval: 3600.,
+            retention: 24,
+        }
+    }
+}
+
+/// Where the server keeps what it writes while running.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Path to the account database.
+    pub database: String,
+    /// Directory the event log is written to.
+    pub logs: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            database: "uo2d.db".to_string(),
+            logs: "logs".to_string(),
+        }
+    }
+}
+
+/// Anonymous gameplay metrics reported for balancing, only gathered once enabled.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Directory the reports are written to.
+    pub directory: String,
+    /// Time each report covers, in seconds.
+    pub interval: f32,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "telemetry".to_string(),
+            interval: 3600.,
+        }
+    }
+}
+
+/// Directory the world is loaded from, such as the regions, NPCs, and dialogue.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ContentConfig {
+    /// Files outside of it are refused, even if something refers to them.
+    pub root: String,
+    /// Largest file loaded, in bytes.
+    pub max_file_size: u64,
+    /// Directories of content packs within the root, each overriding those before it.
+    pub packs: Vec<String>,
+}
+
+impl Default for ContentConfig {
+    fn default() -> Self {
+        Self {
+            root: uo2d_proto::assets::ROOT.to_string(),
+            max_file_size: 1024 * 1024,
+            packs: vec![],
+        }
+    }
+}
+
+/// Connections between the server and its clients.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Time between each heartbeat sent to the clients, in seconds.
+    /// Clients missing three in a row are dropped.
+    pub heartbeat: u64,
+    /// Oldest client release allowed to join, such as `0.1.0`. Any release speaking the same protocol if unset.
+    pub min_client_version: Option<String>,
+    /// Packets sent to a client within a tick are written together at the end of it.
+    /// Pings, errors, and redirects are always written immediately.
+    pub aggregate: bool,
+    /// Most packets a client can send a second on average, unlimited if zero.
+    pub max_packet_rate: f64,
+    /// Most packets a client can send at once.
+    pub packet_burst: f64,
+    /// Accepts WebSockets on the same port for browsers, applied once the server is restarted.
+    pub websocket: bool,
+    /// Most WebSockets relayed at once.
+    pub max_websockets: usize,
+    /// Most WebSockets relayed at once from a single address.
+    pub websockets_per_address: usize,
+}
+
+impl NetworkConfig {
+    /// Oldest client release allowed to join.
+    pub fn min_client_version(&self) -> Version {
+        let Some(text) = &self.min_client_version else {
+            return Version::ZERO;
+        };
+
+        Version::parse(text).unwrap_or_else(|| {
+            sprintln!("Invalid minimum client version '{}', allowing any.", text);
+            Version::ZERO
+        })
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat: 5,
+            min_client_version: None,
+            aggregate: true,
+            max_packet_rate: 120.,
+            packet_burst: 60.,
+            websocket: false,
+            max_websockets: 256,
+            websockets_per_address: 4,
+        }
+    }
+}
+
+/// Distance in world units players see entities from, by the kind of entity.
+/// Measured from the center of the entity to the cells of the spatial hash.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct InterestConfig {
+    pub player: f64,
+    pub npc: f64,
+    pub projectile: f64,
+    /// Everything else, such as items on the ground and areas.
+    pub other: f64,
+}
+
+impl Default for InterestConfig {
+    fn default() -> Self {
+        Self {
+            player: 160.,
+            npc: 160.,
+            projectile: 160.,
+            other: 160.,
+        }
+    }
+}
+
+/// Movement sent to players far from an entity less often, by their distance from it.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ReplicationConfig {
+    /// Players closer than every band are sent each movement.
+    pub bands: Vec<ReplicationBand>,
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            bands: vec![
+                ReplicationBand {
+                    distance: 80.,
+                    ticks: 2,
+                },
+                ReplicationBand {
+                    distance: 120.,
+                    ticks: 4,
+                },
+            ],
+        }
+    }
+}
+
+/// Players at least the distance from an entity, in world units, are sent its movement every
+/// few ticks.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct ReplicationBand {
+    pub distance: f64,
+    pub ticks: u64,
+}
+
+/// Settings that were changed by reloading the configuration.
+#[derive(Debug, Default)]
+pub struct ConfigChanges {
+    /// Applied while running.
+    pub applied: Vec<&'static str>,
+    /// Only take effect once the server is restarted.
+    pub restart: Vec<&'static str>,
+}
+
+/// Settings for the server, loaded at launch.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Password required to join, if set.
+    pub password: Option<String>,
+    pub whitelist: Whitelist,
+    /// Fraction of damage dealt to teammates, none by default.
+    pub friendly_fire: f64,
+    /// Game mode played on the server, such as `ctf`.
+    pub mode: Option<String>,
+    /// Rounds of the game mode, unused while freely roaming.
+    #[serde(rename = "match")]
+    pub matches: MatchConfig,
+    pub anticheat: AntiCheatConfig,
+    pub announcements: AnnouncementConfig,
+    pub shards: ShardConfig,
+    pub backups: BackupConfig,
+    pub storage: StorageConfig,
+    pub telemetry: TelemetryConfig,
+    pub content: ContentConfig,
+    pub network: NetworkConfig,
+    pub interest: InterestConfig,
+    pub replication: ReplicationConfig,
+    pub world_events: Vec<WorldEventConfig>,
+    /// Account names allowed to view the debug overlays.
+    pub gamemasters: Vec<String>,
+    /// Where the configuration was loaded from, reloaded from the same place.
+    #[serde(skip)]
+    pub path: String,
+}
+
+impl ServerConfig {
+    pub const PATH: &'static str = "server.yaml";
+
+    /// Loads the configuration, using the defaults if it does not exist or is invalid.
+    pub fn load(path: &str) -> Self {
+        if !std::path::Path::new(path).exists() {
+            return Self {
+                path: path.to_string(),
+                ..Self::default()
+            };
+        }
+
+        match Self::read(path) {
+            Ok(config) => config,
+            Err(why) => {
+                sprintln!("Error while loading {}: {}", path, why);
+                Self {
+                    path: path.to_string(),
+                    ..Self::default()
+                }
+            }
+        }
+    }
+
+    /// Reads the configuration, failing if it does not exist or is invalid.
+    pub fn read(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = std::fs::read_to_string(path)?;
+        let config: Self = serde_yaml::from_str(&content)?;
+        Ok(Self {
+            path: path.to_string(),
+            ..config
+        })
+    }
+
+    /// Settings that differ in another configuration, split by whether they can be applied while running.
+    pub fn changes(&self, other: &Self) -> ConfigChanges {
+        let mut changes = ConfigChanges::default();
+        let mut compare = |name, changed: bool, live: bool| match (changed, live) {
+            (false, _) => (),
+            (true, true) => changes.applied.push(name),
+            (true, false) => changes.restart.push(name),
+        };
+
+        compare("password", self.password != other.password, true);
+        compare("whitelist", self.whitelist != other.whitelist, true);
+        compare(
+            "friendly_fire",
+            self.friendly_fire != other.friendly_fire,
+            true,
+        );
+        compare("anticheat", self.anticheat != other.anticheat, true);
+        compare(
+            "announcements.motd",
+            self.announcements.motd != other.announcements.motd,
+            true,
+        );
+        compare("backups", self.backups != other.backups, true);
+        compare("telemetry", self.telemetry != other.telemetry, true);
+        compare("network", self.network != other.network, true);
+        compare("gamemasters", self.gamemasters != other.gamemasters, true);
+        compare(
+            "announcements.scheduled",
+            self.announcements.scheduled != other.announcements.scheduled,
+            false,
+        );
+        compare("mode", self.mode != other.mode, false);
+        compare("match", self.matches != other.matches, false);
+        compare("shards", self.shards != other.shards, false);
+        compare("content", self.content != other.content, false);
+        compare("storage", self.storage != other.storage, false);
+        compare("interest", self.interest != other.interest, false);
+        compare("replication", self.replication != other.replication, false);
+        compare(
+            "world_events",
+            self.world_events != other.world_events,
+            false,
+        );
+        changes
+    }
+
+    /// Checks if the account is allowed to view the debug overlays.
+    pub fn is_gamemaster(&self, username: &str) -> bool {
+        self.gamemasters
+            .iter()
+            .any(|account| account.eq_ignore_ascii_case(username))
+    }
+
+    /// Ensures a client is allowed to join, returning the reason if not.
+    pub fn admit(
+        &self,
+        password: Option<&str>,
+        username: Option<&str>,
+    ) -> Result<(), &'static str> {
+        if let Some(required) = self.password.as_deref().filter(|p| !p.is_empty()) {
+            if password != Some(required) {
+                return Err("join.password");
+            }
+        }
+
+        if !self.whitelist.allows(username) {
+            return Err("join.whitelist");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use std::io::{self, BufRead};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use uo2d_proto::ecs::Entity;
+use uo2d_proto::sprintln;
+use uuid::Uuid;
+
+use super::config::ScheduledAnnouncement;
+use super::event_log::{self, ServerEvent};
+use super::handle::{EntityReport, ServerCommand};
+
+/// Reads administrative commands from standard input.
+pub struct Console {
+    commands: Sender<ServerCommand>,
+}
+
+impl Console {
+    const DEFAULT_TAIL: usize = 10;
+    const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Starts reading commands on a separate thread, passing changes on to the gamestate.
+    pub fn start(commands: Sender<ServerCommand>) {
+        let console = Self { commands };
+        std::thread::spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                match line {
+                    Ok(line) if !line.trim().is_empty() => console.execute(line.trim()),
+                    Ok(_) => continue,
+                    Err(_) => break,
+                }
+            }
+        });
+    }
+
+    /// Parses and executes a single command.
+    fn execute(&self, line: &str) {
+        event_log::record(ServerEvent::Admin {
+            command: line.to_string(),
+        });
+
+        let args: Vec<&str> = line.split_whitespace().collect();
+        match args.as_slice() {
+            ["help"] => Self::help(),
+            ["log", "tail"] => Self::log_tail(Self::DEFAULT_TAIL),
+            ["log", "tail", count] => match count.parse::<usize>() {
+                Ok(count) => Self::log_tail(count),
+                Err(_) => sprintln!("Invalid count: {}", count),
+            },
+            ["announce", _, ..] => self.send(ServerCommand::Announce(rest(line, 1))),
+            ["motd"] => self.list(true),
+            ["motd", "clear"] => self.send(ServerCommand::Motd(None)),
+            ["motd", _, ..] => self.send(ServerCommand::Motd(Some(rest(line, 1)))),
+            ["announcements"] => self.list(false),
+            ["announcements", "add", interval, _, ..] => match interval.parse::<f32>() {
+                Ok(interval) => self.send(ServerCommand::Schedule(ScheduledAnnouncement {
+                    message: rest(line, 3),
+                    interval,
+                })),
+                Err(_) => sprintln!("Invalid interval: {}", interval),
+            },
+            ["announcements", "remove", id] => match id.parse::<u32>() {
+                Ok(id) => self.send(ServerCommand::Unschedule(id)),
+                Err(_) => sprintln!("Invalid id: {}", id),
+            },
+            ["shards"] => self.shards(),
+            ["pools"] => self.pools(),
+            ["load"] => self.load(),
+            ["players"] => self.players(),
+            ["entities"] => self.census(),
+            ["find", uuid] => match Uuid::parse_str(uuid) {
+                Ok(uuid) => self.describe(|tx| ServerCommand::Find(uuid, tx)),
+                Err(_) => sprintln!("Invalid uuid: {}", uuid),
+            },
+            ["inspect", entity] | ["inspect", entity, _] => {
+                let instance = match args.get(2).map(|id| id.parse::<u32>()) {
+                    None => None,
+                    Some(Ok(id)) => Some(id),
+                    Some(Err(_)) => {
+                        sprintln!("Invalid instance: {}", args[2]);
+                        return;
+                    }
+                };
+                match entity.parse::<u64>() {
+                    Ok(id) => {
+                        self.describe(|tx| ServerCommand::Inspect(instance, Entity::new(id), tx))
+                    }
+                    Err(_) => sprintln!("Invalid entity: {}", entity),
+                }
+            }
+            ["backup"] => self.send(ServerCommand::Backup),
+            ["reload"] => self.send(ServerCommand::Reload),
+            ["reload", "loot"] => self.send(ServerCommand::ReloadLoot),
+            _ => sprintln!("Unknown command: '{}', try 'help'.", line),
+        }
+    }
+
+    /// Prints all available commands.
+    fn help() {
+        sprintln!("Commands:");
+        sprintln!("  help                                   Shows this message.");
+        sprintln!("  log tail [count]                       Shows the most recent events.");
+        sprintln!("  announce <message>                     Sends a message to every player.");
+        sprintln!("  motd [message|clear]                   Shows or changes the MOTD.");
+        sprintln!("  announcements                          Lists the scheduled announcements.");
+        sprintln!("  announcements add <seconds> <message>  Repeats a message on an interval.");
+        sprintln!("  announcements remove <id>              Stops repeating a message.");
+        sprintln!("  shards                                 Lists the servers sharing the world.");
+        sprintln!(
+            "  pools                                  Shows how often pooled entities are reused."
+        );
+        sprintln!("  load                                   Shows how much work is being shed.");
+        sprintln!(
+            "  players                                Lists the players and their positions."
+        );
+        sprintln!("  entities                               Counts the entities of each kind.");
+        sprintln!("  find <uuid>                            Shows the components of a player.");
+        sprintln!("  inspect <entity> [instance]            Shows the components of an entity.");
+        sprintln!("  backup                                 Saves and backs up the database now.");
+        sprintln!("  reload                                 Re-reads the configuration file.");
+        sprintln!("  reload loot                            Re-reads the loot tables.");
+    }
+
+    /// Prints the most recent events.
+    fn log_tail(count: usize) {
+        match event_log::tail(count) {
+            Ok(lines) => {
+                for line in lines {
+                    sprintln!("{}", line);
+                }
+            }
+            Err(why) => sprintln!("Unable to read the event log: {}", why),
+        }
+    }
+
+    /// Passes a command on to the gamestate.
+    fn send(&self, command: ServerCommand) {
+        if self.commands.send(command).is_err() {
+            sprintln!("The server is no longer running.");
+        }
+    }
+
+    /// Prints the message of the day, and the scheduled announcements unless only the motd is wanted.
+    fn list(&self, motd_only: bool) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(ServerCommand::Announcements(tx));
+        let announcements = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(announcements) => announcements,
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        sprintln!("MOTD: {}", announcements.motd().unwrap_or("(none)"));
+        if motd_only {
+            return;
+        }
+
+        for (id, announcement) in announcements.scheduled() {
+            sprintln!(
+                "  [{}] every {}s: {}",
+                id,
+                announcement.interval,
+                announcement.message
+            );
+        }
+    }
+
+    /// Prints the other servers sharing the world and the players online on each.
+    fn shards(&self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(ServerCommand::Shards(tx));
+        let shards = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(shards) => shards,
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        if shards.is_empty() {
+            sprintln!("No other servers are configured.");
+        }
+        for shard in shards {
+            let online = shard
+                .online
+                .map_or("unknown".to_string(), |online| online.to_string());
+            sprintln!(
+                "  {} ({}), {} online",
+                shard.name,
+                if shard.connected {
+                    "linked"
+                } else {
+                    "unreachable"
+                },
+                online
+            );
+        }
+    }
+
+    /// Prints every player in the world and where they are.
+    fn players(&self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(ServerCommand::Players(tx));
+        let players = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(players) => players,
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        if players.is_empty() {
+            sprintln!("No players are online.");
+        }
+        for player in players {
+            sprintln!(
+                "  {} (entity {}) at ({:.0}, {:.0}), {} health",
+                player.uuid,
+                player.entity,
+                player.position.x(),
+                player.position.y(),
+                player.health
+            );
+        }
+    }
+
+    /// Prints how long ticks are taking and the time spent shedding load.
+    fn load(&self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(ServerCommand::Load(tx));
+        let stats = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(stats) => stats,
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        sprintln!(
+            "  Shedding '{}' work, ticks averaging {:.2}ms of {:.2}ms.",
+            stats.level.name(),
+            stats.average.as_secs_f64() * 1000.,
+            stats.budget.as_secs_f64() * 1000.
+        );
+        sprintln!(
+            "  Raised {} times, lowered {} times.",
+            stats.raised,
+            stats.lowered
+        );
+        for (level, ticks) in stats.ticks {
+            sprintln!("  {}: {} ticks", level.name(), ticks);
+        }
+    }
+
+    /// Prints the entities of each kind within the overworld and every instance.
+    fn census(&self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(ServerCommand::Census(tx));
+        let census = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(census) => census,
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        for world in census {
+            let name = world
+                .instance
+                .map_or("overworld".to_string(), |id| format!("instance [{}]", id));
+            let kinds: Vec<String> = world
+                .kinds
+                .iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(kind, count)| format!("{} {}", count, kind))
+                .collect();
+            sprintln!("  {}: {}", name, kinds.join(", "));
+        }
+    }
+
+    /// Prints the components of the entity found by the command.
+    fn describe(
+        &self,
+        command: impl FnOnce(std::sync::mpsc::Sender<Option<EntityReport>>) -> ServerCommand,
+    ) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(command(tx));
+        let report = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(Some(report)) => report,
+            Ok(None) => {
+                sprintln!("No such entity.");
+                return;
+            }
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        let place = report
+            .instance
+            .map_or("overworld".to_string(), |id| format!("instance [{}]", id));
+        sprintln!("Entity {} in the {}:", report.entity, place);
+        for (name, component) in report.components {
+            sprintln!("  {}: {}", name, component);
+        }
+    }
+
+    /// Prints how many pooled entities were allocated versus reused.
+    fn pools(&self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(ServerCommand::Pools(tx));
+        let report = match rx.recv_timeout(Self::QUERY_TIMEOUT) {
+            Ok(report) => report,
+            Err(_) => {
+                sprintln!("The server did not respond.");
+                return;
+            }
+        };
+
+        for (kind, stats) in report.pools {
+            sprintln!(
+                "  {}: {} allocated, {} reused, {} released, {} waiting",
+                kind,
+                stats.allocated,
+                stats.reused,
+                stats.released,
+                stats.free
+            );
+        }
+        sprintln!(
+            "  {} components reused a released allocation.",
+            report.recycled
+        );
+    }
+}
+
+/// Text following the first `words` of a command, keeping its original spacing.
+fn rest(line: &str, words: usize) -> String {
+    let mut remaining = line.trim_start();
+    for _ in 0..words {
+        remaining = remaining
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim_start());
+    }
+    remaining.trim_end().to_string()
+}
@@ -0,0 +1,393 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use uo2d_proto::packet::Version;
+use uo2d_proto::sprintln;
+
+use crate::config::ContentConfig;
+
+/// Errors that occur while loading a file from the content root.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The path leads outside of the content root.
+    Outside(String),
+    /// The file is larger than the limit.
+    TooLarge { path: String, size: u64, limit: u64 },
+    /// The file could not be read.
+    Io { path: String, source: io::Error },
+    /// The file was read but its contents are invalid.
+    Invalid { path: String, why: String },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Outside(_) => write!(f, "outside of the content root"),
+            LoadError::TooLarge { size, limit, .. } => {
+                write!(f, "{} bytes, over the limit of {}", size, limit)
+            }
+            LoadError::Io { source, .. } => write!(f, "{}", source),
+            LoadError::Invalid { why, .. } => write!(f, "{}", why),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl LoadError {
+    /// Path of the file that failed to load, as it was given.
+    pub fn path(&self) -> &str {
+        match self {
+            LoadError::Outside(path)
+            | LoadError::TooLarge { path, .. }
+            | LoadError::Io { path, .. }
+            | LoadError::Invalid { path, .. } => path,
+        }
+    }
+
+    /// Marks the contents of a file as invalid.
+    pub fn invalid(path: &str, why: impl ToString) -> Self {
+        LoadError::Invalid {
+            path: path.to_string(),
+            why: why.to_string(),
+        }
+    }
+
+    fn io(path: &str, source: io::Error) -> Self {
+        LoadError::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+}
+
+/// Pack that has to be loaded before the one depending on it.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PackDependency {
+    pub name: String,
+    /// Oldest version of it that is supported, any if unset.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// Describes a content pack, read from the manifest within its directory.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PackManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub dependencies: Vec<PackDependency>,
+}
+
+/// Directory of regions, NPCs, loot tables, and dialogue mirroring the layout of the root.
+/// Anything it defines replaces what was loaded before it with the same name.
+#[derive(Debug, Clone)]
+pub struct ContentPack {
+    pub directory: String,
+    pub manifest: PackManifest,
+}
+
+impl ContentPack {
+    pub const MANIFEST: &'static str = "pack.yaml";
+
+    /// Path of the manifest within the pack.
+    fn manifest_path(directory: &str) -> String {
+        Path::new(directory)
+            .join(Self::MANIFEST)
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+/// Progress of a pack while they are being ordered by their dependencies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PackState {
+    Pending,
+    Visiting,
+    Loaded,
+    Failed,
+}
+
+/// Files the world is loaded from, refusing any outside of the root or over the size limit.
+#[derive(Debug, Clone)]
+pub struct Content {
+    root: PathBuf,
+    max_file_size: u64,
+    /// Packs in the order they are applied, dependencies before the packs requiring them.
+    packs: Vec<ContentPack>,
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Self::new(&ContentConfig::default())
+    }
+}
+
+impl Content {
+    /// Confines loading to the root, reading the manifests of the packs. Packs that fail to
+    /// load, or depend on one that did, are skipped.
+    pub fn new(config: &ContentConfig) -> Self {
+        let mut content = Self {
+            root: PathBuf::from(&config.root),
+            max_file_size: config.max_file_size,
+            packs: vec![],
+        };
+
+        let (packs, errors) = content.load_packs(&config.packs);
+        for why in errors {
+            sprintln!("Error while loading {}: {}", why.path(), why);
+        }
+        for pack in packs.iter() {
+            let manifest = &pack.manifest;
+            sprintln!(
+                "Loaded content pack {} {}.",
+                manifest.name,
+                manifest.version
+            );
+        }
+
+        content.packs = packs;
+        content
+    }
+
+    /// Directory everything is loaded from within.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of a file or directory within the root, such as `npcs.yaml`.
+    pub fn path(&self, relative: &str) -> String {
+        self.root.join(relative).to_string_lossy().to_string()
+    }
+
+    /// Packs in the order they are applied.
+    pub fn packs(&self) -> &[ContentPack] {
+        &self.packs
+    }
+
+    /// Reads the manifests of the packs, ordering them so each follows its dependencies and
+    /// otherwise keeping the order they were listed in.
+    fn load_packs(&self, directories: &[String]) -> (Vec<ContentPack>, Vec<LoadError>) {
+        let mut errors = vec![];
+        let mut packs: Vec<ContentPack> = vec![];
+        for directory in directories {
+            let path = ContentPack::manifest_path(directory);
+            let manifest: PackManifest = match self.parse(&path) {
+                Ok(manifest) => manifest,
+                Err(why) => {
+                    errors.push(why);
+                    continue;
+                }
+            };
+
+            if Version::parse(&manifest.version).is_none() {
+                let why = format!("invalid version '{}'", manifest.version);
+                errors.push(LoadError::invalid(&path, why));
+            } else if packs.iter().any(|pack| pack.manifest.name == manifest.name) {
+                let why = format!("pack '{}' is listed twice", manifest.name);
+                errors.push(LoadError::invalid(&path, why));
+            } else {
+                packs.push(ContentPack {
+                    directory: directory.clone(),
+                    manifest,
+                });
+            }
+        }
+
+        let mut states = vec![PackState::Pending; packs.len()];
+        let mut ordered = vec![];
+        for index in 0..packs.len() {
+            Self::visit(index, &packs, &mut states, &mut ordered, &mut errors);
+        }
+
+        let packs = ordered
+            .into_iter()
+            .map(|index| packs[index].clone())
+            .collect();
+        (packs, errors)
+    }
+
+    /// Orders a pack after its dependencies, returning if it can be loaded.
+    fn visit(
+        index: usize,
+        packs: &[ContentPack],
+        states: &mut [PackState],
+        ordered: &mut Vec<usize>,
+        errors: &mut Vec<LoadError>,
+    ) -> bool {
+        let pack = &packs[index];
+        let path = ContentPack::manifest_path(&pack.directory);
+        match states[index] {
+            PackState::Loaded => return true,
+            PackState::Failed => return false,
+            PackState::Visiting => {
+                let why = format!("pack '{}' depends on itself", pack.manifest.name);
+                errors.push(LoadError::invalid(&path, why));
+                return false;
+            }
+            PackState::Pending => states[index] = PackState::Visiting,
+        }
+
+        let mut loaded = true;
+        for dependency in pack.manifest.dependencies.iter() {
+            let name = &dependency.name;
+            let found = packs.iter().position(|other| &other.manifest.name == name);
+            let oldest = dependency.version.as_deref().map(Version::parse);
+            let why = match (found, oldest) {
+                (None, _) => Some(format!("requires '{}', which is not listed", name)),
+                (_, Some(None)) => Some(format!("requires an invalid version of '{}'", name)),
+                (Some(other), _) if !Self::visit(other, packs, states, ordered, errors) => {
+                    Some(format!("requires '{}', which failed to load", name))
+                }
+                (Some(other), Some(Some(oldest))) => {
+                    let version = &packs[other].manifest.version;
+                    let outdated = Version::parse(version).is_none_or(|version| version < oldest);
+                    outdated.then(|| {
+                        format!("requires '{}' {} or newer, found {}", name, oldest, version)
+                    })
+                }
+                _ => None,
+            };
+
+            if let Some(why) = why {
+                errors.push(LoadError::invalid(&path, why));
+                loaded = false;
+            }
+        }
+
+        states[index] = match loaded {
+            true => PackState::Loaded,
+            false => PackState::Failed,
+        };
+        if loaded {
+            ordered.push(index);
+        }
+        loaded
+    }
+
+    /// A path followed by its copies within each pack, in the order they override each other.
+    /// Packs mirror the layout of the root, copies they lack are skipped.
+    pub fn layers(&self, path: &str) -> Vec<String> {
+        let mut layers = vec![path.to_string()];
+        let Ok(relative) = Path::new(path).strip_prefix(&self.root) else {
+            return layers;
+        };
+
+        layers.extend(
+            self.packs
+                .iter()
+                .map(|pack| Path::new(&pack.directory).join(relative))
+                .filter(|path| path.exists())
+                .map(|path| path.to_string_lossy().to_string()),
+        );
+        layers
+    }
+
+    /// Resolves a path relative to the working directory, it must lead within the root.
+    /// Parent directories are refused outright, symbolic links are followed before checking.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, LoadError> {
+        let outside = || LoadError::Outside(path.to_string());
+        if Path::new(path)
+            .components()
+            .any(|component| component == Component::ParentDir)
+        {
+            return Err(outside());
+        }
+
+        let root = self
+            .root
+            .canonicalize()
+            .map_err(|why| LoadError::io(&self.root.to_string_lossy(), why))?;
+        let resolved = Path::new(path)
+            .canonicalize()
+            .map_err(|why| LoadError::io(path, why))?;
+        match resolved.starts_with(root) {
+            true => Ok(resolved),
+            false => Err(outside()),
+        }
+    }
+
+    /// Reads a file within the root, refusing it if it is over the size limit.
+    pub fn read(&self, path: &str) -> Result<String, LoadError> {
+        let resolved = self.resolve(path)?;
+        let too_large = |size| LoadError::TooLarge {
+            path: path.to_string(),
+            size,
+            limit: self.max_file_size,
+        };
+
+        let size = fs::metadata(&resolved)
+            .map_err(|why| LoadError::io(path, why))?
+            .len();
+        if size > self.max_file_size {
+            return Err(too_large(size));
+        }
+
+        // Never reads past the limit, even if the file grew since it was checked.
+        let mut content = String::new();
+        File::open(&resolved)
+            .and_then(|file| {
+                file.take(self.max_file_size + 1)
+                    .read_to_string(&mut content)
+            })
+            .map_err(|why| LoadError::io(path, why))?;
+        match content.len() as u64 > self.max_file_size {
+            true => Err(too_large(content.len() as u64)),
+            false => Ok(content),
+        }
+    }
+
+    /// Reads a YAML file within the root.
+    pub fn parse<T: DeserializeOwned>(&self, path: &str) -> Result<T, LoadError> {
+        serde_yaml::from_str(&self.read(path)?).map_err(|why| LoadError::invalid(path, why))
+    }
+
+    /// YAML files within a directory of the root, sorted by name.
+    pub fn yaml_files(&self, directory: &str) -> Result<Vec<String>, LoadError> {
+        let resolved = self.resolve(directory)?;
+        let entries = fs::read_dir(resolved).map_err(|why| LoadError::io(directory, why))?;
+
+        let mut files: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+            .filter_map(|path| {
+                let name = path.file_name()?;
+                Some(
+                    Path::new(directory)
+                        .join(name)
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            })
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// YAML files within a directory followed by those within its copies in each pack.
+    /// Directories that cannot be read are reported and skipped.
+    pub fn layered_yaml_files(&self, directory: &str) -> Vec<String> {
+        self.layers(directory)
+            .iter()
+            .flat_map(|layer| {
+                self.yaml_files(layer).unwrap_or_else(|why| {
+                    sprintln!("Error while loading {}: {}", why.path(), why);
+                    vec![]
+                })
+            })
+            .collect()
+    }
+}
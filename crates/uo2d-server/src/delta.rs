@@ -0,0 +1,94 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::components::{Position, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::{KeyframePayload, MovementDeltaPayload, MovementPayload};
+use uo2d_proto::packet::quantize::quantize;
+use uuid::Uuid;
+
+use crate::net::Event;
+
+/// Last full position sent for an entity.
+struct Keyframe {
+    id: u8,
+    position: Vec3,
+    recipients: HashSet<Uuid>,
+    deltas: u16,
+}
+
+/// Encodes movement as quantized offsets from the last keyframe sent for an entity.
+pub struct DeltaEncoder {
+    keyframes: HashMap<Entity, Keyframe>,
+}
+
+impl Default for DeltaEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeltaEncoder {
+    /// Deltas sent before a new keyframe is forced.
+    const KEYFRAME_INTERVAL: u16 = 60;
+
+    /// Creates a new encoder with no keyframes.
+    pub fn new() -> Self {
+        Self {
+            keyframes: HashMap::new(),
+        }
+    }
+
+    /// Creates the event for a movement, a delta if every recipient has the current keyframe.
+    pub fn encode(&mut self, movement: MovementPayload, recipients: &HashSet<Uuid>) -> Event {
+        if let Some(keyframe) = self.keyframes.get_mut(&movement.entity) {
+            let current = keyframe.deltas < Self::KEYFRAME_INTERVAL
+                && keyframe.position.z() == movement.position.z()
+                && recipients.is_subset(&keyframe.recipients);
+
+            let offset = movement
+                .position
+                .as_vec2()
+                .offset_from(&keyframe.position.as_vec2());
+            if let (true, Some(offset), Some(velocity)) =
+                (current, quantize(&offset), quantize(&movement.velocity))
+            {
+                keyframe.deltas += 1;
+                return Event::MovementDelta(MovementDeltaPayload::new(
+                    movement.entity,
+                    keyframe.id,
+                    offset,
+                    velocity,
+                ));
+            }
+        }
+
+        // Start a new keyframe, sent reliably. The id lets clients discard deltas for one that
+        // has yet to arrive.
+        let id = self
+            .keyframes
+            .get(&movement.entity)
+            .map_or(0, |keyframe| keyframe.id.wrapping_add(1));
+        self.keyframes.insert(
+            movement.entity,
+            Keyframe {
+                id,
+                position: movement.position,
+                recipients: recipients.clone(),
+                deltas: 0,
+            },
+        );
+
+        Event::Keyframe(KeyframePayload::new(id, movement))
+    }
+
+    /// Number of entities a keyframe is being kept for.
+    pub fn tracked(&self) -> usize {
+        self.keyframes.len()
+    }
+
+    /// Removes keyframes for entities that no longer exist.
+    pub fn prune(&mut self, world: &World) {
+        self.keyframes
+            .retain(|entity, _| world.get_component::<Position>(entity).is_some());
+    }
+}
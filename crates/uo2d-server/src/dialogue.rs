@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use uo2d_proto::components::{ItemId, ItemStack};
+use uo2d_proto::ecs::Entity;
+use uo2d_proto::packet::{Action, Payload};
+use uo2d_proto::sprintln;
+
+use crate::content::{Content, LoadError};
+use crate::gamestate::{GameHandlers, Gamestate};
+use crate::packet_processor::{Context, Handlers, Outcome};
+
+/// Requirement a player has to meet before a choice is offered to them.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Flag(String),
+    NotFlag(String),
+    Quest(String),
+    NotQuest(String),
+    /// Carrying at least `count` of an item.
+    Item {
+        item: ItemId,
+        count: u16,
+    },
+}
+
+/// Effect applied to a player once they pick a choice.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Hook {
+    Flag(String),
+    Quest(String),
+    /// Exchanges items with the player, refused if they lack what is taken or cannot carry what is given.
+    Trade {
+        #[serde(default)]
+        take: Vec<ItemStack>,
+        #[serde(default)]
+        give: Vec<ItemStack>,
+    },
+}
+
+/// Response a player can pick, moving the conversation along.
+#[derive(Debug, Deserialize, Clone)]
+pub struct DialogueChoice {
+    pub text: String,
+    /// Node shown afterwards, the conversation ends if unset.
+    #[serde(default)]
+    pub next: Option<String>,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+}
+
+/// Something said by an NPC along with the responses to it.
+#[derive(Debug, Deserialize, Clone)]
+pub struct DialogueNode {
+    pub text: String,
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+}
+
+/// Conversation an NPC can have, starting from the `start` node.
+#[derive(Debug, Deserialize, Clone)]
+pub struct DialogueTree {
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+impl DialogueTree {
+    /// Reads a tree from a YAML file, ensuring every node it refers to exists.
+    fn load(content: &Content, path: &str) -> Result<Self, LoadError> {
+        let tree: Self = content.parse(path)?;
+        let next = tree
+            .nodes
+            .values()
+            .flat_map(|node| node.choices.iter())
+            .filter_map(|choice| choice.next.as_ref());
+        for name in std::iter::once(&tree.start).chain(next) {
+            if !tree.nodes.contains_key(name) {
+                return Err(LoadError::invalid(
+                    path,
+                    format!("node '{}' does not exist", name),
+                ));
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Obtains a node by its name.
+    pub fn node(&self, name: &str) -> Option<&DialogueNode> {
+        self.nodes.get(name)
+    }
+}
+
+/// Conversation a player is having with an NPC.
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    pub npc: Entity,
+    pub tree: String,
+    pub node: String,
+    pub pages: Vec<String>,
+    /// Choices of the node offered to the player, by their index within the node.
+    pub offered: Vec<usize>,
+}
+
+/// Manages the dialogue trees for all NPCs, named after their file.
+pub struct DialogueManager {
+    trees: HashMap<String, DialogueTree>,
+}
+
+impl DialogueManager {
+    /// Directory of the dialogue trees, within the content root.
+    pub const DIRECTORY: &'static str = "dialogue";
+    /// Longest page of text sent at once, keeping a dialogue within a single packet.
+    const PAGE_LENGTH: usize = 240;
+
+    /// Loads every dialogue tree within a directory of the content root, trees within the packs
+    /// replacing any of the same name.
+    pub fn from_directory(content: &Content, directory: &str) -> Self {
+        let mut trees = HashMap::new();
+        for path in content.layered_yaml_files(directory) {
+            let name = match Path::new(&path).file_stem() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => continue,
+            };
+
+            match DialogueTree::load(content, &path) {
+                Ok(tree) => {
+                    trees.insert(name, tree);
+                }
+                Err(why) => sprintln!("Error while loading {}: {}", path, why),
+            }
+        }
+
+        Self { trees }
+    }
+
+    /// Obtains a tree by its name.
+    pub fn get(&self, name: &str) -> Option<&DialogueTree> {
+        self.trees.get(name)
+    }
+
+    /// Splits the text of a node into pages, breaking between words.
+    pub fn paginate(text: &str) -> Vec<String> {
+        let mut pages = vec![];
+        let mut page = String::new();
+        for word in text.split_whitespace() {
+            if !page.is_empty() && page.len() + word.len() + 1 > Self::PAGE_LENGTH {
+                pages.push(std::mem::take(&mut page));
+            }
+            if !page.is_empty() {
+                page.push(' ');
+            }
+            page.extend(word.chars().take(Self::PAGE_LENGTH));
+        }
+
+        if !page.is_empty() || pages.is_empty() {
+            pages.push(page);
+        }
+        pages
+    }
+}
+
+/// Registers the handler for the replies players pick in conversations.
+pub(crate) fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Dialogue, reply);
+}
+
+/// Registers the gamestate handler moving the conversations along.
+pub(crate) fn register_gamestate(handlers: &mut GameHandlers) {
+    handlers.register_handler(Action::Dialogue, Gamestate::dialogue);
+}
+
+/// Passes the reply on to the gamestate, which holds the conversation.
+fn reply(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::DialogueReply(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
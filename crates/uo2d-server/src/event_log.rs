@@ -0,0 +1,229 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use uo2d_proto::sprintln;
+use uo2d_proto::util::get_utc;
+use uuid::Uuid;
+
+use crate::anticheat::Violation;
+
+/// Global event log for the server, set once the server starts.
+static EVENT_LOG: OnceLock<Mutex<EventLog>> = OnceLock::new();
+
+/// Callback invoked whenever an event is recorded.
+type Listener = Box<dyn Fn(&ServerEvent) + Send + Sync>;
+
+/// Callbacks registered by embedders of the server.
+static LISTENERS: Mutex<Vec<Listener>> = Mutex::new(Vec::new());
+
+/// Events that are recorded to the event log.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ServerEvent {
+    Connect {
+        uuid: Uuid,
+        addr: SocketAddr,
+    },
+    Disconnect {
+        uuid: Uuid,
+        reason: String,
+    },
+    Chat {
+        uuid: Uuid,
+        message: String,
+    },
+    Death {
+        uuid: Uuid,
+    },
+    Admin {
+        command: String,
+    },
+    Violation {
+        uuid: Uuid,
+        violation: Violation,
+        score: f64,
+        evidence: String,
+    },
+}
+
+/// Single line within the event log.
+#[derive(Serialize)]
+struct Record<'a> {
+    time: String,
+    #[serde(flatten)]
+    event: &'a ServerEvent,
+}
+
+/// Writes events as JSON lines, rotating files by date and size.
+pub struct EventLog {
+    directory: PathBuf,
+    max_size: u64,
+    date: String,
+    size: u64,
+    file: File,
+}
+
+impl EventLog {
+    const MAX_SIZE: u64 = 10 * 1024 * 1024;
+
+    /// Opens the event log for the current date, creating the directory if needed.
+    fn open(directory: &Path, max_size: u64) -> io::Result<Self> {
+        fs::create_dir_all(directory)?;
+        let date = Self::today();
+        let (file, size) = Self::open_file(&Self::path_for(directory, &date))?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            max_size,
+            date,
+            size,
+            file,
+        })
+    }
+
+    /// Current date used to name log files.
+    fn today() -> String {
+        Utc::now().format("%Y-%m-%d").to_string()
+    }
+
+    /// Path to the active log file for a date.
+    fn path_for(directory: &Path, date: &str) -> PathBuf {
+        directory.join(format!("events-{}.jsonl", date))
+    }
+
+    /// Opens a file for appending, returning it with its current size.
+    fn open_file(path: &Path) -> io::Result<(File, u64)> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    }
+
+    /// Path to the file currently being written to.
+    fn current_path(&self) -> PathBuf {
+        Self::path_for(&self.directory, &self.date)
+    }
+
+    /// Starts a new file if the date has changed or the size limit is reached.
+    fn rotate(&mut self) -> io::Result<()> {
+        let today = Self::today();
+        if today != self.date {
+            self.date = today;
+        } else if self.size >= self.max_size {
+            // Archive the full file with the next free index.
+            let mut index = 1;
+            let archive = loop {
+                let path = self
+                    .directory
+                    .join(format!("events-{}.{}.jsonl", self.date, index));
+                if !path.exists() {
+                    break path;
+                }
+                index += 1;
+            };
+            fs::rename(self.current_path(), archive)?;
+        } else {
+            return Ok(());
+        }
+
+        (self.file, self.size) = Self::open_file(&self.current_path())?;
+        Ok(())
+    }
+
+    /// Appends an event to the log.
+    fn write(&mut self, event: &ServerEvent) -> io::Result<()> {
+        self.rotate()?;
+
+        let record = Record {
+            time: get_utc(),
+            event,
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Reads the last `count` lines from the current log file.
+    fn tail(&self, count: usize) -> io::Result<Vec<String>> {
+        let reader = BufReader::new(File::open(self.current_path())?);
+        let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
+        let start = lines.len().saturating_sub(count);
+        Ok(lines[start..].to_vec())
+    }
+}
+
+/// Initializes the global event log within the directory, only the first call has any effect.
+pub fn init(directory: &Path) {
+    if EVENT_LOG.get().is_some() {
+        return;
+    }
+
+    match EventLog::open(directory, EventLog::MAX_SIZE) {
+        Ok(log) => {
+            let _ = EVENT_LOG.set(Mutex::new(log));
+        }
+        Err(why) => sprintln!("Unable to open the event log: {}", why),
+    }
+}
+
+/// Registers a callback that is invoked for every recorded event.
+pub fn subscribe(callback: impl Fn(&ServerEvent) + Send + Sync + 'static) {
+    LISTENERS.lock().unwrap().push(Box::new(callback));
+}
+
+/// Records an event to the event log, if it has been initialized.
+pub fn record(event: ServerEvent) {
+    for listener in LISTENERS.lock().unwrap().iter() {
+        listener(&event);
+    }
+
+    if let Some(log) = EVENT_LOG.get() {
+        if let Err(why) = log.lock().unwrap().write(&event) {
+            sprintln!("Unable to write to the event log: {}", why);
+        }
+    }
+}
+
+/// Obtains the most recent events from the event log.
+pub fn tail(count: usize) -> io::Result<Vec<String>> {
+    match EVENT_LOG.get() {
+        Some(log) => log.lock().unwrap().tail(count),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Prints the events from a log file in order, paced by their recorded times when a speed is given.
+pub fn replay(path: &Path, speed: Option<f64>) -> io::Result<usize> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut last: Option<NaiveDateTime> = None;
+    let mut count = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(speed) = speed.filter(|speed| *speed > 0.) {
+            let time = serde_json::from_str::<serde_json::Value>(&line)
+                .ok()
+                .and_then(|record| record.get("time")?.as_str().map(String::from))
+                .and_then(|time| NaiveDateTime::parse_from_str(&time, "%Y-%m-%dT%H:%M:%S").ok());
+
+            // Wait as long as passed between the events, scaled by the speed.
+            if let (Some(previous), Some(time)) = (last, time) {
+                let wait = (time - previous).to_std().unwrap_or_default();
+                std::thread::sleep(wait.div_f64(speed));
+            }
+            last = time.or(last);
+        }
+
+        sprintln!("{}", line);
+        count += 1;
+    }
+
+    Ok(count)
+}
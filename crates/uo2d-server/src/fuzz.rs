@@ -0,0 +1,63 @@
+//! Entry point for the fuzz targets, taking datagrams down the path the socket server
+//! receives them on without a socket.
+
+use uo2d_proto::packet::{parse_handshake, Packet, Reliability};
+use uuid::Uuid;
+
+use crate::anticheat::AntiCheat;
+use crate::config::{AntiCheatConfig, NetworkConfig};
+use crate::middleware::{Inbound, Pipeline, RateLimit};
+use crate::packet_processor::{self, Handlers, Outcome};
+
+/// Single client sending datagrams to the server.
+pub struct Receiver {
+    uuid: Uuid,
+    reliability: Reliability,
+    rate_limit: RateLimit,
+    pipeline: Pipeline,
+    handlers: Handlers,
+    anticheat: AntiCheat,
+}
+
+impl Receiver {
+    pub fn new() -> Self {
+        let network = NetworkConfig::default();
+        Self {
+            uuid: Uuid::new_v4(),
+            reliability: Reliability::new(),
+            rate_limit: RateLimit::new(network.max_packet_rate, network.packet_burst),
+            pipeline: Pipeline::server(),
+            handlers: Handlers::new(),
+            anticheat: AntiCheat::new(AntiCheatConfig::default()),
+        }
+    }
+
+    /// Receives a datagram within the rate limit, passing what is left once acknowledged through
+    /// the middleware and on to its handler. Returns the packets forwarded or replied with.
+    pub fn receive(&mut self, data: &[u8], joined: bool) -> Vec<Packet> {
+        let data = parse_handshake(data).map_or(data, |handshake| handshake.packet);
+        let inbound = Inbound::new(self.uuid, joined, Packet::from_bytes(data));
+        let Some(packet) = packet_processor::admit(&self.rate_limit, &self.anticheat, inbound)
+        else {
+            return vec![];
+        };
+        let Some(packet) = self.reliability.receive(packet).packet else {
+            return vec![];
+        };
+
+        let inbound = Inbound::new(self.uuid, joined, packet);
+        packet_processor::handle(&self.pipeline, &self.handlers, &self.anticheat, inbound)
+            .into_iter()
+            .filter_map(|outcome| match outcome {
+                Outcome::Forward(packet) | Outcome::Reply(packet) => Some(packet),
+                Outcome::Send(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl Default for Receiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
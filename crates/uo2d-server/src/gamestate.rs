@@ -0,0 +1,3758 @@
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self as std_mpsc, Receiver};
+use std::time::Instant;
+
+use chrono::Utc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
+use tokio::time::{interval, timeout, MissedTickBehavior};
+use uo2d_proto::chunk::ChunkCoord;
+use uo2d_proto::components::{
+    Ability, Acceleration, Area, Bank, Banker, Boss, Bounds, Casting, Collidable, Cooldowns,
+    Dormant, Equipment, Falling, Flag, GroundItem, Health, Inventory, ItemId, ItemStack, Loot,
+    Mounted, MovementMode, Npc, Obstacle, ObstacleKind, Player, Position, Progress, Projectile,
+    Pushable, ResourceNode, Skills, Spawned, Spawner, Stats, StatusEffect, StatusEffects, Team,
+    Threat, Vec2, Vec3, Velocity,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::{ItemEffect, ItemManager};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{
+    AttackError, BankPayload, BankRequest, CredentialsPayload, DialoguePayload, DialogueReply,
+    EmotePayload, HealthPayload, LeaderboardPayload, MailboxPayload, MatchPhase, MovementPayload,
+    NotificationKind, NotificationPayload, OverlayKind, SpawnPayload, TargetPayload, TradeOffer,
+    TradePayload, TradeRequest, TradeState,
+};
+use uo2d_proto::packet::{Action, BroadcastScope, Packet, PacketConfiguration, Payload};
+use uo2d_proto::sprintln;
+use uo2d_proto::timer::{TimerData, TimerManager};
+use uo2d_proto::util::get_now;
+use uuid::Uuid;
+
+use super::accounts::{AccountDatabase, AccountError, AccountId, Character};
+use super::config::{AnnouncementConfig, EventHook, NetworkConfig, ServerConfig, ShardPeer};
+use super::handle::{EntityReport, PlayerInfo, PoolReport, ServerCommand, WorldCensus};
+use super::modes::{self, GameMode, ModeContext};
+use super::systems;
+use super::systems::movement::{self};
+use crate::announcements::Announcements;
+use crate::anticheat::{AntiCheat, Violation};
+use crate::backup::Backups;
+use crate::cache::PacketCacheAsync;
+use crate::content::Content;
+use crate::delta::DeltaEncoder;
+use crate::dialogue::{Conversation, DialogueManager};
+use crate::event_log::{self, ServerEvent};
+use crate::instance::{Instance, InstanceId, InstanceInfo, InstanceManager, Party};
+use crate::invasion::Invasion;
+use crate::load::{LoadShedder, Throttle};
+use crate::loot::LootManager;
+use crate::match_state::MatchState;
+use crate::net::{Event, Net};
+use crate::npcs::NpcManager;
+use crate::packet_processor;
+use crate::plots::PlotManager;
+use crate::plugin::{PluginContext, Plugins};
+use crate::region::{Region, RegionManager};
+use crate::replication::Replicator;
+use crate::resources::ResourceManager;
+use crate::shards::{Carried, Handoff, ShardEvent, Shards};
+use crate::spatial_hash::SpatialHash;
+use crate::telemetry::Telemetry;
+use crate::trades::TradeManager;
+use crate::world_events::{Transition, WorldEvents};
+
+/// Password hashed or verified away from the tick, finishing an authentication.
+struct Authentication {
+    uuid: Uuid,
+    username: String,
+    password: Result<Password, AccountError>,
+}
+
+enum Password {
+    /// Hashed for a new account.
+    Hashed(String),
+    /// Verified against an existing account.
+    Verified(AccountId),
+}
+
+/// Acts on a packet from a client within the current world.
+pub(crate) type Handler = fn(&mut Gamestate, Uuid, Payload);
+
+/// Handlers for the actions the gamestate acts on, registered by the modules implementing them.
+#[derive(Default)]
+pub(crate) struct GameHandlers {
+    handlers: HashMap<Action, Handler>,
+}
+
+impl GameHandlers {
+    /// Handlers for every action the gamestate acts on.
+    pub fn new() -> Self {
+        let mut handlers = Self::default();
+        register(&mut handlers);
+        crate::dialogue::register_gamestate(&mut handlers);
+        crate::trades::register_gamestate(&mut handlers);
+        handlers
+    }
+
+    /// Acts on the action with the handler, replacing any registered before it.
+    pub fn register_handler(&mut self, action: Action, handler: Handler) {
+        if self.handlers.insert(action, handler).is_some() {
+            sprintln!("Replaced the gamestate handler for {:?}.", action);
+        }
+    }
+
+    /// Handler for the action, if one is registered.
+    pub fn get(&self, action: &Action) -> Option<Handler> {
+        self.handlers.get(action).copied()
+    }
+}
+
+/// Registers the handlers for the core actions.
+fn register(handlers: &mut GameHandlers) {
+    handlers.register_handler(Action::ClientJoin, Gamestate::guest);
+    handlers.register_handler(Action::Register, |gamestate, uuid, payload| {
+        gamestate.authenticate(uuid, payload, true)
+    });
+    handlers.register_handler(Action::Login, |gamestate, uuid, payload| {
+        gamestate.authenticate(uuid, payload, false)
+    });
+    handlers.register_handler(Action::ClientLeave, |gamestate, uuid, _| {
+        gamestate.leave(&uuid)
+    });
+    handlers.register_handler(Action::Movement, Gamestate::movement);
+    handlers.register_handler(Action::Projectile, Gamestate::projectile);
+    handlers.register_handler(Action::Equip, |gamestate, uuid, payload| {
+        gamestate.equip(uuid, payload, true)
+    });
+    handlers.register_handler(Action::Unequip, |gamestate, uuid, payload| {
+        gamestate.equip(uuid, payload, false)
+    });
+    handlers.register_handler(Action::SplitStack, Gamestate::split_stack);
+    handlers.register_handler(Action::Drop, Gamestate::drop_item);
+    handlers.register_handler(Action::MoveStack, Gamestate::move_stack);
+    handlers.register_handler(Action::Pickup, |gamestate, uuid, _| gamestate.pickup(uuid));
+    handlers.register_handler(Action::Emote, Gamestate::emote);
+    handlers.register_handler(Action::Gather, |gamestate, uuid, _| gamestate.gather(uuid));
+    handlers.register_handler(Action::Leaderboard, |gamestate, uuid, _| {
+        gamestate.leaderboard(uuid, false)
+    });
+    handlers.register_handler(Action::Message, Gamestate::chat);
+    handlers.register_handler(Action::Mail, Gamestate::mail);
+    handlers.register_handler(Action::Mailbox, |gamestate, uuid, _| {
+        gamestate.mailbox(uuid)
+    });
+    handlers.register_handler(Action::Handoff, Gamestate::claim);
+    handlers.register_handler(Action::Bank, Gamestate::bank);
+    handlers.register_handler(Action::Overlay, Gamestate::request_overlays);
+    handlers.register_handler(Action::UseItem, Gamestate::use_item);
+    handlers.register_handler(Action::Mount, |gamestate, uuid, _| {
+        gamestate.toggle_mount(uuid)
+    });
+    handlers.register_handler(Action::Attack, Gamestate::attack);
+    // Only reach the gamestate when a plugin has taken them over.
+    handlers.register_handler(Action::Ping, |gamestate, uuid, payload| {
+        gamestate.answer(uuid, Action::Ping, payload)
+    });
+    handlers.register_handler(Action::Echo, |gamestate, uuid, payload| {
+        gamestate.answer(uuid, Action::Echo, payload)
+    });
+}
+
+/// Ensures the integrity of the game.
+pub struct Gamestate {
+    world: World,
+    sender: Sender<PacketConfiguration>,
+    /// Packets dropped since the last report because the socket server fell behind.
+    dropped: Cell<usize>,
+    timers: TimerManager,
+    cache: PacketCacheAsync,
+    anticheat: AntiCheat,
+    spatial: SpatialHash,
+    deltas: DeltaEncoder,
+    replicator: Replicator,
+    regions: RegionManager,
+    items: ItemManager,
+    npcs: NpcManager,
+    resources: ResourceManager,
+    /// Loot tables rolled for drops, reloadable while running.
+    loot: LootManager,
+    /// Where the world is loaded from, along with the packs applied over it.
+    content: Content,
+    /// Gameplay added by the plugins the server was built with.
+    plugins: Plugins,
+    /// Handlers for the actions forwarded by the socket server.
+    handlers: GameHandlers,
+    plots: PlotManager,
+    players: HashMap<Uuid, Entity>,
+    config: ServerConfig,
+    accounts: Option<AccountDatabase>,
+    sessions: HashMap<Uuid, AccountId>,
+    /// Players waiting on their password to be hashed or verified.
+    authenticating: HashSet<Uuid>,
+    /// Passes the outcome of hashing and verifying passwords back to the tick.
+    passwords: (std_mpsc::Sender<Authentication>, Receiver<Authentication>),
+    visible: HashMap<Uuid, HashMap<Entity, ItemStack>>,
+    chunks: HashMap<Uuid, HashSet<ChunkCoord>>,
+    obstacles: HashMap<Uuid, HashSet<Entity>>,
+    dialogues: DialogueManager,
+    /// Conversations players are having with NPCs, ended when they change worlds.
+    conversations: HashMap<Uuid, Conversation>,
+    /// Trades between players, cancelled when either of them changes worlds.
+    trades: TradeManager,
+    /// Players with their bank open, closed when they change worlds.
+    banking: HashSet<Uuid>,
+    /// Debug overlays each gamemaster is viewing.
+    overlays: HashMap<Uuid, Vec<OverlayKind>>,
+    mode: Option<Box<dyn GameMode>>,
+    matches: Option<MatchState>,
+    announcements: Announcements,
+    world_events: WorldEvents,
+    /// Spawners placed by the world events running, removed once they end.
+    event_spawners: HashMap<usize, Vec<Entity>>,
+    /// Invasions started by the world events running.
+    invasions: Vec<Invasion>,
+    backups: Backups,
+    telemetry: Telemetry,
+    /// Network settings shared with the socket server, updated on reload.
+    network: watch::Sender<NetworkConfig>,
+    commands: Receiver<ServerCommand>,
+    /// Instance currently being simulated, None while running the overworld.
+    instance: Option<InstanceInfo>,
+    instances: InstanceManager,
+    shards: Shards,
+    /// Work skipped in every world while ticks run over their budget.
+    shedder: LoadShedder,
+}
+
+impl Gamestate {
+    const PROJECTILE_LIFESPAN: f32 = 10.0;
+    /// Pool the ids of projectiles are recycled through.
+    const PROJECTILE_POOL: &'static str = "projectile";
+    const PROJECTILE_REACH: f64 = 96.;
+    const PROJECTILE_SIZE: f64 = 16.;
+    /// Distance a locked target can be attacked from.
+    const ATTACK_RANGE: f64 = 320.;
+    const PLAYER_HEALTH: u32 = 100;
+    const PLAYER_CAPACITY: u32 = 100;
+    const ITEM_DECAY: u64 = 300;
+    /// Seconds between emotes.
+    const EMOTE_COOLDOWN: f32 = 2.;
+    /// Seconds between attempts to gather.
+    const GATHER_COOLDOWN: f32 = 1.;
+    const SPAWN_PROTECTION: u64 = 30;
+    const DROP_REPORT_TICKS: u64 = 50;
+    /// Ticks between cross-checks of the spatial hash in debug builds.
+    const SPATIAL_CHECK_TICKS: u64 = 100;
+    /// Letters sent at once, keeping the mailbox within a single packet.
+    const MAILBOX_SIZE: usize = 4;
+    const MAX_MAIL_LENGTH: usize = 120;
+    /// Distance players can trade within.
+    const TRADE_RANGE: f64 = 128.;
+    /// Ticks between each refresh of the debug overlays.
+    const OVERLAY_TICKS: u64 = 5;
+    /// Size of the cells of the spatial hash, roughly that of the entities.
+    const CELL_SIZE: usize = 32;
+
+    /// Create a new Gamestate.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tx: Sender<PacketConfiguration>,
+        cache: PacketCacheAsync,
+        anticheat: AntiCheat,
+        content: Content,
+        regions: RegionManager,
+        plugins: Plugins,
+        config: ServerConfig,
+        network: watch::Sender<NetworkConfig>,
+        commands: Receiver<ServerCommand>,
+    ) -> Self {
+        // Accounts are optional, guests can still join without them.
+        let accounts = match AccountDatabase::open(&config.storage.database) {
+            Ok(db) => Some(db),
+            Err(why) => {
+                sprintln!("Unable to open the account database: {}", why);
+                None
+            }
+        };
+
+        let mut timers = TimerManager::new();
+        let announcements = Announcements::new(config.announcements.clone(), &mut timers);
+        let world_events = WorldEvents::new(config.world_events.clone(), Utc::now());
+        let backups = Backups::new(config.backups.clone(), &mut timers);
+        let telemetry = Telemetry::new(config.telemetry.clone(), &mut timers);
+        let shards = Shards::start(config.shards.clone());
+        let plots = PlotManager::new(regions.plots());
+        let shedder = LoadShedder::new(timers.server_tick_time());
+        let spatial = SpatialHash::bounded(Self::CELL_SIZE, regions.extent());
+        let items = ItemManager::from_root(&content.root().to_string_lossy());
+        let loot = LootManager::new(&content, &items);
+        let dialogues =
+            DialogueManager::from_directory(&content, &content.path(DialogueManager::DIRECTORY));
+
+        let mut gamestate = Self {
+            world: Self::create_world(&plugins),
+            sender: tx,
+            dropped: Cell::new(0),
+            timers,
+            cache,
+            anticheat,
+            spatial,
+            deltas: DeltaEncoder::new(),
+            replicator: Replicator::new(&config.replication),
+            regions,
+            items,
+            npcs: NpcManager::new(&content),
+            resources: ResourceManager::new(&content),
+            loot,
+            content,
+            plugins,
+            handlers: GameHandlers::new(),
+            plots,
+            players: HashMap::new(),
+            config,
+            accounts,
+            sessions: HashMap::new(),
+            authenticating: HashSet::new(),
+            passwords: std_mpsc::channel(),
+            visible: HashMap::new(),
+            chunks: HashMap::new(),
+            obstacles: HashMap::new(),
+            dialogues,
+            conversations: HashMap::new(),
+            trades: TradeManager::new(),
+            banking: HashSet::new(),
+            overlays: HashMap::new(),
+            mode: None,
+            matches: None,
+            announcements,
+            world_events,
+            event_spawners: HashMap::new(),
+            invasions: Vec::new(),
+            backups,
+            telemetry,
+            network,
+            commands,
+            instance: None,
+            instances: InstanceManager::default(),
+            shards,
+            shedder,
+        };
+
+        let interest = gamestate.config.interest.clone();
+        gamestate.spatial.set_interest(interest);
+        gamestate.spawn_obstacles();
+        gamestate.spawn_spawners();
+        gamestate.spawn_resources();
+        gamestate.load_ground_items();
+        gamestate.load_plots();
+        gamestate.start_mode();
+        gamestate
+    }
+
+    /// Creates a world with every component registered, including those of the plugins.
+    fn create_world(plugins: &Plugins) -> World {
+        let mut world = World::new();
+        world.register_component::<Position>();
+        world.register_component::<Velocity>();
+        world.register_component::<Acceleration>();
+        world.register_component::<Player>();
+        world.register_component::<Projectile>();
+        world.register_component::<Health>();
+        world.register_component::<Equipment>();
+        world.register_component::<Inventory>();
+        world.register_component::<GroundItem>();
+        world.register_component::<Collidable>();
+        world.register_component::<Pushable>();
+        world.register_component::<Obstacle>();
+        world.register_component::<Stats>();
+        world.register_component::<Team>();
+        world.register_component::<Flag>();
+        world.register_component::<StatusEffects>();
+        world.register_component::<Cooldowns>();
+        world.register_component::<Casting>();
+        world.register_component::<Mounted>();
+        world.register_component::<MovementMode>();
+        world.register_component::<Falling>();
+        world.register_component::<Npc>();
+        world.register_component::<Spawner>();
+        world.register_component::<Spawned>();
+        world.register_component::<Threat>();
+        world.register_component::<Boss>();
+        world.register_component::<Dormant>();
+        world.register_component::<Banker>();
+        world.register_component::<Bank>();
+        world.register_component::<Loot>();
+        world.register_component::<Area>();
+        world.register_component::<Progress>();
+        world.register_component::<Skills>();
+        world.register_component::<ResourceNode>();
+        plugins.register(&mut world);
+        // Movement iterates the moving entities every tick.
+        world.group::<Position, Velocity>();
+        // Projectiles are only reused once their lifespan timer can no longer refer to them.
+        let lifespan = Self::PROJECTILE_LIFESPAN * TimerManager::SERVER_TICKS_PER_SECOND;
+        world.register_pool(Self::PROJECTILE_POOL, lifespan.ceil() as u64 + 1);
+        world
+    }
+
+    /// Starts the game mode selected by the configuration.
+    fn start_mode(&mut self) {
+        let name = match self.config.mode.as_deref() {
+            Some(name) => name,
+            None => return,
+        };
+
+        let mode = match modes::from_name(name) {
+            Some(mode) => mode,
+            None => {
+                sprintln!("Unknown game mode '{}'.", name);
+                return;
+            }
+        };
+
+        sprintln!("Game mode '{}' started.", mode.name());
+        self.mode = Some(mode);
+        let mut net = Net::new();
+        self.with_mode(&mut net, |mode, ctx| mode.start(ctx));
+        self.dispatch(net);
+        self.matches = Some(MatchState::new(self.config.matches.clone()));
+    }
+
+    /// Runs a function against the game mode, gathering the events it produced.
+    fn with_mode(&mut self, net: &mut Net, f: impl FnOnce(&mut dyn GameMode, &mut ModeContext)) {
+        let mut mode = match self.mode.take() {
+            Some(mode) => mode,
+            None => return,
+        };
+
+        f(
+            mode.as_mut(),
+            &mut ModeContext {
+                world: &mut self.world,
+                spatial: &mut self.spatial,
+                regions: &self.regions,
+                net,
+            },
+        );
+        self.mode = Some(mode);
+    }
+
+    /// Runs a function against the plugins, gathering the events they produced.
+    fn with_plugins<T>(
+        &mut self,
+        net: &mut Net,
+        f: impl FnOnce(&mut Plugins, &mut PluginContext) -> T,
+    ) -> T {
+        let mut plugins = std::mem::take(&mut self.plugins);
+        let result = f(
+            &mut plugins,
+            &mut PluginContext {
+                world: &mut self.world,
+                spatial: &mut self.spatial,
+                regions: &self.regions,
+                items: &self.items,
+                net,
+                tick: self.timers.tick(),
+            },
+        );
+        self.plugins = plugins;
+        result
+    }
+
+    /// Checks if players are held in place while a round is about to begin.
+    /// Matches are only played in the overworld.
+    fn is_frozen(&self) -> bool {
+        self.instance.is_none()
+            && self
+                .matches
+                .as_ref()
+                .is_some_and(|matches| matches.is_frozen())
+    }
+
+    /// Moves the match to its next phase, applying the rules for entering it.
+    fn advance_match(&mut self) {
+        let phase = match self.matches.as_mut() {
+            Some(matches) => matches.advance(&mut self.timers),
+            None => return,
+        };
+
+        let mut net = Net::new();
+        match phase {
+            MatchPhase::Lobby => {
+                self.reset_players(&mut net);
+                Self::announce(&mut net, "match.waiting".into());
+            }
+            MatchPhase::Countdown => {
+                self.halt_players();
+                Self::announce(
+                    &mut net,
+                    Text::new("match.countdown")
+                        .with("seconds", self.config.matches.countdown.ceil()),
+                );
+            }
+            MatchPhase::Active => self.with_mode(&mut net, |mode, ctx| mode.round_start(ctx)),
+            MatchPhase::Ended => self.with_mode(&mut net, |mode, ctx| mode.round_end(ctx)),
+        };
+
+        if let Some(matches) = self.matches.as_ref() {
+            net.broadcast(Event::Match(matches.payload(&self.timers)));
+        }
+        self.dispatch(net);
+    }
+
+    /// Shields a player from damage for a short time after spawning.
+    fn protect(&mut self, net: &mut Net, entity: &Entity) {
+        systems::effects::apply(
+            &mut self.world,
+            &self.spatial,
+            net,
+            entity,
+            StatusEffect::Invulnerable,
+            self.timers.tick() + Self::SPAWN_PROTECTION,
+        )
+    }
+
+    /// Announcement sent to every player.
+    fn announce(net: &mut Net, message: Text) {
+        net.broadcast(Event::Notification(NotificationPayload::new(
+            NotificationKind::Announcement,
+            message,
+        )));
+    }
+
+    /// Starts and ends the world events that are due, announcing them to every player.
+    fn run_world_events(&mut self, net: &mut Net) {
+        for transition in self.world_events.update(Utc::now()) {
+            let (Transition::Started(id) | Transition::Ended(id)) = transition;
+            let Some(event) = self.world_events.get(id).cloned() else {
+                continue;
+            };
+
+            if transition == Transition::Ended(id) {
+                sprintln!("World event '{}' has ended.", event.name);
+                Self::announce(
+                    net,
+                    Text::new("world_event.ended").with("event", &event.name),
+                );
+                // Their NPCs are despawned once they find the spawner gone.
+                for spawner in self.event_spawners.remove(&id).unwrap_or_default() {
+                    self.world.despawn(&spawner);
+                }
+                let (overrun, invasions) = std::mem::take(&mut self.invasions)
+                    .into_iter()
+                    .partition(|invasion| invasion.event == id);
+                self.invasions = invasions;
+                for invasion in overrun {
+                    Self::announce(
+                        net,
+                        Text::new("invasion.overrun")
+                            .with_text("region", Region::text(invasion.region())),
+                    );
+                    invasion.end(&mut self.world);
+                }
+                continue;
+            }
+
+            sprintln!("World event '{}' has started.", event.name);
+            Self::announce(
+                net,
+                Text::new("world_event.started").with("event", &event.name),
+            );
+            for hook in event.hooks {
+                match hook {
+                    EventHook::Spawner(spawn) => {
+                        let spawner = systems::spawners::place(
+                            &mut self.world,
+                            &mut self.spatial,
+                            &self.npcs,
+                            spawn,
+                        );
+                        self.event_spawners.entry(id).or_default().push(spawner);
+                    }
+                    EventHook::Invasion(config) => {
+                        let Some(region) = self.regions.find(&config.region) else {
+                            sprintln!(
+                                "Unable to invade '{}', the region is not defined.",
+                                config.region
+                            );
+                            continue;
+                        };
+                        let invasion = Invasion::start(
+                            &mut self.world,
+                            &mut self.spatial,
+                            &self.npcs,
+                            region,
+                            config,
+                            id,
+                        );
+                        self.invasions.push(invasion);
+                    }
+                    EventHook::Experience(_) => (),
+                }
+            }
+        }
+    }
+
+    /// Advances the invasions, rewarding those who took part once the last wave is defeated.
+    fn run_invasions(&mut self, net: &mut Net) {
+        if self.invasions.is_empty() {
+            return;
+        }
+
+        let online = self.online();
+        let tick = self.timers.tick();
+        for mut invasion in std::mem::take(&mut self.invasions) {
+            let repelled = invasion.update(
+                &mut self.world,
+                &mut self.spatial,
+                &self.npcs,
+                net,
+                tick,
+                online,
+            );
+            if !repelled {
+                self.invasions.push(invasion);
+                continue;
+            }
+
+            sprintln!("The invasion of '{}' was repelled.", invasion.region());
+            Self::announce(
+                net,
+                Text::new("invasion.repelled").with_text("region", Region::text(invasion.region())),
+            );
+            invasion.reward(&mut self.world, &self.items, net);
+            invasion.end(&mut self.world);
+        }
+    }
+
+    /// Stops every player where they stand.
+    fn halt_players(&mut self) {
+        for entity in self.players.values() {
+            self.world.remove_component::<Velocity>(*entity);
+            self.world.remove_component::<Acceleration>(*entity);
+        }
+    }
+
+    /// Returns every player to the spawn with full health, clearing any projectiles in flight.
+    fn reset_players(&mut self, net: &mut Net) {
+        self.halt_players();
+
+        let spawn = self.get_spawn_region().spawn;
+        for entity in self.players.values().copied().collect::<Vec<_>>() {
+            self.teleport(net, &entity, spawn);
+            self.protect(net, &entity);
+            let healed = self
+                .world
+                .get_component_mut::<Health>(&entity)
+                .map(|health| {
+                    let restored = health.heal(health.max);
+                    (restored, health.current)
+                });
+            if let Some((restored, current)) = healed.filter(|(restored, _)| *restored > 0) {
+                net.broadcast_to(
+                    movement::get_observers(&self.world, &self.spatial, &entity),
+                    Event::Healed(HealthPayload::new(entity, restored, current)),
+                );
+            }
+        }
+
+        for (entity, _) in self.world.query1::<Projectile>() {
+            self.timers
+                .add_timer_tick(0, TimerData::EntityDelete(entity));
+        }
+    }
+
+    /// Moves an entity within the current world, sending the movement to those observing it.
+    fn teleport(&mut self, net: &mut Net, entity: &Entity, loc: Vec3) {
+        let Some(position) = self.world.get_component::<Position>(entity).copied() else {
+            return;
+        };
+        let moved = Position::new(loc, position.size);
+        self.spatial.remove_object(entity, &position.bounds());
+        self.spatial.insert_object(entity, &moved.bounds());
+        self.world.upsert_component(*entity, moved);
+        if let Some(player) = self.world.get_component::<Player>(entity) {
+            self.anticheat.relocated(player.uuid());
+        }
+
+        let observers = movement::get_observers(&self.world, &self.spatial, entity);
+        let payload = MovementPayload::new(*entity, moved.size, moved.loc, Vec2::ORIGIN);
+        let event = self.deltas.encode(payload, &observers);
+        net.broadcast_to(observers, event);
+    }
+
+    /// Places the obstacles defined by the regions simulated by the current world.
+    fn spawn_obstacles(&mut self) {
+        let region = self.instance.map(|info| info.region);
+        for obstacle in self.regions.obstacles(region) {
+            let entity = systems::obstacles::spawn(
+                &mut self.world,
+                &mut self.spatial,
+                obstacle.kind,
+                obstacle.position,
+            );
+
+            // Containers are filled right away, everything else drops its loot once destroyed.
+            if let Some(table) = obstacle.loot {
+                let level = obstacle.level;
+                self.world.upsert_component(entity, Loot { table, level });
+                systems::loot::fill(
+                    &mut self.world,
+                    &self.items,
+                    &self.loot,
+                    &entity,
+                    self.timers.tick(),
+                );
+            }
+        }
+    }
+
+    /// Places the NPC spawners defined by the regions simulated by the current world.
+    fn spawn_spawners(&mut self) {
+        let region = self.instance.map(|info| info.region);
+        for spawn in self.regions.spawners(region) {
+            systems::spawners::place(&mut self.world, &mut self.spatial, &self.npcs, spawn);
+        }
+    }
+
+    /// Places the resource nodes defined by the regions simulated by the current world.
+    fn spawn_resources(&mut self) {
+        let region = self.instance.map(|info| info.region);
+        for spawn in self.regions.resources(region) {
+            match self.resources.get(&spawn.resource) {
+                Some(definition) => {
+                    systems::resources::spawn(
+                        &mut self.world,
+                        &mut self.spatial,
+                        definition,
+                        spawn.position,
+                    );
+                }
+                None => sprintln!("Unable to place '{}', it is not defined.", spawn.resource),
+            }
+        }
+    }
+
+    /// Restores the items left on the ground, skipping those that decayed while offline.
+    fn load_ground_items(&mut self) {
+        let items = match &self.accounts {
+            Some(db) => match db.load_ground_items() {
+                Ok(items) => items,
+                Err(why) => {
+                    sprintln!("Unable to load ground items: {}", why);
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        let now = get_now();
+        for ground in items.into_iter().filter(|ground| ground.expires > now) {
+            let entity = systems::ground::place(&mut self.world, &mut self.spatial, ground);
+            self.timers.add_timer_sec(
+                (ground.expires - now) as f32,
+                TimerData::ItemDecay(entity),
+                true,
+            );
+        }
+    }
+
+    /// Restores the claimed housing plots and the objects placed within them.
+    fn load_plots(&mut self) {
+        let records = match &self.accounts {
+            Some(db) => match db.load_plots() {
+                Ok(records) => records,
+                Err(why) => {
+                    sprintln!("Unable to load plots: {}", why);
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        for record in records {
+            let plot = match self.plots.get_mut(&record.name) {
+                Some(plot) => plot,
+                None => {
+                    sprintln!("Plot '{}' is no longer defined, skipping it.", record.name);
+                    continue;
+                }
+            };
+
+            plot.owner = Some(record.owner);
+            plot.members = record.members.clone();
+            systems::plots::restore(&mut self.world, &mut self.spatial, &record);
+        }
+    }
+
+    /// Saves the ownership and furnishings of a plot, releasing it if it is no longer claimed.
+    fn save_plot(&self, name: &str) {
+        let (db, plot) = match (&self.accounts, self.plots.get(name)) {
+            (Some(db), Some(plot)) => (db, plot),
+            _ => return,
+        };
+
+        let saved = match systems::plots::record(&self.world, plot) {
+            Some(record) => db.save_plot(&record),
+            None => db.abandon_plot(name),
+        };
+        if let Err(why) = saved {
+            sprintln!("Unable to save plot '{}': {}", name, why);
+        }
+    }
+
+    /// Obtains all pending packets from the cache.
+    /// Packets left waiting are picked up on the next tick instead of stalling this one.
+    pub async fn get_packets(&mut self) -> Vec<Packet> {
+        let wait = self.timers.server_tick_time() / 4;
+        timeout(wait, self.cache.get_all())
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Queues a packet for the socket server without waiting, counting it if dropped.
+    /// Broadcasts from within an instance only reach the players inside of it.
+    fn send(&self, packet: PacketConfiguration) {
+        let packet = match (packet, self.instance) {
+            (PacketConfiguration::Broadcast(packet, BroadcastScope::Global), Some(_)) => {
+                let players = self.players.keys().copied().collect();
+                PacketConfiguration::Broadcast(packet, BroadcastScope::Local(players))
+            }
+            (packet, _) => packet,
+        };
+
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(packet) {
+            self.dropped.set(self.dropped.get() + 1);
+        }
+    }
+
+    /// Sends the events to the players within the current world.
+    fn dispatch(&self, mut net: Net) {
+        for packet in net.drain(&self.world, &self.spatial) {
+            self.send(packet);
+        }
+    }
+
+    /// Sends an event to a single player.
+    fn send_to(&self, uuid: Uuid, event: Event) {
+        let mut net = Net::new();
+        net.send_to(uuid, event);
+        self.dispatch(net);
+    }
+
+    /// Sends an event to each of the players.
+    fn broadcast_to(&self, uuids: HashSet<Uuid>, event: Event) {
+        let mut net = Net::new();
+        net.broadcast_to(uuids, event);
+        self.dispatch(net);
+    }
+
+    /// Sends an event to every player within the current world.
+    fn broadcast(&self, event: Event) {
+        let mut net = Net::new();
+        net.broadcast(event);
+        self.dispatch(net);
+    }
+
+    /// Warns when packets have been dropped since the last report.
+    fn report_dropped(&self) {
+        let dropped = self.dropped.replace(0);
+        if dropped > 0 {
+            sprintln!(
+                "WARNING: dropped {} outgoing packets, the socket server is falling behind.",
+                dropped
+            );
+        }
+    }
+
+    /// Get the spawn location.
+    pub fn get_spawn_region(&self) -> &Region {
+        self.regions
+            .get_region(&Vec3::new(512., 512., 1.))
+            .expect("Spawn region is not set!")
+    }
+
+    /// Attempts to reverse lookup region from coordinates.
+    pub fn get_region(&self, position: &Vec3) -> Option<&Region> {
+        self.regions.get_region(position)
+    }
+
+    /// Obtains a player based on its UUID.
+    pub fn get_player(&self, uuid: &Uuid) -> Option<(Entity, Player)> {
+        if let Some(entity) = self.players.get(uuid) {
+            if let Some(player) = self.world.get_component::<Player>(entity) {
+                return Some((*entity, *player));
+            }
+        }
+
+        None
+    }
+
+    /// Remove a player.
+    fn remove_player(&mut self, uuid: &Uuid) -> Option<(Entity, Player)> {
+        if let Some((entity, player)) = self.get_player(uuid) {
+            if let Some(pos) = self.world.get_component::<Position>(&entity) {
+                // Remove space it is taking up.
+                let bounds = Bounds::from_vec(pos.loc, pos.size);
+                self.spatial.remove_object(&entity, &bounds)
+            }
+
+            // Remove / despawn the entity from the ECS.
+            self.world.despawn(&entity);
+            return Some((entity, player));
+        }
+
+        None
+    }
+
+    /// Add a new player, restoring a saved character if one is provided.
+    /// Only characters that were never outfitted are given the starter items.
+    fn add_player(
+        &mut self,
+        uuid: Uuid,
+        character: Option<Character>,
+    ) -> (Entity, Player, Position) {
+        // Saved locations that no longer exist within a region use the spawn.
+        let (loc, health) = match &character {
+            Some(c) if self.get_region(&c.position).is_some() => {
+                (c.position, c.health.clamp(1, Self::PLAYER_HEALTH))
+            }
+            _ => (self.get_spawn_region().spawn, Self::PLAYER_HEALTH),
+        };
+        let stats = character.as_ref().map(|c| c.stats).unwrap_or_default();
+        let skills = character.as_ref().map(|c| c.skills).unwrap_or_default();
+        let outfitted = character.as_ref().is_some_and(|c| c.outfitted);
+        let (stacks, equipment) = character
+            .map(|c| (c.inventory, c.equipment))
+            .unwrap_or_default();
+        let team = self.smallest_team();
+
+        let position = Position::new(loc, Vec2::new(32., 32.));
+        let player = Player::new(uuid);
+        let mut hp = Health::new(Self::PLAYER_HEALTH);
+        hp.current = health;
+        let mut inventory = Inventory::new(Self::PLAYER_CAPACITY);
+        inventory.stacks = stacks;
+
+        // Add player to the world and gamestate for tracking.
+        let entity = self
+            .world
+            .spawn()
+            .with(position)
+            .with(player)
+            .with(hp)
+            .with(Collidable)
+            .with(equipment)
+            .with(inventory)
+            .with(Bank(Inventory::new(systems::bank::CAPACITY)))
+            .with(stats)
+            .with(skills)
+            .with(team)
+            .with(StatusEffects::default())
+            .with(Progress::default())
+            .build();
+        self.players.insert(*player.uuid(), entity);
+        self.spatial.insert_object(&entity, &position.bounds());
+
+        let starters = match outfitted {
+            true => vec![],
+            false => self.items.starter_items(),
+        };
+        for stack in starters {
+            systems::inventory::give(
+                &mut self.world,
+                &self.items,
+                &entity,
+                stack.item,
+                stack.count,
+            );
+        }
+
+        (entity, player, position)
+    }
+
+    /// Obtain all players within the interest radius of the entity.
+    fn get_nearby(&self, entity: &Entity) -> Vec<(Entity, Player)> {
+        movement::get_nearby(&self.world, &self.spatial, entity)
+    }
+
+    /// Starts the servers gameloop.
+    pub async fn start(&mut self) {
+        // Create a test timer of 100 ticks and 5 seconds.
+        self.timers.add_timer_tick(1000, TimerData::Empty);
+        self.timers.add_timer_sec(5.0, TimerData::Empty, true);
+        let timers: Vec<(usize, f32)> = self.plugins.timers().collect();
+        for (id, interval) in timers {
+            self.timers
+                .add_timer_sec(interval, TimerData::Plugin(id), true);
+        }
+
+        // Ticks that run long delay the next one instead of bursting to catch up.
+        let mut ticker = interval(self.timers.server_tick_time());
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        'running: loop {
+            ticker.tick().await;
+            let started = Instant::now();
+            for context in self.contexts() {
+                self.within(context, |gamestate| gamestate.expire_timers());
+            }
+
+            // Process the data from the clients within the world they are in.
+            let packets = self.get_packets().await;
+            for packet in packets.into_iter() {
+                if let Some(Action::Shutdown) = packet.action() {
+                    self.save_all();
+                    break 'running;
+                }
+
+                let context = self.instances.of(&packet.uuid());
+                self.within(context, |gamestate| gamestate.handle(packet));
+            }
+
+            // Finish the authentications whose passwords were checked off of the tick.
+            while let Ok(authentication) = self.passwords.1.try_recv() {
+                self.authenticated(authentication);
+            }
+
+            // Respond to requests from the server handle.
+            while let Ok(command) = self.commands.try_recv() {
+                self.command(command);
+            }
+
+            // Keep in step with the other servers sharing the world.
+            for event in self.shards.update() {
+                self.shard_event(event);
+            }
+            let online = self.online();
+            self.shards.presence(online);
+
+            for context in self.contexts() {
+                self.within(context, |gamestate| gamestate.update());
+            }
+            self.relocate();
+            for info in self.instances.teardown() {
+                sprintln!("Instance [{}] closed, no players remain.", info.id);
+            }
+
+            if self.timers.tick().is_multiple_of(Self::DROP_REPORT_TICKS) {
+                self.report_dropped();
+            }
+
+            // Everything sent to the clients this tick is written together.
+            self.send(PacketConfiguration::Flush);
+
+            if let Some(level) = self.shedder.record(started.elapsed()) {
+                sprintln!("Tick load changed, now shedding '{}' work.", level.name());
+            }
+        }
+    }
+
+    /// Acts on something that happened on another server.
+    fn shard_event(&mut self, event: ShardEvent) {
+        match event {
+            ShardEvent::Redirect(uuid, payload) => {
+                self.send_to(uuid, Event::Redirect(payload));
+            }
+            // Players the other servers could not take are returned to the spawn.
+            ShardEvent::Failed(uuid, handoff) => {
+                let character = Character {
+                    position: self.get_spawn_region().spawn,
+                    ..handoff.character
+                };
+                self.join(uuid, Some(character), Some(handoff.carried));
+                self.reply(uuid, "shard.unreachable");
+            }
+            ShardEvent::Chat(uuid, message) => {
+                self.broadcast(Event::Chat {
+                    from: uuid,
+                    message,
+                });
+            }
+        }
+    }
+
+    /// Players within the overworld and every instance.
+    fn online(&mut self) -> u32 {
+        let mut online = 0;
+        for context in self.contexts() {
+            online += self
+                .within(context, |gamestate| gamestate.players.len())
+                .unwrap_or_default();
+        }
+        online as u32
+    }
+
+    /// Processes the timers that have expired within the current world.
+    fn expire_timers(&mut self) {
+        for timer in self.timers.update() {
+            if let TimerData::ItemDecay(entity) = timer.data {
+                // Clients are informed once it leaves their view.
+                systems::ground::remove(&mut self.world, &mut self.spatial, &entity);
+            } else if let TimerData::EntityDelete(entity) = timer.data {
+                let nearby: HashSet<Uuid> = self
+                    .get_nearby(&entity)
+                    .iter()
+                    .map(|(_e, p)| *p.uuid())
+                    .collect();
+
+                if let Some(pos) = self.world.get_component::<Position>(&entity) {
+                    self.spatial.remove_object(&entity, &pos.bounds());
+                }
+                let tick = self.timers.tick();
+                self.world.release(Self::PROJECTILE_POOL, &entity, tick);
+
+                // Tell nearby players that it has been despawned.
+                self.broadcast_to(nearby, Event::EntityRemoved(entity));
+            } else if let TimerData::Respawn(spawner) = timer.data {
+                systems::spawners::respawn(
+                    &mut self.world,
+                    &mut self.spatial,
+                    &self.npcs,
+                    &spawner,
+                );
+            } else if let TimerData::Regrow(node) = timer.data {
+                let definition = self
+                    .world
+                    .get_component::<ResourceNode>(&node)
+                    .and_then(|resource| self.resources.get(&resource.kind));
+                if let Some(definition) = definition {
+                    systems::resources::regrow(
+                        &mut self.world,
+                        &mut self.spatial,
+                        definition,
+                        &node,
+                    );
+                }
+            } else if let TimerData::Backup = timer.data {
+                if self.backups.due(&mut self.timers) {
+                    self.backup();
+                }
+            } else if let TimerData::Telemetry = timer.data {
+                if self.telemetry.due(&mut self.timers) {
+                    self.report_telemetry();
+                }
+            } else if let TimerData::MatchPhase = timer.data {
+                self.advance_match();
+            } else if let TimerData::Announcement(id) = timer.data {
+                if let Some(message) = self.announcements.due(id, &mut self.timers) {
+                    let mut net = Net::new();
+                    Self::announce(&mut net, Text::literal(message));
+                    self.dispatch(net);
+                }
+            } else if let TimerData::Plugin(id) = timer.data {
+                let mut net = Net::new();
+                let interval = self.with_plugins(&mut net, |plugins, ctx| plugins.expire(ctx, id));
+                self.dispatch(net);
+                if let Some(interval) = interval {
+                    self.timers
+                        .add_timer_sec(interval, TimerData::Plugin(id), true);
+                }
+            }
+        }
+    }
+
+    /// Processes a packet from a client within the current world.
+    fn handle(&mut self, packet: Packet) {
+        let uuid = packet.uuid();
+        let Some(action) = packet.action() else {
+            return;
+        };
+        if self.plugins.handles(&action) {
+            let mut net = Net::new();
+            let payload = packet.payload();
+            let handled = self.with_plugins(&mut net, |plugins, ctx| {
+                plugins.handle(ctx, uuid, action, payload)
+            });
+            self.dispatch(net);
+            if handled {
+                return;
+            }
+        }
+
+        if let Some(handler) = self.handlers.get(&action) {
+            handler(self, uuid, packet.payload());
+        }
+    }
+
+    /// Answers a ping or probe a plugin took over, as the socket server would have.
+    fn answer(&mut self, uuid: Uuid, action: Action, payload: Payload) {
+        if let Some(answer) = packet_processor::answer(uuid, action, payload) {
+            self.send(PacketConfiguration::Single(answer));
+        }
+    }
+
+    /// The overworld followed by every running instance.
+    fn contexts(&self) -> Vec<Option<InstanceId>> {
+        std::iter::once(None)
+            .chain(self.instances.ids().into_iter().map(Some))
+            .collect()
+    }
+
+    /// Runs a function against an instance by swapping its world in, or the overworld if None.
+    /// Returns None if the instance is not running.
+    fn within<T>(
+        &mut self,
+        context: Option<InstanceId>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> Option<T> {
+        let id = match context {
+            Some(id) => id,
+            None => return Some(f(self)),
+        };
+
+        let mut instance = self.instances.take(id)?;
+        self.swap(&mut instance);
+        self.instance = Some(instance.info);
+        let result = f(self);
+        self.instance = None;
+        self.swap(&mut instance);
+        self.instances.restore(instance);
+        Some(result)
+    }
+
+    /// Exchanges the simulation state of the current world with an instance.
+    fn swap(&mut self, instance: &mut Instance) {
+        std::mem::swap(&mut self.world, &mut instance.world);
+        std::mem::swap(&mut self.spatial, &mut instance.spatial);
+        std::mem::swap(&mut self.deltas, &mut instance.deltas);
+        std::mem::swap(&mut self.replicator, &mut instance.replicator);
+        std::mem::swap(&mut self.timers, &mut instance.timers);
+        std::mem::swap(&mut self.players, &mut instance.players);
+        std::mem::swap(&mut self.visible, &mut instance.visible);
+        std::mem::swap(&mut self.chunks, &mut instance.chunks);
+        std::mem::swap(&mut self.obstacles, &mut instance.obstacles);
+    }
+
+    /// Obtains the world simulating a region, opening an instance for the party if it is instanced.
+    fn context_for(&mut self, region: u8, party: Party) -> Option<InstanceId> {
+        let name = match self.regions.get(region) {
+            Some(region) if region.instanced => region.name.clone(),
+            _ => return None,
+        };
+
+        if let Some(id) = self.instances.find(region, party) {
+            return Some(id);
+        }
+
+        let info = InstanceInfo {
+            id: self.instances.next_id(),
+            region,
+            party,
+        };
+        let spatial = SpatialHash::bounded(Self::CELL_SIZE, self.regions.extent());
+        let mut instance = Instance::new(
+            info,
+            Self::create_world(&self.plugins),
+            spatial,
+            &self.timers,
+        );
+        instance.spatial.set_interest(self.config.interest.clone());
+        instance.replicator = Replicator::new(&self.config.replication);
+        self.instances.restore(instance);
+        self.within(Some(info.id), |gamestate| {
+            gamestate.spawn_obstacles();
+            gamestate.spawn_spawners();
+            gamestate.spawn_resources();
+        });
+        sprintln!("Instance [{}] of {} opened for {:?}.", info.id, name, party);
+        Some(info.id)
+    }
+
+    /// Checks if a position is within a region simulated by the current world.
+    fn belongs(&self, loc: &Vec3) -> bool {
+        match (self.regions.get_region_id(loc), self.instance) {
+            (Some(region), Some(info)) => region == info.region,
+            (Some(region), None) => self.regions.get(region).is_some_and(|region| {
+                !region.instanced && self.shards.owner(&region.name).is_none()
+            }),
+            (None, _) => true,
+        }
+    }
+
+    /// Players that need to be moved, either standing on a portal or outside of the current world.
+    fn departures(&self) -> Vec<(Uuid, Vec3, Party)> {
+        self.players
+            .iter()
+            .filter_map(|(uuid, entity)| {
+                let position = self.world.get_component::<Position>(entity)?;
+                let destination = match self.regions.portal_destination(&position.bounds()) {
+                    Some(destination) => destination,
+                    None if self.belongs(&position.loc) => return None,
+                    None => position.loc,
+                };
+
+                // Without parties, teammates enter instances together.
+                let party = match self.world.get_component::<Team>(entity) {
+                    Some(team) => Party::Team(*team),
+                    None => Party::Solo(*uuid),
+                };
+                Some((*uuid, destination, party))
+            })
+            .collect()
+    }
+
+    /// Moves players to their destinations, transferring them between worlds when required.
+    fn relocate(&mut self) {
+        let mut departures = vec![];
+        for context in self.contexts() {
+            if let Some(players) = self.within(context, |gamestate| gamestate.departures()) {
+                departures.extend(
+                    players
+                        .into_iter()
+                        .map(|(uuid, loc, party)| (context, uuid, loc, party)),
+                );
+            }
+        }
+
+        for (from, uuid, loc, party) in departures {
+            let region = match self.regions.get_region_id(&loc) {
+                Some(region) => region,
+                None => continue,
+            };
+
+            // Regions owned by another server are entered there instead.
+            let owner = self
+                .regions
+                .get(region)
+                .and_then(|region| self.shards.owner(&region.name))
+                .cloned();
+            if let Some(peer) = owner {
+                self.within(from, |gamestate| gamestate.hand_off(&uuid, loc, &peer));
+                continue;
+            }
+
+            let to = self.context_for(region, party);
+
+            if from == to {
+                self.within(from, |gamestate| {
+                    if let Some(entity) = gamestate.players.get(&uuid).copied() {
+                        gamestate.world.remove_component::<Velocity>(entity);
+                        gamestate.world.remove_component::<Acceleration>(entity);
+                        let mut net = Net::new();
+                        gamestate.teleport(&mut net, &entity, loc);
+                        gamestate.dispatch(net);
+                    }
+                });
+            } else {
+                self.transfer(&uuid, from, to, loc);
+            }
+        }
+    }
+
+    /// Moves a player and their components from one world into another.
+    fn transfer(
+        &mut self,
+        uuid: &Uuid,
+        from: Option<InstanceId>,
+        to: Option<InstanceId>,
+        loc: Vec3,
+    ) {
+        let entity = match self.within(from, |gamestate| gamestate.depart(uuid)) {
+            Some(Some(entity)) => entity,
+            _ => return,
+        };
+
+        let moved = match (from, to) {
+            (None, Some(to)) => self
+                .instances
+                .get_mut(to)
+                .map(|instance| self.world.transfer(&entity, &mut instance.world)),
+            (Some(from), None) => self
+                .instances
+                .get_mut(from)
+                .map(|instance| instance.world.transfer(&entity, &mut self.world)),
+            (Some(from), Some(to)) => {
+                let mut source = self.instances.take(from);
+                let moved = match (source.as_mut(), self.instances.get_mut(to)) {
+                    (Some(source), Some(target)) => {
+                        Some(source.world.transfer(&entity, &mut target.world))
+                    }
+                    _ => None,
+                };
+                if let Some(source) = source {
+                    self.instances.restore(source);
+                }
+                moved
+            }
+            (None, None) => Some(entity),
+        };
+
+        if let Some(moved) = moved {
+            self.within(to, |gamestate| gamestate.arrive(*uuid, moved, loc));
+        }
+    }
+
+    /// Sends a player to the server owning the region they are entering.
+    fn hand_off(&mut self, uuid: &Uuid, loc: Vec3, peer: &ShardPeer) {
+        let entity = match self.depart(uuid) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        let character = self.character(&entity, loc);
+        let carried = Carried {
+            team: self.world.get_component::<Team>(&entity).copied(),
+            progress: self
+                .world
+                .get_component::<Progress>(&entity)
+                .cloned()
+                .unwrap_or_default(),
+            bank: self
+                .world
+                .get_component::<Bank>(&entity)
+                .map(|bank| bank.0.stacks.clone())
+                .unwrap_or_default(),
+        };
+        self.world.despawn(&entity);
+
+        // The character is saved where it is going in case the client never arrives.
+        let account = self.sessions.get(uuid).copied();
+        if let (Some(db), Some(id)) = (&self.accounts, account) {
+            if let Err(why) = db.save_character(id, &character) {
+                sprintln!("Unable to save character for {}: {}", uuid, why);
+            }
+            if let Err(why) = db.save_progress(id, &carried.progress) {
+                sprintln!("Unable to save progress for {}: {}", uuid, why);
+            }
+            if let Err(why) = db.save_bank(id, &carried.bank) {
+                sprintln!("Unable to save bank for {}: {}", uuid, why);
+            }
+        }
+
+        sprintln!(
+            "Player [{}] {} handed off to {}.",
+            entity,
+            uuid,
+            peer.address
+        );
+        let username = match (&self.accounts, account) {
+            (Some(db), Some(id)) => db.username(id).unwrap_or_default(),
+            _ => None,
+        };
+        let handoff = Handoff {
+            token: Uuid::new_v4(),
+            account: username,
+            character,
+            carried,
+        };
+        self.shards.send(peer, *uuid, handoff);
+    }
+
+    /// Joins a player handed off by another server, using the token their client was given.
+    fn claim(&mut self, uuid: Uuid, payload: Payload) {
+        let token = match payload {
+            Payload::Uuid(data) => data.uuid,
+            _ => return,
+        };
+
+        let handoff = match self.shards.claim(&token) {
+            Some(handoff) => handoff,
+            None => {
+                self.refuse(uuid, "join.handoff_expired".into());
+                return;
+            }
+        };
+
+        // Accounts are only known to this server by their name.
+        if let Some(username) = handoff.account.as_deref() {
+            match self.resume(username) {
+                Ok(id) => self.sessions.insert(uuid, id),
+                Err(why) => return self.refuse(uuid, why.text()),
+            };
+        }
+        self.join(uuid, Some(handoff.character), Some(handoff.carried));
+    }
+
+    /// Removes a player from the current world without despawning them.
+    fn depart(&mut self, uuid: &Uuid) -> Option<Entity> {
+        let entity = self.players.remove(uuid)?;
+        let mut observers = movement::get_observers(&self.world, &self.spatial, &entity);
+        observers.remove(uuid);
+        if let Some(position) = self.world.get_component::<Position>(&entity) {
+            self.spatial.remove_object(&entity, &position.bounds());
+        }
+
+        // Anything seen here has to be sent again in the next world.
+        self.visible.remove(uuid);
+        self.chunks.remove(uuid);
+        self.obstacles.remove(uuid);
+        self.conversations.remove(uuid);
+        self.cancel_trade(uuid);
+        self.banking.remove(uuid);
+        self.world.remove_component::<Velocity>(entity);
+        self.world.remove_component::<Acceleration>(entity);
+
+        self.broadcast_to(observers, Event::EntityRemoved(entity));
+        Some(entity)
+    }
+
+    /// Places a player moved from another world, introducing them to those nearby.
+    fn arrive(&mut self, uuid: Uuid, entity: Entity, loc: Vec3) {
+        let position = match self.world.get_component::<Position>(&entity) {
+            Some(position) => Position::new(loc, position.size),
+            None => return,
+        };
+
+        self.world.upsert_component(entity, position);
+        self.players.insert(uuid, entity);
+        self.spatial.insert_object(&entity, &position.bounds());
+        self.anticheat.relocated(&uuid);
+        self.introduce(uuid, &entity);
+
+        match self.instance {
+            Some(info) => sprintln!(
+                "Player [{}] {} entered instance [{}].",
+                entity,
+                uuid,
+                info.id
+            ),
+            None => sprintln!("Player [{}] {} returned to the overworld.", entity, uuid),
+        }
+    }
+
+    /// Spawns a player for themselves and those nearby, informing them of what they carry.
+    fn introduce(&self, uuid: Uuid, entity: &Entity) {
+        let position = match self.world.get_component::<Position>(entity) {
+            Some(position) => *position,
+            None => return,
+        };
+        let appearance = self
+            .world
+            .get_component::<Equipment>(entity)
+            .copied()
+            .unwrap_or_default();
+        let team = self.world.get_component::<Team>(entity).copied();
+        let spawn = SpawnPayload::new(
+            MovementPayload::new(*entity, position.size, position.loc, Vec2::ORIGIN),
+            appearance,
+            team,
+        );
+
+        let nearby = self
+            .get_nearby(entity)
+            .into_iter()
+            .map(|(_e, p)| *p.uuid())
+            .collect();
+
+        // Joining is confirmed before anyone else is told of the player.
+        let mut net = Net::new();
+        net.send_to(uuid, Event::Joined(spawn.clone()));
+        net.broadcast_to(nearby, Event::PlayerJoined { uuid, spawn });
+        systems::equipment::changed(&self.world, &self.spatial, &mut net, entity);
+        self.dispatch(net);
+    }
+
+    /// Details of the players within the current world.
+    fn player_info(&self) -> Vec<PlayerInfo> {
+        self.players
+            .iter()
+            .filter_map(|(uuid, entity)| {
+                let position = self.world.get_component::<Position>(entity)?;
+                let health = self.world.get_component::<Health>(entity)?;
+                Some(PlayerInfo {
+                    uuid: *uuid,
+                    entity: *entity,
+                    position: position.loc,
+                    health: health.current,
+                })
+            })
+            .collect()
+    }
+
+    /// Describes an entity within the current world, None if it does not exist.
+    fn inspect(&self, entity: &Entity) -> Option<EntityReport> {
+        let components = systems::inspect::components(&self.world, entity);
+        if components.is_empty() {
+            return None;
+        }
+
+        Some(EntityReport {
+            entity: *entity,
+            instance: self.instance.map(|info| info.id),
+            components,
+        })
+    }
+
+    /// Executes a request from the server handle.
+    fn command(&mut self, command: ServerCommand) {
+        match command {
+            ServerCommand::Players(reply) => {
+                let mut players = vec![];
+                for context in self.contexts() {
+                    players.extend(self.within(context, |gamestate| gamestate.player_info()));
+                }
+                let _ = reply.send(players.into_iter().flatten().collect());
+            }
+            ServerCommand::Announcements(reply) => {
+                let _ = reply.send(self.announcements.clone());
+            }
+            ServerCommand::Shards(reply) => {
+                let _ = reply.send(self.shards.status());
+            }
+            ServerCommand::Pools(reply) => {
+                let mut report = PoolReport::default();
+                for context in self.contexts() {
+                    let world = self.within(context, |gamestate| {
+                        (
+                            gamestate.world.pool_stats(),
+                            gamestate.world.recycled_components(),
+                        )
+                    });
+                    let Some((pools, recycled)) = world else {
+                        continue;
+                    };
+
+                    report.recycled += recycled;
+                    for (kind, stats) in pools {
+                        match report.pools.iter_mut().find(|(name, _)| *name == kind) {
+                            Some((_, total)) => {
+                                total.allocated += stats.allocated;
+                                total.reused += stats.reused;
+                                total.released += stats.released;
+                                total.free += stats.free;
+                            }
+                            None => report.pools.push((kind, stats)),
+                        }
+                    }
+                }
+                let _ = reply.send(report);
+            }
+            ServerCommand::Census(reply) => {
+                let mut census = vec![];
+                for context in self.contexts() {
+                    let kinds = self.within(context, |gamestate| {
+                        systems::inspect::census(&gamestate.world)
+                    });
+                    census.extend(kinds.map(|kinds| WorldCensus {
+                        instance: context,
+                        kinds,
+                    }));
+                }
+                let _ = reply.send(census);
+            }
+            ServerCommand::Load(reply) => {
+                let _ = reply.send(self.shedder.stats());
+            }
+            ServerCommand::Find(uuid, reply) => {
+                let context = self.instances.of(&uuid);
+                let report = self.within(context, |gamestate| {
+                    let entity = *gamestate.players.get(&uuid)?;
+                    gamestate.inspect(&entity)
+                });
+                let _ = reply.send(report.flatten());
+            }
+            ServerCommand::Inspect(context, entity, reply) => {
+                let report = self.within(context, |gamestate| gamestate.inspect(&entity));
+                let _ = reply.send(report.flatten());
+            }
+            ServerCommand::Announce(message) => {
+                let mut net = Net::new();
+                Self::announce(&mut net, Text::literal(message));
+                self.dispatch(net);
+            }
+            ServerCommand::Motd(motd) => {
+                self.announcements.set_motd(motd);
+                sprintln!("MOTD: {}", self.announcements.motd().unwrap_or("(none)"));
+            }
+            ServerCommand::Schedule(announcement) => {
+                let id = self.announcements.add(announcement, &mut self.timers);
+                sprintln!("Scheduled announcement [{}].", id);
+            }
+            ServerCommand::Unschedule(id) => match self.announcements.remove(id) {
+                Some(_) => sprintln!("Removed announcement [{}].", id),
+                None => sprintln!("No announcement [{}] is scheduled.", id),
+            },
+            ServerCommand::Backup => self.backup(),
+            ServerCommand::Reload => self.reload(),
+            ServerCommand::ReloadLoot => {
+                if self.loot.reload(&self.content, &self.items) {
+                    sprintln!("Reloaded {}.", self.content.path(LootManager::PATH));
+                }
+            }
+        }
+    }
+
+    /// Re-reads the configuration, applying the settings that can change while running.
+    fn reload(&mut self) {
+        let config = match ServerConfig::read(&self.config.path) {
+            Ok(config) => config,
+            Err(why) => {
+                sprintln!("Unable to reload {}: {}", self.config.path, why);
+                return;
+            }
+        };
+
+        let changes = self.config.changes(&config);
+        if changes.applied.is_empty() && changes.restart.is_empty() {
+            sprintln!("Reloaded {}, nothing changed.", self.config.path);
+            return;
+        }
+
+        self.anticheat.reconfigure(config.anticheat.clone());
+        self.announcements
+            .set_motd(config.announcements.motd.clone());
+        self.backups
+            .reconfigure(config.backups.clone(), &mut self.timers);
+        self.telemetry
+            .reconfigure(config.telemetry.clone(), &mut self.timers);
+        self.network.send_replace(config.network.clone());
+
+        // Settings requiring a restart are kept until then.
+        self.config = ServerConfig {
+            password: config.password,
+            whitelist: config.whitelist,
+            friendly_fire: config.friendly_fire,
+            anticheat: config.anticheat,
+            backups: config.backups,
+            telemetry: config.telemetry,
+            network: config.network,
+            announcements: AnnouncementConfig {
+                motd: config.announcements.motd,
+                ..self.config.announcements.clone()
+            },
+            ..self.config.clone()
+        };
+
+        if !changes.applied.is_empty() {
+            sprintln!(
+                "Reloaded {}: {}.",
+                self.config.path,
+                changes.applied.join(", ")
+            );
+        }
+        if !changes.restart.is_empty() {
+            sprintln!(
+                "Restart the server to apply: {}.",
+                changes.restart.join(", ")
+            );
+        }
+    }
+
+    fn join(&mut self, uuid: Uuid, character: Option<Character>, carried: Option<Carried>) {
+        if self.players.contains_key(&uuid) {
+            return;
+        }
+
+        let (entity, _player, _position) = self.add_player(uuid, character);
+        if let Some(carried) = carried {
+            if let Some(team) = carried.team {
+                self.world.upsert_component(entity, team);
+            }
+            self.world.upsert_component(entity, carried.progress);
+            if let Some(bank) = self.world.get_component_mut::<Bank>(&entity) {
+                bank.0.stacks = carried.bank;
+            }
+        }
+        sprintln!("Player [{}] {} joined.", entity, uuid);
+        self.telemetry.joined(uuid, Instant::now());
+        self.introduce(uuid, &entity);
+
+        let mut net = Net::new();
+        if let Some(motd) = self.announcements.motd() {
+            net.send_to(
+                uuid,
+                Event::Notification(NotificationPayload::new(
+                    NotificationKind::Announcement,
+                    Text::literal(motd),
+                )),
+            );
+        }
+
+        // Newly spawned players are briefly protected.
+        self.protect(&mut net, &entity);
+
+        // Catch the player up on the match and the game mode.
+        if let Some(matches) = self.matches.as_ref() {
+            net.send_to(uuid, Event::Match(matches.payload(&self.timers)));
+        }
+        if let Some(hud) = self.mode.as_ref().and_then(|mode| mode.hud(&self.world)) {
+            net.send_to(uuid, Event::Hud(hud));
+        }
+        self.dispatch(net);
+    }
+
+    fn leave(&mut self, uuid: &Uuid) {
+        self.save_character(uuid);
+        self.cancel_trade(uuid);
+        self.banking.remove(uuid);
+        self.sessions.remove(uuid);
+        self.authenticating.remove(uuid);
+        self.conversations.remove(uuid);
+        self.overlays.remove(uuid);
+        self.shards.forget(uuid);
+        self.telemetry.left(uuid, Instant::now());
+
+        if let Some((entity, _player)) = self.remove_player(uuid) {
+            sprintln!("Player [{}] {} left.", entity, uuid);
+
+            self.broadcast_to(
+                self.players.keys().copied().collect(),
+                Event::PlayerLeft {
+                    uuid: *uuid,
+                    entity,
+                },
+            );
+        }
+    }
+
+    /// Refuses a client from joining, informing them of the reason.
+    fn refuse(&self, uuid: Uuid, why: Text) {
+        self.send_to(uuid, Event::Error(why));
+    }
+
+    /// Joins the player as a guest without an account.
+    fn guest(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::Join(request) => request,
+            _ => return,
+        };
+
+        match self.config.admit(request.server_password.as_deref(), None) {
+            Ok(()) => self.join(uuid, None, None),
+            Err(why) => self.refuse(uuid, why.into()),
+        }
+    }
+
+    /// Registers or logs into an account, hashing or verifying the password off of the tick.
+    fn authenticate(&mut self, uuid: Uuid, payload: Payload, register: bool) {
+        let credentials = match payload {
+            Payload::Credentials(credentials) => credentials,
+            _ => return,
+        };
+        if self.authenticating.contains(&uuid) {
+            return;
+        }
+
+        // Check the server password and whitelist before touching the account.
+        let admitted = self.config.admit(
+            credentials.server_password.as_deref(),
+            Some(&credentials.username),
+        );
+        if let Err(why) = admitted {
+            self.refuse(uuid, why.into());
+            return;
+        }
+
+        let CredentialsPayload {
+            username, password, ..
+        } = credentials;
+        let stored = match &self.accounts {
+            Some(db) if register => db.available(&username, &password).map(|_| None),
+            Some(db) => db.password(&username).map(Some),
+            None => Err(AccountError::Unavailable),
+        };
+        let stored = match stored {
+            Ok(stored) => stored,
+            Err(why) => {
+                self.refuse(uuid, why.text());
+                return;
+            }
+        };
+
+        // Argon2 is slow by design, the result is finished on a later tick.
+        self.authenticating.insert(uuid);
+        let sender = self.passwords.0.clone();
+        tokio::task::spawn_blocking(move || {
+            let password = match stored {
+                Some((id, hash)) => {
+                    AccountDatabase::verify(&password, &hash).map(|_| Password::Verified(id))
+                }
+                None => AccountDatabase::hash(&password).map(Password::Hashed),
+            };
+            // Only fails once the gamestate has stopped.
+            let _ = sender.send(Authentication {
+                uuid,
+                username,
+                password,
+            });
+        });
+    }
+
+    /// Joins the player with their saved character once their password has been checked.
+    fn authenticated(&mut self, authentication: Authentication) {
+        let Authentication {
+            uuid,
+            username,
+            password,
+        } = authentication;
+        // The player left while waiting.
+        if !self.authenticating.remove(&uuid) {
+            return;
+        }
+
+        match self.verify(&username, password) {
+            Ok((id, character)) => {
+                sprintln!("Account '{}' [{}] logged in.", username, id);
+                self.sessions.insert(uuid, id);
+                self.join(uuid, character, None);
+                self.load_progress(uuid, id);
+                self.load_bank(uuid, id);
+                self.mail_notice(uuid);
+            }
+            Err(why) => self.refuse(uuid, why.text()),
+        }
+    }
+
+    /// Obtains the account and its saved character after the password was checked.
+    fn verify(
+        &self,
+        username: &str,
+        password: Result<Password, AccountError>,
+    ) -> Result<(AccountId, Option<Character>), AccountError> {
+        let db = match &self.accounts {
+            Some(db) => db,
+            None => return Err(AccountError::Unavailable),
+        };
+
+        let id = match password? {
+            Password::Hashed(hash) => db.create(username, &hash)?,
+            Password::Verified(id) => id,
+        };
+
+        self.unused(id)?;
+        Ok((id, db.load_character(id)?))
+    }
+
+    /// Finds the account of a player handed off by another server within this server's database.
+    fn resume(&self, username: &str) -> Result<AccountId, AccountError> {
+        let db = self.accounts.as_ref().ok_or(AccountError::Unavailable)?;
+        let id = db.account_id(username)?.ok_or(AccountError::Credentials)?;
+        self.unused(id)?;
+        Ok(id)
+    }
+
+    /// Prevents the same account from being used twice.
+    fn unused(&self, id: AccountId) -> Result<(), AccountError> {
+        match self.sessions.values().any(|session| *session == id) {
+            true => Err(AccountError::InUse),
+            false => Ok(()),
+        }
+    }
+
+    /// Persisted state of a player's character, placed at the position given.
+    fn character(&self, entity: &Entity, position: Vec3) -> Character {
+        Character {
+            position,
+            health: self
+                .world
+                .get_component::<Health>(entity)
+                .map_or(Self::PLAYER_HEALTH, |health| health.current),
+            stats: self
+                .world
+                .get_component::<Stats>(entity)
+                .copied()
+                .unwrap_or_default(),
+            skills: self
+                .world
+                .get_component::<Skills>(entity)
+                .copied()
+                .unwrap_or_default(),
+            outfitted: true,
+            inventory: self
+                .world
+                .get_component::<Inventory>(entity)
+                .map(|inventory| inventory.stacks.clone())
+                .unwrap_or_default(),
+            equipment: self
+                .world
+                .get_component::<Equipment>(entity)
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Saves the character for a player that is logged into an account.
+    fn save_character(&self, uuid: &Uuid) {
+        let (db, id) = match (&self.accounts, self.sessions.get(uuid)) {
+            (Some(db), Some(id)) => (db, *id),
+            _ => return,
+        };
+
+        let entity = match self.players.get(uuid) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        if let Some(pos) = self.world.get_component::<Position>(entity) {
+            let character = self.character(entity, pos.loc);
+            if let Err(why) = db.save_character(id, &character) {
+                sprintln!("Unable to save character for {}: {}", uuid, why);
+            }
+        }
+
+        if let Some(progress) = self.world.get_component::<Progress>(entity) {
+            if let Err(why) = db.save_progress(id, progress) {
+                sprintln!("Unable to save progress for {}: {}", uuid, why);
+            }
+        }
+
+        if let Some(bank) = self.world.get_component::<Bank>(entity) {
+            if let Err(why) = db.save_bank(id, &bank.0.stacks) {
+                sprintln!("Unable to save bank for {}: {}", uuid, why);
+            }
+        }
+    }
+
+    /// Restores the flags an account has earned onto their player.
+    fn load_progress(&mut self, uuid: Uuid, id: AccountId) {
+        let (db, entity) = match (&self.accounts, self.players.get(&uuid)) {
+            (Some(db), Some(entity)) => (db, *entity),
+            _ => return,
+        };
+
+        match db.load_progress(id) {
+            Ok(progress) => self.world.upsert_component(entity, progress),
+            Err(why) => sprintln!("Unable to load progress for {}: {}", uuid, why),
+        }
+    }
+
+    /// Restores the stacks an account keeps in their bank onto their player.
+    fn load_bank(&mut self, uuid: Uuid, id: AccountId) {
+        let (db, entity) = match (&self.accounts, self.players.get(&uuid)) {
+            (Some(db), Some(entity)) => (db, *entity),
+            _ => return,
+        };
+
+        match db.load_bank(id) {
+            Ok(stacks) => {
+                if let Some(bank) = self.world.get_component_mut::<Bank>(&entity) {
+                    bank.0.stacks = stacks;
+                }
+            }
+            Err(why) => sprintln!("Unable to load bank for {}: {}", uuid, why),
+        }
+    }
+
+    /// Saves all characters that are logged into accounts and the items on the ground.
+    /// Items dropped within instances are lost once they close.
+    fn save_all(&mut self) {
+        for context in self.contexts() {
+            self.within(context, |gamestate| {
+                for uuid in gamestate.sessions.keys() {
+                    gamestate.save_character(uuid);
+                }
+            });
+        }
+
+        for name in self
+            .plots
+            .all()
+            .map(|plot| plot.name.clone())
+            .collect::<Vec<_>>()
+        {
+            self.save_plot(&name);
+        }
+
+        if let Some(db) = &self.accounts {
+            let items = systems::ground::all(&self.world);
+            if let Err(why) = db.save_ground_items(&items) {
+                sprintln!("Unable to save ground items: {}", why);
+            }
+        }
+    }
+
+    /// Saves the world and snapshots the database into the backup directory.
+    fn backup(&mut self) {
+        if self.accounts.is_none() {
+            sprintln!("Unable to back up, the account database is unavailable.");
+            return;
+        }
+
+        self.save_all();
+        if let Some(db) = &self.accounts {
+            self.backups.spawn(db.path());
+        }
+    }
+
+    /// Writes the gameplay metrics gathered since the last report.
+    fn report_telemetry(&mut self) {
+        let report = self.telemetry.report(Utc::now());
+        match self.telemetry.write(&report) {
+            Ok(path) => sprintln!("Reported gameplay metrics to {}.", path.display()),
+            Err(why) => sprintln!("Unable to report gameplay metrics: {}", why),
+        }
+    }
+
+    fn movement(&mut self, uuid: Uuid, movement: Payload) {
+        let movement = match movement {
+            Payload::Movement(movement) => movement,
+            _ => return,
+        };
+
+        // Players wait for the round to begin.
+        if self.is_frozen() {
+            return;
+        }
+
+        if let Some((entity, _player)) = self.get_player(&uuid) {
+            // Compare where the client believes they are to where they are.
+            if let Some(position) = self.world.get_component::<Position>(&entity) {
+                let per_tick = self
+                    .get_region(&position.loc)
+                    .map_or(0., |region| region.tile);
+                let max_speed = per_tick / self.timers.server_tick_time().as_secs_f64()
+                    * systems::physics::speed_scale(&self.world, &entity);
+                self.anticheat
+                    .moved(uuid, movement.position, position.loc, max_speed);
+            }
+
+            self.world
+                .upsert_component(entity, Acceleration(movement.velocity));
+        }
+    }
+
+    /// Equips or unequips an item for a player.
+    fn equip(&mut self, uuid: Uuid, payload: Payload, equip: bool) {
+        let request = match payload {
+            Payload::Equip(request) => request,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let changed = match (equip, request.item) {
+            (true, Some(item)) => {
+                systems::equipment::equip(&mut self.world, &self.items, &entity, request.slot, item)
+            }
+            (false, _) => {
+                systems::equipment::unequip(&mut self.world, &self.items, &entity, request.slot)
+            }
+            _ => false,
+        };
+
+        if changed {
+            let mut net = Net::new();
+            systems::equipment::changed(&self.world, &self.spatial, &mut net, &entity);
+            self.dispatch(net);
+        }
+    }
+
+    /// Splits part of a stack within a player's inventory.
+    fn split_stack(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::Stack(request) => request,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let index = request.index as usize;
+        if systems::inventory::split(&mut self.world, &entity, index, request.count) {
+            let mut net = Net::new();
+            systems::inventory::changed(&self.world, &mut net, &entity);
+            self.dispatch(net);
+        }
+    }
+
+    /// Moves a stack to another slot of a player's inventory.
+    fn move_stack(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::MoveStack(request) => request,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let (from, to) = (request.from as usize, request.to as usize);
+        if systems::inventory::move_stack(&mut self.world, &entity, from, to) {
+            let mut net = Net::new();
+            systems::inventory::changed(&self.world, &mut net, &entity);
+            self.dispatch(net);
+        }
+    }
+
+    /// Drops part of a stack from a player's inventory onto the ground.
+    fn drop_item(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::Stack(request) => request,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let expires = get_now() + Self::ITEM_DECAY;
+        let index = request.index as usize;
+        if let Some(item) = systems::ground::drop(
+            &mut self.world,
+            &mut self.spatial,
+            &entity,
+            index,
+            request.count,
+            expires,
+        ) {
+            self.timers
+                .add_timer_sec(Self::ITEM_DECAY as f32, TimerData::ItemDecay(item), true);
+
+            let mut net = Net::new();
+            systems::inventory::changed(&self.world, &mut net, &entity);
+            self.dispatch(net);
+        }
+    }
+
+    /// Uses an item from a player's inventory, casting it first if it takes time to use.
+    fn use_item(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::Stack(request) => request,
+            _ => return,
+        };
+
+        // Players wait for the round to begin.
+        let entity = match self.players.get(&uuid) {
+            Some(entity) if !self.is_frozen() => *entity,
+            _ => return,
+        };
+
+        let index = request.index as usize;
+        let (item, effect, cast) = match self
+            .world
+            .get_component::<Inventory>(&entity)
+            .and_then(|inventory| inventory.stacks.get(index))
+            .and_then(|stack| self.items.get(&stack.item))
+            .and_then(|item| Some((item.id, item.effect?, item.cast)))
+        {
+            Some(found) => found,
+            None => {
+                self.reply(uuid, "item.unusable");
+                return;
+            }
+        };
+
+        let tick = self.timers.tick();
+        let ability = Ability::Item(item);
+        if !systems::casting::is_ready(&self.world, &entity, ability, tick) {
+            self.reply(uuid, "item.not_ready");
+            return;
+        } else if let Err(why) = self.item_destination(&entity, effect) {
+            self.reply(uuid, why);
+            return;
+        }
+
+        if cast <= 0. {
+            self.apply_item(&entity, index, item);
+            return;
+        }
+
+        let mut net = Net::new();
+        match systems::casting::begin(
+            &mut self.world,
+            &self.spatial,
+            &mut net,
+            &entity,
+            ability,
+            Some(index),
+            tick,
+            cast,
+        ) {
+            Ok(()) => self.dispatch(net),
+            Err(why) => self.reply(uuid, why),
+        }
+    }
+
+    /// Checks that an item's effect applies to the entity, obtaining where it is sent if it moves them.
+    fn item_destination(
+        &self,
+        entity: &Entity,
+        effect: ItemEffect,
+    ) -> Result<Option<Vec3>, &'static str> {
+        match effect {
+            ItemEffect::Heal(_) => {
+                systems::consumables::can_heal(&self.world, entity).map(|_| None)
+            }
+            ItemEffect::Recall => Ok(Some(self.get_spawn_region().spawn)),
+            ItemEffect::Teleport(range) => systems::consumables::random_destination(
+                &self.world,
+                &self.spatial,
+                &self.regions,
+                entity,
+                range,
+                self.timers.tick(),
+            )
+            .map(Some)
+            .ok_or("item.nowhere"),
+            ItemEffect::Haste(_) => Ok(None),
+        }
+    }
+
+    /// Applies the effect of an item in an inventory slot, consuming it once the effect is known to apply.
+    fn apply_item(&mut self, entity: &Entity, index: usize, item: ItemId) {
+        let (effect, cooldown) = match self.items.get(&item) {
+            Some(definition) => match definition.effect {
+                Some(effect) => (effect, definition.cooldown),
+                None => return,
+            },
+            None => return,
+        };
+        let uuid = match self.world.get_component::<Player>(entity) {
+            Some(player) => *player.uuid(),
+            None => return,
+        };
+
+        // Things may have changed while the item was being cast.
+        let destination = match self.item_destination(entity, effect) {
+            Ok(destination) => destination,
+            Err(why) => {
+                self.reply(uuid, why);
+                return;
+            }
+        };
+
+        if !systems::consumables::consume(&mut self.world, entity, index, item) {
+            return;
+        }
+
+        let tick = self.timers.tick();
+        systems::casting::cooldown(&mut self.world, entity, Ability::Item(item), tick, cooldown);
+        self.telemetry.ability(Ability::Item(item), &self.items);
+        let mut net = Net::new();
+        systems::inventory::changed(&self.world, &mut net, entity);
+        match (effect, destination) {
+            (ItemEffect::Heal(amount), _) => {
+                systems::consumables::heal(
+                    &mut self.world,
+                    &self.spatial,
+                    &mut net,
+                    entity,
+                    amount,
+                );
+            }
+            (ItemEffect::Haste(seconds), _) => {
+                systems::effects::apply(
+                    &mut self.world,
+                    &self.spatial,
+                    &mut net,
+                    entity,
+                    StatusEffect::Haste,
+                    tick + systems::casting::ticks(seconds),
+                );
+            }
+            (_, Some(loc)) => {
+                self.world.remove_component::<Velocity>(*entity);
+                self.teleport(&mut net, entity, loc);
+            }
+            (_, None) => (),
+        }
+        self.dispatch(net);
+    }
+
+    /// Mounts or dismounts a player, whichever they are not.
+    fn toggle_mount(&mut self, uuid: Uuid) {
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let mut net = Net::new();
+        if systems::mounts::dismount(&mut self.world, &self.spatial, &mut net, &entity) {
+            self.dispatch(net);
+            return;
+        }
+
+        match systems::mounts::mount(
+            &mut self.world,
+            &self.spatial,
+            &self.regions,
+            &mut net,
+            &entity,
+        ) {
+            Ok(()) => self.dispatch(net),
+            Err(why) => self.reply(uuid, why),
+        }
+    }
+
+    /// Picks up the closest item on the ground for a player.
+    fn pickup(&mut self, uuid: Uuid) {
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        if systems::ground::pickup(&mut self.world, &mut self.spatial, &self.items, &entity) {
+            let mut net = Net::new();
+            systems::inventory::changed(&self.world, &mut net, &entity);
+            self.dispatch(net);
+        }
+    }
+
+    /// Gathers from the closest resource node within reach of a player, ignoring attempts sent too quickly.
+    fn gather(&mut self, uuid: Uuid) {
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let tick = self.timers.tick();
+        if !systems::casting::is_ready(&self.world, &entity, Ability::Gather, tick) {
+            return;
+        }
+        systems::casting::cooldown(
+            &mut self.world,
+            &entity,
+            Ability::Gather,
+            tick,
+            Self::GATHER_COOLDOWN,
+        );
+        self.telemetry.ability(Ability::Gather, &self.items);
+
+        let node = match systems::resources::within_reach(&self.world, &self.spatial, &entity) {
+            Some(node) => node,
+            None => {
+                self.reply(uuid, "gather.nothing");
+                return;
+            }
+        };
+        let definition = match self
+            .world
+            .get_component::<ResourceNode>(&node)
+            .and_then(|resource| self.resources.get(&resource.kind))
+        {
+            Some(definition) => definition,
+            None => return,
+        };
+
+        let roll = systems::roll(&entity, tick);
+        match systems::resources::gather(
+            &mut self.world,
+            &self.items,
+            definition,
+            &entity,
+            &node,
+            roll,
+            self.world_events.experience(),
+        ) {
+            Ok(gathered) => {
+                if gathered.depleted {
+                    systems::resources::deplete(&self.world, &mut self.spatial, &node);
+                    self.timers
+                        .add_timer_sec(definition.respawn, TimerData::Regrow(node), true);
+                }
+
+                let name = self
+                    .items
+                    .get(&definition.item)
+                    .map_or(Text::literal("items"), |item| item.text());
+                let text = Text::new("gather.gathered")
+                    .with("count", gathered.count)
+                    .with_text("item", name);
+                self.reply(uuid, text);
+                if let Some(level) = gathered.level {
+                    self.reply(uuid, Text::new("gather.level").with("level", level));
+                }
+                if let Some(table) = definition.loot.clone() {
+                    self.find_loot(uuid, &entity, &node, &table);
+                }
+                let mut net = Net::new();
+                systems::inventory::changed(&self.world, &mut net, &entity);
+                self.dispatch(net);
+            }
+            Err(why) => self.reply(uuid, why),
+        }
+    }
+
+    /// Rolls for extra finds while gathering from a node, at the player's gathering level.
+    fn find_loot(&mut self, uuid: Uuid, entity: &Entity, node: &Entity, table: &str) {
+        let level = self
+            .world
+            .get_component::<Skills>(entity)
+            .map_or(0, |skills| skills.gathering_level());
+        let found = self
+            .loot
+            .roll(table, level, systems::roll(node, self.timers.tick()));
+
+        for stack in found {
+            let count = systems::inventory::give(
+                &mut self.world,
+                &self.items,
+                entity,
+                stack.item,
+                stack.count,
+            );
+            if count == 0 {
+                continue;
+            }
+
+            let name = self
+                .items
+                .get(&stack.item)
+                .map_or(Text::literal("items"), |item| item.text());
+            self.reply(
+                uuid,
+                Text::new("gather.found")
+                    .with("count", count)
+                    .with_text("item", name),
+            );
+        }
+    }
+
+    /// Rolls the loot of anything that died or was destroyed, leaving it to decay on the ground.
+    /// Rolled before the spawners despawn the dead and again before destroyed obstacles are removed.
+    fn drop_loot(&mut self) {
+        let expires = get_now() + Self::ITEM_DECAY;
+        let placed = systems::loot::drop(
+            &mut self.world,
+            &mut self.spatial,
+            &self.loot,
+            self.timers.tick(),
+            expires,
+        );
+        for item in placed {
+            self.timers
+                .add_timer_sec(Self::ITEM_DECAY as f32, TimerData::ItemDecay(item), true);
+        }
+    }
+
+    /// Shows a player's emote to those nearby, ignoring ones sent too quickly.
+    fn emote(&mut self, uuid: Uuid, payload: Payload) {
+        let kind = match payload {
+            Payload::Emote(emote) => emote.kind,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let tick = self.timers.tick();
+        if !systems::casting::is_ready(&self.world, &entity, Ability::Emote, tick) {
+            return;
+        }
+        systems::casting::cooldown(
+            &mut self.world,
+            &entity,
+            Ability::Emote,
+            tick,
+            Self::EMOTE_COOLDOWN,
+        );
+        self.telemetry.ability(Ability::Emote, &self.items);
+
+        // The entity is taken from the server, clients cannot emote for others.
+        self.broadcast_to(
+            movement::get_observers(&self.world, &self.spatial, &entity),
+            Event::Emote(EmotePayload::new(entity, kind)),
+        );
+    }
+
+    /// Executes a chat command sent by a player.
+    /// Runs chat commands, while other messages are shared with the other servers.
+    fn chat(&mut self, uuid: Uuid, payload: Payload) {
+        let message = match payload {
+            Payload::Message(payload) => payload.message,
+            _ => return,
+        };
+
+        if message.trim_start().starts_with('/') {
+            self.chat_command(uuid, &message);
+        } else {
+            self.shards.chat(uuid, message);
+        }
+    }
+
+    fn chat_command(&mut self, uuid: Uuid, message: &str) {
+        let (command, argument) = match message.trim().split_once(' ') {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (message.trim(), ""),
+        };
+
+        match command {
+            "/top" => self.leaderboard(uuid, true),
+            "/team" => match Team::from_name(argument) {
+                Some(team) => self.change_team(uuid, team),
+                None => self.reply(uuid, "usage.team"),
+            },
+            "/t" if !argument.is_empty() => self.team_chat(uuid, argument),
+            "/mail" => self.reply(uuid, "usage.mail"),
+            "/claim" => self.claim_plot(uuid),
+            "/abandon" => self.abandon_plot(uuid),
+            "/place" => match ObstacleKind::from_name(argument)
+                .filter(|kind| ObstacleKind::PLACEABLE.contains(kind))
+            {
+                Some(kind) => self.place_object(uuid, kind),
+                None => self.reply(uuid, "usage.place"),
+            },
+            "/remove" => self.remove_object(uuid),
+            "/tp" => self.goto(uuid, argument),
+            "/coowner" => match argument.split_once(' ') {
+                Some(("add", name)) => self.coowner(uuid, name, true),
+                Some(("remove", name)) => self.coowner(uuid, name, false),
+                _ => self.reply(uuid, "usage.coowner"),
+            },
+            _ => {
+                let mut net = Net::new();
+                let handled = self.with_plugins(&mut net, |plugins, ctx| {
+                    plugins.command(ctx, uuid, command, argument)
+                });
+                self.dispatch(net);
+                if !handled {
+                    self.reply(uuid, Text::new("usage.unknown").with("command", command));
+                }
+            }
+        }
+    }
+
+    /// Moves a gamemaster to the coordinates given, changing worlds if they belong to another.
+    fn goto(&mut self, uuid: Uuid, argument: &str) {
+        if !self.is_gamemaster(&uuid) {
+            return self.reply(uuid, "teleport.gamemaster");
+        }
+        let coords: Vec<f64> = argument
+            .split_whitespace()
+            .filter_map(|value| value.parse().ok())
+            .filter(|value: &f64| value.is_finite())
+            .collect();
+        let (x, y) = match coords.as_slice() {
+            [x, y] => (*x, *y),
+            _ => return self.reply(uuid, "usage.tp"),
+        };
+        let (entity, z) = match self.players.get(&uuid).and_then(|entity| {
+            let position = self.world.get_component::<Position>(entity)?;
+            Some((*entity, position.loc.z()))
+        }) {
+            Some(found) => found,
+            None => return self.reply(uuid, "common.absent"),
+        };
+
+        let loc = Vec3::new(x, y, z);
+        if self.regions.get_region_id(&loc).is_none() {
+            return self.reply(uuid, "teleport.nowhere");
+        }
+
+        self.world.remove_component::<Velocity>(entity);
+        self.world.remove_component::<Acceleration>(entity);
+        let mut net = Net::new();
+        self.teleport(&mut net, &entity, loc);
+        self.dispatch(net);
+    }
+
+    /// Account of a player and the plot they are standing within.
+    fn standing_plot(&self, uuid: Uuid) -> Result<(Entity, AccountId, String), &'static str> {
+        let id = match (&self.accounts, self.sessions.get(&uuid)) {
+            (Some(_), Some(id)) => *id,
+            _ => return Err("plot.account"),
+        };
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return Err("common.absent"),
+        };
+
+        // Plots are only within the overworld.
+        let center = match self.world.get_component::<Position>(&entity) {
+            Some(position) if self.instance.is_none() => position.bounds().center_2d(),
+            _ => return Err("plot.not_within"),
+        };
+        match self.plots.at(&Vec3::new(center.x(), center.y(), 0.)) {
+            Some(plot) => Ok((entity, id, plot.name.clone())),
+            None => Err("plot.not_within"),
+        }
+    }
+
+    /// Claims the plot a player is standing within, each account can own a single plot.
+    fn claim_plot(&mut self, uuid: Uuid) {
+        let (_, id, name) = match self.standing_plot(uuid) {
+            Ok(standing) => standing,
+            Err(why) => return self.reply(uuid, why),
+        };
+
+        if let Some(owned) = self.plots.owned_by(id) {
+            let why = Text::new("plot.already_own").with("plot", &owned.name);
+            return self.reply(uuid, why);
+        }
+        match self.plots.get_mut(&name) {
+            Some(plot) if plot.owner.is_none() => plot.owner = Some(id),
+            _ => return self.reply(uuid, "plot.claimed"),
+        }
+
+        self.save_plot(&name);
+        self.reply(uuid, Text::new("plot.owned").with("plot", name));
+    }
+
+    /// Releases the plot a player owns, removing everything placed within it.
+    fn abandon_plot(&mut self, uuid: Uuid) {
+        let id = match self.sessions.get(&uuid) {
+            Some(id) => *id,
+            None => return self.reply(uuid, "plot.account"),
+        };
+        let name = match self.plots.owned_by(id) {
+            Some(plot) => plot.name.clone(),
+            None => return self.reply(uuid, "plot.none"),
+        };
+
+        if let Some(plot) = self.plots.get_mut(&name) {
+            plot.owner = None;
+            plot.members.clear();
+        }
+        systems::plots::clear(&mut self.world, &mut self.spatial, &name);
+        self.save_plot(&name);
+        self.reply(uuid, Text::new("plot.abandoned").with("plot", name));
+    }
+
+    /// Places an object beside a player within a plot they own or share.
+    fn place_object(&mut self, uuid: Uuid, kind: ObstacleKind) {
+        let (entity, id, name) = match self.standing_plot(uuid) {
+            Ok(standing) => standing,
+            Err(why) => return self.reply(uuid, why),
+        };
+        let plot = match self.plots.get(&name) {
+            Some(plot) if plot.permits(id) => plot,
+            _ => return self.reply(uuid, "plot.not_allowed"),
+        };
+
+        match systems::plots::place(&mut self.world, &mut self.spatial, plot, kind, &entity) {
+            Ok(_) => {
+                self.save_plot(&name);
+                self.reply(uuid, Text::new("plot.placed").with("object", kind.name()));
+            }
+            Err(why) => self.reply(uuid, why),
+        }
+    }
+
+    /// Removes the closest object a player can reach within a plot they own or share.
+    fn remove_object(&mut self, uuid: Uuid) {
+        let (entity, id, name) = match self.standing_plot(uuid) {
+            Ok(standing) => standing,
+            Err(why) => return self.reply(uuid, why),
+        };
+        if !self.plots.get(&name).is_some_and(|plot| plot.permits(id)) {
+            return self.reply(uuid, "plot.not_allowed");
+        }
+
+        let object = match systems::plots::within_reach(&self.world, &self.spatial, &entity, &name)
+        {
+            Some(object) => object,
+            None => return self.reply(uuid, "plot.nothing_nearby"),
+        };
+        match systems::plots::remove(&mut self.world, &mut self.spatial, &object) {
+            Ok(()) => {
+                self.save_plot(&name);
+                self.reply(uuid, "plot.removed");
+            }
+            Err(why) => self.reply(uuid, why),
+        }
+    }
+
+    /// Adds or removes a co-owner of the plot a player owns.
+    fn coowner(&mut self, uuid: Uuid, username: &str, add: bool) {
+        let (db, id) = match (&self.accounts, self.sessions.get(&uuid)) {
+            (Some(db), Some(id)) => (db, *id),
+            _ => return self.reply(uuid, "plot.account"),
+        };
+        let member = match db.account_id(username) {
+            Ok(Some(member)) if member != id => member,
+            Ok(_) => {
+                return self.reply(
+                    uuid,
+                    Text::new("plot.unknown_account").with("name", username),
+                )
+            }
+            Err(why) => {
+                sprintln!("Unable to look up account '{}': {}", username, why);
+                return;
+            }
+        };
+        let name = match self.plots.owned_by(id) {
+            Some(plot) => plot.name.clone(),
+            None => return self.reply(uuid, "plot.none"),
+        };
+
+        let plot = match self.plots.get_mut(&name) {
+            Some(plot) => plot,
+            None => return,
+        };
+        let message = if !add {
+            match plot.members.remove(&member) {
+                true => Text::new("plot.member_removed"),
+                false => Text::new("plot.not_member"),
+            }
+        } else if plot.members.len() >= PlotManager::MAX_MEMBERS {
+            Text::new("plot.members_full").with("max", PlotManager::MAX_MEMBERS)
+        } else {
+            plot.members.insert(member);
+            Text::new("plot.member_added")
+        };
+        let message = message.with("name", username).with("plot", &name);
+
+        self.save_plot(&name);
+        self.reply(uuid, message);
+    }
+
+    /// Leaves a letter for another account, letting them know right away if they are online.
+    fn mail(&mut self, uuid: Uuid, payload: Payload) {
+        let mail = match payload {
+            Payload::Mail(mail) => mail,
+            _ => return,
+        };
+
+        let (db, sender) = match (&self.accounts, self.sessions.get(&uuid)) {
+            (Some(db), Some(id)) => (db, *id),
+            _ => {
+                self.reply(uuid, "mail.account");
+                return;
+            }
+        };
+
+        let message = mail.message.trim();
+        if message.is_empty() || message.len() > Self::MAX_MAIL_LENGTH {
+            let why = Text::new("mail.length").with("max", Self::MAX_MAIL_LENGTH);
+            self.reply(uuid, why);
+            return;
+        }
+
+        let sent = db.send_mail(sender, &mail.recipient, message);
+        match sent {
+            Ok(recipient) => {
+                self.reply(uuid, Text::new("mail.sent").with("name", &mail.recipient));
+                let online = self
+                    .sessions
+                    .iter()
+                    .find(|(_, id)| **id == recipient)
+                    .map(|(session, _)| *session);
+                if let Some(online) = online {
+                    self.mail_notice(online);
+                }
+            }
+            Err(why) => self.reply(uuid, Text::new("mail.failed").with("reason", why)),
+        }
+    }
+
+    /// Informs a player of their unread letters, if they have any.
+    fn mail_notice(&self, uuid: Uuid) {
+        let (db, id) = match (&self.accounts, self.sessions.get(&uuid)) {
+            (Some(db), Some(id)) => (db, *id),
+            _ => return,
+        };
+
+        match db.unread_mail(id) {
+            Ok(0) => (),
+            Ok(unread) => self.send_to(uuid, Event::Mailbox(MailboxPayload::new(unread, None))),
+            Err(why) => sprintln!("Unable to check the mail for {}: {}", uuid, why),
+        }
+    }
+
+    /// Sends the letters in a player's mailbox, marking them as read.
+    fn mailbox(&self, uuid: Uuid) {
+        let (db, id) = match (&self.accounts, self.sessions.get(&uuid)) {
+            (Some(db), Some(id)) => (db, *id),
+            _ => {
+                self.reply(uuid, "mail.no_mailbox");
+                return;
+            }
+        };
+
+        let mailbox = db
+            .read_mail(id, Self::MAILBOX_SIZE)
+            .and_then(|letters| Ok(MailboxPayload::new(db.unread_mail(id)?, Some(letters))));
+        match mailbox {
+            Ok(mailbox) => self.send_to(uuid, Event::Mailbox(mailbox)),
+            Err(why) => sprintln!("Unable to read the mail for {}: {}", uuid, why),
+        }
+    }
+
+    /// Name other players know a player by, their account or a shortened guest name.
+    fn display_name(&self, uuid: &Uuid) -> String {
+        let username = match (&self.accounts, self.sessions.get(uuid)) {
+            (Some(db), Some(id)) => db.username(*id).ok().flatten(),
+            _ => None,
+        };
+        username.unwrap_or_else(|| format!("guest-{}", &uuid.simple().to_string()[..8]))
+    }
+
+    /// Acts on a request made while trading, telling both players how the trade changed.
+    pub(crate) fn trade(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::TradeRequest(request) => request,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let result = match request {
+            TradeRequest::Invite(target) => self.invite_trade(uuid, &entity, &target),
+            TradeRequest::Accept => match self.trades.invited_by(&uuid) {
+                Some(inviter) => match self.players.get(&inviter).copied() {
+                    Some(target) => self.invite_trade(uuid, &entity, &target),
+                    None => Err("trade.invalid"),
+                },
+                None => Err("trade.none"),
+            },
+            TradeRequest::Offer { slot, count } => {
+                let offered =
+                    self.world
+                        .get_component::<Inventory>(&entity)
+                        .and_then(|inventory| {
+                            let stack = inventory.stacks.get(slot as usize)?;
+                            Some((
+                                stack.item,
+                                count.min(stack.count),
+                                inventory.count(stack.item),
+                            ))
+                        });
+                match offered {
+                    Some((item, count, owned)) => {
+                        self.trades.offer(&uuid, ItemStack::new(item, count), owned)
+                    }
+                    None => Err("trade.invalid"),
+                }
+            }
+            TradeRequest::Withdraw(index) => self.trades.withdraw(&uuid, index as usize),
+            TradeRequest::Lock(locked) => self.trades.lock(&uuid, locked),
+            TradeRequest::Confirm => match self.trades.confirm(&uuid) {
+                Ok(true) => return self.complete_trade(&uuid),
+                result => result.map(|_| ()),
+            },
+            TradeRequest::Cancel => return self.cancel_trade(&uuid),
+        };
+
+        match result {
+            Ok(()) => self.send_trade(&uuid),
+            Err(why) => self.reply(uuid, why),
+        }
+    }
+
+    /// Opens, closes, deposits into, or withdraws from a player's bank.
+    fn bank(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::BankRequest(request) => request,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        if request == BankRequest::Close {
+            self.banking.remove(&uuid);
+            return;
+        } else if request != BankRequest::Open && !self.banking.contains(&uuid) {
+            self.reply(uuid, "bank.closed");
+            return;
+        }
+
+        // The bank is only reachable from a banker or a bank, checked with every request.
+        let zones = self
+            .world
+            .get_component::<Position>(&entity)
+            .and_then(|position| self.get_region(&position.loc))
+            .map(|region| region.banks.as_slice())
+            .unwrap_or_default();
+        if !systems::bank::within_reach(&self.world, &self.spatial, zones, &entity) {
+            self.banking.remove(&uuid);
+            self.reply(uuid, "bank.too_far");
+            return;
+        }
+
+        let (mut carried, mut bank) = match (
+            self.world.get_component::<Inventory>(&entity),
+            self.world.get_component::<Bank>(&entity),
+        ) {
+            (Some(carried), Some(bank)) => (carried.clone(), bank.0.clone()),
+            _ => return,
+        };
+
+        let moved = match request {
+            BankRequest::Deposit { slot, count } => {
+                let moved = systems::bank::transfer(
+                    &self.items,
+                    &mut carried,
+                    &mut bank,
+                    slot as usize,
+                    count,
+                );
+                (moved == 0).then_some("bank.full")
+            }
+            BankRequest::Withdraw { slot, count } => {
+                let moved = systems::bank::transfer(
+                    &self.items,
+                    &mut bank,
+                    &mut carried,
+                    slot as usize,
+                    count,
+                );
+                (moved == 0).then_some("bank.too_heavy")
+            }
+            _ => {
+                self.banking.insert(uuid);
+                None
+            }
+        };
+        if let Some(why) = moved {
+            self.reply(uuid, why);
+        }
+
+        let payload = BankPayload::new(bank.stacks.clone(), bank.capacity);
+        self.world.upsert_component(entity, carried);
+        self.world.upsert_component(entity, Bank(bank));
+
+        let mut net = Net::new();
+        systems::inventory::changed(&self.world, &mut net, &entity);
+        net.send_to(uuid, Event::Bank(payload));
+        self.dispatch(net);
+    }
+
+    /// Invites the player beneath the pointer to trade, or accepts their invitation.
+    fn invite_trade(
+        &mut self,
+        uuid: Uuid,
+        entity: &Entity,
+        target: &Entity,
+    ) -> Result<(), &'static str> {
+        let other = match self.world.get_component::<Player>(target) {
+            Some(player) => *player.uuid(),
+            None => return Err("trade.invalid"),
+        };
+        if !self.within_trade_range(entity, target) {
+            return Err("trade.too_far");
+        }
+
+        // Whoever they invited before is no longer waiting on them.
+        let replaced = self
+            .trades
+            .inviting(&uuid)
+            .filter(|invited| *invited != other);
+        self.trades.invite(uuid, other)?;
+        if let Some(replaced) = replaced {
+            self.send_trade_state(replaced, uuid, TradeState::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Checks if two players stand close enough to trade.
+    fn within_trade_range(&self, entity: &Entity, other: &Entity) -> bool {
+        match (
+            self.world.get_component::<Position>(entity),
+            self.world.get_component::<Position>(other),
+        ) {
+            (Some(a), Some(b)) => {
+                a.bounds().center_2d().distance(&b.bounds().center_2d()) <= Self::TRADE_RANGE
+            }
+            _ => false,
+        }
+    }
+
+    /// Tells a player and their partner how their trade or invitation stands.
+    fn send_trade(&self, uuid: &Uuid) {
+        if let Some(trade) = self.trades.get(uuid) {
+            for player in trade.players() {
+                let (mine, theirs) = trade.offers(&player);
+                let partner = self.display_name(&trade.partner(&player));
+                self.send_to(
+                    player,
+                    Event::Trade(TradePayload::new(
+                        partner,
+                        TradeState::Open,
+                        mine.clone(),
+                        theirs.clone(),
+                    )),
+                );
+            }
+        } else if let Some(invited) = self.trades.inviting(uuid) {
+            self.send_trade_state(*uuid, invited, TradeState::Waiting);
+            self.send_trade_state(invited, *uuid, TradeState::Invited);
+        }
+    }
+
+    /// Tells a player where a trade stands without the offers, such as for an invitation.
+    fn send_trade_state(&self, uuid: Uuid, partner: Uuid, state: TradeState) {
+        self.send_to(
+            uuid,
+            Event::Trade(TradePayload::new(
+                self.display_name(&partner),
+                state,
+                TradeOffer::default(),
+                TradeOffer::default(),
+            )),
+        );
+    }
+
+    /// Ends the trade and invitations of a player, telling everyone involved.
+    fn cancel_trade(&mut self, uuid: &Uuid) {
+        for other in self.trades.cancel(uuid) {
+            self.send_trade_state(*uuid, other, TradeState::Cancelled);
+            self.send_trade_state(other, *uuid, TradeState::Cancelled);
+        }
+    }
+
+    /// Exchanges the offers once both players have confirmed, cancelling the trade if they cannot.
+    fn complete_trade(&mut self, uuid: &Uuid) {
+        let trade = match self.trades.take(uuid) {
+            Some(trade) => trade,
+            None => return,
+        };
+
+        let [first, second] = trade.players();
+        let entities = match (self.players.get(&first), self.players.get(&second)) {
+            (Some(a), Some(b)) if self.within_trade_range(a, b) => Some((*a, *b)),
+            _ => None,
+        };
+        let exchanged = match entities.map(|(a, b)| {
+            (
+                self.world.get_component::<Inventory>(&a),
+                self.world.get_component::<Inventory>(&b),
+            )
+        }) {
+            Some((Some(a), Some(b))) => trade.exchange(&self.items, a, b),
+            _ => Err("trade.too_far"),
+        };
+
+        let state = match (exchanged, entities) {
+            (Ok([a, b]), Some((first_entity, second_entity))) => {
+                self.world.upsert_component(first_entity, a);
+                self.world.upsert_component(second_entity, b);
+
+                let mut net = Net::new();
+                systems::inventory::changed(&self.world, &mut net, &first_entity);
+                systems::inventory::changed(&self.world, &mut net, &second_entity);
+                self.dispatch(net);
+                TradeState::Completed
+            }
+            (Err(why), _) => {
+                self.reply(first, why);
+                self.reply(second, why);
+                TradeState::Cancelled
+            }
+            _ => TradeState::Cancelled,
+        };
+
+        for player in trade.players() {
+            let (mine, theirs) = trade.offers(&player);
+            self.send_to(
+                player,
+                Event::Trade(TradePayload::new(
+                    self.display_name(&trade.partner(&player)),
+                    state,
+                    mine.clone(),
+                    theirs.clone(),
+                )),
+            );
+        }
+    }
+
+    /// Moves along the conversation a player is having with an NPC.
+    pub(crate) fn dialogue(&mut self, uuid: Uuid, payload: Payload) {
+        let reply = match payload {
+            Payload::DialogueReply(reply) => reply,
+            _ => return,
+        };
+
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        match reply {
+            DialogueReply::Talk => {
+                let npc = systems::npcs::within_reach(&self.world, &self.spatial, &entity)
+                    .into_iter()
+                    .find_map(|npc| {
+                        let tree = self.world.get_component::<Npc>(&npc)?.dialogue.clone()?;
+                        let start = self.dialogues.get(&tree)?.start.clone();
+                        Some((npc, tree, start))
+                    });
+                match npc {
+                    Some((npc, tree, start)) => self.converse(uuid, npc, tree, start),
+                    None => self.reply(uuid, "dialogue.nobody"),
+                }
+            }
+            DialogueReply::Page(page) => {
+                if let Some(conversation) = self.conversations.get(&uuid) {
+                    self.dialogue_page(uuid, conversation, page);
+                }
+            }
+            DialogueReply::Choice(index) => self.choose(uuid, &entity, index as usize),
+            DialogueReply::Close => {
+                self.conversations.remove(&uuid);
+            }
+        }
+    }
+
+    /// Shows a node of a dialogue tree, offering only the choices the player meets the conditions of.
+    fn converse(&mut self, uuid: Uuid, npc: Entity, tree: String, node: String) {
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        let (pages, offered) = match self.dialogues.get(&tree).and_then(|tree| tree.node(&node)) {
+            Some(node) => (
+                DialogueManager::paginate(&node.text),
+                node.choices
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, choice)| {
+                        systems::dialogue::meets(&self.world, &entity, &choice.conditions)
+                    })
+                    .map(|(i, _)| i)
+                    .collect(),
+            ),
+            None => {
+                self.end_conversation(uuid);
+                return;
+            }
+        };
+
+        let conversation = Conversation {
+            npc,
+            tree,
+            node,
+            pages,
+            offered,
+        };
+        self.dialogue_page(uuid, &conversation, 0);
+        self.conversations.insert(uuid, conversation);
+    }
+
+    /// Sends a page of the conversation, along with the choices if it is the last.
+    fn dialogue_page(&self, uuid: Uuid, conversation: &Conversation, page: u8) {
+        let text = match conversation.pages.get(page as usize) {
+            Some(text) => text,
+            None => return,
+        };
+
+        let pages = conversation.pages.len().min(u8::MAX as usize) as u8;
+        let choices = match self
+            .dialogues
+            .get(&conversation.tree)
+            .and_then(|tree| tree.node(&conversation.node))
+        {
+            Some(node) if page + 1 >= pages => conversation
+                .offered
+                .iter()
+                .filter_map(|i| node.choices.get(*i))
+                .map(|choice| choice.text.clone())
+                .collect(),
+            _ => vec![],
+        };
+        let speaker = self
+            .world
+            .get_component::<Npc>(&conversation.npc)
+            .map_or(Text::literal(""), |npc| npc.text());
+
+        self.send_to(
+            uuid,
+            Event::Dialogue(DialoguePayload::new(
+                conversation.npc,
+                speaker,
+                text,
+                page,
+                pages,
+                choices,
+            )),
+        );
+    }
+
+    /// Picks a choice for a player, validating it against what they were offered and can meet.
+    fn choose(&mut self, uuid: Uuid, entity: &Entity, index: usize) {
+        let conversation = match self.conversations.get(&uuid) {
+            Some(conversation) => conversation.clone(),
+            None => return,
+        };
+
+        // Players have to stay with the NPC to answer them.
+        let reachable = systems::npcs::within_reach(&self.world, &self.spatial, entity);
+        if !reachable.contains(&conversation.npc) {
+            self.end_conversation(uuid);
+            self.reply(uuid, "dialogue.too_far");
+            return;
+        }
+
+        let choice = match conversation.offered.get(index).and_then(|i| {
+            self.dialogues
+                .get(&conversation.tree)?
+                .node(&conversation.node)?
+                .choices
+                .get(*i)
+        }) {
+            Some(choice) => choice.clone(),
+            None => {
+                let evidence = format!("dialogue choice {} was never offered", index);
+                self.anticheat.flag(uuid, Violation::Malformed, evidence);
+                return;
+            }
+        };
+
+        // Conditions may have changed since the choice was offered, such as dropping an item.
+        let (npc, tree, node) = (conversation.npc, conversation.tree, conversation.node);
+        if !systems::dialogue::meets(&self.world, entity, &choice.conditions) {
+            self.reply(uuid, "dialogue.unavailable");
+            self.converse(uuid, npc, tree, node);
+            return;
+        }
+
+        match systems::dialogue::apply(&mut self.world, &self.items, entity, &choice.hooks) {
+            Ok(messages) => {
+                for message in messages {
+                    self.send_to(
+                        uuid,
+                        Event::Notification(NotificationPayload::new(
+                            NotificationKind::Announcement,
+                            message,
+                        )),
+                    );
+                }
+            }
+            Err(why) => {
+                self.reply(uuid, why);
+                self.converse(uuid, npc, tree, node);
+                return;
+            }
+        }
+
+        let mut net = Net::new();
+        systems::inventory::changed(&self.world, &mut net, entity);
+        self.dispatch(net);
+
+        match choice.next {
+            Some(next) => self.converse(uuid, npc, tree, next),
+            None => self.end_conversation(uuid),
+        }
+    }
+
+    /// Ends the conversation a player is having, closing it for their client.
+    fn end_conversation(&mut self, uuid: Uuid) {
+        self.conversations.remove(&uuid);
+        self.send_to(uuid, Event::DialogueEnded);
+    }
+
+    /// Checks if the player is logged into an account allowed to view the debug overlays.
+    fn is_gamemaster(&self, uuid: &Uuid) -> bool {
+        let (db, id) = match (&self.accounts, self.sessions.get(uuid)) {
+            (Some(db), Some(id)) => (db, *id),
+            _ => return false,
+        };
+
+        match db.username(id) {
+            Ok(Some(username)) => self.config.is_gamemaster(&username),
+            Ok(None) => false,
+            Err(why) => {
+                sprintln!("Unable to look up account [{}]: {}", id, why);
+                false
+            }
+        }
+    }
+
+    /// Changes the debug overlays a gamemaster is viewing, none turns them off.
+    fn request_overlays(&mut self, uuid: Uuid, payload: Payload) {
+        let request = match payload {
+            Payload::OverlayRequest(request) => request,
+            _ => return,
+        };
+
+        if request.kinds.is_empty() {
+            self.overlays.remove(&uuid);
+            return;
+        } else if !self.is_gamemaster(&uuid) {
+            self.reply(uuid, "overlay.gamemaster");
+            return;
+        }
+
+        self.overlays.insert(uuid, request.kinds);
+        self.send_overlays(&uuid);
+    }
+
+    /// Sends the debug overlays around a gamemaster within the current world.
+    fn send_overlays(&self, uuid: &Uuid) {
+        let (kinds, entity) = match (self.overlays.get(uuid), self.players.get(uuid)) {
+            (Some(kinds), Some(entity)) => (kinds, entity),
+            _ => return,
+        };
+        let center = match self.world.get_component::<Position>(entity) {
+            Some(position) => position.bounds().center_2d(),
+            None => return,
+        };
+
+        let radius = self.spatial.interest().player;
+        for kind in kinds.iter() {
+            let overlay = systems::inspect::overlay(
+                &self.world,
+                &self.spatial,
+                &self.regions,
+                *kind,
+                center,
+                radius,
+            );
+            self.send_to(*uuid, Event::Overlay(overlay));
+        }
+    }
+
+    /// Sends a message only to the player.
+    fn reply(&self, uuid: Uuid, message: impl Into<Text>) {
+        self.send_to(uuid, Event::Text(message.into()));
+    }
+
+    /// Moves a player to another team, showing the change to those nearby.
+    fn change_team(&mut self, uuid: Uuid, team: Team) {
+        let entity = match self.players.get(&uuid) {
+            Some(entity) => *entity,
+            None => return,
+        };
+
+        self.world.upsert_component(entity, team);
+        self.reply(uuid, Text::new("team.joined").with("team", team.name()));
+        let mut net = Net::new();
+        systems::equipment::appearance(&self.world, &self.spatial, &mut net, &entity);
+        self.dispatch(net);
+    }
+
+    /// Sends a message to every member of the player's team.
+    fn team_chat(&self, uuid: Uuid, message: &str) {
+        let team = match self
+            .players
+            .get(&uuid)
+            .and_then(|entity| self.world.get_component::<Team>(entity))
+        {
+            Some(team) => *team,
+            None => return,
+        };
+
+        let members: HashSet<Uuid> = self
+            .world
+            .query2::<Player, Team>()
+            .into_iter()
+            .filter(|(_, _, member)| **member == team)
+            .map(|(_, player, _)| *player.uuid())
+            .collect();
+
+        event_log::record(ServerEvent::Chat {
+            uuid,
+            message: message.to_string(),
+        });
+        self.broadcast_to(
+            members,
+            Event::Chat {
+                from: uuid,
+                message: format!("[team] {}", message),
+            },
+        );
+    }
+
+    /// Team with the fewest players, new players are placed on it to keep the teams even.
+    fn smallest_team(&self) -> Team {
+        let members = |team: Team| {
+            self.world
+                .query1::<Team>()
+                .into_iter()
+                .filter(|(_, member)| **member == team)
+                .count()
+        };
+
+        Team::ALL
+            .into_iter()
+            .min_by_key(|team| members(*team))
+            .unwrap_or(Team::Red)
+    }
+
+    /// Sends the leaderboard to a player, as text if it was requested through chat.
+    fn leaderboard(&self, uuid: Uuid, as_text: bool) {
+        // Saved accounts are ranked by the database, so the online ones are saved first.
+        let mut players: Vec<(String, Stats)> = vec![];
+        if let Some(db) = &self.accounts {
+            for (session, id) in self.sessions.iter() {
+                let stats = self
+                    .players
+                    .get(session)
+                    .and_then(|entity| self.world.get_component::<Stats>(entity));
+                if let Some(stats) = stats {
+                    if let Err(why) = db.save_stats(*id, stats) {
+                        sprintln!("Unable to save stats for {}: {}", session, why);
+                    }
+                }
+            }
+
+            match db.top_stats(systems::stats::LEADERBOARD_SIZE) {
+                Ok(top) => players.extend(top),
+                Err(why) => sprintln!("Unable to load the leaderboard: {}", why),
+            }
+        }
+
+        // Guests are only ranked while they are online.
+        for (guest, entity) in self.players.iter() {
+            if self.sessions.contains_key(guest) {
+                continue;
+            }
+
+            if let Some(stats) = self.world.get_component::<Stats>(entity) {
+                players.push((
+                    format!("guest-{}", &guest.simple().to_string()[..8]),
+                    *stats,
+                ));
+            }
+        }
+
+        let entries = systems::stats::leaderboard(players);
+        if as_text {
+            self.reply(uuid, "leaderboard.title");
+            for (i, entry) in entries.iter().enumerate() {
+                let text = Text::new("leaderboard.entry")
+                    .with("rank", i + 1)
+                    .with("name", &entry.name)
+                    .with("kills", entry.stats.kills)
+                    .with("deaths", entry.stats.deaths);
+                self.reply(uuid, text);
+            }
+            return;
+        }
+
+        self.send_to(uuid, Event::Leaderboard(LeaderboardPayload::new(entries)));
+    }
+
+    fn projectile(&mut self, uuid: Uuid, payload: Payload) {
+        let movement = match payload {
+            Payload::Movement(movement) => movement,
+            _ => return,
+        };
+
+        // Only players in the world can fire projectiles, once the round allows it and out of the water.
+        if self.is_frozen() {
+            return;
+        }
+
+        let owner = match self.players.get(&uuid) {
+            Some(entity) if !systems::terrain::is_swimming(&self.world, entity) => *entity,
+            _ => return,
+        };
+
+        // Projectiles must leave from beside the player firing them.
+        if let Some(origin) = self.world.get_component::<Position>(&owner) {
+            let distance = origin.loc.distance_2d(&movement.position);
+            if distance > Self::PROJECTILE_REACH {
+                let evidence = format!("projectile fired {:.1} away", distance);
+                self.anticheat.flag(uuid, Violation::Teleport, evidence);
+                return;
+            }
+        }
+
+        let position = Position::new(movement.position, movement.size);
+        self.fire(owner, position, movement.velocity);
+    }
+
+    /// Fires at a target a player has locked on to, refusing if it cannot be seen.
+    fn attack(&mut self, uuid: Uuid, payload: Payload) {
+        let target = match payload {
+            Payload::Target(TargetPayload {
+                target: Some(target),
+                ..
+            }) => target,
+            _ => return,
+        };
+
+        // Held to the same rules as firing freely.
+        if self.is_frozen() {
+            return;
+        }
+        let owner = match self.players.get(&uuid) {
+            Some(entity) if !systems::terrain::is_swimming(&self.world, entity) => *entity,
+            _ => return,
+        };
+
+        let (origin, goal) = match self.lock_on(&owner, &target) {
+            Ok(found) => found,
+            Err(error) => {
+                self.send_to(uuid, Event::AttackError(error));
+                return;
+            }
+        };
+
+        // Leave from just outside the attacker, heading for the target.
+        let (from, to) = (origin.bounds().center_2d(), goal.bounds().center_2d());
+        let velocity = Vec2::new(to.x() - from.x(), to.y() - from.y());
+        let direction = velocity.normalize();
+        let reach = origin.size.x().max(origin.size.y()) / 2. + Self::PROJECTILE_SIZE;
+        let half = Self::PROJECTILE_SIZE / 2.;
+        let loc = Vec3::new(
+            from.x() + direction.x() * reach - half,
+            from.y() + direction.y() * reach - half,
+            origin.loc.z(),
+        );
+        let size = Vec2::new(Self::PROJECTILE_SIZE, Self::PROJECTILE_SIZE);
+        self.fire(owner, Position::new(loc, size), velocity);
+    }
+
+    /// Checks that a target can be attacked, obtaining where the attacker and target are.
+    fn lock_on(
+        &self,
+        owner: &Entity,
+        target: &Entity,
+    ) -> Result<(Position, Position), AttackError> {
+        let alive = self
+            .world
+            .get_component::<Health>(target)
+            .is_some_and(|health| !health.is_dead());
+        let (origin, goal) = match (
+            self.world.get_component::<Position>(owner),
+            self.world.get_component::<Position>(target),
+        ) {
+            (Some(origin), Some(goal)) if alive && owner != target => (*origin, *goal),
+            _ => return Err(AttackError::InvalidTarget),
+        };
+
+        let (from, to) = (origin.bounds().center_2d(), goal.bounds().center_2d());
+        if from.distance(&to) > Self::ATTACK_RANGE {
+            Err(AttackError::OutOfRange)
+        } else if !systems::sight::in_sight(&self.world, &self.spatial, owner, target) {
+            Err(AttackError::NotInSight)
+        } else {
+            Ok((origin, goal))
+        }
+    }
+
+    /// Spawns a projectile belonging to its owner, removed once its life runs out.
+    fn fire(&mut self, owner: Entity, position: Position, velocity: Vec2) {
+        let entity = self
+            .world
+            .spawn_pooled(Self::PROJECTILE_POOL, self.timers.tick())
+            .with(position)
+            .with(Velocity(velocity))
+            .with(Projectile { owner })
+            .with(Collidable)
+            .build();
+        self.spatial.insert_object(&entity, &position.bounds());
+
+        // Projectiles have timed life.
+        self.timers.add_timer_sec(
+            Self::PROJECTILE_LIFESPAN,
+            TimerData::EntityDelete(entity),
+            true,
+        );
+    }
+
+    /// Called on every tick for the server.
+    fn update(&mut self) {
+        let mut net = Net::new();
+        // Matches are only played in the overworld.
+        let overworld = self.instance.is_none();
+        if overworld
+            && self
+                .matches
+                .as_ref()
+                .is_some_and(|matches| matches.is_ready(self.players.len()))
+        {
+            self.advance_match();
+        }
+        // World events only take place in the overworld.
+        if overworld {
+            self.run_world_events(&mut net);
+            self.run_invasions(&mut net);
+        }
+
+        let elapsed = self.timers.server_tick_time().as_secs_f64();
+        let level = self.shedder.level();
+        let throttle = Throttle::new(
+            level,
+            &self.world,
+            self.timers.tick(),
+            self.spatial.interest().npc,
+        );
+        self.replicator
+            .advance(self.timers.tick(), level.replication_scale());
+        systems::stats::playtime(&mut self.world, elapsed);
+        systems::dormancy::update(&mut self.world, &self.spatial, self.timers.tick());
+        systems::dormancy::jump(
+            &mut self.world,
+            &mut self.spatial,
+            &self.regions,
+            self.timers.tick(),
+        );
+        self.drop_loot();
+        systems::spawners::update(&mut self.world, &mut self.spatial, &mut self.timers);
+        systems::threat::update(&mut self.world, &self.spatial, &throttle, &mut net, elapsed);
+        systems::bosses::update(
+            &mut self.world,
+            &self.spatial,
+            &self.npcs,
+            &mut net,
+            &self.timers,
+        );
+        systems::areas::update(
+            &mut self.world,
+            &self.spatial,
+            &self.items,
+            &mut net,
+            self.timers.tick(),
+            self.config.friendly_fire,
+        );
+        systems::spawners::wander(&mut self.world, &throttle, self.timers.tick());
+        systems::physics::step(&mut self.world, &self.regions);
+        systems::movement::with_velocity(
+            &mut self.world,
+            &mut self.spatial,
+            &self.regions,
+            &self.items,
+            &mut self.deltas,
+            &mut self.replicator,
+            &mut net,
+            self.config.friendly_fire,
+        );
+        systems::movement::separate(
+            &mut self.world,
+            &mut self.spatial,
+            &self.regions,
+            &mut self.deltas,
+            &mut net,
+        );
+        systems::falling::update(
+            &mut self.world,
+            &mut self.spatial,
+            &self.regions,
+            &mut self.deltas,
+            &mut net,
+        );
+        self.drop_loot();
+        systems::obstacles::destroyed(&mut self.world, &mut self.spatial);
+        systems::effects::expire(&mut self.world, &self.spatial, &mut net, self.timers.tick());
+        systems::terrain::update(&mut self.world, &self.spatial, &self.regions, &mut net);
+        systems::mounts::update(&mut self.world, &self.spatial, &self.regions, &mut net);
+        let cast =
+            systems::casting::update(&mut self.world, &self.spatial, &mut net, self.timers.tick());
+        for (entity, casting) in cast.into_iter() {
+            if let (Ability::Item(item), Some(slot)) = (casting.ability, casting.slot) {
+                self.apply_item(&entity, slot, item);
+            }
+        }
+        self.deltas.prune(&self.world);
+        self.replicator.prune(&self.world);
+        if cfg!(debug_assertions)
+            && !level.pauses()
+            && self.timers.tick().is_multiple_of(Self::SPATIAL_CHECK_TICKS)
+        {
+            systems::consistency::verify(&self.world, &mut self.spatial);
+        }
+        systems::chunks::stream(&self.world, &self.regions, &mut net, &mut self.chunks);
+        systems::obstacles::visibility(&self.world, &self.spatial, &mut net, &mut self.obstacles);
+        let active = overworld
+            && self
+                .matches
+                .as_ref()
+                .is_some_and(|matches| matches.phase() == MatchPhase::Active);
+        if active {
+            self.with_mode(&mut net, |mode, ctx| mode.update(ctx));
+        }
+        self.with_plugins(&mut net, |plugins, ctx| plugins.update(ctx));
+        self.telemetry.deaths(&self.world, &self.regions);
+        systems::ground::visibility(&self.world, &self.spatial, &mut net, &mut self.visible);
+        self.dispatch(net);
+
+        if !level.pauses() && self.timers.tick().is_multiple_of(Self::OVERLAY_TICKS) {
+            for uuid in self.overlays.keys() {
+                self.send_overlays(uuid);
+            }
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::mpsc as std_mpsc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::runtime::Runtime;
+use tokio::sync::{mpsc, watch};
+use uo2d_proto::components::Vec3;
+use uo2d_proto::ecs::{Component, Entity, PoolStats};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{NotificationKind, NotificationPayload};
+use uo2d_proto::packet::{Action, BroadcastScope, Packet, PacketConfiguration, Payload};
+use uo2d_proto::sprintln;
+use uuid::Uuid;
+
+use crate::announcements::Announcements;
+use crate::anticheat::AntiCheat;
+use crate::backup;
+use crate::cache::PacketCacheAsync;
+use crate::config::{ScheduledAnnouncement, ServerConfig};
+use crate::console::Console;
+use crate::content::Content;
+use crate::event_log::{self, ServerEvent};
+use crate::gamestate::Gamestate;
+use crate::instance::InstanceId;
+use crate::load::ShedStats;
+use crate::plugin::{Plugin, PluginContext, Plugins};
+use crate::region::RegionManager;
+use crate::shards::ShardStatus;
+use crate::socket_server::SocketServer;
+use crate::websocket;
+
+/// Requests sent from a handle to the running gamestate.
+pub(crate) enum ServerCommand {
+    Players(std_mpsc::Sender<Vec<PlayerInfo>>),
+    Announcements(std_mpsc::Sender<Announcements>),
+    Shards(std_mpsc::Sender<Vec<ShardStatus>>),
+    Pools(std_mpsc::Sender<PoolReport>),
+    Census(std_mpsc::Sender<Vec<WorldCensus>>),
+    Load(std_mpsc::Sender<ShedStats>),
+    /// Finds the entity of the player with the uuid.
+    Find(Uuid, std_mpsc::Sender<Option<EntityReport>>),
+    /// Describes an entity within the overworld, or an instance if given.
+    Inspect(
+        Option<InstanceId>,
+        Entity,
+        std_mpsc::Sender<Option<EntityReport>>,
+    ),
+    /// Broadcasts a message to every player once.
+    Announce(String),
+    Motd(Option<String>),
+    Schedule(ScheduledAnnouncement),
+    Unschedule(u32),
+    /// Saves the world and backs up the database immediately.
+    Backup,
+    /// Re-reads the configuration file.
+    Reload,
+    /// Re-reads the loot tables, keeping the current ones if the new ones are invalid.
+    ReloadLoot,
+}
+
+/// Snapshot of a connected player.
+#[derive(Debug, Clone)]
+pub struct PlayerInfo {
+    pub uuid: Uuid,
+    pub entity: Entity,
+    pub position: Vec3,
+    pub health: u32,
+}
+
+/// Entities of each kind within the overworld or an instance.
+#[derive(Debug, Clone)]
+pub struct WorldCensus {
+    /// None for the overworld.
+    pub instance: Option<InstanceId>,
+    pub kinds: Vec<(&'static str, usize)>,
+}
+
+/// Components of an entity, formatted for reading.
+#[derive(Debug, Clone)]
+pub struct EntityReport {
+    pub entity: Entity,
+    /// None for the overworld.
+    pub instance: Option<InstanceId>,
+    pub components: Vec<(&'static str, String)>,
+}
+
+/// Allocation counts of the pooled entities across every world.
+#[derive(Debug, Clone, Default)]
+pub struct PoolReport {
+    pub pools: Vec<(&'static str, PoolStats)>,
+    /// Components that reused the allocation of a released entity's.
+    pub recycled: u64,
+}
+
+/// Configures a server before it is spawned.
+pub struct ServerBuilder {
+    address: String,
+    /// Directory of the regions, those within the content root if unset.
+    regions: Option<String>,
+    config: String,
+    console: bool,
+    restore: Option<PathBuf>,
+    plugins: Plugins,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:31013".to_string(),
+            regions: None,
+            config: ServerConfig::PATH.to_string(),
+            console: false,
+            restore: None,
+            plugins: Plugins::default(),
+        }
+    }
+}
+
+impl ServerBuilder {
+    /// Packets the gamestate can queue for the socket server before dropping them.
+    const OUTGOING_CAPACITY: usize = 256;
+
+    /// Address to listen on, port 0 picks a free port.
+    pub fn address(mut self, address: &str) -> Self {
+        self.address = address.to_string();
+        self
+    }
+
+    /// Directory to load the region data from.
+    pub fn regions(mut self, directory: &str) -> Self {
+        self.regions = Some(directory.to_string());
+        self
+    }
+
+    /// Path to the server configuration.
+    pub fn config(mut self, path: &str) -> Self {
+        self.config = path.to_string();
+        self
+    }
+
+    /// Registers a callback invoked for every server event.
+    pub fn on_event(self, callback: impl Fn(&ServerEvent) + Send + Sync + 'static) -> Self {
+        event_log::subscribe(callback);
+        self
+    }
+
+    /// Reads administrative commands from standard input.
+    pub fn console(mut self, enabled: bool) -> Self {
+        self.console = enabled;
+        self
+    }
+
+    /// Replaces the database with a backup before the server starts.
+    pub fn restore(mut self, backup: Option<PathBuf>) -> Self {
+        self.restore = backup;
+        self
+    }
+
+    /// Adds a plugin, registering everything it provides.
+    pub fn plugin(mut self, plugin: impl Plugin) -> Self {
+        plugin.build(&mut self);
+        self.plugins.add_name(plugin.name());
+        sprintln!("Added the {} plugin.", plugin.name());
+        self
+    }
+
+    /// Everything registered by the plugins added so far.
+    pub fn plugins(&self) -> &Plugins {
+        &self.plugins
+    }
+
+    /// Registers a component within every world, including the instances.
+    pub fn add_component<T: Component>(&mut self) -> &mut Self {
+        self.plugins.add_component::<T>();
+        self
+    }
+
+    /// Runs a system every tick within every world, after the built-in systems.
+    pub fn add_system(
+        &mut self,
+        system: impl FnMut(&mut PluginContext) + Send + 'static,
+    ) -> &mut Self {
+        self.plugins.add_system(Box::new(system));
+        self
+    }
+
+    /// Handles an action sent by clients before the built-in handling, which is skipped if the
+    /// handler returns true.
+    pub fn add_handler(
+        &mut self,
+        action: Action,
+        handler: impl FnMut(&mut PluginContext, Uuid, Payload) -> bool + Send + 'static,
+    ) -> &mut Self {
+        self.plugins.add_handler(action, Box::new(handler));
+        self
+    }
+
+    /// Runs a chat command, such as `dance` for `/dance`. Built-in commands take precedence.
+    pub fn add_command(
+        &mut self,
+        name: &str,
+        command: impl FnMut(&mut PluginContext, Uuid, &str) + Send + 'static,
+    ) -> &mut Self {
+        self.plugins.add_command(name, Box::new(command));
+        self
+    }
+
+    /// Runs a timer every interval, in seconds, within the overworld.
+    pub fn add_timer(
+        &mut self,
+        interval: f32,
+        timer: impl FnMut(&mut PluginContext) + Send + 'static,
+    ) -> &mut Self {
+        self.plugins.add_timer(interval, Box::new(timer));
+        self
+    }
+
+    /// Reloads the configuration whenever the process receives SIGHUP.
+    #[cfg(unix)]
+    fn reload_on_hangup(commands: std_mpsc::Sender<ServerCommand>) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(why) => {
+                sprintln!("Unable to listen for SIGHUP: {}", why);
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                if commands.send(ServerCommand::Reload).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    fn reload_on_hangup(_commands: std_mpsc::Sender<ServerCommand>) {}
+
+    /// Binds the socket and starts the server threads.
+    pub fn spawn(self) -> Result<ServerHandle, Box<dyn Error>> {
+        let config = ServerConfig::load(&self.config);
+        if let Some(backup) = &self.restore {
+            backup::restore(backup, config.storage.database.as_ref())?;
+        }
+
+        let socket = std::net::UdpSocket::bind(&self.address)?;
+        socket.set_nonblocking(true)?;
+        let address = socket.local_addr()?;
+
+        let (tx, rx) = mpsc::channel::<PacketConfiguration>(Self::OUTGOING_CAPACITY);
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (command_tx, command_rx) = std_mpsc::channel::<ServerCommand>();
+
+        event_log::init(config.storage.logs.as_ref());
+        if self.console {
+            Console::start(command_tx.clone());
+        }
+        let packet_cache = PacketCacheAsync::new();
+        let websocket = match config.network.websocket {
+            true => {
+                let listener = std::net::TcpListener::bind(address)?;
+                listener.set_nonblocking(true)?;
+                Some((listener, config.network.clone()))
+            }
+            false => None,
+        };
+        let anticheat = AntiCheat::new(config.anticheat.clone());
+        let (network_tx, network_rx) = watch::channel(config.network.clone());
+
+        let cache = packet_cache.clone();
+        let socket_anticheat = anticheat.clone();
+        let plugin_actions = self.plugins.actions();
+        let socket = std::thread::spawn(move || {
+            let rt = Runtime::new().expect("Failed to create a runtime");
+            rt.block_on(async move {
+                let socket = match UdpSocket::from_std(socket) {
+                    Ok(socket) => socket,
+                    Err(why) => {
+                        sprintln!("ERROR starting socket server {}", why);
+                        return;
+                    }
+                };
+
+                // Relays browsers for as long as the socket server runs.
+                if let Some((listener, network)) = websocket {
+                    match TcpListener::from_std(listener) {
+                        Ok(listener) => {
+                            sprintln!("Accepting WebSockets on {}.", address);
+                            tokio::spawn(websocket::relay(listener, address, network));
+                        }
+                        Err(why) => sprintln!("ERROR accepting WebSockets {}", why),
+                    }
+                }
+
+                if let Err(why) = SocketServer::start(
+                    socket,
+                    rx,
+                    cache,
+                    socket_anticheat,
+                    network_rx,
+                    shutdown_rx,
+                    plugin_actions,
+                )
+                .await
+                {
+                    sprintln!("ERROR stopping socket server {}", why);
+                }
+            });
+        });
+
+        let sender = tx.clone();
+        let content = Content::new(&config.content);
+        let regions = match &self.regions {
+            Some(directory) => RegionManager::from_directory(&content, directory),
+            None => RegionManager::from_content(&content),
+        };
+        let plugins = self.plugins;
+        let reload = command_tx.clone();
+        let gamestate = std::thread::spawn(move || {
+            let rt = Runtime::new().expect("Failed to create a runtime");
+            rt.block_on(async {
+                Self::reload_on_hangup(reload);
+                let mut gamestate = Gamestate::new(
+                    tx,
+                    packet_cache,
+                    anticheat,
+                    content,
+                    regions,
+                    plugins,
+                    config,
+                    network_tx,
+                    command_rx,
+                );
+                gamestate.start().await;
+            });
+        });
+
+        Ok(ServerHandle {
+            address,
+            sender,
+            shutdown: shutdown_tx,
+            commands: command_tx,
+            threads: vec![gamestate, socket],
+        })
+    }
+}
+
+/// Controls a running server.
+pub struct ServerHandle {
+    address: SocketAddr,
+    sender: mpsc::Sender<PacketConfiguration>,
+    shutdown: watch::Sender<bool>,
+    commands: std_mpsc::Sender<ServerCommand>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Sends an announcement to every connected client.
+    pub fn broadcast(&self, message: &str) -> Result<(), Box<dyn Error>> {
+        let packet = Packet::new(
+            Action::Notification,
+            Uuid::nil(),
+            Payload::Notification(NotificationPayload::new(
+                NotificationKind::Announcement,
+                Text::literal(message),
+            )),
+        );
+
+        self.sender.try_send(PacketConfiguration::Broadcast(
+            packet,
+            BroadcastScope::Global,
+        ))?;
+        Ok(())
+    }
+
+    /// Obtains all players currently in the world.
+    pub fn players(&self) -> Result<Vec<PlayerInfo>, Box<dyn Error>> {
+        let (tx, rx) = std_mpsc::channel();
+        self.commands.send(ServerCommand::Players(tx))?;
+        Ok(rx.recv_timeout(Self::QUERY_TIMEOUT)?)
+    }
+
+    /// Counts the entities of each kind within the overworld and every instance.
+    pub fn census(&self) -> Result<Vec<WorldCensus>, Box<dyn Error>> {
+        let (tx, rx) = std_mpsc::channel();
+        self.commands.send(ServerCommand::Census(tx))?;
+        Ok(rx.recv_timeout(Self::QUERY_TIMEOUT)?)
+    }
+
+    /// Obtains how long ticks are taking and the time spent shedding load.
+    pub fn load(&self) -> Result<ShedStats, Box<dyn Error>> {
+        let (tx, rx) = std_mpsc::channel();
+        self.commands.send(ServerCommand::Load(tx))?;
+        Ok(rx.recv_timeout(Self::QUERY_TIMEOUT)?)
+    }
+
+    /// Describes the entity of the player with the uuid, wherever they are.
+    pub fn find(&self, uuid: Uuid) -> Result<Option<EntityReport>, Box<dyn Error>> {
+        let (tx, rx) = std_mpsc::channel();
+        self.commands.send(ServerCommand::Find(uuid, tx))?;
+        Ok(rx.recv_timeout(Self::QUERY_TIMEOUT)?)
+    }
+
+    /// Describes an entity within the overworld, or an instance if given.
+    pub fn inspect(
+        &self,
+        instance: Option<InstanceId>,
+        entity: Entity,
+    ) -> Result<Option<EntityReport>, Box<dyn Error>> {
+        let (tx, rx) = std_mpsc::channel();
+        self.commands
+            .send(ServerCommand::Inspect(instance, entity, tx))?;
+        Ok(rx.recv_timeout(Self::QUERY_TIMEOUT)?)
+    }
+
+    /// Stops the server, saving the world and waiting for it to exit.
+    pub fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        self.wait();
+    }
+
+    /// Blocks until the server exits.
+    pub fn wait(self) {
+        for thread in self.threads {
+            if thread.join().is_err() {
+                sprintln!("ERROR while joining the thread.");
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::chunk::ChunkCoord;
+use uo2d_proto::components::{ItemStack, Team};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::timer::TimerManager;
+use uuid::Uuid;
+
+use crate::delta::DeltaEncoder;
+use crate::replication::Replicator;
+use crate::spatial_hash::SpatialHash;
+
+/// Unique identifier for an instance.
+pub type InstanceId = u32;
+
+/// Players that share an instance, teammates enter together while everyone else enters alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Party {
+    Team(Team),
+    Solo(Uuid),
+}
+
+/// Identifies an instance and the region it is a copy of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstanceInfo {
+    pub id: InstanceId,
+    pub region: u8,
+    pub party: Party,
+}
+
+/// Simulation of an instanced region, swapped into the gamestate while it is being run.
+pub struct Instance {
+    pub info: InstanceInfo,
+    pub world: World,
+    pub spatial: SpatialHash,
+    pub deltas: DeltaEncoder,
+    pub replicator: Replicator,
+    pub timers: TimerManager,
+    pub players: HashMap<Uuid, Entity>,
+    pub visible: HashMap<Uuid, HashMap<Entity, ItemStack>>,
+    pub chunks: HashMap<Uuid, HashSet<ChunkCoord>>,
+    pub obstacles: HashMap<Uuid, HashSet<Entity>>,
+}
+
+impl Instance {
+    /// Creates an empty instance, its timers kept in step with the ones provided.
+    pub fn new(
+        info: InstanceInfo,
+        world: World,
+        spatial: SpatialHash,
+        timers: &TimerManager,
+    ) -> Self {
+        Self {
+            info,
+            world,
+            spatial,
+            deltas: DeltaEncoder::new(),
+            replicator: Replicator::default(),
+            timers: TimerManager::synced(timers),
+            players: HashMap::new(),
+            visible: HashMap::new(),
+            chunks: HashMap::new(),
+            obstacles: HashMap::new(),
+        }
+    }
+}
+
+/// Tracks the running instances and the players within them.
+#[derive(Default)]
+pub struct InstanceManager {
+    instances: HashMap<InstanceId, Instance>,
+    members: HashMap<Uuid, InstanceId>,
+    next_id: InstanceId,
+}
+
+impl InstanceManager {
+    /// Instance a player is in, None while in the overworld.
+    pub fn of(&self, uuid: &Uuid) -> Option<InstanceId> {
+        self.members.get(uuid).copied()
+    }
+
+    /// Ids of every running instance.
+    pub fn ids(&self) -> Vec<InstanceId> {
+        let mut ids: Vec<InstanceId> = self.instances.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Finds the instance of a region already opened for a party.
+    pub fn find(&self, region: u8, party: Party) -> Option<InstanceId> {
+        self.instances
+            .values()
+            .find(|instance| instance.info.region == region && instance.info.party == party)
+            .map(|instance| instance.info.id)
+    }
+
+    /// Reserves the id for a new instance.
+    pub fn next_id(&mut self) -> InstanceId {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Obtains a running instance.
+    pub fn get_mut(&mut self, id: InstanceId) -> Option<&mut Instance> {
+        self.instances.get_mut(&id)
+    }
+
+    /// Removes an instance to run it, it is returned with `restore`.
+    pub fn take(&mut self, id: InstanceId) -> Option<Instance> {
+        self.instances.remove(&id)
+    }
+
+    /// Returns an instance, updating which players are within it.
+    pub fn restore(&mut self, instance: Instance) {
+        let id = instance.info.id;
+        self.members.retain(|_, member| *member != id);
+        for uuid in instance.players.keys() {
+            self.members.insert(*uuid, id);
+        }
+
+        self.instances.insert(id, instance);
+    }
+
+    /// Closes the instances that no players are left in.
+    pub fn teardown(&mut self) -> Vec<InstanceInfo> {
+        let empty: Vec<InstanceId> = self
+            .instances
+            .values()
+            .filter(|instance| instance.players.is_empty())
+            .map(|instance| instance.info.id)
+            .collect();
+
+        empty
+            .into_iter()
+            .filter_map(|id| self.instances.remove(&id))
+            .map(|instance| instance.info)
+            .collect()
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+
+use uo2d_proto::components::{
+    Acceleration, Dormant, Health, Npc, Player, Position, Spawned, Spawner, Threat, Vec2, Vec3,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{NotificationKind, NotificationPayload};
+use uo2d_proto::sprintln;
+use uo2d_proto::timer::TimerManager;
+use uuid::Uuid;
+
+use crate::config::InvasionConfig;
+use crate::net::{Event, Net};
+use crate::npcs::NpcManager;
+use crate::region::{Region, SpawnerSpawn};
+use crate::spatial_hash::SpatialHash;
+use crate::systems::{inventory, npcs, spawners};
+
+/// Distance within the edge of the region invaders spawn at.
+const INSET: f64 = 64.;
+/// Distance from the target invaders stop marching within.
+const ARRIVED: f64 = 48.;
+
+/// Invasion started by a world event, sending its waves one after another.
+pub struct Invasion {
+    /// World event the invasion belongs to.
+    pub event: usize,
+    config: InvasionConfig,
+    outline: Vec<Vec3>,
+    target: Vec3,
+    /// Spawner the invaders belong to, its home being the target.
+    spawner: Entity,
+    /// Waves that have arrived so far.
+    wave: u32,
+    /// Tick the next wave arrives on, None while a wave is being fought.
+    next: Option<u64>,
+    /// Players who drew the attention of an invader.
+    participants: HashSet<Uuid>,
+}
+
+impl Invasion {
+    /// Readies an invasion of the region, its first wave arriving on the next update.
+    pub fn start(
+        world: &mut World,
+        spatial: &mut SpatialHash,
+        npcs: &NpcManager,
+        region: &Region,
+        config: InvasionConfig,
+        event: usize,
+    ) -> Self {
+        let target = config.target.unwrap_or(region.spawn);
+        let outline = region.vertices().to_vec();
+
+        // Invaders chasing players are only sent back once pulled beyond the region.
+        let leash = outline
+            .iter()
+            .map(|corner| corner.distance_2d(&target))
+            .fold(INSET, f64::max);
+        let spawner = spawners::place(
+            world,
+            spatial,
+            npcs,
+            SpawnerSpawn {
+                npc: config.npcs.first().cloned().unwrap_or_default(),
+                position: target,
+                max_alive: 0,
+                respawn: 0.,
+                leash,
+            },
+        );
+
+        Self {
+            event,
+            config,
+            outline,
+            target,
+            spawner,
+            wave: 0,
+            next: Some(0),
+            participants: HashSet::new(),
+        }
+    }
+
+    /// Name of the region invaded.
+    pub fn region(&self) -> &str {
+        &self.config.region
+    }
+
+    /// Marches the invaders on the target, sending the next wave once one is defeated.
+    /// Returns true once the last wave has been defeated.
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        spatial: &mut SpatialHash,
+        npcs: &NpcManager,
+        net: &mut Net,
+        tick: u64,
+        online: u32,
+    ) -> bool {
+        // Those that just died still count towards who took part.
+        let invaders: Vec<Entity> = world
+            .query1::<Spawned>()
+            .into_iter()
+            .filter(|(_, spawned)| spawned.0 == self.spawner)
+            .map(|(entity, _)| entity)
+            .collect();
+        self.enlist(world, &invaders);
+
+        let alive: Vec<Entity> = invaders
+            .into_iter()
+            .filter(|entity| {
+                !world
+                    .get_component::<Health>(entity)
+                    .is_some_and(|health| health.is_dead())
+            })
+            .collect();
+        self.march(world, &alive);
+
+        if let Some(next) = self.next {
+            if tick >= next {
+                self.next = None;
+                self.send_wave(world, spatial, npcs, net, online);
+            }
+            return false;
+        } else if !alive.is_empty() {
+            return false;
+        }
+
+        if self.wave >= self.config.waves {
+            return true;
+        }
+
+        announce(
+            net,
+            Text::new("invasion.defeated")
+                .with("wave", self.wave)
+                .with("waves", self.config.waves),
+        );
+        let delay = self.config.delay.max(0.) * TimerManager::SERVER_TICKS_PER_SECOND;
+        self.next = Some(tick + delay.ceil() as u64);
+        false
+    }
+
+    /// Gives the rewards to every player who took part and is still in the world.
+    pub fn reward(&self, world: &mut World, items: &ItemManager, net: &mut Net) {
+        let players: Vec<(Entity, Uuid)> = world
+            .query1::<Player>()
+            .into_iter()
+            .filter(|(_, player)| self.participants.contains(player.uuid()))
+            .map(|(entity, player)| (entity, *player.uuid()))
+            .collect();
+
+        for (entity, uuid) in players.into_iter() {
+            for reward in self.config.rewards.iter() {
+                let name = match items.get(&reward.item) {
+                    Some(definition) => definition.text(),
+                    None => continue,
+                };
+                let given = inventory::give(world, items, &entity, reward.item, reward.count);
+                if given == 0 {
+                    continue;
+                }
+
+                net.send_to(
+                    uuid,
+                    Event::Notification(NotificationPayload::new(
+                        NotificationKind::Announcement,
+                        Text::new("invasion.rewarded")
+                            .with("count", given)
+                            .with_text("item", name),
+                    )),
+                );
+            }
+            inventory::changed(world, net, &entity);
+        }
+    }
+
+    /// Removes the spawner, its invaders are despawned once they find it gone.
+    pub fn end(self, world: &mut World) {
+        world.despawn(&self.spawner);
+    }
+
+    /// Remembers the players the invaders hold threat towards.
+    fn enlist(&mut self, world: &World, invaders: &[Entity]) {
+        for invader in invaders.iter() {
+            let Some(threat) = world.get_component::<Threat>(invader) else {
+                continue;
+            };
+            for entity in threat.table.keys() {
+                if let Some(player) = world.get_component::<Player>(entity) {
+                    self.participants.insert(*player.uuid());
+                }
+            }
+        }
+    }
+
+    /// Moves the invaders towards the target, unless they are fighting or sleeping.
+    fn march(&self, world: &mut World, invaders: &[Entity]) {
+        let mut inputs = vec![];
+        for invader in invaders.iter() {
+            let engaged = world
+                .get_component::<Threat>(invader)
+                .is_some_and(|threat| threat.is_engaged());
+            if engaged || world.get_component::<Dormant>(invader).is_some() {
+                continue;
+            }
+
+            let (Some(position), Some(npc)) = (
+                world.get_component::<Position>(invader),
+                world.get_component::<Npc>(invader),
+            ) else {
+                continue;
+            };
+            let center = position.bounds().center_2d();
+            let offset = Vec2::new(self.target.x() - center.x(), self.target.y() - center.y());
+            if npc.chase > 0. && offset.distance(&Vec2::ORIGIN) > ARRIVED {
+                inputs.push((*invader, offset.scaled(npc.chase)));
+            }
+        }
+
+        for (invader, acceleration) in inputs.into_iter() {
+            world.upsert_component(invader, Acceleration(acceleration));
+        }
+    }
+
+    /// Spawns the next wave around the edge of the region, sized by the players online.
+    fn send_wave(
+        &mut self,
+        world: &mut World,
+        spatial: &mut SpatialHash,
+        npcs: &NpcManager,
+        net: &mut Net,
+        online: u32,
+    ) {
+        self.wave += 1;
+        let size = self.config.wave_size(online);
+        let points = spawn_points(&self.outline, self.target, size);
+        for (point, kind) in points.into_iter().zip(self.config.npcs.iter().cycle()) {
+            let definition = match npcs.get(kind) {
+                Some(definition) => definition,
+                None => {
+                    sprintln!("Unable to invade with '{}', the NPC is not defined.", kind);
+                    continue;
+                }
+            };
+
+            let invader = npcs::spawn(world, spatial, definition, point, self.spawner);
+            // Invaders march on the target rather than wandering.
+            if let Some(npc) = world.get_component_mut::<Npc>(&invader) {
+                npc.wander = 0.;
+            }
+            if let Some(spawner) = world.get_component_mut::<Spawner>(&self.spawner) {
+                spawner.alive += 1;
+            }
+        }
+
+        announce(
+            net,
+            Text::new("invasion.wave")
+                .with("wave", self.wave)
+                .with("waves", self.config.waves)
+                .with_text("region", Region::text(&self.config.region)),
+        );
+    }
+}
+
+/// Points spread evenly along an outline, each pulled in towards the target.
+/// Every point is at the target if there is no outline.
+pub fn spawn_points(outline: &[Vec3], target: Vec3, count: u32) -> Vec<Vec3> {
+    let edges: Vec<(Vec3, Vec3)> = (0..outline.len())
+        .map(|i| (outline[i], outline[(i + 1) % outline.len()]))
+        .collect();
+    let perimeter: f64 = edges.iter().map(|(a, b)| a.distance_2d(b)).sum();
+
+    (0..count)
+        .map(|i| {
+            let mut along = perimeter * (i as f64 + 0.5) / count as f64;
+            let mut point = target;
+            for (a, b) in edges.iter() {
+                let length = a.distance_2d(b);
+                if along <= length && length > 0. {
+                    let t = along / length;
+                    point = Vec3::new(
+                        a.x() + (b.x() - a.x()) * t,
+                        a.y() + (b.y() - a.y()) * t,
+                        target.z(),
+                    );
+                    break;
+                }
+                along -= length;
+            }
+
+            let distance = point.distance_2d(&target);
+            let t = if distance > 0. {
+                INSET.min(distance) / distance
+            } else {
+                0.
+            };
+            Vec3::new(
+                point.x() + (target.x() - point.x()) * t,
+                point.y() + (target.y() - point.y()) * t,
+                target.z(),
+            )
+        })
+        .collect()
+}
+
+/// Announcement sent to every player.
+fn announce(net: &mut Net, message: Text) {
+    net.broadcast(Event::Notification(NotificationPayload::new(
+        NotificationKind::Announcement,
+        message,
+    )));
+}
@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::net::SocketAddr;
+
+pub use handle::{EntityReport, PlayerInfo, ServerBuilder, ServerHandle, WorldCensus};
+pub use plugin::{Plugin, PluginContext};
+pub use socket_server::SocketServer;
+use uo2d_proto::util::get_now;
+use uuid::Uuid;
+
+use self::content::Content;
+use self::region::RegionManager;
+
+pub mod accounts;
+mod announcements;
+mod anticheat;
+mod backup;
+mod bus;
+mod cache;
+pub mod config;
+mod console;
+pub mod content;
+pub mod delta;
+mod dialogue;
+pub mod event_log;
+pub mod fuzz;
+mod gamestate;
+mod handle;
+mod instance;
+pub mod invasion;
+pub mod load;
+pub mod loot;
+mod match_state;
+pub mod middleware;
+mod modes;
+pub mod net;
+pub mod npcs;
+pub mod outbox;
+mod packet_processor;
+mod plots;
+pub mod plugin;
+pub mod region;
+pub mod replication;
+mod resources;
+mod shards;
+pub mod soak;
+pub mod socket_server;
+pub mod spatial_hash;
+pub mod systems;
+pub mod telemetry;
+pub mod trades;
+mod websocket;
+pub mod world_events;
+
+/// Holds all of the relevant client information for send/recving packets.
+#[derive(Clone)]
+pub(crate) struct Client {
+    pub(crate) uuid: Uuid,
+    pub addr: SocketAddr,
+    ping_id: Uuid,
+    last_ping: u64,
+    /// Time the first packet was received from the client.
+    connected: u64,
+    /// Whether the client has completed joining the world.
+    joined: bool,
+}
+
+impl Client {
+    /// Create a new instance of the client to be tracked.
+    pub fn new(uuid: Uuid, addr: SocketAddr) -> Client {
+        Client {
+            uuid,
+            addr,
+            ping_id: Uuid::nil(),
+            last_ping: get_now(),
+            connected: get_now(),
+            joined: false,
+        }
+    }
+}
+
+pub struct Server {}
+
+impl Server {
+    /// Creates a builder for configuring and spawning a server.
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::default()
+    }
+
+    /// Loads the regions from a directory, describing each one that was found.
+    pub fn check_regions(directory: &str) -> Result<Vec<String>, String> {
+        let manager = RegionManager::from_directory(&Content::default(), directory);
+        let regions = manager.regions();
+        if regions.is_empty() {
+            return Err(format!("no regions found in {}", directory));
+        }
+
+        Ok(regions
+            .into_iter()
+            .map(|region| {
+                let bounds = region.bounding_box();
+                format!(
+                    "{}: {}x{} at ({}, {}), tile {}, friction {}, {} obstacles, {} spawners",
+                    region.name,
+                    bounds.width(),
+                    bounds.height(),
+                    bounds.x(),
+                    bounds.y(),
+                    region.tile,
+                    region.friction,
+                    region.obstacles.len(),
+                    region.spawners.len()
+                )
+            })
+            .collect())
+    }
+
+    /// Starts the server with the admin console, blocking until it shuts down.
+    pub fn start(address: &str) -> Result<(), Box<dyn Error>> {
+        Self::builder()
+            .address(address)
+            .console(true)
+            .spawn()?
+            .wait();
+        Ok(())
+    }
+}
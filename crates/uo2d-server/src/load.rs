@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use uo2d_proto::components::{Npc, Player, Position, Vec2};
+use uo2d_proto::ecs::{Entity, World};
+
+/// Work skipped to keep ticks within their budget, each level also shedding what those before it do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ShedLevel {
+    /// Everything runs every tick.
+    #[default]
+    Normal,
+    /// Non-essential systems such as the debug overlays are paused.
+    Paused,
+    /// NPCs away from every player only think every few ticks.
+    Distant,
+    /// Movement is sent to players far from an entity even less often.
+    Replication,
+}
+
+impl ShedLevel {
+    /// Every level, from the least shed to the most.
+    pub const ALL: [ShedLevel; 4] = [
+        ShedLevel::Normal,
+        ShedLevel::Paused,
+        ShedLevel::Distant,
+        ShedLevel::Replication,
+    ];
+
+    /// Name shown to operators.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShedLevel::Normal => "normal",
+            ShedLevel::Paused => "paused",
+            ShedLevel::Distant => "distant",
+            ShedLevel::Replication => "replication",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    fn higher(&self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    fn lower(&self) -> Option<Self> {
+        Self::ALL.get(self.index().checked_sub(1)?).copied()
+    }
+
+    /// Whether non-essential systems are paused.
+    pub fn pauses(&self) -> bool {
+        *self >= ShedLevel::Paused
+    }
+
+    /// Multiplies the ticks between movement sent to far players.
+    pub fn replication_scale(&self) -> u64 {
+        if *self >= ShedLevel::Replication {
+            Throttle::STRIDE
+        } else {
+            1
+        }
+    }
+}
+
+/// How long ticks are taking and the time spent shedding load.
+#[derive(Debug, Clone, Default)]
+pub struct ShedStats {
+    pub level: ShedLevel,
+    /// Moving average of the time spent on each tick.
+    pub average: Duration,
+    pub budget: Duration,
+    /// Ticks spent at each level.
+    pub ticks: Vec<(ShedLevel, u64)>,
+    /// Times the level was raised because ticks ran over their budget.
+    pub raised: u64,
+    /// Times the level was lowered once the load recovered.
+    pub lowered: u64,
+}
+
+/// Watches how long ticks take, shedding more work while they run over the budget and
+/// restoring it once there is plenty of time to spare.
+pub struct LoadShedder {
+    budget: Duration,
+    level: ShedLevel,
+    /// Moving average of the seconds spent on each tick.
+    average: f64,
+    /// Ticks since the level last changed.
+    since: u64,
+    ticks: [u64; ShedLevel::ALL.len()],
+    raised: u64,
+    lowered: u64,
+}
+
+impl LoadShedder {
+    /// Weight of the newest tick within the average.
+    const SAMPLE_WEIGHT: f64 = 0.1;
+    /// Ticks to wait after a change before judging the new level.
+    const SETTLE_TICKS: u64 = 40;
+    /// Ticks the spare time has to last before restoring work, longer to avoid flapping.
+    const RECOVER_TICKS: u64 = 200;
+    /// Portion of the budget that has to be left unused before restoring work.
+    const RECOVER_BELOW: f64 = 0.5;
+
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            level: ShedLevel::Normal,
+            average: 0.,
+            since: 0,
+            ticks: [0; ShedLevel::ALL.len()],
+            raised: 0,
+            lowered: 0,
+        }
+    }
+
+    /// Work currently being shed.
+    pub fn level(&self) -> ShedLevel {
+        self.level
+    }
+
+    /// Records the time spent on a tick, obtaining the new level if it changed.
+    pub fn record(&mut self, tick: Duration) -> Option<ShedLevel> {
+        self.ticks[self.level.index()] += 1;
+        self.average += (tick.as_secs_f64() - self.average) * Self::SAMPLE_WEIGHT;
+        self.since += 1;
+        if self.since < Self::SETTLE_TICKS {
+            return None;
+        }
+
+        let budget = self.budget.as_secs_f64();
+        let level = if self.average > budget {
+            let level = self.level.higher()?;
+            self.raised += 1;
+            level
+        } else if self.average < budget * Self::RECOVER_BELOW && self.since >= Self::RECOVER_TICKS {
+            let level = self.level.lower()?;
+            self.lowered += 1;
+            level
+        } else {
+            return None;
+        };
+
+        self.level = level;
+        self.since = 0;
+        Some(level)
+    }
+
+    /// Snapshot of the load for operators.
+    pub fn stats(&self) -> ShedStats {
+        ShedStats {
+            level: self.level,
+            average: Duration::from_secs_f64(self.average),
+            budget: self.budget,
+            ticks: ShedLevel::ALL
+                .iter()
+                .map(|level| (*level, self.ticks[level.index()]))
+                .collect(),
+            raised: self.raised,
+            lowered: self.lowered,
+        }
+    }
+}
+
+/// Entities that the systems update less often this tick while shedding load.
+#[derive(Debug, Default)]
+pub struct Throttle {
+    tick: u64,
+    /// NPCs further than the range from every player.
+    distant: HashSet<Entity>,
+}
+
+impl Throttle {
+    /// Ticks between the updates of a throttled entity.
+    pub const STRIDE: u64 = 4;
+
+    /// Finds what to throttle at the level, NPCs beyond the range of every player being distant.
+    pub fn new(level: ShedLevel, world: &World, tick: u64, range: f64) -> Self {
+        let mut throttle = Self {
+            tick,
+            ..Default::default()
+        };
+        if level < ShedLevel::Distant {
+            return throttle;
+        }
+
+        let players: Vec<Vec2> = world
+            .query2::<Player, Position>()
+            .into_iter()
+            .map(|(_, _, position)| position.bounds().center_2d())
+            .collect();
+        throttle.distant = world
+            .query2::<Npc, Position>()
+            .into_iter()
+            .filter(|(_, _, position)| {
+                let center = position.bounds().center_2d();
+                players
+                    .iter()
+                    .all(|player| player.distance(&center) > range)
+            })
+            .map(|(entity, _, _)| entity)
+            .collect();
+        throttle
+    }
+
+    /// Whether the entity has its turn this tick, spreading throttled entities across the stride.
+    fn due(&self, entity: &Entity) -> bool {
+        (self.tick + entity.id()).is_multiple_of(Self::STRIDE)
+    }
+
+    /// Whether the AI of an NPC sits out this tick.
+    pub fn skips(&self, entity: &Entity) -> bool {
+        self.distant.contains(entity) && !self.due(entity)
+    }
+
+    /// Seconds an update of the entity covers, including the ticks it sat out.
+    pub fn elapsed(&self, entity: &Entity, elapsed: f64) -> f64 {
+        if self.distant.contains(entity) {
+            elapsed * Self::STRIDE as f64
+        } else {
+            elapsed
+        }
+    }
+}
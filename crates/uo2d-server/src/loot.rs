@@ -0,0 +1,251 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use uo2d_proto::components::{ItemId, ItemStack};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::sprintln;
+
+use crate::content::Content;
+
+/// Outcome of a roll on a loot table, chosen by its weight against the others.
+/// Entries with neither an item nor a table drop nothing.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LootEntry {
+    #[serde(default)]
+    pub item: Option<ItemId>,
+    /// Table rolled in its place.
+    #[serde(default)]
+    pub table: Option<String>,
+    pub weight: u32,
+    /// Least and most of the item dropped.
+    #[serde(default = "LootEntry::default_count")]
+    pub count: [u16; 2],
+    /// Lowest and highest level the entry can be rolled at, any level if unset.
+    #[serde(default)]
+    pub levels: Option<[u32; 2]>,
+}
+
+impl LootEntry {
+    /// Entries that do not specify their count drop one at a time.
+    fn default_count() -> [u16; 2] {
+        [1, 1]
+    }
+
+    /// Checks if the entry can be rolled at a level.
+    fn allows(&self, level: u32) -> bool {
+        self.levels
+            .is_none_or(|[lowest, highest]| lowest <= level && level <= highest)
+    }
+}
+
+/// Entries rolled on a number of times, loaded from the loot data.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LootTable {
+    pub name: String,
+    #[serde(default = "LootTable::default_rolls")]
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    /// Tables that do not specify their rolls are rolled on once.
+    fn default_rolls() -> u32 {
+        1
+    }
+}
+
+/// Manages the loot tables rolled when NPCs die, obstacles break, and resources are gathered.
+#[derive(Default)]
+pub struct LootManager {
+    tables: HashMap<String, LootTable>,
+}
+
+impl LootManager {
+    /// File of the loot tables, within the content root.
+    pub const PATH: &'static str = "loot.yaml";
+
+    /// Loads all loot tables at launch, none are used if any of them are invalid.
+    pub fn new(content: &Content, items: &ItemManager) -> Self {
+        let mut loot = Self::default();
+        loot.reload(content, items);
+        loot
+    }
+
+    /// Re-reads the loot tables, keeping the current ones if any of the new ones are invalid.
+    /// Returns if they were replaced.
+    pub fn reload(&mut self, content: &Content, items: &ItemManager) -> bool {
+        let base = content.path(Self::PATH);
+        let mut layers = vec![];
+        for path in content.layers(&base) {
+            match content.read(&path) {
+                Ok(tables) => layers.push(tables),
+                Err(why) => {
+                    sprintln!("Error while loading {}: {}", path, why);
+                    return false;
+                }
+            }
+        }
+
+        match Self::parse_layers(&layers, items) {
+            Ok(loot) => {
+                *self = loot;
+                true
+            }
+            Err(errors) => {
+                for why in errors {
+                    sprintln!("Error while loading {}: {}", base, why);
+                }
+                false
+            }
+        }
+    }
+
+    /// Reads the loot tables from YAML, listing every problem found with them.
+    pub fn parse(content: &str, items: &ItemManager) -> Result<Self, Vec<String>> {
+        Self::parse_layers(&[content], items)
+    }
+
+    /// Reads the loot tables from several YAML files, tables within the later ones replacing
+    /// any of the same name. Problems are only found once they are all combined.
+    pub fn parse_layers(
+        layers: &[impl AsRef<str>],
+        items: &ItemManager,
+    ) -> Result<Self, Vec<String>> {
+        let mut loot = Self::default();
+        let mut errors = vec![];
+        for layer in layers {
+            let tables: Vec<LootTable> =
+                serde_yaml::from_str(layer.as_ref()).map_err(|why| vec![why.to_string()])?;
+
+            let mut names = HashSet::new();
+            for table in tables {
+                if !names.insert(table.name.clone()) {
+                    errors.push(format!("table '{}' is defined twice", table.name));
+                }
+                loot.tables.insert(table.name.clone(), table);
+            }
+        }
+
+        errors.extend(loot.validate(items));
+        match errors.is_empty() {
+            true => Ok(loot),
+            false => Err(errors),
+        }
+    }
+
+    /// Problems with the tables, such as missing items or tables that contain themselves.
+    fn validate(&self, items: &ItemManager) -> Vec<String> {
+        let mut errors = vec![];
+        for table in self.tables.values() {
+            let name = &table.name;
+            if table.entries.iter().all(|entry| entry.weight == 0) {
+                errors.push(format!("table '{}' has no weighted entries", name));
+            }
+
+            for entry in table.entries.iter() {
+                match (entry.item, &entry.table) {
+                    (Some(_), Some(_)) => errors.push(format!(
+                        "table '{}' has an entry with both an item and a table",
+                        name
+                    )),
+                    (Some(item), None) if items.get(&item).is_none() => {
+                        errors.push(format!("table '{}' drops unknown item {}", name, item))
+                    }
+                    (None, Some(nested)) if !self.tables.contains_key(nested) => {
+                        errors.push(format!("table '{}' rolls unknown table '{}'", name, nested))
+                    }
+                    _ => (),
+                }
+
+                let [least, most] = entry.count;
+                if least == 0 || least > most {
+                    errors.push(format!("table '{}' has an invalid count", name));
+                }
+                if entry
+                    .levels
+                    .is_some_and(|[lowest, highest]| lowest > highest)
+                {
+                    errors.push(format!("table '{}' has an invalid level range", name));
+                }
+            }
+
+            if self.contains(name, name, &mut HashSet::new()) {
+                errors.push(format!("table '{}' rolls itself", name));
+            }
+        }
+
+        errors.sort();
+        errors
+    }
+
+    /// Checks if a table rolls another, directly or through the tables it rolls.
+    fn contains(&self, table: &str, other: &str, seen: &mut HashSet<String>) -> bool {
+        let Some(table) = self.tables.get(table) else {
+            return false;
+        };
+
+        table
+            .entries
+            .iter()
+            .filter_map(|entry| entry.table.as_ref())
+            .any(|nested| {
+                nested == other
+                    || (seen.insert(nested.clone()) && self.contains(nested, other, seen))
+            })
+    }
+
+    /// Rolls on a table at a level, starting from the seed. Stacks of the same item are combined.
+    pub fn roll(&self, table: &str, level: u32, seed: u64) -> Vec<ItemStack> {
+        let mut dropped: Vec<ItemStack> = vec![];
+        let mut seed = seed | 1;
+        self.roll_into(table, level, &mut seed, &mut dropped);
+        dropped
+    }
+
+    /// Rolls on a table, adding what it drops. Validation keeps tables from rolling themselves.
+    fn roll_into(&self, table: &str, level: u32, seed: &mut u64, dropped: &mut Vec<ItemStack>) {
+        let Some(table) = self.tables.get(table) else {
+            return;
+        };
+
+        let allowed: Vec<&LootEntry> = table
+            .entries
+            .iter()
+            .filter(|entry| entry.weight > 0 && entry.allows(level))
+            .collect();
+        let total: u64 = allowed.iter().map(|entry| entry.weight as u64).sum();
+        if total == 0 {
+            return;
+        }
+
+        for _ in 0..table.rolls {
+            let mut roll = next(seed) % total;
+            let entry = allowed.iter().find(|entry| {
+                let weight = entry.weight as u64;
+                let found = roll < weight;
+                roll = roll.saturating_sub(weight);
+                found
+            });
+
+            match entry.map(|entry| (entry.item, &entry.table, entry.count)) {
+                Some((Some(item), _, [least, most])) => {
+                    let count = least + (next(seed) % (most - least + 1) as u64) as u16;
+                    match dropped.iter_mut().find(|stack| stack.item == item) {
+                        Some(stack) => stack.count = stack.count.saturating_add(count),
+                        None => dropped.push(ItemStack::new(item, count)),
+                    }
+                }
+                Some((None, Some(nested), _)) => self.roll_into(nested, level, seed, dropped),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Advances the seed, xorshift is plenty for drops.
+fn next(seed: &mut u64) -> u64 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 7;
+    *seed ^= *seed << 17;
+    *seed
+}
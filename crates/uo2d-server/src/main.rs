@@ -0,0 +1,10 @@
+use std::error::Error;
+
+use uo2d_server::Server;
+
+const ADDRESS: &str = "127.0.0.1:31013";
+
+/// Runs only the server, without requiring any of the client dependencies.
+fn main() -> Result<(), Box<dyn Error>> {
+    Server::start(ADDRESS)
+}
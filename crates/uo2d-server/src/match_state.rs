@@ -0,0 +1,65 @@
+use uo2d_proto::packet::payloads::{MatchPayload, MatchPhase};
+use uo2d_proto::timer::{TimerData, TimerManager};
+
+use crate::config::MatchConfig;
+
+/// Moves a match through the lobby, countdown, active round, and results.
+pub struct MatchState {
+    phase: MatchPhase,
+    /// Tick the current phase ends on, unused in the lobby.
+    ends: u64,
+    config: MatchConfig,
+}
+
+impl MatchState {
+    /// Creates a match waiting in the lobby.
+    pub fn new(config: MatchConfig) -> Self {
+        Self {
+            phase: MatchPhase::Lobby,
+            ends: 0,
+            config,
+        }
+    }
+
+    /// Current phase of the match.
+    pub fn phase(&self) -> MatchPhase {
+        self.phase
+    }
+
+    /// Checks if players are held in place.
+    pub fn is_frozen(&self) -> bool {
+        self.phase == MatchPhase::Countdown
+    }
+
+    /// Checks if the lobby has enough players to begin.
+    pub fn is_ready(&self, players: usize) -> bool {
+        self.phase == MatchPhase::Lobby && players >= self.config.min_players.max(1)
+    }
+
+    /// Moves to the next phase, scheduling the timer for when it ends.
+    pub fn advance(&mut self, timers: &mut TimerManager) -> MatchPhase {
+        let (phase, length) = match self.phase {
+            MatchPhase::Lobby => (MatchPhase::Countdown, self.config.countdown),
+            MatchPhase::Countdown => (MatchPhase::Active, self.config.round_duration),
+            MatchPhase::Active => (MatchPhase::Ended, self.config.intermission),
+            MatchPhase::Ended => (MatchPhase::Lobby, 0.),
+        };
+
+        self.phase = phase;
+        self.ends = 0;
+        if phase != MatchPhase::Lobby {
+            timers.add_timer_sec(length.max(0.), TimerData::MatchPhase, true);
+            let ticks = length.max(0.) as f64 / timers.server_tick_time().as_secs_f64();
+            self.ends = timers.tick() + ticks.round() as u64;
+        }
+
+        phase
+    }
+
+    /// Phase and time remaining to send to players.
+    pub fn payload(&self, timers: &TimerManager) -> MatchPayload {
+        let ticks = self.ends.saturating_sub(timers.tick());
+        let remaining = ticks as f64 * timers.server_tick_time().as_secs_f64();
+        MatchPayload::new(self.phase, remaining.ceil() as u64)
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use uo2d_proto::packet::{Action, Packet, Payload};
+use uuid::Uuid;
+
+/// Actions accepted from clients that have not joined the world yet.
+const UNAUTHENTICATED: [Action; 7] = [
+    Action::Ping,
+    Action::Echo,
+    Action::ClientJoin,
+    Action::Register,
+    Action::Login,
+    Action::ClientLeave,
+    Action::Handoff,
+];
+
+/// Time without packets before a client's rate limit is forgotten.
+const IDLE: Duration = Duration::from_secs(30);
+
+/// Packet from a client on its way to its handler.
+pub struct Inbound {
+    pub uuid: Uuid,
+    /// Whether the client has joined the world.
+    pub joined: bool,
+    pub received: Instant,
+    /// Unknown to this server if not set.
+    pub action: Option<Action>,
+    pub packet: Packet,
+    /// Decoded once by the validation, passed on to the handler.
+    pub payload: Option<Payload>,
+}
+
+impl Inbound {
+    pub fn new(uuid: Uuid, joined: bool, packet: Packet) -> Self {
+        Self {
+            uuid,
+            joined,
+            received: Instant::now(),
+            action: packet.action(),
+            packet,
+            payload: None,
+        }
+    }
+}
+
+/// Why a packet never reached its handler.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    /// Sent before the client joined the world.
+    Unauthenticated,
+    /// Sent faster than a client is allowed to.
+    RateLimited,
+    /// Could not be understood, with the evidence of why.
+    Malformed(String),
+}
+
+/// Check every inbound packet passes through before being handled.
+pub trait Middleware: Send + Sync {
+    /// Stops the packet if it is rejected, stages after it never seeing it.
+    fn check(&self, inbound: &mut Inbound) -> Result<(), Rejection>;
+}
+
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    fn check(&self, inbound: &mut Inbound) -> Result<(), Rejection> {
+        (**self).check(inbound)
+    }
+}
+
+/// Checks applied in order to inbound packets, the first rejection stopping the packet.
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Box<dyn Middleware>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks every packet a client sends passes once unwrapped, the rate limit having already
+    /// been applied to the datagram it arrived in.
+    pub fn server() -> Self {
+        Self::new().with(Authenticated).with(Validation)
+    }
+
+    /// Adds a check after those already added.
+    pub fn with(mut self, stage: impl Middleware + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Passes the packet through every check.
+    pub fn check(&self, inbound: &mut Inbound) -> Result<(), Rejection> {
+        self.stages
+            .iter()
+            .try_for_each(|stage| stage.check(inbound))
+    }
+}
+
+/// Drops all but the packets used to join from clients that have not joined the world.
+pub struct Authenticated;
+
+impl Middleware for Authenticated {
+    fn check(&self, inbound: &mut Inbound) -> Result<(), Rejection> {
+        let allowed = inbound
+            .action
+            .is_some_and(|action| UNAUTHENTICATED.contains(&action));
+        if inbound.joined || allowed {
+            Ok(())
+        } else {
+            Err(Rejection::Unauthenticated)
+        }
+    }
+}
+
+/// Packets a client has left to send, refilled over time.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Limits {
+    rate: f64,
+    burst: f64,
+    buckets: HashMap<Uuid, Bucket>,
+    pruned: Option<Instant>,
+}
+
+/// Limits how quickly each client can send packets, allowing short bursts.
+pub struct RateLimit {
+    limits: Mutex<Limits>,
+}
+
+impl RateLimit {
+    /// Allows packets a second on average, up to the burst at once. Unlimited if the rate is zero.
+    pub fn new(rate: f64, burst: f64) -> Self {
+        Self {
+            limits: Mutex::new(Limits {
+                rate,
+                burst,
+                buckets: HashMap::new(),
+                pruned: None,
+            }),
+        }
+    }
+
+    /// Applies new limits, the clients keeping what they have left.
+    pub fn reconfigure(&self, rate: f64, burst: f64) {
+        let mut limits = self.limits.lock().unwrap();
+        limits.rate = rate;
+        limits.burst = burst;
+    }
+
+    /// Number of clients being limited.
+    pub fn tracked(&self) -> usize {
+        self.limits.lock().unwrap().buckets.len()
+    }
+
+    /// Takes a packet from what the client has left, false if they have nothing left.
+    pub fn allow(&self, uuid: Uuid, now: Instant) -> bool {
+        let mut limits = self.limits.lock().unwrap();
+        if limits.rate <= 0. {
+            return true;
+        }
+
+        if limits
+            .pruned
+            .is_none_or(|pruned| now.saturating_duration_since(pruned) >= IDLE)
+        {
+            limits
+                .buckets
+                .retain(|_, bucket| now.saturating_duration_since(bucket.updated) < IDLE);
+            limits.pruned = Some(now);
+        }
+
+        let (rate, capacity) = (limits.rate, limits.burst.max(1.));
+        let bucket = limits.buckets.entry(uuid).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = bucket.updated.max(now);
+
+        if bucket.tokens < 1. {
+            return false;
+        }
+        bucket.tokens -= 1.;
+        true
+    }
+}
+
+impl Middleware for RateLimit {
+    fn check(&self, inbound: &mut Inbound) -> Result<(), Rejection> {
+        match self.allow(inbound.uuid, inbound.received) {
+            true => Ok(()),
+            false => Err(Rejection::RateLimited),
+        }
+    }
+}
+
+/// Rejects packets with an unknown action or a payload that cannot be decoded.
+pub struct Validation;
+
+impl Middleware for Validation {
+    fn check(&self, inbound: &mut Inbound) -> Result<(), Rejection> {
+        let Some(action) = inbound.action else {
+            return Err(Rejection::Malformed("unknown action".to_string()));
+        };
+
+        match inbound.packet.payload() {
+            Payload::Invalid => Err(Rejection::Malformed(format!(
+                "invalid payload for {:?}",
+                action
+            ))),
+            payload => {
+                inbound.payload = Some(payload);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::{Flag, Health, Player, Position, Team, Vec2};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{
+    FlagState, FlagStatus, HudPayload, NotificationKind, NotificationPayload,
+};
+
+use super::{GameMode, ModeContext};
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Size of a flag in the world.
+const FLAG_SIZE: f64 = 24.;
+
+/// Teams score by carrying the enemy flag back to their own while it is home.
+pub struct CaptureTheFlag {
+    flags: Vec<Entity>,
+    scores: HashMap<Team, u32>,
+    hud: Option<HudPayload>,
+}
+
+impl CaptureTheFlag {
+    pub fn new() -> Self {
+        Self {
+            flags: Vec::new(),
+            scores: HashMap::new(),
+            hud: None,
+        }
+    }
+
+    /// Entity carrying a flag, if they are still alive to carry it.
+    fn carrier(world: &World, flag: &Flag) -> Option<Entity> {
+        let carrier = flag.carrier?;
+        let alive = world
+            .get_component::<Health>(&carrier)
+            .is_some_and(|health| !health.is_dead());
+        (alive && world.get_component::<Position>(&carrier).is_some()).then_some(carrier)
+    }
+
+    /// Players standing on a flag that are still alive.
+    fn touching(world: &World, spatial: &SpatialHash, flag: &Position) -> Vec<(Entity, Team)> {
+        let bounds = flag.bounds();
+        spatial
+            .query(&bounds, None)
+            .into_iter()
+            .filter(|entity| world.get_component::<Player>(entity).is_some())
+            .filter(|entity| {
+                world
+                    .get_component::<Health>(entity)
+                    .is_some_and(|health| !health.is_dead())
+            })
+            .filter(|entity| {
+                world
+                    .get_component::<Position>(entity)
+                    .is_some_and(|pos| pos.bounds().intersects_2d(&bounds))
+            })
+            .filter_map(|entity| Some((entity, *world.get_component::<Team>(&entity)?)))
+            .collect()
+    }
+
+    /// Places a flag back where it rests.
+    fn return_home(world: &mut World, flag: &Entity) {
+        let home = match world.get_component_mut::<Flag>(flag) {
+            Some(state) => {
+                state.carrier = None;
+                state.home
+            }
+            None => return,
+        };
+
+        world.upsert_component(*flag, Position::new(home, Vec2::new(FLAG_SIZE, FLAG_SIZE)));
+    }
+
+    /// Checks if a player is already carrying a flag.
+    fn is_carrying(world: &World, player: &Entity) -> bool {
+        world
+            .query1::<Flag>()
+            .into_iter()
+            .any(|(_, flag)| flag.carrier == Some(*player))
+    }
+}
+
+/// Announcement sent to every player.
+fn announce(net: &mut Net, message: Text) {
+    net.broadcast(Event::Notification(NotificationPayload::new(
+        NotificationKind::Announcement,
+        message,
+    )));
+}
+
+impl GameMode for CaptureTheFlag {
+    fn name(&self) -> &'static str {
+        "ctf"
+    }
+
+    fn start(&mut self, ctx: &mut ModeContext) {
+        for spawn in ctx.regions.flags() {
+            let position = Position::new(spawn.position, Vec2::new(FLAG_SIZE, FLAG_SIZE));
+            let entity = ctx
+                .world
+                .spawn()
+                .with(position)
+                .with(Flag::new(spawn.team, spawn.position))
+                .build();
+            self.flags.push(entity);
+        }
+    }
+
+    fn round_start(&mut self, ctx: &mut ModeContext) {
+        self.scores.clear();
+        for flag in self.flags.iter() {
+            Self::return_home(ctx.world, flag);
+        }
+
+        announce(ctx.net, "ctf.begun".into());
+    }
+
+    fn update(&mut self, ctx: &mut ModeContext) {
+        for entity in self.flags.clone() {
+            let flag = match ctx.world.get_component::<Flag>(&entity) {
+                Some(flag) => *flag,
+                None => continue,
+            };
+
+            // Carried flags follow their carrier, dropping where they fell.
+            if flag.carrier.is_some() {
+                match Self::carrier(ctx.world, &flag)
+                    .and_then(|carrier| ctx.world.get_component::<Position>(&carrier))
+                {
+                    Some(carrier) => {
+                        let position = Position::new(carrier.loc, Vec2::new(FLAG_SIZE, FLAG_SIZE));
+                        ctx.world.upsert_component(entity, position);
+                    }
+                    None => {
+                        if let Some(state) = ctx.world.get_component_mut::<Flag>(&entity) {
+                            state.carrier = None;
+                        }
+                        announce(
+                            ctx.net,
+                            Text::new("ctf.dropped").with("team", flag.team.name()),
+                        );
+                    }
+                }
+                continue;
+            }
+
+            let position = match ctx.world.get_component::<Position>(&entity) {
+                Some(position) => *position,
+                None => continue,
+            };
+            let at_home = position.loc == flag.home;
+
+            for (player, team) in Self::touching(ctx.world, ctx.spatial, &position) {
+                if team != flag.team {
+                    // Enemies take the flag, one at a time.
+                    if Self::is_carrying(ctx.world, &player) {
+                        continue;
+                    }
+
+                    if let Some(state) = ctx.world.get_component_mut::<Flag>(&entity) {
+                        state.carrier = Some(player);
+                    }
+                    announce(
+                        ctx.net,
+                        Text::new("ctf.taken").with("team", flag.team.name()),
+                    );
+                    break;
+                } else if !at_home {
+                    // Teammates return their dropped flag.
+                    Self::return_home(ctx.world, &entity);
+                    announce(
+                        ctx.net,
+                        Text::new("ctf.returned").with("team", flag.team.name()),
+                    );
+                    break;
+                }
+
+                // Bringing an enemy flag to a flag at home captures it.
+                let captured = self.flags.iter().copied().find(|other| {
+                    ctx.world
+                        .get_component::<Flag>(other)
+                        .is_some_and(|other| other.carrier == Some(player))
+                });
+                if let Some(captured) = captured {
+                    let enemy = ctx
+                        .world
+                        .get_component::<Flag>(&captured)
+                        .map(|flag| flag.team);
+                    Self::return_home(ctx.world, &captured);
+                    *self.scores.entry(team).or_insert(0) += 1;
+                    if let Some(enemy) = enemy {
+                        announce(
+                            ctx.net,
+                            Text::new("ctf.captured")
+                                .with("team", team.name())
+                                .with("flag", enemy.name()),
+                        );
+                    }
+                }
+            }
+        }
+
+        // Only send the HUD when something on it has changed.
+        let hud = self.hud(ctx.world);
+        if let (true, Some(payload)) = (self.hud != hud, hud.clone()) {
+            ctx.net.broadcast(Event::Hud(payload));
+        }
+        self.hud = hud;
+    }
+
+    fn round_end(&mut self, ctx: &mut ModeContext) {
+        let red = self.scores.get(&Team::Red).copied().unwrap_or(0);
+        let blue = self.scores.get(&Team::Blue).copied().unwrap_or(0);
+        let result = match red.cmp(&blue) {
+            std::cmp::Ordering::Greater => Text::new("ctf.red_wins"),
+            std::cmp::Ordering::Less => Text::new("ctf.blue_wins"),
+            std::cmp::Ordering::Equal => Text::new("ctf.draw"),
+        };
+        let result = result.with("red", red).with("blue", blue);
+
+        // Nobody keeps carrying a flag between rounds.
+        for flag in self.flags.iter() {
+            Self::return_home(ctx.world, flag);
+        }
+
+        announce(ctx.net, result);
+    }
+
+    /// Current state of the mode shown to every player.
+    fn hud(&self, world: &World) -> Option<HudPayload> {
+        let flags = self
+            .flags
+            .iter()
+            .filter_map(|entity| {
+                let flag = world.get_component::<Flag>(entity)?;
+                let pos = world.get_component::<Position>(entity)?;
+                let status = if flag.carrier.is_some() {
+                    FlagStatus::Carried
+                } else if pos.loc == flag.home {
+                    FlagStatus::Home
+                } else {
+                    FlagStatus::Dropped
+                };
+
+                Some(FlagState {
+                    team: flag.team,
+                    status,
+                    bounds: pos.bounds(),
+                })
+            })
+            .collect();
+
+        Some(HudPayload::Ctf {
+            scores: Team::ALL
+                .into_iter()
+                .map(|team| (team, self.scores.get(&team).copied().unwrap_or(0)))
+                .collect(),
+            flags,
+        })
+    }
+}
@@ -0,0 +1,47 @@
+use uo2d_proto::ecs::World;
+use uo2d_proto::packet::payloads::HudPayload;
+
+use crate::net::Net;
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+mod ctf;
+
+pub use ctf::CaptureTheFlag;
+
+/// Parts of the gamestate a game mode is allowed to change.
+pub struct ModeContext<'a> {
+    pub world: &'a mut World,
+    pub spatial: &'a mut SpatialHash,
+    pub regions: &'a RegionManager,
+    pub net: &'a mut Net,
+}
+
+/// Rules layered on top of the world, such as objectives and scoring.
+pub trait GameMode {
+    /// Name used to select the mode in the configuration.
+    fn name(&self) -> &'static str;
+
+    /// Places the entities for the mode.
+    fn start(&mut self, ctx: &mut ModeContext);
+
+    /// Resets the objectives and scores as a new round begins.
+    fn round_start(&mut self, ctx: &mut ModeContext);
+
+    /// Applies the rules of the mode, called every tick while a round is active.
+    fn update(&mut self, ctx: &mut ModeContext);
+
+    /// Called once the round is over, announcing the results.
+    fn round_end(&mut self, ctx: &mut ModeContext);
+
+    /// Current state of the mode shown to every player.
+    fn hud(&self, world: &World) -> Option<HudPayload>;
+}
+
+/// Creates the game mode with the name provided.
+pub fn from_name(name: &str) -> Option<Box<dyn GameMode>> {
+    match name.trim().to_lowercase().as_str() {
+        "ctf" => Some(Box::new(CaptureTheFlag::new())),
+        _ => None,
+    }
+}
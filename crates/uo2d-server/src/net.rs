@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+
+use uo2d_proto::components::{Player, Vec2};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::*;
+use uo2d_proto::packet::{Action, BroadcastScope, Packet, PacketConfiguration, Payload};
+use uuid::Uuid;
+
+use crate::spatial_hash::SpatialHash;
+
+/// Something the clients are told about.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The player has joined the world, sent to them alone.
+    Joined(SpawnPayload),
+    PlayerJoined {
+        uuid: Uuid,
+        spawn: SpawnPayload,
+    },
+    PlayerLeft {
+        uuid: Uuid,
+        entity: Entity,
+    },
+    /// Message from a player, shown as they wrote it.
+    Chat {
+        from: Uuid,
+        message: String,
+    },
+    /// Message from the server, translated by the client.
+    Text(Text),
+    Error(Text),
+    Notification(NotificationPayload),
+    /// Full position of an entity, outside of the keyframes.
+    EntityMoved(MovementPayload),
+    Keyframe(KeyframePayload),
+    MovementDelta(MovementDeltaPayload),
+    EntityRemoved(Entity),
+    Damaged(HealthPayload),
+    Healed(HealthPayload),
+    Inventory(InventoryPayload),
+    Appearance(AppearancePayload),
+    GroundItem(GroundItemPayload),
+    Chunk(ChunkPayload),
+    Emote(EmotePayload),
+    Leaderboard(LeaderboardPayload),
+    Hud(HudPayload),
+    Match(MatchPayload),
+    StatusEffect(StatusEffectPayload),
+    Mailbox(MailboxPayload),
+    Redirect(RedirectPayload),
+    Dialogue(DialoguePayload),
+    DialogueEnded,
+    Target(TargetPayload),
+    AreaWarning(AreaWarningPayload),
+    Overlay(OverlayPayload),
+    Cast(CastPayload),
+    MovementMode(MovementModePayload),
+    Fall(FallPayload),
+    AttackError(AttackError),
+    Trade(TradePayload),
+    Bank(BankPayload),
+}
+
+impl Event {
+    /// Player the event comes from, shown to those receiving it.
+    fn sender(&self) -> Option<Uuid> {
+        match self {
+            Event::PlayerJoined { uuid, .. } | Event::PlayerLeft { uuid, .. } => Some(*uuid),
+            Event::Chat { from, .. } => Some(*from),
+            _ => None,
+        }
+    }
+
+    /// Action and payload the event is sent as.
+    fn encode(self) -> (Action, Payload) {
+        match self {
+            Event::Joined(spawn) => (Action::Success, Payload::Spawn(spawn)),
+            Event::PlayerJoined { spawn, .. } => (Action::ClientJoin, Payload::Spawn(spawn)),
+            Event::PlayerLeft { entity, .. } => (
+                Action::ClientLeave,
+                Payload::Entity(EntityPayload::new(entity)),
+            ),
+            Event::Chat { message, .. } => (
+                Action::Message,
+                Payload::Message(MessagePayload::new(message)),
+            ),
+            Event::Text(text) => (Action::Message, Payload::Text(text)),
+            Event::Error(text) => (Action::Error, Payload::Text(text)),
+            Event::Notification(data) => (Action::Notification, Payload::Notification(data)),
+            Event::EntityMoved(data) => (Action::Movement, Payload::Movement(data)),
+            Event::Keyframe(data) => (Action::Keyframe, Payload::Keyframe(data)),
+            Event::MovementDelta(data) => (Action::MovementDelta, Payload::MovementDelta(data)),
+            Event::EntityRemoved(entity) => (
+                Action::EntityDelete,
+                Payload::Entity(EntityPayload::new(entity)),
+            ),
+            Event::Damaged(data) => (Action::Damage, Payload::Health(data)),
+            Event::Healed(data) => (Action::Heal, Payload::Health(data)),
+            Event::Inventory(data) => (Action::Inventory, Payload::Inventory(data)),
+            Event::Appearance(data) => (Action::Appearance, Payload::Appearance(data)),
+            Event::GroundItem(data) => (Action::GroundItem, Payload::GroundItem(data)),
+            Event::Chunk(data) => (Action::Chunk, Payload::Chunk(data)),
+            Event::Emote(data) => (Action::Emote, Payload::Emote(data)),
+            Event::Leaderboard(data) => (Action::Leaderboard, Payload::Leaderboard(data)),
+            Event::Hud(data) => (Action::Hud, Payload::Hud(data)),
+            Event::Match(data) => (Action::Match, Payload::Match(data)),
+            Event::StatusEffect(data) => (Action::StatusEffect, Payload::StatusEffect(data)),
+            Event::Mailbox(data) => (Action::Mailbox, Payload::Mailbox(data)),
+            Event::Redirect(data) => (Action::Redirect, Payload::Redirect(data)),
+            Event::Dialogue(data) => (Action::Dialogue, Payload::Dialogue(data)),
+            Event::DialogueEnded => (Action::Dialogue, Payload::Empty),
+            Event::Target(data) => (Action::Target, Payload::Target(data)),
+            Event::AreaWarning(data) => (Action::AreaWarning, Payload::AreaWarning(data)),
+            Event::Overlay(data) => (Action::Overlay, Payload::Overlay(data)),
+            Event::Cast(data) => (Action::Cast, Payload::Cast(data)),
+            Event::MovementMode(data) => (Action::MovementMode, Payload::MovementMode(data)),
+            Event::Fall(data) => (Action::Fall, Payload::Fall(data)),
+            Event::AttackError(data) => (Action::Attack, Payload::AttackError(data)),
+            Event::Trade(data) => (Action::Trade, Payload::Trade(data)),
+            Event::Bank(data) => (Action::Bank, Payload::Bank(data)),
+        }
+    }
+
+    /// Whether the event starts a new keyframe for the entity.
+    pub fn is_keyframe(&self) -> bool {
+        matches!(self, Event::Keyframe(_))
+    }
+}
+
+/// Players an event is sent to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scope {
+    Player(Uuid),
+    Players(HashSet<Uuid>),
+    /// Players within the radius of a position, found once the events are sent.
+    Near(Vec2, f64),
+    /// Every player in the world, or only those in the instance.
+    Everyone,
+}
+
+/// Events for the clients gathered during a tick, translated into packets once they are sent.
+#[derive(Debug, Default)]
+pub struct Net {
+    events: Vec<(Scope, Event)>,
+}
+
+impl Net {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends the event to a single player.
+    pub fn send_to(&mut self, uuid: Uuid, event: Event) {
+        self.events.push((Scope::Player(uuid), event));
+    }
+
+    /// Sends the event to each of the players.
+    pub fn broadcast_to(&mut self, uuids: HashSet<Uuid>, event: Event) {
+        self.events.push((Scope::Players(uuids), event));
+    }
+
+    /// Sends the event to the players within the radius of the position.
+    pub fn broadcast_near(&mut self, pos: Vec2, radius: f64, event: Event) {
+        self.events.push((Scope::Near(pos, radius), event));
+    }
+
+    /// Sends the event to every player.
+    pub fn broadcast(&mut self, event: Event) {
+        self.events.push((Scope::Everyone, event));
+    }
+
+    /// Number of events waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Events waiting to be sent, in the order they happened.
+    pub fn events(&self) -> impl Iterator<Item = &(Scope, Event)> {
+        self.events.iter()
+    }
+
+    /// Translates the events into packets for the socket server, emptying the queue.
+    /// Players near a position are found within the world given.
+    pub fn drain(&mut self, world: &World, spatial: &SpatialHash) -> Vec<PacketConfiguration> {
+        let mut packets = Vec::with_capacity(self.events.len());
+        for (scope, event) in self.events.drain(..) {
+            let sender = event.sender();
+            let (action, payload) = event.encode();
+            let packet = |uuid: Uuid| Packet::new(action, sender.unwrap_or(uuid), payload);
+
+            packets.push(match scope {
+                // Single packets are addressed by their uuid, the sender has to be kept apart.
+                Scope::Player(uuid) if sender.is_some() => PacketConfiguration::Broadcast(
+                    packet(uuid),
+                    BroadcastScope::Local(HashSet::from([uuid])),
+                ),
+                Scope::Player(uuid) => PacketConfiguration::Single(packet(uuid)),
+                Scope::Players(uuids) => PacketConfiguration::Broadcast(
+                    packet(Uuid::nil()),
+                    BroadcastScope::Local(uuids),
+                ),
+                Scope::Near(pos, radius) => {
+                    let uuids = spatial
+                        .query_radius(pos, radius, None)
+                        .iter()
+                        .filter_map(|entity| world.get_component::<Player>(entity))
+                        .map(|player| *player.uuid())
+                        .collect();
+                    PacketConfiguration::Broadcast(
+                        packet(Uuid::nil()),
+                        BroadcastScope::Local(uuids),
+                    )
+                }
+                Scope::Everyone => {
+                    PacketConfiguration::Broadcast(packet(Uuid::nil()), BroadcastScope::Global)
+                }
+            });
+        }
+        packets
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use uo2d_proto::components::AreaShape;
+use uo2d_proto::locale::Text;
+use uo2d_proto::sprintln;
+
+use crate::content::Content;
+
+/// Type of NPC that spawners can place, loaded from the NPC data.
+#[derive(Debug, Deserialize, Clone)]
+pub struct NpcDefinition {
+    pub kind: String,
+    /// Dialogue tree it speaks with, if it can be talked to.
+    #[serde(default)]
+    pub dialogue: Option<String>,
+    /// NPCs without health cannot be harmed.
+    #[serde(default)]
+    pub health: Option<u32>,
+    /// Speed it wanders around at, 0 stands still.
+    #[serde(default)]
+    pub wander: f64,
+    /// Distance players are noticed from, 0 only reacts to being attacked.
+    #[serde(default)]
+    pub aggro: f64,
+    /// Speed it chases its target at.
+    #[serde(default)]
+    pub chase: f64,
+    /// Phases of the fight, only bosses have them.
+    #[serde(default)]
+    pub phases: Vec<PhaseDefinition>,
+    /// Players can reach their bank while standing near it.
+    #[serde(default)]
+    pub banker: bool,
+    /// Loot table rolled once it dies.
+    #[serde(default)]
+    pub loot: Option<String>,
+    /// Level its loot is rolled at.
+    #[serde(default)]
+    pub level: u32,
+}
+
+impl NpcDefinition {
+    /// Name shown to the players, translated from the world strings.
+    pub fn text(&self) -> Text {
+        Text::new(format!("npcs.{}", self.kind))
+    }
+}
+
+/// Stage of a boss fight, entered once its health falls to the threshold.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PhaseDefinition {
+    /// Fraction of its health the phase begins at.
+    pub health: f64,
+    /// Shown to the players nearby as it begins.
+    #[serde(default)]
+    pub announce: Option<String>,
+    /// Replaces the speed it chases its target at.
+    #[serde(default)]
+    pub chase: Option<f64>,
+    #[serde(default)]
+    pub abilities: Vec<AbilityDefinition>,
+}
+
+/// Where an ability is placed.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AbilityTarget {
+    Caster,
+    #[default]
+    Target,
+}
+
+/// Ability striking an area after warning the players about it, it may linger afterwards.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AbilityDefinition {
+    pub name: String,
+    pub shape: AreaShape,
+    #[serde(default)]
+    pub at: AbilityTarget,
+    /// Damage dealt as it strikes.
+    #[serde(default)]
+    pub damage: u32,
+    /// Damage dealt on every tick it lingers.
+    #[serde(default)]
+    pub per_tick: u32,
+    /// Seconds it lingers after striking.
+    #[serde(default)]
+    pub duration: f32,
+    /// Seconds between the warning and the strike.
+    pub delay: f32,
+    /// Seconds between uses.
+    pub cooldown: f32,
+}
+
+/// Manages the definitions for all types of NPCs.
+pub struct NpcManager {
+    npcs: HashMap<String, NpcDefinition>,
+}
+
+impl Default for NpcManager {
+    fn default() -> Self {
+        Self::new(&Content::default())
+    }
+}
+
+impl NpcManager {
+    /// File of the definitions, within the content root.
+    pub const PATH: &'static str = "npcs.yaml";
+
+    /// Loads all NPC definitions at launch, those within the packs replacing any of the same kind.
+    pub fn new(content: &Content) -> Self {
+        let mut npcs = HashMap::new();
+        for path in content.layers(&content.path(Self::PATH)) {
+            match content.parse::<Vec<NpcDefinition>>(&path) {
+                Ok(definitions) => {
+                    npcs.extend(definitions.into_iter().map(|npc| (npc.kind.clone(), npc)))
+                }
+                Err(why) => sprintln!("Error while loading {}: {}", path, why),
+            }
+        }
+
+        Self { npcs }
+    }
+
+    /// Obtains the definition for a type of NPC.
+    pub fn get(&self, kind: &str) -> Option<&NpcDefinition> {
+        self.npcs.get(kind)
+    }
+}
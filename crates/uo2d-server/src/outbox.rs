@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use uo2d_proto::packet::payloads::BundlePayload;
+use uo2d_proto::packet::{Action, Packet, Payload, MAX_DATAGRAM};
+use uuid::Uuid;
+
+/// Packets held back for a client until they are written together.
+#[derive(Default)]
+struct Queue {
+    packets: Vec<Arc<[u8]>>,
+    /// Size of the bundle carrying the packets.
+    size: usize,
+}
+
+impl Queue {
+    /// Writes the queued packets as a single datagram, the packet itself if there is only one.
+    fn datagram(self) -> Option<Vec<u8>> {
+        match self.packets.len() {
+            0 => None,
+            1 => Some(self.packets[0].to_vec()),
+            _ => {
+                let packets = self.packets.iter().map(|bytes| bytes.to_vec()).collect();
+                let bundle = Packet::new(
+                    Action::Bundle,
+                    Uuid::nil(),
+                    Payload::Bundle(BundlePayload::new(packets)),
+                );
+                Some(bundle.as_bytes().to_vec())
+            }
+        }
+    }
+}
+
+/// Aggregates the packets sent to each client within a tick, writing them as bundles.
+#[derive(Default)]
+pub struct Outbox {
+    queues: HashMap<SocketAddr, Queue>,
+}
+
+impl Outbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a packet for the address, returning the datagrams that have to be written now.
+    /// Urgent packets are written immediately, after anything already queued to keep their order.
+    pub fn push(&mut self, addr: SocketAddr, bytes: Arc<[u8]>, urgent: bool) -> Vec<Vec<u8>> {
+        let entry = bytes.len() + BundlePayload::ENTRY_OVERHEAD;
+        let mut ready = Vec::new();
+
+        // Packets too large to share a datagram are written on their own.
+        if urgent || BundlePayload::BASE_SIZE + entry > MAX_DATAGRAM {
+            ready.extend(self.take(&addr));
+            ready.push(bytes.to_vec());
+            return ready;
+        }
+
+        let queue = self.queues.entry(addr).or_default();
+        if queue.size + entry > MAX_DATAGRAM {
+            ready.extend(std::mem::take(queue).datagram());
+        }
+
+        if queue.packets.is_empty() {
+            queue.size = BundlePayload::BASE_SIZE;
+        }
+        queue.size += entry;
+        queue.packets.push(bytes);
+        ready
+    }
+
+    /// Removes the packets queued for an address, as a datagram.
+    pub fn take(&mut self, addr: &SocketAddr) -> Option<Vec<u8>> {
+        self.queues.remove(addr).and_then(Queue::datagram)
+    }
+
+    /// Removes every queued packet, as a datagram for each address.
+    pub fn drain(&mut self) -> Vec<(SocketAddr, Vec<u8>)> {
+        self.queues
+            .drain()
+            .filter_map(|(addr, queue)| queue.datagram().map(|datagram| (addr, datagram)))
+            .collect()
+    }
+}
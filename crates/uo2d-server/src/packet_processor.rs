@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+
+use tokio::sync::mpsc;
+use uo2d_proto::packet::*;
+use uo2d_proto::sprintln;
+use uuid::Uuid;
+
+use crate::anticheat::{AntiCheat, Violation};
+use crate::cache::PacketCacheAsync;
+use crate::event_log::{self, ServerEvent};
+use crate::middleware::{Inbound, Middleware, Pipeline, RateLimit, Rejection};
+
+/// Packet being handled, along with who sent it.
+pub(crate) struct Context<'a> {
+    /// Client that sent the packet.
+    pub uuid: Uuid,
+    /// Packet as received, stamped with the sender.
+    pub packet: &'a Packet,
+    pub anticheat: &'a AntiCheat,
+}
+
+impl Context<'_> {
+    /// Passes the packet on to the gamestate as it was received.
+    pub fn forward(&self) -> Outcome {
+        Outcome::Forward(self.packet.clone())
+    }
+}
+
+/// What becomes of a packet once it has been handled.
+pub(crate) enum Outcome {
+    /// Passed on to the gamestate for its next tick.
+    Forward(Packet),
+    /// Sent straight back to the client.
+    Reply(Packet),
+    /// Sent on to other clients, the last one taking effect.
+    Send(PacketConfiguration),
+}
+
+/// Actions answered here without reaching the gamestate, unless a plugin takes them over.
+const ANSWERED: [Action; 2] = [Action::Ping, Action::Echo];
+
+/// Handles the payload of a packet, all of its effects described by the outcomes.
+pub(crate) type Handler = fn(&Context, Payload) -> Vec<Outcome>;
+
+/// Handlers for the actions clients send, registered by the modules implementing them.
+#[derive(Default)]
+pub(crate) struct Handlers {
+    handlers: HashMap<Action, Handler>,
+}
+
+impl Handlers {
+    /// Handlers for every action the server accepts.
+    pub fn new() -> Self {
+        let mut handlers = Self::default();
+        register(&mut handlers);
+        crate::dialogue::register(&mut handlers);
+        crate::trades::register(&mut handlers);
+        handlers
+    }
+
+    /// Handles the action with the handler, replacing any registered before it.
+    pub fn register_handler(&mut self, action: Action, handler: Handler) {
+        if self.handlers.insert(action, handler).is_some() {
+            sprintln!("Replaced the handler for {:?}.", action);
+        }
+    }
+
+    /// Passes actions handled by the plugins on to the gamestate as they were received. Modules
+    /// already forwarding an action keep validating it, those answered here are taken over.
+    pub fn forwarding(mut self, actions: &[Action]) -> Self {
+        for action in actions {
+            if ANSWERED.contains(action) {
+                sprintln!("Plugins took over answering {:?}.", action);
+                self.handlers.insert(*action, forward);
+            } else {
+                self.handlers.entry(*action).or_insert(forward);
+            }
+        }
+        self
+    }
+
+    /// Handler for the action, if one is registered.
+    pub fn get(&self, action: &Action) -> Option<Handler> {
+        self.handlers.get(action).copied()
+    }
+}
+
+/// Registers the handlers for the core actions.
+fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Ping, ping);
+    handlers.register_handler(Action::Echo, echo);
+    handlers.register_handler(Action::Message, message);
+    handlers.register_handler(Action::ClientJoin, client_join);
+    handlers.register_handler(Action::ClientLeave, client_leave);
+    handlers.register_handler(Action::Register, credentials);
+    handlers.register_handler(Action::Login, credentials);
+    handlers.register_handler(Action::Movement, movement);
+    handlers.register_handler(Action::Projectile, projectile);
+    handlers.register_handler(Action::Equip, equip);
+    handlers.register_handler(Action::Unequip, equip);
+    handlers.register_handler(Action::SplitStack, stack);
+    handlers.register_handler(Action::Drop, stack);
+    handlers.register_handler(Action::UseItem, stack);
+    handlers.register_handler(Action::MoveStack, move_stack);
+    handlers.register_handler(Action::Bank, bank);
+    handlers.register_handler(Action::Pickup, request);
+    handlers.register_handler(Action::Emote, emote);
+    handlers.register_handler(Action::Gather, request);
+    handlers.register_handler(Action::Mount, request);
+    handlers.register_handler(Action::Attack, attack);
+    handlers.register_handler(Action::Leaderboard, request);
+    handlers.register_handler(Action::Mail, mail);
+    handlers.register_handler(Action::Mailbox, request);
+    handlers.register_handler(Action::Handoff, handoff);
+    handlers.register_handler(Action::Overlay, overlay);
+}
+
+/// Sends data from handler to server.
+async fn fwd_packet(tx: &mpsc::Sender<Vec<u8>>, packet: Packet) {
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let _ = tx.send(packet.to_bytes()).await;
+    });
+}
+
+/// Processes all packet types, once they have passed through the middleware.
+pub(crate) async fn process_packet(
+    pipeline: &Pipeline,
+    handlers: &Handlers,
+    packet_cache: &PacketCacheAsync,
+    anticheat: &AntiCheat,
+    tx: &mut mpsc::Sender<Vec<u8>>,
+    inbound: Inbound,
+) -> PacketConfiguration {
+    let mut config = PacketConfiguration::Empty;
+    for outcome in handle(pipeline, handlers, anticheat, inbound) {
+        match outcome {
+            Outcome::Forward(packet) => packet_cache.add(packet).await,
+            Outcome::Reply(packet) => fwd_packet(tx, packet).await,
+            Outcome::Send(send) => config = send,
+        }
+    }
+    config
+}
+
+/// Applies the rate limit to a datagram before anything else is done with it, flagging floods.
+/// Returns the packet if it is let through.
+pub(crate) fn admit(
+    rate_limit: &RateLimit,
+    anticheat: &AntiCheat,
+    mut inbound: Inbound,
+) -> Option<Packet> {
+    match rate_limit.check(&mut inbound) {
+        Ok(()) => Some(inbound.packet),
+        Err(_) => {
+            let evidence = format!("rate limited {:?}", inbound.action);
+            anticheat.flag(inbound.uuid, Violation::Flood, evidence);
+            None
+        }
+    }
+}
+
+/// Passes a packet through the middleware and on to the handler for its action.
+/// Rejected packets and those without a handler have no outcomes.
+pub(crate) fn handle(
+    pipeline: &Pipeline,
+    handlers: &Handlers,
+    anticheat: &AntiCheat,
+    mut inbound: Inbound,
+) -> Vec<Outcome> {
+    let uuid = inbound.uuid;
+    match pipeline.check(&mut inbound) {
+        Ok(()) => (),
+        Err(Rejection::Unauthenticated) => return vec![],
+        Err(Rejection::RateLimited) => {
+            let evidence = format!("rate limited {:?}", inbound.action);
+            anticheat.flag(uuid, Violation::Flood, evidence);
+            return vec![];
+        }
+        Err(Rejection::Malformed(evidence)) => {
+            anticheat.flag(uuid, Violation::Malformed, evidence);
+            return vec![];
+        }
+    }
+
+    let Some(handler) = inbound.action.and_then(|action| handlers.get(&action)) else {
+        return vec![];
+    };
+
+    let packet = inbound.packet.set_uuid(uuid); // Not needed, preventing future spoofing.
+    let payload = inbound.payload.unwrap_or_else(|| packet.payload());
+    let context = Context {
+        uuid,
+        packet: &packet,
+        anticheat,
+    };
+    handler(&context, payload)
+}
+
+/// Answer to a ping or probe, returned to the client as it was sent.
+pub(crate) fn answer(uuid: Uuid, action: Action, payload: Payload) -> Option<Packet> {
+    match payload {
+        Payload::Uuid(data) if ANSWERED.contains(&action) => {
+            Some(Packet::new(action, uuid, Payload::Uuid(data)))
+        }
+        _ => None,
+    }
+}
+
+fn ping(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    answer(ctx.uuid, Action::Ping, payload)
+        .map(Outcome::Reply)
+        .into_iter()
+        .collect()
+}
+
+/// Returns the probe to the client as it was sent.
+fn echo(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    answer(ctx.uuid, Action::Echo, payload)
+        .map(|packet| Outcome::Send(PacketConfiguration::Single(packet)))
+        .into_iter()
+        .collect()
+}
+
+fn message(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    let payload = match payload {
+        Payload::Message(data) => data,
+        _ => return vec![],
+    };
+
+    // Chat commands are handled by the gamestate instead of being broadcast.
+    if payload.message.trim_start().starts_with('/') {
+        return vec![ctx.forward()];
+    }
+
+    event_log::record(ServerEvent::Chat {
+        uuid: ctx.uuid,
+        message: payload.message.clone(),
+    });
+    let packet = Packet::new(Action::Message, ctx.uuid, Payload::Message(payload));
+
+    // Passed on to the gamestate to be shared with the other servers.
+    vec![
+        Outcome::Forward(packet.clone()),
+        Outcome::Send(PacketConfiguration::Broadcast(
+            packet,
+            BroadcastScope::Global,
+        )),
+    ]
+}
+
+fn client_join(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Join(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+/// Packets handled by the plugins, passed on as they were received.
+fn forward(ctx: &Context, _payload: Payload) -> Vec<Outcome> {
+    vec![ctx.forward()]
+}
+
+/// Requests without a payload, passed on as coming from the client.
+fn request(ctx: &Context, _payload: Payload) -> Vec<Outcome> {
+    let Some(action) = ctx.packet.action() else {
+        return vec![];
+    };
+    vec![Outcome::Forward(Packet::new(
+        action,
+        ctx.uuid,
+        Payload::Empty,
+    ))]
+}
+
+fn credentials(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Credentials(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn client_leave(ctx: &Context, _payload: Payload) -> Vec<Outcome> {
+    ctx.anticheat.forget(&ctx.uuid);
+    event_log::record(ServerEvent::Disconnect {
+        uuid: ctx.uuid,
+        reason: "left".to_string(),
+    });
+    let packet = Packet::new(Action::ClientLeave, ctx.uuid, Payload::Empty);
+    vec![Outcome::Forward(packet)]
+}
+
+fn movement(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Movement(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn projectile(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    let Payload::Movement(_) = payload else {
+        return vec![];
+    };
+
+    // Counted before the cache drops all but the latest of the tick.
+    ctx.anticheat.fire(ctx.uuid);
+
+    // The cache only keeps the most recent projectile per tick for each client.
+    vec![ctx.forward()]
+}
+
+fn equip(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Equip(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn stack(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Stack(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn move_stack(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::MoveStack(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn bank(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::BankRequest(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn emote(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Emote(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn attack(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Target(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn mail(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Mail(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn handoff(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::Uuid(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
+
+fn overlay(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::OverlayRequest(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::{BTreeSet, HashMap};
+
+use uo2d_proto::components::{Bounds, Vec3};
+
+use crate::accounts::AccountId;
+use crate::region::PlotSpawn;
+
+/// Housing plot defined by a region, furnished by whoever has claimed it.
+#[derive(Debug, Clone)]
+pub struct Plot {
+    pub name: String,
+    pub bounds: Bounds,
+    pub owner: Option<AccountId>,
+    /// Co-owners allowed to furnish the plot alongside the owner.
+    pub members: BTreeSet<AccountId>,
+}
+
+impl Plot {
+    /// Checks if an account is allowed to place and remove objects.
+    pub fn permits(&self, id: AccountId) -> bool {
+        self.owner == Some(id) || self.members.contains(&id)
+    }
+}
+
+/// Tracks the housing plots of every region and who they belong to.
+pub struct PlotManager {
+    plots: HashMap<String, Plot>,
+}
+
+impl PlotManager {
+    /// Most co-owners a plot can have.
+    pub const MAX_MEMBERS: usize = 8;
+
+    /// Creates the plots defined by the regions, all unclaimed.
+    pub fn new(spawns: Vec<PlotSpawn>) -> Self {
+        let plots = spawns
+            .into_iter()
+            .map(|spawn| {
+                let plot = Plot {
+                    name: spawn.name.clone(),
+                    bounds: spawn.bounds(),
+                    owner: None,
+                    members: BTreeSet::new(),
+                };
+                (spawn.name, plot)
+            })
+            .collect();
+
+        Self { plots }
+    }
+
+    /// Obtains a plot by its name.
+    pub fn get(&self, name: &str) -> Option<&Plot> {
+        self.plots.get(name)
+    }
+
+    /// Obtains a plot by its name to be changed.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Plot> {
+        self.plots.get_mut(name)
+    }
+
+    /// Plot containing a coordinate, if any.
+    pub fn at(&self, coord: &Vec3) -> Option<&Plot> {
+        self.plots
+            .values()
+            .find(|plot| plot.bounds.coord_within_2d(coord))
+    }
+
+    /// Plot claimed by an account, each account can only own one.
+    pub fn owned_by(&self, id: AccountId) -> Option<&Plot> {
+        self.plots.values().find(|plot| plot.owner == Some(id))
+    }
+
+    /// Every plot, claimed or not.
+    pub fn all(&self) -> impl Iterator<Item = &Plot> {
+        self.plots.values()
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use uo2d_proto::ecs::{Component, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::{Action, Payload};
+use uo2d_proto::sprintln;
+use uuid::Uuid;
+
+use crate::handle::ServerBuilder;
+use crate::net::Net;
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Parts of the gamestate a plugin is allowed to change, within the world being updated.
+pub struct PluginContext<'a> {
+    pub world: &'a mut World,
+    pub spatial: &'a mut SpatialHash,
+    pub regions: &'a RegionManager,
+    pub items: &'a ItemManager,
+    pub net: &'a mut Net,
+    /// Tick the world is on.
+    pub tick: u64,
+}
+
+/// Runs every tick within every world, after the built-in systems.
+pub type System = Box<dyn FnMut(&mut PluginContext) + Send>;
+
+/// Handles a packet from a client, returning if the built-in handling is skipped.
+pub type PacketHandler = Box<dyn FnMut(&mut PluginContext, Uuid, Payload) -> bool + Send>;
+
+/// Runs a chat command for a player, given everything typed after it.
+pub type ChatCommand = Box<dyn FnMut(&mut PluginContext, Uuid, &str) + Send>;
+
+/// Runs each time a timer expires.
+pub type TimerHandler = Box<dyn FnMut(&mut PluginContext) + Send>;
+
+/// Gameplay added to the server from another crate, registered as the server is built.
+pub trait Plugin {
+    /// Name shown once the plugin is added.
+    fn name(&self) -> &'static str;
+
+    /// Registers the components, systems, packet handlers, chat commands, and timers it adds.
+    fn build(&self, builder: &mut ServerBuilder);
+}
+
+/// A timer repeating for as long as the server runs.
+struct PluginTimer {
+    /// Seconds between each time it runs.
+    interval: f32,
+    handler: TimerHandler,
+}
+
+/// Everything registered by the plugins, run by the gamestate.
+#[derive(Default)]
+pub struct Plugins {
+    names: Vec<&'static str>,
+    components: Vec<fn(&mut World)>,
+    systems: Vec<System>,
+    handlers: HashMap<Action, PacketHandler>,
+    commands: HashMap<String, ChatCommand>,
+    timers: Vec<PluginTimer>,
+}
+
+impl Plugins {
+    /// Names of the plugins added, in the order they were added.
+    pub fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+
+    pub(crate) fn add_name(&mut self, name: &'static str) {
+        self.names.push(name);
+    }
+
+    pub(crate) fn add_component<T: Component>(&mut self) {
+        self.components
+            .push(|world: &mut World| world.register_component::<T>());
+    }
+
+    pub(crate) fn add_system(&mut self, system: System) {
+        self.systems.push(system);
+    }
+
+    /// Handles the action with the handler, replacing any plugin registered before it.
+    pub(crate) fn add_handler(&mut self, action: Action, handler: PacketHandler) {
+        if self.handlers.insert(action, handler).is_some() {
+            sprintln!("Replaced the plugin handler for {:?}.", action);
+        }
+    }
+
+    /// Runs the command when typed with a leading slash, replacing any plugin registered before it.
+    pub(crate) fn add_command(&mut self, name: &str, command: ChatCommand) {
+        let name = name.trim_start_matches('/').to_lowercase();
+        if self.commands.insert(name.clone(), command).is_some() {
+            sprintln!("Replaced the plugin command '/{}'.", name);
+        }
+    }
+
+    pub(crate) fn add_timer(&mut self, interval: f32, handler: TimerHandler) {
+        self.timers.push(PluginTimer { interval, handler });
+    }
+
+    /// Actions handled by the plugins, passed on to the gamestate as they are received.
+    pub(crate) fn actions(&self) -> Vec<Action> {
+        self.handlers.keys().copied().collect()
+    }
+
+    /// Seconds between each run of every timer, by their id.
+    pub(crate) fn timers(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.timers
+            .iter()
+            .enumerate()
+            .map(|(id, timer)| (id, timer.interval))
+    }
+
+    /// Registers the components of the plugins within a new world.
+    pub(crate) fn register(&self, world: &mut World) {
+        for register in self.components.iter() {
+            register(world);
+        }
+    }
+
+    /// Runs every system once.
+    pub(crate) fn update(&mut self, ctx: &mut PluginContext) {
+        for system in self.systems.iter_mut() {
+            system(ctx);
+        }
+    }
+
+    /// Checks if a plugin handles the action.
+    pub(crate) fn handles(&self, action: &Action) -> bool {
+        self.handlers.contains_key(action)
+    }
+
+    /// Handles a packet, returning if the built-in handling is skipped.
+    pub(crate) fn handle(
+        &mut self,
+        ctx: &mut PluginContext,
+        uuid: Uuid,
+        action: Action,
+        payload: Payload,
+    ) -> bool {
+        match self.handlers.get_mut(&action) {
+            Some(handler) => handler(ctx, uuid, payload),
+            None => false,
+        }
+    }
+
+    /// Runs a chat command such as `/dance`, returning if a plugin provides it.
+    pub(crate) fn command(
+        &mut self,
+        ctx: &mut PluginContext,
+        uuid: Uuid,
+        command: &str,
+        argument: &str,
+    ) -> bool {
+        let name = command.trim_start_matches('/').to_lowercase();
+        match self.commands.get_mut(&name) {
+            Some(command) => {
+                command(ctx, uuid, argument);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs a timer that has expired, returning the seconds until it runs again.
+    pub(crate) fn expire(&mut self, ctx: &mut PluginContext, id: usize) -> Option<f32> {
+        let timer = self.timers.get_mut(id)?;
+        (timer.handler)(ctx);
+        Some(timer.interval)
+    }
+}
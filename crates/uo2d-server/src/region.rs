@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use uo2d_proto::chunk::{tile_bounds, ChunkCoord, CHUNK_TILES, VOID, WATER};
+use uo2d_proto::components::{Bounds, ObstacleKind, Team, Terrain, Transform, Vec2, Vec3};
+use uo2d_proto::locale::Text;
+use uo2d_proto::sprintln;
+
+use crate::content::{Content, LoadError};
+
+/// Layer of the ground beneath every platform.
+pub const GROUND_LAYER: f64 = 1.;
+
+/// An obstacle placed within a region when the server starts.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ObstacleSpawn {
+    pub kind: ObstacleKind,
+    pub position: Vec3,
+    /// Loot table filling a container, or dropped once the obstacle is destroyed.
+    #[serde(default)]
+    pub loot: Option<String>,
+    /// Level its loot is rolled at.
+    #[serde(default)]
+    pub level: u32,
+}
+
+/// Where a team's flag rests when capture the flag is being played.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FlagSpawn {
+    pub team: Team,
+    pub position: Vec3,
+}
+
+/// Keeps NPCs of a type alive around a position, replacing them after a delay.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SpawnerSpawn {
+    /// Type of NPC spawned.
+    pub npc: String,
+    pub position: Vec3,
+    #[serde(default = "SpawnerSpawn::default_max_alive")]
+    pub max_alive: u32,
+    /// Seconds before a replacement is spawned.
+    pub respawn: f32,
+    /// Distance NPCs can stray from the position before they are despawned.
+    pub leash: f64,
+}
+
+impl SpawnerSpawn {
+    /// Spawners that do not specify their limit keep a single NPC alive.
+    fn default_max_alive() -> u32 {
+        1
+    }
+}
+
+/// Tree, ore vein, or similar that can be gathered from.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ResourceSpawn {
+    /// Type of resource node.
+    pub resource: String,
+    pub position: Vec3,
+}
+
+/// Land players can claim, furnishing it with objects of their own.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlotSpawn {
+    /// Unique name the plot is saved under.
+    pub name: String,
+    pub position: Vec3,
+    pub size: Vec2,
+}
+
+impl PlotSpawn {
+    /// Area objects can be placed within.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_vec(self.position, self.size)
+    }
+}
+
+/// Area of a region covered in a terrain other than the ground.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TerrainZone {
+    pub kind: Terrain,
+    pub position: Vec3,
+    pub size: Vec2,
+}
+
+impl TerrainZone {
+    /// Area covered by the terrain.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_vec(self.position, self.size)
+    }
+}
+
+/// Area of a region players can reach their bank from.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BankZone {
+    pub position: Vec3,
+    pub size: Vec2,
+}
+
+impl BankZone {
+    /// Area the bank can be reached from.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_vec(self.position, self.size)
+    }
+}
+
+/// Raised ground within a region, entities stepping off its edge fall to the layer beneath.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Platform {
+    /// Corner of the platform, its layer being the height entities stand at.
+    pub position: Vec3,
+    pub size: Vec2,
+}
+
+impl Platform {
+    /// Area covered by the platform.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_vec(self.position, self.size)
+    }
+}
+
+/// Moves players that step onto it to the spawn of another region.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Portal {
+    pub position: Vec3,
+    /// Name of the region players are sent to.
+    pub destination: String,
+}
+
+impl Portal {
+    const SIZE: f64 = 32.;
+
+    /// Area players step onto to use the portal.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_vec(self.position, Vec2::new(Self::SIZE, Self::SIZE))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Region {
+    pub name: String,
+    pub description: String,
+    pub spawn: Vec3,
+    pub tile: f64,
+    /// Fraction of velocity lost each tick while moving across the region.
+    #[serde(default = "Region::default_friction")]
+    pub friction: f64,
+    pub file: String,
+    #[serde(rename = "vertices")]
+    transform: Transform,
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleSpawn>,
+    #[serde(default)]
+    pub flags: Vec<FlagSpawn>,
+    #[serde(default)]
+    pub portals: Vec<Portal>,
+    #[serde(default)]
+    pub spawners: Vec<SpawnerSpawn>,
+    #[serde(default)]
+    pub resources: Vec<ResourceSpawn>,
+    /// Housing plots are only offered in regions that are not instanced.
+    #[serde(default)]
+    pub plots: Vec<PlotSpawn>,
+    /// Each party entering the region plays within their own copy of it.
+    #[serde(default)]
+    pub instanced: bool,
+    /// Mounts cannot be ridden within the region, such as caves and buildings.
+    #[serde(default)]
+    pub indoors: bool,
+    #[serde(default)]
+    pub terrain: Vec<TerrainZone>,
+    #[serde(default)]
+    pub platforms: Vec<Platform>,
+    #[serde(default)]
+    pub banks: Vec<BankZone>,
+}
+
+impl Region {
+    /// Friction for regions that do not specify their terrain.
+    fn default_friction() -> f64 {
+        0.25
+    }
+
+    /// Name of a region shown to the players, translated from the world strings.
+    pub fn text(name: &str) -> Text {
+        Text::new(format!("regions.{}", name))
+    }
+
+    /// Reads a region from a YAML file within the content root.
+    pub fn load(content: &Content, path: &str) -> Result<Region, LoadError> {
+        content.parse(path)
+    }
+
+    /// Checks if a coordinate is within a region using the ray casting algorithm.
+    pub fn is_within(&self, position: &Vec3) -> bool {
+        self.transform.coord_within(position)
+    }
+
+    /// Checks if an entire object is within bounds by checking all corners.
+    pub fn is_inbounds(&self, bounds: &Bounds) -> bool {
+        self.bounding_box().contains_2d(bounds)
+    }
+
+    /// Terrain at a coordinate within the region, the ground if no zone covers it.
+    pub fn terrain_at(&self, coord: &Vec3) -> Terrain {
+        self.terrain
+            .iter()
+            .find(|zone| zone.bounds().coord_within_2d(coord))
+            .map_or(Terrain::Ground, |zone| zone.kind)
+    }
+
+    /// Layer of the ground at a coordinate within the region, the highest platform covering it.
+    pub fn floor_at(&self, coord: &Vec3) -> f64 {
+        self.platforms
+            .iter()
+            .filter(|platform| platform.bounds().coord_within_2d(coord))
+            .map(|platform| platform.position.z())
+            .fold(GROUND_LAYER, f64::max)
+    }
+
+    /// Obtains the bounding box for region.
+    pub fn bounding_box(&self) -> Bounds {
+        self.transform.bounding_box()
+    }
+
+    /// Corners of the region's outline.
+    pub fn vertices(&self) -> &[Vec3] {
+        self.transform.vertices()
+    }
+
+    /// Size of a tile within the region.
+    pub fn tile_size(&self) -> Vec2 {
+        Vec2::new(self.tile, self.tile)
+    }
+
+    /// Length of a tile within the region.
+    pub fn tile_length(&self) -> f64 {
+        self.tile * f64::sqrt(2.0)
+    }
+}
+
+/// Manages the region data for all loaded regions.
+pub struct RegionManager {
+    regions: HashMap<u8, Region>,
+    map: Vec<Vec<u8>>,
+}
+
+impl Default for RegionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegionManager {
+    /// Directory of the regions, within the content root.
+    pub const DIRECTORY: &'static str = "regions";
+
+    /// Loads all region data at launch, initializing the map.
+    pub fn new() -> Self {
+        Self::from_content(&Content::default())
+    }
+
+    /// Loads all region data from the regions directory of the content root.
+    pub fn from_content(content: &Content) -> Self {
+        Self::from_directory(content, &content.path(Self::DIRECTORY))
+    }
+
+    /// Loads all region data from a directory within the content root, initializing the map.
+    pub fn from_directory(content: &Content, directory: &str) -> Self {
+        let (width, height, regions) = Self::load(content, directory);
+
+        let mut regions_map: HashMap<u8, Region> = HashMap::new();
+        let mut map: Vec<Vec<u8>> = vec![vec![0; height as usize]; width as usize]; // Adjusted for dynamic sizing
+
+        // Adjusted loop to account for regions defined by vertices
+        for (id, region) in regions.iter().enumerate() {
+            let id = id as u8;
+            regions_map.insert(id, region.clone());
+
+            let (min_x, min_y, _) = region.bounding_box().top_left_3d().as_tuple();
+            let (max_x, max_y, _) = region.bounding_box().bottom_right_3d().as_tuple();
+            let (width, height) = region.bounding_box().dimensions().as_tuple();
+
+            // Iterate over each row in the bounding box, adjusting the range as needed
+            for (x, row) in map
+                .iter_mut()
+                .enumerate()
+                .take(max_x as usize + 1)
+                .skip(min_x as usize)
+            {
+                // Iterate over each column in the row within the bounding box
+                for (y, cell) in row
+                    .iter_mut()
+                    .enumerate()
+                    .take(max_y as usize + 1)
+                    .skip(min_y as usize)
+                {
+                    if region.is_within(&Vec3::new(x as f64, y as f64, 0.0))
+                        && x < width as usize
+                        && y < height as usize
+                    {
+                        // Directly modify the cell as needed
+                        *cell = id;
+                    }
+                }
+            }
+        }
+
+        Self {
+            regions: regions_map,
+            map,
+        }
+    }
+
+    /// Finds and returns the Region corresponding to the given Position.
+    pub fn get_region(&self, coord: &Vec3) -> Option<&Region> {
+        let (x, y, _z) = coord.as_tuple();
+
+        // Ensure the position is within the bounds of the map.
+        if x < 0. || y < 0. || x as usize >= self.map.len() || y as usize >= self.map[0].len() {
+            return None;
+        }
+
+        // Find and return the corresponding region from the regions HashMap.
+        self.regions.get(&self.map[x as usize][y as usize])
+    }
+
+    /// Obtains the id of the region corresponding to the given position.
+    pub fn get_region_id(&self, coord: &Vec3) -> Option<u8> {
+        let (x, y, _z) = coord.as_tuple();
+        if x < 0. || y < 0. || x as usize >= self.map.len() || y as usize >= self.map[0].len() {
+            return None;
+        }
+
+        let id = self.map[x as usize][y as usize];
+        self.regions.contains_key(&id).then_some(id)
+    }
+
+    /// Terrain at a coordinate, the ground if it is outside of every region.
+    pub fn terrain_at(&self, coord: &Vec3) -> Terrain {
+        self.get_region(coord)
+            .map_or(Terrain::Ground, |region| region.terrain_at(coord))
+    }
+
+    /// Layer of the ground at a coordinate, the lowest layer if it is outside of every region.
+    pub fn floor_at(&self, coord: &Vec3) -> f64 {
+        self.get_region(coord)
+            .map_or(GROUND_LAYER, |region| region.floor_at(coord))
+    }
+
+    /// Obtains a region by its id.
+    pub fn get(&self, id: u8) -> Option<&Region> {
+        self.regions.get(&id)
+    }
+
+    /// Obtains a region by its name, ignoring case.
+    pub fn find(&self, name: &str) -> Option<&Region> {
+        self.regions
+            .values()
+            .find(|region| region.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Where a portal overlapping the bounds sends players, if they are standing on one.
+    pub fn portal_destination(&self, bounds: &Bounds) -> Option<Vec3> {
+        self.regions
+            .values()
+            .flat_map(|region| region.portals.iter())
+            .find(|portal| portal.bounds().intersects_2d(bounds))
+            .and_then(|portal| self.find(&portal.destination))
+            .map(|region| region.spawn)
+    }
+
+    /// Obtains all loaded regions.
+    pub fn regions(&self) -> Vec<&Region> {
+        let mut regions: Vec<&Region> = self.regions.values().collect();
+        regions.sort_by(|a, b| a.name.cmp(&b.name));
+        regions
+    }
+
+    /// Furthest right and bottom edges reached by any region.
+    pub fn extent(&self) -> Vec2 {
+        let (right, bottom) = self.regions.values().map(Region::bounding_box).fold(
+            (0., 0.),
+            |(right, bottom): (f64, f64), b| {
+                (right.max(b.x() + b.width()), bottom.max(b.y() + b.height()))
+            },
+        );
+        Vec2::new(right, bottom)
+    }
+
+    /// Obtains a region by its id, or every region that is not instanced if None.
+    fn simulated(&self, region: Option<u8>) -> impl Iterator<Item = &Region> {
+        self.regions
+            .iter()
+            .filter(move |(id, other)| match region {
+                Some(region) => **id == region,
+                None => !other.instanced,
+            })
+            .map(|(_, region)| region)
+    }
+
+    /// Obtains the obstacles placed within a region, or within every region that is not instanced.
+    pub fn obstacles(&self, region: Option<u8>) -> Vec<ObstacleSpawn> {
+        self.simulated(region)
+            .flat_map(|region| region.obstacles.iter().cloned())
+            .collect()
+    }
+
+    /// Obtains the spawners placed within a region, or within every region that is not instanced.
+    pub fn spawners(&self, region: Option<u8>) -> Vec<SpawnerSpawn> {
+        self.simulated(region)
+            .flat_map(|region| region.spawners.iter().cloned())
+            .collect()
+    }
+
+    /// Obtains the resource nodes placed within a region, or within every region that is not instanced.
+    pub fn resources(&self, region: Option<u8>) -> Vec<ResourceSpawn> {
+        self.simulated(region)
+            .flat_map(|region| region.resources.iter().cloned())
+            .collect()
+    }
+
+    /// Obtains the housing plots within every region that is not instanced.
+    pub fn plots(&self) -> Vec<PlotSpawn> {
+        self.simulated(None)
+            .flat_map(|region| region.plots.iter().cloned())
+            .collect()
+    }
+
+    /// Obtains the flags placed within all regions.
+    pub fn flags(&self) -> Vec<FlagSpawn> {
+        self.regions
+            .values()
+            .flat_map(|region| region.flags.iter().cloned())
+            .collect()
+    }
+
+    /// Obtains the tiles of a chunk, None if the chunk is outside of the map.
+    pub fn chunk(&self, coord: &ChunkCoord) -> Option<Vec<u8>> {
+        let origin = tile_bounds(coord, 0);
+        if origin.x() as usize >= self.map.len() || origin.y() as usize >= self.map[0].len() {
+            return None;
+        }
+
+        // Sample the center of each tile, 0 is reserved for the void.
+        let tiles = (0..CHUNK_TILES * CHUNK_TILES)
+            .map(|index| {
+                let center = tile_bounds(coord, index).center_2d();
+                let coord = Vec3::new(center.x(), center.y(), 0.);
+                match self.get_region(&coord) {
+                    Some(region) if region.is_within(&coord) => {
+                        let tile = self.map[coord.x() as usize][coord.y() as usize] + 1;
+                        match region.terrain_at(&coord) {
+                            Terrain::Water => tile | WATER,
+                            Terrain::Ground => tile,
+                        }
+                    }
+                    _ => VOID,
+                }
+            })
+            .collect();
+
+        Some(tiles)
+    }
+
+    /// Loads all regions based on the `.*yaml` file extension, skipping those that fail.
+    /// Regions within the packs replace any of the same name.
+    fn load(content: &Content, path: &str) -> (f64, f64, Vec<Region>) {
+        let mut regions: Vec<Region> = Vec::new();
+        for file_path in content.layered_yaml_files(path).iter() {
+            match Region::load(content, file_path) {
+                Ok(region) => match regions.iter_mut().find(|other| other.name == region.name) {
+                    Some(other) => *other = region,
+                    None => regions.push(region),
+                },
+                Err(why) => sprintln!("Error while loading {}: {}", why.path(), why),
+            }
+        }
+
+        // Update max_width and max_height based on the region's vertices
+        let mut max_width = 0.;
+        let mut max_height = 0.;
+        for region in regions.iter() {
+            let (max_x, max_y, _) = region.bounding_box().bottom_right_3d().as_tuple();
+            if max_x > max_width {
+                max_width = max_x;
+            }
+            if max_y > max_height {
+                max_height = max_y;
+            }
+        }
+
+        (max_width, max_height, regions)
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::components::Position;
+use uo2d_proto::ecs::{Entity, World};
+use uuid::Uuid;
+
+use crate::config::{ReplicationBand, ReplicationConfig};
+
+/// When movement of an entity was last sent to a player.
+#[derive(Debug, Clone, Copy)]
+struct Sent {
+    tick: u64,
+    /// Moved since, the player still having to be sent where it ended up.
+    stale: bool,
+}
+
+/// Sends the movement of an entity to players less often the further they are from it.
+#[derive(Debug)]
+pub struct Replicator {
+    /// Sorted nearest first.
+    bands: Vec<ReplicationBand>,
+    tick: u64,
+    /// Multiplies the ticks between movement within a band, raised while shedding load.
+    scale: u64,
+    sent: HashMap<Entity, HashMap<Uuid, Sent>>,
+}
+
+impl Default for Replicator {
+    fn default() -> Self {
+        Self::new(&ReplicationConfig::default())
+    }
+}
+
+impl Replicator {
+    pub fn new(config: &ReplicationConfig) -> Self {
+        let mut bands = config.bands.clone();
+        bands.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        Self {
+            bands,
+            tick: 0,
+            scale: 1,
+            sent: HashMap::new(),
+        }
+    }
+
+    /// Starts a tick, multiplying the ticks between movement within a band by the scale.
+    pub fn advance(&mut self, tick: u64, scale: u64) {
+        self.tick = tick;
+        self.scale = scale.max(1);
+    }
+
+    /// Ticks between movement sent to a player at the distance, every tick closer than any band.
+    pub fn interval(&self, distance: f64) -> u64 {
+        self.bands
+            .iter()
+            .rev()
+            .find(|band| distance >= band.distance)
+            .map_or(1, |band| band.ticks.max(1) * self.scale)
+    }
+
+    /// Players due the movement of the entity this tick, from the observers and their distance.
+    /// Keyframes are sent to every observer so that the deltas that follow can be applied.
+    pub fn due(
+        &mut self,
+        entity: Entity,
+        observers: &[(Uuid, f64)],
+        keyframe: bool,
+    ) -> HashSet<Uuid> {
+        if observers.is_empty() {
+            return HashSet::new();
+        }
+
+        let tick = self.tick;
+        let intervals: Vec<u64> = observers
+            .iter()
+            .map(|(_, distance)| self.interval(*distance))
+            .collect();
+        let sent = self.sent.entry(entity).or_default();
+
+        let mut due = HashSet::new();
+        for ((uuid, _), interval) in observers.iter().zip(intervals) {
+            let last = sent.get(uuid);
+            if keyframe || last.is_none_or(|last| tick.saturating_sub(last.tick) >= interval) {
+                sent.insert(*uuid, Sent { tick, stale: false });
+                due.insert(*uuid);
+            } else if let Some(last) = sent.get_mut(uuid) {
+                last.stale = true;
+            }
+        }
+        due
+    }
+
+    /// Observers that skipped the last movement of an entity that stopped, owed where it ended up.
+    pub fn settle(&mut self, entity: &Entity, observers: &HashSet<Uuid>) -> HashSet<Uuid> {
+        let tick = self.tick;
+        let Some(sent) = self.sent.get_mut(entity) else {
+            return HashSet::new();
+        };
+
+        let mut behind = HashSet::new();
+        for (uuid, last) in sent.iter_mut() {
+            if last.stale && observers.contains(uuid) {
+                *last = Sent { tick, stale: false };
+                behind.insert(*uuid);
+            }
+        }
+        behind
+    }
+
+    /// Number of entities movement is being tracked for.
+    pub fn tracked(&self) -> usize {
+        self.sent.len()
+    }
+
+    /// Forgets the entities that no longer exist.
+    pub fn prune(&mut self, world: &World) {
+        self.sent
+            .retain(|entity, _| world.get_component::<Position>(entity).is_some());
+    }
+}
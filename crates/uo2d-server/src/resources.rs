@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use uo2d_proto::components::ItemId;
+use uo2d_proto::locale::Text;
+use uo2d_proto::sprintln;
+
+use crate::content::Content;
+
+/// Type of resource node placed by the regions, loaded from the resource data.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ResourceDefinition {
+    pub kind: String,
+    /// Item yielded by gathering from it.
+    pub item: ItemId,
+    /// Most of the item yielded at once, reached as the gathering skill grows.
+    #[serde(default = "ResourceDefinition::default_amount")]
+    pub amount: u16,
+    /// Gathers before it is depleted.
+    pub charges: u32,
+    /// Seconds before a depleted node can be gathered from again.
+    pub respawn: f32,
+    /// Gathering level required.
+    #[serde(default)]
+    pub level: u32,
+    /// Experience earned each time it is gathered from.
+    pub experience: u32,
+    /// Loot table rolled for extra finds each time it is gathered from.
+    #[serde(default)]
+    pub loot: Option<String>,
+}
+
+impl ResourceDefinition {
+    /// Resources that do not specify their yield give one at a time.
+    fn default_amount() -> u16 {
+        1
+    }
+
+    /// Name shown to the players, translated from the world strings.
+    pub fn text(&self) -> Text {
+        Text::new(format!("resources.{}", self.kind))
+    }
+}
+
+/// Manages the definitions for all types of resource nodes.
+pub struct ResourceManager {
+    resources: HashMap<String, ResourceDefinition>,
+}
+
+impl Default for ResourceManager {
+    fn default() -> Self {
+        Self::new(&Content::default())
+    }
+}
+
+impl ResourceManager {
+    /// File of the definitions, within the content root.
+    pub const PATH: &'static str = "resources.yaml";
+
+    /// Loads all resource definitions at launch, those within the packs replacing any of the same kind.
+    pub fn new(content: &Content) -> Self {
+        let mut resources = HashMap::new();
+        for path in content.layers(&content.path(Self::PATH)) {
+            match content.parse::<Vec<ResourceDefinition>>(&path) {
+                Ok(definitions) => resources.extend(
+                    definitions
+                        .into_iter()
+                        .map(|resource| (resource.kind.clone(), resource)),
+                ),
+                Err(why) => sprintln!("Error while loading {}: {}", path, why),
+            }
+        }
+
+        Self { resources }
+    }
+
+    /// Obtains the definition for a type of resource node.
+    pub fn get(&self, kind: &str) -> Option<&ResourceDefinition> {
+        self.resources.get(kind)
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uo2d_proto::components::{ItemStack, Progress, Team};
+use uo2d_proto::packet::payloads::RedirectPayload;
+use uo2d_proto::sprintln;
+use uo2d_proto::util::get_now;
+use uuid::Uuid;
+
+use crate::accounts::Character;
+use crate::bus::{Bus, BusMessage};
+use crate::config::{ShardConfig, ShardPeer};
+
+/// Belongings a player keeps when moving between servers, beyond those saved with the character.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Carried {
+    pub team: Option<Team>,
+    #[serde(default)]
+    pub progress: Progress,
+    #[serde(default)]
+    pub bank: Vec<ItemStack>,
+}
+
+/// State of a player being moved to another server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Handoff {
+    /// Presented by the client to claim the player on the receiving server.
+    pub token: Uuid,
+    /// Username of the account, each server finding it within their own database.
+    pub account: Option<String>,
+    pub character: Character,
+    pub carried: Carried,
+}
+
+/// Something from the other servers the gamestate has to act on.
+pub enum ShardEvent {
+    /// A player was accepted by another server, their client has to follow.
+    Redirect(Uuid, RedirectPayload),
+    /// Another server did not take a player in time, they have to be restored.
+    Failed(Uuid, Handoff),
+    /// Chat from a player on another server.
+    Chat(Uuid, String),
+}
+
+/// Another server as seen from this one.
+#[derive(Debug, Clone)]
+pub struct ShardStatus {
+    pub name: String,
+    pub connected: bool,
+    /// Players online there, None until it has reported them.
+    pub online: Option<u32>,
+}
+
+/// Player being sent to another server.
+struct Outgoing {
+    uuid: Uuid,
+    handoff: Handoff,
+    /// Address their client is redirected to.
+    address: String,
+    expires: u64,
+}
+
+/// Hands players off to the servers owning the rest of the world, and accepts those sent here.
+pub struct Shards {
+    config: ShardConfig,
+    bus: Bus,
+    /// Handoffs waiting for the other server to accept them, by their token.
+    outgoing: HashMap<Uuid, Outgoing>,
+    /// Handoffs waiting for their client to connect, with when they expire.
+    pending: HashMap<Uuid, (Handoff, u64)>,
+    /// Players online on each of the other servers.
+    presence: HashMap<String, u32>,
+    /// Players last reported as online here, and when.
+    reported: Option<(u32, u64)>,
+}
+
+impl Shards {
+    /// Seconds a client has to connect after being handed off.
+    const CLAIM_TIMEOUT: u64 = 30;
+    /// Seconds given to another server to accept a handoff.
+    const DELIVERY_TIMEOUT: u64 = 5;
+    /// Seconds between reports of the players online, even if unchanged.
+    const PRESENCE_INTERVAL: u64 = 30;
+
+    /// Creates the shards, linking to the other servers.
+    /// Must be called from within a runtime.
+    pub fn start(config: ShardConfig) -> Self {
+        Self {
+            bus: Bus::start(&config),
+            config,
+            outgoing: HashMap::new(),
+            pending: HashMap::new(),
+            presence: HashMap::new(),
+            reported: None,
+        }
+    }
+
+    /// Server owning a region, None if it is simulated here.
+    pub fn owner(&self, region: &str) -> Option<&ShardPeer> {
+        self.config.peers.iter().find(|peer| peer.owns(region))
+    }
+
+    /// Sends a player to another server, their client is redirected once it has been accepted.
+    pub fn send(&mut self, peer: &ShardPeer, uuid: Uuid, handoff: Handoff) {
+        let token = handoff.token;
+        if !self
+            .bus
+            .send(&peer.name, BusMessage::Handoff(handoff.clone()))
+        {
+            sprintln!("Unable to hand off {}, {} is not a peer.", uuid, peer.name);
+        }
+
+        self.outgoing.insert(
+            token,
+            Outgoing {
+                uuid,
+                handoff,
+                address: peer.address.clone(),
+                expires: get_now() + Self::DELIVERY_TIMEOUT,
+            },
+        );
+    }
+
+    /// Shares a chat message with the players on the other servers.
+    pub fn chat(&self, uuid: Uuid, message: String) {
+        self.bus.publish(BusMessage::Chat { uuid, message });
+    }
+
+    /// Reports the players online here once it changes, or every so often for new links.
+    pub fn presence(&mut self, online: u32) {
+        let now = get_now();
+        let due = match self.reported {
+            Some((reported, at)) => reported != online || now - at >= Self::PRESENCE_INTERVAL,
+            None => true,
+        };
+
+        if due {
+            self.bus.publish(BusMessage::Presence { online });
+            self.reported = Some((online, now));
+        }
+    }
+
+    /// Processes the messages from the other servers, returning what the gamestate has to act on.
+    pub fn update(&mut self) -> Vec<ShardEvent> {
+        let now = get_now();
+        self.pending.retain(|_, (_, expires)| *expires > now);
+
+        let mut events = vec![];
+        for (server, message) in self.bus.receive() {
+            match message {
+                BusMessage::Handoff(handoff) => {
+                    self.bus.send(&server, BusMessage::Accepted(handoff.token));
+                    let expires = now + Self::CLAIM_TIMEOUT;
+                    self.pending.insert(handoff.token, (handoff, expires));
+                }
+                BusMessage::Accepted(token) => {
+                    if let Some(outgoing) = self.outgoing.remove(&token) {
+                        let payload = RedirectPayload::new(outgoing.address, token);
+                        events.push(ShardEvent::Redirect(outgoing.uuid, payload));
+                    }
+                }
+                BusMessage::Chat { uuid, message } => events.push(ShardEvent::Chat(uuid, message)),
+                BusMessage::Presence { online } => {
+                    self.presence.insert(server, online);
+                }
+                BusMessage::Hello { .. } => (),
+            }
+        }
+
+        // Handoffs that were never accepted return the player here.
+        let expired: Vec<Uuid> = self
+            .outgoing
+            .iter()
+            .filter(|(_, outgoing)| outgoing.expires <= now)
+            .map(|(token, _)| *token)
+            .collect();
+        for token in expired {
+            if let Some(outgoing) = self.outgoing.remove(&token) {
+                sprintln!("Handoff of {} was not accepted in time.", outgoing.uuid);
+                events.push(ShardEvent::Failed(outgoing.uuid, outgoing.handoff));
+            }
+        }
+
+        events
+    }
+
+    /// Claims the player handed off with the token.
+    pub fn claim(&mut self, token: &Uuid) -> Option<Handoff> {
+        self.pending
+            .remove(token)
+            .filter(|(_, expires)| *expires > get_now())
+            .map(|(handoff, _)| handoff)
+    }
+
+    /// Stops tracking a player that has left, they have nothing to be returned to.
+    pub fn forget(&mut self, uuid: &Uuid) {
+        self.outgoing.retain(|_, outgoing| outgoing.uuid != *uuid);
+    }
+
+    /// State of every other server.
+    pub fn status(&self) -> Vec<ShardStatus> {
+        self.config
+            .peers
+            .iter()
+            .map(|peer| ShardStatus {
+                name: peer.name.clone(),
+                connected: self.bus.is_connected(&peer.name),
+                online: self.presence.get(&peer.name).copied(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use uo2d_proto::components::{Acceleration, Bounds, Collidable, Position, Vec2, Vec3, Velocity};
+use uo2d_proto::components::{Obstacle, Player, Projectile, Pushable};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::sprintln;
+
+use crate::config::ContentConfig;
+use crate::content::Content;
+use crate::delta::DeltaEncoder;
+use crate::net::Net;
+use crate::region::RegionManager;
+use crate::replication::Replicator;
+use crate::spatial_hash::SpatialHash;
+use crate::systems::{self, movement, physics};
+
+/// Cell size of the spatial hash, matching the one used by the game.
+const CELL_SIZE: usize = 32;
+/// Size of every synthetic entity, small enough to overlap at most four cells.
+const ENTITY_SIZE: f64 = 6.;
+/// Attempts at finding a free spot before an entity is placed regardless.
+const PLACEMENT_ATTEMPTS: u64 = 32;
+/// Entities replaced with new ones each tick, exercising spawning and despawning.
+const CHURN: usize = 8;
+/// Ticks between new headings for an entity.
+const HEADING_TICKS: u64 = 30;
+/// Time between progress reports.
+const REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Moves synthetic entities around without any network IO, checking the world stays sane.
+pub struct Soak {
+    regions: String,
+    content: ContentConfig,
+    entities: usize,
+    duration: Duration,
+}
+
+/// Summary of a soak test that held every invariant.
+#[derive(Debug, Clone)]
+pub struct SoakReport {
+    pub ticks: u64,
+    pub entities: usize,
+    pub elapsed: Duration,
+    /// Longest a single tick took, invariant checks excluded.
+    pub slowest: Duration,
+    /// Most cells the spatial hash held at once.
+    pub cells: usize,
+}
+
+impl fmt::Display for SoakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let average = self.elapsed.as_secs_f64() * 1000. / self.ticks.max(1) as f64;
+        write!(
+            f,
+            "Soak passed: {} ticks with {} entities over {:.1}s, {:.3}ms per tick ({:.3}ms slowest), {} cells.",
+            self.ticks,
+            self.entities,
+            self.elapsed.as_secs_f64(),
+            average,
+            self.slowest.as_secs_f64() * 1000.,
+            self.cells
+        )
+    }
+}
+
+/// World being soaked along with what the movement systems need.
+struct Simulation {
+    world: World,
+    spatial: SpatialHash,
+    regions: RegionManager,
+    items: ItemManager,
+    deltas: DeltaEncoder,
+    replicator: Replicator,
+    /// Area the entities are spawned within.
+    area: Bounds,
+    /// Most cells the regions can be split into, the hash never needs more.
+    max_cells: usize,
+    /// Entities spawned so far, seeding where the next one is placed.
+    spawned: u64,
+    tick: u64,
+}
+
+impl Soak {
+    pub fn new(regions: &str) -> Self {
+        Self {
+            regions: regions.to_string(),
+            content: ContentConfig::default(),
+            entities: 2_000,
+            duration: Duration::from_secs(60),
+        }
+    }
+
+    /// Content root the regions and items are loaded from within.
+    pub fn content(mut self, config: ContentConfig) -> Self {
+        self.content = config;
+        self
+    }
+
+    /// Number of entities kept moving.
+    pub fn entities(mut self, entities: usize) -> Self {
+        self.entities = entities;
+        self
+    }
+
+    /// How long the test runs for.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Ticks as fast as possible until the duration passes, failing on the first broken invariant.
+    pub fn run(self) -> Result<SoakReport, String> {
+        let mut sim = Simulation::new(&Content::new(&self.content), &self.regions)?;
+        for _ in 0..self.entities {
+            sim.spawn();
+        }
+        sprintln!(
+            "Soaking {} entities for {}s.",
+            self.entities,
+            self.duration.as_secs()
+        );
+
+        let mut report = SoakReport {
+            ticks: 0,
+            entities: self.entities,
+            elapsed: Duration::ZERO,
+            slowest: Duration::ZERO,
+            cells: 0,
+        };
+        let start = Instant::now();
+        let mut last_report = start;
+        while start.elapsed() < self.duration {
+            let tick_start = Instant::now();
+            sim.step();
+            report.slowest = report.slowest.max(tick_start.elapsed());
+            report.ticks += 1;
+
+            sim.check(self.entities)
+                .map_err(|why| format!("Soak failed on tick {}: {}", sim.tick, why))?;
+            report.cells = report.cells.max(sim.spatial.cell_count());
+
+            if last_report.elapsed() >= REPORT_INTERVAL {
+                last_report = Instant::now();
+                sprintln!(
+                    "Soak: {} ticks, {} cells, {} hash entries.",
+                    report.ticks,
+                    sim.spatial.cell_count(),
+                    sim.spatial.entry_count()
+                );
+            }
+        }
+
+        report.elapsed = start.elapsed();
+        Ok(report)
+    }
+}
+
+impl Simulation {
+    fn new(content: &Content, directory: &str) -> Result<Self, String> {
+        let regions = RegionManager::from_directory(content, directory);
+        let bounds: Vec<Bounds> = regions
+            .regions()
+            .into_iter()
+            .map(|region| region.bounding_box())
+            .collect();
+        let area = bounds
+            .iter()
+            .max_by(|a, b| (a.width() * a.height()).total_cmp(&(b.width() * b.height())))
+            .copied()
+            .ok_or_else(|| format!("no regions found in {}", directory))?;
+        let extent = regions.extent();
+
+        let mut world = World::new();
+        world.register_component::<Position>();
+        world.register_component::<Velocity>();
+        world.register_component::<Acceleration>();
+        world.register_component::<Collidable>();
+        world.register_component::<Projectile>();
+        world.register_component::<Pushable>();
+        world.register_component::<Obstacle>();
+        world.register_component::<Player>();
+        world.group::<Position, Velocity>();
+
+        let columns = extent.x() as usize / CELL_SIZE + 1;
+        let rows = extent.y() as usize / CELL_SIZE + 1;
+        Ok(Self {
+            world,
+            spatial: SpatialHash::bounded(CELL_SIZE, extent),
+            regions,
+            items: ItemManager::from_root(&content.root().to_string_lossy()),
+            deltas: DeltaEncoder::new(),
+            replicator: Replicator::default(),
+            area,
+            max_cells: columns * rows,
+            spawned: 0,
+            tick: 0,
+        })
+    }
+
+    /// Places a new entity at a free spot in the area, if one is found quickly.
+    fn spawn(&mut self) {
+        let size = Vec2::new(ENTITY_SIZE, ENTITY_SIZE);
+        self.spawned += 1;
+        let seed = Entity::new(self.spawned);
+        let mut loc = self.area.top_left_3d();
+        for attempt in 0..PLACEMENT_ATTEMPTS {
+            let roll = systems::roll(&seed, attempt);
+            let x = (roll % 10_000) as f64 / 10_000.;
+            let y = ((roll >> 16) % 10_000) as f64 / 10_000.;
+            loc = Vec3::new(
+                self.area.x() + x * (self.area.width() - ENTITY_SIZE),
+                self.area.y() + y * (self.area.height() - ENTITY_SIZE),
+                1.,
+            );
+
+            let free = self
+                .spatial
+                .query(&Bounds::from_vec(loc, size), None)
+                .is_empty();
+            if free && self.regions.get_region(&loc).is_some() {
+                break;
+            }
+        }
+
+        let entity = self
+            .world
+            .spawn()
+            .with(Position::new(loc, size))
+            .with(Collidable)
+            .build();
+        self.spatial
+            .insert_object(&entity, &Bounds::from_vec(loc, size));
+    }
+
+    /// Removes an entity from the world and the spatial hash.
+    fn despawn(&mut self, entity: &Entity) {
+        if let Some(pos) = self.world.get_component::<Position>(entity) {
+            let bounds = pos.bounds();
+            self.spatial.remove_object(entity, &bounds);
+        }
+        self.world.despawn(entity);
+    }
+
+    /// Steers the entities, replaces a few, and runs the movement systems for a tick.
+    fn step(&mut self) {
+        self.tick += 1;
+        self.replicator.advance(self.tick, 1);
+
+        let mut inputs = vec![];
+        let mut replaced = vec![];
+        for (entity, _) in self.world.query1::<Position>() {
+            if !(self.tick + entity.id()).is_multiple_of(HEADING_TICKS) {
+                continue;
+            }
+
+            let roll = systems::roll(&entity, self.tick);
+            if replaced.len() < CHURN && roll.is_multiple_of(50) {
+                replaced.push(entity);
+            } else {
+                let angle = ((roll % 360) as f64).to_radians();
+                inputs.push((entity, Vec2::new(angle.cos(), angle.sin()).scaled(4.)));
+            }
+        }
+
+        for entity in replaced.iter() {
+            self.despawn(entity);
+            self.spawn();
+        }
+        for (entity, heading) in inputs.into_iter() {
+            if self.world.get_component::<Position>(&entity).is_some() {
+                self.world.upsert_component(entity, Acceleration(heading));
+            }
+        }
+
+        // Packets are built as normal but never sent anywhere.
+        let mut net = Net::new();
+        physics::step(&mut self.world, &self.regions);
+        movement::with_velocity(
+            &mut self.world,
+            &mut self.spatial,
+            &self.regions,
+            &self.items,
+            &mut self.deltas,
+            &mut self.replicator,
+            &mut net,
+            0.,
+        );
+        movement::separate(
+            &mut self.world,
+            &mut self.spatial,
+            &self.regions,
+            &mut self.deltas,
+            &mut net,
+        );
+        net.drain(&self.world, &self.spatial);
+        self.deltas.prune(&self.world);
+        self.replicator.prune(&self.world);
+    }
+
+    /// Checks the positions are numbers, the spatial hash agrees with them, and nothing grows unbounded.
+    fn check(&self, entities: usize) -> Result<(), String> {
+        let positions: HashMap<Entity, Bounds> = self
+            .world
+            .query1::<Position>()
+            .into_iter()
+            .map(|(entity, pos)| (entity, pos.bounds()))
+            .collect();
+
+        for (entity, pos) in self.world.query1::<Position>() {
+            let (x, y, z) = pos.loc.as_tuple();
+            if !x.is_finite() || !y.is_finite() || !z.is_finite() {
+                return Err(format!(
+                    "entity {} is at ({}, {}, {})",
+                    entity.id(),
+                    x,
+                    y,
+                    z
+                ));
+            }
+        }
+        for (entity, vel) in self.world.query1::<Velocity>() {
+            let (x, y) = vel.0.as_tuple();
+            if !x.is_finite() || !y.is_finite() {
+                return Err(format!(
+                    "entity {} has velocity ({}, {})",
+                    entity.id(),
+                    x,
+                    y
+                ));
+            }
+        }
+
+        let divergence = self.spatial.diverged(&positions);
+        if !divergence.is_empty() {
+            return Err(format!(
+                "spatial hash diverged, {} entities missing and {} stale",
+                divergence.missing.len(),
+                divergence.stale.len()
+            ));
+        }
+
+        if positions.len() != entities {
+            return Err(format!(
+                "expected {} entities, found {}",
+                entities,
+                positions.len()
+            ));
+        }
+        if self.spatial.entry_count() > entities * 4 {
+            return Err(format!(
+                "spatial hash holds {} entries for {} entities",
+                self.spatial.entry_count(),
+                entities
+            ));
+        }
+        if self.spatial.cell_count() > self.max_cells {
+            return Err(format!(
+                "spatial hash grew to {} cells, the area only has {}",
+                self.spatial.cell_count(),
+                self.max_cells
+            ));
+        }
+        if self.deltas.tracked() > entities {
+            return Err(format!(
+                "delta encoder tracks {} keyframes for {} entities",
+                self.deltas.tracked(),
+                entities
+            ));
+        }
+        if self.replicator.tracked() > entities {
+            return Err(format!(
+                "replicator tracks {} entities for {} entities",
+                self.replicator.tracked(),
+                entities
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,589 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::watch;
+use tokio::time::{interval, sleep};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{UuidPayload, VersionPayload};
+use uo2d_proto::packet::{
+    parse_handshake, Action, BroadcastScope, Handshake, Packet, PacketConfiguration, Payload,
+    Reliability, Version, MAX_DATAGRAM, PACKET_VERSION,
+};
+use uo2d_proto::shutdown;
+use uo2d_proto::sprintln;
+use uo2d_proto::util::get_now;
+use uuid::Uuid;
+
+use crate::anticheat::AntiCheat;
+use crate::cache::{ClientCache, PacketCacheAsync};
+use crate::config::NetworkConfig;
+use crate::event_log::{self, ServerEvent};
+use crate::middleware::{Inbound, Pipeline, RateLimit};
+use crate::outbox::Outbox;
+use crate::packet_processor::{admit, process_packet, Handlers};
+use crate::Client;
+
+/// Missed heartbeats before a client is dropped.
+const MAX_MISSED_HEARTBEATS: u64 = 3;
+/// Heartbeats a client has to join the world within before they are dropped.
+const HANDSHAKE_HEARTBEATS: u64 = 2;
+
+/// Server instance responsible for managing clients and send/recving updates.
+pub struct SocketServer {
+    /// The socket to send and receive from.
+    socket: UdpSocket,
+    /// Current active clients.
+    client_cache: ClientCache,
+    /// Cached packets for the gamestate.
+    packet_cache: PacketCacheAsync,
+    /// Violations of the clients, shared with the gamestate.
+    anticheat: AntiCheat,
+    /// Network settings, updated when the configuration is reloaded.
+    network: watch::Receiver<NetworkConfig>,
+    /// Packets held back until the end of the tick.
+    outbox: Mutex<Outbox>,
+    /// Critical packets sent to each client until acknowledged, and those received from them.
+    reliability: Mutex<HashMap<SocketAddr, Reliability>>,
+    /// Handlers for the packets clients send.
+    handlers: Handlers,
+    /// Checks the packets clients send pass before being handled.
+    pipeline: Pipeline,
+    /// Shared with the pipeline, updated when the configuration is reloaded.
+    rate_limit: Arc<RateLimit>,
+}
+
+impl SocketServer {
+    fn new(
+        socket: UdpSocket,
+        packet_cache: PacketCacheAsync,
+        anticheat: AntiCheat,
+        network: watch::Receiver<NetworkConfig>,
+        plugin_actions: &[Action],
+    ) -> Self {
+        let rate_limit = {
+            let network = network.borrow();
+            Arc::new(RateLimit::new(
+                network.max_packet_rate,
+                network.packet_burst,
+            ))
+        };
+        let pipeline = Pipeline::server();
+
+        Self {
+            socket,
+            client_cache: ClientCache::new(),
+            packet_cache,
+            anticheat,
+            network,
+            outbox: Mutex::new(Outbox::new()),
+            reliability: Mutex::new(HashMap::new()),
+            handlers: Handlers::new().forwarding(plugin_actions),
+            pipeline,
+            rate_limit,
+        }
+    }
+
+    /// Starts the server for listening for incoming connections.
+    pub async fn start(
+        socket: UdpSocket,
+        receiver: Receiver<PacketConfiguration>,
+        cache: PacketCacheAsync,
+        anticheat: AntiCheat,
+        network: watch::Receiver<NetworkConfig>,
+        shutdown: watch::Receiver<bool>,
+        plugin_actions: Vec<Action>,
+    ) -> Result<(), Box<dyn Error>> {
+        sprintln!("Listening on {}", socket.local_addr()?);
+
+        let server = Self::new(socket, cache, anticheat, network.clone(), &plugin_actions);
+        server.async_main(receiver, network, shutdown).await
+    }
+
+    async fn async_main(
+        &self,
+        mut gamestate_rx: Receiver<PacketConfiguration>,
+        mut network: watch::Receiver<NetworkConfig>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Result<(), Box<dyn Error>> {
+        // Channels for send/recving meessages from packet processor.
+        let (mut handler_tx, mut handler_rx) = mpsc::channel::<Vec<u8>>(100);
+
+        let mut buf = vec![0; MAX_DATAGRAM];
+        let mut heartbeat = network.borrow().heartbeat.max(1);
+        let mut ping_interval = interval(Duration::from_secs(heartbeat));
+        let mut resend_interval = interval(Reliability::RESEND_AFTER);
+
+        let signal = shutdown::signal();
+        tokio::pin!(signal);
+
+        'listener: loop {
+            tokio::select! {
+                // Obtains data from the socket.
+                result = self.socket.recv_from(&mut buf) => self.client_receiver(&mut buf, result, &mut handler_tx).await,
+                // Sends the heartbeat to all clients.
+                _ = ping_interval.tick() => self.send_heartbeat(heartbeat).await,
+                // Sends the critical packets that have not been acknowledged again.
+                _ = resend_interval.tick() => self.resend().await,
+                // Packet from the gamestate that gets forwarded to clients.
+                packet = gamestate_rx.recv() => self.gamestate_receiver(packet).await,
+                // Message from the packet processor, updates user last ping status.
+                packet = handler_rx.recv() => self.packet_processor_receiver(packet).await,
+                // Configuration was reloaded, restart the heartbeat if it changed.
+                Ok(()) = network.changed() => {
+                    let (updated, rate, burst) = {
+                        let network = network.borrow_and_update();
+                        (network.heartbeat.max(1), network.max_packet_rate, network.packet_burst)
+                    };
+                    self.rate_limit.reconfigure(rate, burst);
+                    if updated != heartbeat {
+                        heartbeat = updated;
+                        ping_interval = interval(Duration::from_secs(heartbeat));
+                    }
+                }
+                // Shutdown signal received.
+                _ = &mut signal => break 'listener,
+                _ = shutdown.changed() => break 'listener,
+            }
+        }
+
+        self.shutdown().await;
+        Ok(())
+    }
+
+    /// Handles packet data coming from remote clients.
+    async fn client_receiver(
+        &self,
+        buf: &mut [u8],
+        result: Result<(usize, SocketAddr), std::io::Error>,
+        handler_tx: &mut Sender<Vec<u8>>,
+    ) {
+        if let Ok((size, addr)) = result {
+            // Clients open with the handshake until the server has replied to them.
+            let data = &buf[..size];
+            let handshake = parse_handshake(data);
+            if let Some(handshake) = &handshake {
+                if !self.compatible(handshake) {
+                    self.refuse_version(addr, handshake).await;
+                    return;
+                }
+            }
+            let stripped = handshake.map(|handshake| handshake.packet);
+
+            let (uuid, data) = if let Some(uuid) = self.client_cache.get_uuid(&addr).await {
+                (uuid, stripped.unwrap_or(data))
+            } else {
+                // Anything not speaking the protocol is ignored before it is tracked.
+                let data = match stripped {
+                    Some(data) => data,
+                    None => return,
+                };
+
+                // Register a new client, nothing sent to or from the address before carrying over.
+                let uuid = Uuid::new_v4();
+                self.forget(&addr);
+                self.client_cache.add(Client::new(uuid, addr)).await;
+                event_log::record(ServerEvent::Connect { uuid, addr });
+                (uuid, data)
+            };
+
+            let joined = self
+                .client_cache
+                .get(&uuid)
+                .await
+                .is_some_and(|client| client.joined);
+
+            // Floods are stopped before anything is unwrapped or remembered for them.
+            let inbound = Inbound::new(uuid, joined, Packet::from_bytes(data));
+            let Some(packet) = admit(&self.rate_limit, &self.anticheat, inbound) else {
+                if self.anticheat.should_kick(&uuid) {
+                    self.kick(uuid).await;
+                }
+                return;
+            };
+
+            // Acknowledgements and duplicates of critical packets stop here.
+            let received = self
+                .reliability
+                .lock()
+                .unwrap()
+                .entry(addr)
+                .or_default()
+                .receive(packet);
+            if let Some(ack) = received.ack {
+                if let Err(why) = self.write(&addr, ack.as_bytes().into(), false).await {
+                    sprintln!("Unable to acknowledge a packet from {}: {}.", uuid, why);
+                }
+            }
+
+            // Process the incoming packet from the client.
+            let Some(packet) = received.packet else {
+                return;
+            };
+
+            // Process and respond to the packet.
+            let packet_config = process_packet(
+                &self.pipeline,
+                &self.handlers,
+                &self.packet_cache,
+                &self.anticheat,
+                handler_tx,
+                Inbound::new(uuid, joined, packet),
+            )
+            .await;
+            self.send_configuration(packet_config).await;
+
+            if self.anticheat.should_kick(&uuid) {
+                self.kick(uuid).await;
+            }
+        }
+    }
+
+    /// Checks if a client speaks the protocol and is recent enough to join.
+    fn compatible(&self, handshake: &Handshake) -> bool {
+        let min = self.network.borrow().min_client_version();
+        handshake.protocol == PACKET_VERSION
+            && handshake.version.is_some_and(|version| version >= min)
+    }
+
+    /// Tells a client which version it has to update to, without tracking it.
+    async fn refuse_version(&self, addr: SocketAddr, handshake: &Handshake<'_>) {
+        let server = Version::current();
+        let mut min_client = self.network.borrow().min_client_version();
+        if handshake.protocol != PACKET_VERSION {
+            // Releases speaking another protocol have to catch up to this one.
+            min_client = min_client.max(server);
+        }
+
+        match handshake.version {
+            Some(version) => sprintln!("Refused {}: client version {} is too old.", addr, version),
+            None => sprintln!(
+                "Refused {}: protocol version {} mismatch.",
+                addr,
+                handshake.protocol
+            ),
+        }
+
+        let packet = Packet::new(
+            Action::VersionMismatch,
+            Uuid::nil(),
+            Payload::Version(VersionPayload::new(server, PACKET_VERSION, min_client)),
+        );
+        if let Err(why) = Self::exec_send(&self.socket, &addr, packet).await {
+            sprintln!(
+                "Unable to inform {} of the version mismatch: {}.",
+                addr,
+                why
+            );
+        }
+    }
+
+    /// Disconnects a client that has accumulated too many violations.
+    async fn kick(&self, uuid: Uuid) {
+        sprintln!("KICKED: {}", uuid);
+        let packet = Packet::new(
+            Action::Error,
+            uuid,
+            Payload::Text(Text::new("server.kicked")),
+        );
+        if let Err(why) = self.send_packet_to_uuid(&uuid, packet).await {
+            sprintln!("Unable to inform {} of the kick: {}.", uuid, why);
+        }
+
+        if let Some(client) = self.client_cache.remove(&uuid).await {
+            self.forget(&client.addr);
+        }
+        self.anticheat.forget(&uuid);
+        event_log::record(ServerEvent::Disconnect {
+            uuid,
+            reason: "kicked".to_string(),
+        });
+
+        // The gamestate removes them from the world.
+        let packet = Packet::new(Action::ClientLeave, uuid, Payload::Empty);
+        self.packet_cache.add(packet).await;
+    }
+
+    /// Handles packets coming from the local gamestate.
+    async fn gamestate_receiver(&self, packet: Option<PacketConfiguration>) {
+        if let Some(packet_config) = packet {
+            self.send_configuration(packet_config).await
+        }
+    }
+
+    /// Handles packets coming from the packet processor.
+    async fn packet_processor_receiver(&self, handler_message: Option<Vec<u8>>) {
+        if let Some(message) = handler_message {
+            let packet: Packet = Packet::from_bytes(&message);
+            if let Payload::Uuid(ping) = packet.payload() {
+                if let Some(client) = self.client_cache.lock().await.get_mut(&packet.uuid()) {
+                    if client.ping_id == ping.uuid {
+                        client.last_ping = get_now();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Broadcasts the server shutting down to all clients.
+    async fn shutdown(&self) {
+        sprintln!("Shutting down.");
+        let packet = Packet::new(
+            Action::Shutdown,
+            Uuid::nil(),
+            Payload::Text(Text::new("server.shutdown")),
+        );
+
+        self.packet_cache.add(packet.clone()).await;
+        if let Err(why) = self.broadcast(packet, None).await {
+            sprintln!("ERROR while shutting down: {}", why);
+        }
+        self.flush().await;
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    /// Sends a packet for the clients to respond to, ensures they are still alive.
+    async fn send_heartbeat(&self, heartbeat: u64) {
+        // UUID they must respond with.
+        let ping_id = Uuid::new_v4();
+
+        let ping_packet = Packet::new(
+            Action::Ping,
+            Uuid::new_v4(),
+            Payload::Uuid(UuidPayload::new(ping_id)),
+        );
+
+        // Update and clean the clients.
+        {
+            let mut expired: HashMap<Uuid, &str> = HashMap::new();
+            let clients = self.client_cache.clone();
+            let now = get_now();
+
+            for (_, client) in clients.lock().await.iter_mut() {
+                if now - client.last_ping > heartbeat * MAX_MISSED_HEARTBEATS {
+                    expired.insert(client.uuid, "timed out");
+                } else if !client.joined
+                    && now - client.connected > heartbeat * HANDSHAKE_HEARTBEATS
+                {
+                    // Answering pings is not enough, scanners and stalled logins are dropped too.
+                    expired.insert(client.uuid, "never joined");
+                } else {
+                    client.ping_id = ping_id;
+                }
+            }
+
+            // Remove the expired clients.
+            for (uuid, reason) in expired {
+                sprintln!("EXPIRED SESSION: {} ({})", uuid, reason);
+                if let Some(client) = clients.remove(&uuid).await {
+                    self.forget(&client.addr);
+                }
+                self.anticheat.forget(&uuid);
+                event_log::record(ServerEvent::Disconnect {
+                    uuid,
+                    reason: reason.to_string(),
+                });
+
+                let packet = Packet::new(Action::ClientLeave, uuid, Payload::Empty);
+                self.packet_cache.add(packet.clone()).await;
+                if let Err(why) = self.broadcast(packet, None).await {
+                    sprintln!("Unable to broadcast {} leaving: {}.", uuid, why);
+                }
+            }
+        }
+
+        // Send the heartbeat to all clients.
+        if let Err(why) = self.broadcast(ping_packet, None).await {
+            sprintln!("ERROR sending heartbeat: {}", why)
+        }
+    }
+
+    /// Sends a packet to the client with the uuid.
+    pub async fn send_packet_to_uuid(
+        &self,
+        uuid: &Uuid,
+        packet: Packet,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(client) = self.client_cache.get(uuid).await {
+            let urgent = Self::is_urgent(&packet);
+            let packet = self.prepare(&client.addr, packet);
+            self.write(&client.addr, packet.as_bytes().into(), urgent)
+                .await
+        } else {
+            Err("unable to find client".into())
+        }
+    }
+
+    /// Sends a packet to the client with the uuid.
+    pub async fn exec_send(
+        socket: &UdpSocket,
+        addr: &SocketAddr,
+        packet: Packet,
+    ) -> Result<usize, Box<dyn Error>> {
+        Self::exec_send_bytes(socket, addr, packet.as_bytes()).await
+    }
+
+    /// Sends an already serialized packet to an address.
+    async fn exec_send_bytes(
+        socket: &UdpSocket,
+        addr: &SocketAddr,
+        bytes: &[u8],
+    ) -> Result<usize, Box<dyn Error>> {
+        let sent_bytes = socket.send_to(bytes, addr).await?;
+        Ok(sent_bytes)
+    }
+
+    /// Checks if a packet is written immediately, instead of waiting for the end of the tick.
+    fn is_urgent(packet: &Packet) -> bool {
+        matches!(
+            packet.action(),
+            Some(
+                Action::Ping
+                    | Action::Echo
+                    | Action::Error
+                    | Action::Shutdown
+                    | Action::Redirect
+                    | Action::VersionMismatch
+            )
+        )
+    }
+
+    /// Queues a packet for an address, writing whatever can no longer wait.
+    async fn write(
+        &self,
+        addr: &SocketAddr,
+        bytes: Arc<[u8]>,
+        urgent: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let urgent = urgent || !self.network.borrow().aggregate;
+        let ready = self.outbox.lock().unwrap().push(*addr, bytes, urgent);
+        for datagram in ready {
+            Self::exec_send_bytes(&self.socket, addr, &datagram).await?;
+        }
+        Ok(())
+    }
+
+    /// Numbers a critical packet for the client, to be sent again until acknowledged.
+    fn prepare(&self, addr: &SocketAddr, packet: Packet) -> Packet {
+        if !Reliability::requires(packet.action()) {
+            return packet;
+        }
+
+        let mut reliability = self.reliability.lock().unwrap();
+        reliability
+            .entry(*addr)
+            .or_default()
+            .wrap(packet, Instant::now())
+    }
+
+    /// Drops the critical packets waiting on an address and the sequences received from it.
+    fn forget(&self, addr: &SocketAddr) {
+        self.reliability.lock().unwrap().remove(addr);
+    }
+
+    /// Sends the critical packets again that the clients have not acknowledged in time.
+    /// Clients that have disconnected are forgotten.
+    async fn resend(&self) {
+        let connected: HashSet<SocketAddr> =
+            self.client_cache.addrs(None).await.into_iter().collect();
+        let now = Instant::now();
+        let resend: Vec<(SocketAddr, Packet)> = {
+            let mut reliability = self.reliability.lock().unwrap();
+            reliability.retain(|addr, _| connected.contains(addr));
+            reliability
+                .iter_mut()
+                .flat_map(|(addr, reliability)| {
+                    let addr = *addr;
+                    reliability
+                        .resend(now)
+                        .into_iter()
+                        .map(move |packet| (addr, packet))
+                })
+                .collect()
+        };
+
+        for (addr, packet) in resend {
+            if let Err(why) = self.write(&addr, packet.as_bytes().into(), false).await {
+                sprintln!("Error while resending to client: {:?}", why.to_string());
+            }
+        }
+    }
+
+    /// Writes every packet held back for the clients.
+    async fn flush(&self) {
+        let ready = self.outbox.lock().unwrap().drain();
+        for (addr, datagram) in ready {
+            if let Err(why) = Self::exec_send_bytes(&self.socket, &addr, &datagram).await {
+                sprintln!("Error while flushing to client: {:?}", why.to_string());
+            }
+        }
+    }
+
+    /// Broadcasts a packet to multiple clients.
+    /// If filter is None, broadcast to all clients in `cache`.
+    /// If filter is Some and not empty, broadcast to only UUIDs in `cache`.
+    /// If filter is Some and empty, broadcast to nobody.
+    pub async fn broadcast(
+        &self,
+        packet: Packet,
+        filter: Option<HashSet<Uuid>>,
+    ) -> Result<(), Box<dyn Error>> {
+        // Get the addresses to send to.
+        let addrs = match &filter {
+            Some(uuids) if uuids.is_empty() => return Ok(()),
+            filter => self.client_cache.addrs(filter.as_ref()).await,
+        };
+
+        // The packet is serialized once, every client shares the same buffer.
+        // Critical packets are numbered for each client instead.
+        let urgent = Self::is_urgent(&packet);
+        let reliable = Reliability::requires(packet.action());
+        let bytes: Arc<[u8]> = packet.as_bytes().into();
+        for addr in addrs.iter() {
+            let bytes = match reliable {
+                true => self.prepare(addr, packet.clone()).as_bytes().into(),
+                false => Arc::clone(&bytes),
+            };
+            if let Err(why) = self.write(addr, bytes, urgent).await {
+                sprintln!("Error while broadcasting to client: {:?}", why.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends a packet configuration to clients.
+    async fn send_configuration(&self, packet_config: PacketConfiguration) {
+        let response = match packet_config {
+            PacketConfiguration::Empty => Ok(()),
+            PacketConfiguration::Flush => {
+                self.flush().await;
+                Ok(())
+            }
+            PacketConfiguration::Single(packet) => {
+                // The client has finished joining the world.
+                if packet.action() == Some(Action::Success) {
+                    if let Some(client) = self.client_cache.lock().await.get_mut(&packet.uuid()) {
+                        client.joined = true;
+                    }
+                }
+                self.send_packet_to_uuid(&packet.uuid(), packet).await
+            }
+            PacketConfiguration::Broadcast(packet, scope) => {
+                let clients: HashSet<Uuid> = match scope {
+                    BroadcastScope::Local(uuids) => uuids,
+                    BroadcastScope::Global => self.client_cache.keys().await,
+                };
+                self.broadcast(packet, Some(clients)).await
+            }
+        };
+
+        if let Err(why) = response {
+            sprintln!("FORWARD {}", why);
+        }
+    }
+}
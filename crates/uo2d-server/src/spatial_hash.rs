@@ -0,0 +1,507 @@
+#[cfg(feature = "bench")]
+use std::cell::Cell as Counter;
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::components::{Bounds, Position, Vec2, Vec3};
+use uo2d_proto::ecs::Entity;
+
+use crate::config::InterestConfig;
+use crate::systems::movement::MoveQuery;
+
+#[derive(Default)]
+struct Cell {
+    entities: HashSet<Entity>,
+}
+
+/// Where the cells of a hash are kept, by their coordinates.
+trait CellStore: Send {
+    fn get(&self, coords: (usize, usize)) -> Option<&Cell>;
+    fn get_mut(&mut self, coords: (usize, usize)) -> Option<&mut Cell>;
+    /// Obtains a cell, creating it the first time it is occupied.
+    fn get_or_create(&mut self, coords: (usize, usize)) -> &mut Cell;
+    /// Number of cells that have ever been occupied.
+    fn len(&self) -> usize;
+    /// Every cell that has been occupied, with its coordinates.
+    fn iter(&self) -> Box<dyn Iterator<Item = ((usize, usize), &Cell)> + '_>;
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Cell> + '_>;
+}
+
+/// Cells hashed by their coordinates, growing with the area occupied. Used for unbounded worlds.
+#[derive(Default)]
+struct SparseCells(HashMap<(usize, usize), Cell>);
+
+impl CellStore for SparseCells {
+    fn get(&self, coords: (usize, usize)) -> Option<&Cell> {
+        self.0.get(&coords)
+    }
+
+    fn get_mut(&mut self, coords: (usize, usize)) -> Option<&mut Cell> {
+        self.0.get_mut(&coords)
+    }
+
+    fn get_or_create(&mut self, coords: (usize, usize)) -> &mut Cell {
+        self.0.entry(coords).or_default()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = ((usize, usize), &Cell)> + '_> {
+        Box::new(self.0.iter().map(|(coords, cell)| (*coords, cell)))
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Cell> + '_> {
+        Box::new(self.0.values_mut())
+    }
+}
+
+/// Cells laid out in a flat array covering a bounded world, indexed by `y * columns + x` without
+/// hashing. Anything beyond the world falls back to hashed cells.
+struct DenseCells {
+    columns: usize,
+    rows: usize,
+    cells: Vec<Option<Cell>>,
+    occupied: usize,
+    outside: SparseCells,
+}
+
+impl DenseCells {
+    fn new(columns: usize, rows: usize) -> Self {
+        let mut cells = Vec::new();
+        cells.resize_with(columns * rows, || None);
+        Self {
+            columns,
+            rows,
+            cells,
+            occupied: 0,
+            outside: SparseCells::default(),
+        }
+    }
+
+    #[inline]
+    fn index(&self, (x, y): (usize, usize)) -> Option<usize> {
+        (x < self.columns && y < self.rows).then_some(y * self.columns + x)
+    }
+}
+
+impl CellStore for DenseCells {
+    fn get(&self, coords: (usize, usize)) -> Option<&Cell> {
+        match self.index(coords) {
+            Some(index) => self.cells[index].as_ref(),
+            None => self.outside.get(coords),
+        }
+    }
+
+    fn get_mut(&mut self, coords: (usize, usize)) -> Option<&mut Cell> {
+        match self.index(coords) {
+            Some(index) => self.cells[index].as_mut(),
+            None => self.outside.get_mut(coords),
+        }
+    }
+
+    fn get_or_create(&mut self, coords: (usize, usize)) -> &mut Cell {
+        let Some(index) = self.index(coords) else {
+            return self.outside.get_or_create(coords);
+        };
+
+        let cell = &mut self.cells[index];
+        if cell.is_none() {
+            self.occupied += 1;
+        }
+        cell.get_or_insert_with(Cell::default)
+    }
+
+    fn len(&self) -> usize {
+        self.occupied + self.outside.len()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = ((usize, usize), &Cell)> + '_> {
+        let columns = self.columns;
+        let inside = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, cell)| {
+                Some(((index % columns, index / columns), cell.as_ref()?))
+            });
+        Box::new(inside.chain(self.outside.iter()))
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Cell> + '_> {
+        let inside = self.cells.iter_mut().flatten();
+        Box::new(inside.chain(self.outside.iter_mut()))
+    }
+}
+
+/// Entities the hash and their positions disagree on.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Entities absent from a cell their bounds overlap.
+    pub missing: HashSet<Entity>,
+    /// Entities left in a cell their bounds no longer overlap, or with no bounds at all.
+    pub stale: HashSet<Entity>,
+}
+
+impl Divergence {
+    /// Whether the hash matches the positions exactly.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.stale.is_empty()
+    }
+}
+
+/// Spatial Hash is used to check locality of entities and check collisions.
+pub struct SpatialHash {
+    cell_size: usize,
+    cells: Box<dyn CellStore>,
+    /// Queries made since the hash was created, only counted for the benchmarks.
+    #[cfg(feature = "bench")]
+    queries: Counter<u64>,
+    /// Distances players see the entities within from.
+    interest: InterestConfig,
+}
+
+impl SpatialHash {
+    /// Creates a new Spatial Hash, the cell_size should be the average size of entities.
+    pub fn new(cell_size: usize) -> Self {
+        Self::with_cells(cell_size, Box::new(SparseCells::default()))
+    }
+
+    /// Creates a hash for a world reaching no further than the extent, its cells kept in a flat
+    /// array rather than hashed.
+    pub fn bounded(cell_size: usize, extent: Vec2) -> Self {
+        let columns = extent.x().max(0.) as usize / cell_size + 1;
+        let rows = extent.y().max(0.) as usize / cell_size + 1;
+        Self::with_cells(cell_size, Box::new(DenseCells::new(columns, rows)))
+    }
+
+    fn with_cells(cell_size: usize, cells: Box<dyn CellStore>) -> Self {
+        Self {
+            cell_size,
+            cells,
+            #[cfg(feature = "bench")]
+            queries: Counter::new(0),
+            interest: InterestConfig::default(),
+        }
+    }
+
+    /// Distances players see the entities within from.
+    pub fn interest(&self) -> &InterestConfig {
+        &self.interest
+    }
+
+    /// Changes the distances players see the entities within from.
+    pub fn set_interest(&mut self, interest: InterestConfig) {
+        self.interest = interest;
+    }
+
+    /// Translates coordinates into cell coordinates.
+    #[inline]
+    fn cell_coords(&self, position: Vec2) -> (usize, usize) {
+        (
+            position.x() as usize / self.cell_size,
+            position.y() as usize / self.cell_size,
+        )
+    }
+
+    /// Adds an entity into a cell, pulling the locational data from it.
+    pub fn insert_object(&mut self, entity: &Entity, obj: &Bounds) {
+        let (start_x, start_y) = self.cell_coords(obj.top_left_2d());
+        let (end_x, end_y) = self.cell_coords(obj.bottom_right_2d());
+
+        for x in start_x..=end_x {
+            for y in start_y..=end_y {
+                self.cells.get_or_create((x, y)).entities.insert(*entity);
+            }
+        }
+    }
+
+    /// Removes an entity from a cell, pulling the locational data from it.
+    pub fn remove_object(&mut self, entity: &Entity, obj: &Bounds) {
+        let (start_cell_x, start_cell_y) = self.cell_coords(obj.top_left_2d());
+        let (end_cell_x, end_cell_y) = self.cell_coords(obj.bottom_right_2d());
+
+        for x in start_cell_x..=end_cell_x {
+            for y in start_cell_y..=end_cell_y {
+                if let Some(cell) = self.cells.get_mut((x, y)) {
+                    cell.entities.remove(entity);
+                }
+            }
+        }
+    }
+
+    /// Number of cells that have ever been occupied.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Number of entities across all cells, counting an entity once per cell it overlaps.
+    pub fn entry_count(&self) -> usize {
+        self.cells.iter().map(|(_, cell)| cell.entities.len()).sum()
+    }
+
+    /// Number of queries made since the hash was created.
+    #[cfg(feature = "bench")]
+    pub fn query_count(&self) -> u64 {
+        self.queries.get()
+    }
+
+    /// Compares the cells against the bounds each entity is expected to occupy.
+    pub fn diverged(&self, expected: &HashMap<Entity, Bounds>) -> Divergence {
+        let mut divergence = Divergence::default();
+        let covers = |bounds: &Bounds, cell: (usize, usize)| {
+            let (start_x, start_y) = self.cell_coords(bounds.top_left_2d());
+            let (end_x, end_y) = self.cell_coords(bounds.bottom_right_2d());
+            (start_x..=end_x).contains(&cell.0) && (start_y..=end_y).contains(&cell.1)
+        };
+
+        for (entity, bounds) in expected.iter() {
+            let (start_x, start_y) = self.cell_coords(bounds.top_left_2d());
+            let (end_x, end_y) = self.cell_coords(bounds.bottom_right_2d());
+            let missing = (start_x..=end_x).any(|x| {
+                (start_y..=end_y).any(|y| {
+                    !self
+                        .cells
+                        .get((x, y))
+                        .is_some_and(|cell| cell.entities.contains(entity))
+                })
+            });
+            if missing {
+                divergence.missing.insert(*entity);
+            }
+        }
+
+        for (coords, cell) in self.cells.iter() {
+            for entity in cell.entities.iter() {
+                if !expected
+                    .get(entity)
+                    .is_some_and(|bounds| covers(bounds, coords))
+                {
+                    divergence.stale.insert(*entity);
+                }
+            }
+        }
+
+        divergence
+    }
+
+    /// Reinserts each diverged entity where it is expected, dropping it from every other cell.
+    pub fn repair(&mut self, expected: &HashMap<Entity, Bounds>, divergence: &Divergence) {
+        for entity in divergence.missing.iter().chain(divergence.stale.iter()) {
+            for cell in self.cells.iter_mut() {
+                cell.entities.remove(entity);
+            }
+            if let Some(bounds) = expected.get(entity) {
+                self.insert_object(entity, bounds);
+            }
+        }
+    }
+
+    // Queries for entities of entities within the specified rectangle
+    pub fn query(&self, bounds: &Bounds, exclude_entity: Option<&Entity>) -> HashSet<Entity> {
+        #[cfg(feature = "bench")]
+        self.queries.set(self.queries.get() + 1);
+        let start = self.cell_coords(bounds.top_left_2d());
+        let end = self.cell_coords(bounds.bottom_right_2d());
+
+        let (start_x, start_y) = start;
+        let (end_x, end_y) = end;
+
+        let mut result = HashSet::new();
+        for cell_x in start_x..=end_x {
+            for cell_y in start_y..=end_y {
+                if let Some(cell) = self.cells.get((cell_x, cell_y)) {
+                    for &entity_id in &cell.entities {
+                        // Check if the entity is not the one to be excluded, if any
+                        if exclude_entity.map_or(true, |excl_entity| entity_id != *excl_entity) {
+                            result.insert(entity_id);
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Checks if a cell lies within the radius, measured to the nearest point of the cell.
+    fn cell_within(&self, cell: (usize, usize), center: Vec2, radius: f64) -> bool {
+        let size = self.cell_size as f64;
+        let nearest = |cell: usize, center: f64| {
+            let start = cell as f64 * size;
+            center.clamp(start, start + size)
+        };
+
+        let dx = nearest(cell.0, center.x()) - center.x();
+        let dy = nearest(cell.1, center.y()) - center.y();
+        dx * dx + dy * dy <= radius * radius
+    }
+
+    /// Queries for the entities within the cells that lie within the radius of the center.
+    /// Entities are matched by the cells they occupy, regardless of their size.
+    pub fn query_radius(
+        &self,
+        center: Vec2,
+        radius: f64,
+        exclude_entity: Option<&Entity>,
+    ) -> HashSet<Entity> {
+        #[cfg(feature = "bench")]
+        self.queries.set(self.queries.get() + 1);
+        let (start_x, start_y) =
+            self.cell_coords(Vec2::new(center.x() - radius, center.y() - radius));
+        let (end_x, end_y) = self.cell_coords(Vec2::new(center.x() + radius, center.y() + radius));
+
+        let mut result = HashSet::new();
+        for cell_x in start_x..=end_x {
+            for cell_y in start_y..=end_y {
+                if !self.cell_within((cell_x, cell_y), center, radius) {
+                    continue;
+                }
+
+                if let Some(cell) = self.cells.get((cell_x, cell_y)) {
+                    let entities = cell.entities.iter();
+                    result.extend(entities.filter(|entity| Some(*entity) != exclude_entity));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Bounds of the occupied cells that lie within the radius of the center.
+    pub fn occupied_within(&self, center: Vec2, radius: f64) -> Vec<Bounds> {
+        let (start_x, start_y) =
+            self.cell_coords(Vec2::new(center.x() - radius, center.y() - radius));
+        let (end_x, end_y) = self.cell_coords(Vec2::new(center.x() + radius, center.y() + radius));
+        let size = self.cell_size as f64;
+
+        let mut result = Vec::new();
+        for cell_x in start_x..=end_x {
+            for cell_y in start_y..=end_y {
+                let occupied = self
+                    .cells
+                    .get((cell_x, cell_y))
+                    .is_some_and(|cell| !cell.entities.is_empty());
+                if occupied && self.cell_within((cell_x, cell_y), center, radius) {
+                    let (x, y) = (cell_x as f64 * size, cell_y as f64 * size);
+                    result.push(Bounds::new(x, y, 0., size, size));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Checks if bounds occupy a cell within the radius, matching what `query_radius` would find.
+    pub fn reaches(&self, bounds: &Bounds, center: Vec2, radius: f64) -> bool {
+        let (start_x, start_y) = self.cell_coords(bounds.top_left_2d());
+        let (end_x, end_y) = self.cell_coords(bounds.bottom_right_2d());
+        (start_x..=end_x)
+            .any(|x| (start_y..=end_y).any(|y| self.cell_within((x, y), center, radius)))
+    }
+
+    pub fn till_collision(query: &MoveQuery, bounds: &Bounds, step: f64) -> Option<Vec3> {
+        if query.nearby.is_empty() {
+            // If there are no nearby objects, the path to the destination is clear.
+            return Some(query.destination);
+        }
+
+        // Extract initial positions and size.
+        let (sx, sy, _) = query.source.as_tuple();
+        let (mut dx, mut dy, dz) = query.destination.as_tuple();
+        let (vel_x, vel_y) = query.velocity.as_tuple();
+        let (w, h) = query.entity_size.as_tuple();
+
+        // If the destination does not intersect with bounds, return it directly.
+        if !bounds.intersects_3d(&Bounds::new(dx, dy, dz, w, h)) {
+            return Some(Vec3::new(dx, dy, dz));
+        }
+
+        // Calculate the step size for backtracking based on velocity direction.
+        let step_x = vel_x.signum() * step;
+        let step_y = vel_y.signum() * step;
+
+        while bounds.intersects_3d(&Bounds::new(dx, dy, dz, w, h)) {
+            // Move back towards the source position incrementally, never stepping past it.
+            if vel_x > 0.0 {
+                dx = (dx - step_x).max(sx);
+            } else if vel_x < 0.0 {
+                dx = (dx - step_x).min(sx);
+            }
+            if vel_y > 0.0 {
+                dy = (dy - step_y).max(sy);
+            } else if vel_y < 0.0 {
+                dy = (dy - step_y).min(sy);
+            }
+
+            // Back at the source, there is nowhere left to go.
+            if dx == sx && dy == sy {
+                break;
+            }
+        }
+
+        // After adjusting, if we're still in bounds or have returned to the source, return None.
+        if bounds.intersects_3d(&Bounds::new(dx, dy, dz, w, h)) || (dx == sx && dy == sy) {
+            return None;
+        }
+
+        // Return the adjusted position if a collision-free spot is found.
+        Some(Vec3::new(dx, dy, dz))
+    }
+
+    /// The coordinates that can be moved in until a collision is detected.
+    pub fn till_collisions(
+        query: &MoveQuery,
+        objects: &HashMap<Entity, &Position>,
+        step: f64,
+    ) -> Option<Vec3> {
+        if query.nearby.is_empty() {
+            // If there are no nearby objects, we can move to the destination.
+            return Some(query.destination);
+        }
+
+        let mut closest_position = query.destination;
+        let mut collision_detected = false;
+
+        for entity in &query.nearby {
+            // Skip checking the query object itself.
+            if *entity == query.entity {
+                continue;
+            }
+
+            if let Some(entity) = objects.get(entity) {
+                let bounds = Bounds::from_vec(entity.loc, entity.size);
+                // Use till_collision for each entity to check for collisions.
+                match SpatialHash::till_collision(query, &bounds, step) {
+                    Some(pos) => {
+                        // If till_collision returns a position, check if it's closer than the current closest_position.
+                        if !collision_detected
+                            || SpatialHash::is_closer_to_source(query.source, pos, closest_position)
+                        {
+                            closest_position = pos;
+                            collision_detected = true;
+                        }
+                    }
+                    None => {
+                        // If till_collision returns None, it means a collision is unavoidable for this entity.
+                        return None;
+                    }
+                }
+            }
+        }
+
+        if collision_detected {
+            Some(closest_position)
+        } else {
+            // If no collisions are detected, we can move to the destination.
+            Some(query.destination)
+        }
+    }
+
+    // Helper function to check if a position is closer to the source
+    fn is_closer_to_source(source: Vec3, new_pos: Vec3, current_pos: Vec3) -> bool {
+        let dist_new = new_pos.distance_2d(&source);
+        let dist_current = current_pos.distance_2d(&source);
+        dist_new < dist_current
+    }
+}
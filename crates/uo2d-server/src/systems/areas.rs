@@ -0,0 +1,102 @@
+use uo2d_proto::components::{Area, Health, Npc, Position};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::payloads::AreaWarningPayload;
+
+use super::combat;
+use super::movement::get_observers;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Places an area that strikes after a delay, warning the players around its source.
+/// Once struck it stays active for the duration, both in seconds.
+pub fn warn(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    area: Area,
+    delay: f32,
+    duration: f32,
+) {
+    let entity = world.spawn().with(area).build();
+    let payload = AreaWarningPayload::new(entity, area.shape, area.center, delay, duration);
+    net.broadcast_to(
+        get_observers(world, spatial, &area.source),
+        Event::AreaWarning(payload),
+    );
+}
+
+/// Damages those within the active areas, removing the areas that have faded.
+/// NPCs never harm each other with their areas.
+pub fn update(
+    world: &mut World,
+    spatial: &SpatialHash,
+    items: &ItemManager,
+    net: &mut Net,
+    tick: u64,
+    friendly_fire: f64,
+) {
+    let active: Vec<(Entity, Area)> = world
+        .query1::<Area>()
+        .into_iter()
+        .filter(|(_, area)| area.is_active(tick))
+        .map(|(entity, area)| (entity, *area))
+        .collect();
+
+    for (entity, area) in active.into_iter() {
+        let damage = match area.struck {
+            true => area.per_tick,
+            false => area.damage + area.per_tick,
+        };
+        if tick >= area.expires {
+            world.despawn(&entity);
+        } else if let Some(area) = world.get_component_mut::<Area>(&entity) {
+            area.struck = true;
+        }
+        if damage == 0 {
+            continue;
+        }
+
+        let from_npc = world.get_component::<Npc>(&area.source).is_some();
+        let struck: Vec<Entity> = spatial
+            .query(&area.shape.bounds(area.center), Some(&area.source))
+            .into_iter()
+            .filter(|target| world.get_component::<Health>(target).is_some())
+            .filter(|target| !(from_npc && world.get_component::<Npc>(target).is_some()))
+            .filter(|target| {
+                world
+                    .get_component::<Position>(target)
+                    .is_some_and(|position| area.shape.touches(area.center, &position.bounds()))
+            })
+            .collect();
+
+        for target in struck.into_iter() {
+            combat::hit(
+                world,
+                spatial,
+                items,
+                net,
+                &area.source,
+                &target,
+                damage,
+                friendly_fire,
+            );
+        }
+    }
+}
+
+/// Removes the areas placed by an entity, letting the players around it know.
+pub fn clear(world: &mut World, spatial: &SpatialHash, net: &mut Net, source: &Entity) {
+    let placed: Vec<Entity> = world
+        .query1::<Area>()
+        .into_iter()
+        .filter(|(_, area)| area.source == *source)
+        .map(|(entity, _)| entity)
+        .collect();
+
+    let observers = get_observers(world, spatial, source);
+    for entity in placed.into_iter() {
+        world.despawn(&entity);
+        net.broadcast_to(observers.clone(), Event::EntityRemoved(entity));
+    }
+}
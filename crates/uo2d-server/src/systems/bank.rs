@@ -0,0 +1,54 @@
+use uo2d_proto::components::{Banker, Inventory, Position};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+
+use crate::region::BankZone;
+use crate::spatial_hash::SpatialHash;
+use crate::systems::npcs;
+
+/// Weight a bank can hold, ten times what a player can carry.
+pub const CAPACITY: u32 = 1000;
+
+/// Checks if an entity can reach their bank, standing near a banker or within a bank.
+pub fn within_reach(
+    world: &World,
+    spatial: &SpatialHash,
+    zones: &[BankZone],
+    entity: &Entity,
+) -> bool {
+    let near_banker = npcs::within_reach(world, spatial, entity)
+        .iter()
+        .any(|npc| world.get_component::<Banker>(npc).is_some());
+    if near_banker {
+        return true;
+    }
+
+    let loc = match world.get_component::<Position>(entity) {
+        Some(position) => position.loc,
+        None => return false,
+    };
+    zones.iter().any(|zone| zone.bounds().coord_within_2d(&loc))
+}
+
+/// Moves up to `count` items from a stack in one inventory to another, 0 moving the whole stack.
+/// Limited by the weight the receiving inventory can hold, returns the amount that was moved.
+pub fn transfer(
+    items: &ItemManager,
+    from: &mut Inventory,
+    to: &mut Inventory,
+    slot: usize,
+    count: u16,
+) -> u16 {
+    let stack = match from.stacks.get(slot) {
+        Some(stack) => *stack,
+        None => return 0,
+    };
+
+    let count = match count {
+        0 => stack.count,
+        count => count.min(stack.count),
+    };
+    let moved = items.add(to, stack.item, count);
+    from.remove_at(slot, moved);
+    moved
+}
@@ -0,0 +1,217 @@
+use uo2d_proto::components::{Area, Boss, Health, Npc, Position, Threat, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{HealthPayload, NotificationKind, NotificationPayload};
+use uo2d_proto::timer::TimerManager;
+
+use super::areas;
+use super::movement::get_observers;
+use crate::net::{Event, Net};
+use crate::npcs::{AbilityTarget, NpcDefinition, NpcManager};
+use crate::spatial_hash::SpatialHash;
+
+/// Advances the fights against bosses, moving them through their phases and using their abilities.
+/// Fights reset once every player fighting the boss has died or left.
+pub fn update(
+    world: &mut World,
+    spatial: &SpatialHash,
+    npcs: &NpcManager,
+    net: &mut Net,
+    timers: &TimerManager,
+) {
+    let bosses: Vec<(Entity, bool, Option<Entity>)> = world
+        .query2::<Boss, Threat>()
+        .into_iter()
+        .map(|(entity, boss, threat)| (entity, boss.engaged, threat.target))
+        .collect();
+
+    for (entity, engaged, target) in bosses.into_iter() {
+        let definition = match world
+            .get_component::<Boss>(&entity)
+            .and_then(|boss| npcs.get(&boss.kind))
+        {
+            Some(definition) => definition,
+            None => continue,
+        };
+
+        match target {
+            None if engaged => reset(world, spatial, net, definition, &entity),
+            None => (),
+            Some(target) => {
+                if !engaged {
+                    if let Some(boss) = world.get_component_mut::<Boss>(&entity) {
+                        boss.engaged = true;
+                    }
+                    enter_phase(world, spatial, net, definition, timers, &entity, 0);
+                }
+                advance(world, spatial, net, definition, timers, &entity);
+                cast(world, spatial, net, definition, timers, &entity, &target);
+            }
+        }
+    }
+}
+
+/// Enters the next phase once the health of the boss falls far enough.
+fn advance(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    definition: &NpcDefinition,
+    timers: &TimerManager,
+    entity: &Entity,
+) {
+    let fraction = match world.get_component::<Health>(entity) {
+        Some(health) if health.max > 0 => health.current as f64 / health.max as f64,
+        _ => return,
+    };
+    let current = world
+        .get_component::<Boss>(entity)
+        .map_or(0, |boss| boss.phase);
+
+    // Phases are only ever entered in order, even if several are passed at once.
+    match definition
+        .phases
+        .iter()
+        .rposition(|phase| fraction <= phase.health)
+    {
+        Some(phase) if phase > current => {
+            enter_phase(world, spatial, net, definition, timers, entity, phase)
+        }
+        _ => (),
+    }
+}
+
+/// Starts a phase, announcing it and readying its abilities after their cooldowns.
+fn enter_phase(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    definition: &NpcDefinition,
+    timers: &TimerManager,
+    entity: &Entity,
+    index: usize,
+) {
+    let phase = match definition.phases.get(index) {
+        Some(phase) => phase,
+        None => return,
+    };
+
+    if let Some(boss) = world.get_component_mut::<Boss>(entity) {
+        boss.phase = index;
+        boss.ready = phase
+            .abilities
+            .iter()
+            .map(|ability| timers.tick() + ticks(timers, ability.cooldown))
+            .collect();
+    }
+    if let Some(chase) = phase.chase {
+        if let Some(npc) = world.get_component_mut::<Npc>(entity) {
+            npc.chase = chase;
+        }
+    }
+
+    if let Some(message) = &phase.announce {
+        net.broadcast_to(
+            get_observers(world, spatial, entity),
+            Event::Notification(NotificationPayload::new(
+                NotificationKind::Announcement,
+                Text::new("boss.announce")
+                    .with_text("npc", definition.text())
+                    .with("message", message),
+            )),
+        );
+    }
+}
+
+/// Uses the abilities of the current phase that are ready, warning players where they strike.
+fn cast(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    definition: &NpcDefinition,
+    timers: &TimerManager,
+    entity: &Entity,
+    target: &Entity,
+) {
+    let (phase, ready) = match world.get_component::<Boss>(entity) {
+        Some(boss) => (boss.phase, boss.ready.clone()),
+        None => return,
+    };
+    let abilities = match definition.phases.get(phase) {
+        Some(phase) => &phase.abilities,
+        None => return,
+    };
+
+    let tick = timers.tick();
+    for (index, ability) in abilities.iter().enumerate() {
+        if ready.get(index).is_none_or(|ready| *ready > tick) {
+            continue;
+        }
+
+        let location = match ability.at {
+            AbilityTarget::Caster => center(world, entity),
+            AbilityTarget::Target => center(world, target),
+        };
+        if let Some(location) = location {
+            let strikes = tick + ticks(timers, ability.delay);
+            let area = Area {
+                shape: ability.shape,
+                center: location,
+                damage: ability.damage,
+                per_tick: ability.per_tick,
+                source: *entity,
+                strikes,
+                expires: strikes + ticks(timers, ability.duration),
+                struck: false,
+            };
+            areas::warn(world, spatial, net, area, ability.delay, ability.duration);
+        }
+
+        if let Some(boss) = world.get_component_mut::<Boss>(entity) {
+            if let Some(ready) = boss.ready.get_mut(index) {
+                *ready = tick + ticks(timers, ability.cooldown);
+            }
+        }
+    }
+}
+
+/// Ends a fight, restoring the boss to how it started.
+fn reset(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    definition: &NpcDefinition,
+    entity: &Entity,
+) {
+    areas::clear(world, spatial, net, entity);
+    if let Some(boss) = world.get_component_mut::<Boss>(entity) {
+        let kind = boss.kind.clone();
+        *boss = Boss::new(&kind);
+    }
+    if let Some(npc) = world.get_component_mut::<Npc>(entity) {
+        npc.chase = definition.chase;
+    }
+
+    let healed = world
+        .get_component_mut::<Health>(entity)
+        .map(|health| (health.heal(health.max), health.current));
+    if let Some((restored, current)) = healed.filter(|(restored, _)| *restored > 0) {
+        net.broadcast_to(
+            get_observers(world, spatial, entity),
+            Event::Healed(HealthPayload::new(*entity, restored, current)),
+        );
+    }
+}
+
+/// Center of an entity on the layer it is on.
+fn center(world: &World, entity: &Entity) -> Option<Vec3> {
+    world.get_component::<Position>(entity).map(|position| {
+        let center = position.bounds().center_2d();
+        Vec3::new(center.x(), center.y(), position.loc.z())
+    })
+}
+
+/// Ticks within a number of seconds.
+fn ticks(timers: &TimerManager, seconds: f32) -> u64 {
+    (seconds / timers.server_tick_time().as_secs_f32()).ceil() as u64
+}
@@ -0,0 +1,155 @@
+use uo2d_proto::components::{Ability, Casting, Cooldowns, Position};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::{CastPayload, CastStage};
+use uo2d_proto::timer::TimerManager;
+
+use super::movement::get_observers;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Distance a caster can drift from where they began before the cast is interrupted.
+const MOVE_TOLERANCE: f64 = 2.;
+
+/// Ticks within a number of seconds, rounded up so short waits are never skipped.
+pub fn ticks(seconds: f32) -> u64 {
+    (seconds * TimerManager::SERVER_TICKS_PER_SECOND)
+        .max(0.)
+        .ceil() as u64
+}
+
+/// Informs nearby players of a cast reaching a stage.
+fn progress(
+    world: &World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    entity: &Entity,
+    ability: Ability,
+    stage: CastStage,
+) {
+    net.broadcast_to(
+        get_observers(world, spatial, entity),
+        Event::Cast(CastPayload::new(*entity, ability, stage)),
+    );
+}
+
+/// Checks if an entity can use an ability on the tick.
+pub fn is_ready(world: &World, entity: &Entity, ability: Ability, tick: u64) -> bool {
+    world
+        .get_component::<Cooldowns>(entity)
+        .is_none_or(|cooldowns| cooldowns.is_ready(ability, tick))
+}
+
+/// Prevents an entity from using an ability for a number of seconds.
+pub fn cooldown(world: &mut World, entity: &Entity, ability: Ability, tick: u64, seconds: f32) {
+    if world.get_component::<Cooldowns>(entity).is_none() {
+        world.upsert_component(*entity, Cooldowns::default());
+    }
+
+    if let Some(cooldowns) = world.get_component_mut::<Cooldowns>(entity) {
+        cooldowns.expire(tick);
+        cooldowns.start(ability, tick + ticks(seconds));
+    }
+}
+
+/// Checks if an entity is in the middle of casting.
+pub fn is_casting(world: &World, entity: &Entity) -> bool {
+    world.get_component::<Casting>(entity).is_some()
+}
+
+/// Begins casting an ability, finishing after a number of seconds.
+/// Fails if the entity is already casting or has nowhere to stand.
+#[allow(clippy::too_many_arguments)]
+pub fn begin(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    entity: &Entity,
+    ability: Ability,
+    slot: Option<usize>,
+    tick: u64,
+    seconds: f32,
+) -> Result<(), &'static str> {
+    if is_casting(world, entity) {
+        return Err("common.busy");
+    }
+
+    let origin = match world.get_component::<Position>(entity) {
+        Some(position) => position.loc,
+        None => return Err("common.not_now"),
+    };
+
+    world.upsert_component(
+        *entity,
+        Casting {
+            ability,
+            slot,
+            origin,
+            finishes: tick + ticks(seconds),
+        },
+    );
+    progress(
+        world,
+        spatial,
+        net,
+        entity,
+        ability,
+        CastStage::Started(seconds),
+    );
+    Ok(())
+}
+
+/// Stops the cast of an entity early, returning whether it was casting.
+pub fn interrupt(world: &mut World, spatial: &SpatialHash, net: &mut Net, entity: &Entity) -> bool {
+    let Some(casting) = world.get_component::<Casting>(entity).copied() else {
+        return false;
+    };
+
+    world.remove_component::<Casting>(*entity);
+    progress(
+        world,
+        spatial,
+        net,
+        entity,
+        casting.ability,
+        CastStage::Interrupted,
+    );
+    true
+}
+
+/// Finishes the casts that are due, interrupting those whose caster moved.
+/// Returns the casts that finished, to be acted on.
+pub fn update(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    tick: u64,
+) -> Vec<(Entity, Casting)> {
+    let casts: Vec<(Entity, Casting, bool)> = world
+        .query2::<Casting, Position>()
+        .into_iter()
+        .map(|(entity, casting, position)| {
+            let moved = position.loc.distance_2d(&casting.origin) > MOVE_TOLERANCE;
+            (entity, *casting, moved)
+        })
+        .collect();
+
+    let mut finished = vec![];
+    for (entity, casting, moved) in casts.into_iter() {
+        if moved {
+            interrupt(world, spatial, net, &entity);
+        } else if casting.finishes <= tick {
+            world.remove_component::<Casting>(entity);
+            progress(
+                world,
+                spatial,
+                net,
+                &entity,
+                casting.ability,
+                CastStage::Finished,
+            );
+            finished.push((entity, casting));
+        }
+    }
+
+    finished
+}
@@ -0,0 +1,46 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::chunk::{chunk_distance, chunks_within, ChunkCoord, CHUNK_SIZE, EVICT_DISTANCE};
+use uo2d_proto::components::{Player, Position};
+use uo2d_proto::ecs::World;
+use uo2d_proto::packet::payloads::ChunkPayload;
+use uuid::Uuid;
+
+use crate::net::{Event, Net};
+use crate::region::RegionManager;
+
+/// Distance from a player that chunks are sent within, must stay inside the eviction distance.
+const STREAM_DISTANCE: f64 = CHUNK_SIZE;
+
+/// Sends the chunks a player is approaching, forgetting the ones the client has evicted.
+pub fn stream(
+    world: &World,
+    regions: &RegionManager,
+    net: &mut Net,
+    loaded: &mut HashMap<Uuid, HashSet<ChunkCoord>>,
+) {
+    let mut players: HashSet<Uuid> = HashSet::new();
+
+    for (_entity, player, pos) in world.query2::<Player, Position>() {
+        let uuid = *player.uuid();
+        players.insert(uuid);
+
+        let chunks = loaded.entry(uuid).or_default();
+        for coord in chunks_within(&pos.loc, STREAM_DISTANCE) {
+            if chunks.contains(&coord) {
+                continue;
+            }
+
+            if let Some(tiles) = regions.chunk(&coord) {
+                chunks.insert(coord);
+                net.send_to(uuid, Event::Chunk(ChunkPayload::new(coord, tiles)));
+            }
+        }
+
+        // Clients drop distant chunks on their own, they are resent when approached again.
+        chunks.retain(|coord| chunk_distance(coord, &pos.loc) <= EVICT_DISTANCE);
+    }
+
+    // Forget the players that are no longer in the world.
+    loaded.retain(|uuid, _| players.contains(uuid));
+}
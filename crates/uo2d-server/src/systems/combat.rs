@@ -0,0 +1,112 @@
+use uo2d_proto::components::{Equipment, Health, Player, StatusEffect, Team};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{HealthPayload, NotificationKind, NotificationPayload};
+
+use super::casting;
+use super::effects;
+use super::mounts;
+use super::movement::get_observers;
+use super::stats;
+use super::threat;
+use crate::event_log::{self, ServerEvent};
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// An attacker strikes a target, equipment modifies the base damage.
+/// Teammates only take the `friendly_fire` fraction of the damage.
+#[allow(clippy::too_many_arguments)]
+pub fn hit(
+    world: &mut World,
+    spatial: &SpatialHash,
+    items: &ItemManager,
+    net: &mut Net,
+    attacker: &Entity,
+    target: &Entity,
+    base: u32,
+    friendly_fire: f64,
+) {
+    let bonus = world
+        .get_component::<Equipment>(attacker)
+        .map_or(0, |equipment| items.damage(equipment));
+    let armor = world
+        .get_component::<Equipment>(target)
+        .map_or(0, |equipment| items.armor(equipment));
+
+    // Armor can never fully negate a hit.
+    let mut amount = (base + bonus).saturating_sub(armor).max(1);
+    let allied = match (
+        world.get_component::<Team>(attacker),
+        world.get_component::<Team>(target),
+    ) {
+        (Some(attacker), Some(target)) => attacker == target,
+        _ => false,
+    };
+    if allied {
+        amount = (amount as f64 * friendly_fire.clamp(0., 1.)).round() as u32;
+        if amount == 0 {
+            return;
+        }
+    }
+
+    let alive = world
+        .get_component::<Health>(target)
+        .is_some_and(|health| !health.is_dead());
+    damage(world, spatial, net, target, amount);
+    threat::damaged(world, target, attacker, amount);
+
+    // Only killing another player counts towards the attacker's stats.
+    let dead = world
+        .get_component::<Health>(target)
+        .is_some_and(|health| health.is_dead());
+    if alive && dead && world.get_component::<Player>(target).is_some() {
+        stats::killed(world, attacker);
+    }
+}
+
+/// Removes health from an entity, informing players of the damage.
+pub fn damage(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    entity: &Entity,
+    amount: u32,
+) {
+    if effects::has(world, entity, StatusEffect::Invulnerable) {
+        return;
+    }
+
+    let (removed, current, died) = match world.get_component_mut::<Health>(entity) {
+        Some(health) => {
+            let removed = health.damage(amount);
+            (removed, health.current, removed > 0 && health.is_dead())
+        }
+        None => return,
+    };
+
+    net.broadcast_to(
+        get_observers(world, spatial, entity),
+        Event::Damaged(HealthPayload::new(*entity, removed, current)),
+    );
+
+    // Being hurt breaks concentration and throws riders from their mounts.
+    if removed > 0 {
+        casting::interrupt(world, spatial, net, entity);
+        mounts::dismount(world, spatial, net, entity);
+    }
+
+    // Let everyone know a player has died.
+    if died {
+        if let Some(player) = world.get_component::<Player>(entity).copied() {
+            stats::died(world, entity);
+            event_log::record(ServerEvent::Death {
+                uuid: *player.uuid(),
+            });
+            net.broadcast(Event::Notification(NotificationPayload::new(
+                NotificationKind::Death,
+                Text::new("combat.died").with("player", player.uuid()),
+            )));
+        }
+    }
+}
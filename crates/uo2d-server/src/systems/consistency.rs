@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::{Bounds, Flag, GroundItem, Position, ResourceNode};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::sprintln;
+
+use crate::spatial_hash::{Divergence, SpatialHash};
+
+/// Bounds each entity should occupy in the spatial hash.
+/// Flags and depleted resource nodes are kept out of it, nothing can run into them.
+pub fn expected(world: &World) -> HashMap<Entity, Bounds> {
+    let mut expected: HashMap<Entity, Bounds> = world
+        .query1::<Position>()
+        .into_iter()
+        .filter(|(entity, _)| {
+            world.get_component::<Flag>(entity).is_none()
+                && !world
+                    .get_component::<ResourceNode>(entity)
+                    .is_some_and(|node| node.is_depleted())
+        })
+        .map(|(entity, pos)| (entity, pos.bounds()))
+        .collect();
+
+    for (entity, ground) in world.query1::<GroundItem>() {
+        expected.insert(entity, ground.bounds);
+    }
+
+    expected
+}
+
+/// Cross-checks the spatial hash against the positions, logging and repairing any divergence.
+pub fn verify(world: &World, spatial: &mut SpatialHash) -> Divergence {
+    let expected = expected(world);
+    let divergence = spatial.diverged(&expected);
+    if !divergence.is_empty() {
+        sprintln!(
+            "Spatial hash diverged, repairing {} missing and {} stale entities.",
+            divergence.missing.len(),
+            divergence.stale.len()
+        );
+        spatial.repair(&expected, &divergence);
+    }
+
+    divergence
+}
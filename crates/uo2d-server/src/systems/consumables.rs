@@ -0,0 +1,93 @@
+use uo2d_proto::components::{Collidable, Health, Inventory, ItemId, Position, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::HealthPayload;
+
+use super::movement::get_observers;
+use super::roll;
+use crate::net::{Event, Net};
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Places tried for a random teleport before giving up.
+const TELEPORT_ATTEMPTS: u64 = 8;
+
+/// Removes one of the item from an inventory slot, failing if the slot no longer holds it.
+pub fn consume(world: &mut World, entity: &Entity, index: usize, item: ItemId) -> bool {
+    let inventory = match world.get_component_mut::<Inventory>(entity) {
+        Some(inventory) => inventory,
+        None => return false,
+    };
+
+    match inventory.stacks.get(index) {
+        Some(stack) if stack.item == item => inventory.remove_at(index, 1),
+        _ => false,
+    }
+}
+
+/// Checks if the entity has any health to restore.
+pub fn can_heal(world: &World, entity: &Entity) -> Result<(), &'static str> {
+    match world.get_component::<Health>(entity) {
+        Some(health) if health.is_dead() => Err("common.dead"),
+        Some(health) if health.current < health.max => Ok(()),
+        _ => Err("heal.full"),
+    }
+}
+
+/// Restores health to an entity, informing those nearby of the amount.
+/// Returns whether the entity has any health to restore.
+pub fn heal(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    entity: &Entity,
+    amount: u32,
+) -> bool {
+    let Some(health) = world.get_component_mut::<Health>(entity) else {
+        return false;
+    };
+    let restored = health.heal(amount);
+    let current = health.current;
+
+    net.broadcast_to(
+        get_observers(world, spatial, entity),
+        Event::Healed(HealthPayload::new(*entity, restored, current)),
+    );
+    true
+}
+
+/// Somewhere random within a distance of the entity that it fits, inside its region.
+pub fn random_destination(
+    world: &World,
+    spatial: &SpatialHash,
+    regions: &RegionManager,
+    entity: &Entity,
+    range: f64,
+    tick: u64,
+) -> Option<Vec3> {
+    let position = world.get_component::<Position>(entity)?;
+    let region = regions.get_region(&position.loc)?;
+
+    (0..TELEPORT_ATTEMPTS).find_map(|attempt| {
+        let roll = roll(entity, tick.wrapping_add(attempt));
+        let angle = ((roll % 360) as f64).to_radians();
+        let distance = range * ((roll >> 16) % 1000) as f64 / 1000.;
+        let loc = Vec3::new(
+            position.loc.x() + angle.cos() * distance,
+            position.loc.y() + angle.sin() * distance,
+            position.loc.z(),
+        );
+
+        let moved = Position::new(loc, position.size).bounds();
+        if !region.is_inbounds(&moved) || !region.is_within(&loc) {
+            return None;
+        }
+
+        let blocked = spatial.query(&moved, Some(entity)).iter().any(|other| {
+            world.get_component::<Collidable>(other).is_some()
+                && world
+                    .get_component::<Position>(other)
+                    .is_some_and(|other| other.bounds().intersects_2d(&moved))
+        });
+        (!blocked).then_some(loc)
+    })
+}
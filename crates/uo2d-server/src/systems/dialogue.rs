@@ -0,0 +1,99 @@
+use uo2d_proto::components::{Inventory, Progress};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::Text;
+
+use super::inventory;
+use crate::dialogue::{Condition, Hook};
+
+/// Checks if an entity meets every condition of a choice.
+pub fn meets(world: &World, entity: &Entity, conditions: &[Condition]) -> bool {
+    let progress = world.get_component::<Progress>(entity);
+    let flag = |flag: &str| progress.is_some_and(|progress| progress.has(flag));
+    let quest = |quest: &str| progress.is_some_and(|progress| progress.has_quest(quest));
+
+    conditions.iter().all(|condition| match condition {
+        Condition::Flag(name) => flag(name),
+        Condition::NotFlag(name) => !flag(name),
+        Condition::Quest(name) => quest(name),
+        Condition::NotQuest(name) => !quest(name),
+        Condition::Item { item, count } => world
+            .get_component::<Inventory>(entity)
+            .is_some_and(|inventory| inventory.count(*item) >= *count as u32),
+    })
+}
+
+/// Applies the hooks of a choice, returning what the player should be told.
+/// Nothing changes if any of them cannot be applied, the reason is returned instead.
+pub fn apply(
+    world: &mut World,
+    items: &ItemManager,
+    entity: &Entity,
+    hooks: &[Hook],
+) -> Result<Vec<Text>, Text> {
+    let inventory = world.get_component::<Inventory>(entity).cloned();
+    let progress = world
+        .get_component::<Progress>(entity)
+        .cloned()
+        .unwrap_or_default();
+
+    match run(world, items, entity, hooks, progress) {
+        Ok((progress, messages)) => {
+            world.upsert_component(*entity, progress);
+            Ok(messages)
+        }
+        Err(why) => {
+            if let Some(inventory) = inventory {
+                world.upsert_component(*entity, inventory);
+            }
+            Err(why)
+        }
+    }
+}
+
+/// Applies each hook in order, stopping at the first that cannot be.
+fn run(
+    world: &mut World,
+    items: &ItemManager,
+    entity: &Entity,
+    hooks: &[Hook],
+    mut progress: Progress,
+) -> Result<(Progress, Vec<Text>), Text> {
+    let mut messages = vec![];
+    for hook in hooks {
+        match hook {
+            Hook::Flag(flag) => {
+                progress.set(flag);
+            }
+            Hook::Quest(quest) => {
+                if progress.start_quest(quest) {
+                    messages.push(Text::new("dialogue.quest_started").with("quest", quest));
+                }
+            }
+            Hook::Trade { take, give } => {
+                for stack in take {
+                    let taken = world
+                        .get_component_mut::<Inventory>(entity)
+                        .is_some_and(|inventory| inventory.remove(stack.item, stack.count));
+                    if !taken {
+                        let name = items
+                            .get(&stack.item)
+                            .map_or(Text::literal("items"), |item| item.text());
+                        return Err(Text::new("dialogue.missing")
+                            .with("count", stack.count)
+                            .with_text("item", name));
+                    }
+                }
+
+                for stack in give {
+                    if inventory::give(world, items, entity, stack.item, stack.count) < stack.count
+                    {
+                        return Err("dialogue.full".into());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((progress, messages))
+}
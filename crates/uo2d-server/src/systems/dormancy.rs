@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+
+use uo2d_proto::components::{
+    Acceleration, Collidable, Dormant, Npc, Player, Position, Spawned, Spawner, Terrain, Threat,
+    Vec2, Vec3, Velocity,
+};
+use uo2d_proto::ecs::{Entity, World};
+
+use super::roll;
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Ticks between checks for the NPCs that have a player nearby.
+const CHECK_TICKS: u64 = 10;
+/// Distance beyond the interest radius NPCs wake within, simulated before they can be seen.
+const WAKE_MARGIN: f64 = 96.;
+/// Ticks between the jumps of a dormant NPC.
+const JUMP_TICKS: u64 = 100;
+/// Portion of its leash a dormant NPC jumps around its home within.
+const JUMP_LEASH: f64 = 0.5;
+
+/// Puts the NPCs no player is near to sleep, waking them again as a player approaches.
+/// Sleeping NPCs forget their threat and stop moving, those returning home carrying on once woken.
+pub fn update(world: &mut World, spatial: &SpatialHash, tick: u64) {
+    if !tick.is_multiple_of(CHECK_TICKS) {
+        return;
+    }
+
+    // Only the populated areas are searched, the cost growing with the players rather than the world.
+    let range = spatial.interest().npc + WAKE_MARGIN;
+    let awake: HashSet<Entity> = world
+        .query2::<Player, Position>()
+        .into_iter()
+        .flat_map(|(entity, _, position)| {
+            spatial.query_radius(position.bounds().center_2d(), range, Some(&entity))
+        })
+        .filter(|entity| world.get_component::<Npc>(entity).is_some())
+        .collect();
+
+    let mut sleep = vec![];
+    let mut wake = vec![];
+    for (entity, _) in world.query1::<Npc>() {
+        let dormant = world.get_component::<Dormant>(&entity).is_some();
+        match (dormant, awake.contains(&entity)) {
+            (false, false) => sleep.push(entity),
+            (true, true) => wake.push(entity),
+            _ => (),
+        }
+    }
+
+    for entity in sleep.into_iter() {
+        world.remove_component::<Velocity>(entity);
+        world.remove_component::<Acceleration>(entity);
+        if let Some(npc) = world.get_component_mut::<Npc>(&entity) {
+            npc.heading = Vec2::ORIGIN;
+        }
+        if let Some(threat) = world.get_component_mut::<Threat>(&entity) {
+            threat.table.clear();
+            threat.target = None;
+        }
+        world.upsert_component(entity, Dormant);
+    }
+    for entity in wake.into_iter() {
+        world.remove_component::<Dormant>(entity);
+    }
+}
+
+/// Moves the dormant NPCs that wander to somewhere around their home every so often, standing in
+/// for the walking they would have done.
+pub fn jump(world: &mut World, spatial: &mut SpatialHash, regions: &RegionManager, tick: u64) {
+    let mut jumps = vec![];
+    for (entity, _) in world.query1::<Dormant>() {
+        if !(tick + entity.id()).is_multiple_of(JUMP_TICKS) {
+            continue;
+        }
+
+        let wanders = world
+            .get_component::<Npc>(&entity)
+            .is_some_and(|npc| npc.wander > 0.);
+        let home = world
+            .get_component::<Spawned>(&entity)
+            .and_then(|spawned| world.get_component::<Spawner>(&spawned.0))
+            .map(|spawner| (spawner.home, spawner.leash));
+        let (Some(position), true, Some((home, leash))) =
+            (world.get_component::<Position>(&entity), wanders, home)
+        else {
+            continue;
+        };
+
+        let roll = roll(&entity, tick);
+        let angle = ((roll % 360) as f64).to_radians();
+        let distance = leash * JUMP_LEASH * ((roll >> 16) % 100) as f64 / 100.;
+        let destination = Vec3::new(
+            home.x() + angle.cos() * distance,
+            home.y() + angle.sin() * distance,
+            position.loc.z(),
+        );
+        let moved = Position::new(destination, position.size);
+        if can_land(world, spatial, regions, &entity, position, &moved) {
+            jumps.push((entity, *position, moved));
+        }
+    }
+
+    for (entity, from, to) in jumps.into_iter() {
+        spatial.remove_object(&entity, &from.bounds());
+        spatial.insert_object(&entity, &to.bounds());
+        world.upsert_component(entity, to);
+    }
+}
+
+/// Whether the NPC fits at the destination, on the same floor and clear of water and anything solid.
+fn can_land(
+    world: &World,
+    spatial: &SpatialHash,
+    regions: &RegionManager,
+    entity: &Entity,
+    from: &Position,
+    to: &Position,
+) -> bool {
+    let bounds = to.bounds();
+    let region = match regions.get_region(&to.loc) {
+        Some(region) => region,
+        None => return false,
+    };
+
+    region.is_inbounds(&bounds)
+        && region.terrain_at(&to.loc) == Terrain::Ground
+        && region.floor_at(&to.loc) == region.floor_at(&from.loc)
+        && !spatial
+            .query(&bounds, Some(entity))
+            .iter()
+            .any(|other| world.get_component::<Collidable>(other).is_some())
+}
@@ -0,0 +1,64 @@
+use uo2d_proto::components::{StatusEffect, StatusEffects};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::StatusEffectPayload;
+
+use super::movement::get_observers;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Informs nearby players of an effect starting or ending on an entity.
+fn changed(
+    world: &World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    entity: &Entity,
+    effect: StatusEffect,
+    active: bool,
+) {
+    net.broadcast_to(
+        get_observers(world, spatial, entity),
+        Event::StatusEffect(StatusEffectPayload::new(*entity, effect, active)),
+    );
+}
+
+/// Applies an effect to an entity until the tick provided, if it can be affected.
+pub fn apply(
+    world: &mut World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    entity: &Entity,
+    effect: StatusEffect,
+    expires: u64,
+) {
+    let Some(effects) = world.get_component_mut::<StatusEffects>(entity) else {
+        return;
+    };
+    effects.apply(effect, expires);
+    changed(world, spatial, net, entity, effect, true);
+}
+
+/// Removes the effects that have run out.
+pub fn expire(world: &mut World, spatial: &SpatialHash, net: &mut Net, tick: u64) {
+    let mut expired = vec![];
+    for entity in world.get_entities::<StatusEffects>() {
+        if let Some(effects) = world.get_component_mut::<StatusEffects>(&entity) {
+            expired.extend(
+                effects
+                    .expire(tick)
+                    .into_iter()
+                    .map(|effect| (entity, effect)),
+            );
+        }
+    }
+
+    for (entity, effect) in expired.into_iter() {
+        changed(world, spatial, net, &entity, effect, false);
+    }
+}
+
+/// Checks if an entity currently has an effect.
+pub fn has(world: &World, entity: &Entity, effect: StatusEffect) -> bool {
+    world
+        .get_component::<StatusEffects>(entity)
+        .is_some_and(|effects| effects.has(effect))
+}
@@ -0,0 +1,91 @@
+use uo2d_proto::components::{EquipSlot, Equipment, Inventory, ItemId, Mounted, Team};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::payloads::AppearancePayload;
+
+use super::inventory;
+use super::movement::get_observers;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Moves an item from the inventory into a slot, returning the replaced item to the inventory.
+pub fn equip(
+    world: &mut World,
+    items: &ItemManager,
+    entity: &Entity,
+    slot: EquipSlot,
+    item: ItemId,
+) -> bool {
+    // The item must exist and belong in the slot.
+    match items.get(&item) {
+        Some(definition) if definition.slot == Some(slot) => {}
+        _ => return false,
+    }
+
+    // The item must be carried.
+    let carried = world
+        .get_component_mut::<Inventory>(entity)
+        .is_some_and(|inventory| inventory.remove(item, 1));
+    if !carried {
+        return false;
+    }
+
+    let previous = match world.get_component_mut::<Equipment>(entity) {
+        Some(equipment) => equipment.set(slot, Some(item)),
+        None => Some(item),
+    };
+
+    // Swap back if the replaced item is too heavy to carry.
+    if let Some(previous) = previous {
+        if inventory::give(world, items, entity, previous, 1) == 0 {
+            if let Some(equipment) = world.get_component_mut::<Equipment>(entity) {
+                equipment.set(slot, Some(previous));
+            }
+            inventory::give(world, items, entity, item, 1);
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Moves an item from a slot back into the inventory, if it can be carried.
+pub fn unequip(world: &mut World, items: &ItemManager, entity: &Entity, slot: EquipSlot) -> bool {
+    let item = match world
+        .get_component::<Equipment>(entity)
+        .and_then(|equipment| equipment.get(slot))
+    {
+        Some(item) => item,
+        None => return false,
+    };
+
+    if inventory::give(world, items, entity, item, 1) == 0 {
+        return false;
+    }
+
+    if let Some(equipment) = world.get_component_mut::<Equipment>(entity) {
+        equipment.set(slot, None);
+    }
+    true
+}
+
+/// Informs the owner of their inventory and nearby players of the new appearance.
+pub fn changed(world: &World, spatial: &SpatialHash, net: &mut Net, entity: &Entity) {
+    inventory::changed(world, net, entity);
+    appearance(world, spatial, net, entity);
+}
+
+/// Informs nearby players of how an entity looks.
+pub fn appearance(world: &World, spatial: &SpatialHash, net: &mut Net, entity: &Entity) {
+    let equipment = world
+        .get_component::<Equipment>(entity)
+        .copied()
+        .unwrap_or_default();
+    let team = world.get_component::<Team>(entity).copied();
+    let mounted = world.get_component::<Mounted>(entity).is_some();
+
+    net.broadcast_to(
+        get_observers(world, spatial, entity),
+        Event::Appearance(AppearancePayload::new(*entity, equipment, team, mounted)),
+    );
+}
@@ -0,0 +1,90 @@
+use uo2d_proto::components::{Falling, Health, Obstacle, Position, Vec2, Vec3};
+use uo2d_proto::ecs::{ComponentChange, Entity, World};
+use uo2d_proto::packet::payloads::{FallPayload, MovementPayload};
+use uo2d_proto::timer::TimerManager;
+
+use super::combat;
+use super::movement::get_observers;
+use crate::delta::DeltaEncoder;
+use crate::net::{Event, Net};
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Layers an entity can step up or down without falling.
+const STEP_HEIGHT: f64 = 1.;
+/// Layers dropped each tick while falling.
+const FALL_SPEED: f64 = 0.5;
+/// Layers that can be fallen without being hurt.
+const SAFE_FALL: f64 = 1.;
+/// Damage for each layer fallen past the safe height.
+const FALL_DAMAGE: f64 = 15.;
+
+/// Damage taken from falling between two layers.
+pub fn fall_damage(from: f64, to: f64) -> u32 {
+    ((from - to - SAFE_FALL).max(0.) * FALL_DAMAGE).round() as u32
+}
+
+/// Steps entities onto the ground beneath them, dropping them over several ticks when it is
+/// further than a step away and hurting them once they land.
+pub fn update(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    regions: &RegionManager,
+    deltas: &mut DeltaEncoder,
+    net: &mut Net,
+) {
+    let grounded: Vec<(Entity, Position, f64)> = world
+        .query2::<Position, Health>()
+        .into_iter()
+        .filter(|(entity, _, _)| world.get_component::<Obstacle>(entity).is_none())
+        .map(|(entity, position, _)| {
+            let center = position.bounds().center_2d();
+            let floor = regions.floor_at(&Vec3::new(center.x(), center.y(), 0.));
+            (entity, *position, floor)
+        })
+        .filter(|(_, position, floor)| position.loc.z() != *floor)
+        .collect();
+
+    let mut pos_changes: Vec<ComponentChange<Position>> = vec![];
+    for (entity, position, floor) in grounded.into_iter() {
+        let z = position.loc.z();
+        let falling = world.get_component::<Falling>(&entity).copied();
+        let layer = match falling {
+            // Continue falling, landing once the ground is reached.
+            Some(falling) if z - FALL_SPEED <= floor => {
+                world.remove_component::<Falling>(entity);
+                let damage = fall_damage(falling.from, floor);
+                if damage > 0 {
+                    combat::damage(world, spatial, net, &entity, damage);
+                }
+                floor
+            }
+            Some(_) => z - FALL_SPEED,
+            // Climbing is only possible a step at a time.
+            None if floor > z && floor - z > STEP_HEIGHT => continue,
+            None if z - floor > STEP_HEIGHT => {
+                world.upsert_component(entity, Falling { from: z });
+                let ticks = ((z - floor) / FALL_SPEED).ceil() as f32;
+                let duration = ticks / TimerManager::SERVER_TICKS_PER_SECOND;
+                net.broadcast_to(
+                    get_observers(world, spatial, &entity),
+                    Event::Fall(FallPayload::new(entity, z, floor, duration)),
+                );
+                z - FALL_SPEED
+            }
+            None => floor,
+        };
+
+        let loc = Vec3::new(position.loc.x(), position.loc.y(), layer);
+        let moved = Position::new(loc, position.size);
+        spatial.remove_object(&entity, &position.bounds());
+        spatial.insert_object(&entity, &moved.bounds());
+        pos_changes.push(ComponentChange::Update(entity, moved));
+
+        let nearby = get_observers(world, spatial, &entity);
+        let movement = MovementPayload::new(entity, moved.size, moved.loc, Vec2::ORIGIN);
+        net.broadcast_to(nearby.clone(), deltas.encode(movement, &nearby));
+    }
+
+    ComponentChange::<Position>::processor(world, pos_changes);
+}
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::components::{
+    Bounds, GroundItem, Inventory, ItemStack, Player, Position, Vec2, Vec3,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::payloads::GroundItemPayload;
+use uuid::Uuid;
+
+use super::inventory;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Size of an item lying on the ground.
+const ITEM_SIZE: f64 = 16.;
+/// Range, as a scale of the player's size, that items can be picked up within.
+const PICKUP_RANGE: f64 = 3.;
+
+/// Places a stack of items on the ground.
+pub fn place(world: &mut World, spatial: &mut SpatialHash, ground: GroundItem) -> Entity {
+    let entity = world.spawn().with(ground).build();
+    spatial.insert_object(&entity, &ground.bounds);
+    entity
+}
+
+/// Places stacks of items on the ground side by side, centered on a point.
+pub fn scatter(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    center: Vec3,
+    stacks: &[ItemStack],
+    expires: u64,
+) -> Vec<Entity> {
+    let offset = (stacks.len() as f64 - 1.) / 2.;
+    stacks
+        .iter()
+        .enumerate()
+        .map(|(i, stack)| {
+            let x = center.x() + (i as f64 - offset) * ITEM_SIZE - ITEM_SIZE / 2.;
+            let loc = Vec3::new(x, center.y() - ITEM_SIZE / 2., center.z());
+            let ground = GroundItem {
+                stack: *stack,
+                bounds: Bounds::from_vec(loc, Vec2::new(ITEM_SIZE, ITEM_SIZE)),
+                expires,
+            };
+            place(world, spatial, ground)
+        })
+        .collect()
+}
+
+/// Drops part of a stack from the inventory onto the ground beneath the entity.
+pub fn drop(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    entity: &Entity,
+    index: usize,
+    count: u16,
+    expires: u64,
+) -> Option<Entity> {
+    let pos = world.get_component::<Position>(entity)?;
+    let (center, z) = (pos.bounds().center_2d(), pos.loc.z());
+
+    let inventory = world.get_component_mut::<Inventory>(entity)?;
+    let item = inventory.stacks.get(index)?.item;
+    if count == 0 || !inventory.remove_at(index, count) {
+        return None;
+    }
+
+    // Center the item beneath the entity.
+    let loc = Vec3::new(center.x() - ITEM_SIZE / 2., center.y() - ITEM_SIZE / 2., z);
+    let ground = GroundItem {
+        stack: ItemStack::new(item, count),
+        bounds: Bounds::from_vec(loc, Vec2::new(ITEM_SIZE, ITEM_SIZE)),
+        expires,
+    };
+
+    Some(place(world, spatial, ground))
+}
+
+/// Picks up the closest item within range, taking as much as the entity can carry.
+pub fn pickup(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    items: &ItemManager,
+    entity: &Entity,
+) -> bool {
+    let bounds = match world.get_component::<Position>(entity) {
+        Some(pos) => pos.bounds(),
+        None => return false,
+    };
+
+    // Find the closest item to the entity.
+    let center = Vec3::from_vec2(bounds.center_2d(), 0.);
+    let target = spatial
+        .query(&bounds.scaled_center(PICKUP_RANGE), Some(entity))
+        .into_iter()
+        .filter_map(|e| world.get_component::<GroundItem>(&e).map(|item| (e, *item)))
+        .min_by(|(_, a), (_, b)| {
+            let a = Vec3::from_vec2(a.bounds.center_2d(), 0.).distance_2d(&center);
+            let b = Vec3::from_vec2(b.bounds.center_2d(), 0.).distance_2d(&center);
+            a.total_cmp(&b)
+        });
+
+    let (target, ground) = match target {
+        Some(target) => target,
+        None => return false,
+    };
+
+    let taken = inventory::give(world, items, entity, ground.stack.item, ground.stack.count);
+    if taken == 0 {
+        return false;
+    }
+
+    if taken < ground.stack.count {
+        // Leave what could not be carried.
+        if let Some(ground) = world.get_component_mut::<GroundItem>(&target) {
+            ground.stack.count -= taken;
+        }
+    } else {
+        remove(world, spatial, &target);
+    }
+
+    true
+}
+
+/// Removes an item from the ground, returning if it existed.
+pub fn remove(world: &mut World, spatial: &mut SpatialHash, entity: &Entity) -> bool {
+    let bounds = match world.get_component::<GroundItem>(entity) {
+        Some(ground) => ground.bounds,
+        None => return false,
+    };
+
+    spatial.remove_object(entity, &bounds);
+    world.despawn(entity);
+    true
+}
+
+/// Informs players of the items entering and leaving their view, tracking what each can see.
+/// Items that changed since they were last seen are sent again.
+pub fn visibility(
+    world: &World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    visible: &mut HashMap<Uuid, HashMap<Entity, ItemStack>>,
+) {
+    let mut players: HashSet<Uuid> = HashSet::new();
+
+    for (entity, player) in world.query1::<Player>() {
+        let uuid = *player.uuid();
+        let center = match world.get_component::<Position>(&entity) {
+            Some(pos) => pos.bounds().center_2d(),
+            None => continue,
+        };
+        players.insert(uuid);
+
+        // All items currently within view.
+        let current: HashMap<Entity, GroundItem> = spatial
+            .query_radius(center, spatial.interest().other, Some(&entity))
+            .into_iter()
+            .filter_map(|e| world.get_component::<GroundItem>(&e).map(|item| (e, *item)))
+            .collect();
+
+        let seen = visible.entry(uuid).or_default();
+        for (item, ground) in current.iter() {
+            if seen.get(item) != Some(&ground.stack) {
+                seen.insert(*item, ground.stack);
+                net.send_to(
+                    uuid,
+                    Event::GroundItem(GroundItemPayload::new(*item, ground.stack, ground.bounds)),
+                );
+            }
+        }
+
+        seen.retain(|item, _| {
+            let keep = current.contains_key(item);
+            if !keep {
+                net.send_to(uuid, Event::EntityRemoved(*item));
+            }
+            keep
+        });
+    }
+
+    // Forget the players that are no longer in the world.
+    visible.retain(|uuid, _| players.contains(uuid));
+}
+
+/// All items on the ground with where they lie and when they decay.
+pub fn all(world: &World) -> Vec<GroundItem> {
+    world
+        .query1::<GroundItem>()
+        .into_iter()
+        .map(|(_, ground)| *ground)
+        .collect()
+}
@@ -0,0 +1,151 @@
+use uo2d_proto::components::{
+    Acceleration, Area, Boss, Casting, Collidable, Cooldowns, Dormant, Equipment, Falling, Flag,
+    GroundItem, Health, Inventory, Mounted, MovementMode, Npc, Obstacle, Player, Position,
+    Progress, Projectile, Pushable, ResourceNode, Skills, Spawned, Spawner, Stats, StatusEffects,
+    Team, Threat, Vec2, Velocity,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::{OverlayKind, OverlayPayload, OverlayShape};
+
+use super::{movement, threat};
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Length of the line drawn for NPCs wandering without a destination.
+const WANDER_LENGTH: f64 = 48.;
+
+/// Formats every component of the listed types the entity has, by the name of the type.
+macro_rules! describe {
+    ($world:expr, $entity:expr, $($component:ty),* $(,)?) => {{
+        let mut components: Vec<(&'static str, String)> = Vec::new();
+        $(
+            if let Some(component) = $world.get_component::<$component>($entity) {
+                components.push((stringify!($component), format!("{:?}", component)));
+            }
+        )*
+        components
+    }};
+}
+
+/// Number of entities of each kind within the world, a kind being the component that identifies it.
+pub fn census(world: &World) -> Vec<(&'static str, usize)> {
+    vec![
+        ("players", world.get_entities::<Player>().len()),
+        ("npcs", world.get_entities::<Npc>().len()),
+        ("dormant npcs", world.get_entities::<Dormant>().len()),
+        ("bosses", world.get_entities::<Boss>().len()),
+        ("projectiles", world.get_entities::<Projectile>().len()),
+        ("ground items", world.get_entities::<GroundItem>().len()),
+        ("obstacles", world.get_entities::<Obstacle>().len()),
+        ("resource nodes", world.get_entities::<ResourceNode>().len()),
+        ("spawners", world.get_entities::<Spawner>().len()),
+        ("areas", world.get_entities::<Area>().len()),
+        ("flags", world.get_entities::<Flag>().len()),
+        ("positioned", world.get_entities::<Position>().len()),
+    ]
+}
+
+/// Every component the entity has, formatted for reading. Empty if the entity does not exist.
+pub fn components(world: &World, entity: &Entity) -> Vec<(&'static str, String)> {
+    describe!(
+        world,
+        entity,
+        Position,
+        Velocity,
+        Acceleration,
+        Player,
+        Projectile,
+        Health,
+        Equipment,
+        Inventory,
+        GroundItem,
+        Collidable,
+        Pushable,
+        Obstacle,
+        Stats,
+        Team,
+        Flag,
+        StatusEffects,
+        Cooldowns,
+        Casting,
+        Mounted,
+        MovementMode,
+        Falling,
+        Npc,
+        Spawner,
+        Spawned,
+        Threat,
+        Boss,
+        Dormant,
+        Area,
+        Progress,
+        Skills,
+        ResourceNode,
+    )
+}
+
+/// Shapes of an overlay within the radius of the center, as many as fit within a packet.
+pub fn overlay(
+    world: &World,
+    spatial: &SpatialHash,
+    regions: &RegionManager,
+    kind: OverlayKind,
+    center: Vec2,
+    radius: f64,
+) -> OverlayPayload {
+    let mut payload = OverlayPayload::new(kind);
+    let nearby = spatial.query_radius(center, radius, None);
+    let bounds = |entity: &Entity| Some(world.get_component::<Position>(entity)?.bounds());
+
+    let shapes: Vec<OverlayShape> = match kind {
+        OverlayKind::Bounds => nearby
+            .iter()
+            .filter_map(|entity| Some(OverlayShape::rect(&bounds(entity)?)))
+            .collect(),
+        OverlayKind::Cells => spatial
+            .occupied_within(center, radius)
+            .iter()
+            .map(OverlayShape::rect)
+            .collect(),
+        OverlayKind::Regions => regions
+            .regions()
+            .into_iter()
+            .map(|region| OverlayShape::polygon(region.vertices()))
+            .collect(),
+        // Only those that move around have anything worth seeing.
+        OverlayKind::Interest => nearby
+            .iter()
+            .filter(|entity| {
+                world.get_component::<Player>(entity).is_some()
+                    || world.get_component::<Npc>(entity).is_some()
+            })
+            .filter_map(|entity| {
+                let radius = movement::interest_radius(world, spatial, entity);
+                Some(OverlayShape::circle(bounds(entity)?.center_2d(), radius))
+            })
+            .collect(),
+        OverlayKind::Paths => nearby
+            .iter()
+            .filter_map(|entity| {
+                let npc = world.get_component::<Npc>(entity)?;
+                let from = bounds(entity)?.center_2d();
+                let to = match threat::destination(world, entity) {
+                    Some(to) => to,
+                    None if npc.heading != Vec2::ORIGIN => {
+                        let step = npc.heading.normalize().scaled(WANDER_LENGTH);
+                        Vec2::new(from.x() + step.x(), from.y() + step.y())
+                    }
+                    None => return None,
+                };
+                Some(OverlayShape::line(from, to))
+            })
+            .collect(),
+    };
+
+    for shape in shapes {
+        if !payload.push(shape) {
+            break;
+        }
+    }
+    payload
+}
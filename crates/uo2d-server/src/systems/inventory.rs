@@ -0,0 +1,59 @@
+use uo2d_proto::components::{Equipment, Inventory, ItemId, Player};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::payloads::InventoryPayload;
+
+use crate::net::{Event, Net};
+
+/// Gives an entity up to `count` of an item, limited by the weight it can carry.
+/// All pickups and trades go through here so the limit is always enforced.
+/// Returns the amount that was accepted.
+pub fn give(
+    world: &mut World,
+    items: &ItemManager,
+    entity: &Entity,
+    item: ItemId,
+    count: u16,
+) -> u16 {
+    match world.get_component_mut::<Inventory>(entity) {
+        Some(inventory) => items.add(inventory, item, count),
+        None => 0,
+    }
+}
+
+/// Splits part of a stack into a new stack.
+pub fn split(world: &mut World, entity: &Entity, index: usize, count: u16) -> bool {
+    world
+        .get_component_mut::<Inventory>(entity)
+        .is_some_and(|inventory| inventory.split(index, count))
+}
+
+/// Moves a stack to another slot of the inventory.
+pub fn move_stack(world: &mut World, entity: &Entity, from: usize, to: usize) -> bool {
+    world
+        .get_component_mut::<Inventory>(entity)
+        .is_some_and(|inventory| inventory.move_stack(from, to))
+}
+
+/// Informs the owner of what they are carrying, if they are a player.
+pub fn changed(world: &World, net: &mut Net, entity: &Entity) {
+    let (Some(player), Some(inventory)) = (
+        world.get_component::<Player>(entity),
+        world.get_component::<Inventory>(entity),
+    ) else {
+        return;
+    };
+    let equipment = world
+        .get_component::<Equipment>(entity)
+        .copied()
+        .unwrap_or_default();
+
+    net.send_to(
+        *player.uuid(),
+        Event::Inventory(InventoryPayload::new(
+            inventory.stacks.clone(),
+            inventory.capacity,
+            equipment,
+        )),
+    );
+}
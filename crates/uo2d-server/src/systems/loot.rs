@@ -0,0 +1,57 @@
+use uo2d_proto::components::{Health, Inventory, Loot, Position, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+
+use super::{ground, roll};
+use crate::loot::LootManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Rolls the loot of the entities that died or were destroyed, scattering it where they fell.
+/// Each entity is only rolled for once, returns the items placed on the ground.
+pub fn drop(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    loot: &LootManager,
+    tick: u64,
+    expires: u64,
+) -> Vec<Entity> {
+    let dead: Vec<(Entity, Loot, Vec3)> = world
+        .query2::<Loot, Health>()
+        .into_iter()
+        .filter(|(_, _, health)| health.is_dead())
+        .filter_map(|(entity, table, _)| {
+            let position = world.get_component::<Position>(&entity)?;
+            let center = position.bounds().center_2d();
+            let center = Vec3::new(center.x(), center.y(), position.loc.z());
+            Some((entity, table.clone(), center))
+        })
+        .collect();
+
+    let mut placed = vec![];
+    for (entity, table, center) in dead.into_iter() {
+        world.remove_component::<Loot>(entity);
+        let stacks = loot.roll(&table.table, table.level, roll(&entity, tick));
+        placed.extend(ground::scatter(world, spatial, center, &stacks, expires));
+    }
+    placed
+}
+
+/// Fills a container from its loot table, as much as it can hold.
+pub fn fill(
+    world: &mut World,
+    items: &ItemManager,
+    loot: &LootManager,
+    entity: &Entity,
+    tick: u64,
+) {
+    let stacks = match world.get_component::<Loot>(entity) {
+        Some(table) => loot.roll(&table.table, table.level, roll(entity, tick)),
+        None => return,
+    };
+
+    if let Some(inventory) = world.get_component_mut::<Inventory>(entity) {
+        for stack in stacks {
+            items.add(inventory, stack.item, stack.count);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+pub mod areas;
+pub mod bank;
+pub mod bosses;
+pub mod casting;
+pub mod chunks;
+pub mod combat;
+pub mod consistency;
+pub mod consumables;
+pub mod dialogue;
+pub mod dormancy;
+pub mod effects;
+pub mod equipment;
+pub mod falling;
+pub mod ground;
+pub mod inspect;
+pub mod inventory;
+pub mod loot;
+pub mod mounts;
+pub mod movement;
+pub mod npcs;
+pub mod obstacles;
+pub mod physics;
+pub mod plots;
+pub mod resources;
+pub mod sight;
+pub mod spawners;
+pub mod stats;
+pub mod terrain;
+pub mod threat;
+
+use uo2d_proto::ecs::Entity;
+
+/// Pseudo-random number for an entity on a tick, xorshift is plenty for gameplay rolls.
+pub fn roll(entity: &Entity, tick: u64) -> u64 {
+    let mut seed = (entity.id() ^ tick.wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1;
+    for _ in 0..3 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+    }
+    seed
+}
@@ -0,0 +1,72 @@
+use uo2d_proto::components::{Health, Mounted, Position};
+use uo2d_proto::ecs::{Entity, World};
+
+use super::equipment::appearance;
+use super::terrain;
+use crate::net::Net;
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// Checks if an entity is riding a mount.
+pub fn is_mounted(world: &World, entity: &Entity) -> bool {
+    world.get_component::<Mounted>(entity).is_some()
+}
+
+/// Checks if an entity is standing somewhere a mount can be ridden.
+fn can_ride(world: &World, regions: &RegionManager, entity: &Entity) -> bool {
+    world
+        .get_component::<Position>(entity)
+        .and_then(|position| regions.get_region(&position.loc))
+        .is_some_and(|region| !region.indoors)
+}
+
+/// Mounts an entity, showing the change to those nearby.
+pub fn mount(
+    world: &mut World,
+    spatial: &SpatialHash,
+    regions: &RegionManager,
+    net: &mut Net,
+    entity: &Entity,
+) -> Result<(), &'static str> {
+    if is_mounted(world, entity) {
+        return Err("mount.already");
+    } else if world
+        .get_component::<Health>(entity)
+        .is_some_and(|health| health.is_dead())
+    {
+        return Err("common.dead");
+    } else if terrain::is_swimming(world, entity) {
+        return Err("mount.swimming");
+    } else if !can_ride(world, regions, entity) {
+        return Err("mount.indoors");
+    }
+
+    world.upsert_component(*entity, Mounted);
+    appearance(world, spatial, net, entity);
+    Ok(())
+}
+
+/// Dismounts an entity, returning whether it was riding.
+pub fn dismount(world: &mut World, spatial: &SpatialHash, net: &mut Net, entity: &Entity) -> bool {
+    if !is_mounted(world, entity) {
+        return false;
+    }
+
+    world.remove_component::<Mounted>(*entity);
+    appearance(world, spatial, net, entity);
+    true
+}
+
+/// Dismounts the riders that have gone indoors.
+pub fn update(world: &mut World, spatial: &SpatialHash, regions: &RegionManager, net: &mut Net) {
+    let indoors: Vec<Entity> = world
+        .query1::<Mounted>()
+        .into_iter()
+        .map(|(entity, _)| entity)
+        .filter(|entity| !can_ride(world, regions, entity))
+        .collect();
+
+    for entity in indoors.iter() {
+        dismount(world, spatial, net, entity);
+    }
+}
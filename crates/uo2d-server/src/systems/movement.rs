@@ -0,0 +1,430 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::components::{
+    Bounds, Collidable, Npc, Obstacle, Player, Position, Projectile, Pushable, Transform, Vec2,
+    Vec3, Velocity,
+};
+use uo2d_proto::ecs::{ComponentChange, Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::payloads::MovementPayload;
+use uuid::Uuid;
+
+use super::{combat, stats};
+use crate::delta::DeltaEncoder;
+use crate::net::{Event, Net};
+use crate::region::{Region, RegionManager};
+use crate::replication::Replicator;
+use crate::spatial_hash::SpatialHash;
+
+/// Amount of damage a projectile deals on impact.
+const PROJECTILE_DAMAGE: u32 = 10;
+/// Furthest an overlapping entity is pushed out in a single tick.
+const MAX_SEPARATION: f64 = 8.;
+
+/// A query to move an entity. Useful to check multiple movements in 1 tick.
+#[derive(Debug)]
+pub struct MoveQuery {
+    pub entity: Entity,
+    pub source: Vec3,
+    pub destination: Vec3,
+    pub velocity: Vec2,
+    pub entity_size: Vec2,
+    pub nearby: HashSet<Entity>,
+}
+
+impl MoveQuery {
+    /// Checks if a move has happened.
+    pub fn has_moved(&self) -> bool {
+        self.source.round() != self.destination.round()
+    }
+
+    /// Checks if the entitiy is potentialy stuck against a boundary or collision.
+    pub fn is_stuck(&self) -> bool {
+        !self.has_moved() && self.velocity != Vec2::ORIGIN
+    }
+
+    // The bounds for the query based on the position provided.
+    pub fn bounds(&self, pos: Vec3) -> Bounds {
+        Bounds::from_vec(pos, self.entity_size)
+    }
+}
+
+/// A system used to process all entities that have positions and velocities. Essentially this is currently moving entities.
+#[allow(clippy::too_many_arguments)]
+pub fn with_velocity(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    regions: &RegionManager,
+    items: &ItemManager,
+    deltas: &mut DeltaEncoder,
+    replicator: &mut Replicator,
+    net: &mut Net,
+    friendly_fire: f64,
+) {
+    let mut pos_changes: Vec<ComponentChange<Position>> = vec![];
+    let mut vel_changes: Vec<ComponentChange<Velocity>> = vec![];
+    let mut despawn: Vec<Entity> = vec![];
+    let mut hits: Vec<(Entity, Entity)> = vec![];
+    let mut traveled: Vec<(Entity, f64)> = vec![];
+
+    let positions: HashMap<Entity, &Position> = world
+        .query2::<Position, Collidable>()
+        .into_iter()
+        .map(|(entity, pos, _)| (entity, pos))
+        .collect();
+
+    // Iterate all entities with position and velocity.
+    for (entity, pos, vel) in world.query2::<Position, Velocity>() {
+        // Obtain the region for the entity.
+        let region = match regions.get_region(&pos.loc) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        // Speed is limited by the physics step, projectiles never slip along the edges.
+        let projectile = world.get_component::<Projectile>(&entity);
+        let is_projectile = projectile.is_some();
+
+        // Get the movement query and check if it can move.
+        let mut query = check_move(region, entity, *pos, vel.0, !is_projectile);
+
+        // One query covers both the players observing the entity and what it may collide with.
+        let center = pos.bounds().center_2d();
+        let radius = interest_radius(world, spatial, &entity);
+        let destination = query.bounds(query.destination);
+        let area = Bounds::from_vertices(&[
+            Vec3::new(center.x() - radius, center.y() - radius, pos.loc.z()),
+            Vec3::new(center.x() + radius, center.y() + radius, pos.loc.z()),
+            destination.top_left_3d(),
+            destination.bottom_right_3d(),
+        ]);
+        let candidates = spatial.query(&area, Some(&entity));
+        if query.has_moved() {
+            query.nearby = candidates
+                .iter()
+                .filter(|other| {
+                    positions
+                        .get(other)
+                        .is_some_and(|other| other.bounds().intersects_2d(&destination))
+                })
+                .copied()
+                .collect();
+        }
+        let pos = match SpatialHash::till_collisions(&query, &positions, 1.0) {
+            Some(pos) => pos,
+            None => {
+                // Unavoidable collision detected.
+                query.source
+            }
+        };
+
+        // Anything pushable that blocked the move is sent along with the same velocity.
+        if !is_projectile && pos != query.destination {
+            let blocked = query.bounds(query.destination);
+            for target in query.nearby.iter() {
+                let pushable = world.get_component::<Pushable>(target).is_some();
+                if let (true, Some(target_pos)) = (pushable, positions.get(target)) {
+                    if target_pos.bounds().intersects_2d(&blocked) {
+                        vel_changes
+                            .push(ComponentChange::Update(*target, Velocity(query.velocity)));
+                    }
+                }
+            }
+        }
+
+        // Obtains the nearby players, including the one moving.
+        let observers = observers_within(world, spatial, &candidates, center, radius, &entity);
+        let nearby: HashSet<Uuid> = observers.iter().map(|(uuid, _)| *uuid).collect();
+
+        // Did not move. Remove velocity.
+        if pos == query.source || query.is_stuck() {
+            if let Some(projectile) = projectile {
+                // Damage everything the projectile ran into.
+                let impact = query.bounds(query.destination);
+                for target in query.nearby.iter() {
+                    if let Some(target_pos) = positions.get(target) {
+                        if target_pos.bounds().intersects_2d(&impact) {
+                            hits.push((projectile.owner, *target));
+                        }
+                    }
+                }
+
+                // It is a projectile that cannot move, delete it.
+                despawn.push(entity);
+                spatial.remove_object(&query.entity, &query.bounds(query.source));
+                net.broadcast_to(nearby, Event::EntityRemoved(entity));
+                continue;
+            }
+
+            // Players that skipped its last movement are sent where it stopped.
+            let behind = replicator.settle(&entity, &nearby);
+            if !behind.is_empty() {
+                let movement =
+                    MovementPayload::new(entity, query.entity_size, query.source, Vec2::ORIGIN);
+                net.broadcast_to(behind.clone(), deltas.encode(movement, &behind));
+            }
+
+            vel_changes.push(ComponentChange::Remove(entity));
+            continue;
+        }
+
+        // Entity moved, the velocity is kept and slowed by friction in the physics step.
+        query.destination = pos;
+        traveled.push((entity, query.source.distance_2d(&query.destination)));
+        let pos_change = Position::new(query.destination, query.entity_size);
+        pos_changes.push(ComponentChange::Update(entity, pos_change));
+        move_entity(spatial, &query);
+
+        // Set the packet to be sent.
+        let movement =
+            MovementPayload::new(entity, query.entity_size, query.destination, query.velocity);
+        let event = deltas.encode(movement, &nearby);
+        // Movement will only be sent to the nearby players, the far ones less often.
+        let due = replicator.due(entity, &observers, event.is_keyframe());
+        net.broadcast_to(due, event);
+    }
+
+    // Process the updates / component changes.
+    ComponentChange::<Velocity>::processor(world, vel_changes);
+    ComponentChange::<Position>::processor(world, pos_changes);
+    for (entity, distance) in traveled.into_iter() {
+        stats::traveled(world, &entity, distance);
+    }
+
+    // Despawn all entities flagged.
+    for entity in despawn.into_iter() {
+        world.despawn(&entity);
+    }
+
+    // Apply the damage from projectile impacts.
+    for (attacker, target) in hits.into_iter() {
+        combat::hit(
+            world,
+            spatial,
+            items,
+            net,
+            &attacker,
+            &target,
+            PROJECTILE_DAMAGE,
+            friendly_fire,
+        );
+    }
+}
+
+/// Pushes apart overlapping entities along the minimum translation vector, a little each tick.
+/// Obstacles that cannot be pushed stay in place, moving the other entity the full distance.
+pub fn separate(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    regions: &RegionManager,
+    deltas: &mut DeltaEncoder,
+    net: &mut Net,
+) {
+    let mut offsets: HashMap<Entity, Vec2> = HashMap::new();
+    let movable = |entity: &Entity| {
+        world.get_component::<Obstacle>(entity).is_none()
+            || world.get_component::<Pushable>(entity).is_some()
+    };
+
+    let positions: HashMap<Entity, Position> = world
+        .query2::<Position, Collidable>()
+        .into_iter()
+        .filter(|(entity, _, _)| world.get_component::<Projectile>(entity).is_none())
+        .map(|(entity, pos, _)| (entity, *pos))
+        .collect();
+
+    for (entity, pos) in positions.iter() {
+        let bounds = pos.bounds();
+        for other in spatial.query(&bounds, Some(entity)) {
+            // Each pair is only resolved once, projectiles impact instead.
+            let other_bounds = match positions.get(&other) {
+                Some(other_pos) if other > *entity => other_pos.bounds(),
+                _ => continue,
+            };
+
+            let mtv = match min_translation(&bounds, &other_bounds) {
+                Some(mtv) => mtv,
+                None => continue,
+            };
+
+            let share = match (movable(entity), movable(&other)) {
+                (true, true) => 0.5,
+                (true, false) => 1.,
+                (false, true) => 0.,
+                (false, false) => continue,
+            };
+
+            let add = |offsets: &mut HashMap<Entity, Vec2>, entity: Entity, scale: f64| {
+                let offset = offsets.entry(entity).or_insert(Vec2::ORIGIN);
+                *offset = Vec2::new(offset.x() + mtv.x() * scale, offset.y() + mtv.y() * scale);
+            };
+            add(&mut offsets, *entity, share);
+            add(&mut offsets, other, share - 1.);
+        }
+    }
+
+    let mut pos_changes: Vec<ComponentChange<Position>> = vec![];
+    for (entity, offset) in offsets.into_iter() {
+        let pos = positions[&entity];
+
+        // Remain within the region while being pushed out.
+        let offset = offset.clamped(0., MAX_SEPARATION);
+        let moved = Bounds::from_vec(
+            Vec3::new(
+                pos.loc.x() + offset.x(),
+                pos.loc.y() + offset.y(),
+                pos.loc.z(),
+            ),
+            pos.size,
+        );
+        let moved = match regions.get_region(&pos.loc) {
+            Some(region) => region.bounding_box().clamp_within(&moved),
+            None => continue,
+        };
+
+        let destination = moved.top_left_3d();
+        if destination == pos.loc {
+            continue;
+        }
+
+        spatial.remove_object(&entity, &pos.bounds());
+        spatial.insert_object(&entity, &moved);
+        pos_changes.push(ComponentChange::Update(
+            entity,
+            Position::new(destination, pos.size),
+        ));
+
+        let nearby = get_observers(world, spatial, &entity);
+        let movement = MovementPayload::new(entity, pos.size, destination, Vec2::ORIGIN);
+        net.broadcast_to(nearby.clone(), deltas.encode(movement, &nearby));
+    }
+
+    ComponentChange::<Position>::processor(world, pos_changes);
+}
+
+/// Smallest offset that moves `bounds` out of `other`, None if they do not overlap.
+fn min_translation(bounds: &Bounds, other: &Bounds) -> Option<Vec2> {
+    if !bounds.intersects_2d(other) {
+        return None;
+    }
+
+    let overlap_x =
+        (bounds.x() + bounds.width()).min(other.x() + other.width()) - bounds.x().max(other.x());
+    let overlap_y =
+        (bounds.y() + bounds.height()).min(other.y() + other.height()) - bounds.y().max(other.y());
+
+    // Push away from the other center, entities stacked exactly are split along the x-axis.
+    let (center, other_center) = (bounds.center_2d(), other.center_2d());
+    let direction = |a: f64, b: f64| if a < b { -1. } else { 1. };
+    if overlap_x <= overlap_y {
+        Some(Vec2::new(
+            overlap_x * direction(center.x(), other_center.x()),
+            0.,
+        ))
+    } else {
+        Some(Vec2::new(
+            0.,
+            overlap_y * direction(center.y(), other_center.y()),
+        ))
+    }
+}
+
+/// Checks the entities attempted movement to ensure it is within the boundaries. Returns a MoveQuery used to check collision with other entities.
+fn check_move(
+    region: &Region,
+    entity: Entity,
+    position: Position,
+    velocity: Vec2,
+    slip: bool,
+) -> MoveQuery {
+    // Apply movement deltas within bounds.
+    let mut transform = Transform::from_bounds(position.bounds());
+    transform = transform.applied_velocity(&velocity, &region.bounding_box(), slip);
+    let velocity = transform.position().offset_from_2d(&position.loc).as_vec2();
+
+    // Builds the query, the nearby entities are filled in by the caller.
+    MoveQuery {
+        entity,
+        source: position.loc,
+        destination: transform.position(),
+        velocity,
+        entity_size: position.size,
+        nearby: HashSet::new(),
+    }
+}
+
+/// Finalizes the movement utilizing the query. Updates the spatial hash with the new position.
+pub fn move_entity(spatial_area: &mut SpatialHash, query: &MoveQuery) {
+    spatial_area.remove_object(&query.entity, &query.bounds(query.source));
+    spatial_area.insert_object(&query.entity, &query.bounds(query.destination));
+}
+
+/// Distance players are able to see the entity from, by its kind.
+pub fn interest_radius(world: &World, spatial: &SpatialHash, entity: &Entity) -> f64 {
+    let interest = spatial.interest();
+    if world.get_component::<Player>(entity).is_some() {
+        interest.player
+    } else if world.get_component::<Npc>(entity).is_some() {
+        interest.npc
+    } else if world.get_component::<Projectile>(entity).is_some() {
+        interest.projectile
+    } else {
+        interest.other
+    }
+}
+
+/// Obtain all players within the interest radius of the entity.
+pub fn get_nearby(world: &World, spatial: &SpatialHash, entity: &Entity) -> Vec<(Entity, Player)> {
+    let pos = match world.get_component::<Position>(entity) {
+        Some(pos) => pos,
+        None => return Vec::new(),
+    };
+
+    let radius = interest_radius(world, spatial, entity);
+    spatial
+        .query_radius(pos.bounds().center_2d(), radius, Some(entity))
+        .into_iter()
+        .filter_map(|other| Some((other, *world.get_component::<Player>(&other)?)))
+        .collect()
+}
+
+/// Obtain all players that can see the entity, including the entity itself if it is a player.
+pub fn get_observers(world: &World, spatial: &SpatialHash, entity: &Entity) -> HashSet<Uuid> {
+    let mut nearby: HashSet<Uuid> = get_nearby(world, spatial, entity)
+        .into_iter()
+        .map(|(_e, p)| *p.uuid())
+        .collect();
+
+    if let Some(player) = world.get_component::<Player>(entity) {
+        nearby.insert(*player.uuid());
+    }
+
+    nearby
+}
+
+/// Players among the candidates within the radius and their distance from the center, including
+/// the entity itself if it is a player.
+fn observers_within(
+    world: &World,
+    spatial: &SpatialHash,
+    candidates: &HashSet<Entity>,
+    center: Vec2,
+    radius: f64,
+    entity: &Entity,
+) -> Vec<(Uuid, f64)> {
+    let within = candidates.iter().filter_map(|other| {
+        let pos = world.get_component::<Position>(other)?;
+        let bounds = pos.bounds();
+        spatial
+            .reaches(&bounds, center, radius)
+            .then(|| (other, bounds.center_2d().distance(&center)))
+    });
+
+    within
+        .chain(std::iter::once((entity, 0.)))
+        .filter_map(|(other, distance)| {
+            let player = world.get_component::<Player>(other)?;
+            Some((*player.uuid(), distance))
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+use uo2d_proto::components::{
+    Banker, Boss, Collidable, Health, Loot, Npc, Position, Spawned, Threat, Vec2, Vec3,
+};
+use uo2d_proto::ecs::{Entity, World};
+
+use crate::npcs::NpcDefinition;
+use crate::spatial_hash::SpatialHash;
+
+/// Size of an NPC placed in the world.
+const NPC_SIZE: f64 = 32.;
+/// Range, as a scale of the player's size, that NPCs can be talked to within.
+const TALK_RANGE: f64 = 3.;
+
+/// Places an NPC in the world on behalf of a spawner.
+pub fn spawn(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    definition: &NpcDefinition,
+    position: Vec3,
+    spawner: Entity,
+) -> Entity {
+    let position = Position::new(position, Vec2::new(NPC_SIZE, NPC_SIZE));
+    let mut builder = world
+        .spawn()
+        .with(position)
+        .with(Npc {
+            kind: definition.kind.clone(),
+            dialogue: definition.dialogue.clone(),
+            wander: definition.wander,
+            heading: Vec2::ORIGIN,
+            chase: definition.chase,
+        })
+        .with(Spawned(spawner))
+        .with(Collidable);
+    if let Some(health) = definition.health {
+        // Only NPCs that can be harmed hold a grudge.
+        builder = builder
+            .with(Health::new(health))
+            .with(Threat::new(definition.aggro));
+    }
+
+    if !definition.phases.is_empty() {
+        builder = builder.with(Boss::new(&definition.kind));
+    }
+
+    if definition.banker {
+        builder = builder.with(Banker);
+    }
+
+    if let Some(table) = &definition.loot {
+        builder = builder.with(Loot {
+            table: table.clone(),
+            level: definition.level,
+        });
+    }
+
+    let entity = builder.build();
+    spatial.insert_object(&entity, &position.bounds());
+    entity
+}
+
+/// NPCs close enough to an entity to be talked to, nearest first.
+pub fn within_reach(world: &World, spatial: &SpatialHash, entity: &Entity) -> Vec<Entity> {
+    let bounds = match world.get_component::<Position>(entity) {
+        Some(position) => position.bounds(),
+        None => return vec![],
+    };
+    let center = bounds.center_2d();
+    let range = bounds.scaled_center(TALK_RANGE);
+
+    let distance = |npc: &Entity| {
+        world
+            .get_component::<Position>(npc)
+            .map_or(f64::MAX, |position| {
+                position.bounds().center_2d().distance(&center)
+            })
+    };
+    let mut npcs: Vec<Entity> = spatial
+        .query(&range, Some(entity))
+        .into_iter()
+        .filter(|npc| world.get_component::<Npc>(npc).is_some())
+        .collect();
+    npcs.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+    npcs
+}
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, HashSet};
+
+use uo2d_proto::components::{
+    Collidable, Health, Inventory, Npc, Obstacle, ObstacleKind, Player, Position, Pushable,
+    ResourceNode, Vec2, Vec3,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::MovementPayload;
+use uuid::Uuid;
+
+use super::movement;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Size of an obstacle placed in the world.
+const OBSTACLE_SIZE: f64 = 32.;
+/// Health of an obstacle that can be destroyed.
+const BARREL_HEALTH: u32 = 30;
+/// Weight a container placed within a plot can hold.
+const CONTAINER_CAPACITY: u32 = 200;
+
+/// Places an obstacle in the world.
+pub fn spawn(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    kind: ObstacleKind,
+    position: Vec3,
+) -> Entity {
+    let position = Position::new(position, Vec2::new(OBSTACLE_SIZE, OBSTACLE_SIZE));
+    let builder = world
+        .spawn()
+        .with(position)
+        .with(Obstacle(kind))
+        .with(Collidable);
+
+    let entity = match kind {
+        ObstacleKind::Block => builder.with(Pushable).build(),
+        ObstacleKind::Barrel => builder.with(Health::new(BARREL_HEALTH)).build(),
+        ObstacleKind::Decoration => builder.build(),
+        ObstacleKind::Container => builder.with(Inventory::new(CONTAINER_CAPACITY)).build(),
+    };
+
+    spatial.insert_object(&entity, &position.bounds());
+    entity
+}
+
+/// Removes the obstacles that have been destroyed, clients are informed once it leaves their view.
+pub fn destroyed(world: &mut World, spatial: &mut SpatialHash) {
+    let destroyed: Vec<(Entity, Position)> = world
+        .query2::<Obstacle, Health>()
+        .into_iter()
+        .filter(|(_, _, health)| health.is_dead())
+        .filter_map(|(entity, _, _)| {
+            let position = world.get_component::<Position>(&entity)?;
+            Some((entity, *position))
+        })
+        .collect();
+
+    for (entity, position) in destroyed.into_iter() {
+        spatial.remove_object(&entity, &position.bounds());
+        world.despawn(&entity);
+    }
+}
+
+/// Informs players of the obstacles, NPCs, and resource nodes entering and leaving their view, tracking what each can see.
+pub fn visibility(
+    world: &World,
+    spatial: &SpatialHash,
+    net: &mut Net,
+    visible: &mut HashMap<Uuid, HashSet<Entity>>,
+) {
+    let mut players: HashSet<Uuid> = HashSet::new();
+
+    for (entity, player, pos) in world.query2::<Player, Position>() {
+        let uuid = *player.uuid();
+        players.insert(uuid);
+
+        // All obstacles currently within view, each kind seen from its own distance.
+        let center = pos.bounds().center_2d();
+        let interest = spatial.interest();
+        let current: HashMap<Entity, Position> = spatial
+            .query_radius(center, interest.npc.max(interest.other), Some(&entity))
+            .into_iter()
+            .filter(|e| {
+                world.get_component::<Obstacle>(e).is_some()
+                    || world.get_component::<Npc>(e).is_some()
+                    || world.get_component::<ResourceNode>(e).is_some()
+            })
+            .filter_map(|e| world.get_component::<Position>(&e).map(|pos| (e, *pos)))
+            .filter(|(e, pos)| {
+                let radius = movement::interest_radius(world, spatial, e);
+                spatial.reaches(&pos.bounds(), center, radius)
+            })
+            .collect();
+
+        let seen = visible.entry(uuid).or_default();
+        for (obstacle, position) in current.iter() {
+            if seen.insert(*obstacle) {
+                net.send_to(
+                    uuid,
+                    Event::EntityMoved(MovementPayload::new(
+                        *obstacle,
+                        position.size,
+                        position.loc,
+                        Vec2::ORIGIN,
+                    )),
+                );
+            }
+        }
+
+        seen.retain(|obstacle| {
+            let keep = current.contains_key(obstacle);
+            if !keep {
+                net.send_to(uuid, Event::EntityRemoved(*obstacle));
+            }
+            keep
+        });
+    }
+
+    // Forget the players that are no longer in the world.
+    visible.retain(|uuid, _| players.contains(uuid));
+}
@@ -0,0 +1,94 @@
+use uo2d_proto::components::{
+    Acceleration, Mounted, Position, Projectile, StatusEffect, Vec2, Velocity,
+};
+use uo2d_proto::ecs::{ComponentChange, Entity, World};
+
+use super::{effects, terrain};
+use crate::region::RegionManager;
+
+/// Velocity gained per tick while a movement input is held.
+const ACCELERATION: f64 = 12.;
+/// Fraction of a projectile's velocity lost each tick.
+const PROJECTILE_DRAG: f64 = 0.05;
+/// Speed below which an entity comes to rest.
+const MIN_SPEED: f64 = 0.5;
+/// Scale of the maximum speed while hastened.
+const HASTE_SCALE: f64 = 1.5;
+/// Scale of the maximum speed while riding a mount.
+const MOUNT_SCALE: f64 = 1.6;
+/// Scale of the maximum speed while swimming.
+const SWIM_SCALE: f64 = 0.5;
+
+/// Scale of the maximum speed an entity moves at, raised by haste and mounts, lowered by swimming.
+pub fn speed_scale(world: &World, entity: &Entity) -> f64 {
+    let mut scale = 1.;
+    if effects::has(world, entity, StatusEffect::Haste) {
+        scale *= HASTE_SCALE;
+    }
+    if world.get_component::<Mounted>(entity).is_some() {
+        scale *= MOUNT_SCALE;
+    }
+    if terrain::is_swimming(world, entity) {
+        scale *= SWIM_SCALE;
+    }
+    scale
+}
+
+/// Applies movement inputs and friction to the velocities, enforcing the maximum speed.
+pub fn step(world: &mut World, regions: &RegionManager) {
+    let mut vel_changes: Vec<ComponentChange<Velocity>> = vec![];
+    let inputs: Vec<Entity> = world
+        .query1::<Acceleration>()
+        .into_iter()
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for (entity, pos) in world.query1::<Position>() {
+        let input = world.get_component::<Acceleration>(&entity).map(|a| a.0);
+        let velocity = match (world.get_component::<Velocity>(&entity), input) {
+            (Some(velocity), _) => velocity.0,
+            (None, Some(_)) => Vec2::ORIGIN,
+            (None, None) => continue,
+        };
+
+        let region = match regions.get_region(&pos.loc) {
+            Some(region) => region,
+            None => continue,
+        };
+
+        // Projectiles travel through the air, everything else drags along the terrain.
+        let is_projectile = world.get_component::<Projectile>(&entity).is_some();
+        let (friction, max_speed) = if is_projectile {
+            (PROJECTILE_DRAG, region.tile_length())
+        } else {
+            (region.friction, region.tile * speed_scale(world, &entity))
+        };
+
+        // Accelerate towards the input, never passing the target it points at.
+        let mut velocity = match input {
+            Some(input) if input != Vec2::ORIGIN => {
+                let thrust = input.scaled(ACCELERATION);
+                Vec2::new(velocity.x() + thrust.x(), velocity.y() + thrust.y())
+                    .apply_scalar(1. - friction)
+                    .clamped(0., max_speed.min(input.length()))
+            }
+            _ => velocity.apply_scalar(1. - friction).clamped(0., max_speed),
+        };
+
+        // Projectiles that slow to a halt are left to impact in the movement system.
+        if !is_projectile && velocity.length() < MIN_SPEED {
+            vel_changes.push(ComponentChange::Remove(entity));
+            continue;
+        } else if velocity.length() < MIN_SPEED {
+            velocity = Vec2::ORIGIN;
+        }
+
+        vel_changes.push(ComponentChange::Update(entity, Velocity(velocity)));
+    }
+
+    // Inputs only apply for a single step, clients resend them while held.
+    ComponentChange::<Velocity>::processor(world, vel_changes);
+    for entity in inputs.into_iter() {
+        world.remove_component::<Acceleration>(entity);
+    }
+}
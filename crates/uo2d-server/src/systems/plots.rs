@@ -0,0 +1,173 @@
+use uo2d_proto::components::{
+    Bounds, Collidable, Furnishing, Inventory, Obstacle, ObstacleKind, Position, Vec2, Vec3,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::locale::Text;
+
+use super::obstacles;
+use crate::accounts::{PlacedObject, PlotRecord};
+use crate::plots::Plot;
+use crate::spatial_hash::SpatialHash;
+
+/// Size of an object placed within a plot, matching the obstacles.
+const OBJECT_SIZE: f64 = 32.;
+/// Range, as a scale of the player's size, that placed objects can be removed within.
+const REMOVE_RANGE: f64 = 2.;
+
+/// Places an object beside an entity, on the first side that is within the plot and unobstructed.
+pub fn place(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    plot: &Plot,
+    kind: ObstacleKind,
+    entity: &Entity,
+) -> Result<Entity, Text> {
+    let loc = match world.get_component::<Position>(entity) {
+        Some(position) => position.loc,
+        None => return Err("common.absent".into()),
+    };
+
+    let sides = [(1., 0.), (-1., 0.), (0., 1.), (0., -1.)];
+    let mut within = false;
+    for (x, y) in sides {
+        let spot = Vec3::new(
+            loc.x() + x * OBJECT_SIZE,
+            loc.y() + y * OBJECT_SIZE,
+            loc.z(),
+        );
+        let bounds = Bounds::from_vec(spot, Vec2::new(OBJECT_SIZE, OBJECT_SIZE));
+        if !plot.bounds.contains_2d(&bounds) {
+            continue;
+        }
+
+        within = true;
+        if !is_obstructed(world, spatial, &bounds) {
+            let object = obstacles::spawn(world, spatial, kind, spot);
+            world.add_component(
+                object,
+                Furnishing {
+                    plot: plot.name.clone(),
+                },
+            );
+            return Ok(object);
+        }
+    }
+
+    match within {
+        true => Err("plot.no_room".into()),
+        false => Err("plot.outside".into()),
+    }
+}
+
+/// Checks if anything that blocks movement overlaps the bounds.
+fn is_obstructed(world: &World, spatial: &SpatialHash, bounds: &Bounds) -> bool {
+    spatial
+        .query(bounds, None)
+        .into_iter()
+        .any(|other| world.get_component::<Collidable>(&other).is_some())
+}
+
+/// Closest object placed within a plot that an entity is within range to remove.
+pub fn within_reach(
+    world: &World,
+    spatial: &SpatialHash,
+    entity: &Entity,
+    plot: &str,
+) -> Option<Entity> {
+    let bounds = world.get_component::<Position>(entity)?.bounds();
+    let center = bounds.center_2d();
+    let distance = |object: &Entity| {
+        world
+            .get_component::<Position>(object)
+            .map_or(f64::MAX, |position| {
+                position.bounds().center_2d().distance(&center)
+            })
+    };
+
+    spatial
+        .query(&bounds.scaled_center(REMOVE_RANGE), Some(entity))
+        .into_iter()
+        .filter(|object| {
+            world
+                .get_component::<Furnishing>(object)
+                .is_some_and(|furnishing| furnishing.plot == plot)
+        })
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+/// Removes a placed object, containers have to be emptied first.
+/// Clients are informed once it leaves their view.
+pub fn remove(world: &mut World, spatial: &mut SpatialHash, object: &Entity) -> Result<(), Text> {
+    let holding = world
+        .get_component::<Inventory>(object)
+        .is_some_and(|inventory| !inventory.stacks.is_empty());
+    if holding {
+        return Err("plot.not_empty".into());
+    }
+
+    if let Some(position) = world.get_component::<Position>(object) {
+        spatial.remove_object(object, &position.bounds());
+    }
+    world.despawn(object);
+    Ok(())
+}
+
+/// Removes every object placed within a plot, along with anything stored inside of them.
+pub fn clear(world: &mut World, spatial: &mut SpatialHash, plot: &str) {
+    for object in objects(world, plot) {
+        if let Some(position) = world.get_component::<Position>(&object) {
+            spatial.remove_object(&object, &position.bounds());
+        }
+        world.despawn(&object);
+    }
+}
+
+/// Objects placed within a plot.
+fn objects(world: &World, plot: &str) -> Vec<Entity> {
+    world
+        .query1::<Furnishing>()
+        .into_iter()
+        .filter(|(_, furnishing)| furnishing.plot == plot)
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+/// Ownership and furnishings of a claimed plot to be saved, None if it is unclaimed.
+pub fn record(world: &World, plot: &Plot) -> Option<PlotRecord> {
+    let objects = objects(world, &plot.name)
+        .into_iter()
+        .filter_map(|object| {
+            Some(PlacedObject {
+                kind: world.get_component::<Obstacle>(&object)?.0,
+                position: world.get_component::<Position>(&object)?.loc,
+                contents: world
+                    .get_component::<Inventory>(&object)
+                    .map(|inventory| inventory.stacks.clone())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    Some(PlotRecord {
+        name: plot.name.clone(),
+        owner: plot.owner?,
+        members: plot.members.clone(),
+        objects,
+    })
+}
+
+/// Places the saved furnishings of a plot back into the world.
+pub fn restore(world: &mut World, spatial: &mut SpatialHash, record: &PlotRecord) {
+    for placed in record.objects.iter() {
+        let object = obstacles::spawn(world, spatial, placed.kind, placed.position);
+        world.add_component(
+            object,
+            Furnishing {
+                plot: record.name.clone(),
+            },
+        );
+        if let Some(inventory) = world.get_component_mut::<Inventory>(&object) {
+            inventory.stacks = placed.contents.clone();
+        }
+    }
+}
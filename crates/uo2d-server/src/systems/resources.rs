@@ -0,0 +1,156 @@
+use uo2d_proto::components::{Position, ResourceNode, Skills, Vec2, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::locale::Text;
+
+use super::inventory;
+use crate::resources::ResourceDefinition;
+use crate::spatial_hash::SpatialHash;
+
+/// Size of a resource node placed in the world.
+const NODE_SIZE: f64 = 32.;
+/// Range, as a scale of the player's size, that resource nodes can be gathered from within.
+const GATHER_RANGE: f64 = 2.;
+/// Chance out of 100 to gather from a node at the level it requires.
+const BASE_CHANCE: u64 = 50;
+/// Added chance for each level above the one required.
+const CHANCE_PER_LEVEL: u64 = 2;
+/// Highest chance out of 100, gathering can always fail.
+const MAX_CHANCE: u64 = 95;
+/// Levels above the requirement needed for each extra item yielded.
+const LEVELS_PER_ITEM: u32 = 10;
+
+/// Result of successfully gathering from a resource node.
+pub struct Gathered {
+    pub count: u16,
+    /// New gathering level if it was raised.
+    pub level: Option<u32>,
+    /// The node has nothing left to gather.
+    pub depleted: bool,
+}
+
+/// Places a resource node in the world.
+pub fn spawn(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    definition: &ResourceDefinition,
+    position: Vec3,
+) -> Entity {
+    let position = Position::new(position, Vec2::new(NODE_SIZE, NODE_SIZE));
+    let entity = world
+        .spawn()
+        .with(position)
+        .with(ResourceNode {
+            kind: definition.kind.clone(),
+            charges: definition.charges,
+        })
+        .build();
+
+    spatial.insert_object(&entity, &position.bounds());
+    entity
+}
+
+/// Closest resource node an entity is within range to gather from.
+pub fn within_reach(world: &World, spatial: &SpatialHash, entity: &Entity) -> Option<Entity> {
+    let bounds = world.get_component::<Position>(entity)?.bounds();
+    let center = bounds.center_2d();
+    let distance = |node: &Entity| {
+        world
+            .get_component::<Position>(node)
+            .map_or(f64::MAX, |position| {
+                position.bounds().center_2d().distance(&center)
+            })
+    };
+
+    spatial
+        .query(&bounds.scaled_center(GATHER_RANGE), Some(entity))
+        .into_iter()
+        .filter(|node| {
+            world
+                .get_component::<ResourceNode>(node)
+                .is_some_and(|node| !node.is_depleted())
+        })
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+/// Gathers from a resource node into the inventory of an entity, raising its gathering skill
+/// by the experience of the node scaled by `experience`, such as during events.
+/// The roll decides if the attempt succeeds, the reason is returned if it does not.
+pub fn gather(
+    world: &mut World,
+    items: &ItemManager,
+    definition: &ResourceDefinition,
+    entity: &Entity,
+    node: &Entity,
+    roll: u64,
+    experience: f64,
+) -> Result<Gathered, Text> {
+    let skills = world
+        .get_component::<Skills>(entity)
+        .copied()
+        .unwrap_or_default();
+    let level = skills.gathering_level();
+    if level < definition.level {
+        return Err(Text::new("gather.level_required")
+            .with("level", definition.level)
+            .with_text("node", definition.text()));
+    }
+
+    let above = level - definition.level;
+    let chance = (BASE_CHANCE + above as u64 * CHANCE_PER_LEVEL).min(MAX_CHANCE);
+    if roll % 100 >= chance {
+        return Err(Text::new("gather.failed").with_text("node", definition.text()));
+    }
+
+    let wanted = (1 + above / LEVELS_PER_ITEM).min(definition.amount as u32) as u16;
+    let count = inventory::give(world, items, entity, definition.item, wanted);
+    if count == 0 {
+        return Err("gather.full".into());
+    }
+
+    let depleted = match world.get_component_mut::<ResourceNode>(node) {
+        Some(node) => {
+            node.charges = node.charges.saturating_sub(1);
+            node.is_depleted()
+        }
+        None => true,
+    };
+
+    let mut raised = None;
+    if let Some(skills) = world.get_component_mut::<Skills>(entity) {
+        let earned = (definition.experience as f64 * experience).round() as u32;
+        skills.gathering = skills.gathering.saturating_add(earned);
+        let now = skills.gathering_level();
+        if now > level {
+            raised = Some(now);
+        }
+    }
+
+    Ok(Gathered {
+        count,
+        level: raised,
+        depleted,
+    })
+}
+
+/// Hides a depleted node until it regrows, clients are informed once it leaves their view.
+pub fn deplete(world: &World, spatial: &mut SpatialHash, node: &Entity) {
+    if let Some(position) = world.get_component::<Position>(node) {
+        spatial.remove_object(node, &position.bounds());
+    }
+}
+
+/// Refills a depleted node, placing it back into the world.
+pub fn regrow(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    definition: &ResourceDefinition,
+    node: &Entity,
+) {
+    if let Some(resource) = world.get_component_mut::<ResourceNode>(node) {
+        resource.charges = definition.charges;
+    }
+    if let Some(position) = world.get_component::<Position>(node) {
+        spatial.insert_object(node, &position.bounds());
+    }
+}
@@ -0,0 +1,77 @@
+use uo2d_proto::components::{Bounds, Collidable, Obstacle, Position, Vec2};
+use uo2d_proto::ecs::{Entity, World};
+
+use crate::spatial_hash::SpatialHash;
+
+/// Checks if the line between two points passes through the bounds.
+pub fn crosses(bounds: &Bounds, from: Vec2, to: Vec2) -> bool {
+    let (mut enter, mut exit) = (0_f64, 1_f64);
+    let axes = [
+        (
+            from.x(),
+            to.x() - from.x(),
+            bounds.x(),
+            bounds.x() + bounds.width(),
+        ),
+        (
+            from.y(),
+            to.y() - from.y(),
+            bounds.y(),
+            bounds.y() + bounds.height(),
+        ),
+    ];
+
+    // Clip the line against each pair of edges, it crosses if any of it remains.
+    for (start, delta, min, max) in axes {
+        if delta == 0. {
+            if start < min || start > max {
+                return false;
+            }
+            continue;
+        }
+
+        let (near, far) = ((min - start) / delta, (max - start) / delta);
+        enter = enter.max(near.min(far));
+        exit = exit.min(near.max(far));
+        if enter > exit {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Checks if an entity can see another, neither being on another layer nor hidden behind an obstacle.
+pub fn in_sight(world: &World, spatial: &SpatialHash, from: &Entity, to: &Entity) -> bool {
+    let (start, end) = match (
+        world.get_component::<Position>(from),
+        world.get_component::<Position>(to),
+    ) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return false,
+    };
+
+    if start.loc.z() != end.loc.z() {
+        return false;
+    }
+
+    let (a, b) = (start.bounds().center_2d(), end.bounds().center_2d());
+    let area = Bounds::new(
+        a.x().min(b.x()),
+        a.y().min(b.y()),
+        start.loc.z(),
+        (a.x() - b.x()).abs(),
+        (a.y() - b.y()).abs(),
+    );
+
+    !spatial
+        .query(&area, Some(from))
+        .iter()
+        .filter(|other| *other != to)
+        .filter(|other| {
+            world.get_component::<Obstacle>(other).is_some()
+                && world.get_component::<Collidable>(other).is_some()
+        })
+        .filter_map(|other| world.get_component::<Position>(other))
+        .any(|other| other.loc.z() == start.loc.z() && crosses(&other.bounds(), a, b))
+}
@@ -0,0 +1,139 @@
+use uo2d_proto::components::{
+    Acceleration, Dormant, Health, Npc, Position, Spawned, Spawner, Threat, Vec2,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::sprintln;
+use uo2d_proto::timer::{TimerData, TimerManager};
+
+use super::{npcs, roll};
+use crate::load::Throttle;
+use crate::npcs::NpcManager;
+use crate::region::SpawnerSpawn;
+use crate::spatial_hash::SpatialHash;
+
+/// Ticks between wandering NPCs picking a new direction.
+const WANDER_TICKS: u64 = 20;
+/// Scale of the leash an NPC returning home can be pulled to before it is despawned.
+const STRAY_SCALE: f64 = 2.;
+
+/// Places a spawner, filling it with NPCs right away.
+pub fn place(
+    world: &mut World,
+    spatial: &mut SpatialHash,
+    npcs: &NpcManager,
+    spawn: SpawnerSpawn,
+) -> Entity {
+    let spawner = world
+        .spawn()
+        .with(Spawner {
+            npc: spawn.npc,
+            home: spawn.position,
+            max_alive: spawn.max_alive,
+            respawn: spawn.respawn,
+            leash: spawn.leash,
+            alive: 0,
+        })
+        .build();
+
+    for _ in 0..spawn.max_alive {
+        respawn(world, spatial, npcs, &spawner);
+    }
+    spawner
+}
+
+/// Spawns an NPC at the home of a spawner, unless it already has as many as it keeps alive.
+pub fn respawn(world: &mut World, spatial: &mut SpatialHash, npcs: &NpcManager, spawner: &Entity) {
+    let (kind, home) = match world.get_component::<Spawner>(spawner) {
+        Some(spawner) if spawner.alive < spawner.max_alive => (spawner.npc.clone(), spawner.home),
+        _ => return,
+    };
+
+    let definition = match npcs.get(&kind) {
+        Some(definition) => definition,
+        None => {
+            sprintln!("Unable to spawn '{}', the NPC is not defined.", kind);
+            return;
+        }
+    };
+
+    npcs::spawn(world, spatial, definition, home, *spawner);
+    if let Some(spawner) = world.get_component_mut::<Spawner>(spawner) {
+        spawner.alive += 1;
+    }
+}
+
+/// Despawns the NPCs that died or strayed beyond their leash, scheduling their replacements.
+/// NPCs holding threat are sent home instead, unless pulled much further.
+/// Clients are informed once they leave their view.
+pub fn update(world: &mut World, spatial: &mut SpatialHash, timers: &mut TimerManager) {
+    let removed: Vec<(Entity, Entity, Position)> = world
+        .query2::<Spawned, Position>()
+        .into_iter()
+        .filter(|(entity, spawned, position)| {
+            let dead = world
+                .get_component::<Health>(entity)
+                .is_some_and(|health| health.is_dead());
+            let scale = match world.get_component::<Threat>(entity) {
+                Some(_) => STRAY_SCALE,
+                None => 1.,
+            };
+            let leashed = world
+                .get_component::<Spawner>(&spawned.0)
+                .is_some_and(|spawner| {
+                    position.loc.distance_2d(&spawner.home) <= spawner.leash * scale
+                });
+            dead || !leashed
+        })
+        .map(|(entity, spawned, position)| (entity, spawned.0, *position))
+        .collect();
+
+    for (entity, spawner, position) in removed.into_iter() {
+        spatial.remove_object(&entity, &position.bounds());
+        world.despawn(&entity);
+
+        if let Some(spawner_data) = world.get_component_mut::<Spawner>(&spawner) {
+            spawner_data.alive = spawner_data.alive.saturating_sub(1);
+            timers.add_timer_sec(spawner_data.respawn, TimerData::Respawn(spawner), true);
+        }
+    }
+}
+
+/// Walks the wandering NPCs, turning them in a new direction or stopping them every so often.
+pub fn wander(world: &mut World, throttle: &Throttle, tick: u64) {
+    let mut inputs = vec![];
+    for (entity, npc) in world.query1::<Npc>() {
+        // Dormant NPCs jump around instead, and throttled ones wait their turn.
+        if world.get_component::<Dormant>(&entity).is_some() || throttle.skips(&entity) {
+            continue;
+        }
+
+        // Those chasing a target or returning home are moved by their threat.
+        let engaged = world
+            .get_component::<Threat>(&entity)
+            .is_some_and(|threat| threat.is_engaged());
+        if npc.wander <= 0. || engaged {
+            continue;
+        }
+
+        let mut heading = npc.heading;
+        if (tick + entity.id()).is_multiple_of(WANDER_TICKS) {
+            let roll = roll(&entity, tick);
+            heading = if roll.is_multiple_of(3) {
+                Vec2::ORIGIN
+            } else {
+                let angle = ((roll % 360) as f64).to_radians();
+                Vec2::new(angle.cos(), angle.sin()).scaled(npc.wander)
+            };
+        }
+        inputs.push((entity, heading));
+    }
+
+    for (entity, heading) in inputs.into_iter() {
+        if let Some(npc) = world.get_component_mut::<Npc>(&entity) {
+            npc.heading = heading;
+        }
+        if heading != Vec2::ORIGIN {
+            world.upsert_component(entity, Acceleration(heading));
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use uo2d_proto::components::Stats;
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::LeaderboardEntry;
+
+/// Most players shown on the leaderboard.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Adds the time spent in the world to every player.
+pub fn playtime(world: &mut World, elapsed: f64) {
+    for entity in world.get_entities::<Stats>() {
+        if let Some(stats) = world.get_component_mut::<Stats>(&entity) {
+            stats.playtime += elapsed;
+        }
+    }
+}
+
+/// Adds to the distance traveled by a player.
+pub fn traveled(world: &mut World, entity: &Entity, distance: f64) {
+    if let Some(stats) = world.get_component_mut::<Stats>(entity) {
+        stats.distance += distance;
+    }
+}
+
+/// Credits a kill to a player.
+pub fn killed(world: &mut World, entity: &Entity) {
+    if let Some(stats) = world.get_component_mut::<Stats>(entity) {
+        stats.kills += 1;
+    }
+}
+
+/// Counts a death for a player.
+pub fn died(world: &mut World, entity: &Entity) {
+    if let Some(stats) = world.get_component_mut::<Stats>(entity) {
+        stats.deaths += 1;
+    }
+}
+
+/// Ranks the players by their stats, keeping only the best.
+pub fn leaderboard(players: Vec<(String, Stats)>) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = players
+        .into_iter()
+        .map(|(name, stats)| LeaderboardEntry { name, stats })
+        .collect();
+    entries.sort_by(|a, b| a.stats.rank(&b.stats).then(a.name.cmp(&b.name)));
+    entries.truncate(LEADERBOARD_SIZE);
+    entries
+}
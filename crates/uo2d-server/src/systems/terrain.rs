@@ -0,0 +1,53 @@
+use uo2d_proto::components::{Health, MovementMode, Obstacle, Position, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::MovementModePayload;
+
+use super::mounts;
+use super::movement::get_observers;
+use crate::net::{Event, Net};
+use crate::region::RegionManager;
+use crate::spatial_hash::SpatialHash;
+
+/// How an entity is currently moving, walking unless told otherwise.
+pub fn mode(world: &World, entity: &Entity) -> MovementMode {
+    world
+        .get_component::<MovementMode>(entity)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Checks if an entity is swimming.
+pub fn is_swimming(world: &World, entity: &Entity) -> bool {
+    mode(world, entity) == MovementMode::Swimming
+}
+
+/// Switches mobiles between walking and swimming as they cross the terrain, informing those nearby.
+pub fn update(world: &mut World, spatial: &SpatialHash, regions: &RegionManager, net: &mut Net) {
+    let changes: Vec<(Entity, MovementMode)> = world
+        .query2::<Position, Health>()
+        .into_iter()
+        .filter(|(entity, _, _)| world.get_component::<Obstacle>(entity).is_none())
+        .filter_map(|(entity, position, _)| {
+            let center = position.bounds().center_2d();
+            let terrain = regions.terrain_at(&Vec3::new(center.x(), center.y(), 0.));
+            let mode = MovementMode::from_terrain(terrain);
+            (mode != self::mode(world, &entity)).then_some((entity, mode))
+        })
+        .collect();
+
+    for (entity, mode) in changes.into_iter() {
+        match mode {
+            MovementMode::Walking => world.remove_component::<MovementMode>(entity),
+            MovementMode::Swimming => {
+                // Mounts are left behind on the shore.
+                mounts::dismount(world, spatial, net, &entity);
+                world.upsert_component(entity, mode);
+            }
+        }
+
+        net.broadcast_to(
+            get_observers(world, spatial, &entity),
+            Event::MovementMode(MovementModePayload::new(entity, mode)),
+        );
+    }
+}
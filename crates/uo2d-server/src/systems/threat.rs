@@ -0,0 +1,177 @@
+use uo2d_proto::components::{
+    Acceleration, Bounds, Dormant, Health, Npc, Player, Position, Spawned, Spawner, Threat, Vec2,
+    Vec3,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::packet::payloads::TargetPayload;
+
+use super::movement::get_observers;
+use crate::load::Throttle;
+use crate::net::{Event, Net};
+use crate::spatial_hash::SpatialHash;
+
+/// Threat gained per point of damage taken.
+const DAMAGE_THREAT: f64 = 1.;
+/// Threat gained each second from a player standing right next to the NPC.
+const PROXIMITY_THREAT: f64 = 4.;
+/// Fraction of threat kept after each second.
+const THREAT_DECAY: f64 = 0.85;
+/// Threat below this is forgotten.
+const MIN_THREAT: f64 = 0.5;
+/// Distance from its home an NPC has to be within to stop returning.
+const HOME_RANGE: f64 = 16.;
+
+/// Where an NPC is heading, its target or its home while returning.
+pub fn destination(world: &World, entity: &Entity) -> Option<Vec2> {
+    let threat = world.get_component::<Threat>(entity)?;
+    if threat.returning {
+        let home = world
+            .get_component::<Spawned>(entity)
+            .and_then(|spawned| world.get_component::<Spawner>(&spawned.0))?
+            .home;
+        return Some(Vec2::new(home.x(), home.y()));
+    }
+
+    world
+        .get_component::<Position>(&threat.target?)
+        .map(|position| position.bounds().center_2d())
+}
+
+/// An entity damaged an NPC, drawing its attention.
+pub fn damaged(world: &mut World, npc: &Entity, attacker: &Entity, amount: u32) {
+    if npc == attacker {
+        return;
+    }
+
+    if let Some(threat) = world.get_component_mut::<Threat>(npc) {
+        threat.add(*attacker, amount as f64 * DAMAGE_THREAT);
+    }
+}
+
+/// Updates the threat every NPC holds, choosing their targets and moving them towards them.
+/// NPCs pulled beyond their leash forget everything and return home.
+pub fn update(
+    world: &mut World,
+    spatial: &SpatialHash,
+    throttle: &Throttle,
+    net: &mut Net,
+    elapsed: f64,
+) {
+    let npcs: Vec<(Entity, Position)> = world
+        .query2::<Threat, Position>()
+        .into_iter()
+        .map(|(entity, _, position)| (entity, *position))
+        .collect();
+
+    for (entity, position) in npcs.into_iter() {
+        // Combat is paused while no player is near.
+        if world.get_component::<Dormant>(&entity).is_some() || throttle.skips(&entity) {
+            continue;
+        }
+
+        let elapsed = throttle.elapsed(&entity, elapsed);
+        let center = position.bounds().center_2d();
+        let home = world
+            .get_component::<Spawned>(&entity)
+            .and_then(|spawned| world.get_component::<Spawner>(&spawned.0))
+            .map(|spawner| (spawner.home, spawner.leash));
+        let alive = |target: &Entity| {
+            world.get_component::<Position>(target).is_some()
+                && !world
+                    .get_component::<Health>(target)
+                    .is_some_and(|health| health.is_dead())
+        };
+        let nearby: Vec<(Entity, f64)> = nearby_players(world, spatial, &entity, &position)
+            .into_iter()
+            .filter(|(player, _)| alive(player))
+            .collect();
+        let (previous, chasing) = match world.get_component::<Threat>(&entity) {
+            Some(threat) => (
+                threat.target,
+                threat.table.keys().copied().collect::<Vec<_>>(),
+            ),
+            None => continue,
+        };
+        let gone: Vec<Entity> = chasing.into_iter().filter(|e| !alive(e)).collect();
+
+        let threat = match world.get_component_mut::<Threat>(&entity) {
+            Some(threat) => threat,
+            None => continue,
+        };
+        for target in gone.iter() {
+            threat.table.remove(target);
+        }
+
+        match home {
+            Some((home, _)) if threat.returning => {
+                if position.loc.distance_2d(&home) <= HOME_RANGE {
+                    threat.returning = false;
+                }
+            }
+            Some((home, leash))
+                if threat.target.is_some() && position.loc.distance_2d(&home) > leash =>
+            {
+                threat.reset();
+            }
+            _ => {
+                for (player, distance) in nearby.into_iter() {
+                    let closeness = 1. - (distance / threat.aggro).min(1.);
+                    threat.add(player, PROXIMITY_THREAT * closeness * elapsed);
+                }
+            }
+        }
+
+        threat.decay(THREAT_DECAY.powf(elapsed), MIN_THREAT);
+        threat.target = threat.highest();
+        let target = threat.target;
+
+        if target != previous {
+            net.broadcast_to(
+                get_observers(world, spatial, &entity),
+                Event::Target(TargetPayload::new(entity, target)),
+            );
+        }
+
+        let speed = world
+            .get_component::<Npc>(&entity)
+            .map_or(0., |npc| npc.chase);
+        if let Some(destination) = destination(world, &entity) {
+            let offset = Vec2::new(destination.x() - center.x(), destination.y() - center.y());
+            if speed > 0. && offset != Vec2::ORIGIN {
+                world.upsert_component(entity, Acceleration(offset.scaled(speed)));
+            }
+        }
+    }
+}
+
+/// Players within the aggro range of an NPC along with their distance.
+fn nearby_players(
+    world: &World,
+    spatial: &SpatialHash,
+    npc: &Entity,
+    position: &Position,
+) -> Vec<(Entity, f64)> {
+    let aggro = match world.get_component::<Threat>(npc) {
+        Some(threat) if threat.aggro > 0. => threat.aggro,
+        _ => return vec![],
+    };
+
+    let center = position.bounds().center_2d();
+    let range = Bounds::from_vec(
+        Vec3::new(center.x() - aggro, center.y() - aggro, position.loc.z()),
+        Vec2::new(aggro * 2., aggro * 2.),
+    );
+    spatial
+        .query(&range, Some(npc))
+        .into_iter()
+        .filter(|entity| world.get_component::<Player>(entity).is_some())
+        .filter_map(|entity| {
+            let distance = world
+                .get_component::<Position>(&entity)?
+                .bounds()
+                .center_2d()
+                .distance(&center);
+            (distance <= aggro).then_some((entity, distance))
+        })
+        .collect()
+}
@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uo2d_proto::components::{Ability, Player, Position, Stats};
+use uo2d_proto::ecs::World;
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::timer::{TimerData, TimerManager};
+use uuid::Uuid;
+
+use crate::config::TelemetryConfig;
+use crate::region::RegionManager;
+
+/// Lengths of the sessions that ended within a report, in seconds.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    pub count: usize,
+    /// Zero without any sessions.
+    pub average: f64,
+    pub longest: f64,
+}
+
+/// Totals gathered over a period, holding nothing that identifies a player.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TelemetryReport {
+    pub started: String,
+    pub ended: String,
+    pub sessions: SessionSummary,
+    /// Player deaths by the region they died in.
+    pub deaths: BTreeMap<String, u64>,
+    /// Times each ability was used, items by their key.
+    pub abilities: BTreeMap<String, u64>,
+}
+
+/// Gameplay metrics gathered for balancing while enabled, reported on a schedule.
+/// Players are only told apart while online to measure their sessions and notice their deaths.
+pub struct Telemetry {
+    config: TelemetryConfig,
+    /// A report is waiting on its timer.
+    scheduled: bool,
+    /// Start of the period being gathered.
+    started: DateTime<Utc>,
+    /// When each session in progress began.
+    joined: HashMap<Uuid, Instant>,
+    /// Deaths of each player online when last checked.
+    seen: HashMap<Uuid, u32>,
+    /// Lengths of the sessions that ended, in seconds.
+    sessions: Vec<f64>,
+    deaths: BTreeMap<String, u64>,
+    abilities: BTreeMap<String, u64>,
+}
+
+impl Telemetry {
+    /// Shortest time allowed between reports, in seconds.
+    const MIN_INTERVAL: f32 = 60.;
+    const PREFIX: &'static str = "telemetry-";
+    /// Region recorded for deaths outside of every region.
+    const UNKNOWN_REGION: &'static str = "unknown";
+
+    /// Schedules the first report if telemetry is enabled.
+    pub fn new(config: TelemetryConfig, timers: &mut TimerManager) -> Self {
+        let mut telemetry = Self {
+            config,
+            scheduled: false,
+            started: Utc::now(),
+            joined: HashMap::new(),
+            seen: HashMap::new(),
+            sessions: vec![],
+            deaths: BTreeMap::new(),
+            abilities: BTreeMap::new(),
+        };
+        telemetry.schedule(timers);
+        telemetry
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Schedules the next report if telemetry is enabled and one is not already waiting.
+    fn schedule(&mut self, timers: &mut TimerManager) {
+        if self.config.enabled && !self.scheduled {
+            let interval = self.config.interval.max(Self::MIN_INTERVAL);
+            timers.add_timer_sec(interval, TimerData::Telemetry, true);
+            self.scheduled = true;
+        }
+    }
+
+    /// Checks if a scheduled report that came due should be written, scheduling the next.
+    pub fn due(&mut self, timers: &mut TimerManager) -> bool {
+        self.scheduled = false;
+        self.schedule(timers);
+        self.config.enabled
+    }
+
+    /// Replaces the settings, discarding everything gathered if it was just disabled.
+    pub fn reconfigure(&mut self, config: TelemetryConfig, timers: &mut TimerManager) {
+        self.config = config;
+        if !self.config.enabled {
+            self.joined.clear();
+            self.seen.clear();
+            self.reset(Utc::now());
+        }
+        self.schedule(timers);
+    }
+
+    /// Starts measuring a session.
+    pub fn joined(&mut self, uuid: Uuid, now: Instant) {
+        if self.config.enabled {
+            self.joined.insert(uuid, now);
+        }
+    }
+
+    /// Ends a session, keeping only its length.
+    pub fn left(&mut self, uuid: &Uuid, now: Instant) {
+        self.seen.remove(uuid);
+        if let Some(joined) = self.joined.remove(uuid) {
+            self.sessions.push(now.duration_since(joined).as_secs_f64());
+        }
+    }
+
+    /// Counts an ability being used.
+    pub fn ability(&mut self, ability: Ability, items: &ItemManager) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let name = match ability {
+            Ability::Item(item) => match items.get(&item) {
+                Some(definition) => format!("item:{}", definition.key),
+                None => format!("item:{}", item),
+            },
+            Ability::Gather => "gather".to_string(),
+            Ability::Emote => "emote".to_string(),
+        };
+        *self.abilities.entry(name).or_default() += 1;
+    }
+
+    /// Counts the players that died since the last check by the region they are in.
+    pub fn deaths(&mut self, world: &World, regions: &RegionManager) {
+        if !self.config.enabled {
+            return;
+        }
+
+        for (entity, player, stats) in world.query2::<Player, Stats>() {
+            let seen = self.seen.entry(*player.uuid()).or_insert(stats.deaths);
+            let died = stats.deaths.saturating_sub(*seen);
+            *seen = stats.deaths;
+            if died == 0 {
+                continue;
+            }
+
+            let region = world
+                .get_component::<Position>(&entity)
+                .and_then(|position| regions.get_region(&position.loc))
+                .map_or(Self::UNKNOWN_REGION.to_string(), |region| {
+                    region.name.clone()
+                });
+            *self.deaths.entry(region).or_default() += died as u64;
+        }
+    }
+
+    /// Totals gathered since the last report, starting the next period.
+    pub fn report(&mut self, now: DateTime<Utc>) -> TelemetryReport {
+        let count = self.sessions.len();
+        let total: f64 = self.sessions.iter().sum();
+        let sessions = SessionSummary {
+            count,
+            average: if count > 0 { total / count as f64 } else { 0. },
+            longest: self.sessions.iter().copied().fold(0., f64::max),
+        };
+
+        let report = TelemetryReport {
+            started: self.started.to_rfc3339(),
+            ended: now.to_rfc3339(),
+            sessions,
+            deaths: std::mem::take(&mut self.deaths),
+            abilities: std::mem::take(&mut self.abilities),
+        };
+        self.reset(now);
+        report
+    }
+
+    /// Discards the totals, the sessions in progress carry on into the next period.
+    fn reset(&mut self, now: DateTime<Utc>) {
+        self.started = now;
+        self.sessions.clear();
+        self.deaths.clear();
+        self.abilities.clear();
+    }
+
+    /// Writes a report into the directory, named by when it ended.
+    pub fn write(&self, report: &TelemetryReport) -> Result<PathBuf, Box<dyn Error>> {
+        let directory = Path::new(&self.config.directory);
+        fs::create_dir_all(directory)?;
+
+        let stamp = Utc::now().format("%Y%m%d-%H%M%S");
+        let path = directory.join(format!("{}{}.json", Self::PREFIX, stamp));
+        fs::write(&path, serde_json::to_string_pretty(report)?)?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::{Inventory, ItemStack};
+use uo2d_proto::items::ItemManager;
+use uo2d_proto::packet::payloads::TradeOffer;
+use uo2d_proto::packet::{Action, Payload};
+use uuid::Uuid;
+
+use crate::gamestate::{GameHandlers, Gamestate};
+use crate::packet_processor::{Context, Handlers, Outcome};
+
+/// One player's side of a trade.
+#[derive(Debug, Clone)]
+struct Side {
+    uuid: Uuid,
+    offer: TradeOffer,
+}
+
+/// Items two players are exchanging, swapped once both have locked and confirmed their offers.
+#[derive(Debug, Clone)]
+pub struct Trade {
+    sides: [Side; 2],
+}
+
+impl Trade {
+    fn new(first: Uuid, second: Uuid) -> Self {
+        let side = |uuid| Side {
+            uuid,
+            offer: TradeOffer::default(),
+        };
+        Self {
+            sides: [side(first), side(second)],
+        }
+    }
+
+    /// Players in the trade, in the order their inventories are exchanged.
+    pub fn players(&self) -> [Uuid; 2] {
+        [self.sides[0].uuid, self.sides[1].uuid]
+    }
+
+    /// Checks if the player is one of the two trading.
+    pub fn includes(&self, uuid: &Uuid) -> bool {
+        self.sides.iter().any(|side| side.uuid == *uuid)
+    }
+
+    /// Offer of a player followed by that of their partner.
+    pub fn offers(&self, uuid: &Uuid) -> (&TradeOffer, &TradeOffer) {
+        if self.sides[0].uuid == *uuid {
+            (&self.sides[0].offer, &self.sides[1].offer)
+        } else {
+            (&self.sides[1].offer, &self.sides[0].offer)
+        }
+    }
+
+    /// Other player in the trade.
+    pub fn partner(&self, uuid: &Uuid) -> Uuid {
+        if self.sides[0].uuid == *uuid {
+            self.sides[1].uuid
+        } else {
+            self.sides[0].uuid
+        }
+    }
+
+    fn side_mut(&mut self, uuid: &Uuid) -> &mut Side {
+        if self.sides[0].uuid == *uuid {
+            &mut self.sides[0]
+        } else {
+            &mut self.sides[1]
+        }
+    }
+
+    /// Inventories of the first and second player once the offers are exchanged.
+    /// Fails unless both still hold what they offered and can carry what they receive.
+    pub fn exchange(
+        &self,
+        items: &ItemManager,
+        first: &Inventory,
+        second: &Inventory,
+    ) -> Result<[Inventory; 2], &'static str> {
+        let mut inventories = [first.clone(), second.clone()];
+        for (inventory, side) in inventories.iter_mut().zip(self.sides.iter()) {
+            for stack in side.offer.items.iter() {
+                if !inventory.remove(stack.item, stack.count) {
+                    return Err("trade.missing");
+                }
+            }
+        }
+
+        for (inventory, side) in inventories.iter_mut().zip(self.sides.iter().rev()) {
+            for stack in side.offer.items.iter() {
+                if items.add(inventory, stack.item, stack.count) < stack.count {
+                    return Err("trade.too_heavy");
+                }
+            }
+        }
+        Ok(inventories)
+    }
+}
+
+/// Invitations and trades between players, across every world.
+#[derive(Default)]
+pub struct TradeManager {
+    /// Invitations by the player invited, to who invited them.
+    invitations: HashMap<Uuid, Uuid>,
+    trades: Vec<Trade>,
+}
+
+impl TradeManager {
+    /// Most stacks a player can offer at once.
+    pub const MAX_OFFER: usize = 8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trade the player is in.
+    pub fn get(&self, uuid: &Uuid) -> Option<&Trade> {
+        self.trades.iter().find(|trade| trade.includes(uuid))
+    }
+
+    fn get_mut(&mut self, uuid: &Uuid) -> Option<&mut Trade> {
+        self.trades.iter_mut().find(|trade| trade.includes(uuid))
+    }
+
+    /// Player who invited them to trade, if they are waiting on an answer.
+    pub fn invited_by(&self, uuid: &Uuid) -> Option<Uuid> {
+        self.invitations.get(uuid).copied()
+    }
+
+    /// Player they invited to trade, if they are waiting on an answer.
+    pub fn inviting(&self, uuid: &Uuid) -> Option<Uuid> {
+        self.invitations
+            .iter()
+            .find(|(_, from)| *from == uuid)
+            .map(|(to, _)| *to)
+    }
+
+    /// Invites a player to trade, opening the trade if they had already invited the player back.
+    /// Returns true if the trade was opened.
+    pub fn invite(&mut self, from: Uuid, to: Uuid) -> Result<bool, &'static str> {
+        if from == to {
+            return Err("trade.invalid");
+        } else if self.get(&from).is_some() || self.get(&to).is_some() {
+            return Err("trade.busy");
+        }
+
+        if self.invitations.get(&from) == Some(&to) {
+            self.invitations.retain(|invited, inviter| {
+                ![from, to].contains(invited) && ![from, to].contains(inviter)
+            });
+            self.trades.push(Trade::new(to, from));
+            return Ok(true);
+        }
+
+        // Only a single invitation is kept for each player, the latest replacing the last.
+        self.invitations.retain(|_, inviter| *inviter != from);
+        self.invitations.insert(to, from);
+        Ok(false)
+    }
+
+    /// Adds items to the offer of a player, limited to the amount of the item they own.
+    pub fn offer(&mut self, uuid: &Uuid, stack: ItemStack, owned: u32) -> Result<(), &'static str> {
+        let side = self.unlocked(uuid)?;
+        let offered: u32 = side
+            .offer
+            .items
+            .iter()
+            .filter(|offered| offered.item == stack.item)
+            .map(|offered| offered.count as u32)
+            .sum();
+        if stack.count == 0 || offered + stack.count as u32 > owned {
+            return Err("trade.not_owned");
+        }
+
+        let items = &mut side.offer.items;
+        match items.iter().position(|offered| offered.item == stack.item) {
+            Some(index) => items[index].count += stack.count,
+            None if items.len() >= Self::MAX_OFFER => return Err("trade.full"),
+            None => items.push(stack),
+        }
+        Ok(())
+    }
+
+    /// Takes a stack back out of the offer of a player.
+    pub fn withdraw(&mut self, uuid: &Uuid, index: usize) -> Result<(), &'static str> {
+        let side = self.unlocked(uuid)?;
+        if index >= side.offer.items.len() {
+            return Err("trade.invalid");
+        }
+        side.offer.items.remove(index);
+        Ok(())
+    }
+
+    /// Locks or unlocks the offer of a player, unlocking takes back both confirmations.
+    pub fn lock(&mut self, uuid: &Uuid, locked: bool) -> Result<(), &'static str> {
+        let trade = self.get_mut(uuid).ok_or("trade.none")?;
+        trade.side_mut(uuid).offer.locked = locked;
+        if !locked {
+            for side in trade.sides.iter_mut() {
+                side.offer.confirmed = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Confirms the trade for a player once both offers are locked.
+    /// Returns true once both players have confirmed.
+    pub fn confirm(&mut self, uuid: &Uuid) -> Result<bool, &'static str> {
+        let trade = self.get_mut(uuid).ok_or("trade.none")?;
+        if !trade.sides.iter().all(|side| side.offer.locked) {
+            return Err("trade.not_locked");
+        }
+
+        trade.side_mut(uuid).offer.confirmed = true;
+        Ok(trade.sides.iter().all(|side| side.offer.confirmed))
+    }
+
+    /// Ends the trade and any invitations the player is part of.
+    /// Returns the other players who were waiting on them or trading with them.
+    pub fn cancel(&mut self, uuid: &Uuid) -> Vec<Uuid> {
+        let mut others = vec![];
+        self.invitations.retain(|invited, inviter| {
+            if invited == uuid {
+                others.push(*inviter);
+            } else if inviter == uuid {
+                others.push(*invited);
+            }
+            invited != uuid && inviter != uuid
+        });
+
+        if let Some(trade) = self.take(uuid) {
+            others.push(trade.partner(uuid));
+        }
+        others
+    }
+
+    /// Removes the trade the player is in, such as once it is complete.
+    pub fn take(&mut self, uuid: &Uuid) -> Option<Trade> {
+        let index = self.trades.iter().position(|trade| trade.includes(uuid))?;
+        Some(self.trades.remove(index))
+    }
+
+    /// Side of the trade the player is in, if it can still be changed.
+    fn unlocked(&mut self, uuid: &Uuid) -> Result<&mut Side, &'static str> {
+        let side = self.get_mut(uuid).ok_or("trade.none")?.side_mut(uuid);
+        match side.offer.locked {
+            true => Err("trade.locked"),
+            false => Ok(side),
+        }
+    }
+}
+
+/// Registers the handler for the requests players make while trading.
+pub(crate) fn register(handlers: &mut Handlers) {
+    handlers.register_handler(Action::Trade, request);
+}
+
+/// Registers the gamestate handler acting on the requests made while trading.
+pub(crate) fn register_gamestate(handlers: &mut GameHandlers) {
+    handlers.register_handler(Action::Trade, Gamestate::trade);
+}
+
+/// Passes the request on to the gamestate, which holds the trades.
+fn request(ctx: &Context, payload: Payload) -> Vec<Outcome> {
+    match payload {
+        Payload::TradeRequest(_) => vec![ctx.forward()],
+        _ => vec![],
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+use uo2d_proto::packet::MAX_DATAGRAM;
+use uo2d_proto::sprintln;
+use uuid::Uuid;
+
+use crate::config::NetworkConfig;
+use crate::middleware::RateLimit;
+
+/// Time a browser has to complete the WebSocket handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Limits applied to browsers by the address they connect from, the game only seeing the relay.
+struct Limits {
+    /// Sockets left to relay.
+    open: Arc<Semaphore>,
+    /// Sockets being relayed from each address.
+    addresses: Mutex<HashMap<IpAddr, usize>>,
+    per_address: usize,
+    /// Packets each address can send, shared by all of its sockets.
+    rate: RateLimit,
+}
+
+impl Limits {
+    fn new(network: &NetworkConfig) -> Self {
+        Self {
+            open: Arc::new(Semaphore::new(network.max_websockets)),
+            addresses: Mutex::new(HashMap::new()),
+            per_address: network.websockets_per_address,
+            rate: RateLimit::new(network.max_packet_rate, network.packet_burst),
+        }
+    }
+
+    /// Takes a place for a socket from the address, None if there are none left for it.
+    fn admit(self: &Arc<Self>, ip: IpAddr) -> Option<Slot> {
+        let permit = Arc::clone(&self.open).try_acquire_owned().ok()?;
+        let mut addresses = self.addresses.lock().unwrap();
+        let open = addresses.entry(ip).or_default();
+        if *open >= self.per_address {
+            return None;
+        }
+
+        *open += 1;
+        Some(Slot {
+            limits: Arc::clone(self),
+            ip,
+            _permit: permit,
+        })
+    }
+
+    /// Takes a packet from what the address has left.
+    fn allow(&self, ip: IpAddr) -> bool {
+        let key = match ip {
+            IpAddr::V4(ip) => u32::from(ip) as u128,
+            IpAddr::V6(ip) => u128::from(ip),
+        };
+        self.rate.allow(Uuid::from_u128(key), Instant::now())
+    }
+}
+
+/// Place held by a socket being relayed, given back once it closes.
+struct Slot {
+    limits: Arc<Limits>,
+    ip: IpAddr,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut addresses = self.limits.addresses.lock().unwrap();
+        if let Some(open) = addresses.get_mut(&self.ip) {
+            *open -= 1;
+            if *open == 0 {
+                addresses.remove(&self.ip);
+            }
+        }
+    }
+}
+
+/// Accepts WebSockets from browsers, which cannot send datagrams, relaying each to the game.
+/// Every socket is given a UDP socket of its own, so the game sees it as any other client.
+pub(crate) async fn relay(listener: TcpListener, game: SocketAddr, network: NetworkConfig) {
+    // Servers listening on every interface are reached over loopback.
+    let game = match game.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => {
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), game.port())
+        }
+        IpAddr::V6(ip) if ip.is_unspecified() => {
+            SocketAddr::new(Ipv6Addr::LOCALHOST.into(), game.port())
+        }
+        _ => game,
+    };
+
+    let limits = Arc::new(Limits::new(&network));
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let Some(slot) = limits.admit(peer.ip()) else {
+                    sprintln!("Refused a WebSocket from {}, too many are open.", peer);
+                    continue;
+                };
+
+                tokio::spawn(async move {
+                    if let Err(why) = connection(stream, game, &slot).await {
+                        sprintln!("WebSocket from {} failed: {}", peer, why);
+                    }
+                });
+            }
+            Err(why) => sprintln!("ERROR accepting a WebSocket: {}", why),
+        }
+    }
+}
+
+/// Relays the messages of a socket until either end closes.
+/// Those sent faster than the address is allowed to are dropped.
+async fn connection(
+    stream: TcpStream,
+    game: SocketAddr,
+    slot: &Slot,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut socket = timeout(HANDSHAKE_TIMEOUT, tokio_tungstenite::accept_async(stream)).await??;
+    let local = match game {
+        SocketAddr::V4(_) => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+        SocketAddr::V6(_) => SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0),
+    };
+    let udp = UdpSocket::bind(local).await?;
+    udp.connect(game).await?;
+
+    let mut buf = [0u8; MAX_DATAGRAM];
+    loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(Message::Binary(bytes))) => {
+                    if slot.limits.allow(slot.ip) {
+                        udp.send(&bytes).await?;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                // Pings are answered by the socket itself, text means nothing to the game.
+                Some(Ok(_)) => (),
+                Some(Err(why)) => return Err(why.into()),
+            },
+            received = udp.recv(&mut buf) => {
+                let n = received?;
+                socket.send(Message::binary(buf[..n].to_vec())).await?;
+            }
+        }
+    }
+}
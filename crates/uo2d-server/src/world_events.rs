@@ -0,0 +1,142 @@
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use uo2d_proto::sprintln;
+
+use crate::config::{EventHook, EventSchedule, WorldEventConfig};
+
+/// A world event starting or ending, by its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    Started(usize),
+    Ended(usize),
+}
+
+/// When an event starts, read from its schedule.
+#[derive(Debug, Clone, Copy)]
+enum Start {
+    Daily(NaiveTime),
+    Every(Duration),
+}
+
+/// World event along with when it next starts.
+#[derive(Debug, Clone)]
+struct Scheduled {
+    config: WorldEventConfig,
+    start: Start,
+    next: DateTime<Utc>,
+    /// Set while the event is running.
+    ends: Option<DateTime<Utc>>,
+}
+
+impl Scheduled {
+    /// First time the event starts after a moment.
+    fn next_after(&self, from: DateTime<Utc>) -> DateTime<Utc> {
+        match self.start {
+            Start::Daily(at) => {
+                let today = from.date_naive().and_time(at).and_utc();
+                if today > from {
+                    today
+                } else {
+                    today + Duration::days(1)
+                }
+            }
+            Start::Every(interval) => from + interval,
+        }
+    }
+}
+
+/// Fires the configured world events at their times of day or intervals.
+#[derive(Debug, Clone, Default)]
+pub struct WorldEvents {
+    events: Vec<Scheduled>,
+}
+
+impl WorldEvents {
+    /// Shortest time allowed between the starts of an event, in seconds.
+    const MIN_INTERVAL: f32 = 10.;
+
+    /// Schedules each event, those with a time that cannot be read are skipped.
+    pub fn new(configs: Vec<WorldEventConfig>, now: DateTime<Utc>) -> Self {
+        let mut events = Vec::with_capacity(configs.len());
+        for config in configs {
+            let start = match &config.schedule {
+                EventSchedule::At(time) => match NaiveTime::parse_from_str(time, "%H:%M") {
+                    Ok(at) => Start::Daily(at),
+                    Err(_) => {
+                        sprintln!(
+                            "Skipped the event '{}', invalid time: {}",
+                            config.name,
+                            time
+                        );
+                        continue;
+                    }
+                },
+                EventSchedule::Interval(interval) => {
+                    Start::Every(seconds(interval.max(Self::MIN_INTERVAL)))
+                }
+            };
+
+            let mut event = Scheduled {
+                config,
+                start,
+                next: now,
+                ends: None,
+            };
+            event.next = event.next_after(now);
+            events.push(event);
+        }
+        Self { events }
+    }
+
+    /// Configuration of an event.
+    pub fn get(&self, id: usize) -> Option<&WorldEventConfig> {
+        self.events.get(id).map(|event| &event.config)
+    }
+
+    /// Events currently running.
+    pub fn running(&self) -> impl Iterator<Item = &WorldEventConfig> {
+        self.events
+            .iter()
+            .filter(|event| event.ends.is_some())
+            .map(|event| &event.config)
+    }
+
+    /// Scale of the experience players earn, raised by the events running.
+    pub fn experience(&self) -> f64 {
+        self.running()
+            .flat_map(|event| event.hooks.iter())
+            .map(|hook| match hook {
+                EventHook::Experience(scale) => scale.max(0.),
+                _ => 1.,
+            })
+            .product()
+    }
+
+    /// Starts and ends the events that are due, events ending before others start.
+    /// An event still running when it is next due keeps running until its original end.
+    pub fn update(&mut self, now: DateTime<Utc>) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+        for (id, event) in self.events.iter_mut().enumerate() {
+            if event.ends.is_some_and(|ends| now >= ends) {
+                event.ends = None;
+                transitions.push(Transition::Ended(id));
+            }
+        }
+
+        for (id, event) in self.events.iter_mut().enumerate() {
+            if now < event.next {
+                continue;
+            }
+
+            event.next = event.next_after(now);
+            if event.ends.is_none() {
+                event.ends = Some(now + seconds(event.config.duration));
+                transitions.push(Transition::Started(id));
+            }
+        }
+        transitions
+    }
+}
+
+fn seconds(seconds: f32) -> Duration {
+    Duration::milliseconds((seconds.max(0.) * 1000.) as i64)
+}
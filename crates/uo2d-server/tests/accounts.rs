@@ -0,0 +1,75 @@
+use uo2d_proto::components::{Equipment, ItemStack, Skills, Stats, Vec3};
+use uo2d_server::accounts::{AccountDatabase, AccountError, Character};
+use uuid::Uuid;
+
+fn database() -> (AccountDatabase, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!("uo2d-accounts-{}.db", Uuid::new_v4()));
+    (AccountDatabase::open(&path).unwrap(), path)
+}
+
+#[test]
+fn passwords_are_hashed_with_unique_salts() {
+    let (db, path) = database();
+    assert!(db.available("alice", "correct horse").is_ok());
+
+    let hash = AccountDatabase::hash("correct horse").unwrap();
+    assert_ne!(hash, AccountDatabase::hash("correct horse").unwrap());
+    let id = db.create("alice", &hash).unwrap();
+
+    // The stored hash verifies only the same password.
+    let (found, stored) = db.password("Alice").unwrap();
+    assert_eq!(found, id);
+    assert!(AccountDatabase::verify("correct horse", &stored).is_ok());
+    assert!(matches!(
+        AccountDatabase::verify("wrong horse", &stored),
+        Err(AccountError::Credentials)
+    ));
+    assert!(matches!(db.password("bob"), Err(AccountError::Credentials)));
+
+    // Usernames claimed while a password was being hashed are not taken twice.
+    assert!(matches!(
+        db.available("alice", "correct horse"),
+        Err(AccountError::Taken)
+    ));
+    assert!(matches!(
+        db.create("ALICE", &hash),
+        Err(AccountError::Taken)
+    ));
+
+    drop(db);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn characters_are_saved_with_what_they_carry() {
+    let (db, path) = database();
+    let id = db
+        .create("alice", &AccountDatabase::hash("correct horse").unwrap())
+        .unwrap();
+    assert!(db.load_character(id).unwrap().is_none());
+
+    let character = Character {
+        position: Vec3::new(10., 20., 1.),
+        health: 50,
+        stats: Stats::default(),
+        skills: Skills::default(),
+        outfitted: true,
+        inventory: vec![ItemStack::new(1, 3), ItemStack::new(2, 1)],
+        equipment: Equipment {
+            weapon: Some(4),
+            ..Equipment::default()
+        },
+    };
+    db.save_character(id, &character).unwrap();
+    let loaded = db
+        .load_character(id)
+        .unwrap()
+        .expect("The character is missing");
+    assert!(loaded.outfitted);
+    assert_eq!(loaded.health, 50);
+    assert_eq!(loaded.inventory, character.inventory);
+    assert_eq!(loaded.equipment, character.equipment);
+
+    drop(db);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,64 @@
+use uo2d_proto::components::{Banker, Inventory, Npc, Position, Vec2, Vec3};
+use uo2d_proto::ecs::World;
+use uo2d_server::region::BankZone;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::bank;
+
+mod common;
+
+#[test]
+fn transfers_are_limited_by_the_receiving_weight() {
+    let items = common::items();
+
+    // Leather tunics weigh 6, only one fits in the bank.
+    let mut carried = Inventory::new(100);
+    let mut stored = Inventory::new(10);
+    assert_eq!(items.add(&mut carried, 2, 2), 2);
+
+    assert_eq!(bank::transfer(&items, &mut carried, &mut stored, 0, 0), 1);
+    assert_eq!(bank::transfer(&items, &mut carried, &mut stored, 0, 0), 0);
+    assert_eq!((carried.count(2), stored.count(2)), (1, 1));
+
+    // Slots that do not exist move nothing.
+    assert_eq!(bank::transfer(&items, &mut stored, &mut carried, 5, 1), 0);
+    assert_eq!(bank::transfer(&items, &mut stored, &mut carried, 0, 0), 1);
+    assert_eq!(carried.count(2), 2);
+    assert!(stored.stacks.is_empty());
+}
+
+#[test]
+fn banks_are_reached_from_bankers_and_zones() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Npc>();
+    world.register_component::<Banker>();
+    let mut spatial = SpatialHash::new(32);
+    let size = Vec2::new(32., 32.);
+
+    let player = Position::new(Vec3::new(100., 100., 1.), size);
+    let entity = world.spawn().with(player).build();
+    spatial.insert_object(&entity, &player.bounds());
+
+    let zone = BankZone {
+        position: Vec3::new(0., 0., 1.),
+        size: Vec2::new(200., 200.),
+    };
+    assert!(bank::within_reach(&world, &spatial, &[zone], &entity));
+    assert!(!bank::within_reach(&world, &spatial, &[], &entity));
+
+    let position = Position::new(Vec3::new(120., 100., 1.), size);
+    let banker = world
+        .spawn()
+        .with(position)
+        .with(Npc {
+            kind: "banker".into(),
+            dialogue: None,
+            wander: 0.,
+            heading: Vec2::ORIGIN,
+            chase: 0.,
+        })
+        .with(Banker)
+        .build();
+    spatial.insert_object(&banker, &position.bounds());
+    assert!(bank::within_reach(&world, &spatial, &[], &entity));
+}
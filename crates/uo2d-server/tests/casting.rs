@@ -0,0 +1,89 @@
+use uo2d_proto::components::{Ability, Health, Position, Vec2, Vec3};
+use uo2d_server::net::Net;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::{casting, combat};
+
+mod common;
+
+#[test]
+fn cooldowns_wait_out_their_ticks() {
+    let mut world = common::world();
+    let entity = world.spawn().build();
+
+    assert!(casting::is_ready(&world, &entity, Ability::Emote, 0));
+    casting::cooldown(&mut world, &entity, Ability::Emote, 0, 2.);
+    assert!(!casting::is_ready(&world, &entity, Ability::Emote, 19));
+    assert!(casting::is_ready(&world, &entity, Ability::Emote, 20));
+
+    // Other abilities are unaffected.
+    assert!(casting::is_ready(&world, &entity, Ability::Item(7), 0));
+}
+
+#[test]
+fn casts_finish_unless_the_caster_moves() {
+    let mut world = common::world();
+    let spatial = SpatialHash::new(32);
+    let position = Position::new(Vec3::new(100., 100., 1.), Vec2::new(32., 32.));
+    let still = world.spawn().with(position).build();
+    let moving = world.spawn().with(position).build();
+
+    let ability = Ability::Item(7);
+    let mut net = Net::new();
+    for entity in [still, moving] {
+        assert!(casting::begin(
+            &mut world,
+            &spatial,
+            &mut net,
+            &entity,
+            ability,
+            Some(0),
+            0,
+            1.
+        )
+        .is_ok());
+    }
+    assert!(casting::begin(&mut world, &spatial, &mut net, &still, ability, None, 0, 1.).is_err());
+
+    let finished = casting::update(&mut world, &spatial, &mut net, 5);
+    assert!(finished.is_empty());
+
+    world
+        .get_component_mut::<Position>(&moving)
+        .unwrap()
+        .loc
+        .set_x(120.);
+    let mut net = Net::new();
+    let finished = casting::update(&mut world, &spatial, &mut net, 10);
+    assert_eq!(net.len(), 2);
+    assert_eq!(finished.len(), 1);
+    assert_eq!(finished[0].0, still);
+    assert_eq!(finished[0].1.slot, Some(0));
+    assert!(!casting::is_casting(&world, &still));
+    assert!(!casting::is_casting(&world, &moving));
+}
+
+#[test]
+fn damage_interrupts_a_cast() {
+    let mut world = common::world();
+    let spatial = SpatialHash::new(32);
+    let position = Position::new(Vec3::new(100., 100., 1.), Vec2::new(32., 32.));
+    let entity = world.spawn().with(position).with(Health::new(100)).build();
+
+    let mut net = Net::new();
+    casting::begin(
+        &mut world,
+        &spatial,
+        &mut net,
+        &entity,
+        Ability::Gather,
+        None,
+        0,
+        3.,
+    )
+    .unwrap();
+    let mut net = Net::new();
+    combat::damage(&mut world, &spatial, &mut net, &entity, 10);
+    assert_eq!(net.len(), 2);
+    assert!(!casting::is_casting(&world, &entity));
+    assert!(casting::update(&mut world, &spatial, &mut net, 30).is_empty());
+}
@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+use uo2d_proto::components::{Bounds, Vec2, Vec3};
+use uo2d_proto::ecs::Entity;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::movement::MoveQuery;
+
+/// Coordinates on a quarter grid, keeping the float math exact enough to compare.
+fn coord() -> impl Strategy<Value = f64> {
+    (0i32..2000).prop_map(|value| value as f64 / 4.)
+}
+
+fn length() -> impl Strategy<Value = f64> {
+    (4i32..256).prop_map(|value| value as f64 / 4.)
+}
+
+/// A move from a source to a destination with an obstacle placed around the destination.
+fn movement() -> impl Strategy<Value = (MoveQuery, Bounds)> {
+    (
+        (coord(), coord()),
+        (-256i32..256, -256i32..256),
+        (length(), length()),
+        (-256i32..256, -256i32..256, length(), length()),
+    )
+        .prop_map(|((sx, sy), (vx, vy), (w, h), (ox, oy, ow, oh))| {
+            let source = Vec3::new(sx, sy, 1.);
+            let velocity = Vec2::new(vx as f64 / 4., vy as f64 / 4.);
+            let query = MoveQuery {
+                entity: Entity::new(0),
+                source,
+                destination: Vec3::new(sx + velocity.x(), sy + velocity.y(), 1.),
+                velocity,
+                entity_size: Vec2::new(w, h),
+                nearby: HashSet::from([Entity::new(1)]),
+            };
+            let (ox, oy) = (ox as f64 / 4., oy as f64 / 4.);
+            let obstacle = Bounds::new(
+                query.destination.x() + ox,
+                query.destination.y() + oy,
+                1.,
+                ow,
+                oh,
+            );
+            (query, obstacle)
+        })
+}
+
+/// Checks if a value lies between two others, in either order.
+fn between(value: f64, a: f64, b: f64) -> bool {
+    a.min(b) <= value && value <= a.max(b)
+}
+
+proptest! {
+    #[test]
+    fn clear_paths_reach_the_destination((mut query, obstacle) in movement()) {
+        query.nearby.clear();
+        prop_assert_eq!(SpatialHash::till_collision(&query, &obstacle, 1.), Some(query.destination));
+    }
+
+    #[test]
+    fn stops_outside_of_the_obstacle((query, obstacle) in movement()) {
+        if let Some(stop) = SpatialHash::till_collision(&query, &obstacle, 1.) {
+            prop_assert!(!obstacle.intersects_3d(&query.bounds(stop)));
+        }
+    }
+
+    #[test]
+    fn never_moves_beyond_the_source((query, obstacle) in movement()) {
+        if let Some(stop) = SpatialHash::till_collision(&query, &obstacle, 1.) {
+            prop_assert!(between(stop.x(), query.source.x(), query.destination.x()));
+            prop_assert!(between(stop.y(), query.source.y(), query.destination.y()));
+        }
+    }
+}
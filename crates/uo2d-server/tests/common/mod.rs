@@ -0,0 +1,88 @@
+//! Fixtures shared by the integration tests, loading the content shipped with the game
+//! from an explicit root instead of the working directory.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use uo2d_proto::components::{
+    Acceleration, Casting, Collidable, Cooldowns, Dormant, Falling, Health, Inventory, Mounted,
+    Npc, Player, Position, Spawned, Spawner, Stats, StatusEffects, Threat, Velocity,
+};
+use uo2d_proto::ecs::World;
+use uo2d_proto::items::ItemManager;
+use uo2d_server::config::ContentConfig;
+use uo2d_server::content::Content;
+use uo2d_server::region::RegionManager;
+use uuid::Uuid;
+
+/// Content root shipped with the game. Canonical, as paths leading through a parent directory
+/// are refused by the content.
+pub fn assets() -> String {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+    std::fs::canonicalize(path)
+        .expect("Unable to find the assets")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Confines loading to the content shipped with the game.
+pub fn content_config() -> ContentConfig {
+    ContentConfig {
+        root: assets(),
+        ..ContentConfig::default()
+    }
+}
+
+pub fn content() -> Content {
+    Content::new(&content_config())
+}
+
+pub fn items() -> ItemManager {
+    ItemManager::from_root(&assets())
+}
+
+pub fn regions() -> RegionManager {
+    RegionManager::from_content(&content())
+}
+
+/// World with the components the systems under test read and write.
+pub fn world() -> World {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    world.register_component::<Acceleration>();
+    world.register_component::<Collidable>();
+    world.register_component::<Player>();
+    world.register_component::<Npc>();
+    world.register_component::<Health>();
+    world.register_component::<Stats>();
+    world.register_component::<Inventory>();
+    world.register_component::<StatusEffects>();
+    world.register_component::<Casting>();
+    world.register_component::<Cooldowns>();
+    world.register_component::<Mounted>();
+    world.register_component::<Falling>();
+    world.register_component::<Spawner>();
+    world.register_component::<Spawned>();
+    world.register_component::<Threat>();
+    world.register_component::<Dormant>();
+    world
+}
+
+/// Writes a server configuration loading the shipped content, followed by the settings given.
+/// It is placed within a directory of its own, which also holds the database and the event
+/// log. Removing the directory is left to the test.
+pub fn server_config(settings: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("uo2d-server-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&directory).expect("Unable to create the directory");
+    let path = directory.join("server.yaml");
+    let config = format!(
+        "content:\n  root: \"{}\"\nstorage:\n  database: \"{}\"\n  logs: \"{}\"\n{}",
+        assets(),
+        directory.join("uo2d.db").display(),
+        directory.join("logs").display(),
+        settings
+    );
+    std::fs::write(&path, config).expect("Unable to write the configuration");
+    path
+}
@@ -0,0 +1,35 @@
+use uo2d_proto::components::{Bounds, Collidable, Position, Vec2, Vec3};
+use uo2d_proto::ecs::World;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::consistency;
+
+#[test]
+fn verify_repairs_stale_and_missing_entities() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Collidable>();
+
+    let size = Vec2::new(8., 8.);
+    let moved = world
+        .spawn()
+        .with(Position::new(Vec3::new(100., 100., 1.), size))
+        .with(Collidable)
+        .build();
+    let unhashed = world
+        .spawn()
+        .with(Position::new(Vec3::new(300., 300., 1.), size))
+        .build();
+
+    // The moved entity was left behind in its old cell and never added to its new one.
+    let mut spatial = SpatialHash::new(32);
+    spatial.insert_object(&moved, &Bounds::new(10., 10., 1., 8., 8.));
+
+    let divergence = consistency::verify(&world, &mut spatial);
+    assert!(divergence.missing.contains(&moved));
+    assert!(divergence.missing.contains(&unhashed));
+    assert!(divergence.stale.contains(&moved));
+
+    assert!(consistency::verify(&world, &mut spatial).is_empty());
+    let old = Bounds::new(10., 10., 1., 8., 8.);
+    assert!(spatial.query(&old, None).is_empty());
+}
@@ -0,0 +1,82 @@
+use uo2d_proto::components::{Collidable, Health, Inventory, ItemStack, Position, Vec2, Vec3};
+use uo2d_proto::items::ItemEffect;
+use uo2d_server::net::Net;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::consumables;
+
+mod common;
+
+#[test]
+fn items_are_consumed_one_at_a_time_from_their_slot() {
+    let items = common::items();
+    assert_eq!(
+        items.get(&7).and_then(|item| item.effect),
+        Some(ItemEffect::Heal(15))
+    );
+
+    let mut world = common::world();
+    let mut inventory = Inventory::new(100);
+    inventory.stacks.push(ItemStack::new(7, 2));
+    let entity = world.spawn().with(inventory).build();
+
+    // The slot has to hold the item that was chosen.
+    assert!(!consumables::consume(&mut world, &entity, 0, 3));
+    assert!(consumables::consume(&mut world, &entity, 0, 7));
+    assert!(consumables::consume(&mut world, &entity, 0, 7));
+    assert!(!consumables::consume(&mut world, &entity, 0, 7));
+    let inventory = world.get_component::<Inventory>(&entity).unwrap();
+    assert!(inventory.stacks.is_empty());
+}
+
+#[test]
+fn healing_is_refused_at_full_health() {
+    let mut world = common::world();
+    let spatial = SpatialHash::new(32);
+    let mut health = Health::new(100);
+    health.current = 90;
+    let entity = world.spawn().with(health).build();
+
+    assert!(consumables::can_heal(&world, &entity).is_ok());
+    assert!(consumables::heal(
+        &mut world,
+        &spatial,
+        &mut Net::new(),
+        &entity,
+        15
+    ));
+    assert_eq!(world.get_component::<Health>(&entity).unwrap().current, 100);
+    assert!(consumables::can_heal(&world, &entity).is_err());
+}
+
+#[test]
+fn teleports_land_within_range_and_clear_of_obstacles() {
+    let regions = common::regions();
+    let mut world = common::world();
+    let mut spatial = SpatialHash::new(32);
+
+    let start = Vec3::new(300., 300., 1.);
+    let size = Vec2::new(32., 32.);
+    let position = Position::new(start, size);
+    let entity = world.spawn().with(position).build();
+    spatial.insert_object(&entity, &position.bounds());
+    let block = Position::new(Vec3::new(340., 200., 1.), Vec2::new(120., 240.));
+    let obstacle = world.spawn().with(block).with(Collidable).build();
+    spatial.insert_object(&obstacle, &block.bounds());
+
+    for tick in 0..50 {
+        let loc = match consumables::random_destination(
+            &world, &spatial, &regions, &entity, 200., tick,
+        ) {
+            Some(loc) => loc,
+            None => continue,
+        };
+        assert!(loc.distance_2d(&start) <= 200. + f64::EPSILON);
+
+        let bounds = Position::new(loc, size).bounds();
+        let region = regions
+            .get_region(&loc)
+            .expect("landed outside every region");
+        assert!(region.is_inbounds(&bounds));
+        assert!(!bounds.intersects_2d(&block.bounds()));
+    }
+}
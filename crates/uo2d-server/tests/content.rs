@@ -0,0 +1,54 @@
+use uo2d_server::config::ContentConfig;
+use uo2d_server::content::{Content, LoadError};
+use uo2d_server::region::{Region, RegionManager};
+
+mod common;
+
+fn sandbox(max_file_size: u64) -> Content {
+    Content::new(&ContentConfig {
+        max_file_size,
+        ..common::content_config()
+    })
+}
+
+#[test]
+fn paths_outside_of_the_root_are_refused() {
+    let content = sandbox(1024 * 1024);
+    assert!(content.read(&content.path("npcs.yaml")).is_ok());
+
+    for path in [
+        content.path("../server.yaml"),
+        content.path("regions/../../Cargo.toml"),
+        format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR")),
+    ] {
+        let why = content.read(&path).unwrap_err();
+        assert!(matches!(why, LoadError::Outside(_)), "{} was read", path);
+        assert_eq!(why.path(), path);
+    }
+}
+
+#[test]
+fn load_errors_are_reported_instead_of_panicking() {
+    let content = sandbox(64);
+    assert!(matches!(
+        Region::load(&content, &content.path("regions/main.yaml")),
+        Err(LoadError::TooLarge { limit: 64, .. })
+    ));
+    assert!(matches!(
+        Region::load(&content, &content.path("regions/missing.yaml")),
+        Err(LoadError::Io { .. })
+    ));
+
+    let content = sandbox(1024 * 1024);
+    assert!(matches!(
+        Region::load(&content, &content.path("npcs.yaml")),
+        Err(LoadError::Invalid { .. })
+    ));
+
+    // Directories outside of the root load no regions at all.
+    let outside = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+    let regions = RegionManager::from_directory(&content, outside);
+    assert!(regions.regions().is_empty());
+    let regions = RegionManager::from_content(&content);
+    assert!(!regions.regions().is_empty());
+}
@@ -0,0 +1,47 @@
+use proptest::prelude::*;
+use uo2d_proto::packet::payloads::UuidPayload;
+use uo2d_proto::packet::{Action, Packet, Payload};
+use uo2d_server::fuzz::Receiver;
+use uuid::Uuid;
+
+/// Datagram opening with an action, known or not, followed by anything at all.
+fn datagram() -> impl Strategy<Value = Vec<u8>> {
+    (
+        any::<u8>(),
+        0u16..128,
+        prop::collection::vec(any::<u8>(), 0..256),
+    )
+        .prop_map(|(version, action, rest)| {
+            let mut data = vec![version];
+            data.extend(action.to_be_bytes());
+            data.extend(rest);
+            data
+        })
+}
+
+proptest! {
+    #[test]
+    fn datagrams_never_panic(data in datagram(), joined in any::<bool>()) {
+        let mut receiver = Receiver::new();
+        receiver.receive(&data, joined);
+    }
+
+    #[test]
+    fn raw_bytes_never_panic(data in prop::collection::vec(any::<u8>(), 0..64)) {
+        let mut receiver = Receiver::new();
+        receiver.receive(&data, true);
+    }
+}
+
+#[test]
+fn pings_are_answered() {
+    let mut receiver = Receiver::new();
+    let probe = Packet::new(
+        Action::Ping,
+        Uuid::nil(),
+        Payload::Uuid(UuidPayload::new(Uuid::new_v4())),
+    );
+    let replies = receiver.receive(&probe.to_bytes(), false);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].action(), Some(Action::Ping));
+}
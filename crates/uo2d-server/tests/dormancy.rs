@@ -0,0 +1,102 @@
+use uo2d_proto::components::{
+    Dormant, Npc, Player, Position, Spawned, Spawner, Threat, Vec2, Vec3, Velocity,
+};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::dormancy;
+use uuid::Uuid;
+
+mod common;
+
+fn place(world: &mut World, spatial: &mut SpatialHash, entity: Entity, loc: Vec3) {
+    if let Some(old) = world.get_component::<Position>(&entity) {
+        spatial.remove_object(&entity, &old.bounds());
+    }
+    let position = Position::new(loc, Vec2::new(32., 32.));
+    spatial.insert_object(&entity, &position.bounds());
+    world.upsert_component(entity, position);
+}
+
+#[test]
+fn npcs_sleep_without_players_and_wake_as_one_approaches() {
+    let regions = common::regions();
+    let mut world = common::world();
+    let mut spatial = SpatialHash::new(32);
+
+    let home = Vec3::new(512., 512., 1.);
+    let spawner = world
+        .spawn()
+        .with(Spawner {
+            npc: "wolf".to_string(),
+            home,
+            max_alive: 1,
+            respawn: 10.,
+            leash: 256.,
+            alive: 1,
+        })
+        .build();
+    let npc = world
+        .spawn()
+        .with(Npc {
+            kind: "wolf".to_string(),
+            dialogue: None,
+            wander: 1.,
+            heading: Vec2::new(1., 0.),
+            chase: 2.,
+        })
+        .with(Spawned(spawner))
+        .with(Threat::new(64.))
+        .with(Velocity(Vec2::new(1., 0.)))
+        .build();
+    place(&mut world, &mut spatial, npc, home);
+    let player = world.spawn().with(Player::new(Uuid::new_v4())).build();
+    place(&mut world, &mut spatial, player, Vec3::new(600., 512., 1.));
+
+    // A player nearby keeps it simulated.
+    dormancy::update(&mut world, &spatial, 0);
+    assert!(world.get_component::<Dormant>(&npc).is_none());
+
+    // Once they leave it stops and forgets its threat, only checked every few ticks.
+    place(
+        &mut world,
+        &mut spatial,
+        player,
+        Vec3::new(4000., 4000., 1.),
+    );
+    if let Some(threat) = world.get_component_mut::<Threat>(&npc) {
+        threat.add(player, 10.);
+        threat.target = Some(player);
+    }
+    dormancy::update(&mut world, &spatial, 1);
+    assert!(world.get_component::<Dormant>(&npc).is_none());
+    dormancy::update(&mut world, &spatial, 10);
+    assert!(world.get_component::<Dormant>(&npc).is_some());
+    assert!(world.get_component::<Velocity>(&npc).is_none());
+    assert!(world
+        .get_component::<Threat>(&npc)
+        .is_some_and(|threat| threat.table.is_empty() && threat.target.is_none()));
+
+    // Instead of walking it jumps around its home, staying well within its leash.
+    let mut jumped = false;
+    for tick in 10..1000 {
+        dormancy::jump(&mut world, &mut spatial, &regions, tick);
+        let loc = world.get_component::<Position>(&npc).unwrap().loc;
+        assert!(loc.distance_2d(&home) <= 128.);
+        jumped |= loc != home;
+    }
+    assert!(jumped);
+    let bounds = world.get_component::<Position>(&npc).unwrap().bounds();
+    assert!(spatial.query(&bounds, None).contains(&npc));
+
+    // Approaching wakes it before it comes into view.
+    let loc = world.get_component::<Position>(&npc).unwrap().loc;
+    let edge = loc.x() + spatial.interest().npc + 32.;
+    place(
+        &mut world,
+        &mut spatial,
+        player,
+        Vec3::new(edge, loc.y(), 1.),
+    );
+    dormancy::update(&mut world, &spatial, 1000);
+    assert!(world.get_component::<Dormant>(&npc).is_none());
+}
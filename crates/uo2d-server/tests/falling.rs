@@ -0,0 +1,72 @@
+use uo2d_proto::components::{Falling, Health, Position, Vec2, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_server::delta::DeltaEncoder;
+use uo2d_server::net::Net;
+use uo2d_server::region::GROUND_LAYER;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::falling;
+
+mod common;
+
+fn place(world: &mut World, spatial: &mut SpatialHash, loc: Vec3) -> Entity {
+    let position = Position::new(loc, Vec2::new(16., 16.));
+    let entity = world.spawn().with(position).with(Health::new(100)).build();
+    spatial.insert_object(&entity, &position.bounds());
+    entity
+}
+
+fn layer(world: &World, entity: &Entity) -> f64 {
+    world.get_component::<Position>(entity).unwrap().loc.z()
+}
+
+#[test]
+fn ledges_are_climbed_a_step_at_a_time() {
+    let regions = common::regions();
+    let (mut world, mut spatial, mut deltas) =
+        (common::world(), SpatialHash::new(32), DeltaEncoder::new());
+    assert_eq!(regions.floor_at(&Vec3::new(650., 240., 0.)), 4.);
+
+    // The western edge rises a layer at a time, the eastern edge is a cliff.
+    let stairs = place(&mut world, &mut spatial, Vec3::new(556., 232., 1.));
+    let cliff = place(&mut world, &mut spatial, Vec3::new(744., 232., 1.));
+    falling::update(
+        &mut world,
+        &mut spatial,
+        &regions,
+        &mut deltas,
+        &mut Net::new(),
+    );
+    assert_eq!(layer(&world, &stairs), 2.);
+    assert_eq!(layer(&world, &cliff), GROUND_LAYER);
+}
+
+#[test]
+fn falls_take_several_ticks_and_hurt_on_landing() {
+    let regions = common::regions();
+    let (mut world, mut spatial, mut deltas) =
+        (common::world(), SpatialHash::new(32), DeltaEncoder::new());
+
+    // Standing past the eastern cliff while still on the top layer.
+    let entity = place(&mut world, &mut spatial, Vec3::new(760., 232., 4.));
+    let mut ticks = 0;
+    while layer(&world, &entity) > GROUND_LAYER {
+        falling::update(
+            &mut world,
+            &mut spatial,
+            &regions,
+            &mut deltas,
+            &mut Net::new(),
+        );
+        ticks += 1;
+        assert!(ticks < 20, "never landed");
+    }
+
+    assert!(ticks > 1);
+    assert!(world.get_component::<Falling>(&entity).is_none());
+    let health = world.get_component::<Health>(&entity).unwrap();
+    assert_eq!(
+        health.max - health.current,
+        falling::fall_damage(4., GROUND_LAYER)
+    );
+    assert_eq!(falling::fall_damage(2., GROUND_LAYER), 0);
+}
@@ -0,0 +1,80 @@
+use uo2d_proto::components::{Health, Player, Position, Vec2, Vec3};
+use uo2d_proto::ecs::World;
+use uo2d_proto::packet::payloads::{OverlayKind, OverlayShape};
+use uo2d_proto::packet::{Action, Packet, Payload, MAX_DATAGRAM};
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::inspect;
+use uuid::Uuid;
+
+mod common;
+
+#[test]
+fn census_and_components_describe_the_world() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Player>();
+    world.register_component::<Health>();
+
+    let position = Position::new(Vec3::new(10., 20., 1.), Vec2::new(4., 4.));
+    let player = world
+        .spawn()
+        .with(position)
+        .with(Player::new(Uuid::new_v4()))
+        .with(Health::new(50))
+        .build();
+    world.spawn().with(position).build();
+
+    let census = inspect::census(&world);
+    let count = |kind: &str| census.iter().find(|(k, _)| *k == kind).map(|(_, n)| *n);
+    assert_eq!(count("players"), Some(1));
+    assert_eq!(count("npcs"), Some(0));
+    assert_eq!(count("positioned"), Some(2));
+
+    let components = inspect::components(&world, &player);
+    let names: Vec<&str> = components.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, vec!["Position", "Player", "Health"]);
+
+    // Entities that no longer exist have nothing to describe.
+    let missing = world.spawn().build();
+    world.despawn(&missing);
+    assert!(inspect::components(&world, &missing).is_empty());
+}
+
+#[test]
+fn overlays_fit_within_a_datagram() {
+    let regions = common::regions();
+
+    let mut world = World::new();
+    world.register_component::<Position>();
+    let mut spatial = SpatialHash::new(32);
+    for i in 0..400 {
+        let loc = Vec3::new(100. + (i % 20) as f64 * 8., 100. + (i / 20) as f64 * 8., 1.);
+        let position = Position::new(loc, Vec2::new(4., 4.));
+        let entity = world.spawn().with(position).build();
+        spatial.insert_object(&entity, &position.bounds());
+    }
+
+    let center = Vec2::new(180., 180.);
+    let bounds = inspect::overlay(
+        &world,
+        &spatial,
+        &regions,
+        OverlayKind::Bounds,
+        center,
+        160.,
+    );
+    assert!(!bounds.shapes.is_empty() && bounds.shapes.len() < 400);
+    let packet = Packet::new(Action::Overlay, Uuid::nil(), Payload::Overlay(bounds));
+    assert!(packet.as_bytes().len() <= MAX_DATAGRAM);
+
+    // Every occupied cell is outlined, none of the empty ones.
+    let cells = inspect::overlay(&world, &spatial, &regions, OverlayKind::Cells, center, 160.);
+    assert_eq!(
+        cells.shapes.len(),
+        spatial.occupied_within(center, 160.).len()
+    );
+    assert!(cells
+        .shapes
+        .iter()
+        .all(|shape| matches!(shape, OverlayShape::Rect([_, _, 32, 32]))));
+}
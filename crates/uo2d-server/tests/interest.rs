@@ -0,0 +1,27 @@
+use uo2d_proto::components::{Bounds, Vec2};
+use uo2d_proto::ecs::World;
+use uo2d_server::spatial_hash::SpatialHash;
+
+#[test]
+fn query_radius_matches_entities_by_their_cells() {
+    let mut world = World::new();
+    let mut spatial = SpatialHash::new(32);
+    let center = Vec2::new(500., 500.);
+
+    // A small entity just within the radius, and a large one whose far edge is.
+    let near = world.spawn().build();
+    spatial.insert_object(&near, &Bounds::new(640., 500., 1., 4., 4.));
+    let large = world.spawn().build();
+    spatial.insert_object(&large, &Bounds::new(650., 400., 1., 200., 200.));
+    let far = world.spawn().build();
+    spatial.insert_object(&far, &Bounds::new(900., 900., 1., 4., 4.));
+
+    let found = spatial.query_radius(center, 160., None);
+    assert!(found.contains(&near));
+    assert!(found.contains(&large));
+    assert!(!found.contains(&far));
+
+    // Checking bounds directly agrees with the query.
+    assert!(spatial.reaches(&Bounds::new(640., 500., 1., 4., 4.), center, 160.));
+    assert!(!spatial.reaches(&Bounds::new(900., 900., 1., 4., 4.), center, 160.));
+}
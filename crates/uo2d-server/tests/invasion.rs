@@ -0,0 +1,91 @@
+use uo2d_proto::components::{ItemStack, Vec3};
+use uo2d_server::config::{EventHook, InvasionConfig, WorldEventConfig};
+use uo2d_server::invasion::spawn_points;
+
+fn square() -> Vec<Vec3> {
+    vec![
+        Vec3::new(0., 0., 1.),
+        Vec3::new(1000., 0., 1.),
+        Vec3::new(1000., 1000., 1.),
+        Vec3::new(0., 1000., 1.),
+    ]
+}
+
+#[test]
+fn waves_spread_around_the_outline() {
+    let target = Vec3::new(500., 500., 1.);
+    let points = spawn_points(&square(), target, 4);
+
+    assert_eq!(points.len(), 4);
+    // One on each edge, pulled in from its middle towards the target.
+    let expected = [(500., 64.), (936., 500.), (500., 936.), (64., 500.)];
+    for (point, (x, y)) in points.iter().zip(expected) {
+        assert!((point.x() - x).abs() < 1e-6, "{:?}", point);
+        assert!((point.y() - y).abs() < 1e-6, "{:?}", point);
+        assert_eq!(point.z(), 1.);
+    }
+}
+
+#[test]
+fn points_stay_within_the_outline() {
+    // Points on edges closer to the target than the inset stop at the target.
+    let target = Vec3::new(20., 20., 1.);
+    for point in spawn_points(&square(), target, 16) {
+        assert!(point.x() >= 0. && point.x() <= 1000., "{:?}", point);
+        assert!(point.y() >= 0. && point.y() <= 1000., "{:?}", point);
+    }
+}
+
+#[test]
+fn waves_gather_at_the_target_without_an_outline() {
+    let target = Vec3::new(20., 40., 1.);
+    assert_eq!(spawn_points(&[], target, 3), vec![target; 3]);
+    assert!(spawn_points(&square(), target, 0).is_empty());
+}
+
+#[test]
+fn waves_grow_with_the_players_online() {
+    let config = InvasionConfig {
+        region: "Mainland".to_string(),
+        target: None,
+        npcs: vec!["cave_rat".to_string()],
+        waves: 3,
+        size: 4,
+        per_player: 0.5,
+        delay: 10.,
+        rewards: Vec::new(),
+    };
+
+    assert_eq!(config.wave_size(0), 4);
+    assert_eq!(config.wave_size(3), 6);
+    assert_eq!(config.wave_size(10), 9);
+}
+
+#[test]
+fn invasions_are_read_from_yaml() {
+    let yaml = r#"
+name: "Rat Horde"
+interval: 600
+duration: 300
+hooks:
+  - invasion:
+      region: "Mainland"
+      npcs: ["cave_rat"]
+      waves: 2
+      size: 3
+      rewards:
+        - item: 3
+          count: 1
+"#;
+    let event: WorldEventConfig = serde_yaml::from_str(yaml).unwrap();
+
+    match &event.hooks[..] {
+        [EventHook::Invasion(invasion)] => {
+            assert_eq!(invasion.target, None);
+            assert_eq!(invasion.per_player, 0.);
+            assert_eq!(invasion.delay, 10.);
+            assert_eq!(invasion.rewards, vec![ItemStack { item: 3, count: 1 }]);
+        }
+        hooks => panic!("unexpected hooks {:?}", hooks),
+    }
+}
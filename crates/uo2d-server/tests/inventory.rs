@@ -0,0 +1,30 @@
+use uo2d_proto::components::{Inventory, ItemStack};
+use uo2d_proto::ecs::World;
+use uo2d_server::systems::inventory;
+
+#[test]
+fn stacks_are_moved_between_slots() {
+    let mut world = World::new();
+    world.register_component::<Inventory>();
+    let mut carried = Inventory::new(100);
+    carried.stacks = vec![
+        ItemStack::new(1, 1),
+        ItemStack::new(2, 1),
+        ItemStack::new(3, 1),
+    ];
+    let entity = world.spawn().with(carried).build();
+
+    assert!(inventory::move_stack(&mut world, &entity, 0, 2));
+    assert!(inventory::move_stack(&mut world, &entity, 2, 1));
+    let items = |world: &World| -> Vec<u16> {
+        let carried = world.get_component::<Inventory>(&entity).unwrap();
+        carried.stacks.iter().map(|stack| stack.item).collect()
+    };
+    assert_eq!(items(&world), vec![2, 1, 3]);
+
+    // Moving to the same slot or beyond the last stack changes nothing.
+    assert!(!inventory::move_stack(&mut world, &entity, 1, 1));
+    assert!(!inventory::move_stack(&mut world, &entity, 0, 3));
+    assert!(!inventory::move_stack(&mut world, &entity, 3, 0));
+    assert_eq!(items(&world), vec![2, 1, 3]);
+}
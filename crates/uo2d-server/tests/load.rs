@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use uo2d_proto::components::{Npc, Player, Position, Vec2, Vec3};
+use uo2d_proto::ecs::World;
+use uo2d_server::load::{LoadShedder, ShedLevel, Throttle};
+use uuid::Uuid;
+
+const BUDGET: Duration = Duration::from_millis(50);
+
+#[test]
+fn shedding_rises_with_slow_ticks_and_recovers() {
+    let mut shedder = LoadShedder::new(BUDGET);
+    let mut changes = vec![];
+    for _ in 0..200 {
+        changes.extend(shedder.record(BUDGET * 2));
+    }
+    assert_eq!(
+        changes,
+        vec![
+            ShedLevel::Paused,
+            ShedLevel::Distant,
+            ShedLevel::Replication
+        ]
+    );
+
+    // Ticks barely within the budget are not enough to restore any work.
+    for _ in 0..400 {
+        assert_eq!(shedder.record(BUDGET.mul_f64(0.9)), None);
+    }
+
+    // Plenty of spare time restores everything, slower than it was shed.
+    changes.clear();
+    for _ in 0..1000 {
+        changes.extend(shedder.record(BUDGET / 10));
+    }
+    assert_eq!(
+        changes,
+        vec![ShedLevel::Distant, ShedLevel::Paused, ShedLevel::Normal]
+    );
+
+    let stats = shedder.stats();
+    assert_eq!(stats.level, ShedLevel::Normal);
+    assert_eq!((stats.raised, stats.lowered), (3, 3));
+    assert_eq!(
+        stats.ticks.iter().map(|(_, ticks)| ticks).sum::<u64>(),
+        1600
+    );
+}
+
+#[test]
+fn only_npcs_away_from_players_are_throttled() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Player>();
+    world.register_component::<Npc>();
+
+    let size = Vec2::new(4., 4.);
+    let npc = |kind: &str| Npc {
+        kind: kind.to_string(),
+        dialogue: None,
+        wander: 1.,
+        heading: Vec2::ORIGIN,
+        chase: 1.,
+    };
+    world
+        .spawn()
+        .with(Position::new(Vec3::new(100., 100., 1.), size))
+        .with(Player::new(Uuid::new_v4()))
+        .build();
+    let near = world
+        .spawn()
+        .with(Position::new(Vec3::new(150., 100., 1.), size))
+        .with(npc("near"))
+        .build();
+    let far = world
+        .spawn()
+        .with(Position::new(Vec3::new(900., 900., 1.), size))
+        .with(npc("far"))
+        .build();
+
+    // The distant NPC has its turn once every stride, catching up on the time it sat out.
+    let skipped = |level| {
+        (0..Throttle::STRIDE)
+            .filter(|tick| Throttle::new(level, &world, *tick, 160.).skips(&far))
+            .count() as u64
+    };
+    assert_eq!(skipped(ShedLevel::Distant), Throttle::STRIDE - 1);
+    assert_eq!(skipped(ShedLevel::Paused), 0);
+
+    let throttle = Throttle::new(ShedLevel::Distant, &world, 0, 160.);
+    assert!(!throttle.skips(&near));
+    assert_eq!(throttle.elapsed(&near, 0.05), 0.05);
+    assert_eq!(throttle.elapsed(&far, 0.05), 0.05 * Throttle::STRIDE as f64);
+
+    // Only the most shed level sends movement to far players less often.
+    assert_eq!(ShedLevel::Distant.replication_scale(), 1);
+    assert_eq!(ShedLevel::Replication.replication_scale(), Throttle::STRIDE);
+}
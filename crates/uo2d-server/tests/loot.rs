@@ -0,0 +1,111 @@
+use uo2d_server::loot::LootManager;
+
+mod common;
+
+use common::items;
+
+#[test]
+fn shipped_tables_are_valid() {
+    let items = items();
+    let content = std::fs::read_to_string(common::content().path(LootManager::PATH)).unwrap();
+    assert!(LootManager::parse(&content, &items).is_ok());
+}
+
+#[test]
+fn invalid_tables_are_rejected() {
+    let items = items();
+    let content = r#"
+- name: "a"
+  entries:
+    - table: "b"
+      weight: 1
+- name: "b"
+  entries:
+    - table: "a"
+      weight: 1
+    - item: 9999
+      weight: 1
+    - table: "missing"
+      weight: 1
+    - item: 7
+      weight: 1
+      count: [3, 1]
+- name: "empty"
+  entries:
+    - item: 7
+      weight: 0
+"#;
+
+    let errors = LootManager::parse(content, &items).err().unwrap();
+    assert_eq!(
+        errors,
+        vec![
+            "table 'a' rolls itself",
+            "table 'b' drops unknown item 9999",
+            "table 'b' has an invalid count",
+            "table 'b' rolls itself",
+            "table 'b' rolls unknown table 'missing'",
+            "table 'empty' has no weighted entries",
+        ]
+    );
+}
+
+#[test]
+fn nested_tables_and_level_ranges_are_rolled() {
+    let items = items();
+    let content = r#"
+- name: "outer"
+  rolls: 3
+  entries:
+    - table: "inner"
+      weight: 1
+- name: "inner"
+  entries:
+    - item: 7
+      weight: 1
+      count: [2, 2]
+    - item: 8
+      weight: 1000
+      levels: [10, 20]
+"#;
+    let loot = LootManager::parse(content, &items).unwrap();
+
+    // Below the range only the bandages can drop, combined into a single stack.
+    let dropped = loot.roll("outer", 1, 42);
+    assert_eq!(dropped.len(), 1);
+    assert_eq!((dropped[0].item, dropped[0].count), (7, 6));
+
+    // Within the range the ore is far more likely, and the same seed rolls the same loot.
+    let dropped = loot.roll("outer", 15, 42);
+    assert!(dropped.iter().any(|stack| stack.item == 8));
+    assert_eq!(dropped, loot.roll("outer", 15, 42));
+    assert!(loot.roll("missing", 15, 42).is_empty());
+}
+
+#[test]
+fn later_layers_replace_tables_of_the_same_name() {
+    let items = items();
+    let base = r#"
+- name: "outer"
+  entries:
+    - table: "inner"
+      weight: 1
+- name: "inner"
+  entries:
+    - item: 7
+      weight: 1
+"#;
+    let pack = r#"
+- name: "inner"
+  entries:
+    - item: 8
+      weight: 1
+"#;
+
+    let loot = LootManager::parse_layers(&[base, pack], &items).unwrap();
+    assert_eq!(loot.roll("outer", 1, 42)[0].item, 8);
+
+    // Names are only unique within each layer.
+    let errors = LootManager::parse_layers(&[pack, &pack.repeat(2)], &items).err();
+    assert_eq!(errors.unwrap(), vec!["table 'inner' is defined twice"]);
+}
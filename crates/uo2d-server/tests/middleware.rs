@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use uo2d_proto::packet::payloads::MessagePayload;
+use uo2d_proto::packet::{Action, Packet, Payload};
+use uo2d_server::middleware::{
+    Authenticated, Inbound, Middleware, Pipeline, RateLimit, Rejection, Validation,
+};
+use uuid::Uuid;
+
+fn inbound(uuid: Uuid, joined: bool, action: Action, payload: Payload) -> Inbound {
+    Inbound::new(uuid, joined, Packet::new(action, uuid, payload))
+}
+
+#[test]
+fn clients_only_send_joining_packets_until_they_join() {
+    let uuid = Uuid::new_v4();
+    let stage = Authenticated;
+
+    assert!(stage
+        .check(&mut inbound(uuid, false, Action::Login, Payload::Empty))
+        .is_ok());
+    assert!(stage
+        .check(&mut inbound(uuid, false, Action::Echo, Payload::Empty))
+        .is_ok());
+    assert_eq!(
+        stage.check(&mut inbound(uuid, false, Action::Pickup, Payload::Empty)),
+        Err(Rejection::Unauthenticated)
+    );
+    assert!(stage
+        .check(&mut inbound(uuid, true, Action::Pickup, Payload::Empty))
+        .is_ok());
+}
+
+#[test]
+fn rate_limit_allows_bursts_then_refills_over_time() {
+    let (uuid, other) = (Uuid::new_v4(), Uuid::new_v4());
+    let limit = RateLimit::new(10., 5.);
+    let start = Instant::now();
+    let at = |uuid, elapsed| {
+        let mut packet = inbound(uuid, true, Action::Pickup, Payload::Empty);
+        packet.received = start + elapsed;
+        limit.check(&mut packet)
+    };
+
+    for _ in 0..5 {
+        assert!(at(uuid, Duration::ZERO).is_ok());
+    }
+    assert_eq!(at(uuid, Duration::ZERO), Err(Rejection::RateLimited));
+    assert!(at(other, Duration::ZERO).is_ok());
+
+    // A tenth of a second later one more packet is allowed.
+    assert!(at(uuid, Duration::from_millis(100)).is_ok());
+    assert_eq!(
+        at(uuid, Duration::from_millis(100)),
+        Err(Rejection::RateLimited)
+    );
+
+    // Clients that stop sending are forgotten.
+    assert_eq!(limit.tracked(), 2);
+    assert!(at(other, Duration::from_secs(60)).is_ok());
+    assert_eq!(limit.tracked(), 1);
+
+    // No rate leaves clients unlimited.
+    limit.reconfigure(0., 0.);
+    for _ in 0..100 {
+        assert!(at(uuid, Duration::from_secs(60)).is_ok());
+    }
+}
+
+#[test]
+fn pipeline_stops_at_the_first_rejection() {
+    let uuid = Uuid::new_v4();
+    let limit = Arc::new(RateLimit::new(10., 1.));
+    let pipeline = Pipeline::new()
+        .with(Authenticated)
+        .with(limit.clone())
+        .with(Validation);
+
+    // Packets sent before joining never count towards the limit.
+    let mut early = inbound(uuid, false, Action::Pickup, Payload::Empty);
+    assert_eq!(pipeline.check(&mut early), Err(Rejection::Unauthenticated));
+    assert_eq!(limit.tracked(), 0);
+
+    // Valid packets are decoded once, the handler given the payload.
+    let message = Payload::Message(MessagePayload::new("hello"));
+    let mut valid = inbound(uuid, true, Action::Message, message);
+    assert!(pipeline.check(&mut valid).is_ok());
+    assert!(matches!(valid.payload, Some(Payload::Message(_))));
+
+    let mut flood = inbound(uuid, true, Action::Message, Payload::Empty);
+    assert_eq!(pipeline.check(&mut flood), Err(Rejection::RateLimited));
+}
+
+#[test]
+fn undecodable_packets_are_malformed() {
+    let uuid = Uuid::new_v4();
+    let mut bytes = Packet::new(Action::Pickup, uuid, Payload::Empty).to_bytes();
+    bytes[1..3].copy_from_slice(&[0xFF, 0xFF]);
+    let mut unknown = Inbound::new(uuid, true, Packet::from_bytes(&bytes));
+    assert_eq!(
+        Validation.check(&mut unknown),
+        Err(Rejection::Malformed("unknown action".to_string()))
+    );
+
+    let mut bytes = Packet::new(Action::Pickup, uuid, Payload::Empty).to_bytes();
+    bytes.truncate(19);
+    bytes.push(0xFF);
+    let mut invalid = Inbound::new(uuid, true, Packet::from_bytes(&bytes));
+    assert!(matches!(
+        Validation.check(&mut invalid),
+        Err(Rejection::Malformed(_))
+    ));
+    assert!(invalid.payload.is_none());
+}
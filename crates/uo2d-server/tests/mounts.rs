@@ -0,0 +1,50 @@
+use uo2d_proto::components::{Health, Position, Vec2, Vec3};
+use uo2d_server::net::Net;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::{combat, mounts, physics};
+
+mod common;
+
+#[test]
+fn mounts_are_ridden_outdoors_and_faster() {
+    let regions = common::regions();
+    let mut world = common::world();
+    let spatial = SpatialHash::new(32);
+
+    let outside = Position::new(Vec3::new(512., 512., 1.), Vec2::new(32., 32.));
+    let inside = Position::new(Vec3::new(1600., 300., 1.), Vec2::new(32., 32.));
+    let rider = world.spawn().with(outside).with(Health::new(100)).build();
+    let caver = world.spawn().with(inside).with(Health::new(100)).build();
+
+    assert!(mounts::mount(&mut world, &spatial, &regions, &mut Net::new(), &caver).is_err());
+    assert_eq!(physics::speed_scale(&world, &rider), 1.);
+    assert!(mounts::mount(&mut world, &spatial, &regions, &mut Net::new(), &rider).is_ok());
+    assert!(mounts::mount(&mut world, &spatial, &regions, &mut Net::new(), &rider).is_err());
+    assert!(physics::speed_scale(&world, &rider) > 1.);
+
+    // Riding into the cave throws the rider off.
+    let mut net = Net::new();
+    mounts::update(&mut world, &spatial, &regions, &mut net);
+    assert!(net.is_empty());
+    world.upsert_component(rider, inside);
+    mounts::update(&mut world, &spatial, &regions, &mut net);
+    assert_eq!(net.len(), 1);
+    assert!(!mounts::is_mounted(&world, &rider));
+}
+
+#[test]
+fn damage_dismounts_the_rider() {
+    let regions = common::regions();
+    let mut world = common::world();
+    let spatial = SpatialHash::new(32);
+
+    let position = Position::new(Vec3::new(512., 512., 1.), Vec2::new(32., 32.));
+    let rider = world.spawn().with(position).with(Health::new(100)).build();
+    mounts::mount(&mut world, &spatial, &regions, &mut Net::new(), &rider).unwrap();
+
+    let mut net = Net::new();
+    combat::damage(&mut world, &spatial, &mut net, &rider, 5);
+    assert_eq!(net.len(), 2);
+    assert!(!mounts::is_mounted(&world, &rider));
+    assert!(!mounts::dismount(&mut world, &spatial, &mut net, &rider));
+}
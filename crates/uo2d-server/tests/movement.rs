@@ -0,0 +1,58 @@
+use uo2d_proto::components::{Bounds, Collidable, Player, Position, Vec2, Vec3, Velocity};
+use uo2d_proto::components::{Obstacle, Projectile, Pushable};
+use uo2d_proto::ecs::World;
+use uo2d_server::delta::DeltaEncoder;
+use uo2d_server::net::Net;
+use uo2d_server::replication::Replicator;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::movement;
+
+mod common;
+
+#[test]
+fn with_velocity_queries_the_hash_once_per_moving_entity() {
+    let regions = common::regions();
+    let items = common::items();
+
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    world.register_component::<Collidable>();
+    world.register_component::<Projectile>();
+    world.register_component::<Pushable>();
+    world.register_component::<Obstacle>();
+    world.register_component::<Player>();
+
+    let mut spatial = SpatialHash::new(32);
+    let size = Vec2::new(4., 4.);
+    let moving = 50;
+    for i in 0..moving * 2 {
+        let loc = Vec3::new(
+            100. + (i % 10) as f64 * 20.,
+            100. + (i / 10) as f64 * 20.,
+            1.,
+        );
+        let mut builder = world
+            .spawn()
+            .with(Position::new(loc, size))
+            .with(Collidable);
+        if i % 2 == 0 {
+            builder = builder.with(Velocity(Vec2::new(1., 1.)));
+        }
+        let entity = builder.build();
+        spatial.insert_object(&entity, &Bounds::from_vec(loc, size));
+    }
+
+    let mut deltas = DeltaEncoder::new();
+    movement::with_velocity(
+        &mut world,
+        &mut spatial,
+        &regions,
+        &items,
+        &mut deltas,
+        &mut Replicator::default(),
+        &mut Net::new(),
+        0.,
+    );
+    assert_eq!(spatial.query_count(), moving as u64);
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use uo2d_proto::components::{Player, Position, Vec2, Vec3};
+use uo2d_proto::ecs::World;
+use uo2d_proto::locale::Text;
+use uo2d_proto::packet::payloads::{MovementPayload, SpawnPayload};
+use uo2d_proto::packet::{Action, BroadcastScope, PacketConfiguration};
+use uo2d_server::net::{Event, Net};
+use uo2d_server::spatial_hash::SpatialHash;
+use uuid::Uuid;
+
+#[test]
+fn events_are_addressed_by_their_scope() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Player>();
+    let mut spatial = SpatialHash::new(32);
+
+    let (near, far) = (Uuid::new_v4(), Uuid::new_v4());
+    for (uuid, loc) in [
+        (near, Vec3::new(100., 100., 1.)),
+        (far, Vec3::new(2000., 2000., 1.)),
+    ] {
+        let position = Position::new(loc, Vec2::new(32., 32.));
+        let entity = world.spawn().with(position).with(Player::new(uuid)).build();
+        spatial.insert_object(&entity, &position.bounds());
+    }
+    let npc = world.spawn().build();
+    let spawn = SpawnPayload::new(
+        MovementPayload::new(
+            npc,
+            Vec2::new(32., 32.),
+            Vec3::new(0., 0., 1.),
+            Vec2::ORIGIN,
+        ),
+        Default::default(),
+        None,
+    );
+
+    let mut net = Net::new();
+    net.send_to(near, Event::Text(Text::literal("hello")));
+    net.send_to(
+        far,
+        Event::PlayerJoined {
+            uuid: near,
+            spawn: spawn.clone(),
+        },
+    );
+    net.broadcast_to(HashSet::from([near, far]), Event::EntityRemoved(npc));
+    net.broadcast_near(Vec2::new(116., 116.), 64., Event::Joined(spawn));
+    net.broadcast(Event::DialogueEnded);
+    assert_eq!(net.len(), 5);
+
+    let packets = net.drain(&world, &spatial);
+    assert!(net.is_empty());
+    assert_eq!(packets.len(), 5);
+
+    // Single packets are sent to the uuid they carry.
+    assert!(matches!(
+        &packets[0],
+        PacketConfiguration::Single(packet)
+            if packet.uuid() == near && packet.action() == Some(Action::Message)
+    ));
+    // Packets from another player keep their uuid, so are addressed separately.
+    assert!(matches!(
+        &packets[1],
+        PacketConfiguration::Broadcast(packet, BroadcastScope::Local(uuids))
+            if packet.uuid() == near && *uuids == HashSet::from([far])
+    ));
+    assert!(matches!(
+        &packets[2],
+        PacketConfiguration::Broadcast(packet, BroadcastScope::Local(uuids))
+            if packet.uuid().is_nil() && uuids.len() == 2
+    ));
+    // Only players within the radius are found.
+    assert!(matches!(
+        &packets[3],
+        PacketConfiguration::Broadcast(packet, BroadcastScope::Local(uuids))
+            if packet.action() == Some(Action::Success) && *uuids == HashSet::from([near])
+    ));
+    assert!(matches!(
+        &packets[4],
+        PacketConfiguration::Broadcast(_, BroadcastScope::Global)
+    ));
+}
@@ -0,0 +1,63 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use uo2d_proto::packet::payloads::MessagePayload;
+use uo2d_proto::packet::{Action, Packet, Payload, MAX_DATAGRAM};
+use uo2d_server::outbox::Outbox;
+use uuid::Uuid;
+
+fn message(text: &str) -> Arc<[u8]> {
+    let packet = Packet::new(
+        Action::Message,
+        Uuid::nil(),
+        Payload::Message(MessagePayload::new(text)),
+    );
+    packet.as_bytes().into()
+}
+
+/// Text carried by the packets within a datagram, in order.
+fn messages(datagram: &[u8]) -> Vec<String> {
+    Packet::from_bytes(datagram)
+        .unbundle()
+        .into_iter()
+        .map(|packet| match packet.payload() {
+            Payload::Message(payload) => payload.message,
+            payload => panic!("unexpected payload {:?}", payload),
+        })
+        .collect()
+}
+
+#[test]
+fn bundles_packets_until_flushed_within_the_datagram_limit() {
+    let addr: SocketAddr = "127.0.0.1:31013".parse().unwrap();
+    let mut outbox = Outbox::new();
+
+    let mut written = Vec::new();
+    let sent: Vec<String> = (0..64).map(|i| format!("message {}", i)).collect();
+    for text in sent.iter() {
+        written.extend(outbox.push(addr, message(text), false));
+    }
+    written.extend(outbox.drain().into_iter().map(|(_, datagram)| datagram));
+
+    // Fewer datagrams than packets, each fitting in a receive buffer.
+    assert!(written.len() > 1 && written.len() < sent.len());
+    assert!(written
+        .iter()
+        .all(|datagram| datagram.len() <= MAX_DATAGRAM));
+
+    let received: Vec<String> = written.iter().flat_map(|d| messages(d)).collect();
+    assert_eq!(received, sent);
+}
+
+#[test]
+fn urgent_packets_are_written_after_those_queued() {
+    let addr: SocketAddr = "127.0.0.1:31013".parse().unwrap();
+    let mut outbox = Outbox::new();
+
+    assert!(outbox.push(addr, message("first"), false).is_empty());
+    let written = outbox.push(addr, message("second"), true);
+    assert_eq!(written.len(), 2);
+    assert_eq!(messages(&written[0]), vec!["first"]);
+    assert_eq!(messages(&written[1]), vec!["second"]);
+    assert!(outbox.drain().is_empty());
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::Path;
+
+use uo2d_server::config::ContentConfig;
+use uo2d_server::content::Content;
+use uo2d_server::npcs::NpcManager;
+use uo2d_server::region::RegionManager;
+
+mod common;
+
+/// Writes a file within the root, creating the directories leading to it.
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn manifest(name: &str, version: &str, dependencies: &str) -> String {
+    format!(
+        "name: \"{}\"\nversion: \"{}\"\ndependencies: {}\n",
+        name, version, dependencies
+    )
+}
+
+#[test]
+fn packs_override_the_base_content_after_their_dependencies() {
+    let floor = fs::read_to_string(common::content().path("regions/floor2.yaml")).unwrap();
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("packs");
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    let root = directory.canonicalize().unwrap().join("assets");
+
+    write(&root, "regions/floor2.yaml", &floor);
+    write(
+        &root,
+        "npcs.yaml",
+        "- kind: \"cave_rat\"\n  health: 10\n- kind: \"banker\"\n  banker: true\n",
+    );
+
+    // Listed first, but loaded after the pack it depends on.
+    write(
+        &root,
+        "packs/hard/pack.yaml",
+        &manifest("hard", "1.0.0", "[{ name: \"extra\", version: \"1.2.0\" }]"),
+    );
+    write(
+        &root,
+        "packs/hard/npcs.yaml",
+        "- kind: \"cave_rat\"\n  health: 50\n",
+    );
+    let renamed = floor.replace("A description here.", "Overridden.");
+    write(&root, "packs/hard/regions/floor2.yaml", &renamed);
+
+    write(
+        &root,
+        "packs/extra/pack.yaml",
+        &manifest("extra", "1.2.3", "[]"),
+    );
+    write(
+        &root,
+        "packs/extra/npcs.yaml",
+        "- kind: \"cave_rat\"\n  health: 20\n- kind: \"bat\"\n  health: 5\n",
+    );
+
+    // Broken packs, and those depending on them, are skipped.
+    write(
+        &root,
+        "packs/missing/pack.yaml",
+        &manifest("missing", "1.0.0", "[{ name: \"unknown\" }]"),
+    );
+    write(
+        &root,
+        "packs/outdated/pack.yaml",
+        &manifest(
+            "outdated",
+            "1.0.0",
+            "[{ name: \"extra\", version: \"2.0.0\" }]",
+        ),
+    );
+    write(
+        &root,
+        "packs/first/pack.yaml",
+        &manifest("first", "1.0.0", "[{ name: \"second\" }]"),
+    );
+    write(
+        &root,
+        "packs/second/pack.yaml",
+        &manifest("second", "1.0.0", "[{ name: \"first\" }]"),
+    );
+    write(
+        &root,
+        "packs/broken/npcs.yaml",
+        "- kind: \"cave_rat\"\n  health: 99\n",
+    );
+
+    let packs = [
+        "hard", "extra", "missing", "outdated", "first", "second", "broken",
+    ];
+    let content = Content::new(&ContentConfig {
+        root: root.to_string_lossy().to_string(),
+        packs: packs
+            .iter()
+            .map(|pack| root.join("packs").join(pack).to_string_lossy().to_string())
+            .collect(),
+        ..ContentConfig::default()
+    });
+    let loaded: Vec<&str> = content
+        .packs()
+        .iter()
+        .map(|pack| pack.manifest.name.as_str())
+        .collect();
+    assert_eq!(loaded, ["extra", "hard"]);
+
+    let npcs = NpcManager::new(&content);
+    assert_eq!(npcs.get("cave_rat").unwrap().health, Some(50));
+    assert_eq!(npcs.get("bat").unwrap().health, Some(5));
+    assert!(npcs.get("banker").unwrap().banker);
+
+    let regions = RegionManager::from_content(&content);
+    assert_eq!(regions.regions().len(), 1);
+    assert_eq!(regions.find("Floor 2").unwrap().description, "Overridden.");
+}
@@ -0,0 +1,158 @@
+use std::net::UdpSocket;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use uo2d_proto::impl_component;
+use uo2d_proto::packet::payloads::{JoinPayload, MessagePayload, UuidPayload};
+use uo2d_proto::packet::{with_handshake, Action, Packet, Payload, Reliability};
+use uo2d_server::{Plugin, Server, ServerBuilder};
+use uuid::Uuid;
+
+mod common;
+
+/// Marks the entities spawned by the plugin.
+struct Marker;
+impl_component!(Marker);
+
+/// Reports what ran through the channel.
+struct Probe(mpsc::Sender<&'static str>);
+
+impl Plugin for Probe {
+    fn name(&self) -> &'static str {
+        "probe"
+    }
+
+    fn build(&self, builder: &mut ServerBuilder) {
+        let system = self.0.clone();
+        let timer = self.0.clone();
+        builder
+            .add_component::<Marker>()
+            .add_system(move |ctx| {
+                // The component is registered within the world before the first update.
+                if ctx.world.get_entities::<Marker>().is_empty() {
+                    ctx.world.spawn().with(Marker).build();
+                    let _ = system.send("system");
+                }
+            })
+            .add_timer(0.1, move |ctx| {
+                if !ctx.world.get_entities::<Marker>().is_empty() {
+                    let _ = timer.send("timer");
+                }
+            })
+            .add_handler(Action::Ping, |_ctx, _uuid, _payload| false)
+            .add_command("/dance", |_ctx, _uuid, _argument| ());
+    }
+}
+
+/// Reports the packets and commands it was given by the clients.
+struct Listener(mpsc::Sender<String>);
+
+impl Plugin for Listener {
+    fn name(&self) -> &'static str {
+        "listener"
+    }
+
+    fn build(&self, builder: &mut ServerBuilder) {
+        let handler = self.0.clone();
+        let command = self.0.clone();
+        builder
+            .add_handler(Action::Ping, move |_ctx, _uuid, payload| {
+                let _ = handler.send(format!("ping {}", matches!(payload, Payload::Uuid(_))));
+                false
+            })
+            .add_command("/dance", move |_ctx, _uuid, argument| {
+                let _ = command.send(format!("dance {}", argument));
+            });
+    }
+}
+
+/// Waits for a packet from the server matching the check.
+fn expect(socket: &UdpSocket, check: impl Fn(&Packet) -> bool) -> bool {
+    let mut reliability = Reliability::new();
+    let mut buf = [0; 65535];
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(10) {
+        let Ok(size) = socket.recv(&mut buf) else {
+            continue;
+        };
+        for packet in Packet::from_bytes(&buf[..size]).unbundle() {
+            if reliability
+                .receive(packet)
+                .packet
+                .is_some_and(|packet| check(&packet))
+            {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[test]
+fn plugins_run_within_the_server() {
+    let (tx, rx) = mpsc::channel();
+    let config = common::server_config("");
+    let builder = Server::builder()
+        .address("127.0.0.1:0")
+        .config(config.to_str().unwrap())
+        .plugin(Probe(tx));
+    assert_eq!(builder.plugins().names(), ["probe"]);
+
+    let handle = builder.spawn().expect("Unable to spawn the server");
+    let timeout = Duration::from_secs(10);
+    assert_eq!(rx.recv_timeout(timeout), Ok("system"));
+    assert_eq!(rx.recv_timeout(timeout), Ok("timer"));
+    // Timers repeat for as long as the server runs.
+    assert_eq!(rx.recv_timeout(timeout), Ok("timer"));
+    handle.shutdown();
+    std::fs::remove_dir_all(config.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn plugins_are_given_packets_and_commands() {
+    let (tx, rx) = mpsc::channel();
+    let config = common::server_config("");
+    let handle = Server::builder()
+        .address("127.0.0.1:0")
+        .config(config.to_str().unwrap())
+        .plugin(Listener(tx))
+        .spawn()
+        .expect("Unable to spawn the server");
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.connect(handle.local_addr()).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+    let join = Packet::new(
+        Action::ClientJoin,
+        Uuid::nil(),
+        Payload::Join(JoinPayload::new(None)),
+    );
+    socket.send(&with_handshake(&join.to_bytes())).unwrap();
+    assert!(expect(&socket, |packet| matches!(
+        packet.payload(),
+        Payload::Spawn(_)
+    )));
+
+    // Pings still reach the plugin, answered as before since it did not skip them.
+    let ping = Packet::new(
+        Action::Ping,
+        Uuid::nil(),
+        Payload::Uuid(UuidPayload::new(Uuid::new_v4())),
+    );
+    socket.send(&ping.to_bytes()).unwrap();
+    let timeout = Duration::from_secs(10);
+    assert_eq!(rx.recv_timeout(timeout).as_deref(), Ok("ping true"));
+    assert!(expect(&socket, |packet| packet.action() == Some(Action::Ping)));
+
+    let dance = Packet::new(
+        Action::Message,
+        Uuid::nil(),
+        Payload::Message(MessagePayload::new("/dance wildly")),
+    );
+    socket.send(&dance.to_bytes()).unwrap();
+    assert_eq!(rx.recv_timeout(timeout).as_deref(), Ok("dance wildly"));
+    handle.shutdown();
+    std::fs::remove_dir_all(config.parent().unwrap()).unwrap();
+}
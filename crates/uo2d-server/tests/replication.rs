@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use uo2d_proto::ecs::World;
+use uo2d_server::config::{ReplicationBand, ReplicationConfig};
+use uo2d_server::replication::Replicator;
+use uuid::Uuid;
+
+fn config() -> ReplicationConfig {
+    ReplicationConfig {
+        bands: vec![
+            ReplicationBand {
+                distance: 120.,
+                ticks: 4,
+            },
+            ReplicationBand {
+                distance: 80.,
+                ticks: 2,
+            },
+        ],
+    }
+}
+
+#[test]
+fn far_players_are_sent_movement_less_often() {
+    let mut world = World::new();
+    let entity = world.spawn().build();
+    let (near, middle, far) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let observers = [(near, 10.), (middle, 100.), (far, 150.)];
+
+    let mut replicator = Replicator::new(&config());
+    assert_eq!(replicator.interval(10.), 1);
+    assert_eq!(replicator.interval(80.), 2);
+    assert_eq!(replicator.interval(150.), 4);
+
+    // Everyone is sent the first movement, then each at the rate of their band.
+    let mut sent = [0, 0, 0];
+    for tick in 0..8 {
+        replicator.advance(tick, 1);
+        let due = replicator.due(entity, &observers, false);
+        for (count, (uuid, _)) in sent.iter_mut().zip(observers.iter()) {
+            *count += due.contains(uuid) as u32;
+        }
+    }
+    assert_eq!(sent, [8, 4, 2]);
+
+    // Keyframes reach every observer so the deltas that follow can be applied.
+    replicator.advance(8, 1);
+    assert_eq!(replicator.due(entity, &observers, true).len(), 3);
+
+    // Shedding load stretches the bands, never the players closest to the entity.
+    replicator.advance(9, 2);
+    assert_eq!(replicator.interval(10.), 1);
+    assert_eq!(replicator.interval(150.), 8);
+}
+
+#[test]
+fn players_behind_are_caught_up_once_the_entity_stops() {
+    let mut world = World::new();
+    let entity = world.spawn().build();
+    let (near, far, gone) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let observers = [(near, 10.), (far, 150.), (gone, 150.)];
+
+    let mut replicator = Replicator::new(&config());
+    replicator.advance(0, 1);
+    replicator.due(entity, &observers, true);
+    replicator.advance(1, 1);
+    assert_eq!(
+        replicator.due(entity, &observers, false),
+        HashSet::from([near])
+    );
+
+    // Only those still watching that missed a movement are owed where it stopped, once.
+    replicator.advance(2, 1);
+    let watching = HashSet::from([near, far]);
+    assert_eq!(replicator.settle(&entity, &watching), HashSet::from([far]));
+    assert!(replicator.settle(&entity, &watching).is_empty());
+
+    // Entities without a position are forgotten.
+    assert_eq!(replicator.tracked(), 1);
+    replicator.prune(&world);
+    assert_eq!(replicator.tracked(), 0);
+}
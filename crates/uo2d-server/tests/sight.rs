@@ -0,0 +1,56 @@
+use uo2d_proto::components::{Bounds, Collidable, Obstacle, ObstacleKind, Position, Vec2, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::sight;
+
+fn place(world: &mut World, spatial: &mut SpatialHash, loc: Vec3, size: Vec2) -> Entity {
+    let position = Position::new(loc, size);
+    let entity = world.spawn().with(position).build();
+    spatial.insert_object(&entity, &position.bounds());
+    entity
+}
+
+#[test]
+fn lines_cross_bounds_they_pass_through() {
+    let bounds = Bounds::new(10., 10., 1., 10., 10.);
+    let line = |x1, y1, x2, y2| sight::crosses(&bounds, Vec2::new(x1, y1), Vec2::new(x2, y2));
+
+    assert!(line(0., 15., 30., 15.));
+    assert!(line(0., 0., 30., 30.));
+    assert!(line(15., 0., 15., 12.));
+    // Stopping short, passing beside, and running parallel outside.
+    assert!(!line(0., 15., 8., 15.));
+    assert!(!line(0., 25., 30., 40.));
+    assert!(!line(25., 0., 25., 30.));
+}
+
+#[test]
+fn obstacles_on_the_same_layer_block_sight() {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Obstacle>();
+    world.register_component::<Collidable>();
+    let mut spatial = SpatialHash::new(32);
+
+    let size = Vec2::new(16., 16.);
+    let archer = place(&mut world, &mut spatial, Vec3::new(0., 100., 1.), size);
+    let target = place(&mut world, &mut spatial, Vec3::new(300., 100., 1.), size);
+    let above = place(&mut world, &mut spatial, Vec3::new(300., 100., 2.), size);
+    assert!(sight::in_sight(&world, &spatial, &archer, &target));
+    assert!(!sight::in_sight(&world, &spatial, &archer, &above));
+
+    // Other mobiles do not hide the target, only solid obstacles.
+    let bystander = place(&mut world, &mut spatial, Vec3::new(150., 96., 1.), size);
+    world.upsert_component(bystander, Collidable);
+    assert!(sight::in_sight(&world, &spatial, &archer, &target));
+
+    let wall = place(
+        &mut world,
+        &mut spatial,
+        Vec3::new(150., 40., 1.),
+        Vec2::new(32., 160.),
+    );
+    world.upsert_component(wall, Obstacle(ObstacleKind::Block));
+    world.upsert_component(wall, Collidable);
+    assert!(!sight::in_sight(&world, &spatial, &archer, &target));
+}
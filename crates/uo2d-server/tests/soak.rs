@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use uo2d_server::region::RegionManager;
+use uo2d_server::soak::Soak;
+
+mod common;
+
+#[test]
+fn short_soak_holds_invariants() {
+    let regions = common::content().path(RegionManager::DIRECTORY);
+    let report = Soak::new(&regions)
+        .content(common::content_config())
+        .entities(1_000)
+        .duration(Duration::from_secs(2))
+        .run()
+        .unwrap_or_else(|why| panic!("{}", why));
+
+    assert!(report.ticks > 0);
+    assert_eq!(report.entities, 1_000);
+}
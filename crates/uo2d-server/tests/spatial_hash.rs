@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use uo2d_proto::components::{Bounds, Vec2, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_server::spatial_hash::SpatialHash;
+
+#[test]
+fn bounded_hash_matches_the_unbounded_one() {
+    let mut world = World::new();
+    let mut sparse = SpatialHash::new(32);
+    let mut dense = SpatialHash::bounded(32, Vec2::new(640., 480.));
+
+    // Entities inside the world, straddling its edge, and well beyond it.
+    let size = Vec2::new(24., 24.);
+    let mut expected: HashMap<Entity, Bounds> = HashMap::new();
+    for (x, y) in [
+        (10., 10.),
+        (300., 200.),
+        (630., 470.),
+        (2000., 100.),
+        (50., 3000.),
+    ] {
+        let entity = world.spawn().build();
+        let bounds = Bounds::from_vec(Vec3::new(x, y, 1.), size);
+        sparse.insert_object(&entity, &bounds);
+        dense.insert_object(&entity, &bounds);
+        expected.insert(entity, bounds);
+    }
+
+    let areas = [
+        Bounds::new(0., 0., 1., 640., 480.),
+        Bounds::new(600., 440., 1., 100., 100.),
+        Bounds::new(1900., 0., 1., 200., 200.),
+    ];
+    for area in areas.iter() {
+        assert_eq!(sparse.query(area, None), dense.query(area, None));
+    }
+    let center = Vec2::new(640., 480.);
+    assert_eq!(
+        sparse.query_radius(center, 200., None),
+        dense.query_radius(center, 200., None)
+    );
+    assert_eq!(sparse.cell_count(), dense.cell_count());
+    assert_eq!(sparse.entry_count(), dense.entry_count());
+    assert!(dense.diverged(&expected).is_empty());
+
+    // Removing clears the cells without forgetting they were occupied.
+    for (entity, bounds) in expected.iter() {
+        sparse.remove_object(entity, bounds);
+        dense.remove_object(entity, bounds);
+    }
+    assert_eq!(dense.entry_count(), 0);
+    assert_eq!(sparse.cell_count(), dense.cell_count());
+    assert!(dense.query(&areas[0], None).is_empty());
+}
@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use uo2d_proto::components::{Ability, Player, Position, Stats, Vec2, Vec3};
+use uo2d_proto::ecs::{Entity, World};
+use uo2d_proto::timer::TimerManager;
+use uo2d_server::config::TelemetryConfig;
+use uo2d_server::telemetry::Telemetry;
+use uuid::Uuid;
+
+mod common;
+
+fn telemetry(enabled: bool) -> Telemetry {
+    let config = TelemetryConfig {
+        enabled,
+        directory: std::env::temp_dir()
+            .join(format!("uo2d-telemetry-{}", Uuid::new_v4()))
+            .display()
+            .to_string(),
+        ..TelemetryConfig::default()
+    };
+    Telemetry::new(config, &mut TimerManager::new())
+}
+
+fn player(world: &mut World, uuid: Uuid, loc: Vec3) -> Entity {
+    world
+        .spawn()
+        .with(Player::new(uuid))
+        .with(Position::new(loc, Vec2::new(16., 16.)))
+        .with(Stats::default())
+        .build()
+}
+
+fn die(world: &mut World, entity: &Entity) {
+    world.get_component_mut::<Stats>(entity).unwrap().deaths += 1;
+}
+
+#[test]
+fn sessions_and_abilities_are_totalled() {
+    let items = common::items();
+    let mut telemetry = telemetry(true);
+
+    let now = Instant::now();
+    let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+    telemetry.joined(first, now);
+    telemetry.joined(second, now);
+    telemetry.left(&first, now + Duration::from_secs(60));
+    telemetry.left(&second, now + Duration::from_secs(180));
+
+    telemetry.ability(Ability::Gather, &items);
+    telemetry.ability(Ability::Gather, &items);
+    telemetry.ability(Ability::Emote, &items);
+    let item = items.get(&1).expect("The item is missing");
+    telemetry.ability(Ability::Item(item.id), &items);
+
+    let report = telemetry.report(Utc::now());
+    assert_eq!(report.sessions.count, 2);
+    assert_eq!(report.sessions.average, 120.);
+    assert_eq!(report.sessions.longest, 180.);
+    assert_eq!(report.abilities["gather"], 2);
+    assert_eq!(report.abilities["emote"], 1);
+    assert_eq!(report.abilities[&format!("item:{}", item.key)], 1);
+
+    // Each report starts over.
+    let report = telemetry.report(Utc::now());
+    assert_eq!(report.sessions.count, 0);
+    assert!(report.abilities.is_empty());
+}
+
+#[test]
+fn deaths_are_counted_by_region_without_identifying_players() {
+    let regions = common::regions();
+    let mut world = common::world();
+    let mut telemetry = telemetry(true);
+
+    let loc = Vec3::new(1600., 300., 1.);
+    let region = regions.get_region(&loc).expect("The region is missing");
+    let uuid = Uuid::new_v4();
+    let entity = player(&mut world, uuid, loc);
+    let lost = player(&mut world, Uuid::new_v4(), Vec3::new(-10000., -10000., 1.));
+
+    // Deaths from before the first check are not counted.
+    die(&mut world, &entity);
+    telemetry.deaths(&world, &regions);
+    die(&mut world, &entity);
+    die(&mut world, &lost);
+    telemetry.deaths(&world, &regions);
+    telemetry.deaths(&world, &regions);
+
+    let report = telemetry.report(Utc::now());
+    assert_eq!(report.deaths[&region.name], 1);
+    assert_eq!(report.deaths["unknown"], 1);
+
+    let path = telemetry.write(&report).unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains(&region.name));
+    assert!(!written.contains(&uuid.to_string()));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn nothing_is_gathered_while_disabled() {
+    let (items, regions) = (common::items(), common::regions());
+    let mut world = common::world();
+    let mut telemetry = telemetry(false);
+    let mut timers = TimerManager::new();
+    assert!(!telemetry.is_enabled());
+    assert!(!telemetry.due(&mut timers));
+
+    let now = Instant::now();
+    let uuid = Uuid::new_v4();
+    let entity = player(&mut world, uuid, Vec3::new(1600., 300., 1.));
+    telemetry.joined(uuid, now);
+    telemetry.deaths(&world, &regions);
+    die(&mut world, &entity);
+    telemetry.deaths(&world, &regions);
+    telemetry.ability(Ability::Emote, &items);
+    telemetry.left(&uuid, now + Duration::from_secs(60));
+
+    let report = telemetry.report(Utc::now());
+    assert_eq!(report.sessions.count, 0);
+    assert!(report.deaths.is_empty() && report.abilities.is_empty());
+}
@@ -0,0 +1,71 @@
+use uo2d_proto::chunk::{chunk_of, tile_bounds, WATER};
+use uo2d_proto::components::{
+    Health, Mounted, MovementMode, Position, StatusEffects, Terrain, Vec2, Vec3,
+};
+use uo2d_proto::ecs::World;
+use uo2d_server::net::Net;
+use uo2d_server::region::RegionManager;
+use uo2d_server::spatial_hash::SpatialHash;
+use uo2d_server::systems::{mounts, physics, terrain};
+
+mod common;
+
+/// Within the pond on the mainland.
+fn pond() -> Vec3 {
+    Vec3::new(224., 336., 1.)
+}
+
+/// Updates the movement modes, returning the number of events sent.
+fn update(world: &mut World, spatial: &SpatialHash, regions: &RegionManager) -> usize {
+    let mut net = Net::new();
+    terrain::update(world, spatial, regions, &mut net);
+    net.len()
+}
+
+#[test]
+fn water_is_marked_on_the_streamed_tiles() {
+    let regions = common::regions();
+    assert_eq!(regions.terrain_at(&pond()), Terrain::Water);
+    assert_eq!(
+        regions.terrain_at(&Vec3::new(512., 512., 1.)),
+        Terrain::Ground
+    );
+
+    let coord = chunk_of(&pond());
+    let tiles = regions.chunk(&coord).unwrap();
+    for (index, tile) in tiles.iter().enumerate() {
+        let center = tile_bounds(&coord, index).center_2d();
+        let terrain = regions.terrain_at(&Vec3::new(center.x(), center.y(), 0.));
+        assert_eq!(*tile & WATER != 0, terrain == Terrain::Water);
+    }
+}
+
+#[test]
+fn swimmers_are_slowed_and_thrown_from_their_mounts() {
+    let regions = common::regions();
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Health>();
+    world.register_component::<StatusEffects>();
+    world.register_component::<Mounted>();
+    world.register_component::<MovementMode>();
+    let spatial = SpatialHash::new(32);
+
+    let shore = Position::new(Vec3::new(512., 512., 1.), Vec2::new(32., 32.));
+    let entity = world.spawn().with(shore).with(Health::new(100)).build();
+    mounts::mount(&mut world, &spatial, &regions, &mut Net::new(), &entity).unwrap();
+    assert_eq!(update(&mut world, &spatial, &regions), 0);
+
+    // Wading in dismounts them along with the change of mode.
+    world.upsert_component(entity, Position::new(pond(), Vec2::new(32., 32.)));
+    assert_eq!(update(&mut world, &spatial, &regions), 2);
+    assert!(terrain::is_swimming(&world, &entity));
+    assert!(!mounts::is_mounted(&world, &entity));
+    assert!(physics::speed_scale(&world, &entity) < 1.);
+    assert!(mounts::mount(&mut world, &spatial, &regions, &mut Net::new(), &entity).is_err());
+    assert_eq!(update(&mut world, &spatial, &regions), 0);
+
+    world.upsert_component(entity, shore);
+    assert_eq!(update(&mut world, &spatial, &regions), 1);
+    assert_eq!(terrain::mode(&world, &entity), MovementMode::Walking);
+}
@@ -0,0 +1,137 @@
+use uo2d_proto::components::{Inventory, ItemStack};
+use uo2d_server::trades::TradeManager;
+use uuid::Uuid;
+
+mod common;
+
+/// Trade opened between two new players.
+fn opened() -> (TradeManager, Uuid, Uuid) {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut trades = TradeManager::new();
+    assert_eq!(trades.invite(a, b), Ok(false));
+    assert_eq!(trades.invite(b, a), Ok(true));
+    (trades, a, b)
+}
+
+#[test]
+fn trades_open_once_the_invitation_is_returned() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let mut trades = TradeManager::new();
+    assert_eq!(trades.invite(a, a), Err("trade.invalid"));
+
+    assert_eq!(trades.invite(a, b), Ok(false));
+    assert_eq!(trades.inviting(&a), Some(b));
+    assert_eq!(trades.invited_by(&b), Some(a));
+
+    // Inviting someone else replaces the earlier invitation.
+    assert_eq!(trades.invite(a, c), Ok(false));
+    assert_eq!(trades.invited_by(&b), None);
+
+    assert_eq!(trades.invite(c, a), Ok(true));
+    assert_eq!(trades.inviting(&a), None);
+    let trade = trades.get(&c).unwrap();
+    assert_eq!(trade.players(), [a, c]);
+    assert_eq!(trade.partner(&c), a);
+
+    assert_eq!(trades.invite(b, a), Err("trade.busy"));
+}
+
+#[test]
+fn offers_are_limited_to_what_is_owned() {
+    let (mut trades, a, _) = opened();
+
+    assert_eq!(trades.offer(&a, ItemStack::new(3, 2), 3), Ok(()));
+    assert_eq!(
+        trades.offer(&a, ItemStack::new(3, 2), 3),
+        Err("trade.not_owned")
+    );
+    assert_eq!(trades.offer(&a, ItemStack::new(3, 1), 3), Ok(()));
+    assert_eq!(
+        trades.offer(&a, ItemStack::new(4, 0), 3),
+        Err("trade.not_owned")
+    );
+
+    let (mine, _) = trades.get(&a).unwrap().offers(&a);
+    assert_eq!(mine.items, vec![ItemStack::new(3, 3)]);
+
+    for item in 4..(4 + TradeManager::MAX_OFFER as u16 - 1) {
+        assert_eq!(trades.offer(&a, ItemStack::new(item, 1), 1), Ok(()));
+    }
+    assert_eq!(
+        trades.offer(&a, ItemStack::new(100, 1), 1),
+        Err("trade.full")
+    );
+
+    assert_eq!(trades.withdraw(&a, 0), Ok(()));
+    assert_eq!(trades.withdraw(&a, 99), Err("trade.invalid"));
+    assert_eq!(trades.offer(&a, ItemStack::new(100, 1), 1), Ok(()));
+}
+
+#[test]
+fn both_offers_are_locked_before_confirming() {
+    let (mut trades, a, b) = opened();
+    assert_eq!(trades.offer(&a, ItemStack::new(3, 1), 1), Ok(()));
+
+    assert_eq!(trades.lock(&a, true), Ok(()));
+    assert_eq!(
+        trades.offer(&a, ItemStack::new(4, 1), 1),
+        Err("trade.locked")
+    );
+    assert_eq!(trades.confirm(&a), Err("trade.not_locked"));
+
+    assert_eq!(trades.lock(&b, true), Ok(()));
+    assert_eq!(trades.confirm(&a), Ok(false));
+
+    // Unlocking takes back every confirmation.
+    assert_eq!(trades.lock(&b, false), Ok(()));
+    assert_eq!(trades.lock(&b, true), Ok(()));
+    assert_eq!(trades.confirm(&b), Ok(false));
+    assert_eq!(trades.confirm(&a), Ok(true));
+}
+
+#[test]
+fn cancelling_returns_who_was_waiting() {
+    let (mut trades, a, b) = opened();
+    let c = Uuid::new_v4();
+    assert_eq!(trades.invite(c, a), Err("trade.busy"));
+
+    assert_eq!(trades.cancel(&b), vec![a]);
+    assert!(trades.get(&a).is_none());
+    assert_eq!(trades.confirm(&a), Err("trade.none"));
+
+    assert_eq!(trades.invite(c, a), Ok(false));
+    assert_eq!(trades.cancel(&a), vec![c]);
+    assert_eq!(trades.inviting(&c), None);
+    assert!(trades.cancel(&a).is_empty());
+}
+
+#[test]
+fn offers_are_exchanged_between_inventories() {
+    let items = common::items();
+    let (mut trades, a, b) = opened();
+
+    let mut first = Inventory::new(100);
+    first.stacks.push(ItemStack::new(3, 1));
+    let mut second = Inventory::new(100);
+    second.stacks.push(ItemStack::new(1, 1));
+
+    assert_eq!(trades.offer(&a, ItemStack::new(3, 1), 1), Ok(()));
+    assert_eq!(trades.offer(&b, ItemStack::new(1, 1), 1), Ok(()));
+    let trade = trades.take(&a).unwrap();
+
+    let [first, second] = trade.exchange(&items, &first, &second).unwrap();
+    assert_eq!(first.stacks, vec![ItemStack::new(1, 1)]);
+    assert_eq!(second.stacks, vec![ItemStack::new(3, 1)]);
+
+    // Offers are only exchanged if the items are still held and can be carried.
+    assert_eq!(
+        trade.exchange(&items, &first, &second).err(),
+        Some("trade.missing")
+    );
+    let mut weak = Inventory::new(0);
+    weak.stacks.push(ItemStack::new(3, 1));
+    assert_eq!(
+        trade.exchange(&items, &weak, &first).err(),
+        Some("trade.too_heavy")
+    );
+}
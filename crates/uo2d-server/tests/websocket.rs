@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::Message;
+use uo2d_proto::packet::payloads::JoinPayload;
+use uo2d_proto::packet::{with_handshake, Action, Packet, Payload, Reliability};
+use uo2d_server::Server;
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn browsers_join_through_the_relay() {
+    let config = common::server_config("network:\n  websocket: true\n");
+    let handle = Server::builder()
+        .address("127.0.0.1:0")
+        .config(config.to_str().unwrap())
+        .spawn()
+        .expect("Unable to spawn the server");
+
+    let url = format!("ws://{}", handle.local_addr());
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .expect("Unable to connect over a WebSocket");
+    let join = Packet::new(
+        Action::ClientJoin,
+        Uuid::nil(),
+        Payload::Join(JoinPayload::new(None)),
+    );
+    let bytes = with_handshake(&join.to_bytes());
+    socket.send(Message::binary(bytes)).await.unwrap();
+
+    // The player is spawned once the server has accepted them.
+    let mut reliability = Reliability::new();
+    let spawned = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(Ok(message)) = socket.next().await {
+            let Message::Binary(bytes) = message else {
+                continue;
+            };
+            for packet in Packet::from_bytes(&bytes).unbundle() {
+                let packet = reliability.receive(packet).packet;
+                if packet.is_some_and(|packet| matches!(packet.payload(), Payload::Spawn(_))) {
+                    return true;
+                }
+            }
+        }
+        false
+    })
+    .await;
+
+    assert_eq!(spawned, Ok(true));
+    handle.shutdown();
+    std::fs::remove_dir_all(config.parent().unwrap()).unwrap();
+}
+
+#[tokio::test]
+async fn addresses_are_limited_in_the_sockets_they_open() {
+    let config =
+        common::server_config("network:\n  websocket: true\n  websockets_per_address: 2\n");
+    let handle = Server::builder()
+        .address("127.0.0.1:0")
+        .config(config.to_str().unwrap())
+        .spawn()
+        .expect("Unable to spawn the server");
+
+    let url = format!("ws://{}", handle.local_addr());
+    let mut open = vec![];
+    for _ in 0..2 {
+        let (socket, _) = tokio_tungstenite::connect_async(&url)
+            .await
+            .expect("Unable to connect over a WebSocket");
+        open.push(socket);
+    }
+    assert!(tokio_tungstenite::connect_async(&url).await.is_err());
+
+    // Closing one makes room for another.
+    open.pop().unwrap().close(None).await.unwrap();
+    let reconnected = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if tokio_tungstenite::connect_async(&url).await.is_ok() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await;
+
+    assert!(reconnected.is_ok());
+    handle.shutdown();
+    std::fs::remove_dir_all(config.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use uo2d_server::config::{EventHook, EventSchedule, WorldEventConfig};
+use uo2d_server::world_events::{Transition, WorldEvents};
+
+fn noon() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()
+}
+
+fn event(schedule: EventSchedule, duration: f32, hooks: Vec<EventHook>) -> WorldEventConfig {
+    WorldEventConfig {
+        name: "Festival".to_string(),
+        schedule,
+        duration,
+        hooks,
+    }
+}
+
+#[test]
+fn interval_events_start_and_end() {
+    let now = noon();
+    let config = event(EventSchedule::Interval(60.), 30., Vec::new());
+    let mut events = WorldEvents::new(vec![config], now);
+
+    assert!(events.update(now).is_empty(), "started before its interval");
+    let started = now + Duration::seconds(60);
+    assert_eq!(events.update(started), vec![Transition::Started(0)]);
+    assert_eq!(events.running().count(), 1);
+
+    assert!(events.update(started + Duration::seconds(29)).is_empty());
+    let ended = started + Duration::seconds(30);
+    assert_eq!(events.update(ended), vec![Transition::Ended(0)]);
+    assert_eq!(events.running().count(), 0);
+
+    assert_eq!(
+        events.update(started + Duration::seconds(60)),
+        vec![Transition::Started(0)]
+    );
+}
+
+#[test]
+fn running_events_are_not_restarted() {
+    let now = noon();
+    let config = event(EventSchedule::Interval(60.), 150., Vec::new());
+    let mut events = WorldEvents::new(vec![config], now);
+
+    assert_eq!(
+        events.update(now + Duration::seconds(60)),
+        vec![Transition::Started(0)]
+    );
+    assert!(events.update(now + Duration::seconds(120)).is_empty());
+    assert!(events.update(now + Duration::seconds(180)).is_empty());
+    assert_eq!(
+        events.update(now + Duration::seconds(210)),
+        vec![Transition::Ended(0)]
+    );
+}
+
+#[test]
+fn daily_events_start_at_their_time() {
+    let now = noon();
+    let config = event(EventSchedule::At("20:00".to_string()), 60., Vec::new());
+    let mut events = WorldEvents::new(vec![config], now);
+
+    assert!(events.update(now + Duration::hours(7)).is_empty());
+    let evening = now + Duration::hours(8);
+    assert_eq!(events.update(evening), vec![Transition::Started(0)]);
+    assert_eq!(
+        events.update(evening + Duration::minutes(1)),
+        vec![Transition::Ended(0)]
+    );
+    assert!(events.update(evening + Duration::hours(23)).is_empty());
+    assert_eq!(
+        events.update(evening + Duration::days(1)),
+        vec![Transition::Started(0)]
+    );
+}
+
+#[test]
+fn invalid_times_are_skipped() {
+    let configs = vec![
+        event(EventSchedule::At("25:99".to_string()), 60., Vec::new()),
+        event(EventSchedule::Interval(60.), 60., Vec::new()),
+    ];
+    let events = WorldEvents::new(configs, noon());
+
+    assert!(events.get(1).is_none());
+    assert_eq!(
+        events.get(0).map(|event| &event.schedule),
+        Some(&EventSchedule::Interval(60.))
+    );
+}
+
+#[test]
+fn experience_scales_with_running_events() {
+    let now = noon();
+    let configs = vec![
+        event(
+            EventSchedule::Interval(60.),
+            60.,
+            vec![EventHook::Experience(2.)],
+        ),
+        event(
+            EventSchedule::Interval(120.),
+            60.,
+            vec![EventHook::Experience(1.5)],
+        ),
+    ];
+    let mut events = WorldEvents::new(configs, now);
+    assert_eq!(events.experience(), 1.);
+
+    events.update(now + Duration::seconds(60));
+    assert_eq!(events.experience(), 2.);
+
+    events.update(now + Duration::seconds(120));
+    assert_eq!(events.experience(), 3.);
+}
+
+#[test]
+fn events_are_read_from_yaml() {
+    let yaml = r#"
+- name: "Harvest Festival"
+  at: "20:00"
+  duration: 3600
+  hooks:
+    - experience: 2.0
+- name: "Rat Infestation"
+  interval: 1800
+  duration: 300
+  hooks:
+    - spawner:
+        npc: "cave_rat"
+        position: [384, 420, 1]
+        respawn: 10.0
+        leash: 12.0
+"#;
+    let configs: Vec<WorldEventConfig> = serde_yaml::from_str(yaml).unwrap();
+
+    assert_eq!(configs[0].schedule, EventSchedule::At("20:00".to_string()));
+    assert_eq!(configs[0].hooks, vec![EventHook::Experience(2.)]);
+    assert_eq!(configs[1].schedule, EventSchedule::Interval(1800.));
+    match &configs[1].hooks[..] {
+        [EventHook::Spawner(spawn)] => {
+            assert_eq!(spawn.npc, "cave_rat");
+            assert_eq!(spawn.max_alive, 1);
+        }
+        hooks => panic!("unexpected hooks {:?}", hooks),
+    }
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uo2d_proto::packet::{parse_handshake, Packet, Reliability};
+
+// Datagrams as the client reads them, acknowledged and then split into what was bundled.
+fuzz_target!(|data: &[u8]| {
+    let data = parse_handshake(data).map_or(data, |handshake| handshake.packet);
+    let mut reliability = Reliability::new();
+    let Some(packet) = reliability.receive(Packet::from_bytes(data)).packet else {
+        return;
+    };
+
+    for packet in packet.unbundle() {
+        let _ = reliability.receive(packet.clone());
+        let _ = packet.action();
+        let _ = packet.payload();
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uo2d_proto::packet::{parse_handshake, strip_handshake, Packet};
+
+// The first packets from a client open with the handshake, read before anything else.
+fuzz_target!(|data: &[u8]| {
+    let _ = strip_handshake(data);
+    if let Some(handshake) = parse_handshake(data) {
+        let packet = Packet::from_bytes(handshake.packet);
+        let _ = packet.action();
+        let _ = packet.payload();
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uo2d_proto::packet::Packet;
+
+// Anything a client sends is read this way once the handshake is removed.
+fuzz_target!(|data: &[u8]| {
+    let packet = Packet::from_bytes(data);
+    let _ = packet.action();
+    let _ = packet.uuid();
+    let _ = packet.payload();
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uo2d_server::fuzz::Receiver;
+
+// Datagrams as the server reads them, through the middleware and on to their handlers.
+// The first byte picks if the client has joined the world.
+fuzz_target!(|data: &[u8]| {
+    let Some((joined, data)) = data.split_first() else {
+        return;
+    };
+
+    let mut receiver = Receiver::new();
+    for packet in receiver.receive(data, joined & 1 == 1) {
+        let _ = packet.action();
+        let _ = packet.payload();
+    }
+});
@@ -16,14 +16,14 @@ else
 fi
 
 # Start the server.
-./target/debug/${PROJECT_NAME} --server &
+./target/debug/${PROJECT_NAME} server &
 server_pid=$!
 
 # Wait for 2 seconds for server to start up.
 sleep 2
 
 # Start the client.
-./target/debug/${PROJECT_NAME}
+./target/debug/${PROJECT_NAME} client
 
 # Optional: stop the server after the client exits.
 if [ "${KILL_SERVER}" == "true" ]; then
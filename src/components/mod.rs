@@ -1,13 +0,0 @@
-mod bounds;
-mod mobile;
-mod position;
-mod transform;
-mod vec;
-mod velocity;
-
-pub use bounds::*;
-pub use mobile::*;
-pub use position::*;
-pub use transform::*;
-pub use vec::*;
-pub use velocity::*;
@@ -1,45 +1,317 @@
-mod cache;
-mod client;
-mod components;
-mod ecs;
-mod entities;
-mod packet;
-mod server;
-mod spatial_hash;
-mod timer;
-mod util;
-
-use std::env;
 use std::error::Error;
+use std::path::PathBuf;
 use std::thread::sleep;
 use std::time::Duration;
 
-use client::Client;
-use server::Server;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use uo2d_client::{CameraSettings, Client, Credentials, Frontend, Quality, QualitySettings};
+use uo2d_proto::crash::CrashReporter;
+use uo2d_proto::locale::Locale;
+use uo2d_proto::util::{set_log_level, LogLevel};
+use uo2d_server::event_log;
+use uo2d_server::soak::Soak;
+use uo2d_server::Server;
 
 const ADDRESS: &str = "127.0.0.1:31013";
+const CONFIG: &str = "server.yaml";
+const REGIONS: &str = "assets/regions";
+
+#[derive(Parser)]
+#[command(version, about = "A 2D multiplayer game server and client.")]
+struct Cli {
+    /// Address of the server to host or connect to.
+    #[arg(long, global = true, default_value = ADDRESS)]
+    address: String,
+
+    /// Amount of output printed to the console.
+    #[arg(long, global = true, value_enum, default_value_t = Level::Info)]
+    log_level: Level,
+
+    /// Plain HTTP address crash reports are posted to, they are always written to disk.
+    #[arg(long, global = true)]
+    crash_endpoint: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Hosts a server.
+    Server(ServerArgs),
+    /// Connects to a server.
+    Client(ClientArgs),
+    /// Hosts a server and connects to it.
+    Solo {
+        #[command(flatten)]
+        server: ServerArgs,
+        #[command(flatten)]
+        client: ClientArgs,
+    },
+    /// Connects clients that wander around on their own.
+    Bot {
+        /// Number of bots to connect.
+        #[arg(long, default_value_t = 1)]
+        count: u32,
+        #[arg(long)]
+        server_password: Option<String>,
+    },
+    /// Loads the regions and describes each one.
+    CheckRegions {
+        #[arg(default_value = REGIONS)]
+        directory: String,
+    },
+    /// Plays back a session recorded by a client.
+    Playback {
+        file: PathBuf,
+        /// Runs without a window.
+        #[arg(long)]
+        headless: bool,
+        #[arg(long, default_value = Locale::DEFAULT)]
+        language: String,
+    },
+    /// Prints the events from an event log.
+    Replay {
+        file: PathBuf,
+        /// Paces the events by their recorded times, scaled by this speed.
+        #[arg(long)]
+        speed: Option<f64>,
+    },
+}
+
+#[derive(Args)]
+struct ServerArgs {
+    /// Path to the server configuration.
+    #[arg(long, default_value = CONFIG)]
+    config: String,
+    /// Directory to load the regions from, those within the content root if unset.
+    #[arg(long)]
+    regions: Option<String>,
+    /// Backup to replace the database with before starting.
+    #[arg(long)]
+    restore: Option<PathBuf>,
+    /// Moves synthetic entities around without any network IO, checking the world stays sane.
+    #[arg(long)]
+    soak: bool,
+    /// Seconds the soak test runs for.
+    #[arg(long, default_value_t = 60)]
+    soak_duration: u64,
+    /// Entities moved around during the soak test.
+    #[arg(long, default_value_t = 2_000)]
+    soak_entities: usize,
+}
+
+#[derive(Args)]
+struct ClientArgs {
+    /// Runs without a window or input.
+    #[arg(long)]
+    headless: bool,
+    /// Account to log in with, joins as a guest without one.
+    #[arg(long, requires = "password")]
+    username: Option<String>,
+    #[arg(long, requires = "username")]
+    password: Option<String>,
+    /// Creates the account instead of logging in.
+    #[arg(long, requires = "username")]
+    register: bool,
+    /// Password required by the server to join.
+    #[arg(long)]
+    server_password: Option<String>,
+    /// Language to show text in, from the files in assets/locale.
+    #[arg(long, default_value = Locale::DEFAULT)]
+    language: String,
+    /// Portion of the screen the player moves within before the camera follows, 0 keeps them centered.
+    #[arg(long, default_value_t = CameraSettings::default().deadzone)]
+    camera_deadzone: f64,
+    /// Portion of the distance the camera catches up each frame, 1 follows rigidly.
+    #[arg(long, default_value_t = CameraSettings::default().smoothing)]
+    camera_smoothing: f64,
+    /// Scale the world is drawn at, above 1 shows less of it.
+    #[arg(long, default_value_t = CameraSettings::default().zoom)]
+    camera_zoom: f64,
+    /// Level of detail to always draw at, lowered automatically to keep up without one.
+    #[arg(long, value_parser = parse_quality)]
+    quality: Option<Quality>,
+    /// Frames per second the automatic quality tries to keep.
+    #[arg(long, default_value_t = QualitySettings::default().target_fps)]
+    target_fps: f64,
+    /// Records the packets received and input of each session, played back with the playback command.
+    #[arg(long)]
+    record: bool,
+    /// Plain HTTP address of the manifest describing the latest release, checked at startup.
+    #[arg(long)]
+    update_manifest: Option<String>,
+    /// Starts without checking the manifest for a newer release.
+    #[arg(long)]
+    skip_update_check: bool,
+}
+
+/// Parses a level of detail from its name.
+fn parse_quality(name: &str) -> Result<Quality, String> {
+    Quality::from_name(name).ok_or_else(|| format!("'{}' is not low, medium, or high", name))
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Level {
+    Off,
+    Info,
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
+    let cli = Cli::parse();
+    set_log_level(match cli.log_level {
+        Level::Off => LogLevel::Off,
+        Level::Info => LogLevel::Info,
+    });
 
-    // Start either server or client.
-    if args.contains(&String::from("--server")) {
-        Server::start(ADDRESS)?;
-    } else {
-        // Start the server instance.
-        if args.contains(&String::from("--solo")) {
-            let server_address = ADDRESS.to_string();
+    let name = match cli.command {
+        Command::Server(_) => "server",
+        Command::Client(_) | Command::Playback { .. } => "client",
+        Command::Solo { .. } => "solo",
+        Command::Bot { .. } => "bot",
+        Command::CheckRegions { .. } | Command::Replay { .. } => "tool",
+    };
+    CrashReporter::new(name, env!("CARGO_PKG_VERSION"))
+        .endpoint(cli.crash_endpoint.clone())
+        .install();
+
+    match cli.command {
+        Command::Server(server) => server_start(&cli.address, &server, true)?,
+        Command::Client(client) => client_start(&cli.address, client)?,
+        Command::Solo { server, client } => {
+            let address = cli.address.clone();
             std::thread::spawn(move || {
-                if let Err(e) = Server::start(&server_address) {
+                if let Err(e) = server_start(&address, &server, true) {
                     eprintln!("Server failed to start: {}", e);
                 }
             });
 
             sleep(Duration::from_secs(1));
+            client_start(&cli.address, client)?;
         }
+        Command::Bot {
+            count,
+            server_password,
+        } => {
+            let bots: Vec<_> = (0..count)
+                .map(|_| {
+                    let address = cli.address.clone();
+                    let password = server_password.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = Client::start(
+                            &address,
+                            None,
+                            password,
+                            Locale::DEFAULT,
+                            Frontend::Bot,
+                            CameraSettings::default(),
+                            QualitySettings::default(),
+                            false,
+                            None,
+                        ) {
+                            eprintln!("Bot stopped: {}", e);
+                        }
+                    })
+                })
+                .collect();
 
-        Client::start(ADDRESS)?;
+            for bot in bots {
+                let _ = bot.join();
+            }
+        }
+        Command::CheckRegions { directory } => {
+            for region in Server::check_regions(&directory)? {
+                println!("{}", region);
+            }
+        }
+        Command::Playback {
+            file,
+            headless,
+            language,
+        } => {
+            let frontend = if headless {
+                Frontend::Headless
+            } else {
+                Frontend::Window
+            };
+            Client::playback(
+                &file,
+                &language,
+                frontend,
+                CameraSettings::default(),
+                QualitySettings::default(),
+            )?;
+        }
+        Command::Replay { file, speed } => {
+            let count = event_log::replay(&file, speed)?;
+            println!("Replayed {} events.", count);
+        }
     }
 
     Ok(())
 }
+
+/// Hosts the server, blocking until it shuts down.
+fn server_start(address: &str, args: &ServerArgs, console: bool) -> Result<(), Box<dyn Error>> {
+    if args.soak {
+        let report = Soak::new(args.regions.as_deref().unwrap_or(REGIONS))
+            .entities(args.soak_entities)
+            .duration(Duration::from_secs(args.soak_duration))
+            .run()?;
+        println!("{}", report);
+        return Ok(());
+    }
+
+    let mut builder = Server::builder()
+        .address(address)
+        .config(&args.config)
+        .console(console)
+        .restore(args.restore.clone());
+    if let Some(regions) = &args.regions {
+        builder = builder.regions(regions);
+    }
+    builder.spawn()?.wait();
+    Ok(())
+}
+
+/// Connects to the server, blocking until the client quits.
+fn client_start(address: &str, args: ClientArgs) -> Result<(), Box<dyn Error>> {
+    let credentials = match (args.username, args.password) {
+        (Some(username), Some(password)) => Some(Credentials {
+            username,
+            password,
+            register: args.register,
+        }),
+        _ => None,
+    };
+
+    let frontend = if args.headless {
+        Frontend::Headless
+    } else {
+        Frontend::Window
+    };
+
+    let camera = CameraSettings {
+        deadzone: args.camera_deadzone,
+        smoothing: args.camera_smoothing,
+        zoom: args.camera_zoom,
+    };
+
+    let quality = QualitySettings {
+        target_fps: args.target_fps,
+        fixed: args.quality,
+    };
+
+    let update_manifest = args.update_manifest.filter(|_| !args.skip_update_check);
+    Client::start(
+        address,
+        credentials,
+        args.server_password,
+        &args.language,
+        frontend,
+        camera,
+        quality,
+        args.record,
+        update_manifest.as_deref(),
+    )
+}